@@ -1,3 +1,4 @@
+use ipnet::IpNet;
 use s3::creds::Credentials;
 use s3::error::S3Error;
 use s3::{Bucket, Region};
@@ -5,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use std::env;
 use std::env::var;
 use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
 use utoipa::ToSchema;
 
 pub const TELEGRAM_BOT_TOKEN: &str = "TELEGRAM_BOT_TOKEN";
@@ -47,6 +49,7 @@ pub const DEFAULT_OIDC_REFRESH_INTERVAL: u64 = 1000 * 60 * 2;
 pub const REVERSE_PROXY: &str = "REVERSE_PROXY";
 pub const REVERSE_PROXY_HEADER: &str = "REVERSE_PROXY_HEADER";
 pub const REVERSE_PROXY_AUTO_SIGN_UP: &str = "REVERSE_PROXY_AUTO_SIGN_UP";
+pub const TRUSTED_PROXIES: &str = "TRUSTED_PROXIES";
 pub const PODFETCH_PROXY_FOR_REQUESTS: &str = "PODFETCH_PROXY";
 #[cfg(feature = "postgresql")]
 pub const CONNECTION_NUMBERS: &str = "DB_CONNECTIONS";
@@ -60,10 +63,14 @@ pub const S3_SESSION_TOKEN: &str = "S3_SESSION_TOKEN";
 pub const POLLING_INTERVAL_DEFAULT: u32 = 300;
 pub const USER_PODCAST_LIMIT: &str = "USER_PODCAST_LIMIT";
 pub const DEFAULT_USER_PODCAST_LIMIT: u32 = 0;
+pub const USER_STORAGE_QUOTA_MB: &str = "USER_STORAGE_QUOTA_MB";
+pub const DEFAULT_USER_STORAGE_QUOTA_MB: u32 = 0;
 pub const TRANSCRIPTION_API_BASE_URL: &str = "TRANSCRIPTION_API_BASE_URL";
 pub const TRANSCRIPTION_API_KEY: &str = "TRANSCRIPTION_API_KEY";
 pub const TRANSCRIPTION_MODEL: &str = "TRANSCRIPTION_MODEL";
 pub const DEFAULT_TRANSCRIPTION_MODEL: &str = "whisper-1";
+pub const METRICS_ENABLED: &str = "METRICS_ENABLED";
+pub const METRICS_TOKEN: &str = "METRICS_TOKEN";
 
 pub fn is_env_var_present_and_true(env_var: &str) -> bool {
     match env::var(env_var) {
@@ -136,6 +143,8 @@ pub struct EnvironmentService {
     pub oidc_configured: bool,
     pub reverse_proxy: bool,
     pub reverse_proxy_config: Option<ReverseProxyConfig>,
+    /// Peers whose `X-Forwarded-For` and `X-Real-IP` headers are believed.
+    pub trusted_proxies: Vec<IpNet>,
     pub gpodder_integration_enabled: bool,
     pub audiobookshelf_integration_enabled: bool,
     pub mopidy_integration_enabled: bool,
@@ -155,7 +164,11 @@ pub struct EnvironmentService {
     pub default_podfetch_folder: String,
     pub s3_config: S3Config,
     pub user_podcast_limit: u32,
+    /// Default storage quota of a user in MB, 0 for unlimited. Admins can
+    /// override it per user.
+    pub user_storage_quota_mb: u32,
     pub transcription_config: Option<TranscriptionConfig>,
+    pub metrics_config: Option<MetricsConfig>,
 }
 
 #[derive(Clone)]
@@ -222,6 +235,12 @@ pub struct TranscriptionConfig {
     pub model: String,
 }
 
+#[derive(Clone)]
+pub struct MetricsConfig {
+    /// Bearer token scrapers have to send; the endpoint is open without one.
+    pub token: Option<String>,
+}
+
 impl Default for EnvironmentService {
     fn default() -> Self {
         Self::new()
@@ -316,6 +335,7 @@ impl EnvironmentService {
             oidc_configured: oidc_configured.is_some(),
             oidc_config: oidc_configured,
             reverse_proxy_config,
+            trusted_proxies: Self::handle_trusted_proxies(),
             gpodder_integration_enabled: is_env_var_present_and_true(GPODDER_INTEGRATION_ENABLED),
             audiobookshelf_integration_enabled: is_env_var_present_and_true(
                 AUDIOBOOKSHELF_INTEGRATION_ENABLED,
@@ -356,10 +376,34 @@ impl EnvironmentService {
                 .ok()
                 .and_then(|v| v.parse::<u32>().ok())
                 .unwrap_or(DEFAULT_USER_PODCAST_LIMIT),
+            user_storage_quota_mb: var(USER_STORAGE_QUOTA_MB)
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(DEFAULT_USER_STORAGE_QUOTA_MB),
             transcription_config: Self::handle_transcription_config(),
+            metrics_config: Self::handle_metrics_config(),
         }
     }
 
+    /// Comma separated addresses or CIDR ranges, e.g. `10.0.0.0/8,::1`.
+    fn handle_trusted_proxies() -> Vec<IpNet> {
+        var(TRUSTED_PROXIES)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let parsed = entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from));
+                if parsed.is_err() {
+                    tracing::warn!("Ignoring invalid entry {entry} in {TRUSTED_PROXIES}");
+                }
+                parsed.ok()
+            })
+            .collect()
+    }
+
     fn handle_default_file_handler() -> (FileHandlerType, S3Config) {
         match var(FILE_HANDLER) {
             Ok(handler) if handler == "s3" => (FileHandlerType::S3, Self::capture_s3_config()),
@@ -418,6 +462,15 @@ impl EnvironmentService {
         })
     }
 
+    fn handle_metrics_config() -> Option<MetricsConfig> {
+        if !is_env_var_present_and_true(METRICS_ENABLED) {
+            return None;
+        }
+        Some(MetricsConfig {
+            token: var(METRICS_TOKEN).ok().filter(|value| !value.is_empty()),
+        })
+    }
+
     pub fn get_polling_interval(&self) -> u32 {
         self.polling_interval
     }
@@ -449,6 +502,10 @@ impl EnvironmentService {
             "Mopidy integration enabled: {}",
             self.mopidy_integration_enabled
         );
+        tracing::info!(
+            "Metrics endpoint enabled: {}",
+            self.metrics_config.is_some()
+        );
         tracing::debug!("Database url is set to: {}", &self.database_url);
         tracing::info!(
             "Podindex API key&secret configured: {}",
@@ -43,6 +43,15 @@ impl Database {
     ) -> Result<r2d2::PooledConnection<ConnectionManager<DBType>>, PersistenceError> {
         self.pool.get().map_err(PersistenceError::Pool)
     }
+
+    /// Open and idle connections of the pool, for monitoring.
+    pub fn pool_state(&self) -> r2d2::State {
+        self.pool.state()
+    }
+
+    pub fn pool_max_size(&self) -> u32 {
+        self.pool.max_size()
+    }
 }
 
 pub fn shared_database(environment: &EnvironmentService) -> Result<Database, PersistenceError> {
@@ -54,6 +54,16 @@ impl ApiError {
         }
     }
 
+    pub fn second_factor_required() -> Self {
+        ApiError {
+            value: ApiErrorValue {
+                error_code: "SECOND_FACTOR_REQUIRED".into(),
+                arguments: HashMap::new(),
+            },
+            status: StatusCode::UNAUTHORIZED,
+        }
+    }
+
     pub fn transcription_job_already_exists() -> Self {
         ApiError {
             value: ApiErrorValue {
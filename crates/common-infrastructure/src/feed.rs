@@ -0,0 +1,518 @@
+//! Reads podcast feeds regardless of their syndication format.
+//!
+//! Everything downstream works on an [`rss::Channel`], so Atom and JSON Feed
+//! documents are translated into one: entries become items, enclosure links
+//! and attachments become enclosures and namespaced extensions (iTunes,
+//! Podcasting 2.0) are carried over unchanged.
+
+use atom_syndication::{Entry, Feed, Link};
+use chrono::DateTime;
+use rss::extension::itunes::{self, ITunesChannelExtension, ITunesItemExtension};
+use rss::extension::{Extension, ExtensionMap};
+use rss::{Channel, Enclosure, Guid, Image, Item};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Bytes looked at to find the root element when the content type does not
+/// tell the format.
+const SNIFF_LENGTH: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    JsonFeed,
+}
+
+#[derive(Debug, Error)]
+pub enum FeedError {
+    #[error("Invalid RSS feed: {0}")]
+    Rss(#[from] rss::Error),
+    #[error("Invalid Atom feed: {0}")]
+    Atom(#[from] atom_syndication::Error),
+    #[error("Invalid JSON feed: {0}")]
+    JsonFeed(#[from] serde_json::Error),
+}
+
+impl FeedFormat {
+    /// Trusts a feed specific content type and sniffs the document for
+    /// everything else, as plenty of servers send `text/xml` or
+    /// `application/octet-stream`. Falls back to RSS.
+    pub fn detect(content_type: Option<&str>, body: &[u8]) -> Self {
+        let mime_type = content_type
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase());
+        match mime_type.as_deref() {
+            Some("application/rss+xml") => Self::Rss,
+            Some("application/atom+xml") => Self::Atom,
+            Some("application/feed+json") => Self::JsonFeed,
+            _ => Self::sniff(body),
+        }
+    }
+
+    fn sniff(body: &[u8]) -> Self {
+        let head = String::from_utf8_lossy(&body[..body.len().min(SNIFF_LENGTH)]);
+        let mut rest = head.trim_start_matches('\u{feff}').trim_start();
+        if rest.starts_with('{') {
+            return Self::JsonFeed;
+        }
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start + 1..];
+            if let Some(comment) = rest.strip_prefix("!--") {
+                rest = comment.split_once("-->").map_or("", |(_, after)| after);
+                continue;
+            }
+            // XML declaration, stylesheets and the doctype
+            if rest.starts_with('?') || rest.starts_with('!') {
+                continue;
+            }
+            let name = rest
+                .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .next()
+                .unwrap_or_default();
+            let local_name = name.rsplit(':').next().unwrap_or_default();
+            return if local_name == "feed" {
+                Self::Atom
+            } else {
+                Self::Rss
+            };
+        }
+        Self::Rss
+    }
+}
+
+/// Parses `body` in whatever format [`FeedFormat::detect`] finds.
+pub fn parse_feed(body: &[u8], content_type: Option<&str>) -> Result<Channel, FeedError> {
+    match FeedFormat::detect(content_type, body) {
+        FeedFormat::Rss => Ok(Channel::read_from(body)?),
+        FeedFormat::Atom => Ok(atom_to_channel(Feed::read_from(body)?)),
+        FeedFormat::JsonFeed => Ok(json_feed_to_channel(serde_json::from_slice(body)?)),
+    }
+}
+
+// ── Atom ──────────────────────────────────────────────────────────────────
+
+fn atom_to_channel(feed: Feed) -> Channel {
+    let mut extensions = convert_extensions(feed.extensions);
+    let itunes_prefix = itunes_prefix(&feed.namespaces);
+    let mut itunes_ext = extensions
+        .remove(&itunes_prefix)
+        .map(ITunesChannelExtension::from_map);
+    if let Some(author) = feed.authors.first() {
+        itunes_ext
+            .get_or_insert_with(Default::default)
+            .author
+            .get_or_insert_with(|| author.name.clone());
+    }
+    let link = alternate_link(&feed.links).unwrap_or_else(|| feed.id.clone());
+    let image = feed
+        .logo
+        .or(feed.icon)
+        .or_else(|| itunes_ext.as_ref().and_then(|ext| ext.image.clone()))
+        .map(|url| Image {
+            url,
+            title: feed.title.value.clone(),
+            link: link.clone(),
+            ..Default::default()
+        });
+
+    Channel {
+        title: feed.title.value,
+        description: feed.subtitle.map(|text| text.value).unwrap_or_default(),
+        language: feed.lang,
+        last_build_date: Some(feed.updated.to_rfc2822()),
+        image,
+        items: feed
+            .entries
+            .into_iter()
+            .map(|entry| atom_entry_to_item(entry, &itunes_prefix))
+            .collect(),
+        link,
+        itunes_ext,
+        extensions,
+        namespaces: feed.namespaces,
+        ..Default::default()
+    }
+}
+
+fn atom_entry_to_item(entry: Entry, itunes_prefix: &str) -> Item {
+    let mut extensions = convert_extensions(entry.extensions);
+    let itunes_ext = extensions
+        .remove(itunes_prefix)
+        .map(ITunesItemExtension::from_map);
+    let enclosure = entry
+        .links
+        .iter()
+        .find(|link| link.rel == "enclosure")
+        .map(|link| Enclosure {
+            url: link.href.clone(),
+            length: link.length.clone().unwrap_or_else(|| "0".to_string()),
+            mime_type: link.mime_type.clone().unwrap_or_default(),
+        });
+    let content = entry.content.and_then(|content| content.value);
+
+    Item {
+        title: Some(entry.title.value),
+        link: alternate_link(&entry.links),
+        description: entry.summary.map(|text| text.value).or(content.clone()),
+        content,
+        author: entry.authors.first().map(|person| person.name.clone()),
+        enclosure,
+        guid: Some(Guid {
+            value: entry.id,
+            permalink: false,
+        }),
+        pub_date: Some(entry.published.unwrap_or(entry.updated).to_rfc2822()),
+        itunes_ext,
+        extensions,
+        ..Default::default()
+    }
+}
+
+fn alternate_link(links: &[Link]) -> Option<String> {
+    links
+        .iter()
+        .find(|link| link.rel == "alternate")
+        .map(|link| link.href.clone())
+}
+
+/// Prefix the feed bound the iTunes namespace to, usually `itunes`.
+fn itunes_prefix(namespaces: &BTreeMap<String, String>) -> String {
+    namespaces
+        .iter()
+        .find(|(_, uri)| uri.eq_ignore_ascii_case(itunes::NAMESPACE))
+        .map_or_else(|| "itunes".to_string(), |(prefix, _)| prefix.clone())
+}
+
+fn convert_extensions(extensions: atom_syndication::extension::ExtensionMap) -> ExtensionMap {
+    extensions
+        .into_iter()
+        .map(|(prefix, elements)| (prefix, convert_elements(elements)))
+        .collect()
+}
+
+fn convert_elements(
+    elements: BTreeMap<String, Vec<atom_syndication::extension::Extension>>,
+) -> BTreeMap<String, Vec<Extension>> {
+    elements
+        .into_iter()
+        .map(|(name, values)| (name, values.into_iter().map(convert_extension).collect()))
+        .collect()
+}
+
+fn convert_extension(extension: atom_syndication::extension::Extension) -> Extension {
+    Extension {
+        name: extension.name,
+        value: extension.value,
+        attrs: extension.attrs,
+        children: convert_elements(extension.children),
+    }
+}
+
+// ── JSON Feed ─────────────────────────────────────────────────────────────
+
+/// The parts of a JSON Feed (1.0 and 1.1) a podcast is built from.
+#[derive(Debug, Deserialize)]
+struct JsonFeed {
+    title: String,
+    home_page_url: Option<String>,
+    feed_url: Option<String>,
+    description: Option<String>,
+    icon: Option<String>,
+    favicon: Option<String>,
+    language: Option<String>,
+    /// JSON Feed 1.0 had a single author.
+    author: Option<JsonFeedAuthor>,
+    #[serde(default)]
+    authors: Vec<JsonFeedAuthor>,
+    #[serde(default)]
+    items: Vec<JsonFeedItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFeedAuthor {
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFeedItem {
+    id: serde_json::Value,
+    url: Option<String>,
+    title: Option<String>,
+    content_html: Option<String>,
+    content_text: Option<String>,
+    summary: Option<String>,
+    image: Option<String>,
+    date_published: Option<String>,
+    date_modified: Option<String>,
+    author: Option<JsonFeedAuthor>,
+    #[serde(default)]
+    authors: Vec<JsonFeedAuthor>,
+    #[serde(default)]
+    attachments: Vec<JsonFeedAttachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFeedAttachment {
+    url: String,
+    mime_type: Option<String>,
+    size_in_bytes: Option<u64>,
+    duration_in_seconds: Option<f64>,
+}
+
+fn author_name(authors: &[JsonFeedAuthor], author: Option<&JsonFeedAuthor>) -> Option<String> {
+    authors
+        .iter()
+        .chain(author)
+        .find_map(|author| author.name.clone())
+}
+
+fn json_feed_to_channel(feed: JsonFeed) -> Channel {
+    let link = feed.home_page_url.or(feed.feed_url).unwrap_or_default();
+    let image = feed.icon.or(feed.favicon).map(|url| Image {
+        url,
+        title: feed.title.clone(),
+        link: link.clone(),
+        ..Default::default()
+    });
+    let author = author_name(&feed.authors, feed.author.as_ref());
+
+    Channel {
+        title: feed.title,
+        description: feed.description.unwrap_or_default(),
+        language: feed.language,
+        image,
+        items: feed.items.into_iter().map(json_feed_item_to_item).collect(),
+        link,
+        itunes_ext: author.map(|author| ITunesChannelExtension {
+            author: Some(author),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn json_feed_item_to_item(item: JsonFeedItem) -> Item {
+    // Podcasts attach the audio, but an item may carry artwork or a
+    // transcript as well.
+    let attachment = item
+        .attachments
+        .iter()
+        .find(|attachment| {
+            attachment
+                .mime_type
+                .as_deref()
+                .is_some_and(|mime| mime.starts_with("audio/") || mime.starts_with("video/"))
+        })
+        .or(item.attachments.first());
+    let guid = match item.id {
+        serde_json::Value::String(id) => id,
+        other => other.to_string(),
+    };
+    let description = item
+        .summary
+        .clone()
+        .or(item.content_text.clone())
+        .or(item.content_html.clone());
+    let duration = attachment
+        .and_then(|attachment| attachment.duration_in_seconds)
+        .map(|seconds| (seconds.round() as u64).to_string());
+    let itunes_ext = (duration.is_some() || item.image.is_some()).then(|| ITunesItemExtension {
+        duration,
+        image: item.image.clone(),
+        ..Default::default()
+    });
+
+    Item {
+        title: item.title.or(item.summary).or(Some(guid.clone())),
+        link: item.url,
+        description,
+        content: item.content_html,
+        author: author_name(&item.authors, item.author.as_ref()),
+        enclosure: attachment.map(|attachment| Enclosure {
+            url: attachment.url.clone(),
+            length: attachment.size_in_bytes.unwrap_or_default().to_string(),
+            mime_type: attachment.mime_type.clone().unwrap_or_default(),
+        }),
+        guid: Some(Guid {
+            value: guid,
+            permalink: false,
+        }),
+        pub_date: item
+            .date_published
+            .or(item.date_modified)
+            .map(|date| rfc3339_to_rfc2822(&date)),
+        itunes_ext,
+        ..Default::default()
+    }
+}
+
+/// RSS dates are RFC 2822; keeps the original if it is not RFC 3339.
+fn rfc3339_to_rfc2822(date: &str) -> String {
+    DateTime::parse_from_rfc3339(date).map_or_else(|_| date.to_string(), |date| date.to_rfc2822())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<!-- generated by hand -->
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <title>Atom Cast</title>
+  <subtitle>Only published as Atom</subtitle>
+  <id>urn:uuid:60a76c80-d399-11d9-b93C-0003939e0af6</id>
+  <updated>2024-03-02T10:00:00Z</updated>
+  <link rel="alternate" href="https://example.com/"/>
+  <logo>https://example.com/cover.jpg</logo>
+  <author><name>Jane Doe</name></author>
+  <entry>
+    <title>First episode</title>
+    <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id>
+    <updated>2024-03-01T10:00:00Z</updated>
+    <published>2024-03-01T09:00:00Z</published>
+    <summary>Where it all starts</summary>
+    <link rel="alternate" href="https://example.com/1"/>
+    <link rel="enclosure" href="https://example.com/1.mp3" type="audio/mpeg" length="1234"/>
+    <itunes:duration>12:34</itunes:duration>
+  </entry>
+</feed>"#;
+
+    const JSON_FEED: &str = r#"{
+  "version": "https://jsonfeed.org/version/1.1",
+  "title": "JSON Cast",
+  "home_page_url": "https://example.org/",
+  "description": "Only published as JSON Feed",
+  "icon": "https://example.org/icon.png",
+  "authors": [{"name": "John Doe"}],
+  "items": [
+    {
+      "id": 42,
+      "title": "Pilot",
+      "content_text": "The very first one",
+      "date_published": "2024-03-01T09:00:00+01:00",
+      "attachments": [
+        {"url": "https://example.org/pilot.jpg", "mime_type": "image/jpeg"},
+        {"url": "https://example.org/pilot.m4a", "mime_type": "audio/x-m4a", "size_in_bytes": 5000, "duration_in_seconds": 1800.4}
+      ]
+    }
+  ]
+}"#;
+
+    #[test]
+    fn format_is_detected_by_content_type_before_sniffing() {
+        assert_eq!(
+            FeedFormat::detect(Some("application/atom+xml; charset=utf-8"), b""),
+            FeedFormat::Atom
+        );
+        assert_eq!(
+            FeedFormat::detect(Some("application/feed+json"), b"<rss/>"),
+            FeedFormat::JsonFeed
+        );
+        assert_eq!(
+            FeedFormat::detect(Some("text/xml"), ATOM.as_bytes()),
+            FeedFormat::Atom
+        );
+        assert_eq!(
+            FeedFormat::detect(Some("application/json"), JSON_FEED.as_bytes()),
+            FeedFormat::JsonFeed
+        );
+        assert_eq!(
+            FeedFormat::detect(
+                None,
+                b"\xef\xbb\xbf<?xml version=\"1.0\"?><rss version=\"2.0\"/>"
+            ),
+            FeedFormat::Rss
+        );
+        assert_eq!(
+            FeedFormat::detect(None, b"<!-- <feed> --><rdf:RDF/>"),
+            FeedFormat::Rss
+        );
+    }
+
+    #[test]
+    fn atom_entries_with_enclosures_become_items() {
+        let channel = parse_feed(ATOM.as_bytes(), None).unwrap();
+
+        assert_eq!(channel.title, "Atom Cast");
+        assert_eq!(channel.description, "Only published as Atom");
+        assert_eq!(channel.link, "https://example.com/");
+        assert_eq!(
+            channel.image.as_ref().map(|image| image.url.as_str()),
+            Some("https://example.com/cover.jpg")
+        );
+        assert_eq!(
+            channel
+                .itunes_ext
+                .as_ref()
+                .and_then(|ext| ext.author.as_deref()),
+            Some("Jane Doe")
+        );
+
+        let item = &channel.items[0];
+        assert_eq!(item.title.as_deref(), Some("First episode"));
+        assert_eq!(item.description.as_deref(), Some("Where it all starts"));
+        assert_eq!(
+            item.guid.as_ref().map(|guid| guid.value.as_str()),
+            Some("urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a")
+        );
+        let enclosure = item.enclosure.as_ref().unwrap();
+        assert_eq!(enclosure.url, "https://example.com/1.mp3");
+        assert_eq!(enclosure.length, "1234");
+        assert_eq!(enclosure.mime_type, "audio/mpeg");
+        assert_eq!(
+            item.pub_date.as_deref(),
+            Some("Fri, 1 Mar 2024 09:00:00 +0000")
+        );
+        assert_eq!(
+            item.itunes_ext
+                .as_ref()
+                .and_then(|ext| ext.duration.as_deref()),
+            Some("12:34")
+        );
+    }
+
+    #[test]
+    fn json_feed_items_use_their_audio_attachment() {
+        let channel = parse_feed(JSON_FEED.as_bytes(), Some("application/json")).unwrap();
+
+        assert_eq!(channel.title, "JSON Cast");
+        assert_eq!(channel.link, "https://example.org/");
+        assert_eq!(
+            channel
+                .itunes_ext
+                .as_ref()
+                .and_then(|ext| ext.author.as_deref()),
+            Some("John Doe")
+        );
+
+        let item = &channel.items[0];
+        assert_eq!(item.title.as_deref(), Some("Pilot"));
+        assert_eq!(
+            item.guid.as_ref().map(|guid| guid.value.as_str()),
+            Some("42")
+        );
+        let enclosure = item.enclosure.as_ref().unwrap();
+        assert_eq!(enclosure.url, "https://example.org/pilot.m4a");
+        assert_eq!(enclosure.length, "5000");
+        assert_eq!(
+            item.itunes_ext
+                .as_ref()
+                .and_then(|ext| ext.duration.as_deref()),
+            Some("1800")
+        );
+        assert_eq!(
+            item.pub_date.as_deref(),
+            Some("Fri, 1 Mar 2024 09:00:00 +0100")
+        );
+    }
+
+    #[test]
+    fn broken_documents_report_their_format() {
+        let err = parse_feed(b"{\"title\": 1}", None).unwrap_err();
+        assert!(matches!(err, FeedError::JsonFeed(_)));
+        let err = parse_feed(b"<feed><title>", Some("application/atom+xml")).unwrap_err();
+        assert!(matches!(err, FeedError::Atom(_)));
+    }
+}
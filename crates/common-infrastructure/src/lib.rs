@@ -1,6 +1,7 @@
 pub mod config;
 pub mod db;
 pub mod error;
+pub mod feed;
 pub mod http;
 pub mod logging;
 pub mod mutex;
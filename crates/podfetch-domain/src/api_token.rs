@@ -0,0 +1,86 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// What a named api token may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiTokenScope {
+    /// Reading the generated RSS feeds.
+    RssRead,
+    /// Fetching episode audio, images and transcripts.
+    Stream,
+    /// Logging in to the gpodder API.
+    GpodderSync,
+    /// Logging in to and using the Audiobookshelf compatible API.
+    Audiobookshelf,
+    /// Everything the owning user may do, including the REST API.
+    Admin,
+}
+
+impl ApiTokenScope {
+    pub const ALL: [ApiTokenScope; 5] = [
+        ApiTokenScope::RssRead,
+        ApiTokenScope::Stream,
+        ApiTokenScope::GpodderSync,
+        ApiTokenScope::Audiobookshelf,
+        ApiTokenScope::Admin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiTokenScope::RssRead => "rss-read",
+            ApiTokenScope::Stream => "stream",
+            ApiTokenScope::GpodderSync => "gpodder-sync",
+            ApiTokenScope::Audiobookshelf => "audiobookshelf",
+            ApiTokenScope::Admin => "admin",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)] // fallible parse returning Option, not FromStr
+    pub fn from_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scope| scope.as_str() == s)
+    }
+}
+
+/// A named token a user hands out instead of their password. Only the sha256
+/// digest of the secret is stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_hash: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl ApiToken {
+    pub fn is_expired(&self, now: NaiveDateTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// `admin` tokens grant every other scope as well.
+    pub fn grants(&self, scope: ApiTokenScope) -> bool {
+        self.scopes
+            .iter()
+            .any(|granted| *granted == scope || *granted == ApiTokenScope::Admin)
+    }
+}
+
+pub trait ApiTokenRepository: Send + Sync {
+    type Error;
+
+    fn create(&self, token: ApiToken) -> Result<ApiToken, Self::Error>;
+
+    fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, Self::Error>;
+
+    fn list_by_user(&self, user_id: Uuid) -> Result<Vec<ApiToken>, Self::Error>;
+
+    fn delete(&self, id: Uuid, user_id: Uuid) -> Result<bool, Self::Error>;
+
+    fn update_last_used(&self, id: Uuid, last_used_at: NaiveDateTime) -> Result<(), Self::Error>;
+
+    /// Removes every token that expired by `now` and returns how many.
+    fn delete_expired(&self, now: NaiveDateTime) -> Result<usize, Self::Error>;
+}
@@ -0,0 +1,91 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// Administrative and security relevant actions that are written to the
+/// audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditAction {
+    PodcastDeleted,
+    DownloadsDeleted,
+    UserRoleChanged,
+    UserDeleted,
+    InviteCreated,
+    InviteDeleted,
+    SettingsUpdated,
+    TwoFactorReset,
+    StorageQuotaChanged,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 9] = [
+        AuditAction::PodcastDeleted,
+        AuditAction::DownloadsDeleted,
+        AuditAction::UserRoleChanged,
+        AuditAction::UserDeleted,
+        AuditAction::InviteCreated,
+        AuditAction::InviteDeleted,
+        AuditAction::SettingsUpdated,
+        AuditAction::TwoFactorReset,
+        AuditAction::StorageQuotaChanged,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::PodcastDeleted => "podcast.delete",
+            AuditAction::DownloadsDeleted => "podcast.downloads.delete",
+            AuditAction::UserRoleChanged => "user.role",
+            AuditAction::UserDeleted => "user.delete",
+            AuditAction::InviteCreated => "invite.create",
+            AuditAction::InviteDeleted => "invite.delete",
+            AuditAction::SettingsUpdated => "settings.update",
+            AuditAction::TwoFactorReset => "user.two-factor.reset",
+            AuditAction::StorageQuotaChanged => "user.storage-quota",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)] // fallible parse returning Option, not FromStr
+    pub fn from_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.as_str() == s)
+    }
+}
+
+/// One row of the append-only audit log. `actor` is the username at the time
+/// of the action, so entries stay readable after the user was deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    pub actor: String,
+    pub action: AuditAction,
+    /// What the action was applied to, e.g. a podcast id or a username.
+    pub target: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub target: Option<String>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+}
+
+pub trait AuditLogRepository: Send + Sync {
+    type Error;
+
+    fn append(&self, entry: AuditLogEntry) -> Result<AuditLogEntry, Self::Error>;
+
+    /// Matching entries, newest first.
+    fn find(
+        &self,
+        filter: &AuditLogFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<AuditLogEntry>, Self::Error>;
+
+    fn count(&self, filter: &AuditLogFilter) -> Result<i64, Self::Error>;
+
+    /// Drops entries created before `cutoff`; the only way rows leave the log.
+    fn delete_older_than(&self, cutoff: NaiveDateTime) -> Result<usize, Self::Error>;
+}
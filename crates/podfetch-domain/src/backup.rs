@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+/// Tables a backup covers, parents before children: rows can be inserted in
+/// this order with foreign keys enforced. Sessions, tokens, jobs and other
+/// state that a fresh instance rebuilds on its own are left out.
+pub const BACKUP_TABLES: &[&str] = &[
+    "users",
+    "transcoding_profiles",
+    "settings",
+    "podcasts",
+    "podcast_settings",
+    "podcast_episodes",
+    "podcast_episode_chapters",
+    "episode_sponsor_segments",
+    "podcast_episode_transcripts",
+    "podcast_episode_transcript_segments",
+    "episode_storage",
+    "devices",
+    "subscriptions",
+    "episodes",
+    "gpodder_settings",
+    "listening_events",
+    "favorites",
+    "favorite_podcast_episodes",
+    "playlists",
+    "playlist_items",
+    "tags",
+    "tags_podcasts",
+    "notification_channels",
+    "notification_rules",
+    "filters",
+    "sponsorblock_user_settings",
+    "two_factor_auth",
+    "user_storage_quotas",
+];
+
+/// Tables every instance fills on its first start. Rows in them don't make an
+/// instance non-empty; a restore replaces them.
+pub const PRESEEDED_TABLES: &[&str] = &["settings"];
+
+/// One row keyed by column name. Values are kept in their text form so a
+/// backup taken on SQLite restores into PostgreSQL and vice versa; the
+/// repository converts booleans and timestamps for the target backend.
+pub type BackupRow = BTreeMap<String, Option<String>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupTable {
+    pub name: String,
+    pub rows: Vec<BackupRow>,
+}
+
+pub trait BackupRepository: Send + Sync {
+    type Error;
+
+    /// All rows of one of the [`BACKUP_TABLES`]. Generated columns are
+    /// skipped.
+    fn export_table(&self, table: &str) -> Result<Vec<BackupRow>, Self::Error>;
+    fn count_rows(&self, table: &str) -> Result<i64, Self::Error>;
+    /// Empties the given tables and inserts their rows in one transaction.
+    /// Columns the target doesn't know are ignored, missing ones fall back
+    /// to their defaults.
+    fn restore(&self, tables: &[BackupTable]) -> Result<(), Self::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BACKUP_TABLES;
+    use std::collections::BTreeSet;
+    use std::path::Path;
+
+    /// Tables deliberately left out of backups. A table a migration adds must
+    /// end up either here or in [`BACKUP_TABLES`].
+    const NOT_BACKED_UP_TABLES: &[&str] = &[
+        // Sessions, credentials and one-off links.
+        "sessions",
+        "api_tokens",
+        "invites",
+        // Job queues and state rebuilt by the background tasks.
+        "download_jobs",
+        "episode_download_states",
+        "transcription_jobs",
+        "podcast_feed_states",
+        "episode_fingerprints",
+        "episode_triages",
+        "chapter_fetch_failures",
+        "device_sync_groups",
+        // Notifications that were already sent, and the audit trail.
+        "notifications",
+        "notification_dismissals",
+        "audit_log",
+        // Rebuilt by scanning the audiobook libraries.
+        "audiobookshelf_libraries",
+        "audiobookshelf_authors",
+        "audiobookshelf_narrators",
+        "audiobookshelf_series",
+        "audiobookshelf_books",
+        "audiobookshelf_book_authors",
+        "audiobookshelf_book_narrators",
+        "audiobookshelf_book_series",
+        "audiobookshelf_book_chapters",
+        "audiobookshelf_book_audio_files",
+        "audiobookshelf_media_progress",
+        "audiobookshelf_listening_sessions",
+        "audiobookshelf_playback_sessions",
+    ];
+
+    /// The tables left after running all `up.sql` migrations of a backend.
+    fn migrated_tables(backend: &str) -> BTreeSet<String> {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../migrations")
+            .join(backend);
+        let mut migrations = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path().join("up.sql"))
+            .filter(|path| path.exists())
+            .collect::<Vec<_>>();
+        migrations.sort();
+
+        let mut tables = BTreeSet::new();
+        for migration in migrations {
+            let sql = std::fs::read_to_string(migration).unwrap();
+            let sql = sql
+                .lines()
+                .map(|line| line.split("--").next().unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+                .replace('"', "");
+            for statement in sql.split(';') {
+                let words = statement.split_whitespace().collect::<Vec<_>>();
+                let words = words.as_slice();
+                match words {
+                    ["create", "table", "if", "not", "exists", name, ..]
+                    | ["create", "table", name, ..] => {
+                        tables.insert(name.split('(').next().unwrap_or_default().to_string());
+                    }
+                    ["drop", "table", "if", "exists", name, ..] | ["drop", "table", name, ..] => {
+                        tables.remove(*name);
+                    }
+                    ["alter", "table", from, "rename", "to", to, ..] => {
+                        tables.remove(*from);
+                        tables.insert(to.to_string());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        tables
+    }
+
+    #[test]
+    fn every_migrated_table_is_backed_up_or_excluded() {
+        for backend in ["sqlite", "postgres"] {
+            let unlisted = migrated_tables(backend)
+                .into_iter()
+                .filter(|table| {
+                    !BACKUP_TABLES.contains(&table.as_str())
+                        && !NOT_BACKED_UP_TABLES.contains(&table.as_str())
+                })
+                .collect::<Vec<_>>();
+            assert!(
+                unlisted.is_empty(),
+                "{backend} tables neither backed up nor excluded: {unlisted:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn listed_tables_exist() {
+        let tables = migrated_tables("sqlite");
+        for table in BACKUP_TABLES.iter().chain(NOT_BACKED_UP_TABLES) {
+            assert!(tables.contains(*table), "{table} is not a table");
+        }
+    }
+}
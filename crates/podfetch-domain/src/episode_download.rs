@@ -0,0 +1,152 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// Progress of an episode download that did not finish. The partial data
+/// stays next to `file_path` (as `<file_path>.part`) so the next attempt can
+/// continue with a `Range` request instead of starting from byte zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpisodeDownloadState {
+    pub episode_id: Uuid,
+    /// Final location of the episode file once the download completes.
+    pub file_path: String,
+    pub bytes_received: i64,
+    /// Validators of the enclosure response the partial data belongs to.
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+pub trait EpisodeDownloadStateRepository: Send + Sync {
+    type Error;
+
+    fn get(&self, episode_id: Uuid) -> Result<Option<EpisodeDownloadState>, Self::Error>;
+
+    /// Insert or replace the state of an episode's download.
+    fn upsert(&self, state: EpisodeDownloadState) -> Result<(), Self::Error>;
+
+    /// Drop the state once the download completed or was abandoned.
+    fn delete(&self, episode_id: Uuid) -> Result<usize, Self::Error>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadJobStatus {
+    Pending,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+/// A queued episode download. Jobs with a higher `priority` are picked first;
+/// within the same priority the oldest job wins. `next_run_at` pushes a job
+/// back after a failed attempt.
+#[derive(Debug, Clone)]
+pub struct DownloadJob {
+    pub id: Uuid,
+    pub episode_id: Uuid,
+    pub podcast_id: Uuid,
+    pub status: DownloadJobStatus,
+    pub priority: i32,
+    pub attempts: i32,
+    pub error: Option<String>,
+    pub next_run_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// The user who asked for the download, `None` for automatic ones.
+    pub requested_by: Option<Uuid>,
+}
+
+pub trait DownloadJobRepository: Send + Sync {
+    type Error;
+
+    /// Queues a download for the episode. Returns Ok(None) if a job for the
+    /// episode is already pending or running; a finished, failed or cancelled
+    /// job is put back on the queue instead.
+    fn enqueue(
+        &self,
+        episode_id: Uuid,
+        podcast_id: Uuid,
+        priority: i32,
+        requested_by: Option<Uuid>,
+    ) -> Result<Option<DownloadJob>, Self::Error>;
+
+    /// Atomically moves the most urgent pending job that is due at `now` to
+    /// `running` and returns it.
+    fn claim_next_due(&self, now: NaiveDateTime) -> Result<Option<DownloadJob>, Self::Error>;
+
+    fn set_status(
+        &self,
+        id: Uuid,
+        status: DownloadJobStatus,
+        error: Option<&str>,
+    ) -> Result<(), Self::Error>;
+
+    /// Puts a failed job back to `pending`, not to be picked before `next_run_at`.
+    fn schedule_retry(
+        &self,
+        id: Uuid,
+        next_run_at: NaiveDateTime,
+        error: &str,
+    ) -> Result<(), Self::Error>;
+
+    fn increment_attempts(&self, id: Uuid) -> Result<i32, Self::Error>;
+
+    /// Resets the job to a fresh pending state: no attempts, no error, due now.
+    fn requeue(&self, id: Uuid) -> Result<(), Self::Error>;
+
+    fn set_priority(&self, id: Uuid, priority: i32) -> Result<(), Self::Error>;
+
+    fn reset_running_to_pending(&self) -> Result<usize, Self::Error>;
+
+    fn get(&self, id: Uuid) -> Result<Option<DownloadJob>, Self::Error>;
+
+    /// All jobs, optionally limited to one status, most urgent first.
+    fn list(&self, status: Option<DownloadJobStatus>) -> Result<Vec<DownloadJob>, Self::Error>;
+
+    /// Number of jobs per status; statuses without any job are left out.
+    fn count_by_status(&self) -> Result<Vec<(DownloadJobStatus, i64)>, Self::Error>;
+}
+
+impl DownloadJobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DownloadJobStatus::Pending => "pending",
+            DownloadJobStatus::Running => "running",
+            DownloadJobStatus::Done => "done",
+            DownloadJobStatus::Failed => "failed",
+            DownloadJobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)] // fallible parse returning Option, not FromStr
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(DownloadJobStatus::Pending),
+            "running" => Some(DownloadJobStatus::Running),
+            "done" => Some(DownloadJobStatus::Done),
+            "failed" => Some(DownloadJobStatus::Failed),
+            "cancelled" => Some(DownloadJobStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn download_job_status_roundtrip() {
+        let variants = vec![
+            DownloadJobStatus::Pending,
+            DownloadJobStatus::Running,
+            DownloadJobStatus::Done,
+            DownloadJobStatus::Failed,
+            DownloadJobStatus::Cancelled,
+        ];
+        for variant in variants {
+            assert_eq!(DownloadJobStatus::from_str(variant.as_str()), Some(variant));
+        }
+        assert_eq!(DownloadJobStatus::from_str("unknown"), None);
+    }
+}
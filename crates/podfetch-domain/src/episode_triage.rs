@@ -72,4 +72,7 @@ pub trait EpisodeTriageRepository: Send + Sync {
     /// Every episode id the user has triaged (any status). Used to exclude
     /// already-decided episodes from the inbox.
     fn list_triaged_episode_ids(&self, user_id: Uuid) -> Result<Vec<Uuid>, Self::Error>;
+
+    /// Every triage row of the user, with the time of the last decision.
+    fn list_by_user(&self, user_id: Uuid) -> Result<Vec<EpisodeTriage>, Self::Error>;
 }
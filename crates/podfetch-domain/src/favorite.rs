@@ -55,6 +55,8 @@ pub trait FavoriteRepository: Send + Sync {
         podcast_id: Uuid,
     ) -> Result<Option<Favorite>, Self::Error>;
     fn find_favored_by_user_id(&self, user_id: Uuid) -> Result<Vec<Favorite>, Self::Error>;
+    /// Users who marked the podcast as a favorite.
+    fn find_user_ids_favoring(&self, podcast_id: Uuid) -> Result<Vec<Uuid>, Self::Error>;
     fn delete_by_user_id(&self, user_id: Uuid) -> Result<(), Self::Error>;
 
     /// Update or insert a favorite status for a podcast.
@@ -1,7 +1,11 @@
+pub mod api_token;
 pub mod audiobookshelf;
+pub mod audit_log;
+pub mod backup;
 pub mod device;
 pub mod device_sync_group;
 pub mod episode;
+pub mod episode_download;
 pub mod episode_triage;
 pub mod favorite;
 pub mod favorite_podcast_episode;
@@ -11,16 +15,23 @@ pub mod ids;
 pub mod invite;
 pub mod listening_event;
 pub mod notification;
+pub mod notification_channel;
+pub mod notification_rule;
 pub mod ordering;
 pub mod playlist;
 pub mod podcast;
 pub mod podcast_episode;
 pub mod podcast_episode_chapter;
 pub mod podcast_episode_transcript;
+pub mod podcast_feed;
 pub mod podcast_settings;
+pub mod search;
 pub mod session;
 pub mod settings;
+pub mod storage_usage;
 pub mod subscription;
 pub mod tag;
+pub mod transcoding_profile;
+pub mod two_factor;
 pub mod user;
 pub mod user_admin;
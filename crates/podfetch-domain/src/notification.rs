@@ -7,12 +7,19 @@ pub struct Notification {
     pub message: String,
     pub created_at: String,
     pub status: String,
+    /// Owner of the notification; `None` for notifications every user sees.
+    pub user_id: Option<Uuid>,
 }
 
 pub trait NotificationRepository: Send + Sync {
     type Error;
 
     fn create(&self, notification: Notification) -> Result<Notification, Self::Error>;
-    fn get_unread_notifications(&self) -> Result<Vec<Notification>, Self::Error>;
+    /// Unread notifications owned by the user or addressed to everyone,
+    /// without the ones the user dismissed.
+    fn get_unread_notifications(&self, user_id: Uuid) -> Result<Vec<Notification>, Self::Error>;
     fn update_status_of_notification(&self, id: Uuid, status: &str) -> Result<(), Self::Error>;
+    /// Hides the notification for this user only. Unknown ids and
+    /// notifications of other users are ignored.
+    fn dismiss_notification(&self, id: Uuid, user_id: Uuid) -> Result<(), Self::Error>;
 }
@@ -0,0 +1,101 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// Where a notification channel delivers its messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationChannelKind {
+    Webhook,
+    Ntfy,
+    Gotify,
+    Smtp,
+}
+
+impl NotificationChannelKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationChannelKind::Webhook => "webhook",
+            NotificationChannelKind::Ntfy => "ntfy",
+            NotificationChannelKind::Gotify => "gotify",
+            NotificationChannelKind::Smtp => "smtp",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)] // fallible parse returning Option, not FromStr
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "webhook" => Some(NotificationChannelKind::Webhook),
+            "ntfy" => Some(NotificationChannelKind::Ntfy),
+            "gotify" => Some(NotificationChannelKind::Gotify),
+            "smtp" => Some(NotificationChannelKind::Smtp),
+            _ => None,
+        }
+    }
+}
+
+/// The events a channel can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationEventType {
+    NewEpisode,
+    DownloadFailed,
+    TranscriptionDone,
+    FeedBroken,
+}
+
+impl NotificationEventType {
+    pub const ALL: [NotificationEventType; 4] = [
+        NotificationEventType::NewEpisode,
+        NotificationEventType::DownloadFailed,
+        NotificationEventType::TranscriptionDone,
+        NotificationEventType::FeedBroken,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationEventType::NewEpisode => "new-episode",
+            NotificationEventType::DownloadFailed => "download-failed",
+            NotificationEventType::TranscriptionDone => "transcription-done",
+            NotificationEventType::FeedBroken => "feed-broken",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)] // fallible parse returning Option, not FromStr
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "new-episode" => Some(NotificationEventType::NewEpisode),
+            "download-failed" => Some(NotificationEventType::DownloadFailed),
+            "transcription-done" => Some(NotificationEventType::TranscriptionDone),
+            "feed-broken" => Some(NotificationEventType::FeedBroken),
+            _ => None,
+        }
+    }
+}
+
+/// A configured notification target. `config` is the JSON document with the
+/// kind specific settings (URL, token, SMTP server, ...).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationChannel {
+    pub id: Uuid,
+    pub name: String,
+    pub kind: NotificationChannelKind,
+    pub enabled: bool,
+    pub events: Vec<NotificationEventType>,
+    pub config: String,
+    pub created_at: NaiveDateTime,
+}
+
+pub trait NotificationChannelRepository: Send + Sync {
+    type Error;
+
+    fn create(&self, channel: NotificationChannel) -> Result<NotificationChannel, Self::Error>;
+
+    fn update(
+        &self,
+        channel: NotificationChannel,
+    ) -> Result<Option<NotificationChannel>, Self::Error>;
+
+    fn delete(&self, id: Uuid) -> Result<bool, Self::Error>;
+
+    fn get(&self, id: Uuid) -> Result<Option<NotificationChannel>, Self::Error>;
+
+    fn list(&self) -> Result<Vec<NotificationChannel>, Self::Error>;
+}
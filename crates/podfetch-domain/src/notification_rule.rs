@@ -0,0 +1,32 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// Decides which new episodes notify a user. A rule is scoped to a podcast,
+/// to the podcasts of one of the user's tags, or to all podcasts when neither
+/// is set; the optional conditions must all hold. Users without any rule are
+/// notified about every new episode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationRule {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub podcast_id: Option<Uuid>,
+    pub tag_id: Option<String>,
+    pub min_duration_seconds: Option<i32>,
+    pub max_duration_seconds: Option<i32>,
+    pub title_regex: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+pub trait NotificationRuleRepository: Send + Sync {
+    type Error;
+
+    fn create(&self, rule: NotificationRule) -> Result<NotificationRule, Self::Error>;
+
+    fn update(&self, rule: NotificationRule) -> Result<Option<NotificationRule>, Self::Error>;
+
+    fn delete(&self, id: Uuid, user_id: Uuid) -> Result<bool, Self::Error>;
+
+    fn get(&self, id: Uuid, user_id: Uuid) -> Result<Option<NotificationRule>, Self::Error>;
+
+    fn list_by_user(&self, user_id: Uuid) -> Result<Vec<NotificationRule>, Self::Error>;
+}
@@ -5,6 +5,9 @@ pub struct Playlist {
     pub id: String,
     pub name: String,
     pub user_id: Uuid,
+    /// JSON rules of a smart playlist. Smart playlists have no stored items;
+    /// their episodes are selected whenever the playlist is read.
+    pub smart_query: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -32,6 +35,12 @@ pub trait PlaylistRepository: Send + Sync {
         user_id: Uuid,
         name: &str,
     ) -> Result<usize, Self::Error>;
+    fn update_smart_query(
+        &self,
+        playlist_id: &str,
+        user_id: Uuid,
+        query: Option<&str>,
+    ) -> Result<usize, Self::Error>;
     fn delete_playlist(&self, playlist_id: &str, user_id: Uuid) -> Result<usize, Self::Error>;
     fn insert_playlist_item(&self, item: PlaylistItem) -> Result<PlaylistItem, Self::Error>;
     fn list_items_by_playlist_id(
@@ -5,7 +5,7 @@ use crate::episode::Episode;
 use crate::favorite_podcast_episode::FavoritePodcastEpisode;
 
 /// A podcast episode - technology-agnostic domain entity.
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct PodcastEpisode {
     pub id: Uuid,
     pub legacy_id: Option<i64>,
@@ -25,6 +25,14 @@ pub struct PodcastEpisode {
     pub episode_numbering_processed: bool,
     pub download_location: Option<String>,
     pub youtube_video_id: Option<String>,
+    pub season: Option<i32>,
+    pub episode_number: Option<i32>,
+    /// `full`, `trailer` or `bonus` as announced by `itunes:episodeType`.
+    pub episode_type: Option<String>,
+    /// Integrated loudness of the downloaded file in LUFS (EBU R128).
+    pub loudness_lufs: Option<f64>,
+    /// True peak of the downloaded file in dBTP.
+    pub loudness_true_peak: Option<f64>,
 }
 
 impl PodcastEpisode {
@@ -46,6 +54,72 @@ pub struct NewPodcastEpisode {
     pub description: String,
     pub guid: String,
     pub youtube_video_id: Option<String>,
+    pub season: Option<i32>,
+    pub episode_number: Option<i32>,
+    pub episode_type: Option<String>,
+}
+
+/// Order of a podcast's episode listing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EpisodeSort {
+    /// Newest first, paginated by `date_of_recording`.
+    #[default]
+    Date,
+    /// By season and episode number as announced by the feed, oldest first.
+    Episode,
+}
+
+impl EpisodeSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EpisodeSort::Date => "date",
+            EpisodeSort::Episode => "episode",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)] // fallible parse returning Option, not FromStr
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "date" => Some(EpisodeSort::Date),
+            "episode" => Some(EpisodeSort::Episode),
+            _ => None,
+        }
+    }
+}
+
+/// Optional narrowing and ordering of a podcast's episode listing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EpisodeListFilter {
+    pub season: Option<i32>,
+    pub episode_type: Option<String>,
+    pub sort: EpisodeSort,
+}
+
+/// Order of the episodes a smart playlist picks from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EpisodeCandidateOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+    ShortestFirst,
+    LongestFirst,
+}
+
+/// The part of a smart playlist query the database answers: the episodes'
+/// own columns, an order and a limit. Listening state, triage and favorites
+/// of the playlist owner are left to the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpisodeCandidateFilter {
+    /// `None` takes the episodes of every podcast.
+    pub podcast_ids: Option<Vec<Uuid>>,
+    pub downloaded: Option<bool>,
+    /// Lower bound on `date_of_recording`, compared as text like the other
+    /// date cursors.
+    pub recorded_from: Option<String>,
+    pub min_total_time: Option<i32>,
+    pub max_total_time: Option<i32>,
+    pub order: EpisodeCandidateOrder,
+    pub limit: i64,
 }
 
 /// Result type for paginated episode queries with history and favorites.
@@ -62,6 +136,8 @@ pub trait PodcastEpisodeRepository: Send + Sync {
     // Basic CRUD operations
     fn create(&self, episode: NewPodcastEpisode) -> Result<PodcastEpisode, Self::Error>;
     fn find_by_id(&self, id: Uuid) -> Result<Option<PodcastEpisode>, Self::Error>;
+    /// The episodes among `ids` that still exist, in no particular order.
+    fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<PodcastEpisode>, Self::Error>;
     /// Resolve an episode by its pre-migration integer id (backwards-compat for
     /// durable deep links). Returns `None` for rows created after the UUID
     /// migration (which have no `legacy_id`).
@@ -87,12 +163,15 @@ pub trait PodcastEpisodeRepository: Send + Sync {
 
     /// Get episodes of a podcast with watch history and favorites for a user.
     /// Returns episodes with optional history and favorites, paginated by date_of_recording.
+    /// Sorting by episode number returns every matching episode and ignores
+    /// `last_date` and `limit`.
     fn get_episodes_with_history(
         &self,
         podcast_id: Uuid,
         username: &str,
         last_date: Option<&str>,
         only_unlistened: bool,
+        filter: &EpisodeListFilter,
         limit: i64,
     ) -> Result<PodcastEpisodeWithHistory, Self::Error>;
 
@@ -116,6 +195,13 @@ pub trait PodcastEpisodeRepository: Send + Sync {
     // Get all episodes
     fn get_all(&self) -> Result<Vec<PodcastEpisode>, Self::Error>;
 
+    /// Non-deleted episodes matching `filter`, in its order and at most
+    /// `filter.limit` of them.
+    fn find_candidates(
+        &self,
+        filter: &EpisodeCandidateFilter,
+    ) -> Result<Vec<PodcastEpisode>, Self::Error>;
+
     /// Inbox query: non-deleted episodes whose id is **not** in
     /// `exclude_episode_ids` (the episodes the user has already triaged),
     /// newest first — regardless of download state. `last_date` is an exclusive
@@ -175,4 +261,12 @@ pub trait PodcastEpisodeRepository: Send + Sync {
         episode_id: &str,
         processed: bool,
     ) -> Result<(), Self::Error>;
+
+    /// Stores the loudness measured on the downloaded file, `None` clears it.
+    fn update_loudness(
+        &self,
+        episode_id: &str,
+        loudness_lufs: Option<f64>,
+        loudness_true_peak: Option<f64>,
+    ) -> Result<(), Self::Error>;
 }
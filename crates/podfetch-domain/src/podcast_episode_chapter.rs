@@ -1,6 +1,34 @@
 use chrono::NaiveDateTime;
 use uuid::Uuid;
 
+/// Where a chapter was read from. Feed chapters take precedence: once an
+/// episode has them, embedded chapters of the downloaded file are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChapterSource {
+    /// ID3/MP4 chapters of the downloaded audio file.
+    Embedded,
+    /// A `<podcast:chapters>` JSON document linked from the feed.
+    Feed,
+}
+
+impl ChapterSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChapterSource::Embedded => "embedded",
+            ChapterSource::Feed => "feed",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)] // fallible parse returning Option, not FromStr
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "embedded" => Some(ChapterSource::Embedded),
+            "feed" => Some(ChapterSource::Feed),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PodcastEpisodeChapter {
     pub id: String,
@@ -10,6 +38,9 @@ pub struct PodcastEpisodeChapter {
     pub end_time: i32,
     pub href: Option<String>,
     pub image: Option<String>,
+    pub source: ChapterSource,
+    /// URL of the chapters document for feed chapters.
+    pub source_url: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -24,13 +55,100 @@ pub struct UpsertPodcastEpisodeChapter {
     pub image: Option<String>,
 }
 
+/// Wait before a chapters document that failed is requested again. It
+/// doubles with every further failure, up to [`CHAPTER_RETRY_MAX_DELAY_HOURS`].
+pub const CHAPTER_RETRY_BASE_DELAY_HOURS: i64 = 1;
+pub const CHAPTER_RETRY_MAX_DELAY_HOURS: i64 = 7 * 24;
+
+/// A `<podcast:chapters>` document that couldn't be fetched or read, so the
+/// feed refresh doesn't request it again on every run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChapterFetchFailure {
+    pub episode_id: Uuid,
+    pub url: String,
+    /// Failed attempts in a row.
+    pub failures: i32,
+    pub last_failure_at: NaiveDateTime,
+    pub last_error: String,
+}
+
+impl ChapterFetchFailure {
+    /// The earliest time the document is requested again.
+    pub fn retry_at(&self) -> NaiveDateTime {
+        let exponent = self.failures.saturating_sub(1).clamp(0, 16) as u32;
+        let hours = CHAPTER_RETRY_BASE_DELAY_HOURS
+            .saturating_mul(2i64.pow(exponent))
+            .min(CHAPTER_RETRY_MAX_DELAY_HOURS);
+        self.last_failure_at + chrono::Duration::hours(hours)
+    }
+}
+
 pub trait PodcastEpisodeChapterRepository: Send + Sync {
     type Error;
 
+    /// Stores an embedded chapter. Does nothing when the episode already has
+    /// feed chapters.
     fn upsert(&self, chapter: UpsertPodcastEpisodeChapter) -> Result<(), Self::Error>;
 
+    /// Replaces all chapters of the episode, embedded ones included, with the
+    /// chapters read from the feed's chapters document at `source_url`.
+    fn replace_with_feed_chapters(
+        &self,
+        episode_id: Uuid,
+        source_url: &str,
+        chapters: Vec<UpsertPodcastEpisodeChapter>,
+    ) -> Result<(), Self::Error>;
+
     fn get_by_episode_id(
         &self,
         episode_id: Uuid,
     ) -> Result<Vec<PodcastEpisodeChapter>, Self::Error>;
+
+    fn get_fetch_failure(
+        &self,
+        episode_id: Uuid,
+    ) -> Result<Option<ChapterFetchFailure>, Self::Error>;
+
+    /// Counts a failed attempt to read the chapters document at `url`. A
+    /// different URL than the recorded one starts counting anew.
+    fn record_fetch_failure(
+        &self,
+        episode_id: Uuid,
+        url: &str,
+        error: &str,
+        at: NaiveDateTime,
+    ) -> Result<ChapterFetchFailure, Self::Error>;
+
+    fn clear_fetch_failure(&self, episode_id: Uuid) -> Result<(), Self::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(failures: i32) -> ChapterFetchFailure {
+        ChapterFetchFailure {
+            episode_id: Uuid::nil(),
+            url: "https://example.com/chapters.json".to_string(),
+            failures,
+            last_failure_at: NaiveDateTime::default(),
+            last_error: "404 Not Found".to_string(),
+        }
+    }
+
+    #[test]
+    fn retries_back_off_up_to_a_week() {
+        let after = |failures| failure(failures).retry_at() - NaiveDateTime::default();
+        assert_eq!(after(1), chrono::Duration::hours(1));
+        assert_eq!(after(2), chrono::Duration::hours(2));
+        assert_eq!(after(5), chrono::Duration::hours(16));
+        assert_eq!(
+            after(9),
+            chrono::Duration::hours(CHAPTER_RETRY_MAX_DELAY_HOURS)
+        );
+        assert_eq!(
+            after(40),
+            chrono::Duration::hours(CHAPTER_RETRY_MAX_DELAY_HOURS)
+        );
+    }
 }
@@ -131,6 +131,8 @@ pub trait TranscriptionJobRepository: Send + Sync {
     fn increment_attempts(&self, id: Uuid) -> Result<i32, Self::Error>;
     fn reset_running_to_pending(&self) -> Result<usize, Self::Error>;
     fn get_by_episode_id(&self, episode_id: Uuid) -> Result<Option<TranscriptionJob>, Self::Error>;
+    /// Number of jobs per status; statuses without any job are left out.
+    fn count_by_status(&self) -> Result<Vec<(TranscriptionJobStatus, i64)>, Self::Error>;
 }
 
 // String conversion implementations
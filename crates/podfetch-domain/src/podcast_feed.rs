@@ -0,0 +1,81 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// What the last successfully processed fetch of a podcast's feed looked
+/// like. The validators are sent back on the next poll so the server can
+/// answer `304 Not Modified`; the hash catches servers that don't support
+/// conditional requests but return the same document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PodcastFeedState {
+    pub podcast_id: Uuid,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// SHA-256 of the feed body, hex encoded.
+    pub content_hash: Option<String>,
+    /// Minutes the feed itself asks clients to wait between fetches, taken
+    /// from `<ttl>` or `<sy:updatePeriod>`/`<sy:updateFrequency>`.
+    pub update_interval_hint: Option<i32>,
+    /// When the podcast is due for its next refresh. `None` means right away.
+    pub next_refresh_at: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+}
+
+/// How the recent refreshes of a podcast's feed went. Kept next to the
+/// validators in the same row, but written independently of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PodcastFeedHealth {
+    pub podcast_id: Uuid,
+    pub last_success_at: Option<NaiveDateTime>,
+    pub last_failure_at: Option<NaiveDateTime>,
+    pub consecutive_failures: i32,
+    /// First failure of the current streak; `None` while the feed is healthy.
+    pub failing_since: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+    /// Status of the last response, `None` if the server was not reached.
+    pub last_http_status: Option<i32>,
+}
+
+pub trait PodcastFeedStateRepository: Send + Sync {
+    type Error;
+
+    fn get(&self, podcast_id: Uuid) -> Result<Option<PodcastFeedState>, Self::Error>;
+
+    fn list(&self) -> Result<Vec<PodcastFeedState>, Self::Error>;
+
+    /// Insert or replace the state of a podcast's feed.
+    fn upsert(&self, state: PodcastFeedState) -> Result<(), Self::Error>;
+
+    /// Schedules the next refresh, creating the state row if the feed was
+    /// never fetched successfully.
+    fn set_next_refresh_at(
+        &self,
+        podcast_id: Uuid,
+        next_refresh_at: NaiveDateTime,
+    ) -> Result<(), Self::Error>;
+
+    fn get_health(&self, podcast_id: Uuid) -> Result<Option<PodcastFeedHealth>, Self::Error>;
+
+    /// Health of every feed whose last refresh failed.
+    fn list_failing(&self) -> Result<Vec<PodcastFeedHealth>, Self::Error>;
+
+    /// Ends a failure streak.
+    fn record_success(
+        &self,
+        podcast_id: Uuid,
+        http_status: Option<i32>,
+        at: NaiveDateTime,
+    ) -> Result<(), Self::Error>;
+
+    /// Extends (or starts) the failure streak and returns the updated health.
+    fn record_failure(
+        &self,
+        podcast_id: Uuid,
+        error: &str,
+        http_status: Option<i32>,
+        at: NaiveDateTime,
+    ) -> Result<PodcastFeedHealth, Self::Error>;
+
+    /// Forgets the failure streak without recording a success, e.g. when a
+    /// deactivated feed is switched back on.
+    fn clear_failures(&self, podcast_id: Uuid) -> Result<(), Self::Error>;
+}
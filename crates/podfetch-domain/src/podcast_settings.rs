@@ -20,6 +20,28 @@ pub struct PodcastSetting {
     pub nfo_format: String,
     pub cover_filename: String,
     pub auto_transcribe: bool,
+    /// `fixed` or `adaptive`, see `refresh_interval`.
+    pub refresh_mode: String,
+    /// Minutes between two refreshes in `fixed` mode; 0 uses the global
+    /// polling interval. In `adaptive` mode it is used until the podcast has
+    /// enough episodes to derive its publishing cadence.
+    pub refresh_interval: i32,
+    /// Only the newest this many downloads are kept; 0 keeps all of them.
+    pub retention_keep_newest: i32,
+    /// Downloads are deleted this many days after every listener played or
+    /// archived them; 0 turns the rule off.
+    pub retention_played_days: i32,
+    /// Downloads that still wait for a listener are never deleted.
+    pub retention_keep_unplayed: bool,
+    /// The oldest downloads are deleted while the podcast takes up more than
+    /// this many MB; 0 means no cap.
+    pub retention_max_size_mb: i32,
+    /// `None` follows the instance settings, [`KEEP_ORIGINAL_AUDIO`] never
+    /// transcodes and anything else is the id of a transcoding profile used
+    /// even while instance-wide transcoding is off.
+    ///
+    /// [`KEEP_ORIGINAL_AUDIO`]: crate::transcoding_profile::KEEP_ORIGINAL_AUDIO
+    pub transcoding_profile_id: Option<String>,
 }
 
 pub trait PodcastSettingsRepository: Send + Sync {
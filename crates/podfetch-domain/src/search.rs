@@ -0,0 +1,169 @@
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+/// One unit of a parsed search query. All terms of a query must match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchTerm {
+    /// A bare word, matched as a prefix so results show up while typing.
+    Prefix(String),
+    /// Words given in double quotes, matched as an exact sequence.
+    Phrase(Vec<String>),
+}
+
+/// A search query reduced to words the full-text backends can take without
+/// interpreting any of them as operators.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchQuery {
+    pub terms: Vec<SearchTerm>,
+}
+
+impl SearchQuery {
+    /// Parses free-form user input. `"double quoted"` parts become phrases,
+    /// everything else is split into prefix words; a trailing `*` is
+    /// accepted and ignored since every bare word is a prefix anyway.
+    /// Characters other than letters and digits separate words, so FTS5 or
+    /// tsquery syntax in the input is never passed through.
+    pub fn parse(input: &str) -> Self {
+        let mut terms = Vec::new();
+        for (index, part) in input.split('"').enumerate() {
+            let words = words_of(part);
+            if words.is_empty() {
+                continue;
+            }
+            // Odd parts sit between two quotes. An unbalanced trailing quote
+            // still counts as a phrase up to the end of the input.
+            if index % 2 == 1 && words.len() > 1 {
+                terms.push(SearchTerm::Phrase(words));
+            } else {
+                terms.extend(words.into_iter().map(SearchTerm::Prefix));
+            }
+        }
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+}
+
+fn words_of(part: &str) -> Vec<String> {
+    part.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Restricts which podcasts and episodes a search may return. Episode-only
+/// criteria (`downloaded`, `from`, `to`) leave podcasts out of the result.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchFilter {
+    pub podcast_id: Option<Uuid>,
+    pub tag_id: Option<Uuid>,
+    pub downloaded: Option<bool>,
+    /// Earliest recording date, inclusive.
+    pub from: Option<NaiveDate>,
+    /// Latest recording date, inclusive.
+    pub to: Option<NaiveDate>,
+}
+
+impl SearchFilter {
+    pub fn filters_episodes_only(&self) -> bool {
+        self.downloaded.is_some() || self.from.is_some() || self.to.is_some()
+    }
+}
+
+/// `rank` is higher-is-better on both backends. Highlights wrap matches in
+/// `<b>`/`</b>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PodcastSearchHit {
+    pub podcast_id: Uuid,
+    pub name_highlight: String,
+    pub summary_snippet: String,
+    pub rank: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpisodeSearchHit {
+    pub episode_id: Uuid,
+    pub podcast_id: Uuid,
+    pub name_highlight: String,
+    pub description_snippet: String,
+    pub rank: f32,
+}
+
+pub trait SearchRepository: Send + Sync {
+    type Error;
+
+    /// Podcasts whose name, summary or author match, best match first.
+    fn search_podcasts(
+        &self,
+        query: &SearchQuery,
+        filter: &SearchFilter,
+        page: i64,
+        page_size: i64,
+    ) -> Result<Vec<PodcastSearchHit>, Self::Error>;
+    /// Episodes whose title or description match, best match first.
+    fn search_episodes(
+        &self,
+        query: &SearchQuery,
+        filter: &SearchFilter,
+        page: i64,
+        page_size: i64,
+    ) -> Result<Vec<EpisodeSearchHit>, Self::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefix(word: &str) -> SearchTerm {
+        SearchTerm::Prefix(word.to_string())
+    }
+
+    #[test]
+    fn parse_splits_bare_words_into_prefix_terms() {
+        let query = SearchQuery::parse("  Rust   async* ");
+        assert_eq!(query.terms, vec![prefix("rust"), prefix("async")]);
+    }
+
+    #[test]
+    fn parse_keeps_quoted_words_together_as_phrase() {
+        let query = SearchQuery::parse("linux \"open source\" news");
+        assert_eq!(
+            query.terms,
+            vec![
+                prefix("linux"),
+                SearchTerm::Phrase(vec!["open".to_string(), "source".to_string()]),
+                prefix("news"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_drops_operator_characters() {
+        let query = SearchQuery::parse("title:foo OR (bar) -baz");
+        assert_eq!(
+            query.terms,
+            vec![
+                prefix("title"),
+                prefix("foo"),
+                prefix("or"),
+                prefix("bar"),
+                prefix("baz"),
+            ]
+        );
+        assert!(SearchQuery::parse(" \"\" * ").is_empty());
+    }
+
+    #[test]
+    fn parse_treats_single_quoted_word_and_unbalanced_quote_sensibly() {
+        assert_eq!(SearchQuery::parse("\"rust\"").terms, vec![prefix("rust")]);
+        assert_eq!(
+            SearchQuery::parse("go \"error handling").terms,
+            vec![
+                prefix("go"),
+                SearchTerm::Phrase(vec!["error".to_string(), "handling".to_string()]),
+            ]
+        );
+    }
+}
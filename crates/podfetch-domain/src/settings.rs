@@ -32,6 +32,22 @@ pub struct Setting {
     pub nfo_format: String,
     /// Base name of the podcast cover file (default "image").
     pub cover_filename: String,
+    /// Days audit log entries are kept; 0 keeps them forever.
+    pub audit_log_retention_days: i32,
+    /// Space all downloads together may take up, in MB; 0 means unlimited.
+    pub storage_quota_mb: i32,
+    /// What happens once a storage quota is used up: "refuse" | "cleanup-oldest".
+    pub storage_quota_policy: String,
+    /// Days a feed may keep failing before it is deactivated; 0 never does.
+    pub dead_feed_deactivation_days: i32,
+    /// Profile downloads are transcoded with while `auto_transcode_opus` is
+    /// on; `None` uses the built-in 48 kbps Opus profile.
+    pub transcoding_profile_id: Option<Uuid>,
+    /// Measure the loudness of every download so players can level it out.
+    pub loudness_analysis: bool,
+    /// Look for long silences and content repeated across a podcast's
+    /// episodes in every download and store them as skippable segments.
+    pub skip_detection: bool,
 }
 
 #[derive(Clone)]
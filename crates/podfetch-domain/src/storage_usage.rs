@@ -0,0 +1,65 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// Bytes a downloaded episode takes up in the storage backend. The usage of a
+/// podcast is the sum over its episodes and counts towards whoever added the
+/// podcast.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpisodeStorage {
+    pub episode_id: Uuid,
+    pub podcast_id: Uuid,
+    pub bytes: i64,
+    pub recorded_at: NaiveDateTime,
+}
+
+/// Quota an admin set for one user, overriding the instance default.
+/// `quota_mb == 0` means unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserStorageQuota {
+    pub user_id: Uuid,
+    pub quota_mb: i32,
+}
+
+/// What happens to automatic downloads once a quota is used up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageQuotaPolicy {
+    /// Skip automatic downloads until space is freed again.
+    #[default]
+    Refuse,
+    /// Keep downloading and delete the oldest downloads until the quota
+    /// holds again.
+    CleanupOldest,
+}
+
+impl StorageQuotaPolicy {
+    pub const ALL: [StorageQuotaPolicy; 2] = [
+        StorageQuotaPolicy::Refuse,
+        StorageQuotaPolicy::CleanupOldest,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageQuotaPolicy::Refuse => "refuse",
+            StorageQuotaPolicy::CleanupOldest => "cleanup-oldest",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|policy| policy.as_str() == s)
+    }
+}
+
+pub trait StorageUsageRepository: Send + Sync {
+    type Error;
+
+    /// Inserts the entry or replaces the one recorded for the same episode.
+    fn upsert(&self, storage: EpisodeStorage) -> Result<(), Self::Error>;
+    fn delete_by_episode_id(&self, episode_id: Uuid) -> Result<(), Self::Error>;
+    /// Every entry, oldest recording first.
+    fn find_all(&self) -> Result<Vec<EpisodeStorage>, Self::Error>;
+    fn find_quota(&self, user_id: Uuid) -> Result<Option<UserStorageQuota>, Self::Error>;
+    fn find_all_quotas(&self) -> Result<Vec<UserStorageQuota>, Self::Error>;
+    fn save_quota(&self, quota: UserStorageQuota) -> Result<(), Self::Error>;
+    fn delete_quota(&self, user_id: Uuid) -> Result<(), Self::Error>;
+}
@@ -92,4 +92,7 @@ pub trait SubscriptionRepository: Send + Sync {
         device_id: &str,
         user_id: Uuid,
     ) -> Result<Vec<String>, Self::Error>;
+    /// Users with an active subscription to the podcast's feed on any
+    /// device.
+    fn find_subscriber_ids(&self, podcast_id: Uuid) -> Result<Vec<Uuid>, Self::Error>;
 }
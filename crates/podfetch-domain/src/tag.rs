@@ -67,6 +67,9 @@ pub trait TagRepository: Send + Sync {
         tag_id: &str,
         user_id: Uuid,
     ) -> Result<Option<Tag>, Self::Error>;
+    /// Podcasts the user has put under the tag.
+    fn get_podcast_ids_of_tag(&self, tag_id: &str, user_id: Uuid)
+    -> Result<Vec<Uuid>, Self::Error>;
     fn update(&self, tag_id: &str, update: TagUpdate) -> Result<Tag, Self::Error>;
     fn delete(&self, tag_id: &str) -> Result<(), Self::Error>;
     fn add_podcast_to_tag(
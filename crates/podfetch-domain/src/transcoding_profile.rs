@@ -0,0 +1,80 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// Per-podcast profile choice that keeps downloads as they are.
+pub const KEEP_ORIGINAL_AUDIO: &str = "original";
+
+/// The audio codec a profile encodes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioCodec {
+    Opus,
+    Mp3,
+    Aac,
+    Vorbis,
+}
+
+impl AudioCodec {
+    pub const ALL: [AudioCodec; 4] = [
+        AudioCodec::Opus,
+        AudioCodec::Mp3,
+        AudioCodec::Aac,
+        AudioCodec::Vorbis,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AudioCodec::Opus => "opus",
+            AudioCodec::Mp3 => "mp3",
+            AudioCodec::Aac => "aac",
+            AudioCodec::Vorbis => "vorbis",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)] // fallible parse returning Option, not FromStr
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "opus" => Some(AudioCodec::Opus),
+            "mp3" => Some(AudioCodec::Mp3),
+            "aac" => Some(AudioCodec::Aac),
+            "vorbis" => Some(AudioCodec::Vorbis),
+            _ => None,
+        }
+    }
+}
+
+/// A named set of ffmpeg options downloads are transcoded with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscodingProfile {
+    pub id: Uuid,
+    pub name: String,
+    pub codec: AudioCodec,
+    pub bitrate_kbps: i32,
+    /// 1 for mono, 2 for stereo, 0 keeps the channels of the source.
+    pub channels: i32,
+    /// In Hz, 0 keeps the sample rate of the source.
+    pub sample_rate: i32,
+    /// Normalize the loudness with ffmpeg's `loudnorm` filter.
+    pub loudnorm: bool,
+    /// Trim leading silence and shorten long pauses.
+    pub trim_silence: bool,
+    /// Keep the downloaded file next to the transcoded one.
+    pub keep_original: bool,
+    pub created_at: NaiveDateTime,
+}
+
+pub trait TranscodingProfileRepository: Send + Sync {
+    type Error;
+
+    fn create(&self, profile: TranscodingProfile) -> Result<TranscodingProfile, Self::Error>;
+
+    fn update(
+        &self,
+        profile: TranscodingProfile,
+    ) -> Result<Option<TranscodingProfile>, Self::Error>;
+
+    fn delete(&self, id: Uuid) -> Result<bool, Self::Error>;
+
+    fn get(&self, id: Uuid) -> Result<Option<TranscodingProfile>, Self::Error>;
+
+    fn list(&self) -> Result<Vec<TranscodingProfile>, Self::Error>;
+}
@@ -0,0 +1,39 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// RFC 6238 second factor of a user. Enrollment stores the secret with
+/// `enabled` unset until the user proved it with a first code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwoFactorAuth {
+    pub user_id: Uuid,
+    /// Base32 encoded shared secret.
+    pub secret: String,
+    pub enabled: bool,
+    /// sha256 digests of the unused recovery codes.
+    pub recovery_codes: Vec<String>,
+    /// Time step of the last accepted code; older and equal steps are
+    /// rejected so a code can't be replayed.
+    pub last_used_step: Option<i64>,
+    /// Wrong codes entered since the last accepted one.
+    pub failed_attempts: i32,
+    /// Codes aren't checked before this time after too many wrong ones.
+    pub locked_until: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+pub trait TwoFactorRepository: Send + Sync {
+    type Error;
+
+    fn find_by_user_id(&self, user_id: Uuid) -> Result<Option<TwoFactorAuth>, Self::Error>;
+
+    /// Inserts or replaces the second factor of `two_factor.user_id`.
+    fn save(&self, two_factor: TwoFactorAuth) -> Result<TwoFactorAuth, Self::Error>;
+
+    /// Stores `updated` in place of `current`, unless another request
+    /// changed or removed the stored second factor since `current` was read.
+    /// Returns whether it was stored.
+    fn replace(&self, current: &TwoFactorAuth, updated: TwoFactorAuth)
+    -> Result<bool, Self::Error>;
+
+    fn delete_by_user_id(&self, user_id: Uuid) -> Result<bool, Self::Error>;
+}
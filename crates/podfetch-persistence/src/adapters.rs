@@ -329,6 +329,296 @@ impl EpisodeTriageRepository for EpisodeTriageRepositoryImpl {
             .list_triaged_episode_ids(user_id)
             .map_err(Into::into)
     }
+
+    fn list_by_user(&self, user_id: Uuid) -> Result<Vec<EpisodeTriage>, Self::Error> {
+        self.inner.list_by_user(user_id).map_err(Into::into)
+    }
+}
+
+// ── EpisodeDownloadState ──────────────────────────────────────────────────────
+
+use crate::episode_download::{DieselDownloadJobRepository, DieselEpisodeDownloadStateRepository};
+use podfetch_domain::episode_download::{
+    DownloadJob, DownloadJobRepository, DownloadJobStatus, EpisodeDownloadState,
+    EpisodeDownloadStateRepository,
+};
+
+pub struct EpisodeDownloadStateRepositoryImpl {
+    inner: DieselEpisodeDownloadStateRepository,
+}
+
+impl EpisodeDownloadStateRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselEpisodeDownloadStateRepository::new(database),
+        }
+    }
+}
+
+impl EpisodeDownloadStateRepository for EpisodeDownloadStateRepositoryImpl {
+    type Error = CustomError;
+
+    fn get(&self, episode_id: Uuid) -> Result<Option<EpisodeDownloadState>, Self::Error> {
+        self.inner.get(episode_id).map_err(Into::into)
+    }
+
+    fn upsert(&self, state: EpisodeDownloadState) -> Result<(), Self::Error> {
+        self.inner.upsert(state).map_err(Into::into)
+    }
+
+    fn delete(&self, episode_id: Uuid) -> Result<usize, Self::Error> {
+        self.inner.delete(episode_id).map_err(Into::into)
+    }
+}
+
+// ── PodcastFeedState ──────────────────────────────────────────────────────────
+
+use crate::podcast_feed::DieselPodcastFeedStateRepository;
+use podfetch_domain::podcast_feed::{
+    PodcastFeedHealth, PodcastFeedState, PodcastFeedStateRepository,
+};
+
+pub struct PodcastFeedStateRepositoryImpl {
+    inner: DieselPodcastFeedStateRepository,
+}
+
+impl PodcastFeedStateRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselPodcastFeedStateRepository::new(database),
+        }
+    }
+}
+
+impl PodcastFeedStateRepository for PodcastFeedStateRepositoryImpl {
+    type Error = CustomError;
+
+    fn get(&self, podcast_id: Uuid) -> Result<Option<PodcastFeedState>, Self::Error> {
+        self.inner.get(podcast_id).map_err(Into::into)
+    }
+
+    fn list(&self) -> Result<Vec<PodcastFeedState>, Self::Error> {
+        self.inner.list().map_err(Into::into)
+    }
+
+    fn upsert(&self, state: PodcastFeedState) -> Result<(), Self::Error> {
+        self.inner.upsert(state).map_err(Into::into)
+    }
+
+    fn set_next_refresh_at(
+        &self,
+        podcast_id: Uuid,
+        next_refresh_at: NaiveDateTime,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .set_next_refresh_at(podcast_id, next_refresh_at)
+            .map_err(Into::into)
+    }
+
+    fn get_health(&self, podcast_id: Uuid) -> Result<Option<PodcastFeedHealth>, Self::Error> {
+        self.inner.get_health(podcast_id).map_err(Into::into)
+    }
+
+    fn list_failing(&self) -> Result<Vec<PodcastFeedHealth>, Self::Error> {
+        self.inner.list_failing().map_err(Into::into)
+    }
+
+    fn record_success(
+        &self,
+        podcast_id: Uuid,
+        http_status: Option<i32>,
+        at: NaiveDateTime,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .record_success(podcast_id, http_status, at)
+            .map_err(Into::into)
+    }
+
+    fn record_failure(
+        &self,
+        podcast_id: Uuid,
+        error: &str,
+        http_status: Option<i32>,
+        at: NaiveDateTime,
+    ) -> Result<PodcastFeedHealth, Self::Error> {
+        self.inner
+            .record_failure(podcast_id, error, http_status, at)
+            .map_err(Into::into)
+    }
+
+    fn clear_failures(&self, podcast_id: Uuid) -> Result<(), Self::Error> {
+        self.inner.clear_failures(podcast_id).map_err(Into::into)
+    }
+}
+
+// ── NotificationChannel ───────────────────────────────────────────────────────
+
+use crate::notification_channel::DieselNotificationChannelRepository;
+use podfetch_domain::notification_channel::{NotificationChannel, NotificationChannelRepository};
+
+pub struct NotificationChannelRepositoryImpl {
+    inner: DieselNotificationChannelRepository,
+}
+
+impl NotificationChannelRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselNotificationChannelRepository::new(database),
+        }
+    }
+}
+
+impl NotificationChannelRepository for NotificationChannelRepositoryImpl {
+    type Error = CustomError;
+
+    fn create(&self, channel: NotificationChannel) -> Result<NotificationChannel, Self::Error> {
+        self.inner.create(channel).map_err(Into::into)
+    }
+
+    fn update(
+        &self,
+        channel: NotificationChannel,
+    ) -> Result<Option<NotificationChannel>, Self::Error> {
+        self.inner.update(channel).map_err(Into::into)
+    }
+
+    fn delete(&self, id: Uuid) -> Result<bool, Self::Error> {
+        self.inner.delete(id).map_err(Into::into)
+    }
+
+    fn get(&self, id: Uuid) -> Result<Option<NotificationChannel>, Self::Error> {
+        self.inner.get(id).map_err(Into::into)
+    }
+
+    fn list(&self) -> Result<Vec<NotificationChannel>, Self::Error> {
+        self.inner.list().map_err(Into::into)
+    }
+}
+
+// ── TranscodingProfile ──────────────────────────────────────────────────────
+
+use crate::transcoding_profile::DieselTranscodingProfileRepository;
+use podfetch_domain::transcoding_profile::{TranscodingProfile, TranscodingProfileRepository};
+
+pub struct TranscodingProfileRepositoryImpl {
+    inner: DieselTranscodingProfileRepository,
+}
+
+impl TranscodingProfileRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselTranscodingProfileRepository::new(database),
+        }
+    }
+}
+
+impl TranscodingProfileRepository for TranscodingProfileRepositoryImpl {
+    type Error = CustomError;
+
+    fn create(&self, profile: TranscodingProfile) -> Result<TranscodingProfile, Self::Error> {
+        self.inner.create(profile).map_err(Into::into)
+    }
+
+    fn update(
+        &self,
+        profile: TranscodingProfile,
+    ) -> Result<Option<TranscodingProfile>, Self::Error> {
+        self.inner.update(profile).map_err(Into::into)
+    }
+
+    fn delete(&self, id: Uuid) -> Result<bool, Self::Error> {
+        self.inner.delete(id).map_err(Into::into)
+    }
+
+    fn get(&self, id: Uuid) -> Result<Option<TranscodingProfile>, Self::Error> {
+        self.inner.get(id).map_err(Into::into)
+    }
+
+    fn list(&self) -> Result<Vec<TranscodingProfile>, Self::Error> {
+        self.inner.list().map_err(Into::into)
+    }
+}
+
+// ── DownloadJob ─────────────────────────────────────────────────────────────
+
+pub struct DownloadJobRepositoryImpl {
+    inner: DieselDownloadJobRepository,
+}
+
+impl DownloadJobRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselDownloadJobRepository::new(database),
+        }
+    }
+}
+
+impl DownloadJobRepository for DownloadJobRepositoryImpl {
+    type Error = CustomError;
+
+    fn enqueue(
+        &self,
+        episode_id: Uuid,
+        podcast_id: Uuid,
+        priority: i32,
+        requested_by: Option<Uuid>,
+    ) -> Result<Option<DownloadJob>, Self::Error> {
+        self.inner
+            .enqueue(episode_id, podcast_id, priority, requested_by)
+            .map_err(Into::into)
+    }
+
+    fn claim_next_due(&self, now: NaiveDateTime) -> Result<Option<DownloadJob>, Self::Error> {
+        self.inner.claim_next_due(now).map_err(Into::into)
+    }
+
+    fn set_status(
+        &self,
+        id: Uuid,
+        status: DownloadJobStatus,
+        error: Option<&str>,
+    ) -> Result<(), Self::Error> {
+        self.inner.set_status(id, status, error).map_err(Into::into)
+    }
+
+    fn schedule_retry(
+        &self,
+        id: Uuid,
+        next_run_at: NaiveDateTime,
+        error: &str,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .schedule_retry(id, next_run_at, error)
+            .map_err(Into::into)
+    }
+
+    fn increment_attempts(&self, id: Uuid) -> Result<i32, Self::Error> {
+        self.inner.increment_attempts(id).map_err(Into::into)
+    }
+
+    fn requeue(&self, id: Uuid) -> Result<(), Self::Error> {
+        self.inner.requeue(id).map_err(Into::into)
+    }
+
+    fn set_priority(&self, id: Uuid, priority: i32) -> Result<(), Self::Error> {
+        self.inner.set_priority(id, priority).map_err(Into::into)
+    }
+
+    fn reset_running_to_pending(&self) -> Result<usize, Self::Error> {
+        self.inner.reset_running_to_pending().map_err(Into::into)
+    }
+
+    fn get(&self, id: Uuid) -> Result<Option<DownloadJob>, Self::Error> {
+        self.inner.get(id).map_err(Into::into)
+    }
+
+    fn list(&self, status: Option<DownloadJobStatus>) -> Result<Vec<DownloadJob>, Self::Error> {
+        self.inner.list(status).map_err(Into::into)
+    }
+
+    fn count_by_status(&self) -> Result<Vec<(DownloadJobStatus, i64)>, Self::Error> {
+        self.inner.count_by_status().map_err(Into::into)
+    }
 }
 
 // ── ListeningEvent ──────────────────────────────────────────────────────────
@@ -404,8 +694,10 @@ impl NotificationRepository for NotificationRepositoryImpl {
         self.inner.create(notification).map_err(Into::into)
     }
 
-    fn get_unread_notifications(&self) -> Result<Vec<Notification>, Self::Error> {
-        self.inner.get_unread_notifications().map_err(Into::into)
+    fn get_unread_notifications(&self, user_id: Uuid) -> Result<Vec<Notification>, Self::Error> {
+        self.inner
+            .get_unread_notifications(user_id)
+            .map_err(Into::into)
     }
 
     fn update_status_of_notification(&self, id: Uuid, status: &str) -> Result<(), Self::Error> {
@@ -413,6 +705,53 @@ impl NotificationRepository for NotificationRepositoryImpl {
             .update_status_of_notification(id, status)
             .map_err(Into::into)
     }
+
+    fn dismiss_notification(&self, id: Uuid, user_id: Uuid) -> Result<(), Self::Error> {
+        self.inner
+            .dismiss_notification(id, user_id)
+            .map_err(Into::into)
+    }
+}
+
+// ── NotificationRule ──────────────────────────────────────────────────────────
+
+use crate::notification_rule::DieselNotificationRuleRepository;
+use podfetch_domain::notification_rule::{NotificationRule, NotificationRuleRepository};
+
+pub struct NotificationRuleRepositoryImpl {
+    inner: DieselNotificationRuleRepository,
+}
+
+impl NotificationRuleRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselNotificationRuleRepository::new(database),
+        }
+    }
+}
+
+impl NotificationRuleRepository for NotificationRuleRepositoryImpl {
+    type Error = CustomError;
+
+    fn create(&self, rule: NotificationRule) -> Result<NotificationRule, Self::Error> {
+        self.inner.create(rule).map_err(Into::into)
+    }
+
+    fn update(&self, rule: NotificationRule) -> Result<Option<NotificationRule>, Self::Error> {
+        self.inner.update(rule).map_err(Into::into)
+    }
+
+    fn delete(&self, id: Uuid, user_id: Uuid) -> Result<bool, Self::Error> {
+        self.inner.delete(id, user_id).map_err(Into::into)
+    }
+
+    fn get(&self, id: Uuid, user_id: Uuid) -> Result<Option<NotificationRule>, Self::Error> {
+        self.inner.get(id, user_id).map_err(Into::into)
+    }
+
+    fn list_by_user(&self, user_id: Uuid) -> Result<Vec<NotificationRule>, Self::Error> {
+        self.inner.list_by_user(user_id).map_err(Into::into)
+    }
 }
 
 // ── Playlist ────────────────────────────────────────────────────────────────
@@ -472,6 +811,17 @@ impl PlaylistRepository for PlaylistRepositoryImpl {
             .map_err(Into::into)
     }
 
+    fn update_smart_query(
+        &self,
+        playlist_id: &str,
+        user_id: Uuid,
+        query: Option<&str>,
+    ) -> Result<usize, Self::Error> {
+        self.inner
+            .update_smart_query(playlist_id, user_id, query)
+            .map_err(Into::into)
+    }
+
     fn delete_playlist(&self, playlist_id: &str, user_id: Uuid) -> Result<usize, Self::Error> {
         self.inner
             .delete_playlist(playlist_id, user_id)
@@ -518,7 +868,8 @@ impl PlaylistRepository for PlaylistRepositoryImpl {
 
 use crate::podcast_episode_chapter::DieselPodcastEpisodeChapterRepository;
 use podfetch_domain::podcast_episode_chapter::{
-    PodcastEpisodeChapter, PodcastEpisodeChapterRepository, UpsertPodcastEpisodeChapter,
+    ChapterFetchFailure, PodcastEpisodeChapter, PodcastEpisodeChapterRepository,
+    UpsertPodcastEpisodeChapter,
 };
 
 pub struct PodcastEpisodeChapterRepositoryImpl {
@@ -540,12 +891,48 @@ impl PodcastEpisodeChapterRepository for PodcastEpisodeChapterRepositoryImpl {
         self.inner.upsert(chapter).map_err(Into::into)
     }
 
+    fn replace_with_feed_chapters(
+        &self,
+        episode_id: Uuid,
+        source_url: &str,
+        chapters: Vec<UpsertPodcastEpisodeChapter>,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .replace_with_feed_chapters(episode_id, source_url, chapters)
+            .map_err(Into::into)
+    }
+
     fn get_by_episode_id(
         &self,
         episode_id: Uuid,
     ) -> Result<Vec<PodcastEpisodeChapter>, Self::Error> {
         self.inner.get_by_episode_id(episode_id).map_err(Into::into)
     }
+
+    fn get_fetch_failure(
+        &self,
+        episode_id: Uuid,
+    ) -> Result<Option<ChapterFetchFailure>, Self::Error> {
+        self.inner.get_fetch_failure(episode_id).map_err(Into::into)
+    }
+
+    fn record_fetch_failure(
+        &self,
+        episode_id: Uuid,
+        url: &str,
+        error: &str,
+        at: NaiveDateTime,
+    ) -> Result<ChapterFetchFailure, Self::Error> {
+        self.inner
+            .record_fetch_failure(episode_id, url, error, at)
+            .map_err(Into::into)
+    }
+
+    fn clear_fetch_failure(&self, episode_id: Uuid) -> Result<(), Self::Error> {
+        self.inner
+            .clear_fetch_failure(episode_id)
+            .map_err(Into::into)
+    }
 }
 
 // ── PodcastSettings ─────────────────────────────────────────────────────────
@@ -725,6 +1112,12 @@ impl SubscriptionRepository for SubscriptionRepositoryImpl {
             .get_active_device_podcast_urls(device_id, user_id)
             .map_err(Into::into)
     }
+
+    fn find_subscriber_ids(&self, podcast_id: Uuid) -> Result<Vec<Uuid>, Self::Error> {
+        self.inner
+            .find_subscriber_ids(podcast_id)
+            .map_err(Into::into)
+    }
 }
 
 // ── Tag ─────────────────────────────────────────────────────────────────────
@@ -775,6 +1168,16 @@ impl TagRepository for TagRepositoryImpl {
             .map_err(Into::into)
     }
 
+    fn get_podcast_ids_of_tag(
+        &self,
+        tag_id: &str,
+        user_id: Uuid,
+    ) -> Result<Vec<Uuid>, Self::Error> {
+        self.inner
+            .get_podcast_ids_of_tag(tag_id, user_id)
+            .map_err(Into::into)
+    }
+
     fn update(&self, tag_id: &str, update: TagUpdate) -> Result<Tag, Self::Error> {
         self.inner.update(tag_id, update).map_err(Into::into)
     }
@@ -850,6 +1253,10 @@ impl UserAdminRepository for UserAdminRepositoryImpl {
         self.inner.find_by_username(username).map_err(Into::into)
     }
 
+    fn find_by_id(&self, id: Uuid) -> Result<Option<ManagedUser>, Self::Error> {
+        self.inner.find_by_id(id).map_err(Into::into)
+    }
+
     fn find_all(&self) -> Result<Vec<ManagedUser>, Self::Error> {
         self.inner.find_all().map_err(Into::into)
     }
@@ -1348,4 +1755,271 @@ impl TranscriptionJobRepository for TranscriptionJobRepositoryImpl {
     fn get_by_episode_id(&self, episode_id: Uuid) -> Result<Option<TranscriptionJob>, Self::Error> {
         self.inner.get_by_episode_id(episode_id).map_err(Into::into)
     }
+
+    fn count_by_status(&self) -> Result<Vec<(TranscriptionJobStatus, i64)>, Self::Error> {
+        self.inner.count_by_status().map_err(Into::into)
+    }
+}
+
+// ── ApiToken ────────────────────────────────────────────────────────────────
+
+use crate::api_token::DieselApiTokenRepository;
+use podfetch_domain::api_token::{ApiToken, ApiTokenRepository};
+
+pub struct ApiTokenRepositoryImpl {
+    inner: DieselApiTokenRepository,
+}
+
+impl ApiTokenRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselApiTokenRepository::new(database),
+        }
+    }
+}
+
+impl ApiTokenRepository for ApiTokenRepositoryImpl {
+    type Error = CustomError;
+
+    fn create(&self, token: ApiToken) -> Result<ApiToken, Self::Error> {
+        self.inner.create(token).map_err(Into::into)
+    }
+
+    fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, Self::Error> {
+        self.inner
+            .find_by_token_hash(token_hash)
+            .map_err(Into::into)
+    }
+
+    fn list_by_user(&self, user_id: Uuid) -> Result<Vec<ApiToken>, Self::Error> {
+        self.inner.list_by_user(user_id).map_err(Into::into)
+    }
+
+    fn delete(&self, id: Uuid, user_id: Uuid) -> Result<bool, Self::Error> {
+        self.inner.delete(id, user_id).map_err(Into::into)
+    }
+
+    fn update_last_used(&self, id: Uuid, last_used_at: NaiveDateTime) -> Result<(), Self::Error> {
+        self.inner
+            .update_last_used(id, last_used_at)
+            .map_err(Into::into)
+    }
+
+    fn delete_expired(&self, now: NaiveDateTime) -> Result<usize, Self::Error> {
+        self.inner.delete_expired(now).map_err(Into::into)
+    }
+}
+
+// ── TwoFactor ───────────────────────────────────────────────────────────────
+
+use crate::two_factor::DieselTwoFactorRepository;
+use podfetch_domain::two_factor::{TwoFactorAuth, TwoFactorRepository};
+
+pub struct TwoFactorRepositoryImpl {
+    inner: DieselTwoFactorRepository,
+}
+
+impl TwoFactorRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselTwoFactorRepository::new(database),
+        }
+    }
+}
+
+impl TwoFactorRepository for TwoFactorRepositoryImpl {
+    type Error = CustomError;
+
+    fn find_by_user_id(&self, user_id: Uuid) -> Result<Option<TwoFactorAuth>, Self::Error> {
+        self.inner.find_by_user_id(user_id).map_err(Into::into)
+    }
+
+    fn save(&self, two_factor: TwoFactorAuth) -> Result<TwoFactorAuth, Self::Error> {
+        self.inner.save(two_factor).map_err(Into::into)
+    }
+
+    fn replace(
+        &self,
+        current: &TwoFactorAuth,
+        updated: TwoFactorAuth,
+    ) -> Result<bool, Self::Error> {
+        self.inner.replace(current, updated).map_err(Into::into)
+    }
+
+    fn delete_by_user_id(&self, user_id: Uuid) -> Result<bool, Self::Error> {
+        self.inner.delete_by_user_id(user_id).map_err(Into::into)
+    }
+}
+
+// ── AuditLog ────────────────────────────────────────────────────────────────
+
+use crate::audit_log::DieselAuditLogRepository;
+use podfetch_domain::audit_log::{AuditLogEntry, AuditLogFilter, AuditLogRepository};
+
+pub struct AuditLogRepositoryImpl {
+    inner: DieselAuditLogRepository,
+}
+
+impl AuditLogRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselAuditLogRepository::new(database),
+        }
+    }
+}
+
+impl AuditLogRepository for AuditLogRepositoryImpl {
+    type Error = CustomError;
+
+    fn append(&self, entry: AuditLogEntry) -> Result<AuditLogEntry, Self::Error> {
+        self.inner.append(entry).map_err(Into::into)
+    }
+
+    fn find(
+        &self,
+        filter: &AuditLogFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<AuditLogEntry>, Self::Error> {
+        self.inner.find(filter, offset, limit).map_err(Into::into)
+    }
+
+    fn count(&self, filter: &AuditLogFilter) -> Result<i64, Self::Error> {
+        self.inner.count(filter).map_err(Into::into)
+    }
+
+    fn delete_older_than(&self, cutoff: NaiveDateTime) -> Result<usize, Self::Error> {
+        self.inner.delete_older_than(cutoff).map_err(Into::into)
+    }
+}
+
+// ── StorageUsage ────────────────────────────────────────────────────────────
+
+use crate::storage_usage::DieselStorageUsageRepository;
+use podfetch_domain::storage_usage::{EpisodeStorage, StorageUsageRepository, UserStorageQuota};
+
+pub struct StorageUsageRepositoryImpl {
+    inner: DieselStorageUsageRepository,
+}
+
+impl StorageUsageRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselStorageUsageRepository::new(database),
+        }
+    }
+}
+
+impl StorageUsageRepository for StorageUsageRepositoryImpl {
+    type Error = CustomError;
+
+    fn upsert(&self, storage: EpisodeStorage) -> Result<(), Self::Error> {
+        self.inner.upsert(storage).map_err(Into::into)
+    }
+
+    fn delete_by_episode_id(&self, episode_id: Uuid) -> Result<(), Self::Error> {
+        self.inner
+            .delete_by_episode_id(episode_id)
+            .map_err(Into::into)
+    }
+
+    fn find_all(&self) -> Result<Vec<EpisodeStorage>, Self::Error> {
+        self.inner.find_all().map_err(Into::into)
+    }
+
+    fn find_quota(&self, user_id: Uuid) -> Result<Option<UserStorageQuota>, Self::Error> {
+        self.inner.find_quota(user_id).map_err(Into::into)
+    }
+
+    fn find_all_quotas(&self) -> Result<Vec<UserStorageQuota>, Self::Error> {
+        self.inner.find_all_quotas().map_err(Into::into)
+    }
+
+    fn save_quota(&self, quota: UserStorageQuota) -> Result<(), Self::Error> {
+        self.inner.save_quota(quota).map_err(Into::into)
+    }
+
+    fn delete_quota(&self, user_id: Uuid) -> Result<(), Self::Error> {
+        self.inner.delete_quota(user_id).map_err(Into::into)
+    }
+}
+
+// ── Search ──────────────────────────────────────────────────────────────────
+
+use crate::search::DieselSearchRepository;
+use podfetch_domain::search::{
+    EpisodeSearchHit, PodcastSearchHit, SearchFilter, SearchQuery, SearchRepository,
+};
+
+pub struct SearchRepositoryImpl {
+    inner: DieselSearchRepository,
+}
+
+impl SearchRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselSearchRepository::new(database),
+        }
+    }
+}
+
+impl SearchRepository for SearchRepositoryImpl {
+    type Error = CustomError;
+
+    fn search_podcasts(
+        &self,
+        query: &SearchQuery,
+        filter: &SearchFilter,
+        page: i64,
+        page_size: i64,
+    ) -> Result<Vec<PodcastSearchHit>, Self::Error> {
+        self.inner
+            .search_podcasts(query, filter, page, page_size)
+            .map_err(Into::into)
+    }
+
+    fn search_episodes(
+        &self,
+        query: &SearchQuery,
+        filter: &SearchFilter,
+        page: i64,
+        page_size: i64,
+    ) -> Result<Vec<EpisodeSearchHit>, Self::Error> {
+        self.inner
+            .search_episodes(query, filter, page, page_size)
+            .map_err(Into::into)
+    }
+}
+
+// ── Backup ──────────────────────────────────────────────────────────────────
+
+use crate::backup::DieselBackupRepository;
+use podfetch_domain::backup::{BackupRepository, BackupRow, BackupTable};
+
+pub struct BackupRepositoryImpl {
+    inner: DieselBackupRepository,
+}
+
+impl BackupRepositoryImpl {
+    pub fn new(database: Database) -> Self {
+        Self {
+            inner: DieselBackupRepository::new(database),
+        }
+    }
+}
+
+impl BackupRepository for BackupRepositoryImpl {
+    type Error = CustomError;
+
+    fn export_table(&self, table: &str) -> Result<Vec<BackupRow>, Self::Error> {
+        self.inner.export_table(table).map_err(Into::into)
+    }
+
+    fn count_rows(&self, table: &str) -> Result<i64, Self::Error> {
+        self.inner.count_rows(table).map_err(Into::into)
+    }
+
+    fn restore(&self, tables: &[BackupTable]) -> Result<(), Self::Error> {
+        self.inner.restore(tables).map_err(Into::into)
+    }
 }
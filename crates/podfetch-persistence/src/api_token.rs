@@ -0,0 +1,154 @@
+use crate::db::{Database, PersistenceError};
+use chrono::NaiveDateTime;
+use diesel::prelude::{Insertable, Queryable, Selectable};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use podfetch_domain::api_token::{ApiToken, ApiTokenRepository, ApiTokenScope};
+use uuid::Uuid;
+
+diesel::table! {
+    api_tokens (id) {
+        id -> Text,
+        user_id -> Text,
+        name -> Text,
+        token_hash -> Text,
+        scopes -> Text,
+        expires_at -> Nullable<Timestamp>,
+        last_used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, Clone)]
+#[diesel(table_name = api_tokens)]
+struct ApiTokenEntity {
+    id: String,
+    user_id: String,
+    name: String,
+    token_hash: String,
+    /// Comma separated scope names.
+    scopes: String,
+    expires_at: Option<NaiveDateTime>,
+    last_used_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+}
+
+impl From<ApiTokenEntity> for ApiToken {
+    fn from(value: ApiTokenEntity) -> Self {
+        Self {
+            id: Uuid::parse_str(&value.id).expect("valid uuid in db"),
+            user_id: Uuid::parse_str(&value.user_id).expect("valid uuid in db"),
+            name: value.name,
+            token_hash: value.token_hash,
+            scopes: value
+                .scopes
+                .split(',')
+                .filter_map(ApiTokenScope::from_str)
+                .collect(),
+            expires_at: value.expires_at,
+            last_used_at: value.last_used_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<ApiToken> for ApiTokenEntity {
+    fn from(value: ApiToken) -> Self {
+        Self {
+            id: value.id.to_string(),
+            user_id: value.user_id.to_string(),
+            name: value.name,
+            token_hash: value.token_hash,
+            scopes: value
+                .scopes
+                .iter()
+                .map(ApiTokenScope::as_str)
+                .collect::<Vec<_>>()
+                .join(","),
+            expires_at: value.expires_at,
+            last_used_at: value.last_used_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+pub struct DieselApiTokenRepository {
+    database: Database,
+}
+
+impl DieselApiTokenRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+impl ApiTokenRepository for DieselApiTokenRepository {
+    type Error = PersistenceError;
+
+    fn create(&self, token: ApiToken) -> Result<ApiToken, Self::Error> {
+        use self::api_tokens::table as at_table;
+
+        let entity = ApiTokenEntity::from(token);
+        diesel::insert_into(at_table)
+            .values(&entity)
+            .execute(&mut self.database.connection()?)?;
+        Ok(entity.into())
+    }
+
+    fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, Self::Error> {
+        use self::api_tokens::dsl as at_dsl;
+        use self::api_tokens::table as at_table;
+
+        at_table
+            .filter(at_dsl::token_hash.eq(token_hash))
+            .first::<ApiTokenEntity>(&mut self.database.connection()?)
+            .optional()
+            .map(|token| token.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    fn list_by_user(&self, user_id: Uuid) -> Result<Vec<ApiToken>, Self::Error> {
+        use self::api_tokens::dsl as at_dsl;
+        use self::api_tokens::table as at_table;
+
+        at_table
+            .filter(at_dsl::user_id.eq(user_id.to_string()))
+            .order(at_dsl::created_at.asc())
+            .load::<ApiTokenEntity>(&mut self.database.connection()?)
+            .map(|tokens| tokens.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    fn delete(&self, id: Uuid, user_id: Uuid) -> Result<bool, Self::Error> {
+        use self::api_tokens::dsl as at_dsl;
+        use self::api_tokens::table as at_table;
+
+        diesel::delete(
+            at_table
+                .filter(at_dsl::id.eq(id.to_string()))
+                .filter(at_dsl::user_id.eq(user_id.to_string())),
+        )
+        .execute(&mut self.database.connection()?)
+        .map(|deleted| deleted > 0)
+        .map_err(Into::into)
+    }
+
+    fn update_last_used(&self, id: Uuid, last_used_at: NaiveDateTime) -> Result<(), Self::Error> {
+        use self::api_tokens::dsl as at_dsl;
+        use self::api_tokens::table as at_table;
+
+        diesel::update(at_table.filter(at_dsl::id.eq(id.to_string())))
+            .set(at_dsl::last_used_at.eq(Some(last_used_at)))
+            .execute(&mut self.database.connection()?)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    fn delete_expired(&self, now: NaiveDateTime) -> Result<usize, Self::Error> {
+        use self::api_tokens::dsl as at_dsl;
+        use self::api_tokens::table as at_table;
+
+        diesel::delete(at_table.filter(at_dsl::expires_at.le(now)))
+            .execute(&mut self.database.connection()?)
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,136 @@
+use crate::db::{DBType, Database, PersistenceError};
+use chrono::NaiveDateTime;
+use diesel::prelude::{Insertable, Queryable, Selectable};
+use diesel::{Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
+use podfetch_domain::audit_log::{AuditAction, AuditLogEntry, AuditLogFilter, AuditLogRepository};
+use uuid::Uuid;
+
+diesel::table! {
+    audit_log (id) {
+        id -> Text,
+        actor -> Text,
+        action -> Text,
+        target -> Nullable<Text>,
+        ip_address -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, Clone)]
+#[diesel(table_name = audit_log)]
+struct AuditLogEntity {
+    id: String,
+    actor: String,
+    action: String,
+    target: Option<String>,
+    ip_address: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl From<AuditLogEntity> for AuditLogEntry {
+    fn from(value: AuditLogEntity) -> Self {
+        Self {
+            id: Uuid::parse_str(&value.id).expect("valid uuid in db"),
+            actor: value.actor,
+            action: AuditAction::from_str(&value.action).expect("valid audit action in db"),
+            target: value.target,
+            ip_address: value.ip_address,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<AuditLogEntry> for AuditLogEntity {
+    fn from(value: AuditLogEntry) -> Self {
+        Self {
+            id: value.id.to_string(),
+            actor: value.actor,
+            action: value.action.as_str().to_string(),
+            target: value.target,
+            ip_address: value.ip_address,
+            created_at: value.created_at,
+        }
+    }
+}
+
+type Backend = <DBType as Connection>::Backend;
+
+fn filtered(filter: &AuditLogFilter) -> audit_log::BoxedQuery<'static, Backend> {
+    use self::audit_log::dsl as al_dsl;
+    use self::audit_log::table as al_table;
+
+    let mut query = al_table.into_boxed();
+    if let Some(actor) = &filter.actor {
+        query = query.filter(al_dsl::actor.eq(actor.clone()));
+    }
+    if let Some(action) = filter.action {
+        query = query.filter(al_dsl::action.eq(action.as_str()));
+    }
+    if let Some(target) = &filter.target {
+        query = query.filter(al_dsl::target.eq(target.clone()));
+    }
+    if let Some(from) = filter.from {
+        query = query.filter(al_dsl::created_at.ge(from));
+    }
+    if let Some(to) = filter.to {
+        query = query.filter(al_dsl::created_at.lt(to));
+    }
+    query
+}
+
+pub struct DieselAuditLogRepository {
+    database: Database,
+}
+
+impl DieselAuditLogRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+impl AuditLogRepository for DieselAuditLogRepository {
+    type Error = PersistenceError;
+
+    fn append(&self, entry: AuditLogEntry) -> Result<AuditLogEntry, Self::Error> {
+        use self::audit_log::table as al_table;
+
+        let entity = AuditLogEntity::from(entry);
+        diesel::insert_into(al_table)
+            .values(&entity)
+            .execute(&mut self.database.connection()?)?;
+        Ok(entity.into())
+    }
+
+    fn find(
+        &self,
+        filter: &AuditLogFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<AuditLogEntry>, Self::Error> {
+        use self::audit_log::dsl as al_dsl;
+
+        filtered(filter)
+            .order((al_dsl::created_at.desc(), al_dsl::id.desc()))
+            .offset(offset)
+            .limit(limit)
+            .load::<AuditLogEntity>(&mut self.database.connection()?)
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    fn count(&self, filter: &AuditLogFilter) -> Result<i64, Self::Error> {
+        filtered(filter)
+            .count()
+            .get_result::<i64>(&mut self.database.connection()?)
+            .map_err(Into::into)
+    }
+
+    fn delete_older_than(&self, cutoff: NaiveDateTime) -> Result<usize, Self::Error> {
+        use self::audit_log::dsl as al_dsl;
+        use self::audit_log::table as al_table;
+
+        diesel::delete(al_table.filter(al_dsl::created_at.lt(cutoff)))
+            .execute(&mut self.database.connection()?)
+            .map_err(Into::into)
+    }
+}
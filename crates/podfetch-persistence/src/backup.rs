@@ -0,0 +1,366 @@
+use crate::db::{DBType, Database, PersistenceError};
+use chrono::{DateTime, NaiveDateTime};
+use diesel::connection::Connection;
+use diesel::result::Error as DieselError;
+use diesel::sql_types::{BigInt, Nullable, Text};
+use diesel::{QueryableByName, RunQueryDsl};
+use podfetch_domain::backup::{BACKUP_TABLES, BackupRepository, BackupRow, BackupTable};
+use std::ops::DerefMut;
+
+// Backups work on whatever columns the live tables have instead of on the
+// Diesel schema, so a table gaining a column doesn't need touching this file.
+// Table names only ever come from `BACKUP_TABLES` and column names from the
+// catalog of the connected database; both are quoted into the SQL.
+
+#[derive(QueryableByName)]
+struct ColumnRow {
+    #[diesel(sql_type = Text)]
+    name: String,
+    #[diesel(sql_type = Text)]
+    column_type: String,
+}
+
+#[derive(QueryableByName)]
+struct JsonRow {
+    #[diesel(sql_type = Text)]
+    row: String,
+}
+
+#[derive(QueryableByName)]
+struct CountRow {
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+fn checked_table(table: &str) -> Result<&str, PersistenceError> {
+    if BACKUP_TABLES.contains(&table) {
+        Ok(table)
+    } else {
+        Err(
+            DieselError::QueryBuilderError(format!("{table} is not part of a backup").into())
+                .into(),
+        )
+    }
+}
+
+fn quoted(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn parse_row(json: &str) -> Result<BackupRow, PersistenceError> {
+    let values: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(json).map_err(|e| DieselError::DeserializationError(Box::new(e)))?;
+    Ok(values
+        .into_iter()
+        .map(|(column, value)| {
+            let value = match value {
+                serde_json::Value::Null => None,
+                serde_json::Value::String(text) => Some(text),
+                other => Some(other.to_string()),
+            };
+            (column, value)
+        })
+        .collect())
+}
+
+/// Reads the timestamp formats either backend hands out: SQLite's
+/// `2024-05-01 10:00:00.5`, Postgres' `2024-05-01T10:00:00.5` and both with an
+/// offset. Offsets are folded into UTC, which is what PodFetch stores.
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    const WITH_OFFSET: &[&str] = &[
+        "%Y-%m-%dT%H:%M:%S%.f%:z",
+        "%Y-%m-%d %H:%M:%S%.f%:z",
+        "%Y-%m-%d %H:%M:%S%.f%#z",
+    ];
+    const WITHOUT_OFFSET: &[&str] = &["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"];
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(value) {
+        return Some(parsed.naive_utc());
+    }
+    WITH_OFFSET
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(value, format).ok())
+        .map(|parsed| parsed.naive_utc())
+        .or_else(|| {
+            WITHOUT_OFFSET
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        })
+}
+
+/// Converts a value from the backup into something the target column accepts
+/// as text: SQLite keeps booleans as 0/1 and timestamps in Diesel's
+/// `%Y-%m-%d %H:%M:%S%.f` layout, Postgres needs an offset for `timestamptz`.
+fn normalize_value(value: &str, column_type: &str, postgres: bool) -> String {
+    let column_type = column_type.to_lowercase();
+    if column_type.contains("bool") {
+        return match value {
+            "true" | "t" if !postgres => "1".to_string(),
+            "false" | "f" if !postgres => "0".to_string(),
+            _ => value.to_string(),
+        };
+    }
+    if (column_type.contains("date") || column_type.contains("time"))
+        && let Some(timestamp) = parse_timestamp(value)
+    {
+        let formatted = timestamp.format("%Y-%m-%d %H:%M:%S%.f").to_string();
+        return if postgres && column_type == "timestamptz" {
+            format!("{formatted}+00:00")
+        } else {
+            formatted
+        };
+    }
+    value.to_string()
+}
+
+fn insert_statement(table: &str, columns: &[&ColumnRow], postgres: bool) -> String {
+    let names = columns
+        .iter()
+        .map(|column| quoted(&column.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let placeholders = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            if postgres {
+                format!("${}::{}", index + 1, quoted(&column.column_type))
+            } else {
+                "?".to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "INSERT INTO {} ({names}) VALUES ({placeholders})",
+        quoted(table)
+    )
+}
+
+/// Values bound for one row, in the order of `columns`.
+fn row_values(row: &BackupRow, columns: &[&ColumnRow], postgres: bool) -> Vec<Option<String>> {
+    columns
+        .iter()
+        .map(|column| {
+            row.get(&column.name)
+                .cloned()
+                .flatten()
+                .map(|value| normalize_value(&value, &column.column_type, postgres))
+        })
+        .collect()
+}
+
+pub struct DieselBackupRepository {
+    database: Database,
+}
+
+impl DieselBackupRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    fn columns(conn: &mut DBType, table: &str) -> Result<Vec<ColumnRow>, PersistenceError> {
+        let columns = match conn {
+            #[cfg(feature = "sqlite")]
+            DBType::Sqlite(conn) => diesel::sql_query(
+                "SELECT name, type AS column_type FROM pragma_table_info(?) ORDER BY cid",
+            )
+            .bind::<Text, _>(table)
+            .load::<ColumnRow>(conn)?,
+            #[cfg(feature = "postgresql")]
+            DBType::Postgresql(conn) => diesel::sql_query(
+                "SELECT column_name::text AS name, udt_name::text AS column_type \
+                 FROM information_schema.columns \
+                 WHERE table_schema = current_schema() AND table_name = $1 \
+                 AND is_generated = 'NEVER' \
+                 ORDER BY ordinal_position",
+            )
+            .bind::<Text, _>(table)
+            .load::<ColumnRow>(conn)?,
+        };
+        Ok(columns)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn restore_sqlite(
+    conn: &mut diesel::SqliteConnection,
+    tables: &[(&BackupTable, Vec<ColumnRow>)],
+) -> Result<(), DieselError> {
+    for (table, _) in tables.iter().rev() {
+        diesel::sql_query(format!("DELETE FROM {}", quoted(&table.name))).execute(conn)?;
+    }
+    for (table, columns) in tables {
+        for row in &table.rows {
+            let columns: Vec<&ColumnRow> = columns
+                .iter()
+                .filter(|column| row.contains_key(&column.name))
+                .collect();
+            let mut query = diesel::sql_query(insert_statement(&table.name, &columns, false))
+                .into_boxed::<diesel::sqlite::Sqlite>();
+            for value in row_values(row, &columns, false) {
+                query = query.bind::<Nullable<Text>, _>(value);
+            }
+            query.execute(conn)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "postgresql")]
+fn restore_postgres(
+    conn: &mut diesel::PgConnection,
+    tables: &[(&BackupTable, Vec<ColumnRow>)],
+) -> Result<(), DieselError> {
+    for (table, _) in tables.iter().rev() {
+        diesel::sql_query(format!("DELETE FROM {}", quoted(&table.name))).execute(conn)?;
+    }
+    for (table, columns) in tables {
+        for row in &table.rows {
+            let columns: Vec<&ColumnRow> = columns
+                .iter()
+                .filter(|column| row.contains_key(&column.name))
+                .collect();
+            let mut query = diesel::sql_query(insert_statement(&table.name, &columns, true))
+                .into_boxed::<diesel::pg::Pg>();
+            for value in row_values(row, &columns, true) {
+                query = query.bind::<Nullable<Text>, _>(value);
+            }
+            query.execute(conn)?;
+        }
+    }
+    Ok(())
+}
+
+impl BackupRepository for DieselBackupRepository {
+    type Error = PersistenceError;
+
+    fn export_table(&self, table: &str) -> Result<Vec<BackupRow>, Self::Error> {
+        let table = checked_table(table)?;
+        let mut conn = self.database.connection()?;
+        let columns = Self::columns(conn.deref_mut(), table)?;
+        if columns.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows: Vec<JsonRow> = match conn.deref_mut() {
+            #[cfg(feature = "sqlite")]
+            DBType::Sqlite(conn) => {
+                let pairs = columns
+                    .iter()
+                    .map(|column| {
+                        format!(
+                            "'{}', {}",
+                            column.name.replace('\'', "''"),
+                            quoted(&column.name)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                diesel::sql_query(format!(
+                    "SELECT json_object({pairs}) AS row FROM {}",
+                    quoted(table)
+                ))
+                .load(conn)?
+            }
+            #[cfg(feature = "postgresql")]
+            DBType::Postgresql(conn) => {
+                let names = columns
+                    .iter()
+                    .map(|column| quoted(&column.name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                diesel::sql_query(format!(
+                    "SELECT row_to_json(t)::text AS row FROM (SELECT {names} FROM {}) t",
+                    quoted(table)
+                ))
+                .load(conn)?
+            }
+        };
+        rows.iter().map(|row| parse_row(&row.row)).collect()
+    }
+
+    fn count_rows(&self, table: &str) -> Result<i64, Self::Error> {
+        let table = checked_table(table)?;
+        let mut conn = self.database.connection()?;
+        let query = diesel::sql_query(format!("SELECT COUNT(*) AS count FROM {}", quoted(table)));
+        let count: CountRow = match conn.deref_mut() {
+            #[cfg(feature = "sqlite")]
+            DBType::Sqlite(conn) => query.get_result(conn)?,
+            #[cfg(feature = "postgresql")]
+            DBType::Postgresql(conn) => query.get_result(conn)?,
+        };
+        Ok(count.count)
+    }
+
+    fn restore(&self, tables: &[BackupTable]) -> Result<(), Self::Error> {
+        let mut conn = self.database.connection()?;
+        let mut with_columns = Vec::with_capacity(tables.len());
+        for table in tables {
+            checked_table(&table.name)?;
+            with_columns.push((table, Self::columns(conn.deref_mut(), &table.name)?));
+        }
+
+        match conn.deref_mut() {
+            #[cfg(feature = "sqlite")]
+            DBType::Sqlite(conn) => conn.transaction(|conn| restore_sqlite(conn, &with_columns))?,
+            #[cfg(feature = "postgresql")]
+            DBType::Postgresql(conn) => {
+                conn.transaction(|conn| restore_postgres(conn, &with_columns))?
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_value_converts_booleans_for_sqlite_only() {
+        assert_eq!(normalize_value("true", "BOOLEAN", false), "1");
+        assert_eq!(normalize_value("false", "BOOLEAN", false), "0");
+        assert_eq!(normalize_value("1", "bool", true), "1");
+        assert_eq!(normalize_value("true", "bool", true), "true");
+        assert_eq!(normalize_value("true", "TEXT", false), "true");
+    }
+
+    #[test]
+    fn normalize_value_moves_timestamps_between_backends() {
+        assert_eq!(
+            normalize_value("2024-05-01T12:30:00.25+02:00", "DATETIME", false),
+            "2024-05-01 10:30:00.250"
+        );
+        assert_eq!(
+            normalize_value("2024-05-01T12:30:00", "TIMESTAMP", false),
+            "2024-05-01 12:30:00"
+        );
+        assert_eq!(
+            normalize_value("2024-05-01 12:30:00", "timestamptz", true),
+            "2024-05-01 12:30:00+00:00"
+        );
+        assert_eq!(
+            normalize_value("2024-05-01 12:30:00+00:00", "timestamp", true),
+            "2024-05-01 12:30:00"
+        );
+        assert_eq!(
+            normalize_value("not a date", "timestamp", true),
+            "not a date"
+        );
+    }
+
+    #[test]
+    fn parse_row_keeps_every_value_as_text() {
+        let row = parse_row(r#"{"id":"a","active":true,"total":12,"gone":null}"#).unwrap();
+        assert_eq!(row["id"].as_deref(), Some("a"));
+        assert_eq!(row["active"].as_deref(), Some("true"));
+        assert_eq!(row["total"].as_deref(), Some("12"));
+        assert_eq!(row["gone"], None);
+    }
+
+    #[test]
+    fn tables_outside_the_backup_are_rejected() {
+        assert!(checked_table("podcasts").is_ok());
+        assert!(checked_table("sessions").is_err());
+    }
+}
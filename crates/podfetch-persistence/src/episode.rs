@@ -105,6 +105,11 @@ struct PodcastEpisodeEntity {
     episode_numbering_processed: bool,
     download_location: Option<String>,
     youtube_video_id: Option<String>,
+    season: Option<i32>,
+    episode_number: Option<i32>,
+    episode_type: Option<String>,
+    loudness_lufs: Option<f64>,
+    loudness_true_peak: Option<f64>,
 }
 
 #[derive(Queryable, Selectable, Debug, Clone)]
@@ -151,6 +156,11 @@ impl From<PodcastEpisodeEntity> for PodcastEpisode {
             episode_numbering_processed: entity.episode_numbering_processed,
             download_location: entity.download_location,
             youtube_video_id: entity.youtube_video_id,
+            season: entity.season,
+            episode_number: entity.episode_number,
+            episode_type: entity.episode_type,
+            loudness_lufs: entity.loudness_lufs,
+            loudness_true_peak: entity.loudness_true_peak,
         }
     }
 }
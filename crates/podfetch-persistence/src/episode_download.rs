@@ -0,0 +1,695 @@
+use crate::db::{Database, PersistenceError};
+use chrono::NaiveDateTime;
+use diesel::prelude::{AsChangeset, Insertable, Queryable, Selectable};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use podfetch_domain::episode_download::{
+    DownloadJob, DownloadJobRepository, DownloadJobStatus, EpisodeDownloadState,
+    EpisodeDownloadStateRepository,
+};
+use uuid::Uuid;
+
+diesel::table! {
+    episode_download_states (episode_id) {
+        episode_id -> Text,
+        file_path -> Text,
+        bytes_received -> BigInt,
+        etag -> Nullable<Text>,
+        last_modified -> Nullable<Text>,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    download_jobs (id) {
+        id -> Text,
+        episode_id -> Text,
+        podcast_id -> Text,
+        status -> Text,
+        priority -> Integer,
+        attempts -> Integer,
+        error -> Nullable<Text>,
+        next_run_at -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        requested_by -> Nullable<Text>,
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Clone)]
+#[diesel(table_name = episode_download_states)]
+#[diesel(treat_none_as_null = true)]
+struct EpisodeDownloadStateEntity {
+    episode_id: String,
+    file_path: String,
+    bytes_received: i64,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    updated_at: NaiveDateTime,
+}
+
+impl From<EpisodeDownloadStateEntity> for EpisodeDownloadState {
+    fn from(value: EpisodeDownloadStateEntity) -> Self {
+        Self {
+            episode_id: Uuid::parse_str(&value.episode_id).expect("valid uuid in db"),
+            file_path: value.file_path,
+            bytes_received: value.bytes_received,
+            etag: value.etag,
+            last_modified: value.last_modified,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+impl From<EpisodeDownloadState> for EpisodeDownloadStateEntity {
+    fn from(value: EpisodeDownloadState) -> Self {
+        Self {
+            episode_id: value.episode_id.to_string(),
+            file_path: value.file_path,
+            bytes_received: value.bytes_received,
+            etag: value.etag,
+            last_modified: value.last_modified,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, Clone)]
+#[diesel(table_name = download_jobs)]
+struct DownloadJobEntity {
+    id: String,
+    episode_id: String,
+    podcast_id: String,
+    status: String,
+    priority: i32,
+    attempts: i32,
+    error: Option<String>,
+    next_run_at: NaiveDateTime,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+    requested_by: Option<String>,
+}
+
+impl From<DownloadJobEntity> for DownloadJob {
+    fn from(value: DownloadJobEntity) -> Self {
+        Self {
+            id: Uuid::parse_str(&value.id).expect("valid uuid in db"),
+            episode_id: Uuid::parse_str(&value.episode_id).expect("valid uuid in db"),
+            podcast_id: Uuid::parse_str(&value.podcast_id).expect("valid uuid in db"),
+            status: DownloadJobStatus::from_str(&value.status).expect("valid status in db"),
+            priority: value.priority,
+            attempts: value.attempts,
+            error: value.error,
+            next_run_at: value.next_run_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+            requested_by: value
+                .requested_by
+                .and_then(|user_id| Uuid::parse_str(&user_id).ok()),
+        }
+    }
+}
+
+pub struct DieselEpisodeDownloadStateRepository {
+    database: Database,
+}
+
+impl DieselEpisodeDownloadStateRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+impl EpisodeDownloadStateRepository for DieselEpisodeDownloadStateRepository {
+    type Error = PersistenceError;
+
+    fn get(&self, episode_id: Uuid) -> Result<Option<EpisodeDownloadState>, Self::Error> {
+        use self::episode_download_states::dsl as eds_dsl;
+        use self::episode_download_states::table as eds_table;
+
+        eds_table
+            .filter(eds_dsl::episode_id.eq(episode_id.to_string()))
+            .first::<EpisodeDownloadStateEntity>(&mut self.database.connection()?)
+            .optional()
+            .map(|state| state.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    fn upsert(&self, state: EpisodeDownloadState) -> Result<(), Self::Error> {
+        use self::episode_download_states::dsl as eds_dsl;
+        use self::episode_download_states::table as eds_table;
+
+        let entity = EpisodeDownloadStateEntity::from(state);
+        let mut conn = self.database.connection()?;
+        let updated = diesel::update(eds_table.filter(eds_dsl::episode_id.eq(&entity.episode_id)))
+            .set(&entity)
+            .execute(&mut conn)?;
+        if updated == 0 {
+            diesel::insert_into(eds_table)
+                .values(&entity)
+                .execute(&mut conn)?;
+        }
+        Ok(())
+    }
+
+    fn delete(&self, episode_id: Uuid) -> Result<usize, Self::Error> {
+        use self::episode_download_states::dsl as eds_dsl;
+        use self::episode_download_states::table as eds_table;
+
+        diesel::delete(eds_table.filter(eds_dsl::episode_id.eq(episode_id.to_string())))
+            .execute(&mut self.database.connection()?)
+            .map_err(Into::into)
+    }
+}
+
+pub struct DieselDownloadJobRepository {
+    database: Database,
+}
+
+impl DieselDownloadJobRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+impl DownloadJobRepository for DieselDownloadJobRepository {
+    type Error = PersistenceError;
+
+    fn enqueue(
+        &self,
+        episode_id: Uuid,
+        podcast_id: Uuid,
+        priority: i32,
+        requested_by: Option<Uuid>,
+    ) -> Result<Option<DownloadJob>, Self::Error> {
+        use self::download_jobs::dsl as dj_dsl;
+        use self::download_jobs::table as dj_table;
+
+        let mut conn = self.database.connection()?;
+        let episode_id_str = episode_id.to_string();
+        let now = chrono::Utc::now().naive_utc();
+        let requested_by = requested_by.map(|user_id| user_id.to_string());
+
+        let existing = dj_table
+            .filter(dj_dsl::episode_id.eq(&episode_id_str))
+            .first::<DownloadJobEntity>(&mut conn)
+            .optional()?;
+        if let Some(existing) = existing {
+            if existing.status == DownloadJobStatus::Pending.as_str()
+                || existing.status == DownloadJobStatus::Running.as_str()
+            {
+                return Ok(None);
+            }
+            diesel::update(dj_table.find(&existing.id))
+                .set((
+                    dj_dsl::status.eq(DownloadJobStatus::Pending.as_str()),
+                    dj_dsl::priority.eq(priority),
+                    dj_dsl::attempts.eq(0),
+                    dj_dsl::error.eq(None::<String>),
+                    dj_dsl::next_run_at.eq(now),
+                    dj_dsl::updated_at.eq(now),
+                    dj_dsl::requested_by.eq(requested_by),
+                ))
+                .execute(&mut conn)?;
+            let reset = dj_table
+                .find(existing.id)
+                .first::<DownloadJobEntity>(&mut conn)?;
+            return Ok(Some(reset.into()));
+        }
+
+        let entity = DownloadJobEntity {
+            id: Uuid::new_v4().to_string(),
+            episode_id: episode_id_str,
+            podcast_id: podcast_id.to_string(),
+            status: DownloadJobStatus::Pending.as_str().to_string(),
+            priority,
+            attempts: 0,
+            error: None,
+            next_run_at: now,
+            created_at: now,
+            updated_at: now,
+            requested_by,
+        };
+        diesel::insert_into(dj_table)
+            .values(entity.clone())
+            .execute(&mut conn)?;
+
+        Ok(Some(entity.into()))
+    }
+
+    fn claim_next_due(&self, now: NaiveDateTime) -> Result<Option<DownloadJob>, Self::Error> {
+        use self::download_jobs::dsl as dj_dsl;
+        use self::download_jobs::table as dj_table;
+
+        let mut conn = self.database.connection()?;
+        loop {
+            let candidate = dj_table
+                .filter(dj_dsl::status.eq(DownloadJobStatus::Pending.as_str()))
+                .filter(dj_dsl::next_run_at.le(now))
+                .order((dj_dsl::priority.desc(), dj_dsl::created_at.asc()))
+                .first::<DownloadJobEntity>(&mut conn)
+                .optional()?;
+            let Some(candidate) = candidate else {
+                return Ok(None);
+            };
+
+            // Only flip the row if nobody else claimed or cancelled it in the
+            // meantime; otherwise look for the next candidate.
+            let claimed = diesel::update(
+                dj_table
+                    .find(&candidate.id)
+                    .filter(dj_dsl::status.eq(DownloadJobStatus::Pending.as_str())),
+            )
+            .set((
+                dj_dsl::status.eq(DownloadJobStatus::Running.as_str()),
+                dj_dsl::updated_at.eq(now),
+            ))
+            .execute(&mut conn)?;
+            if claimed == 1 {
+                let job = dj_table
+                    .find(candidate.id)
+                    .first::<DownloadJobEntity>(&mut conn)?;
+                return Ok(Some(job.into()));
+            }
+        }
+    }
+
+    fn set_status(
+        &self,
+        id: Uuid,
+        status: DownloadJobStatus,
+        error: Option<&str>,
+    ) -> Result<(), Self::Error> {
+        use self::download_jobs::dsl as dj_dsl;
+        use self::download_jobs::table as dj_table;
+
+        let now = chrono::Utc::now().naive_utc();
+        diesel::update(dj_table.find(id.to_string()))
+            .set((
+                dj_dsl::status.eq(status.as_str()),
+                dj_dsl::error.eq(error),
+                dj_dsl::updated_at.eq(now),
+            ))
+            .execute(&mut self.database.connection()?)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    fn schedule_retry(
+        &self,
+        id: Uuid,
+        next_run_at: NaiveDateTime,
+        error: &str,
+    ) -> Result<(), Self::Error> {
+        use self::download_jobs::dsl as dj_dsl;
+        use self::download_jobs::table as dj_table;
+
+        let now = chrono::Utc::now().naive_utc();
+        diesel::update(dj_table.find(id.to_string()))
+            .set((
+                dj_dsl::status.eq(DownloadJobStatus::Pending.as_str()),
+                dj_dsl::error.eq(error),
+                dj_dsl::next_run_at.eq(next_run_at),
+                dj_dsl::updated_at.eq(now),
+            ))
+            .execute(&mut self.database.connection()?)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    fn increment_attempts(&self, id: Uuid) -> Result<i32, Self::Error> {
+        use self::download_jobs::dsl as dj_dsl;
+        use self::download_jobs::table as dj_table;
+
+        let mut conn = self.database.connection()?;
+        let now = chrono::Utc::now().naive_utc();
+        let id_str = id.to_string();
+
+        diesel::update(dj_table.find(&id_str))
+            .set((
+                dj_dsl::attempts.eq(dj_dsl::attempts + 1),
+                dj_dsl::updated_at.eq(now),
+            ))
+            .execute(&mut conn)?;
+
+        dj_table
+            .find(id_str)
+            .select(dj_dsl::attempts)
+            .first::<i32>(&mut conn)
+            .map_err(Into::into)
+    }
+
+    fn requeue(&self, id: Uuid) -> Result<(), Self::Error> {
+        use self::download_jobs::dsl as dj_dsl;
+        use self::download_jobs::table as dj_table;
+
+        let now = chrono::Utc::now().naive_utc();
+        diesel::update(dj_table.find(id.to_string()))
+            .set((
+                dj_dsl::status.eq(DownloadJobStatus::Pending.as_str()),
+                dj_dsl::attempts.eq(0),
+                dj_dsl::error.eq(None::<String>),
+                dj_dsl::next_run_at.eq(now),
+                dj_dsl::updated_at.eq(now),
+            ))
+            .execute(&mut self.database.connection()?)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    fn set_priority(&self, id: Uuid, priority: i32) -> Result<(), Self::Error> {
+        use self::download_jobs::dsl as dj_dsl;
+        use self::download_jobs::table as dj_table;
+
+        let now = chrono::Utc::now().naive_utc();
+        diesel::update(dj_table.find(id.to_string()))
+            .set((dj_dsl::priority.eq(priority), dj_dsl::updated_at.eq(now)))
+            .execute(&mut self.database.connection()?)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    fn reset_running_to_pending(&self) -> Result<usize, Self::Error> {
+        use self::download_jobs::dsl as dj_dsl;
+        use self::download_jobs::table as dj_table;
+
+        let now = chrono::Utc::now().naive_utc();
+        diesel::update(dj_table.filter(dj_dsl::status.eq(DownloadJobStatus::Running.as_str())))
+            .set((
+                dj_dsl::status.eq(DownloadJobStatus::Pending.as_str()),
+                dj_dsl::updated_at.eq(now),
+            ))
+            .execute(&mut self.database.connection()?)
+            .map_err(Into::into)
+    }
+
+    fn get(&self, id: Uuid) -> Result<Option<DownloadJob>, Self::Error> {
+        use self::download_jobs::table as dj_table;
+
+        dj_table
+            .find(id.to_string())
+            .first::<DownloadJobEntity>(&mut self.database.connection()?)
+            .optional()
+            .map(|row| row.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    fn list(&self, status: Option<DownloadJobStatus>) -> Result<Vec<DownloadJob>, Self::Error> {
+        use self::download_jobs::dsl as dj_dsl;
+        use self::download_jobs::table as dj_table;
+
+        let mut query = dj_table.into_boxed();
+        if let Some(status) = status {
+            query = query.filter(dj_dsl::status.eq(status.as_str()));
+        }
+        query
+            .order((
+                dj_dsl::priority.desc(),
+                dj_dsl::next_run_at.asc(),
+                dj_dsl::created_at.asc(),
+            ))
+            .load::<DownloadJobEntity>(&mut self.database.connection()?)
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    fn count_by_status(&self) -> Result<Vec<(DownloadJobStatus, i64)>, Self::Error> {
+        use self::download_jobs::dsl as dj_dsl;
+        use self::download_jobs::table as dj_table;
+
+        let rows = dj_table
+            .group_by(dj_dsl::status)
+            .select((dj_dsl::status, diesel::dsl::count_star()))
+            .load::<(String, i64)>(&mut self.database.connection()?)?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(status, count)| DownloadJobStatus::from_str(&status).map(|s| (s, count)))
+            .collect())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::db::{database, test_db::setup};
+
+    // Shares the crate-wide DB test lock (see `crate::db::test_db`).
+
+    mod seed_schema {
+        diesel::table! {
+            podcasts (id) {
+                id -> Text,
+                name -> Text,
+                directory_id -> Text,
+                rssfeed -> Text,
+                image_url -> Text,
+                active -> Bool,
+                original_image_url -> Text,
+                directory_name -> Text,
+            }
+        }
+
+        diesel::table! {
+            podcast_episodes (id) {
+                id -> Text,
+                podcast_id -> Text,
+                episode_id -> Text,
+                name -> Text,
+                url -> Text,
+                date_of_recording -> Text,
+                image_url -> Text,
+                total_time -> Integer,
+                description -> Text,
+                guid -> Text,
+                deleted -> Bool,
+                episode_numbering_processed -> Bool,
+            }
+        }
+    }
+
+    #[derive(diesel::Insertable)]
+    #[diesel(table_name = seed_schema::podcasts)]
+    struct SeedPodcast {
+        id: String,
+        name: String,
+        directory_id: String,
+        rssfeed: String,
+        image_url: String,
+        active: bool,
+        original_image_url: String,
+        directory_name: String,
+    }
+
+    #[derive(diesel::Insertable)]
+    #[diesel(table_name = seed_schema::podcast_episodes)]
+    struct SeedEpisode {
+        id: String,
+        podcast_id: String,
+        episode_id: String,
+        name: String,
+        url: String,
+        date_of_recording: String,
+        image_url: String,
+        total_time: i32,
+        description: String,
+        guid: String,
+        deleted: bool,
+        episode_numbering_processed: bool,
+    }
+
+    fn seed_episode() -> Uuid {
+        seed_podcast_episode().1
+    }
+
+    /// Seeds a podcast with one episode and returns `(podcast_id, episode_id)`.
+    fn seed_podcast_episode() -> (Uuid, Uuid) {
+        let podcast_uuid = Uuid::new_v4();
+        let podcast_id = podcast_uuid.to_string();
+        let episode_id = Uuid::new_v4();
+        let mut conn = database().connection().expect("db connection");
+        diesel::insert_into(seed_schema::podcasts::table)
+            .values(SeedPodcast {
+                id: podcast_id.clone(),
+                name: format!("Test Podcast {podcast_id}"),
+                directory_id: Uuid::new_v4().to_string(),
+                rssfeed: format!("https://example.com/feed/{podcast_id}.xml"),
+                image_url: "https://example.com/img.png".to_string(),
+                active: true,
+                original_image_url: "https://example.com/img.png".to_string(),
+                directory_name: format!("podcast-{podcast_id}"),
+            })
+            .execute(&mut conn)
+            .expect("seed podcast");
+        diesel::insert_into(seed_schema::podcast_episodes::table)
+            .values(SeedEpisode {
+                id: episode_id.to_string(),
+                podcast_id,
+                episode_id: Uuid::new_v4().to_string(),
+                name: "Test Episode".to_string(),
+                url: format!("https://example.com/ep/{episode_id}.mp3"),
+                date_of_recording: "2024-01-01".to_string(),
+                image_url: "https://example.com/ep.png".to_string(),
+                total_time: 3600,
+                description: "Test description".to_string(),
+                guid: Uuid::new_v4().to_string(),
+                deleted: false,
+                episode_numbering_processed: false,
+            })
+            .execute(&mut conn)
+            .expect("seed episode");
+        (podcast_uuid, episode_id)
+    }
+
+    fn state(episode_id: Uuid, bytes_received: i64, etag: Option<&str>) -> EpisodeDownloadState {
+        EpisodeDownloadState {
+            episode_id,
+            file_path: format!("podcasts/{episode_id}/podcast.mp3"),
+            bytes_received,
+            etag: etag.map(|e| e.to_string()),
+            last_modified: None,
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn upsert_inserts_then_replaces_the_state() {
+        let _guard = setup();
+        let repo = DieselEpisodeDownloadStateRepository::new(database());
+        let episode_id = seed_episode();
+
+        repo.upsert(state(episode_id, 1024, Some("\"v1\"")))
+            .unwrap();
+        repo.upsert(state(episode_id, 4096, None)).unwrap();
+
+        let stored = repo.get(episode_id).unwrap().expect("state stored");
+        assert_eq!(stored.bytes_received, 4096);
+        assert_eq!(stored.etag, None);
+    }
+
+    #[test]
+    fn delete_removes_the_state() {
+        let _guard = setup();
+        let repo = DieselEpisodeDownloadStateRepository::new(database());
+        let episode_id = seed_episode();
+        repo.upsert(state(episode_id, 10, None)).unwrap();
+
+        assert_eq!(repo.delete(episode_id).unwrap(), 1);
+        assert!(repo.get(episode_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn enqueue_refuses_duplicates_while_a_job_is_active() {
+        let _guard = setup();
+        let repo = DieselDownloadJobRepository::new(database());
+        let (podcast_id, episode_id) = seed_podcast_episode();
+
+        let job = repo.enqueue(episode_id, podcast_id, 0, None).unwrap();
+        assert!(job.is_some());
+        assert!(
+            repo.enqueue(episode_id, podcast_id, 0, None)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn enqueue_requeues_a_failed_job() {
+        let _guard = setup();
+        let repo = DieselDownloadJobRepository::new(database());
+        let (podcast_id, episode_id) = seed_podcast_episode();
+        let job = repo
+            .enqueue(episode_id, podcast_id, 0, None)
+            .unwrap()
+            .unwrap();
+        repo.increment_attempts(job.id).unwrap();
+        repo.set_status(job.id, DownloadJobStatus::Failed, Some("boom"))
+            .unwrap();
+
+        let requeued = repo
+            .enqueue(episode_id, podcast_id, 5, None)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(requeued.id, job.id);
+        assert_eq!(requeued.status, DownloadJobStatus::Pending);
+        assert_eq!(requeued.attempts, 0);
+        assert_eq!(requeued.priority, 5);
+        assert_eq!(requeued.error, None);
+    }
+
+    #[test]
+    fn claim_next_due_prefers_priority_and_skips_backed_off_jobs() {
+        let _guard = setup();
+        let repo = DieselDownloadJobRepository::new(database());
+        // Jobs left behind by other tests would compete for the claim.
+        diesel::sql_query("DELETE FROM download_jobs")
+            .execute(&mut database().connection().unwrap())
+            .unwrap();
+        let (podcast_id, low) = seed_podcast_episode();
+        let (_, high) = seed_podcast_episode();
+        let (_, delayed) = seed_podcast_episode();
+        let low = repo.enqueue(low, podcast_id, 0, None).unwrap().unwrap();
+        let high = repo.enqueue(high, podcast_id, 10, None).unwrap().unwrap();
+        let delayed = repo
+            .enqueue(delayed, podcast_id, 20, None)
+            .unwrap()
+            .unwrap();
+        let now = chrono::Utc::now().naive_utc();
+        repo.schedule_retry(delayed.id, now + chrono::Duration::hours(1), "timeout")
+            .unwrap();
+
+        let first = repo.claim_next_due(now).unwrap().unwrap();
+        let second = repo.claim_next_due(now).unwrap().unwrap();
+
+        assert_eq!(first.id, high.id);
+        assert_eq!(first.status, DownloadJobStatus::Running);
+        assert_eq!(second.id, low.id);
+        assert!(repo.claim_next_due(now).unwrap().is_none());
+    }
+
+    #[test]
+    fn reset_running_to_pending_releases_claimed_jobs() {
+        let _guard = setup();
+        let repo = DieselDownloadJobRepository::new(database());
+        let (podcast_id, episode_id) = seed_podcast_episode();
+        let job = repo
+            .enqueue(episode_id, podcast_id, 0, None)
+            .unwrap()
+            .unwrap();
+        let now = chrono::Utc::now().naive_utc();
+        repo.claim_next_due(now).unwrap();
+
+        assert!(repo.reset_running_to_pending().unwrap() >= 1);
+        assert_eq!(
+            repo.get(job.id).unwrap().unwrap().status,
+            DownloadJobStatus::Pending
+        );
+    }
+
+    #[test]
+    fn count_by_status_groups_jobs() {
+        let _guard = setup();
+        let repo = DieselDownloadJobRepository::new(database());
+        diesel::sql_query("DELETE FROM download_jobs")
+            .execute(&mut database().connection().unwrap())
+            .unwrap();
+        let (podcast_id, first) = seed_podcast_episode();
+        let (_, second) = seed_podcast_episode();
+        let (_, third) = seed_podcast_episode();
+        repo.enqueue(first, podcast_id, 0, None).unwrap();
+        repo.enqueue(second, podcast_id, 0, None).unwrap();
+        let failed = repo.enqueue(third, podcast_id, 0, None).unwrap().unwrap();
+        repo.set_status(failed.id, DownloadJobStatus::Failed, Some("boom"))
+            .unwrap();
+
+        let mut counts = repo.count_by_status().unwrap();
+        counts.sort_by_key(|(status, _)| status.as_str());
+
+        assert_eq!(
+            counts,
+            vec![
+                (DownloadJobStatus::Failed, 1),
+                (DownloadJobStatus::Pending, 2)
+            ]
+        );
+    }
+}
@@ -174,4 +174,15 @@ impl EpisodeTriageRepository for DieselEpisodeTriageRepository {
             })
             .map_err(Into::into)
     }
+
+    fn list_by_user(&self, user_id: Uuid) -> Result<Vec<EpisodeTriage>, Self::Error> {
+        use self::episode_triages::dsl as et_dsl;
+        use self::episode_triages::table as et_table;
+
+        et_table
+            .filter(et_dsl::user_id.eq(user_id.to_string()))
+            .load::<EpisodeTriageEntity>(&mut self.database.connection()?)
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
 }
@@ -247,6 +247,21 @@ impl FavoriteRepository for DieselFavoriteRepository {
             .map_err(Into::into)
     }
 
+    fn find_user_ids_favoring(&self, podcast_id: Uuid) -> Result<Vec<Uuid>, Self::Error> {
+        let user_ids = favorites::table
+            .filter(
+                favorites::podcast_id
+                    .eq(podcast_id.to_string())
+                    .and(favorites::favored.eq(true)),
+            )
+            .select(favorites::user_id)
+            .load::<String>(&mut self.database.connection()?)?;
+        Ok(user_ids
+            .iter()
+            .filter_map(|user_id| Uuid::parse_str(user_id).ok())
+            .collect())
+    }
+
     fn delete_by_user_id(&self, user_id: Uuid) -> Result<(), Self::Error> {
         diesel::delete(favorites::table.filter(favorites::user_id.eq(user_id.to_string())))
             .execute(&mut self.database.connection()?)
@@ -4,10 +4,14 @@ pub mod schema;
 pub use db::{database, get_connection, run_migrations};
 
 pub mod adapters;
+pub mod api_token;
 pub mod audiobookshelf;
+pub mod audit_log;
+pub mod backup;
 pub mod device;
 pub mod device_sync_group;
 pub mod episode;
+pub mod episode_download;
 pub mod episode_triage;
 pub mod favorite;
 pub mod favorite_podcast_episode;
@@ -16,15 +20,22 @@ pub mod gpodder_setting;
 pub mod invite;
 pub mod listening_event;
 pub mod notification;
+pub mod notification_channel;
+pub mod notification_rule;
 pub mod playlist;
 pub mod podcast;
 pub mod podcast_episode;
 pub mod podcast_episode_chapter;
 pub mod podcast_episode_transcript;
+pub mod podcast_feed;
 pub mod podcast_settings;
+pub mod search;
 pub mod session;
 pub mod settings;
 pub mod sponsorblock;
+pub mod storage_usage;
 pub mod subscription;
 pub mod tag;
+pub mod transcoding_profile;
+pub mod two_factor;
 pub mod user_admin;
@@ -1,6 +1,7 @@
 use crate::db::{Database, PersistenceError};
+use diesel::dsl::not;
 use diesel::prelude::{AsChangeset, Insertable, Queryable};
-use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use diesel::{BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 use podfetch_domain::notification::{Notification, NotificationRepository};
 use uuid::Uuid;
 
@@ -11,6 +12,15 @@ diesel::table! {
         message -> Text,
         created_at -> Text,
         status -> Text,
+        user_id -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    notification_dismissals (notification_id, user_id) {
+        notification_id -> Text,
+        user_id -> Text,
+        dismissed_at -> Timestamp,
     }
 }
 
@@ -22,6 +32,7 @@ struct NotificationEntity {
     message: String,
     created_at: String,
     status: String,
+    user_id: Option<String>,
 }
 
 impl From<NotificationEntity> for Notification {
@@ -32,6 +43,9 @@ impl From<NotificationEntity> for Notification {
             message: value.message,
             created_at: value.created_at,
             status: value.status,
+            user_id: value
+                .user_id
+                .map(|user_id| Uuid::parse_str(&user_id).expect("valid uuid in db")),
         }
     }
 }
@@ -59,19 +73,32 @@ impl NotificationRepository for DieselNotificationRepository {
                 message.eq(notification.message),
                 created_at.eq(notification.created_at),
                 status.eq(notification.status),
+                user_id.eq(notification.user_id.map(|owner| owner.to_string())),
             ))
             .get_result::<NotificationEntity>(&mut self.database.connection()?)
             .map(Into::into)
             .map_err(Into::into)
     }
 
-    fn get_unread_notifications(&self) -> Result<Vec<Notification>, Self::Error> {
+    fn get_unread_notifications(
+        &self,
+        user_id_to_find: Uuid,
+    ) -> Result<Vec<Notification>, Self::Error> {
+        use self::notification_dismissals::dsl as nd_dsl;
         use self::notifications::dsl::*;
 
+        let mut conn = self.database.connection()?;
+        let user = user_id_to_find.to_string();
+        let dismissed = nd_dsl::notification_dismissals
+            .filter(nd_dsl::user_id.eq(user.clone()))
+            .select(nd_dsl::notification_id)
+            .load::<String>(&mut conn)?;
         notifications
             .filter(status.eq("unread"))
+            .filter(user_id.is_null().or(user_id.eq(user)))
+            .filter(not(id.eq_any(dismissed)))
             .order(created_at.desc())
-            .load::<NotificationEntity>(&mut self.database.connection()?)
+            .load::<NotificationEntity>(&mut conn)
             .map(|items| items.into_iter().map(Into::into).collect())
             .map_err(Into::into)
     }
@@ -89,4 +116,44 @@ impl NotificationRepository for DieselNotificationRepository {
             .map(|_| ())
             .map_err(Into::into)
     }
+
+    fn dismiss_notification(
+        &self,
+        id_to_dismiss: Uuid,
+        user_id_to_dismiss: Uuid,
+    ) -> Result<(), Self::Error> {
+        use self::notification_dismissals::dsl as nd_dsl;
+        use self::notifications::dsl::*;
+
+        let mut conn = self.database.connection()?;
+        let user = user_id_to_dismiss.to_string();
+        let visible = notifications
+            .filter(id.eq(id_to_dismiss.to_string()))
+            .filter(user_id.is_null().or(user_id.eq(user.clone())))
+            .select(id)
+            .first::<String>(&mut conn)
+            .optional()?;
+        let Some(notification_id) = visible else {
+            return Ok(());
+        };
+        let already_dismissed = nd_dsl::notification_dismissals
+            .filter(nd_dsl::notification_id.eq(&notification_id))
+            .filter(nd_dsl::user_id.eq(&user))
+            .count()
+            .get_result::<i64>(&mut conn)?
+            > 0;
+        if already_dismissed {
+            return Ok(());
+        }
+
+        diesel::insert_into(nd_dsl::notification_dismissals)
+            .values((
+                nd_dsl::notification_id.eq(notification_id),
+                nd_dsl::user_id.eq(user),
+                nd_dsl::dismissed_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(&mut conn)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
 }
@@ -0,0 +1,203 @@
+use crate::db::{Database, PersistenceError};
+use chrono::NaiveDateTime;
+use diesel::prelude::{AsChangeset, Insertable, Queryable, Selectable};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use podfetch_domain::notification_channel::{
+    NotificationChannel, NotificationChannelKind, NotificationChannelRepository,
+    NotificationEventType,
+};
+use uuid::Uuid;
+
+diesel::table! {
+    notification_channels (id) {
+        id -> Text,
+        name -> Text,
+        kind -> Text,
+        enabled -> Bool,
+        events -> Text,
+        config -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Clone)]
+#[diesel(table_name = notification_channels)]
+struct NotificationChannelEntity {
+    id: String,
+    name: String,
+    kind: String,
+    enabled: bool,
+    /// Comma separated event types.
+    events: String,
+    config: String,
+    created_at: NaiveDateTime,
+}
+
+impl From<NotificationChannelEntity> for NotificationChannel {
+    fn from(value: NotificationChannelEntity) -> Self {
+        Self {
+            id: Uuid::parse_str(&value.id).expect("valid uuid in db"),
+            name: value.name,
+            kind: NotificationChannelKind::from_str(&value.kind)
+                .expect("valid notification channel kind in db"),
+            enabled: value.enabled,
+            events: value
+                .events
+                .split(',')
+                .filter_map(NotificationEventType::from_str)
+                .collect(),
+            config: value.config,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<NotificationChannel> for NotificationChannelEntity {
+    fn from(value: NotificationChannel) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            kind: value.kind.as_str().to_string(),
+            enabled: value.enabled,
+            events: value
+                .events
+                .iter()
+                .map(NotificationEventType::as_str)
+                .collect::<Vec<_>>()
+                .join(","),
+            config: value.config,
+            created_at: value.created_at,
+        }
+    }
+}
+
+pub struct DieselNotificationChannelRepository {
+    database: Database,
+}
+
+impl DieselNotificationChannelRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+impl NotificationChannelRepository for DieselNotificationChannelRepository {
+    type Error = PersistenceError;
+
+    fn create(&self, channel: NotificationChannel) -> Result<NotificationChannel, Self::Error> {
+        use self::notification_channels::table as nc_table;
+
+        let entity = NotificationChannelEntity::from(channel);
+        diesel::insert_into(nc_table)
+            .values(&entity)
+            .execute(&mut self.database.connection()?)?;
+        Ok(entity.into())
+    }
+
+    fn update(
+        &self,
+        channel: NotificationChannel,
+    ) -> Result<Option<NotificationChannel>, Self::Error> {
+        use self::notification_channels::dsl as nc_dsl;
+        use self::notification_channels::table as nc_table;
+
+        let entity = NotificationChannelEntity::from(channel);
+        let updated = diesel::update(nc_table.filter(nc_dsl::id.eq(&entity.id)))
+            .set((
+                nc_dsl::name.eq(&entity.name),
+                nc_dsl::kind.eq(&entity.kind),
+                nc_dsl::enabled.eq(entity.enabled),
+                nc_dsl::events.eq(&entity.events),
+                nc_dsl::config.eq(&entity.config),
+            ))
+            .execute(&mut self.database.connection()?)?;
+        if updated == 0 {
+            return Ok(None);
+        }
+        self.get(Uuid::parse_str(&entity.id).expect("valid uuid"))
+    }
+
+    fn delete(&self, id: Uuid) -> Result<bool, Self::Error> {
+        use self::notification_channels::dsl as nc_dsl;
+        use self::notification_channels::table as nc_table;
+
+        diesel::delete(nc_table.filter(nc_dsl::id.eq(id.to_string())))
+            .execute(&mut self.database.connection()?)
+            .map(|deleted| deleted > 0)
+            .map_err(Into::into)
+    }
+
+    fn get(&self, id: Uuid) -> Result<Option<NotificationChannel>, Self::Error> {
+        use self::notification_channels::dsl as nc_dsl;
+        use self::notification_channels::table as nc_table;
+
+        nc_table
+            .filter(nc_dsl::id.eq(id.to_string()))
+            .first::<NotificationChannelEntity>(&mut self.database.connection()?)
+            .optional()
+            .map(|channel| channel.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    fn list(&self) -> Result<Vec<NotificationChannel>, Self::Error> {
+        use self::notification_channels::dsl as nc_dsl;
+        use self::notification_channels::table as nc_table;
+
+        nc_table
+            .order(nc_dsl::created_at.asc())
+            .load::<NotificationChannelEntity>(&mut self.database.connection()?)
+            .map(|channels| channels.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::db::{database, test_db::setup};
+
+    fn channel() -> NotificationChannel {
+        NotificationChannel {
+            id: Uuid::new_v4(),
+            name: "Team webhook".to_string(),
+            kind: NotificationChannelKind::Webhook,
+            enabled: true,
+            events: vec![
+                NotificationEventType::NewEpisode,
+                NotificationEventType::FeedBroken,
+            ],
+            config: r#"{"url":"https://example.com/hook"}"#.to_string(),
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn channels_round_trip_with_their_events() {
+        let _guard = setup();
+        let repo = DieselNotificationChannelRepository::new(database());
+        let created = repo.create(channel()).unwrap();
+
+        let stored = repo.get(created.id).unwrap().expect("channel stored");
+        assert_eq!(stored.events, created.events);
+        assert_eq!(stored.kind, NotificationChannelKind::Webhook);
+        assert!(repo.list().unwrap().iter().any(|c| c.id == created.id));
+
+        let updated = repo
+            .update(NotificationChannel {
+                enabled: false,
+                events: vec![NotificationEventType::DownloadFailed],
+                ..created.clone()
+            })
+            .unwrap()
+            .expect("channel updated");
+        assert!(!updated.enabled);
+        assert_eq!(updated.events, vec![NotificationEventType::DownloadFailed]);
+
+        assert!(repo.delete(created.id).unwrap());
+        assert!(repo.get(created.id).unwrap().is_none());
+        assert!(
+            repo.update(channel()).unwrap().is_none(),
+            "updating an unknown channel finds nothing"
+        );
+    }
+}
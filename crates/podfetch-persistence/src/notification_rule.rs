@@ -0,0 +1,269 @@
+use crate::db::{Database, PersistenceError};
+use chrono::NaiveDateTime;
+use diesel::prelude::{Insertable, Queryable, Selectable};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use podfetch_domain::notification_rule::{NotificationRule, NotificationRuleRepository};
+use uuid::Uuid;
+
+diesel::table! {
+    notification_rules (id) {
+        id -> Text,
+        user_id -> Text,
+        podcast_id -> Nullable<Text>,
+        tag_id -> Nullable<Text>,
+        min_duration_seconds -> Nullable<Integer>,
+        max_duration_seconds -> Nullable<Integer>,
+        title_regex -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, Clone)]
+#[diesel(table_name = notification_rules)]
+struct NotificationRuleEntity {
+    id: String,
+    user_id: String,
+    podcast_id: Option<String>,
+    tag_id: Option<String>,
+    min_duration_seconds: Option<i32>,
+    max_duration_seconds: Option<i32>,
+    title_regex: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl From<NotificationRuleEntity> for NotificationRule {
+    fn from(value: NotificationRuleEntity) -> Self {
+        Self {
+            id: Uuid::parse_str(&value.id).expect("valid uuid in db"),
+            user_id: Uuid::parse_str(&value.user_id).expect("valid uuid in db"),
+            podcast_id: value
+                .podcast_id
+                .map(|podcast_id| Uuid::parse_str(&podcast_id).expect("valid uuid in db")),
+            tag_id: value.tag_id,
+            min_duration_seconds: value.min_duration_seconds,
+            max_duration_seconds: value.max_duration_seconds,
+            title_regex: value.title_regex,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<NotificationRule> for NotificationRuleEntity {
+    fn from(value: NotificationRule) -> Self {
+        Self {
+            id: value.id.to_string(),
+            user_id: value.user_id.to_string(),
+            podcast_id: value.podcast_id.map(|podcast_id| podcast_id.to_string()),
+            tag_id: value.tag_id,
+            min_duration_seconds: value.min_duration_seconds,
+            max_duration_seconds: value.max_duration_seconds,
+            title_regex: value.title_regex,
+            created_at: value.created_at,
+        }
+    }
+}
+
+pub struct DieselNotificationRuleRepository {
+    database: Database,
+}
+
+impl DieselNotificationRuleRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+impl NotificationRuleRepository for DieselNotificationRuleRepository {
+    type Error = PersistenceError;
+
+    fn create(&self, rule: NotificationRule) -> Result<NotificationRule, Self::Error> {
+        use self::notification_rules::table as nr_table;
+
+        let entity = NotificationRuleEntity::from(rule);
+        diesel::insert_into(nr_table)
+            .values(&entity)
+            .execute(&mut self.database.connection()?)?;
+        Ok(entity.into())
+    }
+
+    fn update(&self, rule: NotificationRule) -> Result<Option<NotificationRule>, Self::Error> {
+        use self::notification_rules::dsl as nr_dsl;
+        use self::notification_rules::table as nr_table;
+
+        let (id, user_id) = (rule.id, rule.user_id);
+        let entity = NotificationRuleEntity::from(rule);
+        let updated = diesel::update(
+            nr_table
+                .filter(nr_dsl::id.eq(&entity.id))
+                .filter(nr_dsl::user_id.eq(&entity.user_id)),
+        )
+        .set((
+            nr_dsl::podcast_id.eq(&entity.podcast_id),
+            nr_dsl::tag_id.eq(&entity.tag_id),
+            nr_dsl::min_duration_seconds.eq(entity.min_duration_seconds),
+            nr_dsl::max_duration_seconds.eq(entity.max_duration_seconds),
+            nr_dsl::title_regex.eq(&entity.title_regex),
+        ))
+        .execute(&mut self.database.connection()?)?;
+        if updated == 0 {
+            return Ok(None);
+        }
+        self.get(id, user_id)
+    }
+
+    fn delete(&self, id: Uuid, user_id: Uuid) -> Result<bool, Self::Error> {
+        use self::notification_rules::dsl as nr_dsl;
+        use self::notification_rules::table as nr_table;
+
+        diesel::delete(
+            nr_table
+                .filter(nr_dsl::id.eq(id.to_string()))
+                .filter(nr_dsl::user_id.eq(user_id.to_string())),
+        )
+        .execute(&mut self.database.connection()?)
+        .map(|deleted| deleted > 0)
+        .map_err(Into::into)
+    }
+
+    fn get(&self, id: Uuid, user_id: Uuid) -> Result<Option<NotificationRule>, Self::Error> {
+        use self::notification_rules::dsl as nr_dsl;
+        use self::notification_rules::table as nr_table;
+
+        nr_table
+            .filter(nr_dsl::id.eq(id.to_string()))
+            .filter(nr_dsl::user_id.eq(user_id.to_string()))
+            .first::<NotificationRuleEntity>(&mut self.database.connection()?)
+            .optional()
+            .map(|rule| rule.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    fn list_by_user(&self, user_id: Uuid) -> Result<Vec<NotificationRule>, Self::Error> {
+        use self::notification_rules::dsl as nr_dsl;
+        use self::notification_rules::table as nr_table;
+
+        nr_table
+            .filter(nr_dsl::user_id.eq(user_id.to_string()))
+            .order(nr_dsl::created_at.asc())
+            .load::<NotificationRuleEntity>(&mut self.database.connection()?)
+            .map(|rules| rules.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::db::{database, test_db::setup};
+    use crate::notification::DieselNotificationRepository;
+    use podfetch_domain::notification::{Notification, NotificationRepository};
+
+    mod seed_schema {
+        diesel::table! {
+            users (id) { id -> Text, username -> Text, role -> Text, }
+        }
+    }
+
+    #[derive(diesel::Insertable)]
+    #[diesel(table_name = seed_schema::users)]
+    struct SeedUser {
+        id: String,
+        username: String,
+        role: String,
+    }
+
+    fn seed_user() -> Uuid {
+        use seed_schema::users;
+        let user_id = podfetch_domain::ids::new_id();
+        let mut conn = database().connection().expect("db connection");
+        diesel::insert_into(users::table)
+            .values(SeedUser {
+                id: user_id.to_string(),
+                username: format!("notification-test-{user_id}"),
+                role: "user".to_string(),
+            })
+            .execute(&mut conn)
+            .expect("seed user");
+        user_id
+    }
+
+    fn rule(user_id: Uuid) -> NotificationRule {
+        NotificationRule {
+            id: Uuid::new_v4(),
+            user_id,
+            podcast_id: None,
+            tag_id: None,
+            min_duration_seconds: Some(1200),
+            max_duration_seconds: None,
+            title_regex: Some("(?i)interview".to_string()),
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn rules_are_only_visible_to_their_owner() {
+        let _guard = setup();
+        let repo = DieselNotificationRuleRepository::new(database());
+        let owner = seed_user();
+        let other = seed_user();
+        let created = repo.create(rule(owner)).unwrap();
+
+        assert_eq!(repo.list_by_user(owner).unwrap(), vec![created.clone()]);
+        assert!(repo.list_by_user(other).unwrap().is_empty());
+        assert!(repo.get(created.id, other).unwrap().is_none());
+        assert!(
+            repo.update(NotificationRule {
+                user_id: other,
+                ..created.clone()
+            })
+            .unwrap()
+            .is_none()
+        );
+        assert!(!repo.delete(created.id, other).unwrap());
+
+        let updated = repo
+            .update(NotificationRule {
+                title_regex: None,
+                ..created.clone()
+            })
+            .unwrap()
+            .expect("rule updated");
+        assert_eq!(updated.title_regex, None);
+        assert!(repo.delete(created.id, owner).unwrap());
+    }
+
+    #[test]
+    fn dismissals_only_hide_notifications_for_the_dismissing_user() {
+        let _guard = setup();
+        let repo = DieselNotificationRepository::new(database());
+        let alice = seed_user();
+        let bob = seed_user();
+        let notification = |user_id| Notification {
+            id: Uuid::nil(),
+            type_of_message: "Download".to_string(),
+            message: "episode".to_string(),
+            created_at: "2026-09-20 10:00:00".to_string(),
+            status: "unread".to_string(),
+            user_id,
+        };
+        let global = repo.create(notification(None)).unwrap();
+        let owned = repo.create(notification(Some(alice))).unwrap();
+
+        let unread = |user_id| {
+            repo.get_unread_notifications(user_id)
+                .unwrap()
+                .into_iter()
+                .map(|n| n.id)
+                .collect::<Vec<_>>()
+        };
+        assert!(unread(alice).contains(&global.id) && unread(alice).contains(&owned.id));
+        assert!(unread(bob).contains(&global.id) && !unread(bob).contains(&owned.id));
+
+        repo.dismiss_notification(global.id, alice).unwrap();
+        repo.dismiss_notification(global.id, alice).unwrap();
+        repo.dismiss_notification(owned.id, bob).unwrap();
+        assert!(!unread(alice).contains(&global.id));
+        assert!(unread(alice).contains(&owned.id));
+        assert!(unread(bob).contains(&global.id));
+    }
+}
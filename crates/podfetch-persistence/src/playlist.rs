@@ -9,6 +9,7 @@ diesel::table! {
         id -> Text,
         name -> Text,
         user_id -> Text,
+        smart_query -> Nullable<Text>,
     }
 }
 
@@ -26,6 +27,7 @@ struct PlaylistEntity {
     id: String,
     name: String,
     user_id: String,
+    smart_query: Option<String>,
 }
 
 #[derive(Insertable, Clone)]
@@ -34,6 +36,7 @@ struct PlaylistInsertEntity {
     id: String,
     name: String,
     user_id: String,
+    smart_query: Option<String>,
 }
 
 #[derive(Queryable, Selectable, Clone)]
@@ -58,6 +61,7 @@ impl From<PlaylistEntity> for Playlist {
             id: value.id,
             name: value.name,
             user_id: Uuid::parse_str(&value.user_id).expect("valid uuid in db"),
+            smart_query: value.smart_query,
         }
     }
 }
@@ -68,6 +72,7 @@ impl From<Playlist> for PlaylistInsertEntity {
             id: value.id,
             name: value.name,
             user_id: value.user_id.to_string(),
+            smart_query: value.smart_query,
         }
     }
 }
@@ -186,6 +191,25 @@ impl PlaylistRepository for DieselPlaylistRepository {
         .map_err(Into::into)
     }
 
+    fn update_smart_query(
+        &self,
+        playlist_id_to_update: &str,
+        playlist_user_id: Uuid,
+        query: Option<&str>,
+    ) -> Result<usize, Self::Error> {
+        use self::playlists::dsl as p_dsl;
+        use self::playlists::table as p_table;
+
+        diesel::update(
+            p_table
+                .filter(p_dsl::id.eq(playlist_id_to_update))
+                .filter(p_dsl::user_id.eq(playlist_user_id.to_string())),
+        )
+        .set(p_dsl::smart_query.eq(query))
+        .execute(&mut self.database.connection()?)
+        .map_err(Into::into)
+    }
+
     fn delete_playlist(
         &self,
         playlist_id_to_delete: &str,
@@ -10,6 +10,7 @@ use diesel::{
 use podfetch_domain::episode::Episode;
 use podfetch_domain::favorite_podcast_episode::FavoritePodcastEpisode;
 use podfetch_domain::podcast_episode::{
+    EpisodeCandidateFilter, EpisodeCandidateOrder, EpisodeListFilter, EpisodeSort,
     NewPodcastEpisode, PodcastEpisode, PodcastEpisodeRepository, PodcastEpisodeWithHistory,
 };
 use uuid::Uuid;
@@ -34,6 +35,11 @@ diesel::table! {
         episode_numbering_processed -> Bool,
         download_location -> Nullable<Text>,
         youtube_video_id -> Nullable<Text>,
+        season -> Nullable<Integer>,
+        episode_number -> Nullable<Integer>,
+        episode_type -> Nullable<Text>,
+        loudness_lufs -> Nullable<Double>,
+        loudness_true_peak -> Nullable<Double>,
     }
 }
 
@@ -96,9 +102,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     podcasts,
 );
 
-#[derive(
-    Queryable, Identifiable, Selectable, AsChangeset, Debug, Clone, PartialEq, Eq, Default,
-)]
+#[derive(Queryable, Identifiable, Selectable, AsChangeset, Debug, Clone, PartialEq, Default)]
 #[diesel(table_name = podcast_episodes)]
 pub struct PodcastEpisodeEntity {
     pub id: String,
@@ -119,6 +123,11 @@ pub struct PodcastEpisodeEntity {
     pub episode_numbering_processed: bool,
     pub download_location: Option<String>,
     pub youtube_video_id: Option<String>,
+    pub season: Option<i32>,
+    pub episode_number: Option<i32>,
+    pub episode_type: Option<String>,
+    pub loudness_lufs: Option<f64>,
+    pub loudness_true_peak: Option<f64>,
 }
 
 #[derive(Insertable, Debug, Clone)]
@@ -136,6 +145,9 @@ struct NewPodcastEpisodeEntity {
     description: String,
     guid: String,
     youtube_video_id: Option<String>,
+    season: Option<i32>,
+    episode_number: Option<i32>,
+    episode_type: Option<String>,
 }
 
 // Entity for reading from episodes table in joins
@@ -185,6 +197,11 @@ impl From<PodcastEpisodeEntity> for PodcastEpisode {
             episode_numbering_processed: entity.episode_numbering_processed,
             download_location: entity.download_location,
             youtube_video_id: entity.youtube_video_id,
+            season: entity.season,
+            episode_number: entity.episode_number,
+            episode_type: entity.episode_type,
+            loudness_lufs: entity.loudness_lufs,
+            loudness_true_peak: entity.loudness_true_peak,
         }
     }
 }
@@ -210,6 +227,11 @@ impl From<&PodcastEpisode> for PodcastEpisodeEntity {
             episode_numbering_processed: episode.episode_numbering_processed,
             download_location: episode.download_location.clone(),
             youtube_video_id: episode.youtube_video_id.clone(),
+            season: episode.season,
+            episode_number: episode.episode_number,
+            episode_type: episode.episode_type.clone(),
+            loudness_lufs: episode.loudness_lufs,
+            loudness_true_peak: episode.loudness_true_peak,
         }
     }
 }
@@ -229,6 +251,9 @@ impl From<NewPodcastEpisode> for NewPodcastEpisodeEntity {
             description: episode.description,
             guid: episode.guid,
             youtube_video_id: episode.youtube_video_id,
+            season: episode.season,
+            episode_number: episode.episode_number,
+            episode_type: episode.episode_type,
         }
     }
 }
@@ -264,6 +289,11 @@ impl From<PodcastEpisode> for PodcastEpisodeEntity {
             episode_numbering_processed: episode.episode_numbering_processed,
             download_location: episode.download_location,
             youtube_video_id: episode.youtube_video_id,
+            season: episode.season,
+            episode_number: episode.episode_number,
+            episode_type: episode.episode_type,
+            loudness_lufs: episode.loudness_lufs,
+            loudness_true_peak: episode.loudness_true_peak,
         }
     }
 }
@@ -304,6 +334,17 @@ impl PodcastEpisodeRepository for DieselPodcastEpisodeRepository {
             .map_err(Into::into)
     }
 
+    fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<PodcastEpisode>, Self::Error> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        podcast_episodes::table
+            .filter(podcast_episodes::id.eq_any(ids.iter().map(Uuid::to_string)))
+            .load::<PodcastEpisodeEntity>(&mut self.database.connection()?)
+            .map(|episodes| episodes.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
     fn find_by_legacy_id(&self, legacy_id: i64) -> Result<Option<PodcastEpisode>, Self::Error> {
         podcast_episodes::table
             .filter(podcast_episodes::legacy_id.eq(legacy_id))
@@ -426,6 +467,7 @@ impl PodcastEpisodeRepository for DieselPodcastEpisodeRepository {
         username: &str,
         last_date: Option<&str>,
         only_unlistened: bool,
+        filter: &EpisodeListFilter,
         limit: i64,
     ) -> Result<PodcastEpisodeWithHistory, Self::Error> {
         // Empty string is used as a "no such user" sentinel; it can never match
@@ -465,12 +507,32 @@ impl PodcastEpisodeRepository for DieselPodcastEpisodeRepository {
                     .eq_any(subquery)
                     .or(ep1.field(episodes::timestamp).is_null()),
             )
-            .order(podcast_episodes::date_of_recording.desc())
-            .limit(limit)
             .into_boxed();
 
-        if let Some(last_date_str) = last_date {
-            query = query.filter(podcast_episodes::date_of_recording.lt(last_date_str));
+        match filter.sort {
+            EpisodeSort::Date => {
+                query = query
+                    .order(podcast_episodes::date_of_recording.desc())
+                    .limit(limit);
+                if let Some(last_date_str) = last_date {
+                    query = query.filter(podcast_episodes::date_of_recording.lt(last_date_str));
+                }
+            }
+            EpisodeSort::Episode => {
+                query = query.order((
+                    podcast_episodes::season.asc(),
+                    podcast_episodes::episode_number.asc(),
+                    podcast_episodes::date_of_recording.asc(),
+                ));
+            }
+        }
+
+        if let Some(season) = filter.season {
+            query = query.filter(podcast_episodes::season.eq(season));
+        }
+
+        if let Some(episode_type) = &filter.episode_type {
+            query = query.filter(podcast_episodes::episode_type.eq(episode_type.clone()));
         }
 
         if only_unlistened {
@@ -561,6 +623,50 @@ impl PodcastEpisodeRepository for DieselPodcastEpisodeRepository {
             .map_err(Into::into)
     }
 
+    fn find_candidates(
+        &self,
+        filter: &EpisodeCandidateFilter,
+    ) -> Result<Vec<PodcastEpisode>, Self::Error> {
+        let mut query = podcast_episodes::table
+            .filter(podcast_episodes::deleted.eq(false))
+            .limit(filter.limit)
+            .into_boxed();
+
+        if let Some(podcast_ids) = &filter.podcast_ids {
+            let podcast_ids: Vec<String> = podcast_ids.iter().map(Uuid::to_string).collect();
+            query = query.filter(podcast_episodes::podcast_id.eq_any(podcast_ids));
+        }
+        match filter.downloaded {
+            Some(true) => query = query.filter(podcast_episodes::download_location.is_not_null()),
+            Some(false) => query = query.filter(podcast_episodes::download_location.is_null()),
+            None => {}
+        }
+        if let Some(recorded_from) = &filter.recorded_from {
+            query = query.filter(podcast_episodes::date_of_recording.ge(recorded_from.clone()));
+        }
+        if let Some(min_total_time) = filter.min_total_time {
+            query = query.filter(podcast_episodes::total_time.ge(min_total_time));
+        }
+        if let Some(max_total_time) = filter.max_total_time {
+            query = query.filter(podcast_episodes::total_time.le(max_total_time));
+        }
+        query = match filter.order {
+            EpisodeCandidateOrder::NewestFirst => {
+                query.order(podcast_episodes::date_of_recording.desc())
+            }
+            EpisodeCandidateOrder::OldestFirst => {
+                query.order(podcast_episodes::date_of_recording.asc())
+            }
+            EpisodeCandidateOrder::ShortestFirst => query.order(podcast_episodes::total_time.asc()),
+            EpisodeCandidateOrder::LongestFirst => query.order(podcast_episodes::total_time.desc()),
+        };
+
+        query
+            .load::<PodcastEpisodeEntity>(&mut self.database.connection()?)
+            .map(|entities| entities.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
     fn get_inbox_episodes(
         &self,
         exclude_episode_ids: &[Uuid],
@@ -711,6 +817,8 @@ impl PodcastEpisodeRepository for DieselPodcastEpisodeRepository {
                 podcast_episodes::download_time.eq::<Option<NaiveDateTime>>(None),
                 podcast_episodes::file_episode_path.eq::<Option<String>>(None),
                 podcast_episodes::file_image_path.eq::<Option<String>>(None),
+                podcast_episodes::loudness_lufs.eq::<Option<f64>>(None),
+                podcast_episodes::loudness_true_peak.eq::<Option<f64>>(None),
             ))
             .execute(&mut self.database.connection()?)
             .map(|_| ())
@@ -743,4 +851,20 @@ impl PodcastEpisodeRepository for DieselPodcastEpisodeRepository {
             .map(|_| ())
             .map_err(Into::into)
     }
+
+    fn update_loudness(
+        &self,
+        episode_id: &str,
+        loudness_lufs: Option<f64>,
+        loudness_true_peak: Option<f64>,
+    ) -> Result<(), Self::Error> {
+        diesel::update(podcast_episodes::table.filter(podcast_episodes::episode_id.eq(episode_id)))
+            .set((
+                podcast_episodes::loudness_lufs.eq(loudness_lufs),
+                podcast_episodes::loudness_true_peak.eq(loudness_true_peak),
+            ))
+            .execute(&mut self.database.connection()?)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
 }
@@ -1,9 +1,10 @@
 use crate::db::{Database, PersistenceError};
 use chrono::NaiveDateTime;
 use diesel::prelude::{Insertable, Queryable, Selectable};
-use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use diesel::{Connection, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 use podfetch_domain::podcast_episode_chapter::{
-    PodcastEpisodeChapter, PodcastEpisodeChapterRepository, UpsertPodcastEpisodeChapter,
+    ChapterFetchFailure, ChapterSource, PodcastEpisodeChapter, PodcastEpisodeChapterRepository,
+    UpsertPodcastEpisodeChapter,
 };
 use uuid::Uuid;
 
@@ -18,6 +19,18 @@ diesel::table! {
         image -> Nullable<Text>,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        source -> Text,
+        source_url -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    chapter_fetch_failures (episode_id) {
+        episode_id -> Text,
+        url -> Text,
+        failures -> Integer,
+        last_failure_at -> Timestamp,
+        last_error -> Text,
     }
 }
 
@@ -33,6 +46,8 @@ struct PodcastEpisodeChapterEntity {
     image: Option<String>,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
+    source: String,
+    source_url: Option<String>,
 }
 
 #[derive(Insertable, Clone)]
@@ -47,6 +62,8 @@ struct PodcastEpisodeChapterInsertEntity {
     image: Option<String>,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
+    source: String,
+    source_url: Option<String>,
 }
 
 impl From<PodcastEpisodeChapterEntity> for PodcastEpisodeChapter {
@@ -59,12 +76,36 @@ impl From<PodcastEpisodeChapterEntity> for PodcastEpisodeChapter {
             end_time: value.end_time,
             href: value.href,
             image: value.image,
+            source: ChapterSource::from_str(&value.source).unwrap_or(ChapterSource::Embedded),
+            source_url: value.source_url,
             created_at: value.created_at,
             updated_at: value.updated_at,
         }
     }
 }
 
+#[derive(Queryable, Selectable, Insertable, Clone)]
+#[diesel(table_name = chapter_fetch_failures)]
+struct ChapterFetchFailureEntity {
+    episode_id: String,
+    url: String,
+    failures: i32,
+    last_failure_at: NaiveDateTime,
+    last_error: String,
+}
+
+impl From<ChapterFetchFailureEntity> for ChapterFetchFailure {
+    fn from(value: ChapterFetchFailureEntity) -> Self {
+        Self {
+            episode_id: Uuid::parse_str(&value.episode_id).unwrap_or_default(),
+            url: value.url,
+            failures: value.failures,
+            last_failure_at: value.last_failure_at,
+            last_error: value.last_error,
+        }
+    }
+}
+
 pub struct DieselPodcastEpisodeChapterRepository {
     database: Database,
 }
@@ -84,6 +125,15 @@ impl PodcastEpisodeChapterRepository for DieselPodcastEpisodeChapterRepository {
 
         let now = chrono::Utc::now().naive_utc();
         let episode_id = chapter.episode_id.to_string();
+        let has_feed_chapters = pec_table
+            .filter(pec_dsl::episode_id.eq(episode_id.clone()))
+            .filter(pec_dsl::source.eq(ChapterSource::Feed.as_str()))
+            .count()
+            .get_result::<i64>(&mut self.database.connection()?)?
+            > 0;
+        if has_feed_chapters {
+            return Ok(());
+        }
         let existing = pec_table
             .filter(pec_dsl::episode_id.eq(episode_id.clone()))
             .filter(pec_dsl::start_time.eq(chapter.start_time))
@@ -101,6 +151,8 @@ impl PodcastEpisodeChapterRepository for DieselPodcastEpisodeChapterRepository {
                 image: chapter.image,
                 created_at: existing.created_at,
                 updated_at: now,
+                source: ChapterSource::Embedded.as_str().to_string(),
+                source_url: None,
             },
             None => PodcastEpisodeChapterInsertEntity {
                 id: uuid::Uuid::new_v4().to_string(),
@@ -112,6 +164,8 @@ impl PodcastEpisodeChapterRepository for DieselPodcastEpisodeChapterRepository {
                 image: chapter.image,
                 created_at: now,
                 updated_at: now,
+                source: ChapterSource::Embedded.as_str().to_string(),
+                source_url: None,
             },
         };
 
@@ -137,6 +191,45 @@ impl PodcastEpisodeChapterRepository for DieselPodcastEpisodeChapterRepository {
         }
     }
 
+    fn replace_with_feed_chapters(
+        &self,
+        episode_id: Uuid,
+        source_url: &str,
+        chapters: Vec<UpsertPodcastEpisodeChapter>,
+    ) -> Result<(), Self::Error> {
+        use self::podcast_episode_chapters::dsl as pec_dsl;
+        use self::podcast_episode_chapters::table as pec_table;
+
+        let now = chrono::Utc::now().naive_utc();
+        let episode_id = episode_id.to_string();
+        let rows = chapters
+            .into_iter()
+            .map(|chapter| PodcastEpisodeChapterInsertEntity {
+                id: Uuid::new_v4().to_string(),
+                episode_id: episode_id.clone(),
+                title: chapter.title,
+                start_time: chapter.start_time,
+                end_time: chapter.end_time,
+                href: chapter.href,
+                image: chapter.image,
+                created_at: now,
+                updated_at: now,
+                source: ChapterSource::Feed.as_str().to_string(),
+                source_url: Some(source_url.to_string()),
+            })
+            .collect::<Vec<_>>();
+
+        let mut conn = self.database.connection()?;
+        conn.transaction(|conn| {
+            diesel::delete(pec_table.filter(pec_dsl::episode_id.eq(&episode_id))).execute(conn)?;
+            for row in rows {
+                diesel::insert_into(pec_table).values(row).execute(conn)?;
+            }
+            Ok::<_, diesel::result::Error>(())
+        })
+        .map_err(Into::into)
+    }
+
     fn get_by_episode_id(
         &self,
         episode_id_to_search: Uuid,
@@ -150,4 +243,62 @@ impl PodcastEpisodeChapterRepository for DieselPodcastEpisodeChapterRepository {
             .map(|chapters| chapters.into_iter().map(Into::into).collect())
             .map_err(Into::into)
     }
+
+    fn get_fetch_failure(
+        &self,
+        episode_id: Uuid,
+    ) -> Result<Option<ChapterFetchFailure>, Self::Error> {
+        use self::chapter_fetch_failures::dsl as cff_dsl;
+        use self::chapter_fetch_failures::table as cff_table;
+
+        cff_table
+            .filter(cff_dsl::episode_id.eq(episode_id.to_string()))
+            .first::<ChapterFetchFailureEntity>(&mut self.database.connection()?)
+            .optional()
+            .map(|failure| failure.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    fn record_fetch_failure(
+        &self,
+        episode_id: Uuid,
+        url: &str,
+        error: &str,
+        at: NaiveDateTime,
+    ) -> Result<ChapterFetchFailure, Self::Error> {
+        use self::chapter_fetch_failures::dsl as cff_dsl;
+        use self::chapter_fetch_failures::table as cff_table;
+
+        let previous_failures = self
+            .get_fetch_failure(episode_id)?
+            .filter(|previous| previous.url == url)
+            .map_or(0, |previous| previous.failures);
+        let entity = ChapterFetchFailureEntity {
+            episode_id: episode_id.to_string(),
+            url: url.to_string(),
+            failures: previous_failures.saturating_add(1),
+            last_failure_at: at,
+            last_error: error.to_string(),
+        };
+        let mut conn = self.database.connection()?;
+        conn.transaction(|conn| {
+            diesel::delete(cff_table.filter(cff_dsl::episode_id.eq(&entity.episode_id)))
+                .execute(conn)?;
+            diesel::insert_into(cff_table)
+                .values(entity.clone())
+                .execute(conn)?;
+            Ok::<_, diesel::result::Error>(())
+        })?;
+        Ok(entity.into())
+    }
+
+    fn clear_fetch_failure(&self, episode_id: Uuid) -> Result<(), Self::Error> {
+        use self::chapter_fetch_failures::dsl as cff_dsl;
+        use self::chapter_fetch_failures::table as cff_table;
+
+        diesel::delete(cff_table.filter(cff_dsl::episode_id.eq(episode_id.to_string())))
+            .execute(&mut self.database.connection()?)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
 }
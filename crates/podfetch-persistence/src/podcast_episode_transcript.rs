@@ -687,6 +687,22 @@ impl TranscriptionJobRepository for DieselTranscriptionJobRepository {
             .map(|row| row.map(Into::into))
             .map_err(Into::into)
     }
+
+    fn count_by_status(&self) -> Result<Vec<(TranscriptionJobStatus, i64)>, Self::Error> {
+        use self::transcription_jobs::dsl as tj_dsl;
+        use self::transcription_jobs::table as tj_table;
+
+        let rows = tj_table
+            .group_by(tj_dsl::status)
+            .select((tj_dsl::status, diesel::dsl::count_star()))
+            .load::<(String, i64)>(&mut self.database.connection()?)?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(status, count)| {
+                TranscriptionJobStatus::from_str(&status).map(|s| (s, count))
+            })
+            .collect())
+    }
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────────
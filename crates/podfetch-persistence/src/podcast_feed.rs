@@ -0,0 +1,463 @@
+use crate::db::{Database, PersistenceError};
+use chrono::NaiveDateTime;
+use diesel::prelude::{AsChangeset, Insertable, Queryable, Selectable};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl, SelectableHelper};
+use podfetch_domain::podcast_feed::{
+    PodcastFeedHealth, PodcastFeedState, PodcastFeedStateRepository,
+};
+use uuid::Uuid;
+
+diesel::table! {
+    podcast_feed_states (podcast_id) {
+        podcast_id -> Text,
+        etag -> Nullable<Text>,
+        last_modified -> Nullable<Text>,
+        content_hash -> Nullable<Text>,
+        updated_at -> Timestamp,
+        update_interval_hint -> Nullable<Integer>,
+        next_refresh_at -> Nullable<Timestamp>,
+        last_success_at -> Nullable<Timestamp>,
+        last_failure_at -> Nullable<Timestamp>,
+        consecutive_failures -> Integer,
+        failing_since -> Nullable<Timestamp>,
+        last_error -> Nullable<Text>,
+        last_http_status -> Nullable<Integer>,
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Clone)]
+#[diesel(table_name = podcast_feed_states)]
+#[diesel(treat_none_as_null = true)]
+struct PodcastFeedStateEntity {
+    podcast_id: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    content_hash: Option<String>,
+    updated_at: NaiveDateTime,
+    update_interval_hint: Option<i32>,
+    next_refresh_at: Option<NaiveDateTime>,
+}
+
+impl From<PodcastFeedStateEntity> for PodcastFeedState {
+    fn from(value: PodcastFeedStateEntity) -> Self {
+        Self {
+            podcast_id: Uuid::parse_str(&value.podcast_id).expect("valid uuid in db"),
+            etag: value.etag,
+            last_modified: value.last_modified,
+            content_hash: value.content_hash,
+            update_interval_hint: value.update_interval_hint,
+            next_refresh_at: value.next_refresh_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+impl From<PodcastFeedState> for PodcastFeedStateEntity {
+    fn from(value: PodcastFeedState) -> Self {
+        Self {
+            podcast_id: value.podcast_id.to_string(),
+            etag: value.etag,
+            last_modified: value.last_modified,
+            content_hash: value.content_hash,
+            update_interval_hint: value.update_interval_hint,
+            next_refresh_at: value.next_refresh_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+/// The health columns of a state row. Kept apart from
+/// [`PodcastFeedStateEntity`] so storing validators never resets the health
+/// and vice versa.
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Clone)]
+#[diesel(table_name = podcast_feed_states)]
+#[diesel(treat_none_as_null = true)]
+struct PodcastFeedHealthEntity {
+    podcast_id: String,
+    last_success_at: Option<NaiveDateTime>,
+    last_failure_at: Option<NaiveDateTime>,
+    consecutive_failures: i32,
+    failing_since: Option<NaiveDateTime>,
+    last_error: Option<String>,
+    last_http_status: Option<i32>,
+}
+
+impl From<PodcastFeedHealthEntity> for PodcastFeedHealth {
+    fn from(value: PodcastFeedHealthEntity) -> Self {
+        Self {
+            podcast_id: Uuid::parse_str(&value.podcast_id).expect("valid uuid in db"),
+            last_success_at: value.last_success_at,
+            last_failure_at: value.last_failure_at,
+            consecutive_failures: value.consecutive_failures,
+            failing_since: value.failing_since,
+            last_error: value.last_error,
+            last_http_status: value.last_http_status,
+        }
+    }
+}
+
+impl From<PodcastFeedHealth> for PodcastFeedHealthEntity {
+    fn from(value: PodcastFeedHealth) -> Self {
+        Self {
+            podcast_id: value.podcast_id.to_string(),
+            last_success_at: value.last_success_at,
+            last_failure_at: value.last_failure_at,
+            consecutive_failures: value.consecutive_failures,
+            failing_since: value.failing_since,
+            last_error: value.last_error,
+            last_http_status: value.last_http_status,
+        }
+    }
+}
+
+pub struct DieselPodcastFeedStateRepository {
+    database: Database,
+}
+
+impl DieselPodcastFeedStateRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    /// Writes the health columns, creating the row if the feed has none yet.
+    fn store_health(
+        &self,
+        health: PodcastFeedHealth,
+        at: NaiveDateTime,
+    ) -> Result<(), PersistenceError> {
+        use self::podcast_feed_states::dsl as pfs_dsl;
+        use self::podcast_feed_states::table as pfs_table;
+
+        let entity = PodcastFeedHealthEntity::from(health);
+        let mut conn = self.database.connection()?;
+        let updated = diesel::update(pfs_table.filter(pfs_dsl::podcast_id.eq(&entity.podcast_id)))
+            .set(&entity)
+            .execute(&mut conn)?;
+        if updated == 0 {
+            diesel::insert_into(pfs_table)
+                .values((&entity, pfs_dsl::updated_at.eq(at)))
+                .execute(&mut conn)?;
+        }
+        Ok(())
+    }
+}
+
+impl PodcastFeedStateRepository for DieselPodcastFeedStateRepository {
+    type Error = PersistenceError;
+
+    fn get(&self, podcast_id: Uuid) -> Result<Option<PodcastFeedState>, Self::Error> {
+        use self::podcast_feed_states::dsl as pfs_dsl;
+        use self::podcast_feed_states::table as pfs_table;
+
+        pfs_table
+            .filter(pfs_dsl::podcast_id.eq(podcast_id.to_string()))
+            .select(PodcastFeedStateEntity::as_select())
+            .first::<PodcastFeedStateEntity>(&mut self.database.connection()?)
+            .optional()
+            .map(|state| state.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    fn list(&self) -> Result<Vec<PodcastFeedState>, Self::Error> {
+        use self::podcast_feed_states::table as pfs_table;
+
+        pfs_table
+            .select(PodcastFeedStateEntity::as_select())
+            .load::<PodcastFeedStateEntity>(&mut self.database.connection()?)
+            .map(|states| states.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    fn upsert(&self, state: PodcastFeedState) -> Result<(), Self::Error> {
+        use self::podcast_feed_states::dsl as pfs_dsl;
+        use self::podcast_feed_states::table as pfs_table;
+
+        let entity = PodcastFeedStateEntity::from(state);
+        let mut conn = self.database.connection()?;
+        let updated = diesel::update(pfs_table.filter(pfs_dsl::podcast_id.eq(&entity.podcast_id)))
+            .set(&entity)
+            .execute(&mut conn)?;
+        if updated == 0 {
+            diesel::insert_into(pfs_table)
+                .values(&entity)
+                .execute(&mut conn)?;
+        }
+        Ok(())
+    }
+
+    fn set_next_refresh_at(
+        &self,
+        podcast_id: Uuid,
+        next_refresh_at: NaiveDateTime,
+    ) -> Result<(), Self::Error> {
+        use self::podcast_feed_states::dsl as pfs_dsl;
+        use self::podcast_feed_states::table as pfs_table;
+
+        let mut conn = self.database.connection()?;
+        let updated =
+            diesel::update(pfs_table.filter(pfs_dsl::podcast_id.eq(podcast_id.to_string())))
+                .set(pfs_dsl::next_refresh_at.eq(Some(next_refresh_at)))
+                .execute(&mut conn)?;
+        if updated == 0 {
+            diesel::insert_into(pfs_table)
+                .values((
+                    pfs_dsl::podcast_id.eq(podcast_id.to_string()),
+                    pfs_dsl::next_refresh_at.eq(Some(next_refresh_at)),
+                    pfs_dsl::updated_at.eq(chrono::Utc::now().naive_utc()),
+                ))
+                .execute(&mut conn)?;
+        }
+        Ok(())
+    }
+
+    fn get_health(&self, podcast_id: Uuid) -> Result<Option<PodcastFeedHealth>, Self::Error> {
+        use self::podcast_feed_states::dsl as pfs_dsl;
+        use self::podcast_feed_states::table as pfs_table;
+
+        pfs_table
+            .filter(pfs_dsl::podcast_id.eq(podcast_id.to_string()))
+            .select(PodcastFeedHealthEntity::as_select())
+            .first::<PodcastFeedHealthEntity>(&mut self.database.connection()?)
+            .optional()
+            .map(|health| health.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    fn list_failing(&self) -> Result<Vec<PodcastFeedHealth>, Self::Error> {
+        use self::podcast_feed_states::dsl as pfs_dsl;
+        use self::podcast_feed_states::table as pfs_table;
+
+        pfs_table
+            .filter(pfs_dsl::consecutive_failures.gt(0))
+            .order(pfs_dsl::failing_since.asc())
+            .select(PodcastFeedHealthEntity::as_select())
+            .load::<PodcastFeedHealthEntity>(&mut self.database.connection()?)
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    fn record_success(
+        &self,
+        podcast_id: Uuid,
+        http_status: Option<i32>,
+        at: NaiveDateTime,
+    ) -> Result<(), Self::Error> {
+        let health = PodcastFeedHealth {
+            podcast_id,
+            last_failure_at: self
+                .get_health(podcast_id)?
+                .and_then(|health| health.last_failure_at),
+            last_success_at: Some(at),
+            consecutive_failures: 0,
+            failing_since: None,
+            last_error: None,
+            last_http_status: http_status,
+        };
+        self.store_health(health, at)
+    }
+
+    fn record_failure(
+        &self,
+        podcast_id: Uuid,
+        error: &str,
+        http_status: Option<i32>,
+        at: NaiveDateTime,
+    ) -> Result<PodcastFeedHealth, Self::Error> {
+        let previous = self.get_health(podcast_id)?;
+        let health = PodcastFeedHealth {
+            podcast_id,
+            last_success_at: previous.as_ref().and_then(|p| p.last_success_at),
+            last_failure_at: Some(at),
+            consecutive_failures: previous
+                .as_ref()
+                .map_or(0, |p| p.consecutive_failures)
+                .saturating_add(1),
+            failing_since: previous.as_ref().and_then(|p| p.failing_since).or(Some(at)),
+            last_error: Some(error.to_string()),
+            last_http_status: http_status,
+        };
+        self.store_health(health.clone(), at)?;
+        Ok(health)
+    }
+
+    fn clear_failures(&self, podcast_id: Uuid) -> Result<(), Self::Error> {
+        use self::podcast_feed_states::dsl as pfs_dsl;
+        use self::podcast_feed_states::table as pfs_table;
+
+        diesel::update(pfs_table.filter(pfs_dsl::podcast_id.eq(podcast_id.to_string())))
+            .set((
+                pfs_dsl::consecutive_failures.eq(0),
+                pfs_dsl::failing_since.eq(None::<NaiveDateTime>),
+            ))
+            .execute(&mut self.database.connection()?)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::db::{database, test_db::setup};
+    use chrono::Timelike;
+
+    // Shares the crate-wide DB test lock (see `crate::db::test_db`).
+
+    mod seed_schema {
+        diesel::table! {
+            podcasts (id) {
+                id -> Text,
+                name -> Text,
+                directory_id -> Text,
+                rssfeed -> Text,
+                image_url -> Text,
+                active -> Bool,
+                original_image_url -> Text,
+                directory_name -> Text,
+            }
+        }
+    }
+
+    #[derive(diesel::Insertable)]
+    #[diesel(table_name = seed_schema::podcasts)]
+    struct SeedPodcast {
+        id: String,
+        name: String,
+        directory_id: String,
+        rssfeed: String,
+        image_url: String,
+        active: bool,
+        original_image_url: String,
+        directory_name: String,
+    }
+
+    fn seed_podcast() -> Uuid {
+        let podcast_id = Uuid::new_v4();
+        let id = podcast_id.to_string();
+        diesel::insert_into(seed_schema::podcasts::table)
+            .values(SeedPodcast {
+                id: id.clone(),
+                name: format!("Test Podcast {id}"),
+                directory_id: Uuid::new_v4().to_string(),
+                rssfeed: format!("https://example.com/feed/{id}.xml"),
+                image_url: "https://example.com/img.png".to_string(),
+                active: true,
+                original_image_url: "https://example.com/img.png".to_string(),
+                directory_name: format!("podcast-{id}"),
+            })
+            .execute(&mut database().connection().expect("db connection"))
+            .expect("seed podcast");
+        podcast_id
+    }
+
+    #[test]
+    fn upsert_inserts_then_replaces_the_state() {
+        let _guard = setup();
+        let repo = DieselPodcastFeedStateRepository::new(database());
+        let podcast_id = seed_podcast();
+        let state = PodcastFeedState {
+            podcast_id,
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            content_hash: Some("abc".to_string()),
+            update_interval_hint: None,
+            next_refresh_at: None,
+            updated_at: chrono::Utc::now().naive_utc(),
+        };
+
+        repo.upsert(state.clone()).unwrap();
+        repo.upsert(PodcastFeedState {
+            etag: None,
+            content_hash: Some("def".to_string()),
+            ..state
+        })
+        .unwrap();
+
+        let stored = repo.get(podcast_id).unwrap().expect("state stored");
+        assert_eq!(stored.etag, None);
+        assert_eq!(stored.content_hash.as_deref(), Some("def"));
+        assert!(repo.get(Uuid::new_v4()).unwrap().is_none());
+    }
+
+    #[test]
+    fn next_refresh_can_be_scheduled_before_the_first_fetch() {
+        let _guard = setup();
+        let repo = DieselPodcastFeedStateRepository::new(database());
+        let podcast_id = seed_podcast();
+        let due = chrono::Utc::now().naive_utc().with_nanosecond(0).unwrap();
+
+        repo.set_next_refresh_at(podcast_id, due).unwrap();
+        let stored = repo.get(podcast_id).unwrap().expect("state created");
+        assert_eq!(stored.next_refresh_at, Some(due));
+        assert_eq!(stored.etag, None);
+
+        let later = due + chrono::Duration::hours(1);
+        repo.set_next_refresh_at(podcast_id, later).unwrap();
+        assert!(
+            repo.list()
+                .unwrap()
+                .iter()
+                .any(|state| state.podcast_id == podcast_id && state.next_refresh_at == Some(later))
+        );
+    }
+
+    #[test]
+    fn failures_form_a_streak_that_a_success_ends() {
+        let _guard = setup();
+        let repo = DieselPodcastFeedStateRepository::new(database());
+        let podcast_id = seed_podcast();
+        let first = chrono::Utc::now().naive_utc().with_nanosecond(0).unwrap();
+        let second = first + chrono::Duration::hours(1);
+
+        repo.record_failure(podcast_id, "timeout", None, first)
+            .unwrap();
+        let health = repo
+            .record_failure(podcast_id, "not found", Some(404), second)
+            .unwrap();
+        assert_eq!(health.consecutive_failures, 2);
+        assert_eq!(health.failing_since, Some(first));
+        assert_eq!(health.last_error.as_deref(), Some("not found"));
+        assert!(
+            repo.list_failing()
+                .unwrap()
+                .iter()
+                .any(|failing| failing.podcast_id == podcast_id)
+        );
+
+        repo.record_success(podcast_id, Some(200), second).unwrap();
+        let health = repo.get_health(podcast_id).unwrap().unwrap();
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.failing_since, None);
+        assert_eq!(health.last_success_at, Some(second));
+        assert_eq!(health.last_failure_at, Some(second));
+        assert_eq!(health.last_http_status, Some(200));
+    }
+
+    #[test]
+    fn storing_validators_keeps_the_health() {
+        let _guard = setup();
+        let repo = DieselPodcastFeedStateRepository::new(database());
+        let podcast_id = seed_podcast();
+        let now = chrono::Utc::now().naive_utc();
+        repo.record_failure(podcast_id, "invalid xml", Some(200), now)
+            .unwrap();
+
+        repo.upsert(PodcastFeedState {
+            podcast_id,
+            etag: Some("\"v2\"".to_string()),
+            last_modified: None,
+            content_hash: None,
+            update_interval_hint: None,
+            next_refresh_at: None,
+            updated_at: now,
+        })
+        .unwrap();
+
+        let health = repo.get_health(podcast_id).unwrap().unwrap();
+        assert_eq!(health.consecutive_failures, 1);
+        repo.clear_failures(podcast_id).unwrap();
+        let health = repo.get_health(podcast_id).unwrap().unwrap();
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.last_error.as_deref(), Some("invalid xml"));
+    }
+}
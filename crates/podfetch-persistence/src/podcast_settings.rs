@@ -25,11 +25,18 @@ diesel::table! {
         nfo_format -> Text,
         cover_filename -> Text,
         auto_transcribe -> Bool,
+        refresh_mode -> Text,
+        refresh_interval -> Integer,
+        retention_keep_newest -> Integer,
+        retention_played_days -> Integer,
+        retention_keep_unplayed -> Bool,
+        retention_max_size_mb -> Integer,
+        transcoding_profile_id -> Nullable<Text>,
     }
 }
 
 #[derive(Debug, Clone, Queryable, Insertable, AsChangeset)]
-#[diesel(table_name = podcast_settings)]
+#[diesel(table_name = podcast_settings, treat_none_as_null = true)]
 struct PodcastSettingEntity {
     podcast_id: String,
     episode_numbering: bool,
@@ -49,6 +56,13 @@ struct PodcastSettingEntity {
     nfo_format: String,
     cover_filename: String,
     auto_transcribe: bool,
+    refresh_mode: String,
+    refresh_interval: i32,
+    retention_keep_newest: i32,
+    retention_played_days: i32,
+    retention_keep_unplayed: bool,
+    retention_max_size_mb: i32,
+    transcoding_profile_id: Option<String>,
 }
 
 impl From<PodcastSettingEntity> for PodcastSetting {
@@ -72,6 +86,13 @@ impl From<PodcastSettingEntity> for PodcastSetting {
             nfo_format: value.nfo_format,
             cover_filename: value.cover_filename,
             auto_transcribe: value.auto_transcribe,
+            refresh_mode: value.refresh_mode,
+            refresh_interval: value.refresh_interval,
+            retention_keep_newest: value.retention_keep_newest,
+            retention_played_days: value.retention_played_days,
+            retention_keep_unplayed: value.retention_keep_unplayed,
+            retention_max_size_mb: value.retention_max_size_mb,
+            transcoding_profile_id: value.transcoding_profile_id,
         }
     }
 }
@@ -97,6 +118,13 @@ impl From<PodcastSetting> for PodcastSettingEntity {
             nfo_format: value.nfo_format,
             cover_filename: value.cover_filename,
             auto_transcribe: value.auto_transcribe,
+            refresh_mode: value.refresh_mode,
+            refresh_interval: value.refresh_interval,
+            retention_keep_newest: value.retention_keep_newest,
+            retention_played_days: value.retention_played_days,
+            retention_keep_unplayed: value.retention_keep_unplayed,
+            retention_max_size_mb: value.retention_max_size_mb,
+            transcoding_profile_id: value.transcoding_profile_id,
         }
     }
 }
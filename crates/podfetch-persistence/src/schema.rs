@@ -94,6 +94,7 @@ diesel::table! {
         message -> Text,
         created_at -> Text,
         status -> Text,
+        user_id -> Nullable<Text>,
     }
 }
 
@@ -125,6 +126,7 @@ diesel::table! {
         id -> Text,
         name -> Text,
         user_id -> Text,
+        smart_query -> Nullable<Text>,
     }
 }
 
@@ -162,6 +164,11 @@ diesel::table! {
         episode_numbering_processed -> Bool,
         download_location -> Nullable<Text>,
         youtube_video_id -> Nullable<Text>,
+        season -> Nullable<Integer>,
+        episode_number -> Nullable<Integer>,
+        episode_type -> Nullable<Text>,
+        loudness_lufs -> Nullable<Double>,
+        loudness_true_peak -> Nullable<Double>,
     }
 }
 
@@ -296,6 +303,16 @@ diesel::table! {
         locked -> Bool,
         duration_mismatch -> Bool,
         fetched_at -> Timestamp,
+        source -> Text,
+    }
+}
+
+diesel::table! {
+    episode_fingerprints (episode_id) {
+        episode_id -> Text,
+        podcast_id -> Text,
+        fingerprint -> Binary,
+        created_at -> Timestamp,
     }
 }
 
@@ -314,6 +331,7 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(episode_fingerprints -> podcast_episodes (episode_id));
 diesel::joinable!(episode_sponsor_segments -> podcast_episodes (episode_id));
 diesel::joinable!(favorite_podcast_episodes -> podcast_episodes (episode_id));
 diesel::joinable!(listening_events -> podcast_episodes (podcast_episode_db_id));
@@ -329,6 +347,7 @@ diesel::joinable!(tags_podcasts -> tags (tag_id));
 diesel::allow_tables_to_appear_in_same_query!(
     device_sync_groups,
     devices,
+    episode_fingerprints,
     episode_sponsor_segments,
     episodes,
     favorite_podcast_episodes,
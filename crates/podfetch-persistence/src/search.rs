@@ -0,0 +1,291 @@
+use crate::db::{DBType, Database, PersistenceError};
+use diesel::sql_types::{BigInt, Bool, Double, Nullable, Text};
+use diesel::{QueryableByName, RunQueryDsl};
+use podfetch_domain::search::{
+    EpisodeSearchHit, PodcastSearchHit, SearchFilter, SearchQuery, SearchRepository, SearchTerm,
+};
+use std::ops::DerefMut;
+use uuid::Uuid;
+
+// The indexes live in `podcasts_fts`/`podcast_episodes_fts` (SQLite FTS5,
+// kept in sync by triggers) and in the generated `text_search` columns on
+// Postgres. Neither is part of the Diesel schema, so every query here is
+// raw SQL per backend, like the transcript search.
+
+/// Same "higher is better" rank convention as the transcript search: the
+/// SQLite queries negate `bm25()`, the Postgres ones use `ts_rank()` as is.
+#[derive(QueryableByName)]
+struct PodcastHitRow {
+    #[diesel(sql_type = Text)]
+    podcast_id: String,
+    #[diesel(sql_type = Text)]
+    name_highlight: String,
+    #[diesel(sql_type = Text)]
+    summary_snippet: String,
+    #[diesel(sql_type = Double)]
+    rank: f64,
+}
+
+impl From<PodcastHitRow> for PodcastSearchHit {
+    fn from(value: PodcastHitRow) -> Self {
+        Self {
+            podcast_id: Uuid::parse_str(&value.podcast_id).expect("valid uuid in db"),
+            name_highlight: value.name_highlight,
+            summary_snippet: value.summary_snippet,
+            rank: value.rank as f32,
+        }
+    }
+}
+
+#[derive(QueryableByName)]
+struct EpisodeHitRow {
+    #[diesel(sql_type = Text)]
+    episode_id: String,
+    #[diesel(sql_type = Text)]
+    podcast_id: String,
+    #[diesel(sql_type = Text)]
+    name_highlight: String,
+    #[diesel(sql_type = Text)]
+    description_snippet: String,
+    #[diesel(sql_type = Double)]
+    rank: f64,
+}
+
+impl From<EpisodeHitRow> for EpisodeSearchHit {
+    fn from(value: EpisodeHitRow) -> Self {
+        Self {
+            episode_id: Uuid::parse_str(&value.episode_id).expect("valid uuid in db"),
+            podcast_id: Uuid::parse_str(&value.podcast_id).expect("valid uuid in db"),
+            name_highlight: value.name_highlight,
+            description_snippet: value.description_snippet,
+            rank: value.rank as f32,
+        }
+    }
+}
+
+/// Renders the query as an FTS5 `MATCH` expression: `"word"*` per prefix
+/// term and `"two words"` per phrase, implicitly ANDed. The parser only
+/// leaves letters and digits, so the quoting can't be broken out of.
+#[cfg(feature = "sqlite")]
+fn to_fts5_match(query: &SearchQuery) -> String {
+    query
+        .terms
+        .iter()
+        .map(|term| match term {
+            SearchTerm::Prefix(word) => format!("\"{word}\"*"),
+            SearchTerm::Phrase(words) => format!("\"{}\"", words.join(" ")),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Renders the query for `to_tsquery`: `'word':*` per prefix term and
+/// `('two' <-> 'words')` per phrase, joined with `&`. `websearch_to_tsquery`
+/// can't express prefixes, hence building the expression here.
+#[cfg(feature = "postgresql")]
+fn to_tsquery(query: &SearchQuery) -> String {
+    query
+        .terms
+        .iter()
+        .map(|term| match term {
+            SearchTerm::Prefix(word) => format!("'{word}':*"),
+            SearchTerm::Phrase(words) => format!(
+                "({})",
+                words
+                    .iter()
+                    .map(|word| format!("'{word}'"))
+                    .collect::<Vec<_>>()
+                    .join(" <-> ")
+            ),
+        })
+        .collect::<Vec<_>>()
+        .join(" & ")
+}
+
+fn format_date(date: Option<chrono::NaiveDate>) -> Option<String> {
+    date.map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+pub struct DieselSearchRepository {
+    database: Database,
+}
+
+impl DieselSearchRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+impl SearchRepository for DieselSearchRepository {
+    type Error = PersistenceError;
+
+    fn search_podcasts(
+        &self,
+        query: &SearchQuery,
+        filter: &SearchFilter,
+        page: i64,
+        page_size: i64,
+    ) -> Result<Vec<PodcastSearchHit>, Self::Error> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.database.connection()?;
+        let podcast_id = filter.podcast_id.map(|id| id.to_string());
+        let tag_id = filter.tag_id.map(|id| id.to_string());
+        let offset = page.saturating_mul(page_size);
+
+        let rows: Vec<PodcastHitRow> = match conn.deref_mut() {
+            #[cfg(feature = "sqlite")]
+            DBType::Sqlite(conn) => diesel::sql_query(
+                "SELECT p.id AS podcast_id, \
+                 highlight(podcasts_fts, 0, '<b>', '</b>') AS name_highlight, \
+                 coalesce(snippet(podcasts_fts, 1, '<b>', '</b>', '…', 24), '') \
+                   AS summary_snippet, \
+                 -bm25(podcasts_fts, 10.0, 2.0, 5.0) AS rank \
+                 FROM podcasts_fts \
+                 JOIN podcasts p ON p.rowid = podcasts_fts.rowid \
+                 WHERE podcasts_fts MATCH ? \
+                   AND (? IS NULL OR p.id = ?) \
+                   AND (? IS NULL OR p.id IN \
+                        (SELECT tp.podcast_id FROM tags_podcasts tp WHERE tp.tag_id = ?)) \
+                 ORDER BY rank DESC LIMIT ? OFFSET ?",
+            )
+            .bind::<Text, _>(to_fts5_match(query))
+            .bind::<Nullable<Text>, _>(podcast_id.clone())
+            .bind::<Nullable<Text>, _>(podcast_id)
+            .bind::<Nullable<Text>, _>(tag_id.clone())
+            .bind::<Nullable<Text>, _>(tag_id)
+            .bind::<BigInt, _>(page_size)
+            .bind::<BigInt, _>(offset)
+            .load::<PodcastHitRow>(conn)?,
+            #[cfg(feature = "postgresql")]
+            DBType::Postgresql(conn) => diesel::sql_query(
+                "SELECT p.id AS podcast_id, \
+                 ts_headline('simple', p.name, to_tsquery('simple', $1), \
+                             'StartSel=<b>,StopSel=</b>,HighlightAll=true') AS name_highlight, \
+                 ts_headline('simple', coalesce(p.summary, ''), to_tsquery('simple', $1), \
+                             'StartSel=<b>,StopSel=</b>,MaxWords=24,MinWords=8') \
+                   AS summary_snippet, \
+                 ts_rank(p.text_search, to_tsquery('simple', $1))::double precision AS rank \
+                 FROM podcasts p \
+                 WHERE p.text_search @@ to_tsquery('simple', $1) \
+                   AND ($2::text IS NULL OR p.id = $2) \
+                   AND ($3::text IS NULL OR p.id IN \
+                        (SELECT tp.podcast_id FROM tags_podcasts tp WHERE tp.tag_id = $3)) \
+                 ORDER BY rank DESC LIMIT $4 OFFSET $5",
+            )
+            .bind::<Text, _>(to_tsquery(query))
+            .bind::<Nullable<Text>, _>(podcast_id)
+            .bind::<Nullable<Text>, _>(tag_id)
+            .bind::<BigInt, _>(page_size)
+            .bind::<BigInt, _>(offset)
+            .load::<PodcastHitRow>(conn)?,
+        };
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    fn search_episodes(
+        &self,
+        query: &SearchQuery,
+        filter: &SearchFilter,
+        page: i64,
+        page_size: i64,
+    ) -> Result<Vec<EpisodeSearchHit>, Self::Error> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.database.connection()?;
+        let podcast_id = filter.podcast_id.map(|id| id.to_string());
+        let tag_id = filter.tag_id.map(|id| id.to_string());
+        // Recording dates are stored as RFC 3339 UTC strings, so the date
+        // part compares correctly as text on both backends.
+        let from = format_date(filter.from);
+        let to = format_date(filter.to);
+        let offset = page.saturating_mul(page_size);
+
+        let rows: Vec<EpisodeHitRow> = match conn.deref_mut() {
+            #[cfg(feature = "sqlite")]
+            DBType::Sqlite(conn) => diesel::sql_query(
+                "SELECT e.id AS episode_id, e.podcast_id AS podcast_id, \
+                 highlight(podcast_episodes_fts, 0, '<b>', '</b>') AS name_highlight, \
+                 snippet(podcast_episodes_fts, 1, '<b>', '</b>', '…', 24) \
+                   AS description_snippet, \
+                 -bm25(podcast_episodes_fts, 10.0, 1.0) AS rank \
+                 FROM podcast_episodes_fts \
+                 JOIN podcast_episodes e ON e.rowid = podcast_episodes_fts.rowid \
+                 WHERE podcast_episodes_fts MATCH ? \
+                   AND (? IS NULL OR e.podcast_id = ?) \
+                   AND (? IS NULL OR e.podcast_id IN \
+                        (SELECT tp.podcast_id FROM tags_podcasts tp WHERE tp.tag_id = ?)) \
+                   AND (? IS NULL OR (e.download_location IS NOT NULL) = ?) \
+                   AND (? IS NULL OR substr(e.date_of_recording, 1, 10) >= ?) \
+                   AND (? IS NULL OR substr(e.date_of_recording, 1, 10) <= ?) \
+                 ORDER BY rank DESC LIMIT ? OFFSET ?",
+            )
+            .bind::<Text, _>(to_fts5_match(query))
+            .bind::<Nullable<Text>, _>(podcast_id.clone())
+            .bind::<Nullable<Text>, _>(podcast_id)
+            .bind::<Nullable<Text>, _>(tag_id.clone())
+            .bind::<Nullable<Text>, _>(tag_id)
+            .bind::<Nullable<Bool>, _>(filter.downloaded)
+            .bind::<Nullable<Bool>, _>(filter.downloaded)
+            .bind::<Nullable<Text>, _>(from.clone())
+            .bind::<Nullable<Text>, _>(from)
+            .bind::<Nullable<Text>, _>(to.clone())
+            .bind::<Nullable<Text>, _>(to)
+            .bind::<BigInt, _>(page_size)
+            .bind::<BigInt, _>(offset)
+            .load::<EpisodeHitRow>(conn)?,
+            #[cfg(feature = "postgresql")]
+            DBType::Postgresql(conn) => diesel::sql_query(
+                "SELECT e.id AS episode_id, e.podcast_id AS podcast_id, \
+                 ts_headline('simple', e.name, to_tsquery('simple', $1), \
+                             'StartSel=<b>,StopSel=</b>,HighlightAll=true') AS name_highlight, \
+                 ts_headline('simple', e.description, to_tsquery('simple', $1), \
+                             'StartSel=<b>,StopSel=</b>,MaxWords=24,MinWords=8') \
+                   AS description_snippet, \
+                 ts_rank(e.text_search, to_tsquery('simple', $1))::double precision AS rank \
+                 FROM podcast_episodes e \
+                 WHERE e.text_search @@ to_tsquery('simple', $1) \
+                   AND ($2::text IS NULL OR e.podcast_id = $2) \
+                   AND ($3::text IS NULL OR e.podcast_id IN \
+                        (SELECT tp.podcast_id FROM tags_podcasts tp WHERE tp.tag_id = $3)) \
+                   AND ($4::boolean IS NULL OR (e.download_location IS NOT NULL) = $4) \
+                   AND ($5::text IS NULL OR substr(e.date_of_recording, 1, 10) >= $5) \
+                   AND ($6::text IS NULL OR substr(e.date_of_recording, 1, 10) <= $6) \
+                 ORDER BY rank DESC LIMIT $7 OFFSET $8",
+            )
+            .bind::<Text, _>(to_tsquery(query))
+            .bind::<Nullable<Text>, _>(podcast_id)
+            .bind::<Nullable<Text>, _>(tag_id)
+            .bind::<Nullable<Bool>, _>(filter.downloaded)
+            .bind::<Nullable<Text>, _>(from)
+            .bind::<Nullable<Text>, _>(to)
+            .bind::<BigInt, _>(page_size)
+            .bind::<BigInt, _>(offset)
+            .load::<EpisodeHitRow>(conn)?,
+        };
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn to_fts5_match_quotes_prefixes_and_phrases() {
+        let query = SearchQuery::parse("rust \"open source\"");
+        assert_eq!(to_fts5_match(&query), "\"rust\"* \"open source\"");
+    }
+
+    #[cfg(feature = "postgresql")]
+    #[test]
+    fn to_tsquery_joins_prefixes_and_phrases() {
+        let query = SearchQuery::parse("rust \"open source\"");
+        assert_eq!(to_tsquery(&query), "'rust':* & ('open' <-> 'source')");
+    }
+}
@@ -26,11 +26,18 @@ diesel::table! {
         sponsorblock_enabled -> Bool,
         nfo_format -> Text,
         cover_filename -> Text,
+        audit_log_retention_days -> Integer,
+        storage_quota_mb -> Integer,
+        storage_quota_policy -> Text,
+        dead_feed_deactivation_days -> Integer,
+        transcoding_profile_id -> Nullable<Text>,
+        loudness_analysis -> Bool,
+        skip_detection -> Bool,
     }
 }
 
 #[derive(Queryable, Insertable, Identifiable, AsChangeset, Debug, Clone)]
-#[diesel(table_name = settings)]
+#[diesel(table_name = settings, treat_none_as_null = true)]
 struct SettingEntity {
     id: String,
     episode_numbering: bool,
@@ -51,6 +58,13 @@ struct SettingEntity {
     sponsorblock_enabled: bool,
     nfo_format: String,
     cover_filename: String,
+    audit_log_retention_days: i32,
+    storage_quota_mb: i32,
+    storage_quota_policy: String,
+    dead_feed_deactivation_days: i32,
+    transcoding_profile_id: Option<String>,
+    loudness_analysis: bool,
+    skip_detection: bool,
 }
 
 impl From<SettingEntity> for Setting {
@@ -75,6 +89,15 @@ impl From<SettingEntity> for Setting {
             sponsorblock_enabled: value.sponsorblock_enabled,
             nfo_format: value.nfo_format,
             cover_filename: value.cover_filename,
+            audit_log_retention_days: value.audit_log_retention_days,
+            storage_quota_mb: value.storage_quota_mb,
+            storage_quota_policy: value.storage_quota_policy,
+            dead_feed_deactivation_days: value.dead_feed_deactivation_days,
+            transcoding_profile_id: value
+                .transcoding_profile_id
+                .and_then(|id| Uuid::parse_str(&id).ok()),
+            loudness_analysis: value.loudness_analysis,
+            skip_detection: value.skip_detection,
         }
     }
 }
@@ -101,6 +124,13 @@ impl From<Setting> for SettingEntity {
             sponsorblock_enabled: value.sponsorblock_enabled,
             nfo_format: value.nfo_format,
             cover_filename: value.cover_filename,
+            audit_log_retention_days: value.audit_log_retention_days,
+            storage_quota_mb: value.storage_quota_mb,
+            storage_quota_policy: value.storage_quota_policy,
+            dead_feed_deactivation_days: value.dead_feed_deactivation_days,
+            transcoding_profile_id: value.transcoding_profile_id.map(|id| id.to_string()),
+            loudness_analysis: value.loudness_analysis,
+            skip_detection: value.skip_detection,
         }
     }
 }
@@ -166,6 +196,12 @@ impl SettingRepository for DieselSettingsRepository {
                 sponsorblock_enabled.eq(true),
                 nfo_format.eq("off"),
                 cover_filename.eq("image"),
+                audit_log_retention_days.eq(365),
+                storage_quota_mb.eq(0),
+                storage_quota_policy.eq("refuse"),
+                dead_feed_deactivation_days.eq(30),
+                loudness_analysis.eq(false),
+                skip_detection.eq(false),
             ))
             .execute(&mut conn)
             .map(|_| ())
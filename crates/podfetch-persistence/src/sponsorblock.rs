@@ -1,5 +1,5 @@
 use crate::db::get_connection;
-use crate::schema::{episode_sponsor_segments, sponsorblock_user_settings};
+use crate::schema::{episode_fingerprints, episode_sponsor_segments, sponsorblock_user_settings};
 use chrono::NaiveDateTime;
 use common_infrastructure::db::PersistenceError;
 use diesel::prelude::*;
@@ -18,6 +18,25 @@ pub struct SponsorSegmentEntity {
     pub locked: bool,
     pub duration_mismatch: bool,
     pub fetched_at: NaiveDateTime,
+    /// Who found the segment: [`SEGMENT_SOURCE_SPONSORBLOCK`] or
+    /// [`SEGMENT_SOURCE_LOCAL`].
+    pub source: String,
+}
+
+/// Segments fetched from the SponsorBlock API.
+pub const SEGMENT_SOURCE_SPONSORBLOCK: &str = "sponsorblock";
+/// Segments PodFetch found itself by analysing the downloaded audio.
+pub const SEGMENT_SOURCE_LOCAL: &str = "local";
+
+/// Audio fingerprint of a downloaded episode, kept to find content that
+/// repeats across the episodes of a podcast.
+#[derive(Queryable, Selectable, Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = episode_fingerprints)]
+pub struct EpisodeFingerprintEntity {
+    pub episode_id: String,
+    pub podcast_id: String,
+    pub fingerprint: Vec<u8>,
+    pub created_at: NaiveDateTime,
 }
 
 #[derive(Queryable, Selectable, Insertable, Identifiable, AsChangeset, Debug, Clone, PartialEq)]
@@ -39,16 +58,22 @@ pub struct SponsorblockUserSettingsEntity {
 pub struct SponsorblockRepository;
 
 impl SponsorblockRepository {
-    /// Idempotently replace ALL stored segments for an episode with `segments`.
+    /// Idempotently replace the stored segments of one `source` for an
+    /// episode with `segments`. Segments of the other source are kept.
     pub fn replace_segments_for_episode(
         episode_id_value: &str,
+        source_value: &str,
         segments: Vec<SponsorSegmentEntity>,
     ) -> Result<(), PersistenceError> {
         use self::episode_sponsor_segments::dsl as s;
         let mut conn = get_connection();
         conn.transaction::<_, diesel::result::Error, _>(|conn| {
-            diesel::delete(s::episode_sponsor_segments.filter(s::episode_id.eq(episode_id_value)))
-                .execute(conn)?;
+            diesel::delete(
+                s::episode_sponsor_segments
+                    .filter(s::episode_id.eq(episode_id_value))
+                    .filter(s::source.eq(source_value)),
+            )
+            .execute(conn)?;
             for segment in &segments {
                 diesel::insert_into(s::episode_sponsor_segments)
                     .values(segment)
@@ -70,6 +95,55 @@ impl SponsorblockRepository {
             .map_err(Into::into)
     }
 
+    pub fn get_segments_for_episode_from(
+        episode_id_value: &str,
+        source_value: &str,
+    ) -> Result<Vec<SponsorSegmentEntity>, PersistenceError> {
+        use self::episode_sponsor_segments::dsl as s;
+        s::episode_sponsor_segments
+            .filter(s::episode_id.eq(episode_id_value))
+            .filter(s::source.eq(source_value))
+            .order(s::start_ms.asc())
+            .load::<SponsorSegmentEntity>(&mut get_connection())
+            .map_err(Into::into)
+    }
+
+    /// Stores the fingerprint of an episode, replacing an earlier one.
+    pub fn upsert_fingerprint(
+        fingerprint: EpisodeFingerprintEntity,
+    ) -> Result<(), PersistenceError> {
+        use self::episode_fingerprints::dsl as f;
+        let mut conn = get_connection();
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::delete(
+                f::episode_fingerprints.filter(f::episode_id.eq(&fingerprint.episode_id)),
+            )
+            .execute(conn)?;
+            diesel::insert_into(f::episode_fingerprints)
+                .values(&fingerprint)
+                .execute(conn)?;
+            Ok(())
+        })
+        .map_err(Into::into)
+    }
+
+    /// The `limit` most recently analysed episodes of a podcast other than
+    /// `excluded_episode_id`.
+    pub fn get_recent_fingerprints(
+        podcast_id_value: &str,
+        excluded_episode_id: &str,
+        limit: i64,
+    ) -> Result<Vec<EpisodeFingerprintEntity>, PersistenceError> {
+        use self::episode_fingerprints::dsl as f;
+        f::episode_fingerprints
+            .filter(f::podcast_id.eq(podcast_id_value))
+            .filter(f::episode_id.ne(excluded_episode_id))
+            .order(f::created_at.desc())
+            .limit(limit)
+            .load::<EpisodeFingerprintEntity>(&mut get_connection())
+            .map_err(Into::into)
+    }
+
     pub fn get_user_settings(
         user_id_value: &str,
     ) -> Result<Option<SponsorblockUserSettingsEntity>, PersistenceError> {
@@ -281,6 +355,7 @@ mod sponsorblock_tests {
             locked: false,
             duration_mismatch: false,
             fetched_at: chrono::Utc::now().naive_utc(),
+            source: SEGMENT_SOURCE_SPONSORBLOCK.to_string(),
         }
     }
 
@@ -313,13 +388,18 @@ mod sponsorblock_tests {
         // First replace
         SponsorblockRepository::replace_segments_for_episode(
             &episode_id,
+            SEGMENT_SOURCE_SPONSORBLOCK,
             vec![seg1.clone(), seg2.clone()],
         )
         .expect("first replace");
 
         // Second replace with same data
-        SponsorblockRepository::replace_segments_for_episode(&episode_id, vec![seg1, seg2])
-            .expect("second replace");
+        SponsorblockRepository::replace_segments_for_episode(
+            &episode_id,
+            SEGMENT_SOURCE_SPONSORBLOCK,
+            vec![seg1, seg2],
+        )
+        .expect("second replace");
 
         let result =
             SponsorblockRepository::get_segments_for_episode(&episode_id).expect("get segments");
@@ -345,13 +425,21 @@ mod sponsorblock_tests {
             make_segment(&episode_id, 300, 400),
             make_segment(&episode_id, 500, 600),
         ];
-        SponsorblockRepository::replace_segments_for_episode(&episode_id, segs3)
-            .expect("replace 3");
+        SponsorblockRepository::replace_segments_for_episode(
+            &episode_id,
+            SEGMENT_SOURCE_SPONSORBLOCK,
+            segs3,
+        )
+        .expect("replace 3");
 
         // Replace with 1 segment
         let seg_single = make_segment(&episode_id, 999, 1999);
-        SponsorblockRepository::replace_segments_for_episode(&episode_id, vec![seg_single])
-            .expect("replace 1");
+        SponsorblockRepository::replace_segments_for_episode(
+            &episode_id,
+            SEGMENT_SOURCE_SPONSORBLOCK,
+            vec![seg_single],
+        )
+        .expect("replace 1");
 
         let result =
             SponsorblockRepository::get_segments_for_episode(&episode_id).expect("get segments");
@@ -360,6 +448,65 @@ mod sponsorblock_tests {
         assert_eq!(result[0].start_ms, 999);
     }
 
+    #[test]
+    fn replace_keeps_segments_of_the_other_source() {
+        let _guard = setup();
+        let podcast_id = seed_podcast();
+        let episode_id = seed_episode(&podcast_id);
+
+        SponsorblockRepository::replace_segments_for_episode(
+            &episode_id,
+            SEGMENT_SOURCE_SPONSORBLOCK,
+            vec![make_segment(&episode_id, 100, 200)],
+        )
+        .expect("replace sponsorblock");
+        let local = SponsorSegmentEntity {
+            source: SEGMENT_SOURCE_LOCAL.to_string(),
+            ..make_segment(&episode_id, 5000, 9000)
+        };
+        SponsorblockRepository::replace_segments_for_episode(
+            &episode_id,
+            SEGMENT_SOURCE_LOCAL,
+            vec![local],
+        )
+        .expect("replace local");
+        SponsorblockRepository::replace_segments_for_episode(
+            &episode_id,
+            SEGMENT_SOURCE_SPONSORBLOCK,
+            vec![],
+        )
+        .expect("clear sponsorblock");
+
+        let result =
+            SponsorblockRepository::get_segments_for_episode(&episode_id).expect("get segments");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].source, SEGMENT_SOURCE_LOCAL);
+        assert_eq!(result[0].start_ms, 5000);
+    }
+
+    #[test]
+    fn recent_fingerprints_skip_the_analysed_episode() {
+        let _guard = setup();
+        let podcast_id = seed_podcast();
+        let first = seed_episode(&podcast_id);
+        let second = seed_episode(&podcast_id);
+        for (episode_id, bytes) in [(&first, vec![1u8, 2, 3, 4]), (&second, vec![5u8, 6, 7, 8])] {
+            SponsorblockRepository::upsert_fingerprint(EpisodeFingerprintEntity {
+                episode_id: episode_id.clone(),
+                podcast_id: podcast_id.clone(),
+                fingerprint: bytes,
+                created_at: chrono::Utc::now().naive_utc(),
+            })
+            .expect("upsert fingerprint");
+        }
+
+        let others = SponsorblockRepository::get_recent_fingerprints(&podcast_id, &second, 5)
+            .expect("recent fingerprints");
+        assert_eq!(others.len(), 1);
+        assert_eq!(others[0].episode_id, first);
+        assert_eq!(others[0].fingerprint, vec![1u8, 2, 3, 4]);
+    }
+
     #[test]
     fn user_settings_upsert_creates_then_updates() {
         let _guard = setup();
@@ -0,0 +1,178 @@
+use crate::db::{Database, PersistenceError};
+use chrono::NaiveDateTime;
+use diesel::prelude::{AsChangeset, Insertable, Queryable, Selectable};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use podfetch_domain::storage_usage::{EpisodeStorage, StorageUsageRepository, UserStorageQuota};
+use uuid::Uuid;
+
+diesel::table! {
+    episode_storage (episode_id) {
+        episode_id -> Text,
+        podcast_id -> Text,
+        bytes -> BigInt,
+        recorded_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    user_storage_quotas (user_id) {
+        user_id -> Text,
+        quota_mb -> Integer,
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Clone)]
+#[diesel(table_name = episode_storage)]
+struct EpisodeStorageEntity {
+    episode_id: String,
+    podcast_id: String,
+    bytes: i64,
+    recorded_at: NaiveDateTime,
+}
+
+impl From<EpisodeStorageEntity> for EpisodeStorage {
+    fn from(value: EpisodeStorageEntity) -> Self {
+        Self {
+            episode_id: Uuid::parse_str(&value.episode_id).expect("valid uuid in db"),
+            podcast_id: Uuid::parse_str(&value.podcast_id).expect("valid uuid in db"),
+            bytes: value.bytes,
+            recorded_at: value.recorded_at,
+        }
+    }
+}
+
+impl From<EpisodeStorage> for EpisodeStorageEntity {
+    fn from(value: EpisodeStorage) -> Self {
+        Self {
+            episode_id: value.episode_id.to_string(),
+            podcast_id: value.podcast_id.to_string(),
+            bytes: value.bytes,
+            recorded_at: value.recorded_at,
+        }
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Clone)]
+#[diesel(table_name = user_storage_quotas)]
+struct UserStorageQuotaEntity {
+    user_id: String,
+    quota_mb: i32,
+}
+
+impl From<UserStorageQuotaEntity> for UserStorageQuota {
+    fn from(value: UserStorageQuotaEntity) -> Self {
+        Self {
+            user_id: Uuid::parse_str(&value.user_id).expect("valid uuid in db"),
+            quota_mb: value.quota_mb,
+        }
+    }
+}
+
+impl From<UserStorageQuota> for UserStorageQuotaEntity {
+    fn from(value: UserStorageQuota) -> Self {
+        Self {
+            user_id: value.user_id.to_string(),
+            quota_mb: value.quota_mb,
+        }
+    }
+}
+
+pub struct DieselStorageUsageRepository {
+    database: Database,
+}
+
+impl DieselStorageUsageRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+impl StorageUsageRepository for DieselStorageUsageRepository {
+    type Error = PersistenceError;
+
+    fn upsert(&self, storage: EpisodeStorage) -> Result<(), Self::Error> {
+        use self::episode_storage::dsl as es_dsl;
+        use self::episode_storage::table as es_table;
+
+        let mut conn = self.database.connection()?;
+        let entity = EpisodeStorageEntity::from(storage);
+        let updated = diesel::update(es_table.filter(es_dsl::episode_id.eq(&entity.episode_id)))
+            .set(&entity)
+            .execute(&mut conn)?;
+        if updated == 0 {
+            diesel::insert_into(es_table)
+                .values(&entity)
+                .execute(&mut conn)?;
+        }
+        Ok(())
+    }
+
+    fn delete_by_episode_id(&self, episode_id: Uuid) -> Result<(), Self::Error> {
+        use self::episode_storage::dsl as es_dsl;
+        use self::episode_storage::table as es_table;
+
+        diesel::delete(es_table.filter(es_dsl::episode_id.eq(episode_id.to_string())))
+            .execute(&mut self.database.connection()?)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    fn find_all(&self) -> Result<Vec<EpisodeStorage>, Self::Error> {
+        use self::episode_storage::dsl as es_dsl;
+        use self::episode_storage::table as es_table;
+
+        es_table
+            .order((es_dsl::recorded_at.asc(), es_dsl::episode_id.asc()))
+            .load::<EpisodeStorageEntity>(&mut self.database.connection()?)
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    fn find_quota(&self, user_id: Uuid) -> Result<Option<UserStorageQuota>, Self::Error> {
+        use self::user_storage_quotas::dsl as usq_dsl;
+        use self::user_storage_quotas::table as usq_table;
+
+        usq_table
+            .filter(usq_dsl::user_id.eq(user_id.to_string()))
+            .first::<UserStorageQuotaEntity>(&mut self.database.connection()?)
+            .optional()
+            .map(|quota| quota.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    fn find_all_quotas(&self) -> Result<Vec<UserStorageQuota>, Self::Error> {
+        use self::user_storage_quotas::table as usq_table;
+
+        usq_table
+            .load::<UserStorageQuotaEntity>(&mut self.database.connection()?)
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    fn save_quota(&self, quota: UserStorageQuota) -> Result<(), Self::Error> {
+        use self::user_storage_quotas::dsl as usq_dsl;
+        use self::user_storage_quotas::table as usq_table;
+
+        let mut conn = self.database.connection()?;
+        let entity = UserStorageQuotaEntity::from(quota);
+        let updated = diesel::update(usq_table.filter(usq_dsl::user_id.eq(&entity.user_id)))
+            .set(&entity)
+            .execute(&mut conn)?;
+        if updated == 0 {
+            diesel::insert_into(usq_table)
+                .values(&entity)
+                .execute(&mut conn)?;
+        }
+        Ok(())
+    }
+
+    fn delete_quota(&self, user_id: Uuid) -> Result<(), Self::Error> {
+        use self::user_storage_quotas::dsl as usq_dsl;
+        use self::user_storage_quotas::table as usq_table;
+
+        diesel::delete(usq_table.filter(usq_dsl::user_id.eq(user_id.to_string())))
+            .execute(&mut self.database.connection()?)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+}
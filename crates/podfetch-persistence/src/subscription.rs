@@ -280,6 +280,28 @@ impl SubscriptionRepository for DieselSubscriptionRepository {
             .map_err(Into::into)
     }
 
+    fn find_subscriber_ids(&self, podcast_id: Uuid) -> Result<Vec<Uuid>, Self::Error> {
+        use self::podcasts::dsl as podcasts_dsl;
+        use self::subscriptions::dsl as subscriptions_dsl;
+
+        let user_ids = subscriptions_dsl::subscriptions
+            .inner_join(
+                podcasts_dsl::podcasts.on(subscriptions_dsl::podcast.eq(podcasts_dsl::rssfeed)),
+            )
+            .filter(
+                podcasts_dsl::id
+                    .eq(podcast_id.to_string())
+                    .and(subscriptions_dsl::deleted.is_null()),
+            )
+            .select(subscriptions_dsl::user_id)
+            .distinct()
+            .load::<String>(&mut self.database.connection()?)?;
+        Ok(user_ids
+            .iter()
+            .filter_map(|user_id| Uuid::parse_str(user_id).ok())
+            .collect())
+    }
+
     fn get_available_gpodder_podcasts(&self) -> Result<Vec<GPodderAvailablePodcast>, Self::Error> {
         use self::podcasts::dsl as podcasts_dsl;
         use self::subscriptions::dsl as subscriptions_dsl;
@@ -146,6 +146,28 @@ impl TagRepository for DieselTagRepository {
             .map_err(Into::into)
     }
 
+    fn get_podcast_ids_of_tag(
+        &self,
+        tag_id_to_find: &str,
+        user_id_to_find: Uuid,
+    ) -> Result<Vec<Uuid>, Self::Error> {
+        use self::tags::dsl as tags_dsl;
+        use self::tags_podcasts::dsl as tags_podcasts_dsl;
+
+        tags_podcasts::table
+            .inner_join(tags::table.on(tags_dsl::id.eq(tags_podcasts_dsl::tag_id)))
+            .filter(tags_podcasts_dsl::tag_id.eq(tag_id_to_find))
+            .filter(tags_dsl::user_id.eq(user_id_to_find.to_string()))
+            .select(tags_podcasts_dsl::podcast_id)
+            .load::<String>(&mut self.database.connection()?)
+            .map(|ids| {
+                ids.iter()
+                    .map(|id| Uuid::parse_str(id).expect("valid uuid in db"))
+                    .collect()
+            })
+            .map_err(Into::into)
+    }
+
     fn update(&self, tag_id: &str, update: TagUpdate) -> Result<Tag, Self::Error> {
         use self::tags::dsl as tags_dsl;
 
@@ -0,0 +1,206 @@
+use crate::db::{Database, PersistenceError};
+use chrono::NaiveDateTime;
+use diesel::prelude::{AsChangeset, Insertable, Queryable, Selectable};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use podfetch_domain::transcoding_profile::{
+    AudioCodec, TranscodingProfile, TranscodingProfileRepository,
+};
+use uuid::Uuid;
+
+diesel::table! {
+    transcoding_profiles (id) {
+        id -> Text,
+        name -> Text,
+        codec -> Text,
+        bitrate_kbps -> Integer,
+        channels -> Integer,
+        sample_rate -> Integer,
+        loudnorm -> Bool,
+        trim_silence -> Bool,
+        keep_original -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Clone)]
+#[diesel(table_name = transcoding_profiles)]
+struct TranscodingProfileEntity {
+    id: String,
+    name: String,
+    codec: String,
+    bitrate_kbps: i32,
+    channels: i32,
+    sample_rate: i32,
+    loudnorm: bool,
+    trim_silence: bool,
+    keep_original: bool,
+    created_at: NaiveDateTime,
+}
+
+impl From<TranscodingProfileEntity> for TranscodingProfile {
+    fn from(value: TranscodingProfileEntity) -> Self {
+        Self {
+            id: Uuid::parse_str(&value.id).expect("valid uuid in db"),
+            name: value.name,
+            codec: AudioCodec::from_str(&value.codec).expect("valid audio codec in db"),
+            bitrate_kbps: value.bitrate_kbps,
+            channels: value.channels,
+            sample_rate: value.sample_rate,
+            loudnorm: value.loudnorm,
+            trim_silence: value.trim_silence,
+            keep_original: value.keep_original,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<TranscodingProfile> for TranscodingProfileEntity {
+    fn from(value: TranscodingProfile) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            codec: value.codec.as_str().to_string(),
+            bitrate_kbps: value.bitrate_kbps,
+            channels: value.channels,
+            sample_rate: value.sample_rate,
+            loudnorm: value.loudnorm,
+            trim_silence: value.trim_silence,
+            keep_original: value.keep_original,
+            created_at: value.created_at,
+        }
+    }
+}
+
+pub struct DieselTranscodingProfileRepository {
+    database: Database,
+}
+
+impl DieselTranscodingProfileRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+impl TranscodingProfileRepository for DieselTranscodingProfileRepository {
+    type Error = PersistenceError;
+
+    fn create(&self, profile: TranscodingProfile) -> Result<TranscodingProfile, Self::Error> {
+        use self::transcoding_profiles::table as tp_table;
+
+        let entity = TranscodingProfileEntity::from(profile);
+        diesel::insert_into(tp_table)
+            .values(&entity)
+            .execute(&mut self.database.connection()?)?;
+        Ok(entity.into())
+    }
+
+    fn update(
+        &self,
+        profile: TranscodingProfile,
+    ) -> Result<Option<TranscodingProfile>, Self::Error> {
+        use self::transcoding_profiles::dsl as tp_dsl;
+        use self::transcoding_profiles::table as tp_table;
+
+        let entity = TranscodingProfileEntity::from(profile);
+        let updated = diesel::update(tp_table.filter(tp_dsl::id.eq(&entity.id)))
+            .set((
+                tp_dsl::name.eq(&entity.name),
+                tp_dsl::codec.eq(&entity.codec),
+                tp_dsl::bitrate_kbps.eq(entity.bitrate_kbps),
+                tp_dsl::channels.eq(entity.channels),
+                tp_dsl::sample_rate.eq(entity.sample_rate),
+                tp_dsl::loudnorm.eq(entity.loudnorm),
+                tp_dsl::trim_silence.eq(entity.trim_silence),
+                tp_dsl::keep_original.eq(entity.keep_original),
+            ))
+            .execute(&mut self.database.connection()?)?;
+        if updated == 0 {
+            return Ok(None);
+        }
+        self.get(Uuid::parse_str(&entity.id).expect("valid uuid"))
+    }
+
+    fn delete(&self, id: Uuid) -> Result<bool, Self::Error> {
+        use self::transcoding_profiles::dsl as tp_dsl;
+        use self::transcoding_profiles::table as tp_table;
+
+        diesel::delete(tp_table.filter(tp_dsl::id.eq(id.to_string())))
+            .execute(&mut self.database.connection()?)
+            .map(|deleted| deleted > 0)
+            .map_err(Into::into)
+    }
+
+    fn get(&self, id: Uuid) -> Result<Option<TranscodingProfile>, Self::Error> {
+        use self::transcoding_profiles::dsl as tp_dsl;
+        use self::transcoding_profiles::table as tp_table;
+
+        tp_table
+            .filter(tp_dsl::id.eq(id.to_string()))
+            .first::<TranscodingProfileEntity>(&mut self.database.connection()?)
+            .optional()
+            .map(|profile| profile.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    fn list(&self) -> Result<Vec<TranscodingProfile>, Self::Error> {
+        use self::transcoding_profiles::dsl as tp_dsl;
+        use self::transcoding_profiles::table as tp_table;
+
+        tp_table
+            .order(tp_dsl::created_at.asc())
+            .load::<TranscodingProfileEntity>(&mut self.database.connection()?)
+            .map(|profiles| profiles.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::db::{database, test_db::setup};
+
+    fn profile() -> TranscodingProfile {
+        TranscodingProfile {
+            id: Uuid::new_v4(),
+            name: "Speech mono".to_string(),
+            codec: AudioCodec::Opus,
+            bitrate_kbps: 32,
+            channels: 1,
+            sample_rate: 0,
+            loudnorm: true,
+            trim_silence: false,
+            keep_original: false,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn profiles_round_trip() {
+        let _guard = setup();
+        let repo = DieselTranscodingProfileRepository::new(database());
+        let created = repo.create(profile()).unwrap();
+
+        let stored = repo.get(created.id).unwrap().expect("profile stored");
+        assert_eq!(stored.codec, AudioCodec::Opus);
+        assert!(stored.loudnorm);
+        assert!(repo.list().unwrap().iter().any(|p| p.id == created.id));
+
+        let updated = repo
+            .update(TranscodingProfile {
+                codec: AudioCodec::Aac,
+                keep_original: true,
+                ..created.clone()
+            })
+            .unwrap()
+            .expect("profile updated");
+        assert_eq!(updated.codec, AudioCodec::Aac);
+        assert!(updated.keep_original);
+
+        assert!(repo.delete(created.id).unwrap());
+        assert!(repo.get(created.id).unwrap().is_none());
+        assert!(
+            repo.update(profile()).unwrap().is_none(),
+            "updating an unknown profile finds nothing"
+        );
+    }
+}
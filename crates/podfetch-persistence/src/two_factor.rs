@@ -0,0 +1,148 @@
+use crate::db::{Database, PersistenceError};
+use chrono::NaiveDateTime;
+use diesel::prelude::{AsChangeset, Insertable, Queryable, Selectable};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use podfetch_domain::two_factor::{TwoFactorAuth, TwoFactorRepository};
+use uuid::Uuid;
+
+diesel::table! {
+    two_factor_auth (user_id) {
+        user_id -> Text,
+        secret -> Text,
+        enabled -> Bool,
+        recovery_codes -> Text,
+        last_used_step -> Nullable<BigInt>,
+        created_at -> Timestamp,
+        failed_attempts -> Integer,
+        locked_until -> Nullable<Timestamp>,
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, AsChangeset, Clone)]
+#[diesel(table_name = two_factor_auth, treat_none_as_null = true)]
+struct TwoFactorAuthEntity {
+    user_id: String,
+    secret: String,
+    enabled: bool,
+    /// Comma separated digests.
+    recovery_codes: String,
+    last_used_step: Option<i64>,
+    created_at: NaiveDateTime,
+    failed_attempts: i32,
+    locked_until: Option<NaiveDateTime>,
+}
+
+impl From<TwoFactorAuthEntity> for TwoFactorAuth {
+    fn from(value: TwoFactorAuthEntity) -> Self {
+        Self {
+            user_id: Uuid::parse_str(&value.user_id).expect("valid uuid in db"),
+            secret: value.secret,
+            enabled: value.enabled,
+            recovery_codes: value
+                .recovery_codes
+                .split(',')
+                .filter(|code| !code.is_empty())
+                .map(str::to_string)
+                .collect(),
+            last_used_step: value.last_used_step,
+            failed_attempts: value.failed_attempts,
+            locked_until: value.locked_until,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<TwoFactorAuth> for TwoFactorAuthEntity {
+    fn from(value: TwoFactorAuth) -> Self {
+        Self {
+            user_id: value.user_id.to_string(),
+            secret: value.secret,
+            enabled: value.enabled,
+            recovery_codes: value.recovery_codes.join(","),
+            last_used_step: value.last_used_step,
+            created_at: value.created_at,
+            failed_attempts: value.failed_attempts,
+            locked_until: value.locked_until,
+        }
+    }
+}
+
+pub struct DieselTwoFactorRepository {
+    database: Database,
+}
+
+impl DieselTwoFactorRepository {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+}
+
+impl TwoFactorRepository for DieselTwoFactorRepository {
+    type Error = PersistenceError;
+
+    fn find_by_user_id(&self, user_id: Uuid) -> Result<Option<TwoFactorAuth>, Self::Error> {
+        use self::two_factor_auth::dsl as tf_dsl;
+        use self::two_factor_auth::table as tf_table;
+
+        tf_table
+            .filter(tf_dsl::user_id.eq(user_id.to_string()))
+            .first::<TwoFactorAuthEntity>(&mut self.database.connection()?)
+            .optional()
+            .map(|two_factor| two_factor.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    fn save(&self, two_factor: TwoFactorAuth) -> Result<TwoFactorAuth, Self::Error> {
+        use self::two_factor_auth::dsl as tf_dsl;
+        use self::two_factor_auth::table as tf_table;
+
+        let mut conn = self.database.connection()?;
+        let entity = TwoFactorAuthEntity::from(two_factor);
+        let updated = diesel::update(tf_table.filter(tf_dsl::user_id.eq(&entity.user_id)))
+            .set(&entity)
+            .execute(&mut conn)?;
+        if updated == 0 {
+            diesel::insert_into(tf_table)
+                .values(&entity)
+                .execute(&mut conn)?;
+        }
+        Ok(entity.into())
+    }
+
+    fn replace(
+        &self,
+        current: &TwoFactorAuth,
+        updated: TwoFactorAuth,
+    ) -> Result<bool, Self::Error> {
+        use self::two_factor_auth::dsl as tf_dsl;
+        use self::two_factor_auth::table as tf_table;
+
+        let current = TwoFactorAuthEntity::from(current.clone());
+        let entity = TwoFactorAuthEntity::from(updated);
+        // Everything a code check changes must still be as it was read.
+        let unchanged = tf_table
+            .filter(tf_dsl::user_id.eq(current.user_id))
+            .filter(tf_dsl::recovery_codes.eq(current.recovery_codes))
+            .filter(tf_dsl::failed_attempts.eq(current.failed_attempts));
+        let mut conn = self.database.connection()?;
+        let replaced = match current.last_used_step {
+            Some(step) => diesel::update(unchanged.filter(tf_dsl::last_used_step.eq(step)))
+                .set(&entity)
+                .execute(&mut conn)?,
+            None => diesel::update(unchanged.filter(tf_dsl::last_used_step.is_null()))
+                .set(&entity)
+                .execute(&mut conn)?,
+        };
+        Ok(replaced > 0)
+    }
+
+    fn delete_by_user_id(&self, user_id: Uuid) -> Result<bool, Self::Error> {
+        use self::two_factor_auth::dsl as tf_dsl;
+        use self::two_factor_auth::table as tf_table;
+
+        diesel::delete(tf_table.filter(tf_dsl::user_id.eq(user_id.to_string())))
+            .execute(&mut self.database.connection()?)
+            .map(|deleted| deleted > 0)
+            .map_err(Into::into)
+    }
+}
@@ -147,6 +147,18 @@ impl UserAdminRepository for DieselUserAdminRepository {
             .map_err(Into::into)
     }
 
+    fn find_by_id(&self, id_to_find: Uuid) -> Result<Option<ManagedUser>, Self::Error> {
+        use self::users::dsl::*;
+
+        let mut conn = self.database.connection()?;
+        users
+            .filter(id.eq(id_to_find.to_string()))
+            .first::<UserEntity>(&mut conn)
+            .optional()
+            .map(|user| user.map(Into::into))
+            .map_err(Into::into)
+    }
+
     fn find_all(&self) -> Result<Vec<ManagedUser>, Self::Error> {
         use self::users::dsl::*;
 
@@ -51,6 +51,30 @@ pub fn determine_file_extension(
         })
 }
 
+/// Resolves the extension of a streamed download without buffering the body:
+/// the URL suffix wins, then the sniffed leading bytes, then the response's
+/// `Content-Type`.
+pub fn determine_stream_extension(
+    url: &str,
+    content_type: Option<&str>,
+    prefix: &[u8],
+    file_type: FileType,
+) -> String {
+    if let Some(suffix) = get_suffix_by_url(url).ok().filter(|s| !s.is_empty()) {
+        return suffix;
+    }
+    let format = FileFormat::from(prefix);
+    if !matches!(format, FileFormat::Empty | FileFormat::ArbitraryBinaryData) {
+        let sniffed = format.to_string().to_lowercase();
+        if is_valid_extension(&sniffed) {
+            return sniffed;
+        }
+    }
+    content_type
+        .and_then(map_content_type_to_extension)
+        .unwrap_or_else(|| file_type.to_string())
+}
+
 fn map_content_type_to_extension(content_type: &str) -> Option<String> {
     let mime_type = content_type
         .split(';')
@@ -103,4 +127,26 @@ mod tests {
             assert_eq!(super::get_suffix_by_url(url).unwrap(), URL_EXTENSIONS[idx]);
         }
     }
+
+    #[test]
+    fn stream_extension_prefers_url_suffix() {
+        let ext = super::determine_stream_extension(
+            "http://www.contoso.com/test.m4a?x=1",
+            Some("audio/mpeg"),
+            b"ID3\x04\x00",
+            super::FileType::Audio,
+        );
+        assert_eq!(ext, "m4a");
+    }
+
+    #[test]
+    fn stream_extension_falls_back_to_content_type() {
+        let ext = super::determine_stream_extension(
+            "http://www.contoso.com/test",
+            Some("audio/mpeg; charset=binary"),
+            b"",
+            super::FileType::Audio,
+        );
+        assert_eq!(ext, "mp3");
+    }
 }
@@ -5,6 +5,7 @@ use common_infrastructure::error::ErrorSeverity::Critical;
 use common_infrastructure::error::{CustomError, CustomErrorInner, map_io_error};
 use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
 use std::future::Future;
+use std::io::{Read, Write};
 use std::pin::Pin;
 
 /// Represents a podcast episode's file information needed for cleanup.
@@ -53,6 +54,46 @@ impl FileHandleWrapper {
         }
     }
 
+    /// Streams `reader` into the configured storage backend and returns the
+    /// number of bytes written.
+    pub fn write_stream(
+        path: &str,
+        reader: &mut dyn Read,
+        download_location: &FileHandlerType,
+    ) -> Result<u64, CustomError> {
+        match download_location {
+            FileHandlerType::Local => {
+                LocalStorageBackend::write_stream(path, reader).map_err(Self::map_storage_error)
+            }
+            FileHandlerType::S3 => Self::s3_backend()
+                .write_stream(path, reader)
+                .map_err(Self::map_storage_error),
+        }
+    }
+
+    /// Streams `reader` after the first `offset` bytes of an earlier,
+    /// interrupted attempt. Only the local backend keeps partial files, so S3
+    /// can only start from the beginning.
+    pub fn write_stream_from(
+        path: &str,
+        reader: &mut dyn Read,
+        offset: u64,
+        download_location: &FileHandlerType,
+    ) -> Result<u64, CustomError> {
+        match download_location {
+            FileHandlerType::Local => LocalStorageBackend::write_stream_from(path, reader, offset)
+                .map_err(Self::map_storage_error),
+            FileHandlerType::S3 if offset == 0 => {
+                Self::write_stream(path, reader, download_location)
+            }
+            FileHandlerType::S3 => Err(CustomErrorInner::Conflict(
+                "resuming a download is not supported for the S3 backend".to_string(),
+                Critical,
+            )
+            .into()),
+        }
+    }
+
     pub fn write_file_async<'a>(
         path: &'a str,
         content: &'a mut [u8],
@@ -94,6 +135,30 @@ impl FileHandleWrapper {
         }
     }
 
+    /// Size of the stored file in bytes, or `None` when it can't be found.
+    pub fn file_size(path: &str, download_location: &FileHandlerType) -> Option<u64> {
+        match download_location {
+            FileHandlerType::Local => LocalStorageBackend::file_size(path),
+            FileHandlerType::S3 => Self::s3_backend().file_size(path),
+        }
+    }
+
+    /// Copies a stored file into `writer`, e.g. to put it into a backup.
+    pub fn read_stream(
+        path: &str,
+        writer: &mut (dyn Write + Send),
+        download_location: &FileHandlerType,
+    ) -> Result<(), CustomError> {
+        match download_location {
+            FileHandlerType::Local => LocalStorageBackend::read_stream(path, writer)
+                .map(|_| ())
+                .map_err(Self::map_storage_error),
+            FileHandlerType::S3 => Self::s3_backend()
+                .read_stream(path, writer)
+                .map_err(Self::map_storage_error),
+        }
+    }
+
     pub fn remove_dir(
         podcast: &PodcastFileInfo,
         episodes: &[EpisodeFileInfo],
@@ -11,7 +11,9 @@ pub mod s3;
 pub mod sanitizer;
 
 pub use error::StorageError;
-pub use file_extension::{DetermineFileExtensionReturn, determine_file_extension};
+pub use file_extension::{
+    DetermineFileExtensionReturn, determine_file_extension, determine_stream_extension,
+};
 pub use file_handle_wrapper::{EpisodeFileInfo, FileHandleWrapper, PodcastFileInfo};
 pub use file_handler::resolve_file_handler_type;
 pub use file_request::FileRequest;
@@ -1,7 +1,8 @@
 use crate::StorageError;
-use std::fs::File;
+use std::fs::{File, OpenOptions};
 use std::future::Future;
 use std::io;
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::pin::Pin;
 
 #[derive(Clone, Default)]
@@ -27,6 +28,59 @@ impl LocalStorageBackend {
         Ok(())
     }
 
+    /// Copies `reader` into `file_path` in fixed-size chunks. The data lands in
+    /// a `.part` file next to the destination first and is only renamed into
+    /// place once the stream ended cleanly, so a broken download never leaves
+    /// a truncated episode behind.
+    pub fn write_stream(file_path: &str, reader: &mut dyn Read) -> Result<u64, StorageError> {
+        Self::write_stream_from(file_path, reader, 0).inspect_err(|_| {
+            let _ = std::fs::remove_file(Self::part_path(file_path));
+        })
+    }
+
+    /// Like [`Self::write_stream`], but appends to the `.part` file after its
+    /// first `offset` bytes, and keeps the partial file when the stream
+    /// breaks so a later attempt can pick up where this one stopped. Returns
+    /// the total size of the finished file.
+    pub fn write_stream_from(
+        file_path: &str,
+        reader: &mut dyn Read,
+        offset: u64,
+    ) -> Result<u64, StorageError> {
+        let map_err = |source| StorageError::Io {
+            path: file_path.to_string(),
+            source,
+        };
+        let part_path = Self::part_path(file_path);
+        let mut part_file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(offset == 0)
+            .open(&part_path)
+            .map_err(map_err)?;
+        // Anything past `offset` was never confirmed as received.
+        part_file.set_len(offset).map_err(map_err)?;
+        part_file.seek(SeekFrom::End(0)).map_err(map_err)?;
+
+        let written = io::copy(reader, &mut part_file).map_err(map_err)?;
+        part_file.sync_all().map_err(map_err)?;
+        drop(part_file);
+
+        std::fs::rename(&part_path, file_path).map_err(map_err)?;
+        Ok(offset + written)
+    }
+
+    /// Bytes of a previous attempt that are waiting in the `.part` file.
+    pub fn partial_len(file_path: &str) -> u64 {
+        std::fs::metadata(Self::part_path(file_path))
+            .map(|meta| meta.len())
+            .unwrap_or(0)
+    }
+
+    pub fn part_path(file_path: &str) -> String {
+        format!("{file_path}.part")
+    }
+
     pub fn create_dir(path: &str) -> Result<(), StorageError> {
         std::fs::create_dir_all(path).map_err(|source| StorageError::Io {
             path: path.to_string(),
@@ -39,6 +93,19 @@ impl LocalStorageBackend {
         std::path::Path::new(path).exists()
     }
 
+    pub fn file_size(path: &str) -> Option<u64> {
+        std::fs::metadata(path).ok().map(|meta| meta.len())
+    }
+
+    pub fn read_stream(path: &str, writer: &mut (dyn Write + Send)) -> Result<u64, StorageError> {
+        let to_error = |source| StorageError::Io {
+            path: path.to_string(),
+            source,
+        };
+        let mut file = File::open(path).map_err(to_error)?;
+        io::copy(&mut file, writer).map_err(to_error)
+    }
+
     pub fn remove_dir(path: &str) -> Result<(), StorageError> {
         std::fs::remove_dir_all(path).map_err(|source| StorageError::Io {
             path: path.to_string(),
@@ -89,3 +156,77 @@ impl LocalStorageBackend {
         Box::pin(async move { Self::write_file(path, content) })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::LocalStorageBackend;
+    use std::io::Read;
+
+    fn scratch_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("podfetch-storage-{}-{name}", std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn write_stream_moves_completed_part_file_into_place() {
+        let path = scratch_path("complete.mp3");
+        let content = vec![7u8; 200_000];
+
+        let written = LocalStorageBackend::write_stream(&path, &mut content.as_slice()).unwrap();
+
+        assert_eq!(written, content.len() as u64);
+        assert_eq!(std::fs::read(&path).unwrap(), content);
+        assert!(!std::path::Path::new(&LocalStorageBackend::part_path(&path)).exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    struct FailingReader {
+        remaining: usize,
+    }
+
+    impl Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.remaining == 0 {
+                return Err(std::io::Error::other("connection reset"));
+            }
+            let n = buf.len().min(self.remaining);
+            buf[..n].fill(1);
+            self.remaining -= n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn write_stream_leaves_no_file_behind_on_read_error() {
+        let path = scratch_path("broken.mp3");
+
+        let result =
+            LocalStorageBackend::write_stream(&path, &mut FailingReader { remaining: 1024 });
+
+        assert!(result.is_err());
+        assert!(!std::path::Path::new(&path).exists());
+        assert!(!std::path::Path::new(&LocalStorageBackend::part_path(&path)).exists());
+    }
+
+    #[test]
+    fn write_stream_from_keeps_part_file_and_resumes_from_offset() {
+        let path = scratch_path("resume.mp3");
+
+        let result =
+            LocalStorageBackend::write_stream_from(&path, &mut FailingReader { remaining: 10 }, 0);
+        assert!(result.is_err());
+        assert_eq!(LocalStorageBackend::partial_len(&path), 10);
+
+        let total =
+            LocalStorageBackend::write_stream_from(&path, &mut [2u8; 5].as_slice(), 10).unwrap();
+
+        assert_eq!(total, 15);
+        let mut expected = vec![1u8; 10];
+        expected.extend([2u8; 5]);
+        assert_eq!(std::fs::read(&path).unwrap(), expected);
+        assert_eq!(LocalStorageBackend::partial_len(&path), 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,8 +1,11 @@
 use crate::{FileRequest, StorageError};
 use common_infrastructure::config::S3Config;
+use s3::bucket::CHUNK_SIZE;
 use s3::error::S3Error;
+use s3::serde_types::Part;
 use s3::{Bucket, BucketConfiguration};
 use std::future::Future;
+use std::io::{Read, Write};
 use std::pin::Pin;
 
 #[derive(Clone)]
@@ -101,6 +104,87 @@ impl S3StorageBackend {
         Ok(())
     }
 
+    /// Uploads `reader` without holding the whole object in memory. Bodies
+    /// smaller than one part go through a plain PUT; everything else is sent
+    /// as a multipart upload, one `CHUNK_SIZE` buffer at a time.
+    pub fn write_stream(&self, path: &str, reader: &mut dyn Read) -> Result<u64, StorageError> {
+        let bucket = self.get_bucket()?;
+        let s3_path = Self::prepare_path_resolution(path);
+
+        let first_chunk = Self::read_chunk(path, reader)?;
+        if first_chunk.len() < CHUNK_SIZE {
+            bucket
+                .put_object_blocking(&s3_path, &first_chunk)
+                .map_err(Self::map_s3_error)?;
+            return Ok(first_chunk.len() as u64);
+        }
+
+        let upload = bucket
+            .initiate_multipart_upload_blocking(&s3_path, "application/octet-stream")
+            .map_err(Self::map_s3_error)?;
+        let result = Self::upload_parts(&bucket, &s3_path, &upload.upload_id, first_chunk, reader);
+        match result {
+            Ok((parts, written)) => {
+                bucket
+                    .complete_multipart_upload_blocking(&s3_path, &upload.upload_id, parts)
+                    .map_err(Self::map_s3_error)?;
+                Ok(written)
+            }
+            Err(err) => {
+                if let Err(abort_err) = bucket.abort_upload_blocking(&s3_path, &upload.upload_id) {
+                    tracing::warn!("Could not abort multipart upload for {path}: {abort_err}");
+                }
+                Err(err)
+            }
+        }
+    }
+
+    fn upload_parts(
+        bucket: &Bucket,
+        s3_path: &str,
+        upload_id: &str,
+        first_chunk: Vec<u8>,
+        reader: &mut dyn Read,
+    ) -> Result<(Vec<Part>, u64), StorageError> {
+        let mut parts = Vec::new();
+        let mut written = 0u64;
+        let mut chunk = first_chunk;
+        loop {
+            let part_number = parts.len() as u32 + 1;
+            let chunk_len = chunk.len();
+            written += chunk_len as u64;
+            let part = bucket
+                .put_multipart_chunk_blocking(
+                    chunk,
+                    s3_path,
+                    part_number,
+                    upload_id,
+                    "application/octet-stream",
+                )
+                .map_err(Self::map_s3_error)?;
+            parts.push(part);
+            if chunk_len < CHUNK_SIZE {
+                return Ok((parts, written));
+            }
+            chunk = Self::read_chunk(s3_path, reader)?;
+            if chunk.is_empty() {
+                return Ok((parts, written));
+            }
+        }
+    }
+
+    fn read_chunk(path: &str, reader: &mut dyn Read) -> Result<Vec<u8>, StorageError> {
+        let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+        reader
+            .take(CHUNK_SIZE as u64)
+            .read_to_end(&mut chunk)
+            .map_err(|source| StorageError::Io {
+                path: path.to_string(),
+                source,
+            })?;
+        Ok(chunk)
+    }
+
     pub fn write_file_async<'a>(
         &'a self,
         path: &'a str,
@@ -124,6 +208,45 @@ impl S3StorageBackend {
         }
     }
 
+    pub fn file_size(&self, path: &str) -> Option<u64> {
+        let (head, _) = self
+            .get_bucket()
+            .ok()?
+            .head_object_blocking(Self::prepare_path_resolution(path))
+            .ok()?;
+        head.content_length
+            .and_then(|length| u64::try_from(length).ok())
+    }
+
+    /// Copies the object into `writer` one `CHUNK_SIZE` range at a time, so
+    /// large episodes never sit in memory as a whole.
+    pub fn read_stream(
+        &self,
+        path: &str,
+        writer: &mut (dyn Write + Send),
+    ) -> Result<(), StorageError> {
+        let bucket = self.get_bucket()?;
+        let s3_path = Self::prepare_path_resolution(path);
+        let size = self.file_size(path).ok_or_else(|| StorageError::Backend {
+            message: format!("{path} not found in bucket"),
+        })?;
+        let mut start = 0u64;
+        while start < size {
+            let end = (start + CHUNK_SIZE as u64).min(size) - 1;
+            let response = bucket
+                .get_object_range_blocking(&s3_path, start, Some(end))
+                .map_err(Self::map_s3_error)?;
+            writer
+                .write_all(response.as_slice())
+                .map_err(|source| StorageError::Io {
+                    path: path.to_string(),
+                    source,
+                })?;
+            start = end + 1;
+        }
+        Ok(())
+    }
+
     pub fn remove_dir(&self, _: &str) -> Result<(), StorageError> {
         Ok(())
     }
@@ -1,15 +1,21 @@
 use crate::app_state::AppState;
-use crate::file_access::{FileAccessError, check_permissions_for_files as check_file_access};
+use crate::file_access::{
+    FileAccessError, check_permissions_for_files as check_file_access, decode_requested_file_path,
+};
 use crate::rss::RSSAPiKey;
 use crate::services::podcast::service::PodcastService;
+use crate::services::transcoding::stream::{
+    StreamSource, StreamTranscodeParams, transcoded_stream,
+};
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
-use axum::extract::{Request, State};
+use axum::extract::{OriginalUri, Query, Request, State};
 use axum::middleware::Next;
 use axum::response::Response;
 use axum_extra::extract::OptionalQuery;
 use common_infrastructure::error::ErrorSeverity::{Info, Warning};
 use common_infrastructure::error::{CustomError, CustomErrorInner};
 use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
+use podfetch_domain::api_token::ApiTokenScope;
 
 pub async fn check_permissions_for_files(
     State(state): State<AppState>,
@@ -19,12 +25,68 @@ pub async fn check_permissions_for_files(
 ) -> Result<Response, CustomError> {
     let request = query.and_then(|rss_api_key| rss_api_key.api_key);
     let server_url = crate::url_rewriting::resolve_server_url_from_headers(req.headers());
+    authorize_file_request(&state, req.uri().path(), request, &server_url)?;
+    Ok(next.run(req).await)
+}
+
+/// Answers `/podcasts/...` requests for an episode's audio that carry
+/// `?codec=` or `?bitrate=` with a transcoded stream; everything else goes on
+/// to the file server.
+pub async fn stream_transcoded_files(
+    State(state): State<AppState>,
+    OptionalQuery(query): OptionalQuery<RSSAPiKey>,
+    Query(transcode): Query<StreamTranscodeParams>,
+    req: Request,
+    next: Next,
+) -> Result<Response, CustomError> {
+    let Some(encoding) = transcode.encoding()? else {
+        return Ok(next.run(req).await);
+    };
+    // Nesting strips the `/podcasts` prefix from the URI the file server sees.
+    let uri_path = req
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|original| original.path().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let request = query.and_then(|rss_api_key| rss_api_key.api_key);
+    let server_url = crate::url_rewriting::resolve_server_url_from_headers(req.headers());
+    authorize_file_request(&state, &uri_path, request, &server_url)?;
+
+    let file_path = decode_requested_file_path::<CustomError>(&uri_path, &server_url)
+        .map_err(map_file_access_error)?;
+    let episode = PodcastEpisodeService::get_podcast_episodes_by_url(&file_path)?
+        .filter(|episode| episode.file_episode_path.as_deref() == Some(file_path.as_str()))
+        .ok_or_else(|| {
+            CustomError::from(CustomErrorInner::BadRequest(
+                "Only episode audio can be transcoded".to_string(),
+                Warning,
+            ))
+        })?;
+    transcoded_stream(
+        state.audiobookshelf_hls_transcoder.clone(),
+        StreamSource::for_episode(&episode)?,
+        encoding,
+        transcode.start_segment()?,
+    )
+    .await
+}
+
+fn authorize_file_request(
+    state: &AppState,
+    uri_path: &str,
+    request: Option<String>,
+    server_url: &str,
+) -> Result<(), CustomError> {
     check_file_access(
-        req.uri().path(),
+        uri_path,
         request,
         ENVIRONMENT_SERVICE.any_auth_enabled,
-        &server_url,
-        |api_key| state.user_auth_service.is_api_key_valid(api_key),
+        server_url,
+        |api_key| {
+            state
+                .user_auth_service
+                .is_api_key_valid(api_key, ApiTokenScope::Stream)
+        },
         |path| {
             PodcastEpisodeService::get_podcast_episodes_by_url(path)
                 .map(|episode| episode.and_then(|e| e.file_image_path))
@@ -34,8 +96,7 @@ pub async fn check_permissions_for_files(
                 .map(|podcast| podcast.is_some())
         },
     )
-    .map_err(map_file_access_error)?;
-    Ok(next.run(req).await)
+    .map_err(map_file_access_error)
 }
 
 fn map_file_access_error(error: FileAccessError<CustomError>) -> CustomError {
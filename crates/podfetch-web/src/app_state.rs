@@ -1,6 +1,7 @@
 use crate::cast::ServerCastOrchestrator;
 use crate::services::agent::dispatcher::AgentDispatcher;
 use crate::services::agent::registry::AgentRegistry;
+use crate::services::api_token::service::ApiTokenService;
 use crate::services::audiobookshelf::audiobook_scanner::AudiobookScanner;
 use crate::services::audiobookshelf::book_service::AudiobookshelfBookService;
 use crate::services::audiobookshelf::hls_transcoder::HlsTranscoder;
@@ -9,9 +10,11 @@ use crate::services::audiobookshelf::listening_session_service::AudiobookshelfLi
 use crate::services::audiobookshelf::login_service::AudiobookshelfLoginService;
 use crate::services::audiobookshelf::media_progress_service::AudiobookshelfMediaProgressService;
 use crate::services::audiobookshelf::playback_session_service::AudiobookshelfPlaybackSessionService;
+use crate::services::audit_log::service::AuditLogService;
 use crate::services::cast::service::CastOrchestrator;
 use crate::services::device::service::DeviceService;
 use crate::services::device_sync_group::service::DeviceSyncGroupService;
+use crate::services::download::queue::DownloadQueueService;
 use crate::services::episode_triage::service::EpisodeTriageService;
 use crate::services::favorite_podcast_episode::service::FavoritePodcastEpisodeService;
 use crate::services::filter::service::FilterService;
@@ -19,16 +22,23 @@ use crate::services::gpodder_setting::service::GpodderSettingService;
 use crate::services::invite::service::InviteService;
 use crate::services::login::service::LoginService;
 use crate::services::mopidy::driver::{MopidyDriver, MopidyEvent};
+use crate::services::notification::rules::NotificationRuleService;
 use crate::services::notification::service::NotificationService;
+use crate::services::notification_channel::service::NotificationChannelService;
 use crate::services::playlist::service::PlaylistService;
+use crate::services::playlist::smart::SmartPlaylistService;
 use crate::services::podcast_episode_chapter::service::PodcastEpisodeChapterService;
 use crate::services::podcast_settings::service::PodcastSettingsService;
+use crate::services::search::service::SearchService;
 use crate::services::session::service::SessionService;
 use crate::services::settings::service::SettingsService;
 use crate::services::stats::service::StatsService;
+use crate::services::storage_quota::service::StorageQuotaService;
 use crate::services::subscription::service::SubscriptionService;
 use crate::services::tag::service::TagService;
+use crate::services::transcoding::service::TranscodingProfileService;
 use crate::services::transcript::service::TranscriptService;
+use crate::services::two_factor::service::TwoFactorService;
 use crate::services::user_admin::service::UserAdminService;
 use crate::services::user_auth::service::UserAuthService;
 use crate::services::user_onboarding::service::UserOnboardingService;
@@ -36,12 +46,15 @@ use crate::usecases::watchtime::WatchtimeUseCase;
 use common_infrastructure::config::EnvironmentService;
 use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
 use podfetch_cast::StubCastDriver;
+use podfetch_persistence::adapters::ApiTokenRepositoryImpl;
+use podfetch_persistence::adapters::AuditLogRepositoryImpl;
 use podfetch_persistence::adapters::AuthorRepositoryImpl;
 use podfetch_persistence::adapters::BookAudioFileRepositoryImpl;
 use podfetch_persistence::adapters::BookChapterRepositoryImpl;
 use podfetch_persistence::adapters::BookRepositoryImpl;
 use podfetch_persistence::adapters::DeviceRepositoryImpl;
 use podfetch_persistence::adapters::DeviceSyncGroupRepositoryImpl;
+use podfetch_persistence::adapters::DownloadJobRepositoryImpl;
 use podfetch_persistence::adapters::EpisodeTriageRepositoryImpl;
 use podfetch_persistence::adapters::FavoritePodcastEpisodeRepositoryImpl;
 use podfetch_persistence::adapters::FilterRepositoryImpl;
@@ -52,19 +65,26 @@ use podfetch_persistence::adapters::ListeningEventRepositoryImpl;
 use podfetch_persistence::adapters::ListeningSessionRepositoryImpl;
 use podfetch_persistence::adapters::MediaProgressRepositoryImpl;
 use podfetch_persistence::adapters::NarratorRepositoryImpl;
+use podfetch_persistence::adapters::NotificationChannelRepositoryImpl;
 use podfetch_persistence::adapters::NotificationRepositoryImpl;
+use podfetch_persistence::adapters::NotificationRuleRepositoryImpl;
 use podfetch_persistence::adapters::PlaybackSessionRepositoryImpl;
 use podfetch_persistence::adapters::PlaylistRepositoryImpl;
 use podfetch_persistence::adapters::PodcastEpisodeChapterRepositoryImpl;
 use podfetch_persistence::adapters::PodcastEpisodeTranscriptRepositoryImpl;
 use podfetch_persistence::adapters::PodcastSettingsRepositoryImpl;
+use podfetch_persistence::adapters::SearchRepositoryImpl;
 use podfetch_persistence::adapters::SeriesRepositoryImpl;
 use podfetch_persistence::adapters::SessionRepositoryImpl;
 use podfetch_persistence::adapters::SettingsRepositoryImpl;
+use podfetch_persistence::adapters::StorageUsageRepositoryImpl;
 use podfetch_persistence::adapters::SubscriptionRepositoryImpl;
 use podfetch_persistence::adapters::TagRepositoryImpl;
+use podfetch_persistence::adapters::TranscodingProfileRepositoryImpl;
 use podfetch_persistence::adapters::TranscriptionJobRepositoryImpl;
+use podfetch_persistence::adapters::TwoFactorRepositoryImpl;
 use podfetch_persistence::adapters::UserAdminRepositoryImpl;
+use podfetch_persistence::favorite::DieselFavoriteRepository;
 use std::sync::Arc;
 use tokio::sync::Mutex as AsyncMutex;
 use tokio::sync::mpsc;
@@ -73,6 +93,7 @@ use tokio::sync::mpsc;
 pub struct AppState {
     pub agent_dispatcher: Arc<AgentDispatcher>,
     pub agent_registry: Arc<AgentRegistry>,
+    pub audit_log_service: Arc<AuditLogService>,
     pub audiobookshelf_book_service: Arc<AudiobookshelfBookService>,
     pub audiobookshelf_hls_transcoder: Arc<HlsTranscoder>,
     pub audiobookshelf_library_service: Arc<AudiobookshelfLibraryService>,
@@ -85,6 +106,7 @@ pub struct AppState {
     pub device_service: Arc<DeviceService>,
     pub mopidy_event_rx: Arc<AsyncMutex<Option<mpsc::Receiver<MopidyEvent>>>>,
     pub device_sync_group_service: Arc<DeviceSyncGroupService>,
+    pub download_queue_service: Arc<DownloadQueueService>,
     pub environment: Arc<EnvironmentService>,
     pub episode_triage_service: Arc<EpisodeTriageService>,
     pub favorite_podcast_episode_service: Arc<FavoritePodcastEpisodeService>,
@@ -93,17 +115,24 @@ pub struct AppState {
     pub invite_service: Arc<InviteService>,
     pub login_service: Arc<LoginService>,
     pub notification_service: Arc<NotificationService>,
+    pub notification_channel_service: Arc<NotificationChannelService>,
+    pub notification_rule_service: Arc<NotificationRuleService>,
     pub playlist_service: Arc<PlaylistService>,
     pub podcast_episode_chapter_service: Arc<PodcastEpisodeChapterService>,
     pub podcast_settings_service: Arc<PodcastSettingsService>,
+    pub search_service: Arc<SearchService>,
     pub session_service: Arc<SessionService>,
     pub settings_service: Arc<SettingsService>,
     pub stats_service: Arc<StatsService>,
+    pub storage_quota_service: Arc<StorageQuotaService>,
     pub subscription_service: Arc<SubscriptionService>,
     pub tag_service: Arc<TagService>,
+    pub transcoding_profile_service: Arc<TranscodingProfileService>,
     pub transcript_service: Arc<TranscriptService>,
+    pub two_factor_service: Arc<TwoFactorService>,
     pub user_admin_service: Arc<UserAdminService>,
     pub user_auth_service: Arc<UserAuthService>,
+    pub api_token_service: Arc<ApiTokenService>,
     pub user_onboarding_service: Arc<UserOnboardingService>,
     pub watchtime_service: Arc<WatchtimeUseCase>,
 }
@@ -122,6 +151,9 @@ impl AppState {
             database.clone(),
         ))));
         let agent_registry = Arc::new(AgentRegistry::new());
+        let audit_log_service = Arc::new(AuditLogService::new(Arc::new(
+            AuditLogRepositoryImpl::new(database.clone()),
+        )));
         let agent_dispatcher = Arc::new(AgentDispatcher::new(agent_registry.clone()));
         let (mopidy_tx, mopidy_rx) = mpsc::channel::<MopidyEvent>(64);
         let mopidy_driver = Arc::new(MopidyDriver::new(mopidy_tx));
@@ -151,18 +183,42 @@ impl AppState {
         ))));
         let user_auth_service = Arc::new(UserAuthService::new(
             Arc::new(UserAdminRepositoryImpl::new(database.clone())),
+            Arc::new(ApiTokenRepositoryImpl::new(database.clone())),
+            Arc::new(TwoFactorRepositoryImpl::new(database.clone())),
             environment.clone(),
         ));
+        let api_token_service = Arc::new(ApiTokenService::new(Arc::new(
+            ApiTokenRepositoryImpl::new(database.clone()),
+        )));
+        let two_factor_service = Arc::new(TwoFactorService::new(Arc::new(
+            TwoFactorRepositoryImpl::new(database.clone()),
+        )));
         let login_service = Arc::new(LoginService::new(
             environment.clone(),
             user_auth_service.clone(),
+            two_factor_service.clone(),
+            api_token_service.clone(),
         ));
         let notification_service = Arc::new(NotificationService::new(Arc::new(
             NotificationRepositoryImpl::new(database.clone()),
         )));
-        let playlist_service = Arc::new(PlaylistService::new(Arc::new(
-            PlaylistRepositoryImpl::new(database.clone()),
+        let notification_channel_service = Arc::new(NotificationChannelService::new(Arc::new(
+            NotificationChannelRepositoryImpl::new(database.clone()),
         )));
+        let notification_rule_service = Arc::new(NotificationRuleService::new(
+            Arc::new(NotificationRuleRepositoryImpl::new(database.clone())),
+            Arc::new(TagRepositoryImpl::new(database.clone())),
+            Arc::new(DieselFavoriteRepository::new(database.clone())),
+            Arc::new(SubscriptionRepositoryImpl::new(database.clone())),
+        ));
+        let playlist_service = Arc::new(PlaylistService::new(
+            Arc::new(PlaylistRepositoryImpl::new(database.clone())),
+            SmartPlaylistService::new(
+                Arc::new(TagRepositoryImpl::new(database.clone())),
+                Arc::new(EpisodeTriageRepositoryImpl::new(database.clone())),
+                Arc::new(FavoritePodcastEpisodeRepositoryImpl::new(database.clone())),
+            ),
+        ));
         let podcast_episode_chapter_service = Arc::new(PodcastEpisodeChapterService::new(
             Arc::new(PodcastEpisodeChapterRepositoryImpl::new(database.clone())),
         ));
@@ -180,18 +236,32 @@ impl AppState {
                 ListeningEventRepositoryImpl::new(database.clone()),
             )),
         )));
+        let storage_quota_service = Arc::new(StorageQuotaService::new(Arc::new(
+            StorageUsageRepositoryImpl::new(database.clone()),
+        )));
         let subscription_service = Arc::new(SubscriptionService::new(Arc::new(
             SubscriptionRepositoryImpl::new(database.clone()),
         )));
         let tag_service = Arc::new(TagService::new(Arc::new(TagRepositoryImpl::new(
             database.clone(),
         ))));
+        let download_queue_service = Arc::new(DownloadQueueService::new(Arc::new(
+            DownloadJobRepositoryImpl::new(database.clone()),
+        )));
+        let transcoding_profile_service = Arc::new(TranscodingProfileService::new(Arc::new(
+            TranscodingProfileRepositoryImpl::new(database.clone()),
+        )));
         let transcript_service = Arc::new(TranscriptService::new(
             Arc::new(PodcastEpisodeTranscriptRepositoryImpl::new(
                 database.clone(),
             )),
             Arc::new(TranscriptionJobRepositoryImpl::new(database.clone())),
         ));
+        let search_service = Arc::new(SearchService::new(
+            Arc::new(SearchRepositoryImpl::new(database.clone())),
+            Arc::new(TagRepositoryImpl::new(database.clone())),
+            transcript_service.clone(),
+        ));
         let watchtime_service = Arc::new(WatchtimeUseCase::new());
         let user_admin_service = Arc::new(UserAdminService::new(
             Arc::new(UserAdminRepositoryImpl::new(database.clone())),
@@ -255,6 +325,7 @@ impl AppState {
         Self {
             agent_dispatcher,
             agent_registry,
+            audit_log_service,
             audiobookshelf_book_service,
             audiobookshelf_hls_transcoder,
             audiobookshelf_library_service,
@@ -267,6 +338,7 @@ impl AppState {
             device_service,
             mopidy_event_rx: Arc::new(AsyncMutex::new(Some(mopidy_rx))),
             device_sync_group_service,
+            download_queue_service,
             environment,
             episode_triage_service,
             favorite_podcast_episode_service,
@@ -275,17 +347,24 @@ impl AppState {
             invite_service,
             login_service,
             notification_service,
+            notification_channel_service,
+            notification_rule_service,
             playlist_service,
             podcast_episode_chapter_service,
             podcast_settings_service,
+            search_service,
             session_service,
             settings_service,
             stats_service,
+            storage_quota_service,
             subscription_service,
             tag_service,
+            transcoding_profile_service,
             transcript_service,
+            two_factor_service,
             user_admin_service,
             user_auth_service,
+            api_token_service,
             user_onboarding_service,
             watchtime_service,
         }
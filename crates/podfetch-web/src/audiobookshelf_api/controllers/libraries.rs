@@ -227,7 +227,7 @@ pub async fn list_library_playlists(
     };
     let expanded: Vec<Value> = slice
         .iter()
-        .map(|p| expand_playlist(p, &library.id))
+        .map(|p| expand_playlist(&state, p, &user, &library.id))
         .collect::<Result<_, _>>()?;
     Ok(Json(json!({
         "results": expanded,
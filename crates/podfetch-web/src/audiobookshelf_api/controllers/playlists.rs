@@ -3,6 +3,9 @@
 //! so each handler just wraps `PlaylistRepositoryImpl` and maps to/from the
 //! upstream `Playlist.toOldJSONExpanded` byte shape.
 //!
+//! Smart playlists show up like any other playlist with their evaluated
+//! episodes; their items can't be edited through this API.
+//!
 //! Books are not yet supported (PodFetch has no audiobook playlists), but
 //! the request body grammar mirrors upstream — books can be added later
 //! without breaking the API surface.
@@ -11,6 +14,7 @@ use crate::app_state::AppState;
 use crate::audiobookshelf_api::auth_middleware::AuthenticatedUser;
 use crate::audiobookshelf_api::id_resolution::{resolve_episode, resolve_podcast_library_item};
 use crate::audiobookshelf_api::mapping::podcast::{map_episode, map_podcast_without_episodes};
+use crate::services::playlist::service::PlaylistService;
 use crate::services::podcast::service::PodcastService;
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
 use axum::Json;
@@ -20,6 +24,7 @@ use common_infrastructure::error::ErrorSeverity::{Debug, Warning};
 use common_infrastructure::error::{CustomError, CustomErrorInner};
 use podfetch_domain::audiobookshelf::library_item_id::{EpisodeId, LibraryItemId};
 use podfetch_domain::playlist::{Playlist, PlaylistItem, PlaylistRepository};
+use podfetch_domain::user::User;
 use podfetch_persistence::adapters::PlaylistRepositoryImpl;
 use podfetch_persistence::db::database;
 use serde::Deserialize;
@@ -82,7 +87,7 @@ pub async fn list_playlists(
     let playlists = repo().list_by_user(user.id)?;
     let dtos: Vec<Value> = playlists
         .into_iter()
-        .map(|p| expand_playlist(&p, &library_id))
+        .map(|p| expand_playlist(&state, &p, &user, &library_id))
         .collect::<Result<_, _>>()?;
     Ok(Json(json!({ "playlists": dtos })))
 }
@@ -101,7 +106,12 @@ pub async fn get_playlist(
 ) -> Result<Json<Value>, CustomError> {
     let playlist = ensure_owned(&id, user.id)?;
     let library_id = library_id(&state)?;
-    Ok(Json(expand_playlist(&playlist, &library_id)?))
+    Ok(Json(expand_playlist(
+        &state,
+        &playlist,
+        &user,
+        &library_id,
+    )?))
 }
 
 #[utoipa::path(
@@ -125,6 +135,7 @@ pub async fn create_playlist(
         id: uuid::Uuid::new_v4().to_string(),
         name: name.to_string(),
         user_id: user.id,
+        smart_query: None,
     })?;
     let mut seen = HashSet::new();
     for (idx, item) in body.items.iter().enumerate() {
@@ -138,7 +149,12 @@ pub async fn create_playlist(
             position: idx as i32,
         })?;
     }
-    Ok(Json(expand_playlist(&playlist, &library_id)?))
+    Ok(Json(expand_playlist(
+        &state,
+        &playlist,
+        &user,
+        &library_id,
+    )?))
 }
 
 #[utoipa::path(
@@ -165,6 +181,7 @@ pub async fn update_playlist(
         }
     }
     if let Some(items) = body.items.as_ref() {
+        PlaylistService::ensure_manual(&playlist)?;
         repo().delete_items_by_playlist_id(&playlist.id)?;
         for (idx, item) in items.iter().enumerate() {
             let episode_id = require_episode_id(item)?;
@@ -176,7 +193,12 @@ pub async fn update_playlist(
         }
     }
     let playlist = ensure_owned(&id, user.id)?;
-    Ok(Json(expand_playlist(&playlist, &library_id)?))
+    Ok(Json(expand_playlist(
+        &state,
+        &playlist,
+        &user,
+        &library_id,
+    )?))
 }
 
 #[utoipa::path(
@@ -211,6 +233,7 @@ pub async fn add_item(
     Json(item): Json<PlaylistItemInput>,
 ) -> Result<Json<Value>, CustomError> {
     let playlist = ensure_owned(&id, user.id)?;
+    PlaylistService::ensure_manual(&playlist)?;
     let library_id = library_id(&state)?;
     let episode_id = require_episode_id(&item)?;
     let existing = repo().list_items_by_playlist_id(&playlist.id)?;
@@ -222,7 +245,12 @@ pub async fn add_item(
         episode: episode_id,
         position: existing.len() as i32,
     })?;
-    Ok(Json(expand_playlist(&playlist, &library_id)?))
+    Ok(Json(expand_playlist(
+        &state,
+        &playlist,
+        &user,
+        &library_id,
+    )?))
 }
 
 #[utoipa::path(
@@ -257,7 +285,12 @@ pub async fn remove_item_by_library_item(
     })?;
     let playlist = ensure_owned(&id, user.id)?;
     let library_id = library_id(&state)?;
-    Ok(Json(expand_playlist(&playlist, &library_id)?))
+    Ok(Json(expand_playlist(
+        &state,
+        &playlist,
+        &user,
+        &library_id,
+    )?))
 }
 
 #[utoipa::path(
@@ -281,7 +314,12 @@ pub async fn remove_item_by_episode(
     remove_items_in(&id, user.id, |_| vec![ep])?;
     let playlist = ensure_owned(&id, user.id)?;
     let library_id = library_id(&state)?;
-    Ok(Json(expand_playlist(&playlist, &library_id)?))
+    Ok(Json(expand_playlist(
+        &state,
+        &playlist,
+        &user,
+        &library_id,
+    )?))
 }
 
 #[utoipa::path(
@@ -299,6 +337,7 @@ pub async fn batch_add(
     Json(body): Json<BatchItemsRequest>,
 ) -> Result<Json<Value>, CustomError> {
     let playlist = ensure_owned(&id, user.id)?;
+    PlaylistService::ensure_manual(&playlist)?;
     let library_id = library_id(&state)?;
     let mut existing = repo().list_items_by_playlist_id(&playlist.id)?;
     let mut next_pos = existing.iter().map(|i| i.position).max().unwrap_or(-1) + 1;
@@ -316,7 +355,12 @@ pub async fn batch_add(
         existing.push(new_item);
         next_pos += 1;
     }
-    Ok(Json(expand_playlist(&playlist, &library_id)?))
+    Ok(Json(expand_playlist(
+        &state,
+        &playlist,
+        &user,
+        &library_id,
+    )?))
 }
 
 #[utoipa::path(
@@ -348,7 +392,12 @@ pub async fn batch_remove(
     remove_items_in(&id, user.id, |_| to_remove.clone())?;
     let playlist = ensure_owned(&id, user.id)?;
     let library_id = library_id(&state)?;
-    Ok(Json(expand_playlist(&playlist, &library_id)?))
+    Ok(Json(expand_playlist(
+        &state,
+        &playlist,
+        &user,
+        &library_id,
+    )?))
 }
 
 // ── helpers ─────────────────────────────────────────────────────────────────
@@ -390,6 +439,7 @@ fn remove_items_in(
     selector: impl FnOnce(&[PlaylistItem]) -> Vec<Uuid>,
 ) -> Result<(), CustomError> {
     let playlist = ensure_owned(playlist_id, user_id)?;
+    PlaylistService::ensure_manual(&playlist)?;
     let items = repo().list_items_by_playlist_id(&playlist.id)?;
     let to_remove = selector(&items);
     if to_remove.is_empty() {
@@ -413,15 +463,16 @@ fn remove_items_in(
     Ok(())
 }
 
-pub fn expand_playlist(playlist: &Playlist, library_id: &str) -> Result<Value, CustomError> {
-    let items = repo().list_items_by_playlist_id(&playlist.id)?;
+pub fn expand_playlist(
+    state: &AppState,
+    playlist: &Playlist,
+    user: &User,
+    library_id: &str,
+) -> Result<Value, CustomError> {
+    let episodes = state.playlist_service.episodes_of(playlist, user)?;
     let now_ms = Utc::now().naive_utc().and_utc().timestamp_millis();
-    let mut item_values: Vec<Value> = Vec::with_capacity(items.len());
-    for (idx, pmi) in items.iter().enumerate() {
-        let Some(episode) = PodcastEpisodeService::get_podcast_episode_by_internal_id(pmi.episode)?
-        else {
-            continue;
-        };
+    let mut item_values: Vec<Value> = Vec::with_capacity(episodes.len());
+    for (idx, episode) in episodes.into_iter().enumerate() {
         let episode_uuid = Uuid::parse_str(&episode.id)
             .map_err(|_| CustomError::from(CustomErrorInner::NotFound(Debug)))?;
         let podcast = PodcastService::get_podcast_by_episode_id(episode_uuid)?;
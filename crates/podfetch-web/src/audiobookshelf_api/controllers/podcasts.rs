@@ -19,6 +19,7 @@ use axum::extract::State;
 use chrono::DateTime;
 use common_infrastructure::error::ErrorSeverity::Error as ErrSeverityError;
 use common_infrastructure::error::{CustomError, CustomErrorInner, map_reqwest_error};
+use common_infrastructure::feed::parse_feed;
 use common_infrastructure::http::{COMMON_USER_AGENT, get_http_client};
 use common_infrastructure::request::add_basic_auth_headers_conditionally;
 use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
@@ -183,10 +184,15 @@ async fn fetch_and_parse_feed(feed_url: &str) -> Result<Channel, CustomError> {
         )
         .into());
     }
-    let body = response.text().await.map_err(map_reqwest_error)?;
-    Channel::read_from(body.as_bytes()).map_err(|e| {
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = response.bytes().await.map_err(map_reqwest_error)?;
+    parse_feed(&body, content_type.as_deref()).map_err(|e| {
         CustomError::from(CustomErrorInner::BadRequest(
-            format!("Invalid RSS feed: {e}"),
+            e.to_string(),
             ErrSeverityError,
         ))
     })
@@ -112,6 +112,58 @@ async fn login_unknown_user_is_rejected() {
     assert!(response.status_code().is_client_error());
 }
 
+#[tokio::test]
+#[serial]
+async fn login_with_second_factor_needs_an_app_password() {
+    let mut server = handle_test_startup().await;
+    let state = AppState::new();
+    let user = create_user_for_audiobookshelf(&state);
+    let enrollment = state.two_factor_service.start_enrollment(&user).unwrap();
+    state
+        .two_factor_service
+        .confirm_enrollment(
+            &user,
+            &crate::services::two_factor::totp::code_at(
+                &enrollment.secret,
+                chrono::Utc::now().timestamp(),
+            ),
+        )
+        .unwrap();
+    let (_, app_password) = state
+        .api_token_service
+        .create(
+            &user,
+            "Audiobookshelf app",
+            vec![podfetch_domain::api_token::ApiTokenScope::Audiobookshelf],
+            None,
+        )
+        .unwrap();
+
+    server.test_server.clear_headers();
+    let with_password = server
+        .test_server
+        .post("/login")
+        .json(&json!({ "username": user.username, "password": "password" }))
+        .await;
+    assert!(with_password.status_code().is_client_error());
+
+    let with_app_password = server
+        .test_server
+        .post("/login")
+        .json(&json!({ "username": user.username, "password": app_password }))
+        .await;
+    assert_eq!(with_app_password.status_code().as_u16(), 200);
+    let body: Value = with_app_password.json();
+    assert_eq!(body["user"]["token"], json!(app_password));
+
+    let me_resp = server
+        .test_server
+        .get("/api/me")
+        .authorization_bearer(&app_password)
+        .await;
+    assert_eq!(me_resp.status_code().as_u16(), 200);
+}
+
 // ── Bearer middleware: protected routes refuse missing / bad tokens ────────
 
 #[tokio::test]
@@ -664,6 +716,9 @@ fn insert_test_episode_with_path(
         description: format!("description of {title}"),
         guid: format!("guid-{}", uuid::Uuid::new_v4().simple()),
         youtube_video_id: None,
+        season: None,
+        episode_number: None,
+        episode_type: None,
     };
     let mut domain_ep: podfetch_domain::podcast_episode::PodcastEpisode =
         repo.create(new).expect("create episode");
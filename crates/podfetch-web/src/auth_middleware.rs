@@ -1,5 +1,6 @@
 use crate::app_state::AppState;
 use crate::auth::{AuthControllerError, parse_basic_auth};
+use crate::services::api_token::service::API_TOKEN_PREFIX;
 use crate::services::user_auth::service::UserAuthService;
 use axum::extract::{Request, State};
 use axum::http::HeaderValue;
@@ -12,6 +13,7 @@ use common_infrastructure::http::get_async_sync_client;
 use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
 use jsonwebtoken::jwk::JwkSet;
 use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
+use podfetch_domain::api_token::ApiTokenScope;
 use podfetch_domain::user::User;
 use serde_json::Value;
 use sha256::digest;
@@ -87,6 +89,12 @@ async fn handle_auth_internal(
     auth_type: AuthType,
     state: &AppState,
 ) -> Result<User, CustomError> {
+    if matches!(auth_type, AuthType::Basic | AuthType::Oidc)
+        && let Some(user) =
+            AuthFilter::handle_api_token_auth(req, state.user_auth_service.as_ref())?
+    {
+        return Ok(user);
+    }
     match auth_type {
         AuthType::Basic => {
             AuthFilter::handle_basic_auth_internal(req, state.user_auth_service.as_ref())
@@ -129,6 +137,28 @@ impl AuthFilter {
         Ok((u.to_string(), p.to_string()))
     }
 
+    /// Named api tokens sent as `Authorization: Bearer pft_…` are accepted in
+    /// place of basic auth or an OIDC token. The REST API needs the `admin`
+    /// scope; `None` means the request doesn't carry such a token.
+    fn handle_api_token_auth(
+        req: &Request,
+        user_auth_service: &UserAuthService,
+    ) -> Result<Option<User>, CustomError> {
+        let Some(token) = req
+            .headers()
+            .get("Authorization")
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .filter(|token| token.starts_with(API_TOKEN_PREFIX))
+        else {
+            return Ok(None);
+        };
+        user_auth_service
+            .find_by_api_key(token, ApiTokenScope::Admin)?
+            .map(Some)
+            .ok_or_else(|| CustomErrorInner::Forbidden(Warning).into())
+    }
+
     fn handle_basic_auth_internal(
         req: &Request,
         user_auth_service: &UserAuthService,
@@ -145,6 +175,7 @@ impl AuthFilter {
 
                     if let Some(password_from_user) = &found_user.password {
                         if password_from_user == &digest(password) {
+                            user_auth_service.ensure_password_only_login_allowed(&found_user)?;
                             Ok(found_user)
                         } else {
                             Err(CustomErrorInner::Forbidden(Warning).into())
@@ -84,6 +84,10 @@ pub struct CastStartRequest {
     pub title: String,
     pub artwork_url: Option<String>,
     pub duration_secs: Option<f64>,
+    /// Volume (0.0 to 1.0) to start at. It is turned down by the episode's
+    /// ReplayGain when its loudness has been measured.
+    #[serde(default)]
+    pub volume: Option<f32>,
 }
 
 /// Request body for `POST /cast/sessions/:id/control`.
@@ -0,0 +1,156 @@
+//! Address of the client behind a request, as recorded in the audit log.
+
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::HeaderMap;
+use axum::http::request::Parts;
+use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
+use ipnet::IpNet;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+/// The client address of a request: the socket peer, unless the peer is one
+/// of the `TRUSTED_PROXIES`. Only then `X-Forwarded-For` and `X-Real-IP` are
+/// believed, since any other client can send them with whatever it likes.
+/// `None` when the peer is unknown, e.g. in tests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIp(pub Option<String>);
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| *addr);
+        Ok(ClientIp(resolve_client_ip(
+            &parts.headers,
+            peer,
+            &ENVIRONMENT_SERVICE.trusted_proxies,
+        )))
+    }
+}
+
+fn resolve_client_ip(
+    headers: &HeaderMap,
+    peer: Option<SocketAddr>,
+    trusted_proxies: &[IpNet],
+) -> Option<String> {
+    let peer = peer?.ip().to_canonical();
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if !is_trusted(&peer) {
+        return Some(peer.to_string());
+    }
+
+    let header = |name: &str| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    // Every proxy appends the address it got the request from, so the
+    // right-most hop that isn't one of ours is the client. Everything left of
+    // it was written by the client itself.
+    let forwarded_for = header("x-forwarded-for");
+    let mut hops = forwarded_for.iter().rev().peekable();
+    while let Some(hop) = hops.next() {
+        match hop.parse::<IpAddr>().map(|ip| ip.to_canonical()) {
+            Ok(ip) if is_trusted(&ip) && hops.peek().is_some() => continue,
+            Ok(ip) => return Some(ip.to_string()),
+            Err(_) => break,
+        }
+    }
+    if !forwarded_for.is_empty() {
+        return Some(peer.to_string());
+    }
+    header("x-real-ip")
+        .last()
+        .and_then(|value| value.parse::<IpAddr>().ok())
+        .map(|ip| ip.to_canonical().to_string())
+        .or_else(|| Some(peer.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_client_ip;
+    use axum::http::HeaderMap;
+    use ipnet::IpNet;
+
+    fn proxies() -> Vec<IpNet> {
+        vec!["10.0.0.0/24".parse().unwrap(), "::1/128".parse().unwrap()]
+    }
+
+    #[test]
+    fn untrusted_peers_cannot_forge_their_address() {
+        let peer = Some("198.51.100.9:51234".parse().unwrap());
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "203.0.113.7".parse().unwrap());
+        headers.insert("x-real-ip", "203.0.113.8".parse().unwrap());
+
+        assert_eq!(
+            resolve_client_ip(&headers, peer, &proxies()),
+            Some("198.51.100.9".to_string())
+        );
+        assert_eq!(
+            resolve_client_ip(&headers, peer, &[]),
+            Some("198.51.100.9".to_string())
+        );
+        assert_eq!(resolve_client_ip(&headers, None, &proxies()), None);
+    }
+
+    #[test]
+    fn trusted_proxies_report_the_right_most_untrusted_hop() {
+        let peer = Some("10.0.0.2:51234".parse().unwrap());
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            resolve_client_ip(&headers, peer, &proxies()),
+            Some("10.0.0.2".to_string())
+        );
+
+        headers.insert("x-real-ip", "198.51.100.4".parse().unwrap());
+        assert_eq!(
+            resolve_client_ip(&headers, peer, &proxies()),
+            Some("198.51.100.4".to_string())
+        );
+
+        // The client prepended a fake hop; the address our proxy saw wins.
+        headers.insert(
+            "x-forwarded-for",
+            "192.0.2.66, 203.0.113.7, 10.0.0.1".parse().unwrap(),
+        );
+        assert_eq!(
+            resolve_client_ip(&headers, peer, &proxies()),
+            Some("203.0.113.7".to_string())
+        );
+
+        // Only proxies of ours in the chain: the first of them is the client.
+        headers.insert("x-forwarded-for", "10.0.0.5, 10.0.0.1".parse().unwrap());
+        assert_eq!(
+            resolve_client_ip(&headers, peer, &proxies()),
+            Some("10.0.0.5".to_string())
+        );
+
+        // A chain we can't read falls back to the proxy itself.
+        headers.insert("x-forwarded-for", "unknown, 10.0.0.1".parse().unwrap());
+        assert_eq!(
+            resolve_client_ip(&headers, peer, &proxies()),
+            Some("10.0.0.2".to_string())
+        );
+    }
+
+    #[test]
+    fn ipv4_mapped_peers_match_ipv4_proxies() {
+        let peer = Some("[::ffff:10.0.0.2]:51234".parse().unwrap());
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "203.0.113.7".parse().unwrap());
+        assert_eq!(
+            resolve_client_ip(&headers, peer, &proxies()),
+            Some("203.0.113.7".to_string())
+        );
+    }
+}
@@ -14,6 +14,7 @@ use axum::routing::get;
 use chrono::Utc;
 use futures::{SinkExt, StreamExt};
 use podfetch_agent_protocol::{AgentMsg, ErrorCode, PROTOCOL_VERSION, ServerMsg};
+use podfetch_domain::api_token::ApiTokenScope;
 use podfetch_domain::user::User;
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -38,7 +39,7 @@ async fn agent_ws_upgrade(
 
     let user = state
         .user_auth_service
-        .find_by_api_key(&api_key)
+        .find_by_api_key(&api_key, ApiTokenScope::Admin)
         .map_err(|e| {
             warn!("agent ws: api_key lookup failed: {e}");
             StatusCode::INTERNAL_SERVER_ERROR
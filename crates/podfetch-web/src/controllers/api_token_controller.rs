@@ -0,0 +1,260 @@
+//! Named api tokens of the signed-in user. A token is handed out instead of
+//! the account api key, e.g. only for reading the RSS feeds, and can be
+//! revoked on its own.
+
+use crate::app_state::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::api_token::{ApiToken, ApiTokenScope};
+use podfetch_domain::user::User;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use uuid::Uuid;
+
+// ── DTOs ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTokenDto {
+    pub id: String,
+    pub name: String,
+    /// Any of `rss-read`, `stream`, `gpodder-sync`, `audiobookshelf` and `admin`.
+    pub scopes: Vec<String>,
+    pub expires_at: Option<String>,
+    pub last_used_at: Option<String>,
+    pub created_at: String,
+}
+
+impl From<ApiToken> for ApiTokenDto {
+    fn from(token: ApiToken) -> Self {
+        Self {
+            id: token.id.to_string(),
+            name: token.name,
+            scopes: token
+                .scopes
+                .iter()
+                .map(|scope| scope.as_str().to_string())
+                .collect(),
+            expires_at: token.expires_at.map(|at| at.and_utc().to_rfc3339()),
+            last_used_at: token.last_used_at.map(|at| at.and_utc().to_rfc3339()),
+            created_at: token.created_at.and_utc().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTokenCreate {
+    pub name: String,
+    pub scopes: Vec<String>,
+    /// RFC 3339 timestamp after which the token stops working.
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTokenCreated {
+    pub token: ApiTokenDto,
+    /// The secret to hand to the client. It is only shown once.
+    pub secret: String,
+}
+
+// ── handlers ──────────────────────────────────────────────────────────────
+
+fn bad_request(message: String) -> CustomError {
+    CustomErrorInner::BadRequest(message, Warning).into()
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/me/tokens",
+    responses(
+        (status = 200, description = "The api tokens of the current user.", body = [ApiTokenDto])
+    ),
+    tag = "user"
+)]
+pub async fn get_api_tokens(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+) -> Result<Json<Vec<ApiTokenDto>>, CustomError> {
+    let tokens = state.api_token_service.list(&requester)?;
+    Ok(Json(tokens.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/tokens",
+    request_body = ApiTokenCreate,
+    responses(
+        (status = 200, description = "The created token and its secret.", body = ApiTokenCreated),
+        (status = 400, description = "Missing name, unknown scope or an expiry in the past.")
+    ),
+    tag = "user"
+)]
+pub async fn create_api_token(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+    Json(create): Json<ApiTokenCreate>,
+) -> Result<Json<ApiTokenCreated>, CustomError> {
+    let scopes = create
+        .scopes
+        .iter()
+        .map(|scope| {
+            ApiTokenScope::from_str(scope)
+                .ok_or_else(|| bad_request(format!("'{scope}' is not a valid token scope")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let expires_at = create
+        .expires_at
+        .as_deref()
+        .map(|expires_at| {
+            chrono::DateTime::parse_from_rfc3339(expires_at)
+                .map(|at| at.naive_utc())
+                .map_err(|_| bad_request(format!("'{expires_at}' is not a valid timestamp")))
+        })
+        .transpose()?;
+    let (token, secret) =
+        state
+            .api_token_service
+            .create(&requester, &create.name, scopes, expires_at)?;
+    Ok(Json(ApiTokenCreated {
+        token: token.into(),
+        secret,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/me/tokens/{id}",
+    responses(
+        (status = 204, description = "The token was revoked."),
+        (status = 404, description = "No such token.")
+    ),
+    tag = "user"
+)]
+pub async fn delete_api_token(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(requester): Extension<User>,
+) -> Result<StatusCode, CustomError> {
+    let id = Uuid::parse_str(&id).map_err(|_| bad_request("invalid token id".to_string()))?;
+    state.api_token_service.delete(&requester, id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn get_api_token_router() -> OpenApiRouter<AppState> {
+    OpenApiRouter::new()
+        .routes(routes!(get_api_tokens, create_api_token))
+        .routes(routes!(delete_api_token))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controllers::api_token_controller::{ApiTokenCreated, ApiTokenDto};
+    use crate::test_support::tests::handle_test_startup;
+    use serde_json::json;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn tokens_can_be_created_listed_and_revoked() {
+        let server = handle_test_startup().await;
+
+        let created = server
+            .test_server
+            .post("/api/v1/users/me/tokens")
+            .json(&json!({"name": "Podcatcher", "scopes": ["rss-read", "stream"]}))
+            .await;
+        assert_eq!(created.status_code(), 200);
+        let created = created.json::<ApiTokenCreated>();
+        assert!(created.secret.starts_with("pft_"));
+        assert_eq!(created.token.scopes, vec!["rss-read", "stream"]);
+
+        let listed = server.test_server.get("/api/v1/users/me/tokens").await;
+        assert_eq!(listed.status_code(), 200);
+        let listed = listed.json::<Vec<ApiTokenDto>>();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "Podcatcher");
+
+        let deleted = server
+            .test_server
+            .delete(&format!("/api/v1/users/me/tokens/{}", created.token.id))
+            .await;
+        assert_eq!(deleted.status_code(), 204);
+        let listed = server.test_server.get("/api/v1/users/me/tokens").await;
+        assert!(listed.json::<Vec<ApiTokenDto>>().is_empty());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn creating_a_token_rejects_unknown_scopes_and_past_expiry() {
+        let server = handle_test_startup().await;
+
+        let unknown_scope = server
+            .test_server
+            .post("/api/v1/users/me/tokens")
+            .json(&json!({"name": "Broken", "scopes": ["everything"]}))
+            .await;
+        assert_eq!(unknown_scope.status_code(), 400);
+
+        let expired = server
+            .test_server
+            .post("/api/v1/users/me/tokens")
+            .json(&json!({
+                "name": "Expired",
+                "scopes": ["rss-read"],
+                "expiresAt": "2020-01-01T00:00:00Z"
+            }))
+            .await;
+        assert_eq!(expired.status_code(), 400);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn scopes_decide_where_a_token_is_accepted() {
+        let server = handle_test_startup().await;
+
+        let rss_token = server
+            .test_server
+            .post("/api/v1/users/me/tokens")
+            .json(&json!({"name": "Feed reader", "scopes": ["rss-read"]}))
+            .await
+            .json::<ApiTokenCreated>();
+
+        let feed = server
+            .test_server
+            .get(&format!("/rss?apiKey={}", rss_token.secret))
+            .await;
+        assert_eq!(feed.status_code(), 200);
+
+        let api = server
+            .test_server
+            .get("/api/v1/users/me/tokens")
+            .clear_headers()
+            .authorization_bearer(&rss_token.secret)
+            .await;
+        assert_eq!(api.status_code(), 403);
+
+        let admin_token = server
+            .test_server
+            .post("/api/v1/users/me/tokens")
+            .json(&json!({"name": "Script", "scopes": ["admin"]}))
+            .await
+            .json::<ApiTokenCreated>();
+        let api = server
+            .test_server
+            .get("/api/v1/users/me/tokens")
+            .clear_headers()
+            .authorization_bearer(&admin_token.secret)
+            .await;
+        assert_eq!(api.status_code(), 200);
+        let listed = api.json::<Vec<ApiTokenDto>>();
+        let script = listed.iter().find(|token| token.name == "Script").unwrap();
+        assert!(script.last_used_at.is_some());
+    }
+}
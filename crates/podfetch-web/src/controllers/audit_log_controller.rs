@@ -0,0 +1,233 @@
+//! Admin view of the audit log: who deleted, reconfigured or invited what,
+//! from which address and when. Entries are only ever appended; the
+//! retention setting is the only thing that removes them.
+
+use crate::app_state::AppState;
+use axum::extract::{Query, State};
+use axum::{Extension, Json};
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::audit_log::{AuditAction, AuditLogEntry, AuditLogFilter};
+use podfetch_domain::user::User;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+// ── DTOs ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntryDto {
+    pub id: String,
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: String,
+}
+
+impl From<AuditLogEntry> for AuditLogEntryDto {
+    fn from(entry: AuditLogEntry) -> Self {
+        Self {
+            id: entry.id.to_string(),
+            actor: entry.actor,
+            action: entry.action.as_str().to_string(),
+            target: entry.target,
+            ip_address: entry.ip_address,
+            created_at: entry.created_at.and_utc().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogPageDto {
+    pub items: Vec<AuditLogEntryDto>,
+    pub page: i64,
+    pub page_size: i64,
+    /// Number of entries matching the filters across all pages.
+    pub total: i64,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogQuery {
+    /// Zero based page, newest entries first.
+    pub page: Option<i64>,
+    /// Entries per page, at most 500.
+    pub page_size: Option<i64>,
+    pub actor: Option<String>,
+    /// e.g. `podcast.delete`, `user.role`, `invite.create` or `settings.update`.
+    pub action: Option<String>,
+    pub target: Option<String>,
+    /// RFC 3339 timestamp; only entries at or after it.
+    pub from: Option<String>,
+    /// RFC 3339 timestamp; only entries before it.
+    pub to: Option<String>,
+}
+
+// ── handlers ──────────────────────────────────────────────────────────────
+
+fn bad_request(message: String) -> CustomError {
+    CustomErrorInner::BadRequest(message, Warning).into()
+}
+
+fn parse_timestamp(value: Option<&str>) -> Result<Option<chrono::NaiveDateTime>, CustomError> {
+    value
+        .map(|value| {
+            chrono::DateTime::parse_from_rfc3339(value)
+                .map(|at| at.naive_utc())
+                .map_err(|_| bad_request(format!("'{value}' is not a valid timestamp")))
+        })
+        .transpose()
+}
+
+#[utoipa::path(
+    get,
+    path = "/audit-log",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, description = "One page of the audit log (admin).", body = AuditLogPageDto),
+        (status = 400, description = "Unknown action or malformed timestamp."),
+        (status = 403, description = "Only admins may read the audit log.")
+    ),
+    tag = "audit-log"
+)]
+pub async fn get_audit_log(
+    State(state): State<AppState>,
+    Query(query): Query<AuditLogQuery>,
+    Extension(requester): Extension<User>,
+) -> Result<Json<AuditLogPageDto>, CustomError> {
+    if !requester.is_admin() {
+        return Err(CustomErrorInner::Forbidden(Warning).into());
+    }
+    let action = query
+        .action
+        .as_deref()
+        .map(|action| {
+            AuditAction::from_str(action)
+                .ok_or_else(|| bad_request(format!("'{action}' is not a valid audit action")))
+        })
+        .transpose()?;
+    let filter = AuditLogFilter {
+        actor: query.actor,
+        action,
+        target: query.target,
+        from: parse_timestamp(query.from.as_deref())?,
+        to: parse_timestamp(query.to.as_deref())?,
+    };
+    let page = query.page.unwrap_or_default().max(0);
+    let page_size = query
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let (entries, total) = state.audit_log_service.list(&filter, page, page_size)?;
+    Ok(Json(AuditLogPageDto {
+        items: entries.into_iter().map(Into::into).collect(),
+        page,
+        page_size,
+        total,
+    }))
+}
+
+pub fn get_audit_log_router() -> OpenApiRouter<AppState> {
+    OpenApiRouter::new().routes(routes!(get_audit_log))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app_state::AppState;
+    use crate::controllers::audit_log_controller::AuditLogPageDto;
+    use crate::test_support::tests::handle_test_startup;
+    use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde_json::json;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn admin_actions_are_recorded_and_can_be_filtered() {
+        let server = handle_test_startup().await;
+        let user = AppState::new()
+            .user_admin_service
+            .create_user(UserTestDataBuilder::new().build())
+            .unwrap();
+
+        let role = server
+            .test_server
+            .put(&format!("/api/v1/users/{}/role", user.username))
+            .add_header("X-Forwarded-For", "203.0.113.7")
+            .json(&json!({"role": "uploader", "explicitConsent": true}))
+            .await;
+        assert_eq!(role.status_code(), 200);
+        let invite = server
+            .test_server
+            .post("/api/v1/invites")
+            .json(&json!({"role": "user", "explicitConsent": false}))
+            .await;
+        assert_eq!(invite.status_code(), 200);
+
+        let log = server.test_server.get("/api/v1/audit-log").await;
+        assert_eq!(log.status_code(), 200);
+        let log = log.json::<AuditLogPageDto>();
+        assert_eq!(log.total, 2);
+        assert_eq!(log.items[0].action, "invite.create");
+        assert_eq!(log.items[1].action, "user.role");
+        assert_eq!(log.items[1].actor, "postgres");
+        assert_eq!(log.items[1].target.as_deref(), Some(user.username.as_str()));
+        // The test client is no trusted proxy, so its header is ignored.
+        assert_eq!(log.items[1].ip_address, None);
+
+        let filtered = server
+            .test_server
+            .get("/api/v1/audit-log?action=user.role&pageSize=1")
+            .await
+            .json::<AuditLogPageDto>();
+        assert_eq!(filtered.total, 1);
+        assert_eq!(filtered.page_size, 1);
+        assert_eq!(filtered.items.len(), 1);
+
+        let second_page = server
+            .test_server
+            .get("/api/v1/audit-log?page=1&pageSize=1")
+            .await
+            .json::<AuditLogPageDto>();
+        assert_eq!(second_page.items[0].action, "user.role");
+
+        let unknown = server
+            .test_server
+            .get("/api/v1/audit-log?action=everything")
+            .await;
+        assert_eq!(unknown.status_code(), 400);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn only_admins_can_read_the_audit_log() {
+        let server = handle_test_startup().await;
+        let user = AppState::new()
+            .user_admin_service
+            .create_user(UserTestDataBuilder::new().build())
+            .unwrap();
+
+        let response = server
+            .test_server
+            .get("/api/v1/audit-log")
+            .clear_headers()
+            .add_header(
+                "Authorization",
+                format!(
+                    "Basic {}",
+                    STANDARD.encode(format!("{}:password", user.username))
+                ),
+            )
+            .await;
+        assert_eq!(response.status_code(), 403);
+    }
+}
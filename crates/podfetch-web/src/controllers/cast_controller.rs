@@ -3,11 +3,12 @@ use crate::cast::{
     CastControlRequest, CastDeviceResponse, CastSessionResponse, CastStartRequest,
     CastStatusResponse, DiscoveredCastDeviceResponse, parse_device_uuid, parse_session_id,
 };
+use crate::services::loudness::service::{playback_volume, replay_gain_db};
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase;
 use axum::extract::{Path, State};
 use axum::{Extension, Json};
 use common_infrastructure::error::CustomError;
-use podfetch_cast::CastMedia;
+use podfetch_cast::{CastMedia, ControlCmd};
 use podfetch_domain::user::User;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
@@ -81,9 +82,12 @@ pub async fn start_cast_session(
             common_infrastructure::error::ErrorSeverity::Warning,
         ))
     })?;
-    let episode_string_id =
-        PodcastEpisodeUseCase::get_podcast_episode_by_internal_id(episode_uuid)?
-            .map(|e| e.episode_id);
+    let episode = PodcastEpisodeUseCase::get_podcast_episode_by_internal_id(episode_uuid)?;
+    let replay_gain = episode
+        .as_ref()
+        .and_then(|e| e.loudness_lufs)
+        .map(replay_gain_db);
+    let episode_string_id = episode.map(|e| e.episode_id);
 
     let media = CastMedia {
         url: req.url,
@@ -104,6 +108,21 @@ pub async fn start_cast_session(
         )
         .await
         .map_err(CustomError::from)?;
+    if let Some(volume) = req.volume {
+        let volume = f64::from(volume) * replay_gain.map_or(1.0, playback_volume);
+        let cmd = ControlCmd::SetVolume {
+            volume: volume.clamp(0.0, 1.0) as f32,
+        };
+        // The session is already playing, so a device that ignores the
+        // volume shouldn't turn the start into an error.
+        if let Err(e) = state
+            .cast_orchestrator
+            .control(&user, &session.session_id, cmd)
+            .await
+        {
+            tracing::warn!("Could not set the volume of cast session: {e}");
+        }
+    }
     Ok(Json(CastSessionResponse::from_active(&session)))
 }
 
@@ -0,0 +1,350 @@
+//! HTTP surface of the persistent download queue: listing jobs and
+//! cancelling, reprioritizing or retrying a single job. Live updates are
+//! pushed separately as `downloadStatus`/`downloadProgress` socket.io events.
+
+use crate::app_state::AppState;
+use axum::extract::{Path, Query, State};
+use axum::{Extension, Json};
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::episode_download::{DownloadJob, DownloadJobStatus};
+use podfetch_domain::user::User;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use uuid::Uuid;
+
+// ── DTOs ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadJobDto {
+    pub id: String,
+    pub episode_id: String,
+    pub podcast_id: String,
+    pub status: String,
+    pub priority: i32,
+    pub attempts: i32,
+    pub error: Option<String>,
+    pub next_run_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<DownloadJob> for DownloadJobDto {
+    fn from(job: DownloadJob) -> Self {
+        Self {
+            id: job.id.to_string(),
+            episode_id: job.episode_id.to_string(),
+            podcast_id: job.podcast_id.to_string(),
+            status: job.status.as_str().to_string(),
+            priority: job.priority,
+            attempts: job.attempts,
+            error: job.error,
+            next_run_at: job.next_run_at.and_utc().to_rfc3339(),
+            created_at: job.created_at.and_utc().to_rfc3339(),
+            updated_at: job.updated_at.and_utc().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadJobQuery {
+    /// One of `pending`, `running`, `done`, `failed` or `cancelled`.
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadJobPriorityUpdate {
+    /// Jobs with a higher priority are downloaded first.
+    pub priority: i32,
+}
+
+// ── handlers ──────────────────────────────────────────────────────────────
+
+fn require_privileged(requester: &User) -> Result<(), CustomError> {
+    if !requester.is_privileged_user() {
+        return Err(CustomErrorInner::Forbidden(Warning).into());
+    }
+    Ok(())
+}
+
+fn parse_job_uuid(id: &str) -> Result<Uuid, CustomError> {
+    Uuid::parse_str(id)
+        .map_err(|_| CustomErrorInner::BadRequest("invalid job id".to_string(), Warning).into())
+}
+
+#[utoipa::path(
+    get,
+    path = "/downloads/jobs",
+    params(DownloadJobQuery),
+    responses(
+        (status = 200, description = "Download jobs, most urgent first.", body = [DownloadJobDto])
+    ),
+    tag = "downloads"
+)]
+pub async fn get_download_jobs(
+    State(state): State<AppState>,
+    Query(query): Query<DownloadJobQuery>,
+    Extension(requester): Extension<User>,
+) -> Result<Json<Vec<DownloadJobDto>>, CustomError> {
+    require_privileged(&requester)?;
+    let status = query
+        .status
+        .as_deref()
+        .map(|status| {
+            DownloadJobStatus::from_str(status).ok_or_else(|| {
+                CustomError::from(CustomErrorInner::BadRequest(
+                    format!("'{status}' is not a valid download job status"),
+                    Warning,
+                ))
+            })
+        })
+        .transpose()?;
+
+    let jobs = state.download_queue_service.list(status)?;
+    Ok(Json(jobs.into_iter().map(DownloadJobDto::from).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/downloads/jobs/{id}/cancel",
+    responses(
+        (status = 200, description = "The job was taken off the queue.", body = DownloadJobDto),
+        (status = 404, description = "No such job."),
+        (status = 409, description = "The job is already running or done.")
+    ),
+    tag = "downloads"
+)]
+pub async fn cancel_download_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(requester): Extension<User>,
+) -> Result<Json<DownloadJobDto>, CustomError> {
+    require_privileged(&requester)?;
+    let job = state.download_queue_service.cancel(parse_job_uuid(&id)?)?;
+    Ok(Json(job.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/downloads/jobs/{id}/priority",
+    request_body = DownloadJobPriorityUpdate,
+    responses(
+        (status = 200, description = "The job's new priority.", body = DownloadJobDto),
+        (status = 404, description = "No such job.")
+    ),
+    tag = "downloads"
+)]
+pub async fn update_download_job_priority(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(requester): Extension<User>,
+    Json(update): Json<DownloadJobPriorityUpdate>,
+) -> Result<Json<DownloadJobDto>, CustomError> {
+    require_privileged(&requester)?;
+    let job = state
+        .download_queue_service
+        .set_priority(parse_job_uuid(&id)?, update.priority)?;
+    Ok(Json(job.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/downloads/jobs/{id}/retry",
+    responses(
+        (status = 200, description = "The job was put back on the queue.", body = DownloadJobDto),
+        (status = 404, description = "No such job."),
+        (status = 409, description = "Only failed or cancelled jobs can be retried.")
+    ),
+    tag = "downloads"
+)]
+pub async fn retry_download_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(requester): Extension<User>,
+) -> Result<Json<DownloadJobDto>, CustomError> {
+    require_privileged(&requester)?;
+    let job = state.download_queue_service.retry(parse_job_uuid(&id)?)?;
+    Ok(Json(job.into()))
+}
+
+pub fn get_download_queue_router() -> OpenApiRouter<AppState> {
+    OpenApiRouter::new()
+        .routes(routes!(get_download_jobs))
+        .routes(routes!(cancel_download_job))
+        .routes(routes!(update_download_job_priority))
+        .routes(routes!(retry_download_job))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::download::queue::MANUAL_PRIORITY;
+    use crate::test_support::tests::handle_test_startup;
+    use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
+    use axum::Extension;
+    use axum::extract::{Path, State};
+    use common_infrastructure::error::CustomErrorInner;
+    use diesel::prelude::*;
+    use podfetch_persistence::db::get_connection;
+    use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
+    use podfetch_persistence::schema::podcast_episodes::dsl as pe_dsl;
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use uuid::Uuid;
+
+    fn unique(prefix: &str) -> String {
+        format!("{prefix}-{}", Uuid::new_v4())
+    }
+
+    fn app_state() -> crate::app_state::AppState {
+        crate::app_state::AppState::new()
+    }
+
+    /// Creates a podcast with one missing episode and queues its download,
+    /// returning the job id.
+    fn queued_job() -> String {
+        let slug = unique("download-queue-podcast");
+        let podcast = crate::services::podcast::service::PodcastService::add_podcast_to_database(
+            &slug,
+            &slug,
+            &format!("https://example.com/{slug}.xml"),
+            "http://localhost:8080/ui/default.jpg",
+            &slug,
+        )
+        .unwrap();
+
+        let episode_id = Uuid::new_v4().to_string();
+        diesel::insert_into(pe_dsl::podcast_episodes)
+            .values((
+                pe_dsl::id.eq(episode_id.clone()),
+                pe_dsl::podcast_id.eq(podcast.id.clone()),
+                pe_dsl::episode_id.eq(unique("episode")),
+                pe_dsl::name.eq("Download Queue Test Episode".to_string()),
+                pe_dsl::url.eq(format!("https://example.com/{episode_id}.mp3")),
+                pe_dsl::date_of_recording.eq("2026-03-01T00:00:00Z".to_string()),
+                pe_dsl::image_url.eq("http://localhost:8080/ui/default.jpg".to_string()),
+                pe_dsl::total_time.eq(1800),
+                pe_dsl::description.eq("download queue test".to_string()),
+                pe_dsl::guid.eq(unique("guid")),
+                pe_dsl::deleted.eq(false),
+                pe_dsl::episode_numbering_processed.eq(false),
+            ))
+            .execute(&mut get_connection())
+            .unwrap();
+        let episode = pe_dsl::podcast_episodes
+            .filter(pe_dsl::id.eq(episode_id))
+            .first::<PodcastEpisode>(&mut get_connection())
+            .unwrap();
+
+        let state = app_state();
+        state
+            .download_queue_service
+            .enqueue(&episode, MANUAL_PRIORITY, None)
+            .unwrap();
+        let job = state
+            .download_queue_service
+            .list(None)
+            .unwrap()
+            .into_iter()
+            .find(|job| job.episode_id.to_string() == episode.id)
+            .unwrap();
+        job.id.to_string()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn queued_job_is_listed_and_can_be_reprioritized() {
+        let server = handle_test_startup().await;
+        let job_id = queued_job();
+
+        let list = server
+            .test_server
+            .get("/api/v1/downloads/jobs?status=pending")
+            .await;
+        assert_eq!(list.status_code(), 200);
+        let list = list.json::<Value>();
+        assert!(
+            list.as_array()
+                .unwrap()
+                .iter()
+                .any(|job| job["id"] == json!(job_id))
+        );
+
+        let updated = server
+            .test_server
+            .put(&format!("/api/v1/downloads/jobs/{job_id}/priority"))
+            .json(&json!({ "priority": 42 }))
+            .await;
+        assert_eq!(updated.status_code(), 200);
+        assert_eq!(updated.json::<Value>()["priority"], json!(42));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cancelled_job_can_be_retried() {
+        let server = handle_test_startup().await;
+        let job_id = queued_job();
+
+        let retry_pending = server
+            .test_server
+            .post(&format!("/api/v1/downloads/jobs/{job_id}/retry"))
+            .await;
+        assert_eq!(retry_pending.status_code(), 409);
+
+        let cancelled = server
+            .test_server
+            .post(&format!("/api/v1/downloads/jobs/{job_id}/cancel"))
+            .await;
+        assert_eq!(cancelled.status_code(), 200);
+        assert_eq!(cancelled.json::<Value>()["status"], json!("cancelled"));
+
+        let retried = server
+            .test_server
+            .post(&format!("/api/v1/downloads/jobs/{job_id}/retry"))
+            .await;
+        assert_eq!(retried.status_code(), 200);
+        let retried = retried.json::<Value>();
+        assert_eq!(retried["status"], json!("pending"));
+        assert_eq!(retried["attempts"], json!(0));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn unknown_job_and_invalid_status_are_rejected() {
+        let server = handle_test_startup().await;
+
+        let missing = server
+            .test_server
+            .post(&format!("/api/v1/downloads/jobs/{}/cancel", Uuid::new_v4()))
+            .await;
+        assert_eq!(missing.status_code(), 404);
+
+        let invalid = server
+            .test_server
+            .get("/api/v1/downloads/jobs?status=stalled")
+            .await;
+        assert_eq!(invalid.status_code(), 400);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn non_privileged_user_cannot_manage_the_queue() {
+        let _server = handle_test_startup().await;
+        let user = UserTestDataBuilder::new().build();
+
+        let result = super::retry_download_job(
+            State(app_state()),
+            Path(Uuid::new_v4().to_string()),
+            Extension(user),
+        )
+        .await;
+        match result {
+            Err(err) => assert!(matches!(err.inner, CustomErrorInner::Forbidden(_))),
+            Ok(_) => panic!("expected forbidden error for retry_download_job"),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::app_state::AppState;
 use crate::controllers::podcast_episode_controller::{
-    PodcastEpisodeWithHistory, resolve_episode_uuid, spawn_single_episode_download,
+    PodcastEpisodeWithHistory, queue_single_episode_download, resolve_episode_uuid,
 };
 use crate::services::episode_triage::service::DEFAULT_PAGE_SIZE;
 use crate::url_rewriting::resolve_server_url_from_headers;
@@ -146,7 +146,7 @@ pub async fn set_episode_triage(
     // list; an admin's download then makes it playable for everyone.
     if status == TriageStatus::Queued && requester.is_privileged_user() && !episode.is_downloaded()
     {
-        spawn_single_episode_download(episode.episode_id);
+        queue_single_episode_download(episode.episode_id, requester.id);
     }
 
     Ok(StatusCode::OK)
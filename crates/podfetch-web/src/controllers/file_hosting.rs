@@ -1,4 +1,4 @@
-use crate::api_file_access::check_permissions_for_files;
+use crate::api_file_access::{check_permissions_for_files, stream_transcoded_files};
 use crate::app_state::AppState;
 use axum::middleware::from_fn_with_state;
 use axum::routing::get;
@@ -12,6 +12,60 @@ pub fn podcast_serving(state: AppState) -> OpenApiRouter {
         OpenApiRouter::new()
             .route("/trololol", get(|| async { "trololol" }))
             .fallback_service(ServeDir::new(&ENVIRONMENT_SERVICE.default_podfetch_folder))
-            .route_layer(from_fn_with_state(state, check_permissions_for_files)),
+            .route_layer(from_fn_with_state(
+                state.clone(),
+                check_permissions_for_files,
+            ))
+            .layer(from_fn_with_state(state, stream_transcoded_files)),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::app_state::AppState;
+    use crate::test_support::tests::handle_test_startup;
+    use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
+    use serial_test::serial;
+
+    fn create_api_key_user() -> String {
+        let mut user = UserTestDataBuilder::new().build();
+        user.username = format!("file-hosting-user-{}", uuid::Uuid::new_v4());
+        let api_key = format!("file-hosting-key-{}", uuid::Uuid::new_v4());
+        user.api_key = Some(api_key.clone());
+        AppState::new()
+            .user_admin_service
+            .create_user(user)
+            .unwrap();
+        api_key
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn transcoding_requests_validate_the_encoding_first() {
+        let server = handle_test_startup().await;
+
+        let resp = server
+            .test_server
+            .get(&format!(
+                "/podcasts/some-podcast/episode.mp3?apiKey={}&codec=flac",
+                create_api_key_user()
+            ))
+            .await;
+        assert_eq!(resp.status_code(), 400);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn transcoding_requests_for_unknown_files_are_not_found() {
+        let server = handle_test_startup().await;
+
+        let resp = server
+            .test_server
+            .get(&format!(
+                "/podcasts/some-podcast/episode.mp3?apiKey={}&codec=aac&bitrate=48",
+                create_api_key_user()
+            ))
+            .await;
+        assert_eq!(resp.status_code(), 404);
+    }
+}
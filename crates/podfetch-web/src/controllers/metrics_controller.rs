@@ -0,0 +1,118 @@
+use crate::app_state::AppState;
+use crate::metrics::{METRICS, MetricsSnapshot};
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
+use podfetch_persistence::db::database;
+use utoipa_axum::router::OpenApiRouter;
+
+/// Prometheus scrape endpoint. Open unless `METRICS_TOKEN` is set, in which
+/// case the scraper has to send it as bearer token.
+pub async fn get_metrics(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let expected_token = ENVIRONMENT_SERVICE
+        .metrics_config
+        .as_ref()
+        .and_then(|config| config.token.as_deref());
+    if let Some(expected_token) = expected_token {
+        let provided = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim);
+        if provided != Some(expected_token) {
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+    }
+
+    let body = tokio::task::spawn_blocking(move || METRICS.render(&collect_snapshot(&state)))
+        .await
+        .unwrap_or_default();
+    ([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response()
+}
+
+/// Samples the gauges. A failing query only leaves its gauge empty so one
+/// broken source does not take the whole scrape down.
+fn collect_snapshot(state: &AppState) -> MetricsSnapshot {
+    let download_jobs = state
+        .download_queue_service
+        .count_by_status()
+        .unwrap_or_else(|err| {
+            tracing::warn!("Could not count download jobs for metrics: {err}");
+            Vec::new()
+        });
+    let transcription_jobs = state
+        .transcript_service
+        .count_jobs_by_status()
+        .unwrap_or_else(|err| {
+            tracing::warn!("Could not count transcription jobs for metrics: {err}");
+            Vec::new()
+        });
+    let (chromecast_sessions, mopidy_sessions) = state.cast_orchestrator.active_session_counts();
+    let database = database();
+    let pool_state = database.pool_state();
+
+    MetricsSnapshot {
+        download_jobs,
+        transcription_jobs,
+        hls_active_transcodes: state.audiobookshelf_hls_transcoder.active_transcodes(),
+        hls_max_concurrent: state.audiobookshelf_hls_transcoder.max_concurrent(),
+        hls_cache_bytes: state.audiobookshelf_hls_transcoder.cache_size_bytes(),
+        chromecast_sessions,
+        mopidy_sessions,
+        db_pool_connections: pool_state.connections,
+        db_pool_idle_connections: pool_state.idle_connections,
+        db_pool_max_size: database.pool_max_size(),
+    }
+}
+
+pub fn get_metrics_router() -> OpenApiRouter<AppState> {
+    OpenApiRouter::new().route("/metrics", get(get_metrics))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::tests::handle_test_startup;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_metrics_require_the_configured_token() {
+        let mut server = handle_test_startup().await;
+        server.test_server.clear_headers();
+
+        let response = server.test_server.get("/metrics").await;
+        assert_eq!(response.status_code(), 401);
+
+        let response = server
+            .test_server
+            .get("/metrics")
+            .add_header("Authorization", "Bearer wrong-token")
+            .await;
+        assert_eq!(response.status_code(), 401);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_metrics_report_gauges_and_request_latency_per_route() {
+        let mut server = handle_test_startup().await;
+        server.test_server.get("/api/v1/sys/config").await;
+        server.test_server.clear_headers();
+
+        let response = server
+            .test_server
+            .get("/metrics")
+            .add_header("Authorization", "Bearer test-metrics-token")
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let body = response.text();
+
+        assert!(body.contains("podfetch_db_pool_max_connections"));
+        assert!(body.contains("podfetch_hls_max_concurrent_transcodes"));
+        assert!(body.contains("podfetch_cast_sessions{backend=\"mopidy\"} 0"));
+        assert!(body.contains(
+            "podfetch_http_request_duration_seconds_count{method=\"GET\",route=\"/api/v1/sys/config\",status=\"200\"}"
+        ));
+    }
+}
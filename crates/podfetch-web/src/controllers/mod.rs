@@ -1,22 +1,30 @@
 pub mod agent_ws_controller;
+pub mod api_token_controller;
+pub mod audit_log_controller;
 pub mod cast_controller;
 pub mod controller_utils;
 pub mod discover_controller;
+pub mod download_queue_controller;
 pub mod episode_triage_controller;
 pub mod file_hosting;
 pub mod id_resolver;
 pub mod manifest_controller;
+pub mod metrics_controller;
 pub mod mopidy_controller;
+pub mod notification_channel_controller;
 pub mod notification_controller;
 pub mod playlist_controller;
 pub mod podcast_controller;
 pub mod podcast_episode_controller;
+pub mod search_controller;
 pub mod settings_controller;
 pub mod sponsorblock_controller;
 pub mod stats_controller;
 pub mod sys_info_controller;
 pub mod tags_controller;
+pub mod transcoding_profile_controller;
 pub mod transcript_controller;
+pub mod two_factor_controller;
 pub mod user_controller;
 pub mod watch_time_controller;
 pub mod websocket_controller;
@@ -0,0 +1,350 @@
+//! Settings API for outgoing notification channels (webhooks, ntfy, Gotify
+//! and SMTP). Only admins can see or change channels since their
+//! configuration carries tokens and passwords.
+
+use crate::app_state::AppState;
+use crate::services::notification_channel::service::NotificationChannelInput;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::notification_channel::{
+    NotificationChannel, NotificationChannelKind, NotificationEventType,
+};
+use podfetch_domain::user::User;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use uuid::Uuid;
+
+// ── DTOs ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationChannelDto {
+    pub id: String,
+    pub name: String,
+    /// One of `webhook`, `ntfy`, `gotify` or `smtp`.
+    pub kind: String,
+    pub enabled: bool,
+    /// Subscribed events: `new-episode`, `download-failed`,
+    /// `transcription-done` and `feed-broken`.
+    pub events: Vec<String>,
+    #[schema(value_type = Object)]
+    pub config: serde_json::Value,
+    pub created_at: String,
+}
+
+impl From<NotificationChannel> for NotificationChannelDto {
+    fn from(channel: NotificationChannel) -> Self {
+        Self {
+            id: channel.id.to_string(),
+            name: channel.name,
+            kind: channel.kind.as_str().to_string(),
+            enabled: channel.enabled,
+            events: channel
+                .events
+                .iter()
+                .map(|event| event.as_str().to_string())
+                .collect(),
+            config: serde_json::from_str(&channel.config).unwrap_or_default(),
+            created_at: channel.created_at.and_utc().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationChannelUpsert {
+    pub name: String,
+    pub kind: String,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    pub events: Vec<String>,
+    /// Kind specific settings, e.g. `{"url": "...", "secret": "..."}` for
+    /// webhooks or `{"serverUrl": "...", "topic": "..."}` for ntfy.
+    #[schema(value_type = Object)]
+    pub config: serde_json::Value,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl TryFrom<NotificationChannelUpsert> for NotificationChannelInput {
+    type Error = CustomError;
+
+    fn try_from(upsert: NotificationChannelUpsert) -> Result<Self, Self::Error> {
+        let kind = NotificationChannelKind::from_str(&upsert.kind).ok_or_else(|| {
+            bad_request(format!(
+                "'{}' is not a valid notification channel kind",
+                upsert.kind
+            ))
+        })?;
+        let events = upsert
+            .events
+            .iter()
+            .map(|event| {
+                NotificationEventType::from_str(event).ok_or_else(|| {
+                    bad_request(format!("'{event}' is not a valid notification event"))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            name: upsert.name,
+            kind,
+            enabled: upsert.enabled,
+            events,
+            config: upsert.config.to_string(),
+        })
+    }
+}
+
+// ── handlers ──────────────────────────────────────────────────────────────
+
+fn bad_request(message: String) -> CustomError {
+    CustomErrorInner::BadRequest(message, Warning).into()
+}
+
+fn require_admin(requester: &User) -> Result<(), CustomError> {
+    if !requester.is_admin() {
+        return Err(CustomErrorInner::Forbidden(Warning).into());
+    }
+    Ok(())
+}
+
+fn parse_channel_uuid(id: &str) -> Result<Uuid, CustomError> {
+    Uuid::parse_str(id).map_err(|_| bad_request("invalid notification channel id".to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/settings/notification-channels",
+    responses(
+        (status = 200, description = "All configured notification channels.", body = [NotificationChannelDto])
+    ),
+    tag = "settings"
+)]
+pub async fn get_notification_channels(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+) -> Result<Json<Vec<NotificationChannelDto>>, CustomError> {
+    require_admin(&requester)?;
+    let channels = state.notification_channel_service.list()?;
+    Ok(Json(channels.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/settings/notification-channels",
+    request_body = NotificationChannelUpsert,
+    responses(
+        (status = 200, description = "The created channel.", body = NotificationChannelDto),
+        (status = 400, description = "Unknown kind or event, or a configuration that doesn't fit the kind.")
+    ),
+    tag = "settings"
+)]
+pub async fn create_notification_channel(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+    Json(upsert): Json<NotificationChannelUpsert>,
+) -> Result<Json<NotificationChannelDto>, CustomError> {
+    require_admin(&requester)?;
+    let channel = state
+        .notification_channel_service
+        .create(upsert.try_into()?)?;
+    Ok(Json(channel.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/settings/notification-channels/{id}",
+    request_body = NotificationChannelUpsert,
+    responses(
+        (status = 200, description = "The updated channel.", body = NotificationChannelDto),
+        (status = 400, description = "Unknown kind or event, or a configuration that doesn't fit the kind."),
+        (status = 404, description = "No such channel.")
+    ),
+    tag = "settings"
+)]
+pub async fn update_notification_channel(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(requester): Extension<User>,
+    Json(upsert): Json<NotificationChannelUpsert>,
+) -> Result<Json<NotificationChannelDto>, CustomError> {
+    require_admin(&requester)?;
+    let channel = state
+        .notification_channel_service
+        .update(parse_channel_uuid(&id)?, upsert.try_into()?)?;
+    Ok(Json(channel.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/settings/notification-channels/{id}",
+    responses(
+        (status = 204, description = "The channel was removed."),
+        (status = 404, description = "No such channel.")
+    ),
+    tag = "settings"
+)]
+pub async fn delete_notification_channel(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(requester): Extension<User>,
+) -> Result<StatusCode, CustomError> {
+    require_admin(&requester)?;
+    state
+        .notification_channel_service
+        .delete(parse_channel_uuid(&id)?)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/settings/notification-channels/{id}/test",
+    responses(
+        (status = 204, description = "A test notification was delivered."),
+        (status = 400, description = "The channel couldn't deliver the notification."),
+        (status = 404, description = "No such channel.")
+    ),
+    tag = "settings"
+)]
+pub async fn test_notification_channel(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(requester): Extension<User>,
+) -> Result<StatusCode, CustomError> {
+    require_admin(&requester)?;
+    let service = state.notification_channel_service.clone();
+    let id = parse_channel_uuid(&id)?;
+    tokio::task::spawn_blocking(move || service.send_test(id))
+        .await
+        .unwrap()?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn get_notification_channel_router() -> OpenApiRouter<AppState> {
+    OpenApiRouter::new()
+        .routes(routes!(
+            get_notification_channels,
+            create_notification_channel
+        ))
+        .routes(routes!(
+            update_notification_channel,
+            delete_notification_channel
+        ))
+        .routes(routes!(test_notification_channel))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::tests::handle_test_startup;
+    use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
+    use axum::Extension;
+    use axum::extract::State;
+    use common_infrastructure::error::CustomErrorInner;
+    use serde_json::{Value, json};
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn channels_can_be_created_updated_and_deleted() {
+        let server = handle_test_startup().await;
+
+        let created = server
+            .test_server
+            .post("/api/v1/settings/notification-channels")
+            .json(&json!({
+                "name": "Team ntfy",
+                "kind": "ntfy",
+                "events": ["new-episode", "feed-broken"],
+                "config": { "topic": "podfetch" }
+            }))
+            .await;
+        assert_eq!(created.status_code(), 200);
+        let created = created.json::<Value>();
+        assert_eq!(created["enabled"], json!(true));
+        assert_eq!(created["config"]["topic"], json!("podfetch"));
+        let id = created["id"].as_str().unwrap().to_string();
+
+        let updated = server
+            .test_server
+            .put(&format!("/api/v1/settings/notification-channels/{id}"))
+            .json(&json!({
+                "name": "Team ntfy",
+                "kind": "ntfy",
+                "enabled": false,
+                "events": ["download-failed"],
+                "config": { "topic": "podfetch-failures" }
+            }))
+            .await;
+        assert_eq!(updated.status_code(), 200);
+        assert_eq!(
+            updated.json::<Value>()["events"],
+            json!(["download-failed"])
+        );
+
+        let list = server
+            .test_server
+            .get("/api/v1/settings/notification-channels")
+            .await
+            .json::<Value>();
+        assert!(
+            list.as_array()
+                .unwrap()
+                .iter()
+                .any(|c| c["id"] == json!(id))
+        );
+
+        let deleted = server
+            .test_server
+            .delete(&format!("/api/v1/settings/notification-channels/{id}"))
+            .await;
+        assert_eq!(deleted.status_code(), 204);
+        let missing = server
+            .test_server
+            .post(&format!("/api/v1/settings/notification-channels/{id}/test"))
+            .await;
+        assert_eq!(missing.status_code(), 404);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn unknown_kinds_events_and_broken_configs_are_rejected() {
+        let server = handle_test_startup().await;
+
+        for body in [
+            json!({ "name": "x", "kind": "pager", "events": [], "config": {} }),
+            json!({ "name": "x", "kind": "ntfy", "events": ["played"], "config": { "topic": "t" } }),
+            json!({ "name": "x", "kind": "gotify", "events": [], "config": { "serverUrl": "https://gotify.example.com" } }),
+        ] {
+            let response = server
+                .test_server
+                .post("/api/v1/settings/notification-channels")
+                .json(&body)
+                .await;
+            assert_eq!(response.status_code(), 400, "{body}");
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn non_admin_cannot_list_channels() {
+        let _server = handle_test_startup().await;
+        let user = UserTestDataBuilder::new().build();
+
+        let result = super::get_notification_channels(
+            State(crate::app_state::AppState::new()),
+            Extension(user),
+        )
+        .await;
+        match result {
+            Err(err) => assert!(matches!(err.inner, CustomErrorInner::Forbidden(_))),
+            Ok(_) => panic!("expected forbidden error for get_notification_channels"),
+        }
+    }
+}
@@ -1,50 +1,203 @@
 use crate::app_state::AppState;
 use crate::notification::{self, Notification, NotificationId};
-use axum::Json;
-use axum::extract::State;
-use common_infrastructure::error::CustomError;
+use crate::services::notification::rules::NotificationRuleInput;
+use axum::extract::{Path, State};
+use axum::{Extension, Json};
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::notification_rule::NotificationRule;
+use podfetch_domain::user::User;
 use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationRuleDto {
+    pub id: String,
+    pub podcast_id: Option<String>,
+    pub tag_id: Option<String>,
+    pub min_duration_seconds: Option<i32>,
+    pub max_duration_seconds: Option<i32>,
+    pub title_regex: Option<String>,
+    pub created_at: String,
+}
+
+impl From<NotificationRule> for NotificationRuleDto {
+    fn from(rule: NotificationRule) -> Self {
+        Self {
+            id: rule.id.to_string(),
+            podcast_id: rule.podcast_id.map(|podcast_id| podcast_id.to_string()),
+            tag_id: rule.tag_id,
+            min_duration_seconds: rule.min_duration_seconds,
+            max_duration_seconds: rule.max_duration_seconds,
+            title_regex: rule.title_regex,
+            created_at: rule.created_at.and_utc().to_rfc3339(),
+        }
+    }
+}
+
+/// Leave `podcastId` and `tagId` empty for a rule covering all podcasts.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationRuleUpsert {
+    pub podcast_id: Option<String>,
+    pub tag_id: Option<String>,
+    /// Only episodes at least this long (in seconds) match.
+    pub min_duration_seconds: Option<i32>,
+    /// Only episodes at most this long (in seconds) match.
+    pub max_duration_seconds: Option<i32>,
+    /// Regular expression the episode title must match, e.g. `(?i)interview`.
+    pub title_regex: Option<String>,
+}
+
+impl TryFrom<NotificationRuleUpsert> for NotificationRuleInput {
+    type Error = CustomError;
+
+    fn try_from(upsert: NotificationRuleUpsert) -> Result<Self, Self::Error> {
+        Ok(Self {
+            podcast_id: upsert
+                .podcast_id
+                .as_deref()
+                .map(|podcast_id| parse_uuid(podcast_id, "podcast id"))
+                .transpose()?,
+            tag_id: upsert.tag_id.filter(|tag_id| !tag_id.is_empty()),
+            min_duration_seconds: upsert.min_duration_seconds,
+            max_duration_seconds: upsert.max_duration_seconds,
+            title_regex: upsert.title_regex.filter(|pattern| !pattern.is_empty()),
+        })
+    }
+}
+
+fn parse_uuid(id: &str, what: &str) -> Result<Uuid, CustomError> {
+    Uuid::parse_str(id).map_err(|_| {
+        CustomErrorInner::BadRequest(format!("{what} must be a valid id"), Warning).into()
+    })
+}
 
 #[utoipa::path(
 get,
 path="/notifications/unread",
 responses(
-(status = 200, description = "Gets all unread notifications.",body= Vec<Notification>)),
+(status = 200, description = "Gets the unread notifications of the current user.",body= Vec<Notification>)),
 tag="notifications"
 )]
 pub async fn get_unread_notifications(
     State(state): State<AppState>,
+    Extension(requester): Extension<User>,
 ) -> Result<Json<Vec<Notification>>, CustomError> {
-    notification::get_unread_notifications(state.notification_service.as_ref()).map(Json)
+    notification::get_unread_notifications(state.notification_service.as_ref(), requester.id)
+        .map(Json)
 }
 
 #[utoipa::path(
 put,
 path="/notifications/dismiss",
 responses(
-(status = 200, description = "Dismisses a notification")),
+(status = 200, description = "Dismisses a notification for the current user")),
 tag="notifications"
 )]
 pub async fn dismiss_notifications(
     State(state): State<AppState>,
+    Extension(requester): Extension<User>,
     Json(id): Json<NotificationId>,
 ) -> Result<StatusCode, CustomError> {
-    let notification_uuid = uuid::Uuid::parse_str(&id.id).map_err(|_| {
-        CustomError::from(common_infrastructure::error::CustomErrorInner::BadRequest(
-            "notification id must be a valid id".to_string(),
-            common_infrastructure::error::ErrorSeverity::Warning,
-        ))
-    })?;
-    notification::dismiss_notification(state.notification_service.as_ref(), notification_uuid)?;
+    let notification_uuid = parse_uuid(&id.id, "notification id")?;
+    notification::dismiss_notification(
+        state.notification_service.as_ref(),
+        notification_uuid,
+        requester.id,
+    )?;
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+get,
+path="/notifications/rules",
+responses(
+(status = 200, description = "The new-episode alert rules of the current user. Without rules every new episode notifies.", body = Vec<NotificationRuleDto>)),
+tag="notifications"
+)]
+pub async fn get_notification_rules(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+) -> Result<Json<Vec<NotificationRuleDto>>, CustomError> {
+    let rules = state.notification_rule_service.list(requester.id)?;
+    Ok(Json(rules.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+post,
+path="/notifications/rules",
+request_body = NotificationRuleUpsert,
+responses(
+(status = 200, description = "The created rule.", body = NotificationRuleDto),
+(status = 400, description = "Unknown podcast or tag, or invalid conditions.")),
+tag="notifications"
+)]
+pub async fn create_notification_rule(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+    Json(upsert): Json<NotificationRuleUpsert>,
+) -> Result<Json<NotificationRuleDto>, CustomError> {
+    let rule = state
+        .notification_rule_service
+        .create(requester.id, upsert.try_into()?)?;
+    Ok(Json(rule.into()))
+}
+
+#[utoipa::path(
+put,
+path="/notifications/rules/{id}",
+request_body = NotificationRuleUpsert,
+responses(
+(status = 200, description = "The updated rule.", body = NotificationRuleDto),
+(status = 400, description = "Unknown podcast or tag, or invalid conditions."),
+(status = 404, description = "The current user has no such rule.")),
+tag="notifications"
+)]
+pub async fn update_notification_rule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(requester): Extension<User>,
+    Json(upsert): Json<NotificationRuleUpsert>,
+) -> Result<Json<NotificationRuleDto>, CustomError> {
+    let rule = state.notification_rule_service.update(
+        requester.id,
+        parse_uuid(&id, "rule id")?,
+        upsert.try_into()?,
+    )?;
+    Ok(Json(rule.into()))
+}
+
+#[utoipa::path(
+delete,
+path="/notifications/rules/{id}",
+responses(
+(status = 204, description = "The rule was removed."),
+(status = 404, description = "The current user has no such rule.")),
+tag="notifications"
+)]
+pub async fn delete_notification_rule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(requester): Extension<User>,
+) -> Result<StatusCode, CustomError> {
+    state
+        .notification_rule_service
+        .delete(requester.id, parse_uuid(&id, "rule id")?)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub fn get_notification_router() -> OpenApiRouter<AppState> {
     OpenApiRouter::new()
         .routes(routes!(get_unread_notifications))
         .routes(routes!(dismiss_notifications))
+        .routes(routes!(get_notification_rules, create_notification_rule))
+        .routes(routes!(update_notification_rule, delete_notification_rule))
 }
 
 #[cfg(test)]
@@ -53,7 +206,8 @@ mod tests {
     use crate::services::notification::service::NotificationService;
     use crate::test_support::tests::handle_test_startup;
     use crate::test_utils::test_builder::notification_test_builder::tests::NotificationTestDataBuilder;
-    use serde_json::json;
+    use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
+    use serde_json::{Value, json};
     use serial_test::serial;
 
     fn assert_client_error_status(status: u16) {
@@ -199,6 +353,7 @@ mod tests {
             message: "should-be-returned".to_string(),
             created_at: "2026-03-14 10:00:00".to_string(),
             status: "unread".to_string(),
+            user_id: None,
         })
         .unwrap();
         NotificationService::create_notification(Notification {
@@ -207,6 +362,7 @@ mod tests {
             message: "should-be-filtered".to_string(),
             created_at: "2026-03-14 11:00:00".to_string(),
             status: "dismissed".to_string(),
+            user_id: None,
         })
         .unwrap();
 
@@ -232,6 +388,7 @@ mod tests {
             message: "older-message".to_string(),
             created_at: "2026-03-14 08:00:00".to_string(),
             status: "unread".to_string(),
+            user_id: None,
         })
         .unwrap();
         NotificationService::create_notification(Notification {
@@ -240,6 +397,7 @@ mod tests {
             message: "newer-message".to_string(),
             created_at: "2026-03-14 12:00:00".to_string(),
             status: "unread".to_string(),
+            user_id: None,
         })
         .unwrap();
 
@@ -395,4 +553,227 @@ mod tests {
             .json::<Vec<Notification>>();
         assert_eq!(unread_after.len(), 1);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_notifications_of_other_users_are_not_visible() {
+        let test_server = handle_test_startup().await;
+        let other = crate::app_state::AppState::new()
+            .user_admin_service
+            .create_user(UserTestDataBuilder::new().build())
+            .unwrap();
+
+        NotificationService::create_notification(Notification {
+            user_id: Some(other.id.to_string()),
+            ..NotificationTestDataBuilder::new().build().into()
+        })
+        .unwrap();
+        NotificationService::create_notification(NotificationTestDataBuilder::new().build().into())
+            .unwrap();
+
+        let unread = test_server
+            .test_server
+            .get("/api/v1/notifications/unread")
+            .await
+            .json::<Vec<Notification>>();
+        assert_eq!(unread.len(), 1);
+        assert_eq!(unread[0].user_id, None);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_notification_rules_can_be_created_updated_and_deleted() {
+        let test_server = handle_test_startup().await;
+
+        let created = test_server
+            .test_server
+            .post("/api/v1/notifications/rules")
+            .json(&json!({ "minDurationSeconds": 1200, "titleRegex": "(?i)interview" }))
+            .await;
+        assert_eq!(created.status_code(), 200);
+        let created = created.json::<Value>();
+        assert_eq!(created["minDurationSeconds"], json!(1200));
+        let id = created["id"].as_str().unwrap().to_string();
+
+        let updated = test_server
+            .test_server
+            .put(&format!("/api/v1/notifications/rules/{id}"))
+            .json(&json!({ "maxDurationSeconds": 600 }))
+            .await;
+        assert_eq!(updated.status_code(), 200);
+        let updated = updated.json::<Value>();
+        assert_eq!(updated["minDurationSeconds"], Value::Null);
+        assert_eq!(updated["maxDurationSeconds"], json!(600));
+
+        let rules = test_server
+            .test_server
+            .get("/api/v1/notifications/rules")
+            .await
+            .json::<Vec<Value>>();
+        assert_eq!(rules.len(), 1);
+
+        let deleted = test_server
+            .test_server
+            .delete(&format!("/api/v1/notifications/rules/{id}"))
+            .await;
+        assert_eq!(deleted.status_code(), 204);
+        let deleted_again = test_server
+            .test_server
+            .delete(&format!("/api/v1/notifications/rules/{id}"))
+            .await;
+        assert_eq!(deleted_again.status_code(), 404);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_notification_rules_reject_invalid_conditions() {
+        let test_server = handle_test_startup().await;
+
+        for body in [
+            json!({ "titleRegex": "(unclosed" }),
+            json!({ "minDurationSeconds": 600, "maxDurationSeconds": 60 }),
+            json!({ "podcastId": "not-a-uuid" }),
+            json!({ "podcastId": uuid::Uuid::new_v4().to_string() }),
+            json!({ "tagId": "unknown-tag" }),
+        ] {
+            let response = test_server
+                .test_server
+                .post("/api/v1/notifications/rules")
+                .json(&body)
+                .await;
+            assert_eq!(response.status_code(), 400, "{body}");
+        }
+    }
+
+    /// A podcast with one episode, and three users: one who favors the
+    /// podcast, one subscribed to its feed through gPodder and one who does
+    /// neither.
+    fn podcast_with_audience() -> (
+        podfetch_persistence::podcast_episode::PodcastEpisodeEntity,
+        uuid::Uuid,
+        uuid::Uuid,
+        uuid::Uuid,
+    ) {
+        use diesel::prelude::*;
+        use podfetch_domain::favorite::{Favorite, FavoriteRepository};
+        use podfetch_domain::subscription::SubscriptionRepository;
+        use podfetch_persistence::adapters::SubscriptionRepositoryImpl;
+        use podfetch_persistence::db::{database, get_connection};
+        use podfetch_persistence::favorite::DieselFavoriteRepository;
+        use podfetch_persistence::schema::podcast_episodes::dsl as pe_dsl;
+
+        let slug = format!("audience-{}", uuid::Uuid::new_v4());
+        let feed = format!("https://example.com/{slug}.xml");
+        let podcast = crate::services::podcast::service::PodcastService::add_podcast_to_database(
+            "Audience Test Cast",
+            &slug,
+            &feed,
+            "http://localhost:8080/ui/default.jpg",
+            &slug,
+        )
+        .unwrap();
+        let episode = diesel::insert_into(pe_dsl::podcast_episodes)
+            .values((
+                pe_dsl::id.eq(uuid::Uuid::new_v4().to_string()),
+                pe_dsl::podcast_id.eq(podcast.id.clone()),
+                pe_dsl::episode_id.eq(format!("{slug}-1")),
+                pe_dsl::name.eq("A short one".to_string()),
+                pe_dsl::url.eq(format!("https://example.com/{slug}.mp3")),
+                pe_dsl::date_of_recording.eq("2026-03-01T00:00:00Z".to_string()),
+                pe_dsl::image_url.eq("http://localhost:8080/ui/default.jpg".to_string()),
+                pe_dsl::total_time.eq(600),
+                pe_dsl::description.eq(String::new()),
+                pe_dsl::guid.eq(format!("{slug}-guid")),
+                pe_dsl::deleted.eq(false),
+                pe_dsl::episode_numbering_processed.eq(false),
+            ))
+            .get_result::<podfetch_persistence::podcast_episode::PodcastEpisodeEntity>(
+                &mut get_connection(),
+            )
+            .unwrap();
+
+        let users = crate::app_state::AppState::new().user_admin_service;
+        let [fan, gpodder_user, stranger] = [(); 3].map(|_| {
+            users
+                .create_user(UserTestDataBuilder::new().build())
+                .unwrap()
+                .id
+        });
+        DieselFavoriteRepository::new(database())
+            .upsert(Favorite::new(
+                fan,
+                uuid::Uuid::parse_str(&podcast.id).unwrap(),
+                true,
+            ))
+            .unwrap();
+        SubscriptionRepositoryImpl::new(database())
+            .update_subscriptions("phone", gpodder_user, &[feed], &[])
+            .unwrap();
+        (episode, fan, gpodder_user, stranger)
+    }
+
+    fn unread_of(user_id: uuid::Uuid, type_of_message: &str) -> usize {
+        NotificationService::default_service()
+            .get_unread_notifications(user_id)
+            .unwrap()
+            .into_iter()
+            .filter(|notification| {
+                notification.type_of_message == type_of_message
+                    && notification.user_id == Some(user_id.to_string())
+            })
+            .count()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_new_episodes_notify_subscribers_whose_rules_match() {
+        use crate::services::notification::rules::{
+            EpisodeFacts, NotificationRuleInput, NotificationRuleService,
+        };
+
+        let _test_server = handle_test_startup().await;
+        let (episode, fan, gpodder_user, stranger) = podcast_with_audience();
+        let rules = NotificationRuleService::default_service();
+        let facts = EpisodeFacts {
+            podcast_id: uuid::Uuid::parse_str(&episode.podcast_id).unwrap(),
+            title: &episode.name,
+            duration_seconds: episode.total_time,
+        };
+
+        let mut expected = vec![fan, gpodder_user];
+        expected.sort();
+        assert_eq!(rules.recipients(&facts).unwrap(), expected);
+        assert!(!rules.recipients(&facts).unwrap().contains(&stranger));
+
+        rules
+            .create(
+                gpodder_user,
+                NotificationRuleInput {
+                    min_duration_seconds: Some(20 * 60),
+                    ..NotificationRuleInput::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(rules.recipients(&facts).unwrap(), vec![fan]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_failed_downloads_notify_the_requester_or_the_subscribers() {
+        use crate::usecases::podcast_episode::PodcastEpisodeUseCase;
+        use common_infrastructure::error::{CustomErrorInner, ErrorSeverity};
+
+        let _test_server = handle_test_startup().await;
+        let (episode, fan, gpodder_user, stranger) = podcast_with_audience();
+        let err = CustomErrorInner::Unknown(ErrorSeverity::Error).into();
+
+        PodcastEpisodeUseCase::notify_download_failed(&episode, &err, Some(stranger));
+        assert_eq!(unread_of(stranger, "DownloadFailed"), 1);
+        assert_eq!(unread_of(fan, "DownloadFailed"), 0);
+
+        PodcastEpisodeUseCase::notify_download_failed(&episode, &err, None);
+        assert_eq!(unread_of(fan, "DownloadFailed"), 1);
+        assert_eq!(unread_of(gpodder_user, "DownloadFailed"), 1);
+        assert_eq!(unread_of(stranger, "DownloadFailed"), 1);
+    }
 }
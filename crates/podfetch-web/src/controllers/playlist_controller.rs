@@ -327,6 +327,7 @@ mod tests {
             Json(PlaylistDtoPost {
                 name: "Hacker Rename".to_string(),
                 items: vec![],
+                smart: None,
             }),
         )
         .await;
@@ -1092,4 +1093,96 @@ mod tests {
             .unwrap();
         assert_eq!(rows_after, 0);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_smart_playlist_is_evaluated_from_its_rules() {
+        let server = handle_test_startup().await;
+        let unique = Uuid::new_v4().to_string();
+        let podcast_slug = format!("smart-playlist-podcast-{unique}");
+
+        let podcast = crate::services::podcast::service::PodcastService::add_podcast_to_database(
+            &format!("Smart Playlist Podcast {unique}"),
+            &podcast_slug,
+            &format!("https://example.com/{podcast_slug}.xml"),
+            "http://localhost:8080/ui/default.jpg",
+            &podcast_slug,
+        )
+        .unwrap();
+        let first = insert_episode(
+            &podcast.id,
+            &format!("smart-playlist-episode-1-{unique}"),
+            &format!("smart-playlist-guid-1-{unique}"),
+            "Smart Playlist Episode 1",
+        );
+        insert_episode(
+            &podcast.id,
+            &format!("smart-playlist-episode-2-{unique}"),
+            &format!("smart-playlist-guid-2-{unique}"),
+            "Smart Playlist Episode 2",
+        );
+
+        let create_response = server
+            .test_server
+            .post("/api/v1/playlist")
+            .json(&json!({
+                "name": unique_name("Smart Playlist"),
+                "smart": {"podcastIds": [podcast.id.clone()], "maxTotalSeconds": 3600}
+            }))
+            .await;
+        assert_eq!(create_response.status_code(), 200);
+        let created = create_response.json::<serde_json::Value>();
+        assert_eq!(created["smart"]["podcastIds"], json!([podcast.id.clone()]));
+        assert_eq!(created["items"].as_array().unwrap().len(), 2);
+        let playlist_id = created["id"].as_str().unwrap().to_string();
+
+        let rows = pli_dsl::playlist_items
+            .filter(pli_dsl::playlist_id.eq(&playlist_id))
+            .select(count_star())
+            .get_result::<i64>(&mut get_connection())
+            .unwrap();
+        assert_eq!(rows, 0);
+
+        let delete_item = server
+            .test_server
+            .delete(&format!(
+                "/api/v1/playlist/{}/episode/{}",
+                playlist_id, first.id
+            ))
+            .await;
+        assert_eq!(delete_item.status_code(), 400);
+
+        let update_response = server
+            .test_server
+            .put(&format!("/api/v1/playlist/{playlist_id}"))
+            .json(&json!({
+                "name": unique_name("Smart Playlist"),
+                "smart": {"podcastIds": [podcast.id.clone()], "limit": 1}
+            }))
+            .await;
+        assert_eq!(update_response.status_code(), 200);
+        assert_eq!(
+            update_response.json::<serde_json::Value>()["items"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_smart_playlist_rejects_unknown_podcast() {
+        let server = handle_test_startup().await;
+
+        let create_response = server
+            .test_server
+            .post("/api/v1/playlist")
+            .json(&json!({
+                "name": unique_name("Broken Smart Playlist"),
+                "smart": {"podcastIds": [Uuid::new_v4().to_string()]}
+            }))
+            .await;
+        assert_client_error_status(create_response.status_code().as_u16());
+    }
 }
@@ -1,9 +1,12 @@
 use crate::app_state::AppState;
+use crate::client_ip::ClientIp;
 use crate::controllers::controller_utils::{get_default_image, unwrap_string};
 use crate::controllers::id_resolver::{ResolvedId, parse_resolved_id};
+use crate::services::feed_health::service::FeedHealthService;
 use crate::services::podcast::service::PodcastService;
+use crate::services::tag::service::TagService;
+use crate::settings::{OpmlFeed, read_opml_feeds};
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
-use async_recursion::async_recursion;
 use axum::body::Body;
 use axum::extract::{Path, Query, State};
 use axum::http::HeaderMap;
@@ -12,21 +15,22 @@ use axum::response::IntoResponse;
 use axum::{Extension, Json, debug_handler};
 use axum_extra::extract::OptionalQuery;
 use common_infrastructure::config::{BASIC_AUTH, OIDC_AUTH};
+use common_infrastructure::feed::parse_feed;
 use common_infrastructure::http::COMMON_USER_AGENT;
 use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
-use opml::{OPML, Outline};
+use podfetch_domain::api_token::ApiTokenScope;
+use podfetch_domain::audit_log::AuditAction;
 use rand::RngExt;
 use rand::rngs::ThreadRng;
-use rss::Channel;
 use serde_json::Value;
 use std::thread;
 use tokio::task::spawn_blocking;
 
 use crate::filter::Filter;
 pub use crate::podcast::{
-    DeletePodcast, OpmlModel, PodcastAddModel, PodcastFavorUpdateModel, PodcastInsertModel,
-    PodcastRSSAddModel, PodcastSearchModelUtoipa, PodcastSearchReturn, PodcastUpdateNameRequest,
-    ProxyPodcastParams,
+    DeletePodcast, OpmlModel, PodcastAddModel, PodcastFavorUpdateModel, PodcastFeedHealthDto,
+    PodcastInsertModel, PodcastRSSAddModel, PodcastSearchModelUtoipa, PodcastSearchReturn,
+    PodcastUpdateNameRequest, ProxyPodcastParams,
     SearchType::{ITunes, Podindex},
 };
 use crate::podcast::{
@@ -44,7 +48,7 @@ use podfetch_domain::favorite_podcast_episode::FavoritePodcastEpisode;
 use podfetch_domain::podcast::PodcastRepository;
 use podfetch_domain::user::User;
 use podfetch_persistence::db::database;
-use podfetch_persistence::podcast::DieselPodcastRepository;
+use podfetch_persistence::podcast::{DieselPodcastRepository, PodcastEntity};
 use reqwest::header::HeaderMap as ReqwestHeaderMap;
 use tokio::runtime::Runtime;
 
@@ -326,9 +330,11 @@ pub async fn add_podcast_by_feed(
         .await
         .map_err(map_reqwest_error)?;
 
-    let bytes = result.text().await.unwrap();
+    let content_type = response_content_type(&result);
+    let bytes = result.bytes().await.map_err(map_reqwest_error)?;
 
-    let channel = Channel::read_from(bytes.as_bytes()).unwrap();
+    let channel = parse_feed(&bytes, content_type.as_deref())
+        .map_err(|e| CustomErrorInner::BadRequest(e.to_string(), ErrorSeverity::Warning))?;
     let num = rand::rng().random_range(100..10000000);
 
     let server_url = resolve_server_url_from_headers(&headers);
@@ -364,8 +370,12 @@ pub async fn import_podcasts_from_opml(
     requester: Extension<User>,
     Json(opml): Json<OpmlModel>,
 ) -> Result<StatusCode, CustomError> {
-    let document = OPML::from_str(&opml.content).unwrap();
-    let outline_count = count_opml_feeds(&document.body.outlines);
+    let feeds = read_opml_feeds(&opml.content).map_err(|error| {
+        CustomError::from(CustomErrorInner::BadRequest(
+            format!("Invalid OPML file: {error}"),
+            ErrorSeverity::Warning,
+        ))
+    })?;
     let current_count = DieselPodcastRepository::new(database())
         .count_by_added_by(requester.id)
         .map_err(CustomError::from)?;
@@ -373,18 +383,19 @@ pub async fn import_podcasts_from_opml(
         requester.is_privileged_user(),
         ENVIRONMENT_SERVICE.user_podcast_limit,
         current_count,
-        outline_count,
+        feeds.len() as u32,
     )
     .map_err(map_podcast_error)?;
+    let feeds = opml_feeds_for_requester(feeds, &requester);
 
     let user_id = requester.id;
     spawn_blocking(move || {
-        for outline in document.body.outlines {
+        for feed in feeds {
             let added_by = user_id;
             thread::spawn(move || {
                 let rt = Runtime::new().unwrap();
                 let rng = rand::rng();
-                rt.block_on(insert_outline(outline.clone(), rng.clone(), Some(added_by)));
+                rt.block_on(insert_outline(feed, rng, added_by));
             });
         }
     });
@@ -392,19 +403,6 @@ pub async fn import_podcasts_from_opml(
     Ok(StatusCode::OK)
 }
 
-fn count_opml_feeds(outlines: &[Outline]) -> u32 {
-    outlines
-        .iter()
-        .map(|o| {
-            if o.outlines.is_empty() {
-                if o.xml_url.is_some() { 1 } else { 0 }
-            } else {
-                count_opml_feeds(&o.outlines)
-            }
-        })
-        .sum()
-}
-
 #[utoipa::path(
 post,
 path="/podcasts/podindex",
@@ -590,6 +588,23 @@ pub async fn get_favored_podcasts(
     Ok(Json(podcasts))
 }
 
+#[utoipa::path(
+get,
+path="/podcasts/feed-health",
+responses(
+(status = 200, description = "Podcasts whose feed failed on the last refresh, longest failing \
+first.", body = [PodcastFeedHealthDto])),
+tag="podcasts"
+)]
+pub async fn get_unhealthy_feeds(
+    Extension(requester): Extension<User>,
+) -> Result<Json<Vec<PodcastFeedHealthDto>>, CustomError> {
+    require_privileged::<CustomError>(requester.is_privileged_user()).map_err(map_podcast_error)?;
+
+    let unhealthy = FeedHealthService::default_service().list_unhealthy()?;
+    Ok(Json(unhealthy))
+}
+
 #[utoipa::path(
 put,
 path="/podcasts/{id}/active",
@@ -609,30 +624,28 @@ pub async fn update_active_podcast(
     Ok(StatusCode::OK)
 }
 
-#[async_recursion(?Send)]
-async fn insert_outline(podcast: Outline, mut rng: ThreadRng, added_by: Option<uuid::Uuid>) {
-    if !podcast.outlines.is_empty() {
-        for outline_nested in podcast.clone().outlines {
-            insert_outline(outline_nested, rng.clone(), added_by).await;
-        }
-        return;
-    }
-    let feed_url = podcast.clone().xml_url;
-    if feed_url.is_none() {
-        return;
-    }
+fn response_content_type(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
 
+async fn insert_outline(feed: OpmlFeed, mut rng: ThreadRng, added_by: uuid::Uuid) {
     let feed_response = get_http_client(&ENVIRONMENT_SERVICE)
-        .get(feed_url.unwrap())
+        .get(&feed.xml_url)
         .send()
         .await;
     if feed_response.is_err() {
         ChatServerHandle::broadcast_opml_error(feed_response.err().unwrap().to_string());
         return;
     }
-    let content = feed_response.unwrap().bytes().await.unwrap();
+    let feed_response = feed_response.unwrap();
+    let content_type = response_content_type(&feed_response);
+    let content = feed_response.bytes().await.unwrap();
 
-    let channel = Channel::read_from(&content[..]);
+    let channel = parse_feed(&content, content_type.as_deref());
 
     match channel {
         Ok(channel) => {
@@ -646,22 +659,31 @@ async fn insert_outline(podcast: Outline, mut rng: ThreadRng, added_by: Option<u
                 }
             };
 
-            let inserted_podcast = PodcastService::handle_insert_of_podcast(
+            let inserted_podcast = PodcastService::handle_insert_of_podcast_with_settings(
                 PodcastInsertModel {
-                    feed_url: podcast.clone().xml_url.expect("No feed url"),
+                    feed_url: feed.xml_url.clone(),
                     title: channel.clone().title.to_string(),
                     id: rng.random::<i32>(),
                     image_url,
                 },
                 Some(channel),
-                added_by,
+                Some(added_by),
+                feed.settings.clone(),
             )
             .await;
             match inserted_podcast {
                 Ok(podcast) => {
+                    apply_opml_organization(&podcast, &feed, added_by);
                     ChatServerHandle::broadcast_opml_added(&podcast);
                 }
                 Err(e) => {
+                    // The podcast is already on this instance, but the
+                    // importing user may not have it in their tags yet.
+                    if matches!(e.inner, CustomErrorInner::Conflict(_, _))
+                        && let Ok(podcast) = PodcastService::get_podcast_by_rss_feed(&feed.xml_url)
+                    {
+                        apply_opml_tags(&podcast, &feed, added_by);
+                    }
                     ChatServerHandle::broadcast_opml_error(e.to_string());
                 }
             }
@@ -671,6 +693,49 @@ async fn insert_outline(podcast: Outline, mut rng: ThreadRng, added_by: Option<u
         }
     }
 }
+
+/// Drops the podcast settings and active flag of an import by a user who may
+/// not change them through their own endpoints either. Tags are per user and
+/// stay.
+fn opml_feeds_for_requester(feeds: Vec<OpmlFeed>, requester: &User) -> Vec<OpmlFeed> {
+    if requester.is_privileged_user() {
+        return feeds;
+    }
+    feeds
+        .into_iter()
+        .map(|feed| OpmlFeed {
+            active: None,
+            settings: None,
+            ..feed
+        })
+        .collect()
+}
+
+/// Restores the tags and active flag a PodFetch export carried for a newly
+/// added podcast. Its settings were stored while inserting it.
+fn apply_opml_organization(podcast: &PodcastEntity, feed: &OpmlFeed, user_id: uuid::Uuid) {
+    apply_opml_tags(podcast, feed, user_id);
+    if feed.active == Some(false)
+        && let Ok(podcast_id) = uuid::Uuid::parse_str(&podcast.id)
+        && let Err(err) = PodcastService::set_podcast_active(podcast_id, false)
+    {
+        tracing::warn!(
+            "Could not deactivate imported podcast {}: {err}",
+            podcast.name
+        );
+    }
+}
+
+fn apply_opml_tags(podcast: &PodcastEntity, feed: &OpmlFeed, user_id: uuid::Uuid) {
+    let Ok(podcast_id) = uuid::Uuid::parse_str(&podcast.id) else {
+        return;
+    };
+    if let Err(err) =
+        TagService::default_service().tag_podcast_by_names(user_id, podcast_id, &feed.tags)
+    {
+        tracing::warn!("Could not tag imported podcast {}: {err}", podcast.name);
+    }
+}
 use crate::podcast::PodcastDto;
 use crate::podcast::{map_podcast_to_dto, map_podcast_with_context_to_dto};
 use crate::podcast_episode_dto::PodcastEpisodeDto;
@@ -700,6 +765,7 @@ pub async fn delete_podcast(
     State(state): State<AppState>,
     Path(id): Path<String>,
     Extension(requester): Extension<User>,
+    ClientIp(ip): ClientIp,
     Json(data): Json<DeletePodcast>,
 ) -> Result<StatusCode, CustomError> {
     require_privileged::<CustomError>(requester.is_privileged_user()).map_err(map_podcast_error)?;
@@ -718,14 +784,24 @@ pub async fn delete_podcast(
     state.tag_service.delete_podcast_tags(podcast_uuid)?;
 
     PodcastService::delete_podcast(podcast_uuid)?;
+    state.audit_log_service.record(
+        &requester,
+        AuditAction::PodcastDeleted,
+        Some(podcast_uuid.to_string()),
+        ip,
+    );
     Ok(StatusCode::OK)
 }
+use crate::services::transcoding::stream::{
+    StreamSource, StreamTranscodeParams, transcoded_stream,
+};
 use axum::response::Response;
 use common_infrastructure::error::ErrorSeverity::Debug;
 
 #[utoipa::path(
 get,
 path="/proxy/podcast",
+params(StreamTranscodeParams),
 responses(
 (status = 200, description = "Proxies a podcast so people can stream podcasts from the remote \
 server")),
@@ -734,6 +810,7 @@ tag="podcasts"
 pub(crate) async fn proxy_podcast(
     State(state): State<AppState>,
     Query(params): Query<ProxyPodcastParams>,
+    Query(transcode): Query<StreamTranscodeParams>,
     OptionalQuery(api_key): OptionalQuery<RSSAPiKey>,
     req: axum::extract::Request,
 ) -> Result<axum::http::response::Response<Body>, CustomError> {
@@ -746,7 +823,11 @@ pub(crate) async fn proxy_podcast(
     ensure_proxy_api_access::<CustomError, _>(
         is_auth_enabled,
         api_key.and_then(|q| q.api_key),
-        |key| state.user_auth_service.is_api_key_valid(key),
+        |key| {
+            state
+                .user_auth_service
+                .is_api_key_valid(key, ApiTokenScope::Stream)
+        },
     )
     .map_err(map_proxy_podcast_error)?;
 
@@ -754,6 +835,15 @@ pub(crate) async fn proxy_podcast(
         PodcastEpisodeService::get_podcast_episode_by_id(&params.episode_id)?,
     )
     .map_err(map_proxy_podcast_error)?;
+    if let Some(encoding) = transcode.encoding()? {
+        return transcoded_stream(
+            state.audiobookshelf_hls_transcoder.clone(),
+            StreamSource::for_episode(&episode)?,
+            encoding,
+            transcode.start_segment()?,
+        )
+        .await;
+    }
     sanitize_proxy_request_headers(headers);
 
     let cloned_headers = headers.clone();
@@ -794,6 +884,7 @@ pub(crate) async fn proxy_podcast(
 #[utoipa::path(
 get,
 path="/proxy/podcast/apiKey/{apiKey}",
+params(StreamTranscodeParams),
 responses(
 (status = 200, description = "Proxies a podcast (API key in path)")),
 tag="podcasts"
@@ -802,12 +893,13 @@ pub(crate) async fn proxy_podcast_with_path_api_key(
     State(state): State<AppState>,
     Path(api_key): Path<String>,
     Query(params): Query<ProxyPodcastParams>,
+    transcode: Query<StreamTranscodeParams>,
     req: axum::extract::Request,
 ) -> Result<axum::http::response::Response<Body>, CustomError> {
     let api_key_query = OptionalQuery(Some(RSSAPiKey {
         api_key: Some(api_key),
     }));
-    proxy_podcast(State(state), Query(params), api_key_query, req).await
+    proxy_podcast(State(state), Query(params), transcode, api_key_query, req).await
 }
 
 #[utoipa::path(
@@ -891,6 +983,13 @@ pub async fn retrieve_podcast_sample_format(
         sponsorblock_enabled: true,
         nfo_format: "off".to_string(),
         cover_filename: "image".to_string(),
+        audit_log_retention_days: 365,
+        storage_quota_mb: 0,
+        storage_quota_policy: "refuse".to_string(),
+        dead_feed_deactivation_days: 30,
+        transcoding_profile_id: None,
+        loudness_analysis: false,
+        skip_detection: false,
     };
     let result = perform_podcast_variable_replacement(settings.into(), podcast, None);
 
@@ -915,6 +1014,7 @@ pub fn get_podcast_router() -> OpenApiRouter<AppState> {
         .routes(routes!(refresh_all_podcasts))
         .routes(routes!(download_podcast))
         .routes(routes!(favorite_podcast))
+        .routes(routes!(get_unhealthy_feeds))
         .routes(routes!(update_active_podcast))
         .routes(routes!(delete_podcast))
         .routes(routes!(update_podcast_settings))
@@ -928,6 +1028,7 @@ pub fn get_podcast_router() -> OpenApiRouter<AppState> {
 #[cfg(test)]
 pub mod tests {
     use crate::app_state::AppState;
+    use crate::client_ip::ClientIp;
     use crate::controllers::podcast_controller::PodcastUpdateNameRequest;
     use crate::controllers::podcast_controller::find_podcast;
     use crate::podcast::{OpmlModel, PodcastAddModel, PodcastRSSAddModel, SearchType};
@@ -1144,6 +1245,158 @@ pub mod tests {
         assert_eq!(persisted.name, original_name);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_failing_feeds_are_listed_until_the_podcast_is_reactivated() {
+        let ts_server = handle_test_startup().await;
+        let saved_podcast =
+            crate::services::podcast::service::PodcastService::add_podcast_to_database(
+                &unique_name("failing-collection"),
+                &unique_name("Failing Feed Podcast"),
+                "https://example.com/failing-feed.xml",
+                "https://example.com/failing-image.jpg",
+                &unique_name("failing-feed-id"),
+            )
+            .unwrap();
+        let error = CustomErrorInner::BadRequest(
+            "Feed server answered with HTTP 410 Gone".to_string(),
+            common_infrastructure::error::ErrorSeverity::Warning,
+        )
+        .into();
+        crate::services::feed_health::service::FeedHealthService::default_service().record_failure(
+            &saved_podcast,
+            Some(410),
+            &error,
+        );
+
+        let unhealthy = ts_server
+            .test_server
+            .get("/api/v1/podcasts/feed-health")
+            .await;
+        assert_eq!(unhealthy.status_code(), 200);
+        let unhealthy = unhealthy.json::<Vec<crate::podcast::PodcastFeedHealthDto>>();
+        let entry = unhealthy
+            .iter()
+            .find(|entry| entry.podcast_id == saved_podcast.id)
+            .expect("failing feed is listed");
+        assert_eq!(entry.consecutive_failures, 1);
+        assert_eq!(entry.last_http_status, Some(410));
+        assert!(entry.failing_since.is_some());
+
+        for _ in 0..2 {
+            let resp = ts_server
+                .test_server
+                .put(&format!("/api/v1/podcasts/{}/active", saved_podcast.id))
+                .await;
+            assert_eq!(resp.status_code(), 200);
+        }
+        let unhealthy = ts_server
+            .test_server
+            .get("/api/v1/podcasts/feed-health")
+            .await
+            .json::<Vec<crate::podcast::PodcastFeedHealthDto>>();
+        assert!(
+            unhealthy
+                .iter()
+                .all(|entry| entry.podcast_id != saved_podcast.id)
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_opml_import_rejects_malformed_documents() {
+        let ts_server = handle_test_startup().await;
+
+        let resp = ts_server
+            .test_server
+            .post("/api/v1/podcasts/opml")
+            .json(&json!({
+                "content": "<opml><body><outline xmlUrl=\"https://example.com/a.xml\"></body></opml>"
+            }))
+            .await;
+
+        assert_eq!(resp.status_code(), 400);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_opml_tags_and_active_flag_are_reapplied_on_import() {
+        let _ts_server = handle_test_startup().await;
+        let user_id = app_state()
+            .user_auth_service
+            .find_by_username(
+                &common_infrastructure::runtime::ENVIRONMENT_SERVICE
+                    .username
+                    .clone()
+                    .unwrap_or_else(|| "postgres".to_string()),
+            )
+            .unwrap()
+            .id;
+        let tag_service = crate::services::tag::service::TagService::default_service();
+        let existing_tag = tag_service
+            .create_tag(user_id, "Evening".to_string(), None, "Blue".to_string())
+            .unwrap();
+        let saved_podcast =
+            crate::services::podcast::service::PodcastService::add_podcast_to_database(
+                &unique_name("imported-collection"),
+                &unique_name("Imported Podcast"),
+                "https://example.com/imported.xml",
+                "https://example.com/imported.jpg",
+                &unique_name("imported-id"),
+            )
+            .unwrap();
+        let feed = crate::settings::OpmlFeed {
+            xml_url: saved_podcast.rssfeed.clone(),
+            tags: vec!["Evening".to_string(), "Long reads".to_string()],
+            active: Some(false),
+            settings: None,
+        };
+
+        super::apply_opml_organization(&saved_podcast, &feed, user_id);
+        // A second import of the same file must not tag the podcast twice.
+        super::apply_opml_organization(&saved_podcast, &feed, user_id);
+
+        let podcast_id = Uuid::parse_str(&saved_podcast.id).unwrap();
+        let mut tags = tag_service
+            .get_tags_of_podcast(podcast_id, user_id)
+            .unwrap()
+            .into_iter()
+            .map(|tag| (tag.name, tag.id))
+            .collect::<Vec<_>>();
+        tags.sort();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0], ("Evening".to_string(), existing_tag.id));
+        assert_eq!(tags[1].0, "Long reads");
+        assert!(
+            !crate::services::podcast::service::PodcastService::get_podcast(podcast_id)
+                .unwrap()
+                .active
+        );
+    }
+
+    #[test]
+    fn test_opml_settings_and_active_flag_need_privileges() {
+        let feed = crate::settings::OpmlFeed {
+            xml_url: "https://example.com/imported.xml".to_string(),
+            tags: vec!["Evening".to_string()],
+            active: Some(false),
+            settings: Some(PodcastSetting {
+                podcast_prefill: 2,
+                ..Default::default()
+            }),
+        };
+
+        let imported = super::opml_feeds_for_requester(vec![feed.clone()], &non_privileged_user());
+        assert_eq!(imported[0].tags, feed.tags);
+        assert_eq!(imported[0].active, None);
+        assert_eq!(imported[0].settings, None);
+
+        let mut admin = non_privileged_user();
+        admin.role = "admin".to_string();
+        let imported = super::opml_feeds_for_requester(vec![feed.clone()], &admin);
+        assert_eq!(imported, vec![feed]);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_update_name_of_podcast_returns_forbidden_for_non_admin() {
@@ -1228,6 +1481,13 @@ pub mod tests {
             nfo_format: "off".to_string(),
             cover_filename: "cover".to_string(),
             auto_transcribe: false,
+            refresh_mode: "adaptive".to_string(),
+            refresh_interval: 120,
+            retention_keep_newest: 5,
+            retention_played_days: 0,
+            retention_keep_unplayed: true,
+            retention_max_size_mb: 0,
+            transcoding_profile_id: Some("original".to_string()),
         };
 
         let update_resp = ts_server
@@ -1247,6 +1507,44 @@ pub mod tests {
         assert!(persisted.episode_numbering);
         assert!(persisted.auto_download);
         assert_eq!(persisted.replacement_strategy, "replace-with-dash");
+        assert_eq!(persisted.refresh_mode, "adaptive");
+        assert_eq!(persisted.refresh_interval, 120);
+        assert_eq!(persisted.retention_keep_newest, 5);
+        assert!(persisted.retention_keep_unplayed);
+        assert_eq!(
+            persisted.transcoding_profile_id.as_deref(),
+            Some("original")
+        );
+
+        let unknown_profile = ts_server
+            .test_server
+            .put(&format!("/api/v1/podcasts/{}/settings", saved_podcast.id))
+            .json(&PodcastSetting {
+                transcoding_profile_id: Some(uuid::Uuid::new_v4().to_string()),
+                ..update_payload.clone()
+            })
+            .await;
+        assert_eq!(unknown_profile.status_code(), 400);
+
+        let negative_retention = ts_server
+            .test_server
+            .put(&format!("/api/v1/podcasts/{}/settings", saved_podcast.id))
+            .json(&PodcastSetting {
+                retention_max_size_mb: -1,
+                ..update_payload.clone()
+            })
+            .await;
+        assert_eq!(negative_retention.status_code(), 400);
+
+        let invalid_mode = ts_server
+            .test_server
+            .put(&format!("/api/v1/podcasts/{}/settings", saved_podcast.id))
+            .json(&PodcastSetting {
+                refresh_mode: "hourly".to_string(),
+                ..update_payload
+            })
+            .await;
+        assert_eq!(invalid_mode.status_code(), 400);
     }
 
     #[tokio::test]
@@ -1408,6 +1706,7 @@ pub mod tests {
             State(app_state()),
             Path("1".to_string()),
             Extension(non_privileged.clone()),
+            ClientIp(None),
             Json(super::DeletePodcast {
                 delete_files: false,
             }),
@@ -1514,6 +1813,60 @@ pub mod tests {
         assert_eq!(resp.status_code(), 404);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_proxy_podcast_rejects_unsupported_stream_encodings() {
+        use diesel::ExpressionMethods;
+        use diesel::RunQueryDsl;
+        use podfetch_persistence::db::get_connection;
+        use podfetch_persistence::schema::podcast_episodes::dsl as pe_dsl;
+
+        let ts_server = handle_test_startup().await;
+        let podcast = crate::services::podcast::service::PodcastService::add_podcast_to_database(
+            &unique_name("proxy-transcode-collection"),
+            &unique_name("Proxy Transcode Podcast"),
+            "https://example.com/proxy-transcode-feed.xml",
+            "https://example.com/proxy-transcode-image.jpg",
+            &unique_name("proxy-transcode-id"),
+        )
+        .unwrap();
+        let episode_id = unique_name("proxy-transcode-episode");
+        diesel::insert_into(pe_dsl::podcast_episodes)
+            .values((
+                pe_dsl::id.eq(podfetch_domain::ids::new_id().to_string()),
+                pe_dsl::podcast_id.eq(podcast.id.clone()),
+                pe_dsl::episode_id.eq(episode_id.clone()),
+                pe_dsl::name.eq("Proxy Transcode Episode".to_string()),
+                pe_dsl::url.eq("https://example.com/proxy-transcode.mp3".to_string()),
+                pe_dsl::date_of_recording.eq("2026-02-27T00:00:00Z".to_string()),
+                pe_dsl::image_url.eq("http://localhost:8080/ui/default.jpg".to_string()),
+                pe_dsl::total_time.eq(1800),
+                pe_dsl::description.eq("test description".to_string()),
+                pe_dsl::guid.eq(unique_name("proxy-transcode-guid")),
+                pe_dsl::deleted.eq(false),
+                pe_dsl::episode_numbering_processed.eq(false),
+            ))
+            .execute(&mut get_connection())
+            .unwrap();
+
+        let api_key = create_api_key_user();
+
+        for query in [
+            "codec=opus",
+            "bitrate=1000",
+            "codec=aac&bitrate=8",
+            "codec=mp3&t=-6",
+        ] {
+            let resp = ts_server
+                .test_server
+                .get(&format!(
+                    "/proxy/podcast/apiKey/{api_key}?episodeId={episode_id}&{query}"
+                ))
+                .await;
+            assert_eq!(resp.status_code(), 400, "{query}");
+        }
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_proxy_podcast_with_path_api_key_rejects_invalid_key() {
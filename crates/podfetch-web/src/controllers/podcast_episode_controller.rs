@@ -1,4 +1,5 @@
 use crate::app_state::AppState;
+use crate::client_ip::ClientIp;
 use crate::controllers::id_resolver::{ResolvedId, parse_resolved_id};
 use crate::history::EpisodeDto;
 use crate::history::map_episode_to_dto;
@@ -16,6 +17,7 @@ use crate::podcast_episode::{
 };
 use crate::podcast_episode_dto::PodcastEpisodeDto;
 use crate::server::ChatServerHandle;
+use crate::services::download::queue::{DownloadQueueService, MANUAL_PRIORITY};
 use crate::services::file::service::perform_episode_variable_replacement;
 use crate::services::podcast::service::PodcastService;
 use crate::settings::Setting;
@@ -30,6 +32,8 @@ use axum::http::StatusCode;
 use axum::{Extension, Json};
 use common_infrastructure::error::ErrorSeverity::Warning;
 use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::audit_log::AuditAction;
+use podfetch_domain::podcast_episode::{EpisodeListFilter, EpisodeSort};
 use podfetch_domain::user::User;
 use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
 use utoipa_axum::router::OpenApiRouter;
@@ -74,6 +78,9 @@ pub async fn find_all_chapters_of_podcast_episode(
             title: v.title,
             start_time: v.start_time,
             end_time: v.end_time,
+            href: v.href,
+            image: v.image,
+            source: v.source.as_str().to_string(),
         })
         .collect();
 
@@ -138,6 +145,7 @@ pub async fn find_all_podcast_episodes_of_podcast(
 ) -> Result<Json<Vec<PodcastEpisodeWithHistory>>, CustomError> {
     let server_url = resolve_server_url_from_headers(&headers);
     let podcast_uuid = resolve_podcast_uuid(&id)?;
+    let filter = episode_list_filter(&last_podcast_episode)?;
     let mapped_podcasts = web_get_podcast_episodes_with_history(
         podcast_uuid,
         &user.username,
@@ -148,6 +156,7 @@ pub async fn find_all_podcast_episodes_of_podcast(
                 podcast_id,
                 last_episode,
                 only_unlistened,
+                &filter,
                 &user,
             )
             .map(|episodes| {
@@ -177,6 +186,26 @@ pub async fn find_all_podcast_episodes_of_podcast(
     Ok(Json(mapped_podcasts))
 }
 
+fn episode_list_filter(query: &OptionalId) -> Result<EpisodeListFilter, CustomError> {
+    let sort = match query.sort.as_deref() {
+        None => EpisodeSort::default(),
+        Some(sort) => EpisodeSort::from_str(sort).ok_or_else(|| {
+            CustomError::from(CustomErrorInner::BadRequest(
+                format!("Unknown episode sort {sort}"),
+                Warning,
+            ))
+        })?,
+    };
+    Ok(EpisodeListFilter {
+        season: query.season,
+        episode_type: query
+            .episode_type
+            .as_ref()
+            .map(|episode_type| episode_type.to_lowercase()),
+        sort,
+    })
+}
+
 #[utoipa::path(
     get,
     path="/podcasts/available/gpodder",
@@ -266,7 +295,7 @@ pub async fn like_podcast_episode(
 put,
 path="/podcasts/{id}/episodes/download",
 responses(
-(status = 200, description = "Starts the download of a given podcast episode")),
+(status = 200, description = "Queues the download of a given podcast episode")),
 tag = "podcast_episodes"
 )]
 pub async fn download_podcast_episodes_of_podcast(
@@ -276,65 +305,24 @@ pub async fn download_podcast_episodes_of_podcast(
     web_require_privileged::<CustomError>(requester.is_privileged_user())
         .map_err(map_podcast_episode_controller_error)?;
 
-    spawn_single_episode_download(id);
+    queue_single_episode_download(id, requester.id);
 
     Ok(StatusCode::from_u16(200).unwrap())
 }
 
-/// Download a single episode (identified by its RSS `episode_id`) on a blocking
-/// worker, then mark it available and broadcast the change. Shared by the
-/// per-episode download endpoint and the inbox "queue for download" action.
-pub(crate) fn spawn_single_episode_download(episode_id: String) {
+/// Put a single episode (identified by its RSS `episode_id`) on the download
+/// queue, ahead of automatic downloads. Shared by the per-episode download
+/// endpoint and the inbox "queue for download" action.
+pub(crate) fn queue_single_episode_download(episode_id: String, requested_by: uuid::Uuid) {
     tokio::task::spawn_blocking(move || {
         match PodcastEpisodeService::get_podcast_episode_by_id(&episode_id) {
             Ok(Some(podcast_episode)) => {
-                let podcast_uuid = match uuid::Uuid::parse_str(&podcast_episode.podcast_id) {
-                    Ok(uuid) => uuid,
-                    Err(_) => {
-                        tracing::error!(
-                            "Invalid podcast id {} for episode {}",
-                            podcast_episode.podcast_id,
-                            podcast_episode.episode_id
-                        );
-                        return;
-                    }
-                };
-                match PodcastService::get_podcast(podcast_uuid) {
-                    Ok(podcast_found) => {
-                        if let Err(err) = PodcastEpisodeService::perform_download(
-                            &podcast_episode,
-                            &podcast_found,
-                        ) {
-                            tracing::error!(
-                                "Error downloading episode {}: {}",
-                                podcast_episode.episode_id,
-                                err
-                            );
-                            return;
-                        }
-                        if let Err(err) = PodcastEpisodeService::update_deleted(
-                            &podcast_episode.episode_id,
-                            false,
-                        ) {
-                            tracing::error!(
-                                "Error updating deleted status for episode {}: {}",
-                                podcast_episode.episode_id,
-                                err
-                            );
-                        }
-                        ChatServerHandle::broadcast_podcast_episode_offline_available(
-                            &podcast_episode,
-                            &podcast_found,
-                        );
-                    }
-                    Err(err) => {
-                        tracing::error!(
-                            "Could not load podcast {} for episode {}: {}",
-                            podcast_episode.podcast_id,
-                            podcast_episode.episode_id,
-                            err
-                        );
-                    }
+                if let Err(err) = DownloadQueueService::default_service().enqueue(
+                    &podcast_episode,
+                    MANUAL_PRIORITY,
+                    Some(requested_by),
+                ) {
+                    tracing::error!("Error queueing download of episode {episode_id}: {err}");
                 }
             }
             Ok(None) => {
@@ -425,7 +413,9 @@ pub async fn download_all_missing_episodes(
 
     tokio::task::spawn_blocking(move || {
         let podcast = PodcastService::get_podcast_by_id(podcast_id);
-        if let Err(err) = PodcastEpisodeService::download_missing_episodes_for_podcast(&podcast) {
+        if let Err(err) =
+            PodcastEpisodeService::download_missing_episodes_for_podcast(&podcast, requester.id)
+        {
             tracing::error!("download-all failed for podcast {podcast_id}: {err}");
         }
     });
@@ -463,6 +453,7 @@ pub async fn download_episode_range(
             &podcast,
             range.from as usize,
             range.to as usize,
+            requester.id,
         ) {
             tracing::error!("download-range failed for podcast {podcast_id}: {err}");
         }
@@ -488,7 +479,9 @@ pub async fn resync_files_for_podcast(
 
     tokio::task::spawn_blocking(move || {
         let podcast = PodcastService::get_podcast_by_id(podcast_id);
-        if let Err(err) = PodcastEpisodeService::redownload_missing_files_for_podcast(&podcast) {
+        if let Err(err) =
+            PodcastEpisodeService::redownload_missing_files_for_podcast(&podcast, requester.id)
+        {
             tracing::error!("resync-files failed for podcast {podcast_id}: {err}");
         }
     });
@@ -528,8 +521,10 @@ responses(
 tag = "podcast_episodes"
 )]
 pub async fn delete_all_downloaded_files(
+    State(state): State<AppState>,
     Extension(requester): Extension<User>,
     Path(id): Path<String>,
+    ClientIp(ip): ClientIp,
 ) -> Result<Json<BatchActionResponse>, CustomError> {
     web_require_privileged::<CustomError>(requester.is_admin())
         .map_err(map_podcast_episode_controller_error)?;
@@ -541,6 +536,12 @@ pub async fn delete_all_downloaded_files(
     .await
     .unwrap()?;
 
+    state.audit_log_service.record(
+        &requester,
+        AuditAction::DownloadsDeleted,
+        Some(podcast_id.to_string()),
+        ip,
+    );
     Ok(Json(BatchActionResponse { affected }))
 }
 
@@ -574,6 +575,11 @@ pub async fn retrieve_episode_sample_format(
         episode_numbering_processed: false,
         download_location: None,
         youtube_video_id: None,
+        season: None,
+        episode_number: None,
+        episode_type: None,
+        loudness_lufs: None,
+        loudness_true_peak: None,
     };
     let settings = Setting {
         id: uuid::Uuid::nil().to_string(),
@@ -595,6 +601,13 @@ pub async fn retrieve_episode_sample_format(
         sponsorblock_enabled: true,
         nfo_format: "off".to_string(),
         cover_filename: "image".to_string(),
+        audit_log_retention_days: 365,
+        storage_quota_mb: 0,
+        storage_quota_policy: "refuse".to_string(),
+        dead_feed_deactivation_days: 30,
+        transcoding_profile_id: None,
+        loudness_analysis: false,
+        skip_detection: false,
     };
     let result = perform_episode_variable_replacement(settings.into(), episode, None, 1, None)?;
 
@@ -622,9 +635,12 @@ pub fn get_podcast_episode_router() -> OpenApiRouter<AppState> {
 #[cfg(test)]
 mod tests {
     use crate::app_state::AppState;
+    use crate::client_ip::ClientIp;
+    use crate::services::download::queue::DownloadQueueService;
     use crate::services::favorite_podcast_episode::service::FavoritePodcastEpisodeService;
     use crate::test_support::tests::handle_test_startup;
     use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
+    use crate::usecases::podcast_episode::PodcastEpisodeUseCase;
     use axum::Extension;
     use axum::extract::{Path, State};
     use chrono::Utc;
@@ -633,6 +649,7 @@ mod tests {
     use diesel::ExpressionMethods;
     use diesel::QueryDsl;
     use diesel::RunQueryDsl;
+    use podfetch_domain::episode_download::DownloadJobStatus;
     use podfetch_domain::user::User;
     use podfetch_persistence::db::get_connection;
     use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
@@ -1219,6 +1236,66 @@ mod tests {
         assert_eq!(response.status_code(), 202);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_redownload_queues_episodes_with_missing_files() {
+        let _server = handle_test_startup().await;
+        let unique = Uuid::new_v4().to_string();
+        let slug = format!("redownload-podcast-{unique}");
+
+        let podcast = crate::services::podcast::service::PodcastService::add_podcast_to_database(
+            &unique_name("Redownload Podcast"),
+            &slug,
+            &format!("https://example.com/{slug}.xml"),
+            "http://localhost:8080/ui/default.jpg",
+            &slug,
+        )
+        .unwrap();
+        let missing = insert_episode(
+            &podcast.id,
+            &format!("redownload-missing-{unique}"),
+            &format!("redownload-missing-guid-{unique}"),
+            "Missing file",
+        );
+        mark_episode_downloaded(&missing, "./podcasts/definitely-not-a-real-file.mp3");
+        let never_downloaded = insert_episode(
+            &podcast.id,
+            &format!("redownload-new-{unique}"),
+            &format!("redownload-new-guid-{unique}"),
+            "Never downloaded",
+        );
+
+        let requester = crate::app_state::AppState::new()
+            .user_admin_service
+            .create_user(UserTestDataBuilder::new().build())
+            .unwrap();
+
+        let queued =
+            PodcastEpisodeUseCase::redownload_missing_files_for_podcast(&podcast, requester.id)
+                .unwrap();
+
+        assert_eq!(queued, 1);
+        let persisted = pe_dsl::podcast_episodes
+            .filter(pe_dsl::id.eq(missing.id.clone()))
+            .first::<PodcastEpisode>(&mut get_connection())
+            .unwrap();
+        assert!(persisted.download_location.is_none());
+        assert!(persisted.file_episode_path.is_none());
+        let queued_jobs = DownloadQueueService::default_service()
+            .list(Some(DownloadJobStatus::Pending))
+            .unwrap();
+        let job = queued_jobs
+            .iter()
+            .find(|job| job.episode_id.to_string() == missing.id)
+            .unwrap();
+        assert_eq!(job.requested_by, Some(requester.id));
+        assert!(
+            !queued_jobs
+                .iter()
+                .any(|job| job.episode_id.to_string() == never_downloaded.id)
+        );
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_batch_actions_reject_non_admin_users() {
@@ -1268,8 +1345,13 @@ mod tests {
                 Ok(_) => panic!("expected forbidden for download_episode_range"),
             }
 
-            let delete_all =
-                super::delete_all_downloaded_files(Extension(caller), Path("1".to_string())).await;
+            let delete_all = super::delete_all_downloaded_files(
+                State(AppState::new()),
+                Extension(caller),
+                Path("1".to_string()),
+                ClientIp(None),
+            )
+            .await;
             match delete_all {
                 Err(err) => assert!(matches!(err.inner, CustomErrorInner::Forbidden(_))),
                 Ok(_) => panic!("expected forbidden for delete_all_downloaded_files"),
@@ -0,0 +1,408 @@
+//! Unified search over podcasts, episode titles and show notes, and
+//! transcripts, backed by the database's full-text index.
+
+use crate::app_state::AppState;
+use crate::controllers::transcript_controller::TranscriptSearchHitDto;
+use crate::podcast::{PodcastDto, map_podcast_to_dto};
+use crate::podcast_episode_dto::PodcastEpisodeDto;
+use crate::services::podcast::service::PodcastService;
+use crate::services::search::service::{EpisodeSearchResult, SearchResults};
+use crate::url_rewriting::resolve_server_url_from_headers;
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::{Extension, Json};
+use chrono::NaiveDate;
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::search::SearchFilter;
+use podfetch_domain::user::User;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchParams {
+    /// Words match as prefixes, `"quoted words"` as an exact phrase.
+    pub q: String,
+    pub podcast_id: Option<String>,
+    pub tag_id: Option<String>,
+    /// Only downloaded (`true`) or only not yet downloaded (`false`) episodes.
+    pub downloaded: Option<bool>,
+    /// Earliest recording date (`YYYY-MM-DD`), inclusive.
+    pub from: Option<String>,
+    /// Latest recording date (`YYYY-MM-DD`), inclusive.
+    pub to: Option<String>,
+    /// Zero-based page.
+    pub page: Option<i64>,
+}
+
+/// Highlights wrap the matched words in `<b>`/`</b>`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PodcastSearchResultDto {
+    pub podcast: PodcastDto,
+    pub name_highlight: String,
+    pub summary_snippet: String,
+    pub rank: f32,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeSearchResultDto {
+    pub episode: PodcastEpisodeDto,
+    /// `None` when only the transcript matched.
+    pub name_highlight: Option<String>,
+    pub description_snippet: Option<String>,
+    pub transcript_hits: Vec<TranscriptSearchHitDto>,
+    pub rank: f32,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResultDto {
+    pub podcasts: Vec<PodcastSearchResultDto>,
+    pub episodes: Vec<EpisodeSearchResultDto>,
+}
+
+impl EpisodeSearchResultDto {
+    fn new(result: EpisodeSearchResult, requester: &User, server_url: &str) -> Self {
+        Self {
+            episode: PodcastEpisodeDto::from_episode_with_user(
+                result.episode,
+                Some(requester.clone()),
+                None,
+                server_url,
+            ),
+            name_highlight: result.name_highlight,
+            description_snippet: result.description_snippet,
+            transcript_hits: result
+                .transcript_hits
+                .into_iter()
+                .map(|hit| TranscriptSearchHitDto {
+                    transcript_id: hit.transcript_id.to_string(),
+                    start_ms: hit.start_ms,
+                    snippet: hit.snippet,
+                    rank: hit.rank,
+                })
+                .collect(),
+            rank: result.rank,
+        }
+    }
+}
+
+fn bad_request(message: &str) -> CustomError {
+    CustomErrorInner::BadRequest(message.to_string(), Warning).into()
+}
+
+fn parse_uuid(value: Option<&str>, name: &str) -> Result<Option<Uuid>, CustomError> {
+    value
+        .map(|value| Uuid::parse_str(value).map_err(|_| bad_request(&format!("invalid {name}"))))
+        .transpose()
+}
+
+fn parse_date(value: Option<&str>, name: &str) -> Result<Option<NaiveDate>, CustomError> {
+    value
+        .map(|value| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| bad_request(&format!("invalid {name}, expected YYYY-MM-DD")))
+        })
+        .transpose()
+}
+
+#[utoipa::path(
+    get,
+    path = "/search",
+    params(SearchParams),
+    responses(
+        (status = 200, description = "Podcasts and episodes matching the query, best match first. \
+        Episode results include the matching transcript segments.", body = SearchResultDto)
+    ),
+    tag = "search"
+)]
+pub async fn search(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<SearchParams>,
+    Extension(requester): Extension<User>,
+) -> Result<Json<SearchResultDto>, CustomError> {
+    let server_url = resolve_server_url_from_headers(&headers);
+    let filter = SearchFilter {
+        podcast_id: parse_uuid(params.podcast_id.as_deref(), "podcastId")?,
+        tag_id: parse_uuid(params.tag_id.as_deref(), "tagId")?,
+        downloaded: params.downloaded,
+        from: parse_date(params.from.as_deref(), "from")?,
+        to: parse_date(params.to.as_deref(), "to")?,
+    };
+    let page = params.page.unwrap_or(0).max(0);
+
+    let SearchResults { podcasts, episodes } =
+        state
+            .search_service
+            .search(params.q.trim(), &filter, &requester, page)?;
+
+    let mut podcast_results = Vec::with_capacity(podcasts.len());
+    for hit in podcasts {
+        let podcast = match PodcastService::get_podcast(hit.podcast_id) {
+            Ok(podcast) => podcast,
+            // Deleted since the search ran.
+            Err(e) if matches!(e.inner, CustomErrorInner::NotFound(_)) => continue,
+            Err(e) => return Err(e),
+        };
+        podcast_results.push(PodcastSearchResultDto {
+            podcast: map_podcast_to_dto(podcast.into(), &server_url),
+            name_highlight: hit.name_highlight,
+            summary_snippet: hit.summary_snippet,
+            rank: hit.rank,
+        });
+    }
+
+    Ok(Json(SearchResultDto {
+        podcasts: podcast_results,
+        episodes: episodes
+            .into_iter()
+            .map(|result| EpisodeSearchResultDto::new(result, &requester, &server_url))
+            .collect(),
+    }))
+}
+
+pub fn get_search_router() -> OpenApiRouter<AppState> {
+    OpenApiRouter::new().routes(routes!(search))
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use crate::test_support::tests::handle_test_startup;
+    use diesel::prelude::*;
+    use podfetch_domain::podcast_episode_transcript::{
+        PodcastEpisodeTranscriptRepository, TranscriptSegment, TranscriptSource, TranscriptStatus,
+        UpsertTranscript,
+    };
+    use podfetch_persistence::adapters::PodcastEpisodeTranscriptRepositoryImpl;
+    use podfetch_persistence::db::{database, get_connection};
+    use podfetch_persistence::schema::podcast_episodes::dsl as pe_dsl;
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use uuid::Uuid;
+
+    fn unique(prefix: &str) -> String {
+        format!("{prefix}-{}", Uuid::new_v4())
+    }
+
+    /// Creates a podcast named `podcast_name` and returns its id.
+    fn seed_podcast(podcast_name: &str) -> String {
+        let slug = unique("search-podcast");
+        crate::services::podcast::service::PodcastService::add_podcast_to_database(
+            podcast_name,
+            &slug,
+            &format!("https://example.com/{slug}.xml"),
+            "http://localhost:8080/ui/default.jpg",
+            &slug,
+        )
+        .unwrap()
+        .id
+    }
+
+    fn seed_episode(podcast_id: &str, name: &str, description: &str, recorded: &str) -> Uuid {
+        let episode_id = Uuid::new_v4();
+        diesel::insert_into(pe_dsl::podcast_episodes)
+            .values((
+                pe_dsl::id.eq(episode_id.to_string()),
+                pe_dsl::podcast_id.eq(podcast_id.to_string()),
+                pe_dsl::episode_id.eq(unique("episode")),
+                pe_dsl::name.eq(name.to_string()),
+                pe_dsl::url.eq(format!("https://example.com/{episode_id}.mp3")),
+                pe_dsl::date_of_recording.eq(recorded.to_string()),
+                pe_dsl::image_url.eq("http://localhost:8080/ui/default.jpg".to_string()),
+                pe_dsl::total_time.eq(1800),
+                pe_dsl::description.eq(description.to_string()),
+                pe_dsl::guid.eq(unique("guid")),
+                pe_dsl::deleted.eq(false),
+                pe_dsl::episode_numbering_processed.eq(false),
+            ))
+            .execute(&mut get_connection())
+            .unwrap();
+        episode_id
+    }
+
+    fn seed_transcript(episode_id: Uuid, text: &str) {
+        let repo = PodcastEpisodeTranscriptRepositoryImpl::new(database());
+        let transcript_id = repo
+            .upsert(UpsertTranscript {
+                episode_id,
+                source: TranscriptSource::Feed,
+                original_url: Some(format!("https://example.com/{episode_id}.vtt")),
+                mime_type: "text/vtt".to_string(),
+                language: Some("en".to_string()),
+            })
+            .unwrap();
+        repo.replace_segments(
+            transcript_id,
+            &[TranscriptSegment {
+                idx: 0,
+                start_ms: Some(1500),
+                end_ms: Some(4000),
+                speaker: None,
+                text: text.to_string(),
+            }],
+        )
+        .unwrap();
+        repo.set_status(transcript_id, TranscriptStatus::Parsed, None)
+            .unwrap();
+    }
+
+    fn episode_ids(payload: &Value) -> Vec<String> {
+        payload["episodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| result["episode"]["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn search_ranks_highlights_and_merges_transcript_hits() {
+        let server = handle_test_startup().await;
+        let podcast_id = seed_podcast("Quaxling Weekly");
+        let titled = seed_episode(
+            &podcast_id,
+            "Quaxling deep dive",
+            "All about the quaxling migration",
+            "2026-03-01T00:00:00+00:00",
+        );
+        let transcribed = seed_episode(
+            &podcast_id,
+            "Unrelated title",
+            "Nothing to see",
+            "2025-01-01T00:00:00+00:00",
+        );
+        seed_transcript(transcribed, "and then we talked about the quaxlings");
+
+        let response = server.test_server.get("/api/v1/search?q=quaxl").await;
+        assert_eq!(response.status_code(), 200);
+        let payload = response.json::<Value>();
+
+        assert_eq!(payload["podcasts"][0]["podcast"]["id"], json!(podcast_id));
+        assert_eq!(
+            payload["podcasts"][0]["nameHighlight"],
+            json!("<b>Quaxling</b> Weekly")
+        );
+        assert_eq!(
+            episode_ids(&payload),
+            vec![titled.to_string(), transcribed.to_string()]
+        );
+        assert_eq!(
+            payload["episodes"][0]["nameHighlight"],
+            json!("<b>Quaxling</b> deep dive")
+        );
+        let transcript_only = &payload["episodes"][1];
+        assert_eq!(transcript_only["nameHighlight"], Value::Null);
+        assert_eq!(transcript_only["transcriptHits"][0]["startMs"], json!(1500));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn podcast_episode_query_returns_every_match() {
+        let server = handle_test_startup().await;
+        let podcast_id = seed_podcast("Paging Test Cast");
+        let total = crate::services::search::service::SEARCH_PAGE_SIZE as usize + 5;
+        for number in 0..total {
+            seed_episode(
+                &podcast_id,
+                &format!("Zorbleflux part {number}"),
+                "",
+                "2026-03-01T00:00:00+00:00",
+            );
+        }
+
+        let response = server
+            .test_server
+            .get("/api/v1/podcasts/zorbleflux/query")
+            .await;
+        assert_eq!(response.status_code(), 200);
+        assert_eq!(response.json::<Vec<Value>>().len(), total);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn search_supports_phrases_and_filters() {
+        let server = handle_test_startup().await;
+        let podcast_id = seed_podcast("Filter Test Cast");
+        let old = seed_episode(
+            &podcast_id,
+            "Zorblat open source",
+            "first",
+            "2024-05-01T00:00:00+00:00",
+        );
+        let new = seed_episode(
+            &podcast_id,
+            "Zorblat source open",
+            "second",
+            "2026-05-01T00:00:00+00:00",
+        );
+
+        let phrase = server
+            .test_server
+            .get("/api/v1/search?q=%22open%20source%22%20zorblat")
+            .await
+            .json::<Value>();
+        assert_eq!(episode_ids(&phrase), vec![old.to_string()]);
+
+        let dated = server
+            .test_server
+            .get("/api/v1/search?q=zorblat&from=2025-01-01&to=2026-12-31")
+            .await
+            .json::<Value>();
+        assert_eq!(episode_ids(&dated), vec![new.to_string()]);
+        // Podcasts have no recording date, so date filters leave them out.
+        assert!(dated["podcasts"].as_array().unwrap().is_empty());
+
+        let downloaded = server
+            .test_server
+            .get("/api/v1/search?q=zorblat&downloaded=true")
+            .await
+            .json::<Value>();
+        assert!(episode_ids(&downloaded).is_empty());
+
+        let other_podcast = server
+            .test_server
+            .get(&format!(
+                "/api/v1/search?q=zorblat&podcastId={}",
+                Uuid::new_v4()
+            ))
+            .await
+            .json::<Value>();
+        assert!(episode_ids(&other_podcast).is_empty());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn search_rejects_malformed_filters() {
+        let server = handle_test_startup().await;
+
+        let bad_date = server
+            .test_server
+            .get("/api/v1/search?q=anything&from=01.01.2024")
+            .await;
+        assert_eq!(bad_date.status_code(), 400);
+
+        let unknown_tag = server
+            .test_server
+            .get(&format!(
+                "/api/v1/search?q=anything&tagId={}",
+                Uuid::new_v4()
+            ))
+            .await;
+        assert_eq!(unknown_tag.status_code(), 404);
+
+        let empty = server.test_server.get("/api/v1/search?q=%22%22").await;
+        assert_eq!(empty.status_code(), 200);
+        assert_eq!(
+            empty.json::<Value>(),
+            json!({ "podcasts": [], "episodes": [] })
+        );
+    }
+}
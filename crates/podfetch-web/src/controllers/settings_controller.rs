@@ -1,21 +1,30 @@
 use crate::app_state::AppState;
+use crate::client_ip::ClientIp;
 use crate::services::episode_rescan::service::{
     EpisodeRescanService, RescanApplyStats, RescanOptions,
 };
 use crate::services::episode_scan::service::EpisodeScanServiceImpl;
 use crate::services::podcast::service::PodcastService;
+use crate::services::podcast_settings::service::PodcastSettingsService;
+use crate::services::retention::service::{RetentionCandidate, RetentionReason, RetentionService};
+use crate::services::tag::service::TagService;
+use crate::services::transcoding::service::TranscodingProfileService;
 use crate::settings::{
-    self, Mode, OpmlError, OpmlPodcast, RescanError, Setting, SettingsControllerError,
-    UpdateNameSettings,
+    self, Mode, OpmlError, OpmlExportParams, OpmlPodcast, OpmlScope, RescanError, Setting,
+    SettingsControllerError, UpdateNameSettings,
 };
 use crate::url_rewriting::resolve_server_url_from_headers;
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::HeaderMap;
 use axum::http::Response;
 use axum::{Extension, Json};
+use podfetch_domain::audit_log::AuditAction;
 use podfetch_domain::user::User;
 use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
 
 #[utoipa::path(
 get,
@@ -119,15 +128,26 @@ tag="settings"
 pub async fn update_settings(
     State(state): State<AppState>,
     Extension(requester): Extension<User>,
+    ClientIp(ip): ClientIp,
     Json(settings): Json<Setting>,
 ) -> Result<Json<Setting>, CustomError> {
-    settings::update_settings(
+    if requester.is_admin() {
+        TranscodingProfileService::default_service()
+            .validate_reference(settings.transcoding_profile_id.as_deref())?;
+    }
+    let settings = settings::update_settings(
         state.settings_service.as_ref(),
         requester.is_admin(),
         settings,
     )
-    .map(Json)
-    .map_err(map_settings_controller_error)
+    .map_err(map_settings_controller_error)?;
+    state.audit_log_service.record(
+        &requester,
+        AuditAction::SettingsUpdated,
+        Some("settings".to_string()),
+        ip,
+    );
+    Ok(Json(settings))
 }
 
 #[utoipa::path(
@@ -144,32 +164,127 @@ pub async fn run_cleanup(
     let settings =
         settings::cleanup_settings(state.settings_service.as_ref(), requester.is_admin())
             .map_err(map_settings_controller_error)?;
-    PodcastEpisodeService::cleanup_old_episodes(settings.auto_cleanup_days);
+    PodcastEpisodeService::cleanup_old_episodes(Some(settings.auto_cleanup_days));
     Ok(StatusCode::OK)
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct CleanupPreviewParams {
+    /// Only list downloads of this podcast.
+    pub podcast_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionCandidateDto {
+    pub podcast_id: String,
+    pub podcast_name: String,
+    pub episode_id: String,
+    pub episode_name: String,
+    pub bytes: i64,
+    pub reason: RetentionReason,
+}
+
+impl From<RetentionCandidate> for RetentionCandidateDto {
+    fn from(candidate: RetentionCandidate) -> Self {
+        Self {
+            podcast_id: candidate.podcast.id,
+            podcast_name: candidate.podcast.name,
+            episode_id: candidate.episode.id,
+            episode_name: candidate.episode.name,
+            bytes: candidate.bytes,
+            reason: candidate.reason,
+        }
+    }
+}
+
+#[utoipa::path(
+get,
+path="/settings/cleanup/preview",
+params(CleanupPreviewParams),
+responses(
+(status = 200, description = "Lists the downloads the next scheduled cleanup deletes, without deleting anything", body=Vec<RetentionCandidateDto>)),
+tag="settings"
+)]
+pub async fn preview_cleanup(
+    State(state): State<AppState>,
+    requester: Extension<User>,
+    Query(params): Query<CleanupPreviewParams>,
+) -> Result<Json<Vec<RetentionCandidateDto>>, CustomError> {
+    let settings =
+        settings::cleanup_settings(state.settings_service.as_ref(), requester.is_admin())
+            .map_err(map_settings_controller_error)?;
+    let global_cleanup_days = settings.auto_cleanup.then_some(settings.auto_cleanup_days);
+    let podcast_id = params
+        .podcast_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|_| {
+            CustomError::from(CustomErrorInner::BadRequest(
+                "Invalid podcast id".to_string(),
+                Warning,
+            ))
+        })?;
+    let candidates =
+        RetentionService::default_service().preview(global_cleanup_days, podcast_id)?;
+    Ok(Json(candidates.into_iter().map(Into::into).collect()))
+}
+
 #[utoipa::path(
 get,
 path="/settings/opml/{type_of}",
+params(OpmlExportParams),
 responses(
-(status = 200, description = "Gets the podcasts in opml format", body = String)),
+(status = 200, description = "Gets the podcasts in opml format, grouped by the requester's tags", body = String)),
 tag="settings"
 )]
 pub async fn get_opml(
     State(state): State<AppState>,
     Extension(requester): Extension<User>,
     Path(type_of): Path<String>,
+    Query(params): Query<OpmlExportParams>,
     headers: HeaderMap,
 ) -> Result<Response<String>, CustomError> {
-    let podcasts_found = PodcastService::get_all_podcasts_raw()?
-        .into_iter()
-        .map(|podcast| OpmlPodcast {
+    // Podcast settings can only be read by users who may change them.
+    if params.extended && !requester.is_privileged_user() {
+        return Err(map_opml_error(OpmlError::Forbidden));
+    }
+    let podcasts = match params.scope {
+        OpmlScope::Subscriptions => PodcastService::get_subscribed_podcasts_raw(requester.id)?,
+        OpmlScope::All => {
+            if !requester.is_privileged_user() {
+                return Err(map_opml_error(OpmlError::Forbidden));
+            }
+            PodcastService::get_all_podcasts_raw()?
+        }
+    };
+    let tag_service = TagService::default_service();
+    let mut podcasts_found = Vec::with_capacity(podcasts.len());
+    for podcast in podcasts {
+        let podcast_id = Uuid::parse_str(&podcast.id)
+            .map_err(|_| CustomError::from(CustomErrorInner::Unknown(Error)))?;
+        let tags = tag_service
+            .get_tags_of_podcast(podcast_id, requester.id)?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        let settings = if params.extended {
+            PodcastSettingsService::get_settings_for_podcast(podcast_id)?
+        } else {
+            None
+        };
+        podcasts_found.push(OpmlPodcast {
             id: podcast.id,
             name: podcast.name,
             summary: podcast.summary,
             rssfeed: podcast.rssfeed,
-        })
-        .collect();
+            tags,
+            active: podcast.active,
+            settings,
+        });
+    }
     let server_url = resolve_server_url_from_headers(&headers);
     let xml = settings::build_opml(
         podcasts_found,
@@ -177,6 +292,7 @@ pub async fn get_opml(
         requester.api_key.as_deref(),
         state.environment.any_auth_enabled,
         &server_url,
+        params.extended,
     )
     .map_err(map_opml_error)?;
     let response = Response::builder()
@@ -197,15 +313,22 @@ request_body=UpdateNameSettings
 pub async fn update_name(
     State(state): State<AppState>,
     Extension(requester): Extension<User>,
+    ClientIp(ip): ClientIp,
     Json(update_information): Json<UpdateNameSettings>,
 ) -> Result<Json<Setting>, CustomError> {
-    settings::update_name(
+    let settings = settings::update_name(
         state.settings_service.as_ref(),
         requester.is_admin(),
         update_information,
     )
-    .map(Json)
-    .map_err(map_settings_controller_error)
+    .map_err(map_settings_controller_error)?;
+    state.audit_log_service.record(
+        &requester,
+        AuditAction::SettingsUpdated,
+        Some("settings/name".to_string()),
+        ip,
+    );
+    Ok(Json(settings))
 }
 
 use common_infrastructure::error::ErrorSeverity::{Critical, Debug, Error, Warning};
@@ -238,6 +361,7 @@ fn map_opml_error(error: OpmlError) -> CustomError {
             CustomErrorInner::UnAuthorized("Please generate an api key".to_string(), Critical)
                 .into()
         }
+        OpmlError::Forbidden => CustomErrorInner::Forbidden(Warning).into(),
         OpmlError::Xml(_) => CustomErrorInner::Unknown(Error).into(),
     }
 }
@@ -247,6 +371,7 @@ pub fn get_settings_router() -> OpenApiRouter<AppState> {
         .routes(routes!(get_settings))
         .routes(routes!(update_settings))
         .routes(routes!(run_cleanup))
+        .routes(routes!(preview_cleanup))
         .routes(routes!(get_opml))
         .routes(routes!(update_name))
         .routes(routes!(rescan_episodes))
@@ -255,15 +380,22 @@ pub fn get_settings_router() -> OpenApiRouter<AppState> {
 #[cfg(test)]
 mod tests {
     use crate::app_state::AppState;
-    use crate::settings::{ReplacementStrategy, Setting};
+    use crate::client_ip::ClientIp;
+    use crate::podcast_settings::PodcastSetting;
+    use crate::services::podcast::service::PodcastService;
+    use crate::services::podcast_settings::service::PodcastSettingsService;
+    use crate::services::tag::service::TagService;
+    use crate::settings::{self, OpmlExportParams, OpmlScope, ReplacementStrategy, Setting};
     use crate::test_support::tests::handle_test_startup;
     use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
     use axum::Extension;
     use axum::Json;
-    use axum::extract::State;
+    use axum::extract::{Path, Query, State};
+    use axum::http::HeaderMap;
     use common_infrastructure::error::CustomErrorInner;
-    use diesel::RunQueryDsl;
+    use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
     use podfetch_persistence::db::get_connection;
+    use podfetch_persistence::schema::podcast_episodes::dsl as pe_dsl;
     use podfetch_persistence::schema::settings::dsl as s_dsl;
     use serde_json::json;
     use serial_test::serial;
@@ -344,6 +476,117 @@ mod tests {
         assert!(xml.contains("PodFetch Feed Export"));
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_get_opml_exports_subscriptions_grouped_by_tag() {
+        let server = handle_test_startup().await;
+        let admin = app_state()
+            .user_auth_service
+            .find_by_username(
+                &common_infrastructure::runtime::ENVIRONMENT_SERVICE
+                    .username
+                    .clone()
+                    .unwrap_or_else(|| "postgres".to_string()),
+            )
+            .unwrap();
+        let subscribed = PodcastService::add_podcast_to_database(
+            "Subscribed",
+            "opml-subscribed",
+            "https://example.com/subscribed.xml",
+            "http://localhost:8080/ui/default.jpg",
+            "opml-subscribed",
+        )
+        .unwrap();
+        PodcastService::add_podcast_to_database(
+            "Other",
+            "opml-other",
+            "https://example.com/other.xml",
+            "http://localhost:8080/ui/default.jpg",
+            "opml-other",
+        )
+        .unwrap();
+        let subscribed_id = uuid::Uuid::parse_str(&subscribed.id).unwrap();
+        PodcastService::update_favor_podcast(subscribed_id, true, admin.id).unwrap();
+        TagService::default_service()
+            .tag_podcast_by_names(admin.id, subscribed_id, &["Morning".to_string()])
+            .unwrap();
+        PodcastSettingsService::update_settings_for_podcast(PodcastSetting {
+            podcast_id: subscribed.id.clone(),
+            podcast_prefill: 2,
+            replacement_strategy: "remove".to_string(),
+            refresh_mode: "fixed".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let response = server
+            .test_server
+            .get("/api/v1/settings/opml/online?extended=true")
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let feeds = settings::read_opml_feeds(&response.text()).unwrap();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].xml_url, "https://example.com/subscribed.xml");
+        assert_eq!(feeds[0].tags, vec!["Morning"]);
+        assert_eq!(feeds[0].active, Some(true));
+        assert_eq!(feeds[0].settings.as_ref().unwrap().podcast_prefill, 2);
+
+        let response = server
+            .test_server
+            .get("/api/v1/settings/opml/online?scope=all")
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let xml = response.text();
+        assert!(xml.contains("https://example.com/other.xml"));
+        assert!(!xml.contains("podfetch:"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_opml_of_whole_instance_requires_privileges() {
+        let _server = handle_test_startup().await;
+
+        let result = super::get_opml(
+            State(app_state()),
+            Extension(non_admin_user()),
+            Path("online".to_string()),
+            Query(OpmlExportParams {
+                scope: OpmlScope::All,
+                extended: false,
+            }),
+            HeaderMap::new(),
+        )
+        .await;
+
+        match result {
+            Ok(_) => panic!("expected forbidden for a user without privileges"),
+            Err(err) => assert!(matches!(err.inner, CustomErrorInner::Forbidden(_))),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_extended_opml_requires_privileges() {
+        let _server = handle_test_startup().await;
+
+        let result = super::get_opml(
+            State(app_state()),
+            Extension(non_admin_user()),
+            Path("online".to_string()),
+            Query(OpmlExportParams {
+                scope: OpmlScope::Subscriptions,
+                extended: true,
+            }),
+            HeaderMap::new(),
+        )
+        .await;
+
+        match result {
+            Ok(_) => panic!("expected forbidden for a user without privileges"),
+            Err(err) => assert!(matches!(err.inner, CustomErrorInner::Forbidden(_))),
+        }
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_update_settings_persists_values() {
@@ -568,6 +811,77 @@ mod tests {
         assert_eq!(rescan_response.status_code(), 200);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_preview_cleanup_lists_downloads_beyond_keep_newest() {
+        let server = handle_test_startup().await;
+        let slug = format!("retention-{}", uuid::Uuid::new_v4());
+        let podcast = PodcastService::add_podcast_to_database(
+            &slug,
+            &slug,
+            &format!("https://example.com/{slug}.xml"),
+            "https://example.com/retention.jpg",
+            &slug,
+        )
+        .unwrap();
+        PodcastSettingsService::default_service()
+            .update_settings(PodcastSetting {
+                podcast_id: podcast.id.clone(),
+                replacement_strategy: "remove".to_string(),
+                refresh_mode: "fixed".to_string(),
+                retention_keep_newest: 1,
+                ..Default::default()
+            })
+            .unwrap();
+        let downloaded = |name: &str, recorded: &str| {
+            let id = uuid::Uuid::new_v4().to_string();
+            diesel::insert_into(pe_dsl::podcast_episodes)
+                .values((
+                    pe_dsl::id.eq(id.clone()),
+                    pe_dsl::podcast_id.eq(podcast.id.clone()),
+                    pe_dsl::episode_id.eq(format!("{slug}-{name}")),
+                    pe_dsl::name.eq(name.to_string()),
+                    pe_dsl::url.eq(format!("https://example.com/{id}.mp3")),
+                    pe_dsl::date_of_recording.eq(recorded.to_string()),
+                    pe_dsl::image_url.eq("https://example.com/retention.jpg".to_string()),
+                    pe_dsl::total_time.eq(1800),
+                    pe_dsl::description.eq("retention test".to_string()),
+                    pe_dsl::guid.eq(format!("{slug}-{name}")),
+                    pe_dsl::deleted.eq(false),
+                    pe_dsl::episode_numbering_processed.eq(false),
+                    pe_dsl::download_location.eq(Some("Local".to_string())),
+                    pe_dsl::download_time.eq(Some(chrono::Utc::now().naive_utc())),
+                ))
+                .execute(&mut get_connection())
+                .unwrap();
+            id
+        };
+        let older = downloaded("Older", "2026-03-01T00:00:00Z");
+        downloaded("Newer", "2026-03-08T00:00:00Z");
+
+        let response = server
+            .test_server
+            .get("/api/v1/settings/cleanup/preview")
+            .add_query_param("podcastId", &podcast.id)
+            .await;
+
+        assert_eq!(response.status_code(), 200);
+        let candidates = response.json::<serde_json::Value>();
+        let candidates = candidates.as_array().unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0]["episodeId"], older);
+        assert_eq!(candidates[0]["reason"], "keepNewest");
+        // A dry run leaves the download in place.
+        assert!(
+            pe_dsl::podcast_episodes
+                .filter(pe_dsl::id.eq(older))
+                .select(pe_dsl::download_location)
+                .first::<Option<String>>(&mut get_connection())
+                .unwrap()
+                .is_some()
+        );
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_get_settings_returns_not_found_when_settings_missing() {
@@ -599,9 +913,21 @@ mod tests {
             Ok(_) => panic!("expected forbidden error for run_cleanup"),
         }
 
+        let preview_result = super::preview_cleanup(
+            State(app_state()),
+            Extension(user.clone()),
+            Query(super::CleanupPreviewParams { podcast_id: None }),
+        )
+        .await;
+        match preview_result {
+            Err(err) => assert!(matches!(err.inner, CustomErrorInner::Forbidden(_))),
+            Ok(_) => panic!("expected forbidden error for preview_cleanup"),
+        }
+
         let update_result = super::update_settings(
             State(app_state()),
             Extension(user.clone()),
+            ClientIp(None),
             Json(Setting {
                 id: uuid::Uuid::new_v4().to_string(),
                 episode_numbering: false,
@@ -622,6 +948,13 @@ mod tests {
                 sponsorblock_enabled: true,
                 nfo_format: "off".to_string(),
                 cover_filename: "image".to_string(),
+                audit_log_retention_days: 365,
+                storage_quota_mb: 0,
+                storage_quota_policy: "refuse".to_string(),
+                dead_feed_deactivation_days: 30,
+                transcoding_profile_id: None,
+                loudness_analysis: false,
+                skip_detection: false,
             }),
         )
         .await;
@@ -633,6 +966,7 @@ mod tests {
         let update_name_result = super::update_name(
             State(app_state()),
             Extension(user.clone()),
+            ClientIp(None),
             Json(super::UpdateNameSettings {
                 use_existing_filename: true,
                 replace_invalid_characters: true,
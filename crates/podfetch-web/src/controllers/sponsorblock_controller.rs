@@ -20,6 +20,8 @@ pub struct SponsorSegmentDto {
     pub votes: i32,
     pub locked: bool,
     pub duration_mismatch: bool,
+    /// `sponsorblock` or `local` for segments found by skip detection.
+    pub source: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, utoipa::ToSchema)]
@@ -118,6 +120,7 @@ pub async fn get_episode_sponsorblock(
             votes: e.votes,
             locked: e.locked,
             duration_mismatch: e.duration_mismatch,
+            source: e.source,
         })
         .collect();
 
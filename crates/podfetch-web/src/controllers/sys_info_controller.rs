@@ -1,6 +1,6 @@
 use crate::app_state::AppState;
 use axum::extract::State;
-use axum::http::HeaderMap;
+use axum::http::{HeaderMap, StatusCode};
 use axum::{Extension, Json};
 use common_infrastructure::config::ConfigModel;
 use common_infrastructure::error::ErrorSeverity::Info;
@@ -25,10 +25,14 @@ fn get_size(path: &str) -> Result<u64, std::io::Error> {
     }
     Ok(total)
 }
-use crate::sys::{self, LoginControllerError, LoginRequest, SysExtraInfo, VersionInfo};
+use crate::services::api_token::service::API_TOKEN_PREFIX;
+use crate::services::storage_quota::service::StorageQuotaService;
+use crate::sys::{
+    self, LoginControllerError, LoginRequest, LoginResponse, StorageOverview, SysExtraInfo,
+    VersionInfo,
+};
 use crate::url_rewriting::resolve_server_url_from_headers;
 use podfetch_domain::user::User;
-use reqwest::StatusCode;
 use sysinfo::{Disks, System};
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
@@ -69,6 +73,7 @@ body = SysExtraInfo)),
 tag="sys"
 )]
 pub async fn get_sys_info(
+    State(state): State<AppState>,
     Extension(requester): Extension<User>,
 ) -> Result<Json<SysExtraInfo>, CustomError> {
     if !requester.is_admin() {
@@ -91,10 +96,20 @@ pub async fn get_sys_info(
             )
         })?;
 
+    let settings = state.settings_service.get_settings()?.unwrap_or_default();
+    let users = state.user_admin_service.list_users()?;
+    let storage = StorageOverview {
+        used_bytes: state.storage_quota_service.usage()?.total_bytes,
+        quota_bytes: StorageQuotaService::global_quota_bytes(&settings),
+        quota_policy: StorageQuotaService::policy(&settings).as_str().to_string(),
+        users: state.storage_quota_service.user_reports(&users)?,
+    };
+
     Ok(Json(SysExtraInfo {
         system: sys::map_system(&sys),
         disks: simplified_disks,
         podcast_directory: podcast_byte_size,
+        storage,
     }))
 }
 
@@ -122,23 +137,50 @@ path="/login",
 operation_id="sys_login",
 request_body=LoginRequest,
 responses(
-(status = 200, description = "Performs a login if basic auth is enabled",
-body=String)),
+(status = 200, description = "Performs a login if basic auth is enabled. Users with a second \
+factor also need `totpCode` and receive a token to use instead of basic auth.",
+body=LoginResponse)),
 tag="sys"
 )]
 pub async fn login(
     State(state): State<AppState>,
     auth: Json<LoginRequest>,
-) -> Result<StatusCode, ErrorType> {
+) -> Result<Json<LoginResponse>, ErrorType> {
     sys::login(state.login_service.as_ref(), &auth.0)
-        .map(|_| StatusCode::OK)
+        .map(Json)
         .map_err(map_login_error)
 }
 
+#[utoipa::path(
+post,
+path="/logout",
+operation_id="sys_logout",
+responses(
+(status = 204, description = "Revokes the token handed out by a two-factor login that authenticated \
+this request. Other logins need nothing revoked.")),
+tag="sys"
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+    headers: HeaderMap,
+) -> Result<StatusCode, CustomError> {
+    if let Some(token) = headers
+        .get("Authorization")
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .filter(|token| token.starts_with(API_TOKEN_PREFIX))
+    {
+        state.login_service.logout(&requester, token)?;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
 fn map_login_error(error: LoginControllerError<CustomError>) -> ErrorType {
     match error {
         LoginControllerError::Unauthorized => ApiError::wrong_user_or_password().into(),
         LoginControllerError::Forbidden => CustomErrorInner::Forbidden(Info).into(),
+        LoginControllerError::SecondFactorRequired => ApiError::second_factor_required().into(),
         LoginControllerError::Service(error) => CustomErrorType(error),
     }
 }
@@ -165,6 +207,7 @@ pub fn get_sys_info_router() -> OpenApiRouter<AppState> {
     OpenApiRouter::new()
         .routes(routes!(get_sys_info))
         .routes(routes!(get_info))
+        .routes(routes!(logout))
 }
 
 #[cfg(test)]
@@ -318,6 +361,8 @@ mod tests {
         assert!(payload["podcast_directory"].is_number());
         assert!(payload["system"].is_object());
         assert!(payload["disks"].is_array());
+        assert_eq!(payload["storage"]["quotaPolicy"], "refuse");
+        assert!(payload["storage"]["users"].is_array());
     }
 
     #[tokio::test]
@@ -354,7 +399,7 @@ mod tests {
             true,
         );
 
-        let result = super::get_sys_info(Extension(non_admin)).await;
+        let result = super::get_sys_info(State(AppState::new()), Extension(non_admin)).await;
         match result {
             Err(err) => assert!(matches!(err.inner, CustomErrorInner::Forbidden(_))),
             Ok(_) => panic!("expected forbidden error"),
@@ -419,6 +464,7 @@ mod tests {
             axum::Json(super::LoginRequest {
                 username,
                 password: "wrong-password".to_string(),
+                totp_code: None,
             }),
         )
         .await;
@@ -0,0 +1,324 @@
+//! Settings API for the transcoding profiles downloads can be converted with.
+//! Everyone may list them to pick one for a podcast, only admins change them.
+
+use crate::app_state::AppState;
+use crate::services::transcoding::service::TranscodingProfileInput;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::transcoding_profile::{AudioCodec, TranscodingProfile};
+use podfetch_domain::user::User;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+use uuid::Uuid;
+
+// ── DTOs ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscodingProfileDto {
+    pub id: String,
+    pub name: String,
+    /// One of `opus`, `mp3`, `aac` or `vorbis`.
+    pub codec: String,
+    pub bitrate_kbps: i32,
+    /// 1 for mono, 2 for stereo, 0 keeps the channels of the source.
+    pub channels: i32,
+    /// In Hz, 0 keeps the sample rate of the source.
+    pub sample_rate: i32,
+    pub loudnorm: bool,
+    pub trim_silence: bool,
+    pub keep_original: bool,
+    pub created_at: String,
+}
+
+impl From<TranscodingProfile> for TranscodingProfileDto {
+    fn from(profile: TranscodingProfile) -> Self {
+        Self {
+            id: profile.id.to_string(),
+            name: profile.name,
+            codec: profile.codec.as_str().to_string(),
+            bitrate_kbps: profile.bitrate_kbps,
+            channels: profile.channels,
+            sample_rate: profile.sample_rate,
+            loudnorm: profile.loudnorm,
+            trim_silence: profile.trim_silence,
+            keep_original: profile.keep_original,
+            created_at: profile.created_at.and_utc().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscodingProfileUpsert {
+    pub name: String,
+    pub codec: String,
+    pub bitrate_kbps: i32,
+    #[serde(default)]
+    pub channels: i32,
+    #[serde(default)]
+    pub sample_rate: i32,
+    #[serde(default)]
+    pub loudnorm: bool,
+    #[serde(default)]
+    pub trim_silence: bool,
+    #[serde(default)]
+    pub keep_original: bool,
+}
+
+impl TryFrom<TranscodingProfileUpsert> for TranscodingProfileInput {
+    type Error = CustomError;
+
+    fn try_from(upsert: TranscodingProfileUpsert) -> Result<Self, Self::Error> {
+        let codec = AudioCodec::from_str(&upsert.codec)
+            .ok_or_else(|| bad_request(format!("'{}' is not a supported codec", upsert.codec)))?;
+        Ok(Self {
+            name: upsert.name,
+            codec,
+            bitrate_kbps: upsert.bitrate_kbps,
+            channels: upsert.channels,
+            sample_rate: upsert.sample_rate,
+            loudnorm: upsert.loudnorm,
+            trim_silence: upsert.trim_silence,
+            keep_original: upsert.keep_original,
+        })
+    }
+}
+
+// ── handlers ──────────────────────────────────────────────────────────────
+
+fn bad_request(message: String) -> CustomError {
+    CustomErrorInner::BadRequest(message, Warning).into()
+}
+
+fn require_admin(requester: &User) -> Result<(), CustomError> {
+    if !requester.is_admin() {
+        return Err(CustomErrorInner::Forbidden(Warning).into());
+    }
+    Ok(())
+}
+
+fn parse_profile_uuid(id: &str) -> Result<Uuid, CustomError> {
+    Uuid::parse_str(id).map_err(|_| bad_request("invalid transcoding profile id".to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/settings/transcoding-profiles",
+    responses(
+        (status = 200, description = "All transcoding profiles.", body = [TranscodingProfileDto])
+    ),
+    tag = "settings"
+)]
+pub async fn get_transcoding_profiles(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<TranscodingProfileDto>>, CustomError> {
+    let profiles = state.transcoding_profile_service.list()?;
+    Ok(Json(profiles.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/settings/transcoding-profiles",
+    request_body = TranscodingProfileUpsert,
+    responses(
+        (status = 200, description = "The created profile.", body = TranscodingProfileDto),
+        (status = 400, description = "Unknown codec or out of range values.")
+    ),
+    tag = "settings"
+)]
+pub async fn create_transcoding_profile(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+    Json(upsert): Json<TranscodingProfileUpsert>,
+) -> Result<Json<TranscodingProfileDto>, CustomError> {
+    require_admin(&requester)?;
+    let profile = state
+        .transcoding_profile_service
+        .create(upsert.try_into()?)?;
+    Ok(Json(profile.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/settings/transcoding-profiles/{id}",
+    request_body = TranscodingProfileUpsert,
+    responses(
+        (status = 200, description = "The updated profile.", body = TranscodingProfileDto),
+        (status = 400, description = "Unknown codec or out of range values."),
+        (status = 404, description = "No such profile.")
+    ),
+    tag = "settings"
+)]
+pub async fn update_transcoding_profile(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(requester): Extension<User>,
+    Json(upsert): Json<TranscodingProfileUpsert>,
+) -> Result<Json<TranscodingProfileDto>, CustomError> {
+    require_admin(&requester)?;
+    let profile = state
+        .transcoding_profile_service
+        .update(parse_profile_uuid(&id)?, upsert.try_into()?)?;
+    Ok(Json(profile.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/settings/transcoding-profiles/{id}",
+    responses(
+        (status = 204, description = "The profile was removed. Podcasts that used it fall back to the instance settings."),
+        (status = 404, description = "No such profile.")
+    ),
+    tag = "settings"
+)]
+pub async fn delete_transcoding_profile(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Extension(requester): Extension<User>,
+) -> Result<StatusCode, CustomError> {
+    require_admin(&requester)?;
+    state
+        .transcoding_profile_service
+        .delete(parse_profile_uuid(&id)?)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn get_transcoding_profile_router() -> OpenApiRouter<AppState> {
+    OpenApiRouter::new()
+        .routes(routes!(
+            get_transcoding_profiles,
+            create_transcoding_profile
+        ))
+        .routes(routes!(
+            update_transcoding_profile,
+            delete_transcoding_profile
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::tests::handle_test_startup;
+    use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
+    use axum::extract::{Path, State};
+    use axum::{Extension, Json};
+    use common_infrastructure::error::CustomErrorInner;
+    use serde_json::{Value, json};
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn profiles_can_be_created_picked_and_deleted() {
+        let server = handle_test_startup().await;
+
+        let created = server
+            .test_server
+            .post("/api/v1/settings/transcoding-profiles")
+            .json(&json!({
+                "name": "Speech",
+                "codec": "opus",
+                "bitrateKbps": 32,
+                "channels": 1,
+                "loudnorm": true
+            }))
+            .await;
+        assert_eq!(created.status_code(), 200);
+        let created = created.json::<Value>();
+        assert_eq!(created["sampleRate"], json!(0));
+        let id = created["id"].as_str().unwrap().to_string();
+
+        let updated = server
+            .test_server
+            .put(&format!("/api/v1/settings/transcoding-profiles/{id}"))
+            .json(&json!({
+                "name": "Speech",
+                "codec": "aac",
+                "bitrateKbps": 64,
+                "keepOriginal": true
+            }))
+            .await;
+        assert_eq!(updated.status_code(), 200);
+        assert_eq!(updated.json::<Value>()["codec"], json!("aac"));
+
+        let mut settings = server
+            .test_server
+            .get("/api/v1/settings")
+            .await
+            .json::<Value>();
+        settings["transcodingProfileId"] = json!(id);
+        let picked = server
+            .test_server
+            .put("/api/v1/settings")
+            .json(&settings)
+            .await;
+        assert_eq!(picked.status_code(), 200);
+        assert_eq!(picked.json::<Value>()["transcodingProfileId"], json!(id));
+
+        let deleted = server
+            .test_server
+            .delete(&format!("/api/v1/settings/transcoding-profiles/{id}"))
+            .await;
+        assert_eq!(deleted.status_code(), 204);
+        settings["transcodingProfileId"] = json!(id);
+        let dangling = server
+            .test_server
+            .put("/api/v1/settings")
+            .json(&settings)
+            .await;
+        assert_eq!(dangling.status_code(), 400);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn unknown_codecs_and_out_of_range_values_are_rejected() {
+        let server = handle_test_startup().await;
+
+        for body in [
+            json!({ "name": "x", "codec": "flac", "bitrateKbps": 64 }),
+            json!({ "name": "x", "codec": "opus", "bitrateKbps": 2 }),
+            json!({ "name": "x", "codec": "opus", "bitrateKbps": 64, "channels": 6 }),
+            json!({ "name": "x", "codec": "mp3", "bitrateKbps": 64, "sampleRate": 100 }),
+            json!({ "name": " ", "codec": "mp3", "bitrateKbps": 64 }),
+        ] {
+            let response = server
+                .test_server
+                .post("/api/v1/settings/transcoding-profiles")
+                .json(&body)
+                .await;
+            assert_eq!(response.status_code(), 400, "{body}");
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn non_admin_cannot_change_profiles() {
+        let _server = handle_test_startup().await;
+        let user = UserTestDataBuilder::new().build();
+
+        let result = super::update_transcoding_profile(
+            State(crate::app_state::AppState::new()),
+            Path(uuid::Uuid::new_v4().to_string()),
+            Extension(user),
+            Json(super::TranscodingProfileUpsert {
+                name: "Speech".to_string(),
+                codec: "opus".to_string(),
+                bitrate_kbps: 32,
+                channels: 0,
+                sample_rate: 0,
+                loudnorm: false,
+                trim_silence: false,
+                keep_original: false,
+            }),
+        )
+        .await;
+        match result {
+            Err(err) => assert!(matches!(err.inner, CustomErrorInner::Forbidden(_))),
+            Ok(_) => panic!("expected forbidden error for update_transcoding_profile"),
+        }
+    }
+}
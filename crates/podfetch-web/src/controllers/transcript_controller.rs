@@ -14,6 +14,7 @@ use axum::{Extension, http};
 use common_infrastructure::error::ErrorSeverity::Warning;
 use common_infrastructure::error::{CustomError, CustomErrorInner};
 use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
+use podfetch_domain::api_token::ApiTokenScope;
 use podfetch_domain::podcast_episode_transcript::PodcastEpisodeTranscript;
 use podfetch_domain::podcast_episode_transcript::TranscriptSegment;
 use podfetch_domain::user::User;
@@ -321,7 +322,10 @@ pub async fn get_transcript_file_with_api_key(
     State(state): State<AppState>,
     Path((id, tid, api_key)): Path<(String, String, String)>,
 ) -> Result<Response, CustomError> {
-    if !state.user_auth_service.is_api_key_valid(&api_key) {
+    if !state
+        .user_auth_service
+        .is_api_key_valid(&api_key, ApiTokenScope::Stream)
+    {
         return Err(CustomErrorInner::Forbidden(Warning).into());
     }
 
@@ -0,0 +1,437 @@
+//! TOTP second factor of the signed-in user: enrollment through an
+//! `otpauth://` URI, recovery codes and switching it off again. Admins can
+//! reset the second factor of a user who lost access to it.
+
+use crate::app_state::AppState;
+use crate::client_ip::ClientIp;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::audit_log::AuditAction;
+use podfetch_domain::user::User;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::routes;
+
+// ── DTOs ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorStatusDto {
+    pub enabled: bool,
+    /// Enrollment was started but not confirmed with a code yet.
+    pub pending: bool,
+    pub recovery_codes_left: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorEnrollmentDto {
+    /// Base32 secret for entering the account by hand.
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TwoFactorCode {
+    /// Current TOTP code, or a recovery code where noted.
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorRecoveryCodesDto {
+    /// Each code works once. They are only shown now.
+    pub recovery_codes: Vec<String>,
+}
+
+// ── handlers ──────────────────────────────────────────────────────────────
+
+#[utoipa::path(
+    get,
+    path = "/users/me/two-factor",
+    responses(
+        (status = 200, description = "Two-factor state of the current user.", body = TwoFactorStatusDto)
+    ),
+    tag = "user"
+)]
+pub async fn get_two_factor_status(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+) -> Result<Json<TwoFactorStatusDto>, CustomError> {
+    let two_factor = state.two_factor_service.find(&requester)?;
+    Ok(Json(TwoFactorStatusDto {
+        enabled: two_factor
+            .as_ref()
+            .is_some_and(|two_factor| two_factor.enabled),
+        pending: two_factor
+            .as_ref()
+            .is_some_and(|two_factor| !two_factor.enabled),
+        recovery_codes_left: two_factor
+            .map(|two_factor| two_factor.recovery_codes.len())
+            .unwrap_or_default(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/two-factor",
+    responses(
+        (status = 200, description = "A new secret to add to an authenticator app.", body = TwoFactorEnrollmentDto),
+        (status = 409, description = "Two-factor authentication is already enabled.")
+    ),
+    tag = "user"
+)]
+pub async fn start_two_factor_enrollment(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+) -> Result<Json<TwoFactorEnrollmentDto>, CustomError> {
+    let enrollment = state.two_factor_service.start_enrollment(&requester)?;
+    Ok(Json(TwoFactorEnrollmentDto {
+        secret: enrollment.secret,
+        otpauth_uri: enrollment.otpauth_uri,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/two-factor/confirm",
+    request_body = TwoFactorCode,
+    responses(
+        (status = 200, description = "Two-factor authentication is enabled.", body = TwoFactorRecoveryCodesDto),
+        (status = 400, description = "The code doesn't match the pending secret."),
+        (status = 404, description = "No enrollment was started.")
+    ),
+    tag = "user"
+)]
+pub async fn confirm_two_factor_enrollment(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+    Json(code): Json<TwoFactorCode>,
+) -> Result<Json<TwoFactorRecoveryCodesDto>, CustomError> {
+    let recovery_codes = state
+        .two_factor_service
+        .confirm_enrollment(&requester, &code.code)?;
+    Ok(Json(TwoFactorRecoveryCodesDto { recovery_codes }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/two-factor/recovery-codes",
+    request_body = TwoFactorCode,
+    responses(
+        (status = 200, description = "Replaces the recovery codes.", body = TwoFactorRecoveryCodesDto),
+        (status = 400, description = "Invalid code.")
+    ),
+    tag = "user"
+)]
+pub async fn regenerate_two_factor_recovery_codes(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+    Json(code): Json<TwoFactorCode>,
+) -> Result<Json<TwoFactorRecoveryCodesDto>, CustomError> {
+    let recovery_codes = state
+        .two_factor_service
+        .regenerate_recovery_codes(&requester, &code.code)?;
+    Ok(Json(TwoFactorRecoveryCodesDto { recovery_codes }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/two-factor/disable",
+    request_body = TwoFactorCode,
+    responses(
+        (status = 204, description = "Two-factor authentication was switched off."),
+        (status = 400, description = "Invalid code.")
+    ),
+    tag = "user"
+)]
+pub async fn disable_two_factor(
+    State(state): State<AppState>,
+    Extension(requester): Extension<User>,
+    Json(code): Json<TwoFactorCode>,
+) -> Result<StatusCode, CustomError> {
+    state.two_factor_service.disable(&requester, &code.code)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/{username}/two-factor",
+    responses(
+        (status = 204, description = "The second factor of the user was removed (admin)."),
+        (status = 403, description = "Only admins may reset a second factor."),
+        (status = 404, description = "Unknown user or no second factor.")
+    ),
+    tag = "user"
+)]
+pub async fn reset_two_factor(
+    State(state): State<AppState>,
+    Path(username): Path<String>,
+    Extension(requester): Extension<User>,
+    ClientIp(ip): ClientIp,
+) -> Result<StatusCode, CustomError> {
+    if !requester.is_admin() {
+        return Err(CustomErrorInner::Forbidden(Warning).into());
+    }
+    let user = state
+        .user_admin_service
+        .find_user_by_username(&username)?
+        .ok_or_else(|| CustomError::from(CustomErrorInner::NotFound(Warning)))?;
+    state.two_factor_service.reset(user.id)?;
+    state
+        .audit_log_service
+        .record(&requester, AuditAction::TwoFactorReset, Some(username), ip);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn get_two_factor_router() -> OpenApiRouter<AppState> {
+    OpenApiRouter::new()
+        .routes(routes!(get_two_factor_status, start_two_factor_enrollment))
+        .routes(routes!(confirm_two_factor_enrollment))
+        .routes(routes!(regenerate_two_factor_recovery_codes))
+        .routes(routes!(disable_two_factor))
+        .routes(routes!(reset_two_factor))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app_state::AppState;
+    use crate::controllers::two_factor_controller::{
+        TwoFactorEnrollmentDto, TwoFactorRecoveryCodesDto, TwoFactorStatusDto,
+    };
+    use crate::services::two_factor::totp;
+    use crate::sys::LoginResponse;
+    use crate::test_support::tests::{TestServerWrapper, handle_test_startup};
+    use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use podfetch_domain::api_token::ApiTokenScope;
+    use podfetch_domain::user::User;
+    use serde_json::{Value, json};
+    use serial_test::serial;
+
+    fn basic_auth(user: &User) -> String {
+        format!(
+            "Basic {}",
+            STANDARD.encode(format!("{}:password", user.username))
+        )
+    }
+
+    /// Enrolls `user` through the API and returns their recovery codes.
+    async fn enroll(server: &TestServerWrapper<'_>, user: &User) -> Vec<String> {
+        let enrollment = server
+            .test_server
+            .post("/api/v1/users/me/two-factor")
+            .clear_headers()
+            .add_header("Authorization", basic_auth(user))
+            .await;
+        assert_eq!(enrollment.status_code(), 200);
+        let enrollment = enrollment.json::<TwoFactorEnrollmentDto>();
+        assert!(
+            enrollment
+                .otpauth_uri
+                .starts_with("otpauth://totp/PodFetch:")
+        );
+
+        let code = totp::code_at(&enrollment.secret, chrono::Utc::now().timestamp());
+        let confirmed = server
+            .test_server
+            .post("/api/v1/users/me/two-factor/confirm")
+            .clear_headers()
+            .add_header("Authorization", basic_auth(user))
+            .json(&json!({ "code": code }))
+            .await;
+        assert_eq!(confirmed.status_code(), 200);
+        confirmed.json::<TwoFactorRecoveryCodesDto>().recovery_codes
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn enrolled_users_need_a_code_to_log_in_and_lose_basic_auth() {
+        let server = handle_test_startup().await;
+        let user = AppState::new()
+            .user_admin_service
+            .create_user(UserTestDataBuilder::new().build())
+            .unwrap();
+
+        let recovery_codes = enroll(&server, &user).await;
+        assert_eq!(recovery_codes.len(), 10);
+
+        let basic = server
+            .test_server
+            .get("/api/v1/users/me/two-factor")
+            .clear_headers()
+            .add_header("Authorization", basic_auth(&user))
+            .await;
+        assert_eq!(basic.status_code(), 401);
+
+        let without_code = server
+            .test_server
+            .post("/api/v1/login")
+            .json(&json!({ "username": user.username, "password": "password" }))
+            .await;
+        assert_eq!(without_code.status_code(), 401);
+        assert_eq!(
+            without_code.json::<Value>()["errorCode"],
+            "SECOND_FACTOR_REQUIRED"
+        );
+
+        let wrong_code = server
+            .test_server
+            .post("/api/v1/login")
+            .json(&json!({
+                "username": user.username,
+                "password": "password",
+                "totpCode": "000000"
+            }))
+            .await;
+        assert_eq!(wrong_code.status_code(), 403);
+
+        let login = server
+            .test_server
+            .post("/api/v1/login")
+            .json(&json!({
+                "username": user.username,
+                "password": "password",
+                "totpCode": recovery_codes[0]
+            }))
+            .await;
+        assert_eq!(login.status_code(), 200);
+        let token = login.json::<LoginResponse>().token.expect("login token");
+
+        let status = server
+            .test_server
+            .get("/api/v1/users/me/two-factor")
+            .clear_headers()
+            .authorization_bearer(&token)
+            .await;
+        assert_eq!(status.status_code(), 200);
+        let status = status.json::<TwoFactorStatusDto>();
+        assert!(status.enabled);
+        assert_eq!(status.recovery_codes_left, 9);
+
+        let reused = server
+            .test_server
+            .post("/api/v1/login")
+            .json(&json!({
+                "username": user.username,
+                "password": "password",
+                "totpCode": recovery_codes[0]
+            }))
+            .await;
+        assert_eq!(reused.status_code(), 403);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn login_tokens_are_capped_and_revoked_on_logout() {
+        let server = handle_test_startup().await;
+        let state = AppState::new();
+        let user = state
+            .user_admin_service
+            .create_user(UserTestDataBuilder::new().build())
+            .unwrap();
+        let recovery_codes = enroll(&server, &user).await;
+        let mut earlier_logins = Vec::new();
+        for _ in 0..10 {
+            let (_, secret) = state
+                .api_token_service
+                .create(&user, "Web login", vec![ApiTokenScope::Admin], None)
+                .unwrap();
+            earlier_logins.push(secret);
+        }
+
+        let login = server
+            .test_server
+            .post("/api/v1/login")
+            .json(&json!({
+                "username": user.username,
+                "password": "password",
+                "totpCode": recovery_codes[0]
+            }))
+            .await;
+        assert_eq!(login.status_code(), 200);
+        let token = login.json::<LoginResponse>().token.expect("login token");
+
+        let logins = state
+            .api_token_service
+            .list(&user)
+            .unwrap()
+            .into_iter()
+            .filter(|token| token.name == "Web login")
+            .count();
+        assert_eq!(logins, 10);
+        let oldest = server
+            .test_server
+            .get("/api/v1/users/me/two-factor")
+            .clear_headers()
+            .authorization_bearer(&earlier_logins[0])
+            .await;
+        assert_eq!(oldest.status_code(), 403);
+
+        let logout = server
+            .test_server
+            .post("/api/v1/logout")
+            .clear_headers()
+            .authorization_bearer(&token)
+            .await;
+        assert_eq!(logout.status_code(), 204);
+        let after_logout = server
+            .test_server
+            .get("/api/v1/users/me/two-factor")
+            .clear_headers()
+            .authorization_bearer(&token)
+            .await;
+        assert_eq!(after_logout.status_code(), 403);
+        let other_login = server
+            .test_server
+            .get("/api/v1/users/me/two-factor")
+            .clear_headers()
+            .authorization_bearer(&earlier_logins[1])
+            .await;
+        assert_eq!(other_login.status_code(), 200);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn admins_can_reset_a_second_factor() {
+        let server = handle_test_startup().await;
+        let state = AppState::new();
+        let user = state
+            .user_admin_service
+            .create_user(UserTestDataBuilder::new().build())
+            .unwrap();
+        let other = state
+            .user_admin_service
+            .create_user(UserTestDataBuilder::new().build())
+            .unwrap();
+        enroll(&server, &user).await;
+
+        let not_admin = server
+            .test_server
+            .delete(&format!("/api/v1/users/{}/two-factor", user.username))
+            .clear_headers()
+            .add_header("Authorization", basic_auth(&other))
+            .await;
+        assert_eq!(not_admin.status_code(), 403);
+
+        let reset = server
+            .test_server
+            .delete(&format!("/api/v1/users/{}/two-factor", user.username))
+            .await;
+        assert_eq!(reset.status_code(), 204);
+
+        let basic = server
+            .test_server
+            .get("/api/v1/users/me/two-factor")
+            .clear_headers()
+            .add_header("Authorization", basic_auth(&user))
+            .await;
+        assert_eq!(basic.status_code(), 200);
+        assert!(!basic.json::<TwoFactorStatusDto>().enabled);
+    }
+}
@@ -1,15 +1,17 @@
 use crate::app_state::AppState;
+use crate::client_ip::ClientIp;
 use crate::invite::Invite;
 use crate::invite::{self, InviteControllerError, InvitePostModel};
 use crate::role::STANDARD_USER;
 use crate::user_admin::{
-    self, UserAdminControllerError, UserCoreUpdateModel, UserRoleUpdateModel, UserSummary,
-    UserWithApiKey,
+    self, UserAdminControllerError, UserCoreUpdateModel, UserRoleUpdateModel,
+    UserStorageQuotaUpdate, UserStorageUsage, UserSummary, UserWithApiKey,
 };
 use crate::user_onboarding::{self, UserOnboardingModel};
 use axum::extract::{Path, State};
 use axum::{Extension, Json};
-use podfetch_domain::user::User;
+use podfetch_domain::audit_log::AuditAction;
+use podfetch_domain::user::{User, UserWithoutPassword};
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -88,9 +90,10 @@ pub async fn update_role(
     State(state): State<AppState>,
     Path(username): Path<String>,
     Extension(requester): Extension<User>,
+    ClientIp(ip): ClientIp,
     Json(role): Json<UserRoleUpdateModel>,
 ) -> Result<Json<UserSummary>, CustomError> {
-    user_admin::update_role(
+    let summary = user_admin::update_role(
         state.user_admin_service.as_ref(),
         &username,
         requester.is_admin(),
@@ -99,8 +102,11 @@ pub async fn update_role(
             explicit_consent: role.explicit_consent,
         },
     )
-    .map(Json)
-    .map_err(map_user_admin_error)
+    .map_err(map_user_admin_error)?;
+    state
+        .audit_log_service
+        .record(&requester, AuditAction::UserRoleChanged, Some(username), ip);
+    Ok(Json(summary))
 }
 
 #[utoipa::path(
@@ -142,11 +148,18 @@ tag="invite"
 pub async fn create_invite(
     State(state): State<AppState>,
     Extension(requester): Extension<User>,
+    ClientIp(ip): ClientIp,
     Json(invite): Json<InvitePostModel>,
 ) -> Result<Json<Invite>, CustomError> {
-    invite::create_invite(state.invite_service.as_ref(), requester.is_admin(), invite)
-        .map(Json)
-        .map_err(map_invite_controller_error)
+    let invite = invite::create_invite(state.invite_service.as_ref(), requester.is_admin(), invite)
+        .map_err(map_invite_controller_error)?;
+    state.audit_log_service.record(
+        &requester,
+        AuditAction::InviteCreated,
+        Some(invite.id.clone()),
+        ip,
+    );
+    Ok(Json(invite))
 }
 
 #[utoipa::path(
@@ -192,14 +205,18 @@ pub async fn delete_user(
     State(state): State<AppState>,
     Path(username): Path<String>,
     Extension(requester): Extension<User>,
+    ClientIp(ip): ClientIp,
 ) -> Result<StatusCode, CustomError> {
     user_admin::delete_user(
         state.user_admin_service.as_ref(),
         requester.is_admin(),
         &username,
     )
-    .map(|_| StatusCode::OK)
-    .map_err(map_user_admin_error)
+    .map_err(map_user_admin_error)?;
+    state
+        .audit_log_service
+        .record(&requester, AuditAction::UserDeleted, Some(username), ip);
+    Ok(StatusCode::OK)
 }
 
 #[utoipa::path(
@@ -234,14 +251,21 @@ pub async fn delete_invite(
     State(state): State<AppState>,
     invite_id: Path<String>,
     requester: Extension<User>,
+    ClientIp(ip): ClientIp,
 ) -> Result<StatusCode, CustomError> {
     invite::delete_invite(
         state.invite_service.as_ref(),
         requester.is_admin(),
         &invite_id.0,
     )
-    .map(|_| StatusCode::OK)
-    .map_err(map_invite_link_error)
+    .map_err(map_invite_link_error)?;
+    state.audit_log_service.record(
+        &requester,
+        AuditAction::InviteDeleted,
+        Some(invite_id.0),
+        ip,
+    );
+    Ok(StatusCode::OK)
 }
 
 fn map_invite_controller_error(error: InviteControllerError<CustomError>) -> CustomError {
@@ -290,6 +314,96 @@ fn map_user_admin_error_type(error: UserAdminControllerError<CustomError>) -> Er
     }
 }
 
+/// Resolves the user whose storage is asked for. Everyone may look at their
+/// own usage (also via `me`), only admins at anyone else's.
+fn storage_subject(
+    state: &AppState,
+    username: &str,
+    requester: &User,
+) -> Result<UserWithoutPassword, CustomError> {
+    if username == "me" || username == requester.username {
+        return Ok(UserWithoutPassword {
+            id: requester.id,
+            username: requester.username.clone(),
+            role: requester.role.clone(),
+            created_at: requester.created_at,
+            explicit_consent: requester.explicit_consent,
+        });
+    }
+    if !requester.is_admin() {
+        return Err(CustomErrorInner::Forbidden(Warning).into());
+    }
+    state
+        .user_admin_service
+        .list_users()?
+        .into_iter()
+        .find(|user| user.username == username)
+        .ok_or_else(|| CustomErrorInner::NotFound(Info).into())
+}
+
+#[utoipa::path(
+get,
+path="/{username}/storage",
+responses(
+(status = 200, description = "Storage used by the podcasts the user added, and the quota that \
+applies to them", body = UserStorageUsage)),
+tag="user"
+)]
+pub async fn get_user_storage(
+    State(state): State<AppState>,
+    Path(username): Path<String>,
+    Extension(requester): Extension<User>,
+) -> Result<Json<UserStorageUsage>, CustomError> {
+    let user = storage_subject(&state, &username, &requester)?;
+    state
+        .storage_quota_service
+        .user_reports(&[user])?
+        .pop()
+        .map(Json)
+        .ok_or_else(|| CustomErrorInner::NotFound(Info).into())
+}
+
+#[utoipa::path(
+put,
+path="/{username}/storage",
+request_body = UserStorageQuotaUpdate,
+responses(
+(status = 200, description = "Sets the storage quota of a user (admin)", body = UserStorageUsage)),
+tag="user"
+)]
+pub async fn update_user_storage_quota(
+    State(state): State<AppState>,
+    Path(username): Path<String>,
+    Extension(requester): Extension<User>,
+    ClientIp(ip): ClientIp,
+    Json(update): Json<UserStorageQuotaUpdate>,
+) -> Result<Json<UserStorageUsage>, CustomError> {
+    if !requester.is_admin() {
+        return Err(CustomErrorInner::Forbidden(Warning).into());
+    }
+    if update.quota_mb.is_some_and(|quota_mb| quota_mb < 0) {
+        return Err(
+            CustomErrorInner::BadRequest("quotaMb must not be negative".to_string(), Info).into(),
+        );
+    }
+    let user = storage_subject(&state, &username, &requester)?;
+    state
+        .storage_quota_service
+        .set_quota_override(user.id, update.quota_mb)?;
+    state.audit_log_service.record(
+        &requester,
+        AuditAction::StorageQuotaChanged,
+        Some(user.username.clone()),
+        ip,
+    );
+    state
+        .storage_quota_service
+        .user_reports(&[user])?
+        .pop()
+        .map(Json)
+        .ok_or_else(|| CustomErrorInner::NotFound(Info).into())
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserLocaleUpdate {
@@ -336,7 +450,8 @@ pub fn get_user_router() -> OpenApiRouter<AppState> {
                 .routes(routes!(update_role))
                 .routes(routes!(update_locale))
                 .routes(routes!(delete_user))
-                .routes(routes!(update_user)),
+                .routes(routes!(update_user))
+                .routes(routes!(get_user_storage, update_user_storage_quota)),
         )
         .routes(routes!(delete_invite))
         .routes(routes!(get_invite_link))
@@ -347,15 +462,24 @@ pub fn get_user_router() -> OpenApiRouter<AppState> {
 #[cfg(test)]
 mod tests {
     use crate::app_state::AppState;
+    use crate::client_ip::ClientIp;
     use crate::invite::Invite;
     use crate::test_support::tests::handle_test_startup;
     use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
-    use crate::user_admin::UserWithApiKey;
+    use crate::user_admin::{UserStorageUsage, UserWithApiKey};
     use axum::extract::{Path, State};
     use axum::{Extension, Json};
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use common_infrastructure::config::FileHandlerType;
     use common_infrastructure::error::{CustomErrorInner, ErrorType};
     use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
+    use diesel::prelude::*;
     use podfetch_domain::user::User;
+    use podfetch_persistence::db::get_connection;
+    use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
+    use podfetch_persistence::schema::podcast_episodes::dsl as pe_dsl;
+    use podfetch_persistence::schema::podcasts::dsl as p_dsl;
     use serde_json::json;
     use serial_test::serial;
     use uuid::Uuid;
@@ -650,6 +774,7 @@ mod tests {
             State(app_state()),
             Path("someone".to_string()),
             Extension(non_admin.clone()),
+            ClientIp(None),
             Json(super::UserRoleUpdateModel {
                 role: "user".to_string(),
                 explicit_consent: true,
@@ -665,6 +790,7 @@ mod tests {
             State(app_state()),
             Path("someone".to_string()),
             Extension(non_admin.clone()),
+            ClientIp(None),
         )
         .await;
         match delete_user_result {
@@ -676,6 +802,7 @@ mod tests {
             State(app_state()),
             Path("some-invite".to_string()),
             Extension(non_admin),
+            ClientIp(None),
         )
         .await;
         match delete_invite_result {
@@ -683,4 +810,118 @@ mod tests {
             Ok(_) => panic!("expected forbidden error for delete_invite"),
         }
     }
+
+    /// Creates a podcast added by `user` with one downloaded episode of
+    /// `bytes` bytes and records it in the storage accounting.
+    fn downloaded_episode_of(user: &User, bytes: usize) -> std::path::PathBuf {
+        let slug = unique_username("storage-podcast");
+        let podcast = crate::services::podcast::service::PodcastService::add_podcast_to_database(
+            &slug,
+            &slug,
+            &format!("https://example.com/{slug}.xml"),
+            "http://localhost:8080/ui/default.jpg",
+            &slug,
+        )
+        .unwrap();
+        diesel::update(p_dsl::podcasts.filter(p_dsl::id.eq(&podcast.id)))
+            .set(p_dsl::added_by.eq(user.id.to_string()))
+            .execute(&mut get_connection())
+            .unwrap();
+
+        let episode_id = Uuid::new_v4().to_string();
+        diesel::insert_into(pe_dsl::podcast_episodes)
+            .values((
+                pe_dsl::id.eq(episode_id.clone()),
+                pe_dsl::podcast_id.eq(podcast.id.clone()),
+                pe_dsl::episode_id.eq(unique_username("episode")),
+                pe_dsl::name.eq("Storage Test Episode".to_string()),
+                pe_dsl::url.eq(format!("https://example.com/{episode_id}.mp3")),
+                pe_dsl::date_of_recording.eq("2026-03-01T00:00:00Z".to_string()),
+                pe_dsl::image_url.eq("http://localhost:8080/ui/default.jpg".to_string()),
+                pe_dsl::total_time.eq(1800),
+                pe_dsl::description.eq("storage test".to_string()),
+                pe_dsl::guid.eq(unique_username("guid")),
+                pe_dsl::deleted.eq(false),
+                pe_dsl::episode_numbering_processed.eq(false),
+            ))
+            .execute(&mut get_connection())
+            .unwrap();
+        let episode = pe_dsl::podcast_episodes
+            .filter(pe_dsl::id.eq(episode_id.clone()))
+            .first::<PodcastEpisode>(&mut get_connection())
+            .unwrap();
+
+        let file = std::env::temp_dir().join(format!("{episode_id}.mp3"));
+        std::fs::write(&file, vec![0u8; bytes]).unwrap();
+        app_state()
+            .storage_quota_service
+            .record_download(&episode, file.to_str().unwrap(), &FileHandlerType::Local)
+            .unwrap();
+        file
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn storage_usage_is_attributed_to_the_user_who_added_the_podcast() {
+        let server = handle_test_startup().await;
+        let user = app_state()
+            .user_admin_service
+            .create_user(non_admin_user())
+            .unwrap();
+        let basic_auth = format!(
+            "Basic {}",
+            STANDARD.encode(format!("{}:password", user.username))
+        );
+        let file = downloaded_episode_of(&user, 2048);
+
+        let updated = server
+            .test_server
+            .put(&format!("/api/v1/users/{}/storage", user.username))
+            .json(&json!({ "quotaMb": 5 }))
+            .await;
+        assert_eq!(updated.status_code(), 200);
+        let updated = updated.json::<UserStorageUsage>();
+        assert_eq!(updated.used_bytes, 2048);
+        assert_eq!(updated.quota_bytes, Some(5 * 1024 * 1024));
+        assert_eq!(updated.quota_override_mb, Some(5));
+        assert_eq!(updated.podcasts.len(), 1);
+        assert_eq!(updated.podcasts[0].used_bytes, 2048);
+
+        let own = server
+            .test_server
+            .get("/api/v1/users/me/storage")
+            .clear_headers()
+            .add_header("Authorization", basic_auth.clone())
+            .await;
+        assert_eq!(own.status_code(), 200);
+        assert_eq!(own.json::<UserStorageUsage>(), updated);
+
+        let forbidden = server
+            .test_server
+            .put(&format!("/api/v1/users/{}/storage", user.username))
+            .clear_headers()
+            .add_header("Authorization", basic_auth)
+            .json(&json!({ "quotaMb": 0 }))
+            .await;
+        assert_eq!(forbidden.status_code(), 403);
+
+        let negative = server
+            .test_server
+            .put(&format!("/api/v1/users/{}/storage", user.username))
+            .json(&json!({ "quotaMb": -1 }))
+            .await;
+        assert_eq!(negative.status_code(), 400);
+
+        let reset = server
+            .test_server
+            .put(&format!("/api/v1/users/{}/storage", user.username))
+            .json(&json!({ "quotaMb": null }))
+            .await;
+        assert_eq!(reset.status_code(), 200);
+        let reset = reset.json::<UserStorageUsage>();
+        assert_eq!(reset.quota_override_mb, None);
+        assert_eq!(reset.used_bytes, 2048);
+
+        std::fs::remove_file(file).ok();
+    }
 }
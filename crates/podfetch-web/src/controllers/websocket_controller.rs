@@ -6,6 +6,7 @@ use axum::extract::{Path, State};
 use axum::http::HeaderMap;
 use axum::response::{IntoResponse, Response};
 use axum_extra::extract::OptionalQuery;
+use podfetch_domain::api_token::ApiTokenScope;
 use podfetch_persistence::podcast::PodcastEntity as Podcast;
 
 use crate::podcast_episode_dto::PodcastEpisodeDto;
@@ -57,7 +58,9 @@ pub async fn get_rss_feed(
             .and_then(|q| q.api_key.as_deref())
             .ok_or_else(|| CustomError::from(CustomErrorInner::Forbidden(Warning)))?;
 
-        let api_key_exists = state.user_auth_service.is_api_key_valid(api_key);
+        let api_key_exists = state
+            .user_auth_service
+            .is_api_key_valid(api_key, ApiTokenScope::RssRead);
 
         if !&api_key_exists {
             return Err(CustomErrorInner::Forbidden(Warning).into());
@@ -204,7 +207,9 @@ pub async fn get_rss_feed_for_podcast(
             .and_then(|q| q.api_key.as_deref())
             .ok_or_else(|| CustomError::from(CustomErrorInner::Forbidden(Warning)))?;
 
-        let api_key_exists = state.user_auth_service.is_api_key_valid(api_key);
+        let api_key_exists = state
+            .user_auth_service
+            .is_api_key_valid(api_key, ApiTokenScope::RssRead);
 
         if !&api_key_exists {
             return Err(CustomErrorInner::Forbidden(Warning).into());
@@ -294,6 +299,97 @@ pub async fn get_rss_feed_for_podcast(
     Ok(response)
 }
 
+#[utoipa::path(
+get,
+path="/rss/playlist/{id}",
+responses(
+(status = 200, description = "Gets the rss feed of a playlist. Smart playlists are evaluated for their owner."))
+, tag = "rss")]
+pub async fn get_rss_feed_for_playlist(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    OptionalQuery(api_key): OptionalQuery<RSSAPiKey>,
+) -> Result<impl IntoResponse, CustomError> {
+    let server_url = resolve_server_url_from_headers(&headers);
+    let api_key = api_key.and_then(|c| c.api_key);
+    // Playlists belong to a user, so the api key decides whose playlist is
+    // served. Without authentication everything belongs to the admin user.
+    let user = if ENVIRONMENT_SERVICE.http_basic || ENVIRONMENT_SERVICE.oidc_configured {
+        let api_key = api_key
+            .as_deref()
+            .ok_or_else(|| CustomError::from(CustomErrorInner::Forbidden(Warning)))?;
+        state
+            .user_auth_service
+            .find_by_api_key(api_key, ApiTokenScope::RssRead)?
+            .ok_or_else(|| CustomError::from(CustomErrorInner::Forbidden(Warning)))?
+    } else {
+        state.user_auth_service.ensure_admin_user()?
+    };
+    let playlist = state
+        .playlist_service
+        .find_playlist_by_user_and_id(&id, user.id)?;
+
+    let episodes: Vec<PodcastEpisodeDto> = state
+        .playlist_service
+        .episodes_of(&playlist, &user)?
+        .into_iter()
+        .map(|c| {
+            PodcastEpisodeDto::from_episode_with_api_key(
+                c,
+                api_key.clone(),
+                None::<FavoritePodcastEpisode>,
+                &server_url,
+            )
+        })
+        .collect();
+
+    let feed_url = add_api_key_to_url(format!("{server_url}rss/playlist/{id}"), &api_key);
+    let itunes_ext = ITunesChannelExtensionBuilder::default()
+        .owner(Some(get_itunes_owner("Podfetch", "dev@podfetch.com")))
+        .explicit(Some("no".to_string()))
+        .author(Some("Podfetch".to_string()))
+        .new_feed_url(feed_url.clone())
+        .summary(Some(format!("Playlist {} on Podfetch", playlist.name)))
+        .build();
+
+    // A playlist mixes shows just like the aggregated feed, so every item
+    // names the podcast it comes from.
+    let podcast_by_id: HashMap<String, Podcast> = PodcastService::get_all_podcasts_raw()?
+        .into_iter()
+        .map(|p| (p.id.clone(), p))
+        .collect();
+    let items = get_podcast_items_rss(
+        &state,
+        &episodes,
+        &api_key,
+        &server_url,
+        Some(&podcast_by_id),
+    );
+
+    let channel_builder = ChannelBuilder::default()
+        .namespaces(podcast_namespace())
+        .language("en".to_string())
+        .title(playlist.name.clone())
+        .link(feed_url)
+        .description(format!("Playlist {} on Podfetch", playlist.name))
+        .items(items)
+        .clone();
+    let channel = generate_itunes_extension_conditionally(
+        itunes_ext,
+        channel_builder,
+        None,
+        &api_key,
+        &server_url,
+    );
+
+    let response = Response::builder()
+        .header("Content-Type", "application/rss+xml")
+        .body(channel.to_string())
+        .unwrap();
+    Ok(response)
+}
+
 fn get_podcast_items_rss(
     state: &AppState,
     downloaded_episodes: &[PodcastEpisodeDto],
@@ -500,12 +596,31 @@ pub async fn get_rss_feed_for_podcast_with_path_api_key(
     get_rss_feed_for_podcast(State(state), headers, Path(id), api_key_query).await
 }
 
+#[utoipa::path(
+get,
+path="/rss/apiKey/{apiKey}/playlist/{id}",
+responses(
+(status = 200, description = "Gets the rss feed of a playlist (API key in path)"))
+, tag = "rss")]
+pub async fn get_rss_feed_for_playlist_with_path_api_key(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((api_key, id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, CustomError> {
+    let api_key_query = OptionalQuery(Some(RSSAPiKey {
+        api_key: Some(api_key),
+    }));
+    get_rss_feed_for_playlist(State(state), headers, Path(id), api_key_query).await
+}
+
 pub fn get_websocket_router() -> OpenApiRouter<AppState> {
     OpenApiRouter::new()
         .routes(routes!(get_rss_feed))
         .routes(routes!(get_rss_feed_for_podcast))
         .routes(routes!(get_rss_feed_with_path_api_key))
         .routes(routes!(get_rss_feed_for_podcast_with_path_api_key))
+        .routes(routes!(get_rss_feed_for_playlist))
+        .routes(routes!(get_rss_feed_for_playlist_with_path_api_key))
 }
 
 #[cfg(test)]
@@ -1058,4 +1173,60 @@ mod tests {
             .await;
         assert_eq!(response.status_code(), 404);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_rss_feed_for_playlist_lists_playlist_episodes_for_owner() {
+        let server = handle_test_startup().await;
+        let podcast = create_podcast_for_rss();
+        let unique = Uuid::new_v4().to_string();
+        insert_downloaded_episode(
+            &podcast.id,
+            &format!("rss-playlist-episode-{unique}"),
+            &format!("rss-playlist-guid-{unique}"),
+            &format!("podcasts/rss-playlist-{unique}/episode.mp3"),
+            &format!("podcasts/rss-playlist-{unique}/image.jpg"),
+        );
+        let playlist_name = format!("RSS Smart Playlist {unique}");
+        let create_response = server
+            .test_server
+            .post("/api/v1/playlist")
+            .json(&serde_json::json!({
+                "name": playlist_name,
+                "smart": {"podcastIds": [podcast.id.clone()], "downloaded": true}
+            }))
+            .await;
+        assert_eq!(create_response.status_code(), 200);
+        let playlist_id = create_response.json::<serde_json::Value>()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        // The admin api key resolves to the configured admin, who owns the
+        // playlist created through basic auth.
+        let response = server
+            .test_server
+            .get(&format!("/rss/apiKey/test-api-key/playlist/{playlist_id}"))
+            .await;
+        assert_eq!(response.status_code(), 200);
+        let body = response.text();
+        assert!(body.contains(&playlist_name));
+        assert!(body.contains(&format!("rss-playlist-episode-{unique}")));
+
+        let other_user_key = create_api_key_user();
+        let response = server
+            .test_server
+            .get(&with_api_key(
+                &format!("/rss/playlist/{playlist_id}"),
+                &other_user_key,
+            ))
+            .await;
+        assert_eq!(response.status_code(), 404);
+
+        let response = server
+            .test_server
+            .get(&format!("/rss/playlist/{playlist_id}"))
+            .await;
+        assert_eq!(response.status_code(), 403);
+    }
 }
@@ -95,3 +95,24 @@ pub struct TranscriptionStatusMessage {
     pub status: String,
     pub error: Option<String>,
 }
+
+/// Status change of a queued episode download, for the `downloadStatus`
+/// event. `status` mirrors `DownloadJobStatus::as_str()`; a failed attempt
+/// that will be retried is reported as `"pending"` together with its `error`.
+#[derive(Serialize)]
+pub struct DownloadStatusMessage {
+    pub job_id: String,
+    pub episode_id: String,
+    pub status: String,
+    pub attempts: i32,
+    pub error: Option<String>,
+}
+
+/// Byte progress of a running episode download, for the `downloadProgress`
+/// event. `total_bytes` is `None` when the server sent no content length.
+#[derive(Serialize)]
+pub struct DownloadProgressMessage {
+    pub episode_id: String,
+    pub bytes_received: u64,
+    pub total_bytes: Option<u64>,
+}
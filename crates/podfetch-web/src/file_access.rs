@@ -38,11 +38,9 @@ where
         }
     }
 
-    let requested_path = uri_path.replace(server_url, "");
-    let requested_path = requested_path.trim_start_matches('/').to_string();
-    let decoded_path = urlencoding::decode(&requested_path)
-        .map_err(|_| FileAccessError::BadRequest("Error while decoding URL".to_string()))?;
-    let decoded_path = decoded_path.as_ref();
+    let requested_path = requested_file_path(uri_path, server_url);
+    let decoded_path = decode_requested_file_path(uri_path, server_url)?;
+    let decoded_path = decoded_path.as_str();
 
     let podcast_episode = find_episode_by_url(decoded_path).map_err(FileAccessError::Service)?;
     if podcast_episode.is_some() {
@@ -56,3 +54,21 @@ where
         Err(FileAccessError::NotFound)
     }
 }
+
+fn requested_file_path(uri_path: &str, server_url: &str) -> String {
+    uri_path
+        .replace(server_url, "")
+        .trim_start_matches('/')
+        .to_string()
+}
+
+/// The file a `/podcasts/...` request asks for, as stored in
+/// `file_episode_path`.
+pub fn decode_requested_file_path<E: Display>(
+    uri_path: &str,
+    server_url: &str,
+) -> Result<String, FileAccessError<E>> {
+    urlencoding::decode(&requested_file_path(uri_path, server_url))
+        .map(|decoded| decoded.into_owned())
+        .map_err(|_| FileAccessError::BadRequest("Error while decoding URL".to_string()))
+}
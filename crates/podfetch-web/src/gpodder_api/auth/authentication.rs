@@ -11,6 +11,7 @@ use axum_extra::extract::cookie::{Cookie, SameSite};
 use common_infrastructure::config::EnvironmentService;
 use common_infrastructure::error::ErrorSeverity::Warning;
 use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::api_token::ApiTokenScope;
 use podfetch_domain::session::Session;
 use sha256::digest;
 
@@ -195,12 +196,18 @@ fn handle_gpodder_basic_auth(
 
     match user_auth_service.find_by_username(username) {
         Ok(user) => {
-            let password_hash = digest(&password);
-            if user.password.as_deref() != Some(password_hash.as_str()) {
-                tracing::warn!("GPodder basic auth: password mismatch for user '{username}'");
+            // gpodder clients only speak basic auth, so a `gpodder-sync` token
+            // is accepted as password. It is also the only way in once the
+            // account has a second factor.
+            if !user_auth_service.is_api_token_of(&user, &password, ApiTokenScope::GpodderSync)? {
+                let password_hash = digest(&password);
+                if user.password.as_deref() != Some(password_hash.as_str()) {
+                    tracing::warn!("GPodder basic auth: password mismatch for user '{username}'");
+                }
+                require_password_match::<CustomError>(user.password.as_deref(), &password_hash)
+                    .map_err(map_gpodder_error)?;
+                user_auth_service.ensure_password_only_login_allowed(&user)?;
             }
-            require_password_match::<CustomError>(user.password.as_deref(), &password_hash)
-                .map_err(map_gpodder_error)?;
 
             let session = session_service.create_session(user.username, user.id)?;
             tracing::info!("GPodder basic auth: login successful for user '{username}'");
@@ -336,4 +343,39 @@ mod tests {
             .await;
         assert_eq!(response.status_code(), 403);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_login_with_gpodder_sync_token_as_password() {
+        use base64::Engine;
+        use base64::engine::general_purpose;
+        use podfetch_domain::api_token::ApiTokenScope;
+
+        let server = handle_test_startup().await;
+        let state = app_state();
+        let user = state
+            .user_admin_service
+            .create_user(UserTestDataBuilder::new().build())
+            .unwrap();
+        let login = |secret: String| {
+            let encoded = general_purpose::STANDARD.encode(format!("{}:{secret}", user.username));
+            server
+                .test_server
+                .post(&format!("/api/2/auth/{}/login.json", user.username))
+                .clear_headers()
+                .add_header("Authorization", format!("Basic {encoded}"))
+        };
+
+        let (_, sync_secret) = state
+            .api_token_service
+            .create(&user, "Kodi", vec![ApiTokenScope::GpodderSync], None)
+            .unwrap();
+        assert_eq!(login(sync_secret).await.status_code(), 200);
+
+        let (_, rss_secret) = state
+            .api_token_service
+            .create(&user, "Feeds", vec![ApiTokenScope::RssRead], None)
+            .unwrap();
+        assert_eq!(login(rss_secret).await.status_code(), 403);
+    }
 }
@@ -10,6 +10,7 @@ use axum::response::IntoResponse;
 use common_infrastructure::error::CustomError;
 use common_infrastructure::error::CustomErrorInner;
 use common_infrastructure::error::ErrorSeverity::Warning;
+use podfetch_domain::api_token::ApiTokenScope;
 use podfetch_domain::session::Session;
 use sha256::digest;
 
@@ -52,8 +53,17 @@ pub async fn handle_cookie_session(
                 .user_auth_service
                 .find_by_username(&username)
                 .map_err(|_| CustomError::from(CustomErrorInner::Forbidden(Warning)))?;
-            require_password_match::<CustomError>(user.password.as_deref(), &digest(password))
-                .map_err(map_gpodder_error)?;
+            if !state.user_auth_service.is_api_token_of(
+                &user,
+                &password,
+                ApiTokenScope::GpodderSync,
+            )? {
+                require_password_match::<CustomError>(user.password.as_deref(), &digest(password))
+                    .map_err(map_gpodder_error)?;
+                state
+                    .user_auth_service
+                    .ensure_password_only_login_allowed(&user)?;
+            }
             Session::new(user.username, user.id)
         };
 
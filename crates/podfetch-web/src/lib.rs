@@ -1,6 +1,7 @@
 // Existing DTOs, traits, and types
 pub mod auth;
 pub mod cast;
+pub mod client_ip;
 pub mod device;
 pub mod events;
 pub mod file_access;
@@ -9,6 +10,7 @@ pub mod gpodder;
 pub mod history;
 pub mod invite;
 pub mod manifest;
+pub mod metrics;
 pub mod notification;
 pub mod playlist;
 pub mod podcast;
@@ -83,6 +85,8 @@ fn init_test_env() {
         std::env::set_var("GPODDER_INTEGRATION_ENABLED", "true");
         std::env::set_var("AUDIOBOOKSHELF_INTEGRATION_ENABLED", "true");
         std::env::set_var("API_KEY", "test-api-key");
+        std::env::set_var("METRICS_ENABLED", "true");
+        std::env::set_var("METRICS_TOKEN", "test-metrics-token");
         #[cfg(feature = "sqlite")]
         std::env::set_var("DATABASE_URL", "sqlite://./podcast.db");
         #[cfg(all(feature = "postgresql", not(feature = "sqlite")))]
@@ -0,0 +1,304 @@
+//! Prometheus metrics served on `/metrics`.
+//!
+//! Feed refreshes, downloads and HTTP requests are recorded where they
+//! happen. Queue depths, transcoder usage, cast sessions and the database
+//! pool are sampled into gauges whenever the endpoint is scraped.
+
+use podfetch_domain::episode_download::DownloadJobStatus;
+use podfetch_domain::podcast_episode_transcript::TranscriptionJobStatus;
+use prometheus::{
+    HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+const FEED_REFRESH_BUCKETS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+const DOWNLOAD_BUCKETS: &[f64] = &[1.0, 5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0];
+
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Values sampled from the running services right before a scrape.
+#[derive(Debug, Default, Clone)]
+pub struct MetricsSnapshot {
+    pub download_jobs: Vec<(DownloadJobStatus, i64)>,
+    pub transcription_jobs: Vec<(TranscriptionJobStatus, i64)>,
+    pub hls_active_transcodes: usize,
+    pub hls_max_concurrent: usize,
+    pub hls_cache_bytes: u64,
+    pub chromecast_sessions: usize,
+    pub mopidy_sessions: usize,
+    pub db_pool_connections: u32,
+    pub db_pool_idle_connections: u32,
+    pub db_pool_max_size: u32,
+}
+
+pub struct Metrics {
+    registry: Registry,
+    feed_refresh_duration: HistogramVec,
+    feed_refresh_failures: IntCounterVec,
+    download_bytes: IntCounter,
+    download_duration: HistogramVec,
+    download_failures: IntCounter,
+    download_jobs: IntGaugeVec,
+    transcription_jobs: IntGaugeVec,
+    hls_active_transcodes: IntGauge,
+    hls_max_concurrent: IntGauge,
+    hls_cache_bytes: IntGauge,
+    cast_sessions: IntGaugeVec,
+    db_pool_connections: IntGauge,
+    db_pool_idle_connections: IntGauge,
+    db_pool_max_size: IntGauge,
+    http_request_duration: HistogramVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("podfetch".to_string()), None)
+            .expect("metrics registry should be valid");
+
+        let metrics = Self {
+            feed_refresh_duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "feed_refresh_duration_seconds",
+                    "Time spent fetching and processing a podcast feed",
+                )
+                .buckets(FEED_REFRESH_BUCKETS.to_vec()),
+                &["podcast_id", "podcast"],
+            )
+            .expect("metric should be valid"),
+            feed_refresh_failures: IntCounterVec::new(
+                Opts::new(
+                    "feed_refresh_failures_total",
+                    "Failed podcast feed refreshes",
+                ),
+                &["podcast_id", "podcast"],
+            )
+            .expect("metric should be valid"),
+            download_bytes: IntCounter::new(
+                "download_bytes_total",
+                "Bytes of episode audio written to storage",
+            )
+            .expect("metric should be valid"),
+            download_duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "download_duration_seconds",
+                    "Time spent on one download attempt of the download queue",
+                )
+                .buckets(DOWNLOAD_BUCKETS.to_vec()),
+                &["outcome"],
+            )
+            .expect("metric should be valid"),
+            download_failures: IntCounter::new(
+                "download_failures_total",
+                "Failed download attempts, including ones that are retried",
+            )
+            .expect("metric should be valid"),
+            download_jobs: IntGaugeVec::new(
+                Opts::new("download_jobs", "Download queue jobs by status"),
+                &["status"],
+            )
+            .expect("metric should be valid"),
+            transcription_jobs: IntGaugeVec::new(
+                Opts::new("transcription_jobs", "Transcription jobs by status"),
+                &["status"],
+            )
+            .expect("metric should be valid"),
+            hls_active_transcodes: IntGauge::new(
+                "hls_active_transcodes",
+                "HLS segments currently being transcoded",
+            )
+            .expect("metric should be valid"),
+            hls_max_concurrent: IntGauge::new(
+                "hls_max_concurrent_transcodes",
+                "Configured limit of concurrent HLS transcodes",
+            )
+            .expect("metric should be valid"),
+            hls_cache_bytes: IntGauge::new(
+                "hls_cache_bytes",
+                "Disk space used by cached HLS segments",
+            )
+            .expect("metric should be valid"),
+            cast_sessions: IntGaugeVec::new(
+                Opts::new("cast_sessions", "Active cast sessions by backend"),
+                &["backend"],
+            )
+            .expect("metric should be valid"),
+            db_pool_connections: IntGauge::new(
+                "db_pool_connections",
+                "Connections currently opened by the database pool",
+            )
+            .expect("metric should be valid"),
+            db_pool_idle_connections: IntGauge::new(
+                "db_pool_idle_connections",
+                "Idle connections of the database pool",
+            )
+            .expect("metric should be valid"),
+            db_pool_max_size: IntGauge::new(
+                "db_pool_max_connections",
+                "Maximum size of the database pool",
+            )
+            .expect("metric should be valid"),
+            http_request_duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "http_request_duration_seconds",
+                    "Latency of HTTP requests by route",
+                ),
+                &["method", "route", "status"],
+            )
+            .expect("metric should be valid"),
+            registry,
+        };
+        metrics.register_all();
+        metrics
+    }
+
+    fn register_all(&self) {
+        let collectors: Vec<Box<dyn prometheus::core::Collector>> = vec![
+            Box::new(self.feed_refresh_duration.clone()),
+            Box::new(self.feed_refresh_failures.clone()),
+            Box::new(self.download_bytes.clone()),
+            Box::new(self.download_duration.clone()),
+            Box::new(self.download_failures.clone()),
+            Box::new(self.download_jobs.clone()),
+            Box::new(self.transcription_jobs.clone()),
+            Box::new(self.hls_active_transcodes.clone()),
+            Box::new(self.hls_max_concurrent.clone()),
+            Box::new(self.hls_cache_bytes.clone()),
+            Box::new(self.cast_sessions.clone()),
+            Box::new(self.db_pool_connections.clone()),
+            Box::new(self.db_pool_idle_connections.clone()),
+            Box::new(self.db_pool_max_size.clone()),
+            Box::new(self.http_request_duration.clone()),
+        ];
+        for collector in collectors {
+            self.registry
+                .register(collector)
+                .expect("metric names should be unique");
+        }
+    }
+
+    pub fn observe_feed_refresh(
+        &self,
+        podcast_id: &str,
+        podcast: &str,
+        elapsed: Duration,
+        succeeded: bool,
+    ) {
+        let labels = [podcast_id, podcast];
+        self.feed_refresh_duration
+            .with_label_values(&labels)
+            .observe(elapsed.as_secs_f64());
+        if !succeeded {
+            self.feed_refresh_failures.with_label_values(&labels).inc();
+        }
+    }
+
+    pub fn observe_download(&self, elapsed: Duration, succeeded: bool) {
+        let outcome = if succeeded { "success" } else { "failure" };
+        self.download_duration
+            .with_label_values(&[outcome])
+            .observe(elapsed.as_secs_f64());
+        if !succeeded {
+            self.download_failures.inc();
+        }
+    }
+
+    pub fn add_downloaded_bytes(&self, bytes: u64) {
+        self.download_bytes.inc_by(bytes);
+    }
+
+    pub fn observe_http_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        self.http_request_duration
+            .with_label_values(&[method, route, &status.to_string()])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Encodes all metrics in the Prometheus text format after applying the
+    /// sampled gauge values.
+    pub fn render(&self, snapshot: &MetricsSnapshot) -> String {
+        // Statuses that dropped to zero jobs are missing from the snapshot
+        // and must not keep their last value.
+        self.download_jobs.reset();
+        for (status, count) in &snapshot.download_jobs {
+            self.download_jobs
+                .with_label_values(&[status.as_str()])
+                .set(*count);
+        }
+        self.transcription_jobs.reset();
+        for (status, count) in &snapshot.transcription_jobs {
+            self.transcription_jobs
+                .with_label_values(&[status.as_str()])
+                .set(*count);
+        }
+        self.hls_active_transcodes
+            .set(snapshot.hls_active_transcodes as i64);
+        self.hls_max_concurrent
+            .set(snapshot.hls_max_concurrent as i64);
+        self.hls_cache_bytes.set(snapshot.hls_cache_bytes as i64);
+        self.cast_sessions
+            .with_label_values(&["chromecast"])
+            .set(snapshot.chromecast_sessions as i64);
+        self.cast_sessions
+            .with_label_values(&["mopidy"])
+            .set(snapshot.mopidy_sessions as i64);
+        self.db_pool_connections
+            .set(i64::from(snapshot.db_pool_connections));
+        self.db_pool_idle_connections
+            .set(i64::from(snapshot.db_pool_idle_connections));
+        self.db_pool_max_size
+            .set(i64::from(snapshot.db_pool_max_size));
+
+        TextEncoder::new()
+            .encode_to_string(&self.registry.gather())
+            .unwrap_or_else(|err| {
+                tracing::error!("Could not encode metrics: {err}");
+                String::new()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_resets_job_gauges_for_statuses_without_jobs() {
+        let metrics = Metrics::new();
+        metrics.render(&MetricsSnapshot {
+            download_jobs: vec![(DownloadJobStatus::Pending, 3)],
+            ..Default::default()
+        });
+
+        let output = metrics.render(&MetricsSnapshot {
+            download_jobs: vec![(DownloadJobStatus::Failed, 1)],
+            transcription_jobs: vec![(TranscriptionJobStatus::Running, 2)],
+            mopidy_sessions: 1,
+            ..Default::default()
+        });
+
+        assert!(output.contains("podfetch_download_jobs{status=\"failed\"} 1"));
+        assert!(!output.contains("podfetch_download_jobs{status=\"pending\"}"));
+        assert!(output.contains("podfetch_transcription_jobs{status=\"running\"} 2"));
+        assert!(output.contains("podfetch_cast_sessions{backend=\"chromecast\"} 0"));
+        assert!(output.contains("podfetch_cast_sessions{backend=\"mopidy\"} 1"));
+    }
+
+    #[test]
+    fn feed_refresh_failures_are_counted_per_podcast() {
+        let metrics = Metrics::new();
+        metrics.observe_feed_refresh("7", "Tech Talk", Duration::from_millis(300), true);
+        metrics.observe_feed_refresh("7", "Tech Talk", Duration::from_millis(300), false);
+        metrics.observe_feed_refresh("8", "News", Duration::from_millis(300), true);
+
+        let output = metrics.render(&MetricsSnapshot::default());
+
+        assert!(output.contains(
+            "podfetch_feed_refresh_failures_total{podcast=\"Tech Talk\",podcast_id=\"7\"} 1"
+        ));
+        assert!(!output.contains("podfetch_feed_refresh_failures_total{podcast=\"News\""));
+        assert!(output.contains(
+            "podfetch_feed_refresh_duration_seconds_count{podcast=\"Tech Talk\",podcast_id=\"7\"} 2"
+        ));
+    }
+}
@@ -10,6 +10,8 @@ pub struct Notification {
     pub message: String,
     pub created_at: String,
     pub status: String,
+    /// Owner of the notification; `None` for notifications every user sees.
+    pub user_id: Option<String>,
 }
 
 impl From<podfetch_domain::notification::Notification> for Notification {
@@ -20,6 +22,7 @@ impl From<podfetch_domain::notification::Notification> for Notification {
             message: value.message,
             created_at: value.created_at,
             status: value.status,
+            user_id: value.user_id.map(|user_id| user_id.to_string()),
         }
     }
 }
@@ -32,6 +35,9 @@ impl From<Notification> for podfetch_domain::notification::Notification {
             message: value.message,
             created_at: value.created_at,
             status: value.status,
+            user_id: value
+                .user_id
+                .and_then(|user_id| Uuid::parse_str(&user_id).ok()),
         }
     }
 }
@@ -44,20 +50,23 @@ pub struct NotificationId {
 pub trait NotificationApplicationService {
     type Error;
 
-    fn get_unread_notifications(&self) -> Result<Vec<Notification>, Self::Error>;
-    fn dismiss_notification(&self, id: Uuid) -> Result<(), Self::Error>;
+    fn get_unread_notifications(&self, user_id: Uuid) -> Result<Vec<Notification>, Self::Error>;
+    fn dismiss_notification(&self, id: Uuid, user_id: Uuid) -> Result<(), Self::Error>;
 }
 
-pub fn get_unread_notifications<S>(service: &S) -> Result<Vec<Notification>, S::Error>
+pub fn get_unread_notifications<S>(
+    service: &S,
+    user_id: Uuid,
+) -> Result<Vec<Notification>, S::Error>
 where
     S: NotificationApplicationService,
 {
-    service.get_unread_notifications()
+    service.get_unread_notifications(user_id)
 }
 
-pub fn dismiss_notification<S>(service: &S, id: Uuid) -> Result<(), S::Error>
+pub fn dismiss_notification<S>(service: &S, id: Uuid, user_id: Uuid) -> Result<(), S::Error>
 where
     S: NotificationApplicationService,
 {
-    service.dismiss_notification(id)
+    service.dismiss_notification(id, user_id)
 }
@@ -11,7 +11,11 @@ pub struct PlaylistItem {
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct PlaylistDtoPost {
     pub name: String,
+    #[serde(default)]
     pub items: Vec<PlaylistItem>,
+    /// Makes the playlist a smart playlist; `items` are ignored then.
+    #[serde(default)]
+    pub smart: Option<SmartPlaylistQuery>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
@@ -19,6 +23,71 @@ pub struct PlaylistDto<T> {
     pub id: String,
     pub name: String,
     pub items: Vec<T>,
+    pub smart: Option<SmartPlaylistQuery>,
+}
+
+/// Listening progress of an episode for the playlist owner.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PlayState {
+    Unplayed,
+    InProgress,
+    Played,
+}
+
+/// Triage state to match; `inbox` selects episodes that weren't triaged yet.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TriageFilter {
+    Inbox,
+    Queued,
+    Archived,
+    Dismissed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SmartPlaylistOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+    ShortestFirst,
+    LongestFirst,
+}
+
+/// Stored rules of a smart playlist, evaluated whenever the playlist is read.
+/// Every condition that is set has to hold; podcasts and tags are combined,
+/// so `podcastIds` plus `tagIds` selects the episodes of either.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartPlaylistQuery {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub podcast_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tag_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub play_state: Option<PlayState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub triage: Option<TriageFilter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favorite: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloaded: Option<bool>,
+    /// Only episodes published within this many days.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_within_days: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_duration_seconds: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_duration_seconds: Option<u32>,
+    #[serde(default)]
+    pub order: SmartPlaylistOrder,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Leaves out episodes that would push the total length past this many
+    /// seconds, e.g. `7200` for "at most two hours".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_total_seconds: Option<u32>,
 }
 
 pub trait PlaylistApplicationService {
@@ -4,6 +4,7 @@ use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
 use http::HeaderMap;
 use podfetch_domain::ordering::{OrderCriteria, OrderOption};
 use podfetch_domain::podcast::Podcast;
+use podfetch_domain::podcast_feed::PodcastFeedHealth;
 use podfetch_domain::user::User;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -134,6 +135,41 @@ pub struct PodcastDto {
     pub tags: Vec<Tag>,
 }
 
+/// A podcast whose feed failed on its last refresh.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PodcastFeedHealthDto {
+    pub podcast_id: String,
+    pub name: String,
+    pub rssfeed: String,
+    pub active: bool,
+    pub last_success_at: Option<String>,
+    pub last_failure_at: Option<String>,
+    pub consecutive_failures: i32,
+    pub failing_since: Option<String>,
+    pub last_error: Option<String>,
+    /// Status of the last response, missing if the server was not reached.
+    pub last_http_status: Option<i32>,
+}
+
+impl PodcastFeedHealthDto {
+    pub fn new(podcast: &Podcast, health: PodcastFeedHealth) -> Self {
+        let to_rfc3339 = |at: Option<chrono::NaiveDateTime>| at.map(|at| at.and_utc().to_rfc3339());
+        Self {
+            podcast_id: podcast.id.to_string(),
+            name: podcast.name.clone(),
+            rssfeed: podcast.rssfeed.clone(),
+            active: podcast.active,
+            last_success_at: to_rfc3339(health.last_success_at),
+            last_failure_at: to_rfc3339(health.last_failure_at),
+            consecutive_failures: health.consecutive_failures,
+            failing_since: to_rfc3339(health.failing_since),
+            last_error: health.last_error,
+            last_http_status: health.last_http_status,
+        }
+    }
+}
+
 pub fn map_podcast_to_dto(value: Podcast, server_url: &str) -> PodcastDto {
     let image_url = resolve_image_url(&value.image_url, server_url);
     let keywords = dedupe_keywords(value.keywords.clone());
@@ -6,6 +6,13 @@ use utoipa::{IntoParams, ToSchema};
 pub struct OptionalId {
     pub last_podcast_episode: Option<String>,
     pub only_unlistened: Option<bool>,
+    /// Only episodes of this season.
+    pub season: Option<i32>,
+    /// Only episodes of this type: `full`, `trailer` or `bonus`.
+    pub episode_type: Option<String>,
+    /// `date` (default, newest first) or `episode` (by season and episode
+    /// number). The `episode` order is not paginated.
+    pub sort: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
@@ -15,6 +22,11 @@ pub struct PodcastChapterDto {
     pub start_time: i32,
     pub title: String,
     pub end_time: i32,
+    pub href: Option<String>,
+    pub image: Option<String>,
+    /// `feed` for `<podcast:chapters>`, `embedded` for chapters read from the
+    /// audio file.
+    pub source: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, IntoParams)]
@@ -1,3 +1,4 @@
+use crate::services::loudness::service::replay_gain_db;
 use crate::url_rewriting::resolve_image_url;
 use chrono::NaiveDateTime;
 use common_infrastructure::config::FileHandlerType;
@@ -32,6 +33,12 @@ pub struct PodcastEpisodeDto {
     pub deleted: bool,
     pub episode_numbering_processed: bool,
     pub favored: Option<bool>,
+    pub season: Option<i32>,
+    pub episode_number: Option<i32>,
+    pub episode_type: Option<String>,
+    /// Gain in dB that levels the downloaded file to the ReplayGain
+    /// reference, set once its loudness has been measured.
+    pub replay_gain_db: Option<f64>,
 }
 
 pub enum FileType {
@@ -84,6 +91,10 @@ impl PodcastEpisodeDto {
             episode_numbering_processed: episode.episode_numbering_processed,
             favored: favorite.map(|f| f.favorite),
             status: episode.is_downloaded(),
+            season: episode.season,
+            episode_number: episode.episode_number,
+            episode_type: episode.episode_type.clone(),
+            replay_gain_db: episode.loudness_lufs.map(replay_gain_db),
         }
     }
 
@@ -129,6 +140,10 @@ impl PodcastEpisodeDto {
             episode_numbering_processed: episode.episode_numbering_processed,
             favored: favorite.map(|f| f.favorite),
             status: episode.is_downloaded(),
+            season: episode.season,
+            episode_number: episode.episode_number,
+            episode_type: episode.episode_type.clone(),
+            replay_gain_db: episode.loudness_lufs.map(replay_gain_db),
         }
     }
 }
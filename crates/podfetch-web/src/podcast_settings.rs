@@ -26,6 +26,22 @@ pub struct PodcastSetting {
     pub cover_filename: String,
     #[serde(default)]
     pub auto_transcribe: bool,
+    #[serde(default = "crate::settings::default_refresh_mode")]
+    pub refresh_mode: String,
+    #[serde(default)]
+    pub refresh_interval: i32,
+    #[serde(default)]
+    pub retention_keep_newest: i32,
+    #[serde(default)]
+    pub retention_played_days: i32,
+    #[serde(default)]
+    pub retention_keep_unplayed: bool,
+    #[serde(default)]
+    pub retention_max_size_mb: i32,
+    /// Unset follows the instance settings, `original` keeps downloads as
+    /// they are, anything else is the id of a transcoding profile.
+    #[serde(default)]
+    pub transcoding_profile_id: Option<String>,
 }
 
 impl From<podfetch_domain::podcast_settings::PodcastSetting> for PodcastSetting {
@@ -49,6 +65,13 @@ impl From<podfetch_domain::podcast_settings::PodcastSetting> for PodcastSetting
             nfo_format: value.nfo_format,
             cover_filename: value.cover_filename,
             auto_transcribe: value.auto_transcribe,
+            refresh_mode: value.refresh_mode,
+            refresh_interval: value.refresh_interval,
+            retention_keep_newest: value.retention_keep_newest,
+            retention_played_days: value.retention_played_days,
+            retention_keep_unplayed: value.retention_keep_unplayed,
+            retention_max_size_mb: value.retention_max_size_mb,
+            transcoding_profile_id: value.transcoding_profile_id,
         }
     }
 }
@@ -74,6 +97,13 @@ impl From<PodcastSetting> for podfetch_domain::podcast_settings::PodcastSetting
             nfo_format: value.nfo_format,
             cover_filename: value.cover_filename,
             auto_transcribe: value.auto_transcribe,
+            refresh_mode: value.refresh_mode,
+            refresh_interval: value.refresh_interval,
+            retention_keep_newest: value.retention_keep_newest,
+            retention_played_days: value.retention_played_days,
+            retention_keep_unplayed: value.retention_keep_unplayed,
+            retention_max_size_mb: value.retention_max_size_mb,
+            transcoding_profile_id: value.transcoding_profile_id,
         }
     }
 }
@@ -16,6 +16,7 @@ use crate::audiobookshelf_api::controllers::search::get_search_router as get_aud
 use crate::audiobookshelf_api::controllers::server_status::get_status_router as get_audiobookshelf_status_router;
 use crate::audiobookshelf_api::controllers::sessions::get_sessions_router as get_audiobookshelf_sessions_router;
 use crate::audiobookshelf_api::controllers::uploads::get_upload_router as get_audiobookshelf_upload_router;
+use crate::controllers::metrics_controller::get_metrics_router;
 use crate::gpodder::{ClientParametrization, build_client_parametrization};
 use crate::gpodder_api::auth::authentication::get_auth_router;
 use crate::gpodder_api::device::device_controller::get_device_router;
@@ -51,9 +52,12 @@ pub fn global_routes(state: AppState, api_config: OpenApiRouter) -> OpenApiRoute
         .unwrap_or_default();
     let service = api_config;
 
-    let inner = OpenApiRouter::new()
+    let mut inner = OpenApiRouter::new()
         .merge(get_client_parametrization_router())
         .merge(service);
+    if ENVIRONMENT_SERVICE.metrics_config.is_some() {
+        inner = inner.merge(get_metrics_router().with_state(state.clone()));
+    }
 
     let mut router = if base_path.is_empty() || base_path == "/" {
         OpenApiRouter::new().merge(inner)
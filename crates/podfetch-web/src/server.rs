@@ -1,7 +1,8 @@
 use crate::events::{
-    CastEndedMessage, CastEndedReason, CastStatusMessage, OpmlAddedMessage, OpmlErrorMessage,
-    PodcastAddedMessage, PodcastEpisodeDeleteMessage, PodcastEpisodeOfflineAvailableMessage,
-    PodcastEpisodesAdded, PodcastRefreshedMessage, PodcastType, TranscriptionStatusMessage,
+    CastEndedMessage, CastEndedReason, CastStatusMessage, DownloadProgressMessage,
+    DownloadStatusMessage, OpmlAddedMessage, OpmlErrorMessage, PodcastAddedMessage,
+    PodcastEpisodeDeleteMessage, PodcastEpisodeOfflineAvailableMessage, PodcastEpisodesAdded,
+    PodcastRefreshedMessage, PodcastType, TranscriptionStatusMessage,
 };
 use crate::podcast::PodcastDto;
 use crate::podcast::map_podcast_to_dto;
@@ -212,4 +213,42 @@ impl ChatServerHandle {
             "transcriptionStatus",
         );
     }
+
+    /// Broadcasts a download job's status change so the UI can keep its
+    /// queue view current without polling `/downloads/jobs`.
+    pub fn broadcast_download_status(
+        job_id: &str,
+        episode_id: &str,
+        status: &str,
+        attempts: i32,
+        error: Option<&str>,
+    ) {
+        Self::send_broadcast_sync(
+            MAIN_ROOM.parse().unwrap(),
+            &DownloadStatusMessage {
+                job_id: job_id.to_string(),
+                episode_id: episode_id.to_string(),
+                status: status.to_string(),
+                attempts,
+                error: error.map(|e| e.to_string()),
+            },
+            "downloadStatus",
+        );
+    }
+
+    pub fn broadcast_download_progress(
+        episode_id: &str,
+        bytes_received: u64,
+        total_bytes: Option<u64>,
+    ) {
+        Self::send_broadcast_sync(
+            MAIN_ROOM.parse().unwrap(),
+            &DownloadProgressMessage {
+                episode_id: episode_id.to_string(),
+                bytes_received,
+                total_bytes,
+            },
+            "downloadProgress",
+        );
+    }
 }
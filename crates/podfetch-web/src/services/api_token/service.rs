@@ -0,0 +1,99 @@
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::api_token::{ApiToken, ApiTokenRepository, ApiTokenScope};
+use podfetch_domain::user::User;
+use podfetch_persistence::adapters::ApiTokenRepositoryImpl;
+use podfetch_persistence::db::database;
+use sha256::digest;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Prefix of generated token secrets, so they can be told apart from JWTs
+/// and account api keys.
+pub const API_TOKEN_PREFIX: &str = "pft_";
+
+#[derive(Clone)]
+pub struct ApiTokenService {
+    repository: Arc<dyn ApiTokenRepository<Error = CustomError>>,
+}
+
+impl ApiTokenService {
+    pub fn new(repository: Arc<dyn ApiTokenRepository<Error = CustomError>>) -> Self {
+        Self { repository }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(Arc::new(ApiTokenRepositoryImpl::new(database())))
+    }
+
+    pub fn list(&self, user: &User) -> Result<Vec<ApiToken>, CustomError> {
+        self.repository.list_by_user(user.id)
+    }
+
+    /// Creates a token for `user` and returns it together with its secret.
+    /// The secret is not stored and can't be shown again.
+    pub fn create(
+        &self,
+        user: &User,
+        name: &str,
+        scopes: Vec<ApiTokenScope>,
+        expires_at: Option<chrono::NaiveDateTime>,
+    ) -> Result<(ApiToken, String), CustomError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(bad_request("a token needs a name"));
+        }
+        if scopes.is_empty() {
+            return Err(bad_request("a token needs at least one scope"));
+        }
+        let now = chrono::Utc::now().naive_utc();
+        if expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(bad_request("the expiry lies in the past"));
+        }
+
+        let secret = format!(
+            "{API_TOKEN_PREFIX}{}{}",
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+        let mut unique_scopes = Vec::with_capacity(scopes.len());
+        for scope in scopes {
+            if !unique_scopes.contains(&scope) {
+                unique_scopes.push(scope);
+            }
+        }
+        let token = self.repository.create(ApiToken {
+            id: Uuid::new_v4(),
+            user_id: user.id,
+            name: name.to_string(),
+            token_hash: digest(secret.as_str()),
+            scopes: unique_scopes,
+            expires_at,
+            last_used_at: None,
+            created_at: now,
+        })?;
+        Ok((token, secret))
+    }
+
+    /// The token with secret `secret`, expired or not.
+    pub fn find_by_secret(&self, secret: &str) -> Result<Option<ApiToken>, CustomError> {
+        self.repository.find_by_token_hash(&digest(secret))
+    }
+
+    /// Drops tokens past their expiry, which can't be used anymore anyway.
+    pub fn purge_expired(&self) -> Result<usize, CustomError> {
+        self.repository
+            .delete_expired(chrono::Utc::now().naive_utc())
+    }
+
+    pub fn delete(&self, user: &User, id: Uuid) -> Result<(), CustomError> {
+        match self.repository.delete(id, user.id)? {
+            true => Ok(()),
+            false => Err(CustomErrorInner::NotFound(Warning).into()),
+        }
+    }
+}
+
+fn bad_request(message: &str) -> CustomError {
+    CustomErrorInner::BadRequest(message.to_string(), Warning).into()
+}
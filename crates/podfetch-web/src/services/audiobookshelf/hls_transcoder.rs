@@ -9,13 +9,22 @@
 //! `supportedMimeTypes` does NOT include the source's MIME (or it sends
 //! `forceTranscode=true`), the play handler picks playMethod=1 (HLS); else
 //! playMethod=0 (direct).
+//!
+//! The native streaming endpoints reuse the same cache and permits for
+//! `?codec=`/`?bitrate=` requests. A progressive stream has no segment
+//! timestamps to hide the encoder delay of separately encoded segments, so
+//! it is encoded by one continuous ffmpeg run into a single cache file that
+//! the response follows while it grows.
 
+use axum::body::Bytes;
 use common_infrastructure::config::EnvironmentService;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::sync::Arc;
-use std::time::SystemTime;
-use tokio::sync::Semaphore;
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncReadExt;
+use tokio::sync::{Semaphore, watch};
 
 pub const SEGMENT_DURATION_SECONDS: f64 = 6.0;
 
@@ -86,6 +95,99 @@ pub fn build_media_playlist(stream_id: &str, duration_seconds: f64) -> String {
     out
 }
 
+/// Codecs a progressive stream can be transcoded to. Both write headerless
+/// frames, so the output can be sent while ffmpeg is still encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamCodec {
+    Mp3,
+    Aac,
+}
+
+impl StreamCodec {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamCodec::Mp3 => "mp3",
+            StreamCodec::Aac => "aac",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)] // fallible parse returning Option, not FromStr
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "mp3" => Some(StreamCodec::Mp3),
+            "aac" => Some(StreamCodec::Aac),
+            _ => None,
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            StreamCodec::Mp3 => "audio/mpeg",
+            StreamCodec::Aac => "audio/aac",
+        }
+    }
+
+    fn ffmpeg_args(&self) -> &'static [&'static str] {
+        match self {
+            // No Xing/ID3 header, ffmpeg can't go back to fill it in on a pipe.
+            StreamCodec::Mp3 => &[
+                "-c:a",
+                "libmp3lame",
+                "-write_xing",
+                "0",
+                "-id3v2_version",
+                "0",
+                "-f",
+                "mp3",
+            ],
+            StreamCodec::Aac => &["-c:a", "aac", "-f", "adts"],
+        }
+    }
+}
+
+/// What ffmpeg reads from. Episode URLs come from feeds, so ffmpeg only gets
+/// the protocols the input needs: a remote source can't point it at local
+/// files through `file:`, `concat:` or a playlist, and a file can't be read
+/// as anything but a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscodeInput {
+    File(PathBuf),
+    Url(String),
+}
+
+impl TranscodeInput {
+    /// The URL as an input, `None` unless it is a valid http(s) URL.
+    pub fn remote(url: &str) -> Option<Self> {
+        let parsed = url::Url::parse(url).ok()?;
+        matches!(parsed.scheme(), "http" | "https").then(|| TranscodeInput::Url(parsed.into()))
+    }
+
+    fn ffmpeg_args(&self) -> [OsString; 4] {
+        let (protocols, input) = match self {
+            TranscodeInput::File(path) => {
+                // The prefix keeps a path with a colon from being taken for
+                // a protocol.
+                let mut input = OsString::from("file:");
+                input.push(path);
+                ("file", input)
+            }
+            TranscodeInput::Url(url) => ("http,https,tcp,tls", OsString::from(url)),
+        };
+        [
+            "-protocol_whitelist".into(),
+            protocols.into(),
+            "-i".into(),
+            input,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamEncoding {
+    pub codec: StreamCodec,
+    pub bitrate_kbps: u32,
+}
+
 #[derive(Clone)]
 pub struct HlsTranscoder {
     pub environment: Arc<EnvironmentService>,
@@ -94,13 +196,34 @@ pub struct HlsTranscoder {
 
 impl HlsTranscoder {
     pub fn new(environment: Arc<EnvironmentService>) -> Self {
-        let max = environment.audiobookshelf_transcoder_max_concurrent.max(1) as usize;
+        let max = Self::max_concurrent_for(&environment);
         Self {
             environment,
             concurrency: Arc::new(Semaphore::new(max)),
         }
     }
 
+    fn max_concurrent_for(environment: &EnvironmentService) -> usize {
+        environment.audiobookshelf_transcoder_max_concurrent.max(1) as usize
+    }
+
+    pub fn max_concurrent(&self) -> usize {
+        Self::max_concurrent_for(&self.environment)
+    }
+
+    /// Number of ffmpeg processes currently holding a transcode permit.
+    pub fn active_transcodes(&self) -> usize {
+        self.max_concurrent()
+            .saturating_sub(self.concurrency.available_permits())
+    }
+
+    /// Bytes currently used by cached segments on disk.
+    pub fn cache_size_bytes(&self) -> u64 {
+        let mut files: Vec<(PathBuf, u64, SystemTime)> = Vec::new();
+        collect_segment_files(&self.cache_root(), &mut files);
+        files.iter().map(|f| f.1).sum()
+    }
+
     pub fn cache_root(&self) -> PathBuf {
         PathBuf::from(&self.environment.audiobookshelf_data_dir).join("hls")
     }
@@ -111,6 +234,26 @@ impl HlsTranscoder {
             .join(format!("seg-{segment}.ts"))
     }
 
+    /// Cache file of a progressive stream that starts at `start_segment` and
+    /// runs to the end of the source. `stream_key` identifies the source, the
+    /// encoding is part of the directory so every variant is cached on its
+    /// own.
+    pub fn stream_path(
+        &self,
+        stream_key: &str,
+        encoding: StreamEncoding,
+        start_segment: u32,
+    ) -> PathBuf {
+        self.cache_root()
+            .join("stream")
+            .join(format!(
+                "{stream_key}-{}-{}",
+                encoding.codec.as_str(),
+                encoding.bitrate_kbps
+            ))
+            .join(format!("from-{start_segment}.{}", encoding.codec.as_str()))
+    }
+
     /// Produce (or return cached) TS bytes for the requested segment.
     pub async fn ensure_segment(
         &self,
@@ -119,6 +262,108 @@ impl HlsTranscoder {
         segment: u32,
     ) -> Result<PathBuf, TranscodeError> {
         let segment_file = self.segment_path(stream_id, segment);
+        self.ensure_cached_segment(
+            segment_file,
+            &TranscodeInput::File(source_audio.to_path_buf()),
+            segment,
+            &["-c:a", "aac", "-b:a", "128k", "-f", "mpegts"],
+        )
+        .await
+    }
+
+    /// Opens a progressive stream of `source` from `start_segment` to its
+    /// end, from the cache or from a new ffmpeg run that encodes in the
+    /// background. A start past the end of the source gives an empty stream.
+    pub async fn open_stream(
+        &self,
+        stream_key: &str,
+        source: &TranscodeInput,
+        encoding: StreamEncoding,
+        start_segment: u32,
+    ) -> Result<EncodedStream, TranscodeError> {
+        let stream_file = self.stream_path(stream_key, encoding, start_segment);
+        if let Ok(file) = tokio::fs::File::open(&stream_file).await {
+            let (_, state) = watch::channel(EncodeState::Finished);
+            return Ok(EncodedStream { file, state });
+        }
+        if let Some(parent) = stream_file.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| TranscodeError::Io(e.to_string()))?;
+        }
+
+        // The reader opens the partial file before ffmpeg starts, so it
+        // keeps following the same file after the rename into place.
+        let partial_file = partial_segment_path(&stream_file);
+        let output =
+            std::fs::File::create(&partial_file).map_err(|e| TranscodeError::Io(e.to_string()))?;
+        let file = tokio::fs::File::open(&partial_file)
+            .await
+            .map_err(|e| TranscodeError::Io(e.to_string()))?;
+
+        let bitrate = format!("{}k", encoding.bitrate_kbps);
+        let mut command = tokio::process::Command::new("ffmpeg");
+        command
+            .args(["-v", "error", "-ss"])
+            .arg(format!(
+                "{}",
+                start_segment as f64 * SEGMENT_DURATION_SECONDS
+            ))
+            .args(source.ffmpeg_args())
+            .args(["-map", "0:a:0", "-map_metadata", "-1", "-b:a", &bitrate])
+            .args(encoding.codec.ffmpeg_args())
+            .arg("pipe:1")
+            .stdin(Stdio::null())
+            .stdout(output);
+
+        let (sender, state) = watch::channel(EncodeState::Running);
+        let transcoder = self.clone();
+        tokio::spawn(async move {
+            let result = transcoder
+                .run_stream_encode(command, &partial_file, &stream_file)
+                .await;
+            if result.is_err() {
+                let _ = std::fs::remove_file(&partial_file);
+            }
+            let _ = sender.send(match result {
+                Ok(()) => EncodeState::Finished,
+                Err(e) => EncodeState::Failed(e.to_string()),
+            });
+        });
+        Ok(EncodedStream { file, state })
+    }
+
+    async fn run_stream_encode(
+        &self,
+        mut command: tokio::process::Command,
+        partial_file: &Path,
+        stream_file: &Path,
+    ) -> Result<(), TranscodeError> {
+        let status = {
+            let _permit = self
+                .concurrency
+                .acquire()
+                .await
+                .map_err(|_| TranscodeError::Other("semaphore closed".to_string()))?;
+            command
+                .status()
+                .await
+                .map_err(|e| TranscodeError::Spawn(e.to_string()))?
+        };
+        if !status.success() {
+            return Err(TranscodeError::FfmpegFailed(status.code().unwrap_or(-1)));
+        }
+        std::fs::rename(partial_file, stream_file)
+            .map_err(|e| TranscodeError::Io(e.to_string()))?;
+        self.evict_if_over_budget();
+        Ok(())
+    }
+
+    async fn ensure_cached_segment(
+        &self,
+        segment_file: PathBuf,
+        source_audio: &TranscodeInput,
+        segment: u32,
+        output_args: &[&str],
+    ) -> Result<PathBuf, TranscodeError> {
         if segment_file.is_file() {
             return Ok(segment_file);
         }
@@ -137,26 +382,34 @@ impl HlsTranscoder {
             return Ok(segment_file);
         }
 
+        // ffmpeg writes next to the segment and the result is renamed into
+        // place, so a failed or killed run never leaves a truncated segment
+        // that later requests would take for a finished one.
+        let partial_file = partial_segment_path(&segment_file);
         let start = (segment as f64) * SEGMENT_DURATION_SECONDS;
         let status = Command::new("ffmpeg")
             .args(["-v", "error", "-ss", &format!("{start}"), "-t"])
             .arg(format!("{SEGMENT_DURATION_SECONDS}"))
-            .args(["-i"])
-            .arg(source_audio)
-            .args([
-                "-map", "0:a:0", "-c:a", "aac", "-b:a", "128k", "-f", "mpegts", "-y",
-            ])
-            .arg(&segment_file)
+            .args(source_audio.ffmpeg_args())
+            .args(["-map", "0:a:0"])
+            .args(output_args)
+            .arg("-y")
+            .arg(&partial_file)
             .status()
             .map_err(|e| TranscodeError::Spawn(e.to_string()))?;
         if !status.success() {
+            let _ = std::fs::remove_file(&partial_file);
             return Err(TranscodeError::FfmpegFailed(status.code().unwrap_or(-1)));
         }
-        if !segment_file.is_file() {
+        if !partial_file.is_file() {
             return Err(TranscodeError::Other(
                 "ffmpeg produced no segment output".to_string(),
             ));
         }
+        std::fs::rename(&partial_file, &segment_file).map_err(|e| {
+            let _ = std::fs::remove_file(&partial_file);
+            TranscodeError::Io(e.to_string())
+        })?;
         self.evict_if_over_budget();
         Ok(segment_file)
     }
@@ -175,6 +428,13 @@ impl HlsTranscoder {
             if total <= max_bytes {
                 break;
             }
+            // ffmpeg is still writing those.
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "part")
+            {
+                continue;
+            }
             if std::fs::remove_file(&path).is_ok() {
                 total = total.saturating_sub(size);
             }
@@ -182,6 +442,71 @@ impl HlsTranscoder {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EncodeState {
+    Running,
+    Finished,
+    Failed(String),
+}
+
+/// A progressive stream being read from its cache file, which ffmpeg may
+/// still be writing.
+pub struct EncodedStream {
+    file: tokio::fs::File,
+    state: watch::Receiver<EncodeState>,
+}
+
+/// How long a reader that caught up with ffmpeg waits before looking for
+/// more output.
+const STREAM_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const STREAM_CHUNK_BYTES: usize = 64 * 1024;
+
+impl EncodedStream {
+    /// The next bytes of the stream, `None` once it is complete. Waits while
+    /// ffmpeg hasn't written more yet.
+    pub async fn next_chunk(&mut self) -> Result<Option<Bytes>, TranscodeError> {
+        let mut buffer = vec![0; STREAM_CHUNK_BYTES];
+        loop {
+            // Taken before reading: once ffmpeg is done, a read that comes
+            // back empty really is the end.
+            let state = self.state.borrow().clone();
+            let read = self
+                .file
+                .read(&mut buffer)
+                .await
+                .map_err(|e| TranscodeError::Io(e.to_string()))?;
+            if read > 0 {
+                buffer.truncate(read);
+                return Ok(Some(Bytes::from(buffer)));
+            }
+            match state {
+                EncodeState::Finished => return Ok(None),
+                EncodeState::Failed(e) => return Err(TranscodeError::Other(e)),
+                EncodeState::Running => {
+                    tokio::select! {
+                        changed = self.state.changed() => {
+                            if changed.is_err() {
+                                return Err(TranscodeError::Other(
+                                    "the encoder stopped".to_string(),
+                                ));
+                            }
+                        }
+                        _ = tokio::time::sleep(STREAM_POLL_INTERVAL) => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A file of its own for every ffmpeg run, so concurrent runs for the same
+/// output don't write into each other.
+fn partial_segment_path(segment_file: &Path) -> PathBuf {
+    let mut name = segment_file.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.part", uuid::Uuid::new_v4()));
+    segment_file.with_file_name(name)
+}
+
 fn collect_segment_files(root: &Path, out: &mut Vec<(PathBuf, u64, SystemTime)>) {
     let Ok(entries) = std::fs::read_dir(root) else {
         return;
@@ -291,6 +616,230 @@ mod tests {
         assert!(m.contains("#EXTINF:2."));
     }
 
+    #[test]
+    fn stream_segments_are_cached_per_encoding() {
+        let transcoder = HlsTranscoder::new(Arc::new(EnvironmentService::default()));
+        let mp3 = StreamEncoding {
+            codec: StreamCodec::Mp3,
+            bitrate_kbps: 64,
+        };
+        let aac = StreamEncoding {
+            codec: StreamCodec::Aac,
+            bitrate_kbps: 64,
+        };
+        let mp3_path = transcoder.stream_path("episode", mp3, 3);
+        assert!(mp3_path.starts_with(transcoder.cache_root().join("stream")));
+        assert!(mp3_path.ends_with("episode-mp3-64/from-3.mp3"));
+        assert_ne!(
+            mp3_path.parent(),
+            transcoder.stream_path("episode", aac, 3).parent()
+        );
+    }
+
+    #[test]
+    fn segments_are_written_to_a_partial_file_first() {
+        let segment = Path::new("/cache/hls/stream/episode-mp3-64/seg-3.mp3");
+        let partial = partial_segment_path(segment);
+        assert_eq!(partial.parent(), segment.parent());
+        let name = partial.file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.starts_with("seg-3.mp3.") && name.ends_with(".part"));
+        assert_ne!(partial, partial_segment_path(segment));
+    }
+
+    fn temp_transcoder() -> HlsTranscoder {
+        let environment = EnvironmentService {
+            audiobookshelf_data_dir: std::env::temp_dir()
+                .join(format!("podfetch-hls-{}", uuid::Uuid::new_v4()))
+                .to_string_lossy()
+                .to_string(),
+            ..EnvironmentService::default()
+        };
+        HlsTranscoder::new(Arc::new(environment))
+    }
+
+    async fn read_stream(mut stream: EncodedStream) -> Result<Vec<u8>, TranscodeError> {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = stream.next_chunk().await? {
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+
+    #[tokio::test]
+    async fn failed_runs_leave_no_stream_behind() {
+        let transcoder = temp_transcoder();
+        let encoding = StreamEncoding {
+            codec: StreamCodec::Mp3,
+            bitrate_kbps: 64,
+        };
+
+        let stream = transcoder
+            .open_stream(
+                "missing",
+                &TranscodeInput::File("/does/not/exist.mp3".into()),
+                encoding,
+                0,
+            )
+            .await
+            .unwrap();
+
+        assert!(read_stream(stream).await.is_err());
+        let stream_file = transcoder.stream_path("missing", encoding, 0);
+        let leftovers = std::fs::read_dir(stream_file.parent().unwrap())
+            .unwrap()
+            .count();
+        assert_eq!(leftovers, 0);
+        let _ = std::fs::remove_dir_all(transcoder.cache_root());
+    }
+
+    #[tokio::test]
+    async fn cached_streams_are_served_without_ffmpeg() {
+        let transcoder = temp_transcoder();
+        let encoding = StreamEncoding {
+            codec: StreamCodec::Aac,
+            bitrate_kbps: 48,
+        };
+        let stream_file = transcoder.stream_path("episode", encoding, 2);
+        std::fs::create_dir_all(stream_file.parent().unwrap()).unwrap();
+        std::fs::write(&stream_file, "cached frames").unwrap();
+
+        let stream = transcoder
+            .open_stream(
+                "episode",
+                &TranscodeInput::File("/does/not/exist.mp3".into()),
+                encoding,
+                2,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(read_stream(stream).await.unwrap(), b"cached frames");
+        let _ = std::fs::remove_dir_all(transcoder.cache_root());
+    }
+
+    fn ffmpeg_available() -> bool {
+        Command::new("ffmpeg")
+            .arg("-version")
+            .stdout(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    /// Seconds of audio in `encoded`, decoded by ffmpeg at 44.1 kHz mono.
+    fn decoded_seconds(encoded: &Path) -> f64 {
+        let output = Command::new("ffmpeg")
+            .args(["-v", "error", "-i"])
+            .arg(encoded)
+            .args(["-f", "s16le", "-ac", "1", "-ar", "44100", "pipe:1"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        output.stdout.len() as f64 / 2.0 / 44100.0
+    }
+
+    #[tokio::test]
+    async fn streams_are_as_long_as_their_source() {
+        if !ffmpeg_available() {
+            eprintln!("ffmpeg is not installed, skipping");
+            return;
+        }
+        let transcoder = temp_transcoder();
+        let source = transcoder.cache_root().join("source.wav");
+        std::fs::create_dir_all(transcoder.cache_root()).unwrap();
+        let generated = Command::new("ffmpeg")
+            .args(["-v", "error", "-f", "lavfi", "-i"])
+            .arg("sine=frequency=440:sample_rate=44100:duration=60")
+            .arg(&source)
+            .status()
+            .unwrap();
+        assert!(generated.success());
+
+        for codec in [StreamCodec::Mp3, StreamCodec::Aac] {
+            let encoding = StreamEncoding {
+                codec,
+                bitrate_kbps: 64,
+            };
+            for (start_segment, expected_seconds) in [(0, 60.0), (5, 30.0)] {
+                let stream = transcoder
+                    .open_stream(
+                        "sine",
+                        &TranscodeInput::File(source.clone()),
+                        encoding,
+                        start_segment,
+                    )
+                    .await
+                    .unwrap();
+                let encoded = transcoder
+                    .cache_root()
+                    .join(format!("streamed-{start_segment}.{}", codec.as_str()));
+                std::fs::write(&encoded, read_stream(stream).await.unwrap()).unwrap();
+
+                // Ten separately encoded segments would add the encoder
+                // delay and padding once per segment, about half a second.
+                let seconds = decoded_seconds(&encoded);
+                assert!(
+                    (seconds - expected_seconds).abs() < 0.1,
+                    "{codec:?} from segment {start_segment}: {seconds}s"
+                );
+            }
+        }
+        let _ = std::fs::remove_dir_all(transcoder.cache_root());
+    }
+
+    #[test]
+    fn only_http_urls_are_remote_inputs() {
+        assert_eq!(
+            TranscodeInput::remote("https://example.com/episode.mp3"),
+            Some(TranscodeInput::Url(
+                "https://example.com/episode.mp3".to_string()
+            ))
+        );
+        assert!(TranscodeInput::remote("http://example.com/episode.mp3").is_some());
+        for url in [
+            "file:///etc/passwd",
+            "concat:/etc/passwd|/etc/hosts",
+            "/etc/passwd",
+            "ftp://example.com/episode.mp3",
+            "",
+        ] {
+            assert_eq!(TranscodeInput::remote(url), None, "{url}");
+        }
+    }
+
+    #[test]
+    fn inputs_restrict_the_protocols_ffmpeg_may_use() {
+        let remote = TranscodeInput::remote("https://example.com/a.mp3").unwrap();
+        assert_eq!(
+            remote.ffmpeg_args(),
+            [
+                "-protocol_whitelist",
+                "http,https,tcp,tls",
+                "-i",
+                "https://example.com/a.mp3"
+            ]
+            .map(OsString::from)
+        );
+        let file = TranscodeInput::File("/podcasts/a:b.mp3".into());
+        assert_eq!(
+            file.ffmpeg_args(),
+            [
+                "-protocol_whitelist",
+                "file",
+                "-i",
+                "file:/podcasts/a:b.mp3"
+            ]
+            .map(OsString::from)
+        );
+    }
+
+    #[test]
+    fn stream_codecs_parse_and_know_their_mime_type() {
+        assert_eq!(StreamCodec::from_str("mp3"), Some(StreamCodec::Mp3));
+        assert_eq!(StreamCodec::from_str("aac"), Some(StreamCodec::Aac));
+        assert_eq!(StreamCodec::from_str("opus"), None);
+        assert_eq!(StreamCodec::Aac.mime_type(), "audio/aac");
+    }
+
     #[test]
     fn media_playlist_handles_exact_segment_boundary() {
         let m = build_media_playlist("session_q", 12.0);
@@ -3,6 +3,8 @@
 //! Validates a `(username, password)` against the existing `user_auth_service`,
 //! then returns the user's `api_key` as Bearer token. If the user has no
 //! `api_key` yet, one is generated and persisted via `UserAdminService::update`.
+//! Users with a second factor log in with an `audiobookshelf` app password,
+//! which is then returned as the token.
 //!
 //! Reverse-proxy / OIDC-only setups are not supported here yet; the flow
 //! requires a password hash to validate against. (TODO: proxy/OIDC parity in a
@@ -13,6 +15,7 @@ use crate::services::user_admin::service::UserAdminService;
 use crate::services::user_auth::service::UserAuthService;
 use common_infrastructure::error::ErrorSeverity::{Debug, Warning};
 use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::api_token::ApiTokenScope;
 use podfetch_domain::user::User;
 use sha256::digest;
 use std::sync::Arc;
@@ -43,6 +46,18 @@ impl AudiobookshelfLoginService {
             .find_by_username(username)
             .map_err(|_| CustomError::from(CustomErrorInner::Forbidden(Warning)))?;
 
+        // Audiobookshelf apps can't do 2FA; an `audiobookshelf` app password
+        // logs in instead and doubles as the bearer token afterwards.
+        if self
+            .user_auth_service
+            .is_api_token_of(&user, password, ApiTokenScope::Audiobookshelf)?
+        {
+            return Ok(User {
+                api_key: Some(password.to_string()),
+                ..user
+            });
+        }
+
         let Some(stored_hash) = user.password.as_deref() else {
             tracing::warn!(
                 "audiobookshelf login: user '{username}' has no local password (likely OIDC/proxy)"
@@ -54,13 +69,16 @@ impl AudiobookshelfLoginService {
             tracing::warn!("audiobookshelf login: password mismatch for user '{username}'");
             return Err(CustomErrorInner::Forbidden(Warning).into());
         }
+        self.user_auth_service
+            .ensure_password_only_login_allowed(&user)?;
 
         self.ensure_api_key(user)
     }
 
     /// Looks up a user by bearer token. Returns `None` if no user matches.
     pub fn user_from_token(&self, token: &str) -> Result<Option<User>, CustomError> {
-        self.user_auth_service.find_by_api_key(token)
+        self.user_auth_service
+            .find_by_api_key(token, ApiTokenScope::Audiobookshelf)
     }
 
     /// Rotates the user's api_key to a fresh UUID. Used by /logout when the
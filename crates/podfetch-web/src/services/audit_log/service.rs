@@ -0,0 +1,72 @@
+use common_infrastructure::error::CustomError;
+use podfetch_domain::audit_log::{AuditAction, AuditLogEntry, AuditLogFilter, AuditLogRepository};
+use podfetch_domain::user::User;
+use podfetch_persistence::adapters::AuditLogRepositoryImpl;
+use podfetch_persistence::db::database;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct AuditLogService {
+    repository: Arc<dyn AuditLogRepository<Error = CustomError>>,
+}
+
+impl AuditLogService {
+    pub fn new(repository: Arc<dyn AuditLogRepository<Error = CustomError>>) -> Self {
+        Self { repository }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(Arc::new(AuditLogRepositoryImpl::new(database())))
+    }
+
+    /// Appends an entry for an action `actor` just performed. The action has
+    /// already happened at this point, so a failing write is logged instead
+    /// of turning the response into an error.
+    pub fn record(
+        &self,
+        actor: &User,
+        action: AuditAction,
+        target: Option<String>,
+        ip_address: Option<String>,
+    ) {
+        let entry = AuditLogEntry {
+            id: Uuid::new_v4(),
+            actor: actor.username.clone(),
+            action,
+            target,
+            ip_address,
+            created_at: chrono::Utc::now().naive_utc(),
+        };
+        if let Err(err) = self.repository.append(entry) {
+            tracing::error!(
+                "Could not write audit log entry {} by {}: {err}",
+                action.as_str(),
+                actor.username
+            );
+        }
+    }
+
+    /// One page of matching entries, newest first, and the number of all
+    /// matching entries.
+    pub fn list(
+        &self,
+        filter: &AuditLogFilter,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<AuditLogEntry>, i64), CustomError> {
+        let entries = self.repository.find(filter, page * page_size, page_size)?;
+        let total = self.repository.count(filter)?;
+        Ok((entries, total))
+    }
+
+    /// Drops entries older than `retention_days`; 0 keeps everything.
+    pub fn purge_expired(&self, retention_days: i32) -> Result<usize, CustomError> {
+        if retention_days <= 0 {
+            return Ok(0);
+        }
+        let cutoff =
+            chrono::Utc::now().naive_utc() - chrono::Duration::days(i64::from(retention_days));
+        self.repository.delete_older_than(cutoff)
+    }
+}
@@ -0,0 +1,594 @@
+//! Full backups of an instance as a single zip archive.
+//!
+//! The archive holds `manifest.json`, one `database/<table>.json` per table in
+//! [`BACKUP_TABLES`] and, when asked for, the downloaded media under
+//! `media/`. Rows are stored as plain JSON objects with text values, so a
+//! backup taken on SQLite restores into PostgreSQL and the other way round.
+//! Restoring is only allowed into an instance without any data of its own.
+
+use common_infrastructure::config::FileHandlerType;
+use common_infrastructure::error::ErrorSeverity::{Critical, Warning};
+use common_infrastructure::error::{CustomError, CustomErrorInner, map_io_error};
+use podfetch_domain::backup::{
+    BACKUP_TABLES, BackupRepository, BackupRow, BackupTable, PRESEEDED_TABLES,
+};
+use podfetch_persistence::adapters::BackupRepositoryImpl;
+use podfetch_persistence::db::database;
+use podfetch_storage::FileHandleWrapper;
+use podfetch_storage::file_handler::resolve_file_handler_type;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path};
+use std::sync::Arc;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Bumped whenever the archive layout changes in a way older versions of
+/// [`BackupService::restore_backup`] can't read.
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub podfetch_version: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub tables: Vec<BackupTableSummary>,
+    pub media: Vec<BackupMediaFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupTableSummary {
+    pub name: String,
+    pub rows: usize,
+}
+
+/// A stored file, restored to `path` on the storage backend it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupMediaFile {
+    pub path: String,
+    pub location: String,
+    pub entry: String,
+}
+
+fn table_entry(table: &str) -> String {
+    format!("database/{table}.json")
+}
+
+fn zip_error(error: zip::result::ZipError) -> CustomError {
+    match error {
+        zip::result::ZipError::Io(source) => map_io_error(source, None, Critical),
+        other => {
+            CustomErrorInner::BadRequest(format!("Invalid backup archive: {other}"), Warning).into()
+        }
+    }
+}
+
+fn json_error(error: serde_json::Error) -> CustomError {
+    CustomErrorInner::BadRequest(format!("Invalid backup archive: {error}"), Warning).into()
+}
+
+fn column<'a>(row: &'a BackupRow, name: &str) -> Option<&'a str> {
+    row.get(name).and_then(|value| value.as_deref())
+}
+
+/// Every regular file below `dir`, depth first. Missing directories are
+/// skipped: a podcast without downloads may never have had one created.
+fn collect_local_files(dir: &Path, files: &mut Vec<String>) -> Result<(), CustomError> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let path = Some(dir.to_string_lossy().to_string());
+    let mut entries = std::fs::read_dir(dir)
+        .map_err(|e| map_io_error(e, path.clone(), Critical))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| map_io_error(e, path, Critical))?;
+    entries.sort_by_key(|entry| entry.path());
+    for entry in entries {
+        let entry_path = entry.path();
+        if entry_path.is_dir() {
+            collect_local_files(&entry_path, files)?;
+        } else {
+            files.push(entry_path.to_string_lossy().to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Rejects paths that would end up outside the storage root on restore:
+/// absolute ones, drive prefixes and `..`.
+fn is_safe_media_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+#[derive(Clone)]
+pub struct BackupService {
+    repository: Arc<dyn BackupRepository<Error = CustomError>>,
+}
+
+impl BackupService {
+    pub fn new(repository: Arc<dyn BackupRepository<Error = CustomError>>) -> Self {
+        Self { repository }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(Arc::new(BackupRepositoryImpl::new(database())))
+    }
+
+    /// Writes a backup to `target`. The archive is assembled next to it and
+    /// only moved into place once complete.
+    pub fn create_backup(
+        &self,
+        target: &Path,
+        include_media: bool,
+    ) -> Result<BackupManifest, CustomError> {
+        let part_path = target.with_extension("part");
+        let result = self.write_archive(&part_path, include_media);
+        match result {
+            Ok(manifest) => {
+                std::fs::rename(&part_path, target).map_err(|e| {
+                    map_io_error(e, Some(target.to_string_lossy().to_string()), Critical)
+                })?;
+                Ok(manifest)
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&part_path);
+                Err(e)
+            }
+        }
+    }
+
+    fn write_archive(
+        &self,
+        path: &Path,
+        include_media: bool,
+    ) -> Result<BackupManifest, CustomError> {
+        let file = File::create(path)
+            .map_err(|e| map_io_error(e, Some(path.to_string_lossy().to_string()), Critical))?;
+        let mut zip = ZipWriter::new(file);
+        let json_options =
+            SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        let mut tables = Vec::with_capacity(BACKUP_TABLES.len());
+        let mut exported = Vec::with_capacity(BACKUP_TABLES.len());
+        for table in BACKUP_TABLES {
+            let rows = self.repository.export_table(table)?;
+            zip.start_file(table_entry(table), json_options)
+                .map_err(zip_error)?;
+            serde_json::to_writer(&mut zip, &rows).map_err(json_error)?;
+            tables.push(BackupTableSummary {
+                name: table.to_string(),
+                rows: rows.len(),
+            });
+            exported.push((*table, rows));
+        }
+
+        let media = if include_media {
+            Self::media_files(&exported)?
+        } else {
+            Vec::new()
+        };
+        // Audio and images are compressed already; storing them keeps large
+        // backups fast.
+        let media_options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Stored)
+            .large_file(true);
+        for file in &media {
+            zip.start_file(file.entry.as_str(), media_options)
+                .map_err(zip_error)?;
+            FileHandleWrapper::read_stream(
+                &file.path,
+                &mut zip,
+                &FileHandlerType::from(file.location.as_str()),
+            )?;
+        }
+
+        let manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            podfetch_version: crate::controllers::sys_info_controller::built_info::PKG_VERSION
+                .to_string(),
+            created_at: chrono::Utc::now().naive_utc(),
+            tables,
+            media,
+        };
+        zip.start_file(MANIFEST_ENTRY, json_options)
+            .map_err(zip_error)?;
+        serde_json::to_writer_pretty(&mut zip, &manifest).map_err(json_error)?;
+        zip.finish().map_err(zip_error)?;
+        Ok(manifest)
+    }
+
+    /// The files that belong to the exported podcasts: the whole podcast
+    /// directory on local storage, the cover and episode files on S3.
+    fn media_files(
+        exported: &[(&str, Vec<BackupRow>)],
+    ) -> Result<Vec<BackupMediaFile>, CustomError> {
+        let rows_of = |table: &str| {
+            exported
+                .iter()
+                .find(|(name, _)| *name == table)
+                .map(|(_, rows)| rows.as_slice())
+                .unwrap_or_default()
+        };
+        let location_of = |row: &BackupRow| {
+            resolve_file_handler_type(column(row, "download_location").map(str::to_string))
+        };
+
+        let mut files = BTreeSet::new();
+        for podcast in rows_of("podcasts") {
+            match location_of(podcast) {
+                FileHandlerType::Local => {
+                    if let Some(directory) = column(podcast, "directory_name") {
+                        let mut local = Vec::new();
+                        collect_local_files(Path::new(directory), &mut local)?;
+                        files.extend(
+                            local
+                                .into_iter()
+                                .map(|path| (FileHandlerType::Local.to_string(), path)),
+                        );
+                    }
+                }
+                FileHandlerType::S3 => {
+                    if let Some(image) = column(podcast, "image_url")
+                        && let Ok(image) = urlencoding::decode(image)
+                    {
+                        files.insert((FileHandlerType::S3.to_string(), image.to_string()));
+                    }
+                }
+            }
+        }
+        for episode in rows_of("podcast_episodes") {
+            if column(episode, "download_location").is_none() {
+                continue;
+            }
+            let location = location_of(episode).to_string();
+            for path_column in ["file_episode_path", "file_image_path"] {
+                if let Some(path) = column(episode, path_column) {
+                    files.insert((location.clone(), path.to_string()));
+                }
+            }
+        }
+
+        Ok(files
+            .into_iter()
+            .filter(|(location, path)| {
+                FileHandleWrapper::path_exists(
+                    path,
+                    podfetch_storage::FileRequest::File,
+                    &FileHandlerType::from(location.as_str()),
+                )
+            })
+            .enumerate()
+            .map(|(index, (location, path))| {
+                let name = Path::new(&path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                BackupMediaFile {
+                    entry: format!("media/{index:06}-{name}"),
+                    path,
+                    location,
+                }
+            })
+            .collect())
+    }
+
+    /// Restores the backup at `source` into this instance, which must not
+    /// hold any data yet apart from the default settings.
+    pub fn restore_backup(&self, source: &Path) -> Result<BackupManifest, CustomError> {
+        let file = File::open(source)
+            .map_err(|e| map_io_error(e, Some(source.to_string_lossy().to_string()), Critical))?;
+        let mut archive = ZipArchive::new(file).map_err(zip_error)?;
+        let manifest: BackupManifest =
+            serde_json::from_reader(archive.by_name(MANIFEST_ENTRY).map_err(zip_error)?)
+                .map_err(json_error)?;
+        if manifest.format_version > BACKUP_FORMAT_VERSION {
+            return Err(CustomErrorInner::BadRequest(
+                format!(
+                    "The backup uses format version {}, this PodFetch reads up to {BACKUP_FORMAT_VERSION}",
+                    manifest.format_version
+                ),
+                Warning,
+            )
+            .into());
+        }
+        for file in &manifest.media {
+            if !is_safe_media_path(&file.path) || !matches!(file.location.as_str(), "Local" | "S3")
+            {
+                return Err(CustomErrorInner::BadRequest(
+                    format!("Invalid media file {} in backup", file.path),
+                    Warning,
+                )
+                .into());
+            }
+        }
+        self.ensure_empty()?;
+
+        let mut tables = Vec::with_capacity(BACKUP_TABLES.len());
+        for table in BACKUP_TABLES {
+            let rows: Vec<BackupRow> = match archive.by_name(&table_entry(table)) {
+                Ok(entry) => serde_json::from_reader(entry).map_err(json_error)?,
+                // Tables added after the backup was taken simply stay empty.
+                Err(zip::result::ZipError::FileNotFound) => continue,
+                Err(e) => return Err(zip_error(e)),
+            };
+            tables.push(BackupTable {
+                name: table.to_string(),
+                rows,
+            });
+        }
+        self.repository.restore(&tables)?;
+
+        for file in &manifest.media {
+            let location = FileHandlerType::from(file.location.as_str());
+            if location == FileHandlerType::Local
+                && let Some(parent) = Path::new(&file.path).parent()
+                && !parent.as_os_str().is_empty()
+            {
+                FileHandleWrapper::create_dir(&parent.to_string_lossy(), &location)?;
+            }
+            let mut entry = archive.by_name(&file.entry).map_err(zip_error)?;
+            FileHandleWrapper::write_stream(&file.path, &mut entry as &mut dyn Read, &location)?;
+        }
+        Ok(manifest)
+    }
+
+    fn ensure_empty(&self) -> Result<(), CustomError> {
+        for table in BACKUP_TABLES {
+            if PRESEEDED_TABLES.contains(table) {
+                continue;
+            }
+            if self.repository.count_rows(table)? > 0 {
+                return Err(CustomErrorInner::Conflict(
+                    format!("Backups can only be restored into an empty instance, but {table} already has data"),
+                    Warning,
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::AppState;
+    use crate::services::podcast::service::PodcastService;
+    use crate::services::transcoding::service::builtin_opus_profile;
+    use crate::test_support::tests::handle_test_startup;
+    use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
+    use diesel::prelude::*;
+    use podfetch_domain::notification_channel::{
+        NotificationChannel, NotificationChannelKind, NotificationChannelRepository,
+        NotificationEventType,
+    };
+    use podfetch_domain::notification_rule::{NotificationRule, NotificationRuleRepository};
+    use podfetch_domain::podcast_episode_transcript::{
+        PodcastEpisodeTranscriptRepository, TranscriptSegment, TranscriptSource, UpsertTranscript,
+    };
+    use podfetch_domain::transcoding_profile::{TranscodingProfile, TranscodingProfileRepository};
+    use podfetch_persistence::adapters::{
+        NotificationChannelRepositoryImpl, NotificationRuleRepositoryImpl,
+        PodcastEpisodeTranscriptRepositoryImpl, TranscodingProfileRepositoryImpl,
+    };
+    use podfetch_persistence::db::get_connection;
+    use podfetch_persistence::schema::podcast_episodes::dsl as pe_dsl;
+    use serial_test::serial;
+    use uuid::Uuid;
+
+    /// A podcast with one downloaded episode and a transcript, with the
+    /// episode file in a fresh podcast directory. Returns the episode id and
+    /// file.
+    fn seed_instance() -> (Uuid, String) {
+        let slug = format!("backup-{}", Uuid::new_v4());
+        let directory = Path::new("podcasts").join(&slug);
+        std::fs::create_dir_all(&directory).unwrap();
+        let file_path = directory.join("episode.mp3").to_string_lossy().to_string();
+        std::fs::write(&file_path, b"ID3 backup test audio").unwrap();
+
+        let podcast = PodcastService::add_podcast_to_database(
+            "Backup Show",
+            &slug,
+            &format!("https://example.com/{slug}.xml"),
+            "http://localhost:8080/ui/default.jpg",
+            &directory.to_string_lossy(),
+        )
+        .unwrap();
+        let episode_id = Uuid::new_v4();
+        diesel::insert_into(pe_dsl::podcast_episodes)
+            .values((
+                pe_dsl::id.eq(episode_id.to_string()),
+                pe_dsl::podcast_id.eq(podcast.id.clone()),
+                pe_dsl::episode_id.eq(format!("{slug}-episode")),
+                pe_dsl::name.eq("Restorable episode".to_string()),
+                pe_dsl::url.eq(format!("https://example.com/{episode_id}.mp3")),
+                pe_dsl::date_of_recording.eq("2026-01-02T03:04:05".to_string()),
+                pe_dsl::image_url.eq("http://localhost:8080/ui/default.jpg".to_string()),
+                pe_dsl::total_time.eq(1800),
+                pe_dsl::description.eq("Survives a round trip".to_string()),
+                pe_dsl::guid.eq(format!("{slug}-guid")),
+                pe_dsl::deleted.eq(false),
+                pe_dsl::episode_numbering_processed.eq(false),
+                pe_dsl::download_time.eq(chrono::Utc::now().naive_utc()),
+                pe_dsl::download_location.eq(Some("Local".to_string())),
+                pe_dsl::file_episode_path.eq(Some(file_path.clone())),
+            ))
+            .execute(&mut get_connection())
+            .unwrap();
+
+        let transcripts = PodcastEpisodeTranscriptRepositoryImpl::new(database());
+        let transcript_id = transcripts
+            .upsert(UpsertTranscript {
+                episode_id,
+                source: TranscriptSource::Feed,
+                original_url: None,
+                mime_type: "text/vtt".to_string(),
+                language: Some("en".to_string()),
+            })
+            .unwrap();
+        transcripts
+            .replace_segments(
+                transcript_id,
+                &[TranscriptSegment {
+                    idx: 0,
+                    start_ms: Some(0),
+                    end_ms: Some(1000),
+                    speaker: None,
+                    text: "hello from the backup".to_string(),
+                }],
+            )
+            .unwrap();
+        (episode_id, file_path)
+    }
+
+    fn wipe_database() {
+        let empty = BACKUP_TABLES
+            .iter()
+            .map(|table| BackupTable {
+                name: table.to_string(),
+                rows: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        BackupRepositoryImpl::new(database())
+            .restore(&empty)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn backup_round_trips_rows_and_media_into_an_empty_instance() {
+        let _ts = handle_test_startup().await;
+        let (episode_id, file_path) = seed_instance();
+        let profiles = TranscodingProfileRepositoryImpl::new(database());
+        let profile = profiles
+            .create(TranscodingProfile {
+                id: Uuid::new_v4(),
+                name: "Backup profile".to_string(),
+                created_at: chrono::Utc::now().naive_utc(),
+                ..builtin_opus_profile()
+            })
+            .unwrap();
+        let user = AppState::new()
+            .user_admin_service
+            .create_user(UserTestDataBuilder::new().build())
+            .unwrap();
+        NotificationChannelRepositoryImpl::new(database())
+            .create(NotificationChannel {
+                id: Uuid::new_v4(),
+                name: "Backup hook".to_string(),
+                kind: NotificationChannelKind::Webhook,
+                enabled: true,
+                events: vec![NotificationEventType::NewEpisode],
+                config: r#"{"url":"https://example.com/hook"}"#.to_string(),
+                created_at: chrono::Utc::now().naive_utc(),
+            })
+            .unwrap();
+        let rules = NotificationRuleRepositoryImpl::new(database());
+        let rule = rules
+            .create(NotificationRule {
+                id: Uuid::new_v4(),
+                user_id: user.id,
+                podcast_id: None,
+                tag_id: None,
+                min_duration_seconds: Some(600),
+                max_duration_seconds: None,
+                title_regex: None,
+                created_at: chrono::Utc::now().naive_utc(),
+            })
+            .unwrap();
+        let archive = std::env::temp_dir().join(format!("podfetch-{}.zip", Uuid::new_v4()));
+        let service = BackupService::default_service();
+
+        let manifest = service.create_backup(&archive, true).unwrap();
+        let rows_of = |name: &str| {
+            manifest
+                .tables
+                .iter()
+                .find(|table| table.name == name)
+                .unwrap()
+                .rows
+        };
+        assert_eq!(rows_of("podcasts"), 1);
+        assert_eq!(rows_of("podcast_episodes"), 1);
+        assert_eq!(rows_of("podcast_episode_transcript_segments"), 1);
+        assert_eq!(rows_of("transcoding_profiles"), 1);
+        assert_eq!(rows_of("notification_channels"), 1);
+        assert_eq!(rows_of("notification_rules"), 1);
+        assert_eq!(manifest.media.len(), 1);
+        assert_eq!(manifest.media[0].path, file_path);
+
+        wipe_database();
+        std::fs::remove_file(&file_path).unwrap();
+        let restored = service.restore_backup(&archive).unwrap();
+
+        assert_eq!(restored, manifest);
+        let episode = pe_dsl::podcast_episodes
+            .filter(pe_dsl::id.eq(episode_id.to_string()))
+            .select((pe_dsl::name, pe_dsl::download_time))
+            .first::<(String, Option<chrono::NaiveDateTime>)>(&mut get_connection())
+            .unwrap();
+        assert_eq!(episode.0, "Restorable episode");
+        assert!(episode.1.is_some());
+        assert_eq!(std::fs::read(&file_path).unwrap(), b"ID3 backup test audio");
+        let segments = BackupRepositoryImpl::new(database())
+            .export_table("podcast_episode_transcript_segments")
+            .unwrap();
+        assert_eq!(
+            segments[0]["text"].as_deref(),
+            Some("hello from the backup")
+        );
+        assert_eq!(
+            profiles
+                .get(profile.id)
+                .unwrap()
+                .map(|restored| restored.name),
+            Some("Backup profile".to_string())
+        );
+        assert_eq!(rules.get(rule.id, user.id).unwrap(), Some(rule));
+        assert_eq!(
+            BackupRepositoryImpl::new(database())
+                .export_table("notification_channels")
+                .unwrap()[0]["name"]
+                .as_deref(),
+            Some("Backup hook")
+        );
+        let _ = std::fs::remove_file(archive);
+        let _ = std::fs::remove_dir_all(Path::new(&file_path).parent().unwrap());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn restore_refuses_an_instance_that_already_has_data() {
+        let _ts = handle_test_startup().await;
+        let (_, file_path) = seed_instance();
+        let archive = std::env::temp_dir().join(format!("podfetch-{}.zip", Uuid::new_v4()));
+        let service = BackupService::default_service();
+        service.create_backup(&archive, false).unwrap();
+
+        let error = service.restore_backup(&archive).unwrap_err();
+
+        assert!(error.to_string().contains("podcasts already has data"));
+        let _ = std::fs::remove_file(archive);
+        let _ = std::fs::remove_dir_all(Path::new(&file_path).parent().unwrap());
+    }
+
+    #[test]
+    fn media_paths_must_stay_below_the_storage_root() {
+        assert!(is_safe_media_path("podcasts/show/episode.mp3"));
+        assert!(is_safe_media_path("./podcasts/show/image.jpg"));
+        assert!(!is_safe_media_path("podcasts/../../etc/passwd"));
+        assert!(!is_safe_media_path("/etc/cron.d/podfetch"));
+        assert!(!is_safe_media_path("/podcasts/show/episode.mp3"));
+        assert!(!is_safe_media_path(""));
+    }
+}
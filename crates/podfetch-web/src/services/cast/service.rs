@@ -282,6 +282,19 @@ impl<L: CastDriver> CastOrchestrator<L> {
             .remove(session_id)
     }
 
+    /// Number of active sessions as `(chromecast, mopidy)`.
+    pub fn active_session_counts(&self) -> (usize, usize) {
+        let guard = self
+            .sessions
+            .read()
+            .expect("orchestrator session lock poisoned");
+        let mopidy = guard
+            .values()
+            .filter(|session| device_kind::is_mopidy(&session.device_kind))
+            .count();
+        (guard.len() - mopidy, mopidy)
+    }
+
     fn lookup_session(
         &self,
         user: &User,
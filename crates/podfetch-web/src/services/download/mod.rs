@@ -1,2 +1,5 @@
 pub mod chapter;
+pub mod progress;
+pub mod queue;
 pub mod service;
+pub mod worker;
@@ -0,0 +1,60 @@
+use crate::server::ChatServerHandle;
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two `downloadProgress` events of the same download.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Wraps an episode download and reports how many bytes went through it over
+/// the socket.io channel, at most once per [`PROGRESS_INTERVAL`].
+pub struct ProgressReader<R> {
+    inner: R,
+    episode_id: String,
+    bytes_received: u64,
+    total_bytes: Option<u64>,
+    last_report: Option<Instant>,
+}
+
+impl<R: Read> ProgressReader<R> {
+    /// `already_received` counts bytes a resumed download does not fetch
+    /// again, so the reported progress covers the whole file.
+    pub fn new(
+        inner: R,
+        episode_id: String,
+        already_received: u64,
+        total_bytes: Option<u64>,
+    ) -> Self {
+        Self {
+            inner,
+            episode_id,
+            bytes_received: already_received,
+            total_bytes,
+            last_report: None,
+        }
+    }
+
+    fn report(&mut self) {
+        ChatServerHandle::broadcast_download_progress(
+            &self.episode_id,
+            self.bytes_received,
+            self.total_bytes,
+        );
+        self.last_report = Some(Instant::now());
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.bytes_received += read as u64;
+        let due = self
+            .last_report
+            .is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL);
+        // A zero-length read marks the end of the body; always report it so
+        // the last event shows the download as complete.
+        if due || read == 0 {
+            self.report();
+        }
+        Ok(read)
+    }
+}
@@ -0,0 +1,155 @@
+use crate::server::ChatServerHandle;
+use crate::services::download::worker;
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::episode_download::{DownloadJob, DownloadJobRepository, DownloadJobStatus};
+use podfetch_persistence::adapters::DownloadJobRepositoryImpl;
+use podfetch_persistence::db::database;
+use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Priority of downloads started by the feed refresh.
+pub const AUTOMATIC_PRIORITY: i32 = 0;
+/// Priority of downloads a user asked for, so they overtake a long backlog of
+/// automatic ones.
+pub const MANUAL_PRIORITY: i32 = 10;
+
+/// Bookkeeping of the persistent download queue. The downloads themselves are
+/// run by [`worker::run_download_worker`].
+pub struct DownloadQueueService {
+    job_repo: Arc<dyn DownloadJobRepository<Error = CustomError>>,
+}
+
+impl DownloadQueueService {
+    pub fn new(job_repo: Arc<dyn DownloadJobRepository<Error = CustomError>>) -> Self {
+        Self { job_repo }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(Arc::new(DownloadJobRepositoryImpl::new(database())))
+    }
+
+    /// Queues the episode for download. `requested_by` is the user who asked
+    /// for it, who hears about it if it fails. Returns false when a job for
+    /// it is already pending or running.
+    pub fn enqueue(
+        &self,
+        episode: &PodcastEpisode,
+        priority: i32,
+        requested_by: Option<Uuid>,
+    ) -> Result<bool, CustomError> {
+        let episode_id = parse_uuid(&episode.id)?;
+        let podcast_id = parse_uuid(&episode.podcast_id)?;
+        let Some(job) = self
+            .job_repo
+            .enqueue(episode_id, podcast_id, priority, requested_by)?
+        else {
+            return Ok(false);
+        };
+        broadcast_status(&job);
+        worker::wake_download_worker();
+        Ok(true)
+    }
+
+    /// Queues every episode that is neither downloaded nor deleted. Returns
+    /// how many of them were newly queued.
+    pub fn enqueue_missing(
+        &self,
+        episodes: impl IntoIterator<Item = PodcastEpisode>,
+        priority: i32,
+        requested_by: Option<Uuid>,
+    ) -> Result<usize, CustomError> {
+        let mut queued = 0;
+        for episode in episodes {
+            if episode.deleted || episode.is_downloaded() {
+                continue;
+            }
+            if self.enqueue(&episode, priority, requested_by)? {
+                queued += 1;
+            }
+        }
+        Ok(queued)
+    }
+
+    pub fn list(&self, status: Option<DownloadJobStatus>) -> Result<Vec<DownloadJob>, CustomError> {
+        self.job_repo.list(status)
+    }
+
+    pub fn count_by_status(&self) -> Result<Vec<(DownloadJobStatus, i64)>, CustomError> {
+        self.job_repo.count_by_status()
+    }
+
+    /// Takes a job off the queue. A running download cannot be interrupted,
+    /// and a finished one has nothing left to cancel.
+    pub fn cancel(&self, id: Uuid) -> Result<DownloadJob, CustomError> {
+        let job = self.get(id)?;
+        match job.status {
+            DownloadJobStatus::Pending | DownloadJobStatus::Failed => {}
+            DownloadJobStatus::Cancelled => return Ok(job),
+            DownloadJobStatus::Running | DownloadJobStatus::Done => {
+                return Err(CustomErrorInner::Conflict(
+                    format!("download job is {}", job.status.as_str()),
+                    Warning,
+                )
+                .into());
+            }
+        }
+        self.job_repo
+            .set_status(id, DownloadJobStatus::Cancelled, job.error.as_deref())?;
+        self.get_and_broadcast(id)
+    }
+
+    pub fn set_priority(&self, id: Uuid, priority: i32) -> Result<DownloadJob, CustomError> {
+        self.get(id)?;
+        self.job_repo.set_priority(id, priority)?;
+        self.get(id)
+    }
+
+    /// Puts a failed or cancelled job back on the queue with a fresh attempt
+    /// budget.
+    pub fn retry(&self, id: Uuid) -> Result<DownloadJob, CustomError> {
+        let job = self.get(id)?;
+        if !matches!(
+            job.status,
+            DownloadJobStatus::Failed | DownloadJobStatus::Cancelled
+        ) {
+            return Err(CustomErrorInner::Conflict(
+                format!("download job is {}", job.status.as_str()),
+                Warning,
+            )
+            .into());
+        }
+        self.job_repo.requeue(id)?;
+        let job = self.get_and_broadcast(id)?;
+        worker::wake_download_worker();
+        Ok(job)
+    }
+
+    fn get(&self, id: Uuid) -> Result<DownloadJob, CustomError> {
+        self.job_repo
+            .get(id)?
+            .ok_or_else(|| CustomErrorInner::NotFound(Warning).into())
+    }
+
+    fn get_and_broadcast(&self, id: Uuid) -> Result<DownloadJob, CustomError> {
+        let job = self.get(id)?;
+        broadcast_status(&job);
+        Ok(job)
+    }
+}
+
+pub(crate) fn broadcast_status(job: &DownloadJob) {
+    ChatServerHandle::broadcast_download_status(
+        &job.id.to_string(),
+        &job.episode_id.to_string(),
+        job.status.as_str(),
+        job.attempts,
+        job.error.as_deref(),
+    );
+}
+
+fn parse_uuid(id: &str) -> Result<Uuid, CustomError> {
+    Uuid::parse_str(id)
+        .map_err(|_| CustomErrorInner::BadRequest(format!("Invalid UUID: {id}"), Warning).into())
+}
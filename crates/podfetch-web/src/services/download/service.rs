@@ -1,8 +1,14 @@
+use crate::metrics::METRICS;
 use crate::services::download::chapter::{Chapter, Link};
+use crate::services::download::progress::ProgressReader;
 use crate::services::file::service::{FileService, prepare_podcast_episode_title_to_directory};
+use crate::services::loudness::service as loudness;
 use crate::services::podcast_episode_chapter::service::PodcastEpisodeChapterService;
 use crate::services::podcast_settings::service::PodcastSettingsService;
 use crate::services::settings::service::SettingsService;
+use crate::services::skip_detection::service as skip_detection;
+use crate::services::storage_quota::service::StorageQuotaService;
+use crate::services::transcoding::service::{self as transcoding, TranscodingProfileService};
 use podfetch_persistence::podcast::PodcastEntity as Podcast;
 use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
 use std::fs::File;
@@ -11,27 +17,69 @@ use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeSer
 use chrono::Duration;
 use common_infrastructure::config::FileHandlerType;
 use common_infrastructure::error::{
-    CustomError, CustomErrorInner, ErrorSeverity, map_reqwest_error,
+    CustomError, CustomErrorInner, ErrorSeverity, map_io_error, map_reqwest_error,
 };
 use common_infrastructure::http::COMMON_USER_AGENT;
 use common_infrastructure::http::{get_async_sync_client, get_sync_client};
 use common_infrastructure::runtime::{DEFAULT_IMAGE_URL, ENVIRONMENT_SERVICE, PODCAST_FILENAME};
 use file_format::FileFormat;
 use id3::{ErrorKind, Tag, TagLike};
+use podfetch_domain::episode_download::{EpisodeDownloadState, EpisodeDownloadStateRepository};
+use podfetch_persistence::adapters::EpisodeDownloadStateRepositoryImpl;
+use podfetch_persistence::db::database;
 use podfetch_persistence::db::get_connection;
 use podfetch_storage::FileHandleWrapper;
 use podfetch_storage::FileRequest;
+use podfetch_storage::LocalStorageBackend;
 use podfetch_storage::{
     DetermineFileExtensionReturn, FileType, FilenameBuilder, FilenameBuilderReturn,
-    determine_file_extension,
+    determine_file_extension, determine_stream_extension,
 };
-use reqwest::header::{ACCEPT_ENCODING, HeaderMap, HeaderValue, USER_AGENT};
-use std::io::Read;
+use reqwest::StatusCode;
+use reqwest::header::{
+    ACCEPT_ENCODING, CONTENT_RANGE, CONTENT_TYPE, ETAG, HeaderMap, HeaderValue, IF_RANGE,
+    LAST_MODIFIED, RANGE, USER_AGENT,
+};
+use std::io::{BufRead, BufReader, Read};
 use std::path::PathBuf;
 use std::thread::sleep;
 use std::time::Duration as StdDuration;
 
 pub struct DownloadService {}
+
+/// Size of the read buffer in front of an episode download; its first fill is
+/// what the extension and HLS sniffing look at.
+const STREAM_PEEK_BYTES: usize = 64 * 1024;
+
+/// Episode audio ready to be streamed into storage.
+struct EpisodeAudioSource {
+    suffix: String,
+    reader: Box<dyn Read + Send>,
+    /// ffmpeg output of an HLS episode; removed once it has been stored.
+    temp_file: Option<String>,
+    /// Bytes an earlier attempt already left in the `.part` file; `reader`
+    /// continues right after them.
+    resume_from: u64,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Size of the complete file, when the server announced it.
+    total_bytes: Option<u64>,
+}
+
+/// Outcome of asking the server for the rest of an interrupted download.
+enum ResumeAttempt {
+    Resumed(EpisodeAudioSource),
+    /// The server sent the whole file instead: it ignored the range, or the
+    /// enclosure changed since the partial data was written.
+    FullBody(reqwest::blocking::Response),
+    /// Nothing to build on; start over with a fresh request.
+    Restart,
+}
+
+fn download_state_repo() -> EpisodeDownloadStateRepositoryImpl {
+    EpisodeDownloadStateRepositoryImpl::new(database())
+}
+
 const DEFAULT_FALLBACK_IMAGE_BYTES: &[u8] = include_bytes!("../../../../../ui/public/default.jpg");
 
 /// Parse a stored podfetch id (entity rows carry the canonical UUID as a
@@ -181,7 +229,7 @@ impl DownloadService {
         podcast_id = podcast.id,
     ))]
     pub fn download_podcast_episode(
-        podcast_episode: PodcastEpisode,
+        mut podcast_episode: PodcastEpisode,
         podcast: &Podcast,
     ) -> Result<(), CustomError> {
         let mut header_map = HeaderMap::new();
@@ -196,17 +244,14 @@ impl DownloadService {
             .build()
             .map_err(map_reqwest_error)?;
         let conn = &mut get_connection();
-        let mut podcast_data = Self::handle_suffix_response(
-            determine_file_extension(&podcast_episode.url, &client, FileType::Audio),
-            &podcast_episode.url,
-        )?;
-        if Self::is_hls_playlist(&podcast_data.1) {
-            tracing::info!(
-                "Episode {} is served as an HLS playlist, downloading via ffmpeg",
-                podcast_episode.episode_id
-            );
-            podcast_data = Self::download_hls_episode(&podcast_episode.url)?;
-        }
+        let resume_state = Self::resumable_download_state(&podcast_episode);
+        let mut audio = Self::open_episode_audio(&client, &podcast_episode, resume_state.as_ref())?;
+        audio.reader = Box::new(ProgressReader::new(
+            audio.reader,
+            podcast_episode.episode_id.clone(),
+            audio.resume_from,
+            audio.total_bytes,
+        ));
         let settings_in_db = crate::services::settings::service::SettingsService::shared()
             .get_settings()?
             .unwrap();
@@ -263,7 +308,7 @@ impl DownloadService {
         let paths = FilenameBuilder::default()
             .with_podcast_directory(&podcast.directory_name)
             .with_episode_stem(&episode_stem)
-            .with_suffix(&podcast_data.0)
+            .with_suffix(&audio.suffix)
             .with_image_suffix(
                 &image_data
                     .as_ref()
@@ -303,20 +348,36 @@ impl DownloadService {
 
         let image_written =
             should_download_main_image && image_data.as_ref().is_some_and(|d| !d.1.is_empty());
-        if image_written
-            && let Some(ref mut data) = image_data {
-                FileHandleWrapper::write_file(
-                    &paths.image_filename,
-                    data.1.as_mut_slice(),
-                    &ENVIRONMENT_SERVICE.default_file_handler,
-                )?;
-            }
+        if image_written && let Some(ref mut data) = image_data {
+            FileHandleWrapper::write_file(
+                &paths.image_filename,
+                data.1.as_mut_slice(),
+                &ENVIRONMENT_SERVICE.default_file_handler,
+            )?;
+        }
 
-        FileHandleWrapper::write_file(
-            &paths.filename,
-            podcast_data.1.as_mut_slice(),
-            &ENVIRONMENT_SERVICE.default_file_handler,
-        )?;
+        let written = if let Some(temp_file) = &audio.temp_file {
+            let written = FileHandleWrapper::write_stream(
+                &paths.filename,
+                &mut audio.reader,
+                &ENVIRONMENT_SERVICE.default_file_handler,
+            );
+            let _ = std::fs::remove_file(temp_file);
+            written?
+        } else {
+            Self::write_resumable(
+                &podcast_episode,
+                &paths.filename,
+                &mut audio,
+                resume_state.as_ref(),
+            )?
+        };
+        tracing::debug!(
+            "Stored {} bytes for episode {}",
+            written,
+            podcast_episode.episode_id
+        );
+        METRICS.add_downloaded_bytes(written);
 
         // Read chapters and embed tags BEFORE transcoding. Opus has no
         // id3/mp4-atom metadata, and transcoding would also delete the
@@ -388,17 +449,50 @@ impl DownloadService {
             ),
         }
 
-        let final_episode_path = if settings_in_db.auto_transcode_opus {
-            match Self::transcode_to_opus(&paths.filename) {
-                Ok(opus_path) => opus_path,
-                Err(e) => {
-                    tracing::warn!("Opus transcoding failed, keeping original file: {e}");
+        let transcoding_profile = TranscodingProfileService::default_service()
+            .profile_for(&settings_in_db, podcast_settings_override.as_ref())
+            .unwrap_or_else(|e| {
+                tracing::error!("Error loading the transcoding profile: {e}");
+                None
+            });
+        let final_episode_path = match transcoding_profile {
+            Some(profile) => {
+                transcoding::transcode(&paths.filename, &profile).unwrap_or_else(|e| {
+                    tracing::warn!("Transcoding failed, keeping original file: {e}");
                     paths.filename.clone()
-                }
+                })
             }
+            None => paths.filename.clone(),
+        };
+
+        // Loudness: measured on the final file, so a profile's loudnorm is
+        // already applied. Non-fatal — the episode just plays without gain.
+        let loudness = if settings_in_db.loudness_analysis
+            && ENVIRONMENT_SERVICE.default_file_handler == FileHandlerType::Local
+        {
+            loudness::analyze_download(&podcast_episode, &final_episode_path)
         } else {
-            paths.filename.clone()
+            None
         };
+        if let Some(loudness) = loudness {
+            podcast_episode.loudness_lufs = Some(loudness.integrated_lufs);
+            podcast_episode.loudness_true_peak = Some(loudness.true_peak_dbtp);
+        }
+        if final_episode_path != paths.filename || loudness.is_some() {
+            Self::retag_final_file(&final_episode_path, &paths, &podcast_episode, podcast);
+        }
+
+        // Skip detection: silences and audio shared with earlier episodes of
+        // the podcast, stored next to the SponsorBlock segments. Non-fatal.
+        if settings_in_db.skip_detection
+            && ENVIRONMENT_SERVICE.default_file_handler == FileHandlerType::Local
+            && let Err(err) = skip_detection::analyze_episode(&podcast_episode, &final_episode_path)
+        {
+            tracing::warn!(
+                "Skip detection failed for episode {}: {err}",
+                podcast_episode.id
+            );
+        }
 
         // Jellyfin/Kodi NFO sidecar files (non-fatal). Uses the FINAL media path
         // so the per-episode .nfo basename matches the audio file even after
@@ -418,9 +512,314 @@ impl DownloadService {
             },
             &final_episode_path,
         )?;
+
+        // Storage accounting. Non-fatal — the daily reconcile catches up.
+        if let Err(err) = StorageQuotaService::default_service().record_download(
+            &podcast_episode,
+            &final_episode_path,
+            &ENVIRONMENT_SERVICE.default_file_handler,
+        ) {
+            tracing::error!(
+                "Error recording the size of episode {}: {err}",
+                podcast_episode.id
+            );
+        }
         Ok(())
     }
 
+    /// Sends the enclosure request, retrying only until the server answers.
+    /// The body is left unread so it can be streamed straight into storage.
+    fn open_episode_stream(
+        client: &reqwest::blocking::Client,
+        url: &str,
+    ) -> Result<reqwest::blocking::Response, CustomError> {
+        const MAX_RETRIES: usize = 3;
+        for attempt in 1..=MAX_RETRIES {
+            let result = client
+                .get(url)
+                .send()
+                .and_then(|resp| resp.error_for_status())
+                .map_err(map_reqwest_error);
+
+            match result {
+                Ok(resp) => return Ok(resp),
+                Err(err) if attempt == MAX_RETRIES => return Err(err),
+                Err(err) => {
+                    tracing::warn!(
+                        "Download attempt {}/{} failed for {}: {}",
+                        attempt,
+                        MAX_RETRIES,
+                        url,
+                        err
+                    );
+                    sleep(StdDuration::from_millis(250 * attempt as u64));
+                }
+            }
+        }
+        Err(CustomErrorInner::Conflict(
+            "Download failed after retries".to_string(),
+            ErrorSeverity::Error,
+        )
+        .into())
+    }
+
+    /// The state of an earlier, interrupted download of this episode. Only
+    /// local storage keeps partial files, so S3 always starts from scratch.
+    fn resumable_download_state(podcast_episode: &PodcastEpisode) -> Option<EpisodeDownloadState> {
+        if ENVIRONMENT_SERVICE.default_file_handler != FileHandlerType::Local {
+            return None;
+        }
+        let episode_id = parse_id(&podcast_episode.id).ok()?;
+        download_state_repo()
+            .get(episode_id)
+            .inspect_err(|e| tracing::warn!("Could not load download state of {episode_id}: {e}"))
+            .ok()
+            .flatten()
+    }
+
+    /// Streams the audio into `file_path`, recording how far it got so a
+    /// failed attempt can be resumed. The state is dropped once the file is
+    /// complete.
+    fn write_resumable(
+        podcast_episode: &PodcastEpisode,
+        file_path: &str,
+        audio: &mut EpisodeAudioSource,
+        resume_state: Option<&EpisodeDownloadState>,
+    ) -> Result<u64, CustomError> {
+        let file_handler = &ENVIRONMENT_SERVICE.default_file_handler;
+        let episode_id = parse_id(&podcast_episode.id)?;
+        if *file_handler != FileHandlerType::Local {
+            return FileHandleWrapper::write_stream(file_path, &mut audio.reader, file_handler);
+        }
+
+        // The episode may resolve to a different path than last time (e.g.
+        // after a settings change); carry the partial data over.
+        if let Some(state) = resume_state
+            && audio.resume_from > 0
+            && state.file_path != file_path
+        {
+            FileHandleWrapper::rename_file(
+                &LocalStorageBackend::part_path(&state.file_path),
+                &LocalStorageBackend::part_path(file_path),
+                file_handler,
+            )?;
+        }
+
+        let record_state = |bytes_received: u64| {
+            let state = EpisodeDownloadState {
+                episode_id,
+                file_path: file_path.to_string(),
+                bytes_received: bytes_received as i64,
+                etag: audio.etag.clone(),
+                last_modified: audio.last_modified.clone(),
+                updated_at: chrono::Utc::now().naive_utc(),
+            };
+            if let Err(e) = download_state_repo().upsert(state) {
+                tracing::warn!("Could not store download state of {episode_id}: {e}");
+            }
+        };
+        record_state(audio.resume_from);
+
+        match FileHandleWrapper::write_stream_from(
+            file_path,
+            &mut audio.reader,
+            audio.resume_from,
+            file_handler,
+        ) {
+            Ok(written) => {
+                if let Err(e) = download_state_repo().delete(episode_id) {
+                    tracing::warn!("Could not clear download state of {episode_id}: {e}");
+                }
+                Ok(written)
+            }
+            Err(err) => {
+                let received = LocalStorageBackend::partial_len(file_path);
+                tracing::info!(
+                    "Download of episode {} stopped after {received} bytes, keeping the partial file",
+                    podcast_episode.episode_id
+                );
+                if audio.etag.is_some() || audio.last_modified.is_some() {
+                    record_state(received);
+                } else {
+                    // Without a validator the partial data can never be
+                    // trusted on resume, so don't keep it around.
+                    let _ = download_state_repo().delete(episode_id);
+                    let _ = std::fs::remove_file(LocalStorageBackend::part_path(file_path));
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// The `If-Range` value for resuming `state`: a strong ETag if we have one,
+    /// else the Last-Modified date. Weak ETags are not allowed in `If-Range`.
+    fn if_range_validator(state: &EpisodeDownloadState) -> Option<String> {
+        state
+            .etag
+            .clone()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or_else(|| state.last_modified.clone())
+    }
+
+    /// First byte position of a `Content-Range: bytes <start>-<end>/<len>`
+    /// header.
+    fn content_range_start(headers: &HeaderMap) -> Option<u64> {
+        headers
+            .get(CONTENT_RANGE)?
+            .to_str()
+            .ok()?
+            .trim()
+            .strip_prefix("bytes ")?
+            .split('-')
+            .next()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    fn header_value(headers: &HeaderMap, name: reqwest::header::HeaderName) -> Option<String> {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    }
+
+    /// Asks the server for the bytes after the ones already in the `.part`
+    /// file. Only a `206` starting exactly at that offset is appended to it.
+    fn request_remaining_bytes(
+        client: &reqwest::blocking::Client,
+        url: &str,
+        state: &EpisodeDownloadState,
+    ) -> Result<ResumeAttempt, CustomError> {
+        let offset = LocalStorageBackend::partial_len(&state.file_path);
+        let Some(validator) = Self::if_range_validator(state).filter(|_| offset > 0) else {
+            return Ok(ResumeAttempt::Restart);
+        };
+
+        let response = client
+            .get(url)
+            .header(RANGE, format!("bytes={offset}-"))
+            .header(IF_RANGE, validator)
+            .send()
+            .map_err(map_reqwest_error)?;
+
+        if response.status() == StatusCode::PARTIAL_CONTENT
+            && Self::content_range_start(response.headers()) == Some(offset)
+        {
+            tracing::info!("Resuming download of {url} at byte {offset}");
+            let suffix = std::path::Path::new(&state.file_path)
+                .extension()
+                .map(|ext| ext.to_string_lossy().into_owned())
+                .unwrap_or_else(|| FileType::Audio.to_string());
+            let total_bytes = response.content_length().map(|len| offset + len);
+            return Ok(ResumeAttempt::Resumed(EpisodeAudioSource {
+                suffix,
+                reader: Box::new(response),
+                temp_file: None,
+                resume_from: offset,
+                etag: state.etag.clone(),
+                last_modified: state.last_modified.clone(),
+                total_bytes,
+            }));
+        }
+
+        tracing::info!(
+            "Server answered the range request for {url} with {}, downloading from the start",
+            response.status()
+        );
+        if response.status() == StatusCode::OK {
+            Ok(ResumeAttempt::FullBody(response))
+        } else {
+            Ok(ResumeAttempt::Restart)
+        }
+    }
+
+    /// Opens the episode audio without reading it into memory. Only the first
+    /// buffer is inspected, to sniff the file extension and to detect HLS
+    /// playlists, which are remuxed by ffmpeg into a temporary file instead.
+    /// With a `resume_state` the server is first asked for the missing bytes
+    /// only; if it sends the whole file instead, the earlier partial download
+    /// is dropped.
+    fn open_episode_audio(
+        client: &reqwest::blocking::Client,
+        podcast_episode: &PodcastEpisode,
+        resume_state: Option<&EpisodeDownloadState>,
+    ) -> Result<EpisodeAudioSource, CustomError> {
+        let url = &podcast_episode.url;
+        let attempt = match resume_state {
+            Some(state) => Self::request_remaining_bytes(client, url, state)?,
+            None => ResumeAttempt::Restart,
+        };
+        let response = match attempt {
+            ResumeAttempt::Resumed(source) => return Ok(source),
+            ResumeAttempt::FullBody(response) => response,
+            ResumeAttempt::Restart => Self::open_episode_stream(client, url)?,
+        };
+        if let Some(state) = resume_state {
+            Self::discard_partial_download(state);
+        }
+        let content_type = Self::header_value(response.headers(), CONTENT_TYPE);
+        let etag = Self::header_value(response.headers(), ETAG);
+        let last_modified = Self::header_value(response.headers(), LAST_MODIFIED);
+        let total_bytes = response.content_length();
+        let mut reader = BufReader::with_capacity(STREAM_PEEK_BYTES, response);
+        let prefix = reader
+            .fill_buf()
+            .map_err(|e| map_io_error(e, Some(url.clone()), ErrorSeverity::Error))?;
+
+        if Self::is_hls_playlist(prefix) {
+            tracing::info!(
+                "Episode {} is served as an HLS playlist, downloading via ffmpeg",
+                podcast_episode.episode_id
+            );
+            let (suffix, temp_file) = Self::download_hls_episode(url)?;
+            let file = File::open(&temp_file).map_err(|e| {
+                let _ = std::fs::remove_file(&temp_file);
+                map_io_error(e, Some(temp_file.clone()), ErrorSeverity::Error)
+            })?;
+            let total_bytes = file.metadata().ok().map(|meta| meta.len());
+            return Ok(EpisodeAudioSource {
+                suffix,
+                reader: Box::new(file),
+                temp_file: Some(temp_file),
+                resume_from: 0,
+                etag: None,
+                last_modified: None,
+                total_bytes,
+            });
+        }
+
+        let suffix =
+            determine_stream_extension(url, content_type.as_deref(), prefix, FileType::Audio);
+        Ok(EpisodeAudioSource {
+            suffix,
+            reader: Box::new(reader),
+            temp_file: None,
+            resume_from: 0,
+            etag,
+            last_modified,
+            total_bytes,
+        })
+    }
+
+    /// Removes the `.part` file and state of a download that is started over.
+    /// The fresh body may get another suffix, e.g. once it turns out to be
+    /// HLS, and would otherwise leave the old partial file behind.
+    fn discard_partial_download(state: &EpisodeDownloadState) {
+        let part_path = LocalStorageBackend::part_path(&state.file_path);
+        if let Err(e) = std::fs::remove_file(&part_path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::warn!("Could not remove stale partial download {part_path}: {e}");
+        }
+        if let Err(e) = download_state_repo().delete(state.episode_id) {
+            tracing::warn!(
+                "Could not clear download state of {}: {e}",
+                state.episode_id
+            );
+        }
+    }
+
     /// True when the downloaded body is an HLS playlist (`#EXTM3U`) instead of
     /// audio — some hosts (e.g. podtoo.com, issue #1402) serve HLS behind a
     /// `.mp3` enclosure URL, so the suffix check never sees it.
@@ -434,10 +833,9 @@ impl DownloadService {
     }
 
     /// Let ffmpeg fetch the HLS segments and remux them into a single audio
-    /// file. Returns `(suffix, bytes)` like `handle_suffix_response`, so the
-    /// rest of the download flow (tagging, S3 upload, opus transcode) runs
-    /// unchanged.
-    fn download_hls_episode(url: &str) -> Result<(String, Vec<u8>), CustomError> {
+    /// file. Returns `(suffix, temp_path)`; the caller streams the temporary
+    /// file into storage like any other download and removes it afterwards.
+    fn download_hls_episode(url: &str) -> Result<(String, String), CustomError> {
         // Pick a lossless remux container from the segment codec: raw MP3
         // segments stay .mp3, everything else (usually ADTS AAC) goes into
         // .m4a — ffmpeg inserts the aac_adtstoasc filter automatically.
@@ -506,51 +904,30 @@ impl DownloadService {
             .into());
         }
 
-        let bytes = std::fs::read(&tmp_path);
-        let _ = std::fs::remove_file(&tmp_path);
-        let bytes = bytes.map_err(|e| {
-            CustomErrorInner::Conflict(
-                format!("Could not read ffmpeg HLS output: {e}"),
-                ErrorSeverity::Error,
-            )
-        })?;
-        Ok((suffix.to_string(), bytes))
+        Ok((suffix.to_string(), tmp_path))
     }
 
-    pub(crate) fn transcode_to_opus(input_path: &str) -> Result<String, CustomError> {
-        let opus_path = {
-            let p = std::path::Path::new(input_path);
-            p.with_extension("opus").to_string_lossy().to_string()
-        };
-
-        let output = std::process::Command::new("ffmpeg")
-            .args([
-                "-i", input_path, "-c:a", "libopus", "-b:a", "48k", "-vn", "-y", &opus_path,
-            ])
-            .output()
-            .map_err(|e| {
-                CustomErrorInner::Conflict(
-                    format!("Failed to run ffmpeg: {e}"),
-                    ErrorSeverity::Warning,
-                )
-            })?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(CustomErrorInner::Conflict(
-                format!("ffmpeg transcode failed: {stderr}"),
-                ErrorSeverity::Warning,
-            )
-            .into());
+    /// Writes the tags of MP3 and MP4 files again once the file is final.
+    /// ffmpeg carries tags over, but not the cover PodFetch embeds, and the
+    /// ReplayGain tags only exist once the final file has been measured.
+    pub(crate) fn retag_final_file(
+        file_path: &str,
+        paths: &FilenameBuilderReturn,
+        podcast_episode: &PodcastEpisode,
+        podcast: &Podcast,
+    ) {
+        let is_taggable = std::path::Path::new(file_path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| ["mp3", "m4a"].contains(&extension));
+        if !is_taggable {
+            return;
         }
-
-        // Remove the original file after successful transcoding
-        if let Err(e) = std::fs::remove_file(input_path) {
-            tracing::warn!("Could not remove original file after opus transcode: {e}");
+        let final_file =
+            FilenameBuilderReturn::new(file_path.to_string(), paths.image_filename.clone());
+        if let Err(err) = Self::handle_metadata_insertion(&final_file, podcast_episode, podcast) {
+            tracing::warn!("Error tagging {file_path}: {err}");
         }
-
-        tracing::info!("Transcoded to opus: {opus_path}");
-        Ok(opus_path)
     }
 
     pub fn handle_metadata_insertion(
@@ -633,10 +1010,7 @@ impl DownloadService {
             });
         }
 
-        let index = PodcastEpisodeService::get_position_of_episode(
-            &podcast_episode.date_of_recording,
-            parse_id(&podcast_episode.podcast_id)?,
-        )?;
+        let index = Self::episode_index(podcast_episode)?;
 
         let numbering_enabled = Self::episode_numbering_enabled(parse_id(&podcast.id)?)?;
         if let Some((title, processed)) = Self::resolve_episode_title(
@@ -674,10 +1048,13 @@ impl DownloadService {
             });
         }
 
-        let track_number = PodcastEpisodeService::get_track_number_for_episode(
-            parse_id(&podcast.id)?,
-            &podcast_episode.date_of_recording,
-        );
+        let track_number = match podcast_episode.episode_number {
+            Some(number) => Ok(i64::from(number)),
+            None => PodcastEpisodeService::get_track_number_for_episode(
+                parse_id(&podcast.id)?,
+                &podcast_episode.date_of_recording,
+            ),
+        };
 
         if tag.track().is_none()
             && let Ok(track_number) = track_number
@@ -685,6 +1062,20 @@ impl DownloadService {
             tag.set_track(track_number as u32);
         }
 
+        if let (Some(lufs), Some(true_peak)) = (
+            podcast_episode.loudness_lufs,
+            podcast_episode.loudness_true_peak,
+        ) {
+            tag.add_frame(id3::frame::ExtendedText {
+                description: "REPLAYGAIN_TRACK_GAIN".to_string(),
+                value: loudness::format_replay_gain(loudness::replay_gain_db(lufs)),
+            });
+            tag.add_frame(id3::frame::ExtendedText {
+                description: "REPLAYGAIN_TRACK_PEAK".to_string(),
+                value: format!("{:.6}", loudness::replay_gain_peak(true_peak)),
+            });
+        }
+
         let write_succesful: Result<(), CustomError> = tag
             // Always write ID3v2.4 because otherwise there are compatibility issues with
             // embedded tags
@@ -709,10 +1100,7 @@ impl DownloadService {
         let tag = mp4ameta::Tag::read_from_path(&paths.filename);
         match tag {
             Ok(mut tag) => {
-                let index = PodcastEpisodeService::get_position_of_episode(
-                    &podcast_episode.date_of_recording,
-                    parse_id(&podcast_episode.podcast_id)?,
-                )?;
+                let index = Self::episode_index(podcast_episode)?;
                 let numbering_enabled = Self::episode_numbering_enabled(parse_id(&podcast.id)?)?;
                 if let Some((title, processed)) = Self::resolve_episode_title(
                     &podcast_episode.name,
@@ -731,10 +1119,13 @@ impl DownloadService {
                 tag.set_genre(podcast.clone().keywords.unwrap_or("Unknown".to_string()));
 
                 tag.set_comment(&podcast_episode.description);
-                let track_number = PodcastEpisodeService::get_track_number_for_episode(
-                    parse_id(&podcast.id)?,
-                    &podcast_episode.date_of_recording,
-                );
+                let track_number = match podcast_episode.episode_number {
+                    Some(number) => Ok(i64::from(number)),
+                    None => PodcastEpisodeService::get_track_number_for_episode(
+                        parse_id(&podcast.id)?,
+                        &podcast_episode.date_of_recording,
+                    ),
+                };
 
                 match track_number {
                     Ok(track_number) => {
@@ -745,6 +1136,31 @@ impl DownloadService {
                     }
                 }
 
+                if let (Some(lufs), Some(true_peak)) = (
+                    podcast_episode.loudness_lufs,
+                    podcast_episode.loudness_true_peak,
+                ) {
+                    tag.set_data(
+                        mp4ameta::FreeformIdent::new_static(
+                            "com.apple.iTunes",
+                            "replaygain_track_gain",
+                        ),
+                        mp4ameta::Data::Utf8(loudness::format_replay_gain(
+                            loudness::replay_gain_db(lufs),
+                        )),
+                    );
+                    tag.set_data(
+                        mp4ameta::FreeformIdent::new_static(
+                            "com.apple.iTunes",
+                            "replaygain_track_peak",
+                        ),
+                        mp4ameta::Data::Utf8(format!(
+                            "{:.6}",
+                            loudness::replay_gain_peak(true_peak)
+                        )),
+                    );
+                }
+
                 if let Err(e) = tag.write_to_path(&paths.filename) {
                     tracing::error!(
                         "Error writing MP4 metadata for episode {}, file may use an unsupported atom layout: {e}",
@@ -766,6 +1182,18 @@ impl DownloadService {
         }
     }
 
+    /// Number used to prefix the title: the feed's episode number when it
+    /// announces one, otherwise the episode's position by date.
+    fn episode_index(podcast_episode: &PodcastEpisode) -> Result<usize, CustomError> {
+        match podcast_episode.episode_number {
+            Some(number) => Ok(number as usize),
+            None => PodcastEpisodeService::get_position_of_episode(
+                &podcast_episode.date_of_recording,
+                parse_id(&podcast_episode.podcast_id)?,
+            ),
+        }
+    }
+
     /// Resolve whether episode-number title prefixing applies to a podcast.
     ///
     /// The per-podcast `episode_numbering` flag is OR-ed with the instance-wide
@@ -945,7 +1373,10 @@ impl DownloadService {
 
 #[cfg(test)]
 mod tests {
-    use super::{DownloadService, FilenameBuilderReturn, Podcast, PodcastEpisode};
+    use super::{
+        DownloadService, EpisodeDownloadState, FilenameBuilderReturn, LocalStorageBackend, Podcast,
+        PodcastEpisode,
+    };
 
     #[test]
     fn resolve_episode_title_handles_all_numbering_states() {
@@ -1259,4 +1690,157 @@ mod tests {
         let _ = std::fs::remove_file(&tmp);
         assert!(result, "local file exists → downloaded");
     }
+
+    const RANGED_BODY: &[u8] = b"0123456789abcdef";
+
+    async fn serve_ranged(headers: axum::http::HeaderMap) -> axum::response::Response {
+        use axum::http::{StatusCode, header};
+        use axum::response::IntoResponse;
+
+        let offset = headers
+            .get(header::RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("bytes="))
+            .and_then(|v| v.trim_end_matches('-').parse::<usize>().ok());
+        let validator_matches = headers.get(header::IF_RANGE).is_some_and(|v| v == "\"v1\"");
+        match offset {
+            Some(offset) if validator_matches => (
+                StatusCode::PARTIAL_CONTENT,
+                [
+                    (
+                        header::CONTENT_RANGE,
+                        format!(
+                            "bytes {offset}-{}/{}",
+                            RANGED_BODY.len() - 1,
+                            RANGED_BODY.len()
+                        ),
+                    ),
+                    (header::ETAG, "\"v1\"".to_string()),
+                ],
+                RANGED_BODY[offset..].to_vec(),
+            )
+                .into_response(),
+            _ => ([(header::ETAG, "\"v1\"")], RANGED_BODY.to_vec()).into_response(),
+        }
+    }
+
+    async fn serve_ignoring_range() -> axum::response::Response {
+        use axum::response::IntoResponse;
+        ([(axum::http::header::ETAG, "\"v2\"")], RANGED_BODY.to_vec()).into_response()
+    }
+
+    fn spawn_enclosure_server() -> String {
+        let app = axum::Router::new()
+            .route("/ranged.mp3", axum::routing::get(serve_ranged))
+            .route("/ignores.mp3", axum::routing::get(serve_ignoring_range));
+        let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().expect("build mock server runtime");
+            rt.block_on(async move {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+                    .await
+                    .expect("bind mock enclosure server");
+                addr_tx.send(listener.local_addr().unwrap()).unwrap();
+                axum::serve(listener, app).await.unwrap();
+            });
+        });
+        format!("http://{}", addr_rx.recv().expect("mock server address"))
+    }
+
+    fn partial_download(name: &str, received: &[u8]) -> EpisodeDownloadState {
+        let file_path = std::env::temp_dir()
+            .join(format!(
+                "podfetch-resume-{}-{name}.mp3",
+                uuid::Uuid::new_v4()
+            ))
+            .to_string_lossy()
+            .into_owned();
+        std::fs::write(LocalStorageBackend::part_path(&file_path), received).unwrap();
+        EpisodeDownloadState {
+            episode_id: uuid::Uuid::new_v4(),
+            file_path,
+            bytes_received: received.len() as i64,
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            updated_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    fn read_all(audio: &mut super::EpisodeAudioSource) -> Vec<u8> {
+        let mut body = Vec::new();
+        std::io::Read::read_to_end(&mut audio.reader, &mut body).unwrap();
+        body
+    }
+
+    #[test]
+    fn open_episode_audio_resumes_after_the_partial_file() {
+        let base = spawn_enclosure_server();
+        let state = partial_download("ranged", &RANGED_BODY[..4]);
+        let episode = PodcastEpisode {
+            url: format!("{base}/ranged.mp3"),
+            ..Default::default()
+        };
+
+        let client = reqwest::blocking::Client::new();
+        let mut audio =
+            DownloadService::open_episode_audio(&client, &episode, Some(&state)).unwrap();
+
+        assert_eq!(audio.resume_from, 4);
+        assert_eq!(audio.suffix, "mp3");
+        assert_eq!(read_all(&mut audio), RANGED_BODY[4..].to_vec());
+        let _ = std::fs::remove_file(LocalStorageBackend::part_path(&state.file_path));
+    }
+
+    #[test]
+    fn open_episode_audio_falls_back_to_full_body_when_range_is_ignored() {
+        let base = spawn_enclosure_server();
+        let state = partial_download("ignored", &RANGED_BODY[..4]);
+        let episode = PodcastEpisode {
+            url: format!("{base}/ignores.mp3"),
+            ..Default::default()
+        };
+
+        let client = reqwest::blocking::Client::new();
+        let mut audio =
+            DownloadService::open_episode_audio(&client, &episode, Some(&state)).unwrap();
+
+        assert_eq!(audio.resume_from, 0);
+        assert_eq!(audio.etag.as_deref(), Some("\"v2\""));
+        assert_eq!(read_all(&mut audio), RANGED_BODY.to_vec());
+        assert!(
+            !std::path::Path::new(&LocalStorageBackend::part_path(&state.file_path)).exists(),
+            "the stale partial file is removed"
+        );
+    }
+
+    #[test]
+    fn if_range_validator_skips_weak_etags() {
+        let mut state = partial_download("validator", b"x");
+        state.last_modified = Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string());
+        assert_eq!(
+            DownloadService::if_range_validator(&state).as_deref(),
+            Some("\"v1\"")
+        );
+        state.etag = Some("W/\"weak\"".to_string());
+        assert_eq!(
+            DownloadService::if_range_validator(&state).as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+        let _ = std::fs::remove_file(LocalStorageBackend::part_path(&state.file_path));
+    }
+
+    #[test]
+    fn content_range_start_parses_byte_ranges() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::CONTENT_RANGE,
+            "bytes 1024-2047/4096".parse().unwrap(),
+        );
+        assert_eq!(DownloadService::content_range_start(&headers), Some(1024));
+        headers.insert(
+            reqwest::header::CONTENT_RANGE,
+            "bytes */4096".parse().unwrap(),
+        );
+        assert_eq!(DownloadService::content_range_start(&headers), None);
+    }
 }
@@ -0,0 +1,430 @@
+//! Worker pool draining the persistent download queue.
+//!
+//! A single dispatcher thread claims due jobs and hands each one to its own
+//! download thread, never running more of them at once than the
+//! `max_parallel_downloads` setting allows (re-read on every round, so a
+//! changed setting applies without a restart). [`process_job`] is the
+//! testable core that runs one claimed job and records the outcome: a failed
+//! attempt goes back on the queue with an exponentially growing delay until
+//! [`MAX_ATTEMPTS`] is reached.
+
+use crate::metrics::METRICS;
+use crate::server::ChatServerHandle;
+use crate::services::download::queue::broadcast_status;
+use crate::services::notification_channel::channel::NotificationEvent;
+use crate::services::notification_channel::service::NotificationChannelService;
+use crate::services::podcast::service::PodcastService;
+use crate::services::settings::service::SettingsService;
+use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
+use common_infrastructure::config::{TELEGRAM_API_ENABLED, is_env_var_present_and_true};
+use common_infrastructure::error::{CustomError, CustomErrorInner, ErrorSeverity};
+use common_infrastructure::mutex::LockResultExt;
+use common_infrastructure::telegram::send_new_episode_notification;
+use podfetch_domain::episode_download::{DownloadJob, DownloadJobRepository, DownloadJobStatus};
+use podfetch_persistence::adapters::DownloadJobRepositoryImpl;
+use podfetch_persistence::db::database;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// After this many failed attempts a job is given up on (`failed`).
+const MAX_ATTEMPTS: i32 = 5;
+/// Delay before the first retry; doubled for every further failed attempt.
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(30);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(60 * 60);
+/// How long the dispatcher waits when nothing is due and nobody wakes it.
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+type DownloadFn = dyn Fn(&DownloadJob) -> Result<(), CustomError> + Send + Sync;
+
+/// Set (and signalled) whenever there may be new work for the dispatcher: a
+/// job was queued or retried, or a download slot became free.
+static WAKE: LazyLock<(Mutex<bool>, Condvar)> =
+    LazyLock::new(|| (Mutex::new(false), Condvar::new()));
+
+pub fn wake_download_worker() {
+    let (woken, signal) = &*WAKE;
+    *woken.lock().ignore_poison() = true;
+    signal.notify_all();
+}
+
+fn wait_for_wake(timeout: Duration) {
+    let (woken, signal) = &*WAKE;
+    let guard = woken.lock().ignore_poison();
+    let (mut guard, _) = signal
+        .wait_timeout_while(guard, timeout, |woken| !*woken)
+        .ignore_poison();
+    *guard = false;
+}
+
+/// Delay before the next attempt of a job that failed `attempts` times.
+fn retry_delay(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    RETRY_BASE_DELAY
+        .saturating_mul(2u32.pow(exponent))
+        .min(RETRY_MAX_DELAY)
+}
+
+fn parallel_limit() -> usize {
+    match SettingsService::shared().get_settings() {
+        Ok(Some(settings)) => settings.max_parallel_downloads.max(1) as usize,
+        Ok(None) => 1,
+        Err(err) => {
+            tracing::error!("Download worker: could not read settings: {err}");
+            1
+        }
+    }
+}
+
+/// Runs one claimed job through `download` and records the outcome on the
+/// job row. A failing or panicking download is not an error here; only a
+/// repository failure while updating the job propagates.
+fn process_job(
+    job_repo: &dyn DownloadJobRepository<Error = CustomError>,
+    job: &DownloadJob,
+    download: &DownloadFn,
+) -> Result<(), CustomError> {
+    let started = Instant::now();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| download(job)))
+        .unwrap_or_else(|_| Err(CustomErrorInner::Unknown(ErrorSeverity::Error).into()));
+    METRICS.observe_download(started.elapsed(), result.is_ok());
+
+    match result {
+        Ok(()) => job_repo.set_status(job.id, DownloadJobStatus::Done, None)?,
+        Err(err) => {
+            let error_message = err.to_string();
+            let attempts = job_repo.increment_attempts(job.id)?;
+            if attempts >= MAX_ATTEMPTS {
+                tracing::error!(
+                    "Giving up on downloading episode {} after {attempts} attempts: {err}",
+                    job.episode_id
+                );
+                job_repo.set_status(job.id, DownloadJobStatus::Failed, Some(&error_message))?;
+                notify_final_failure(job, &err);
+            } else {
+                let delay = retry_delay(attempts);
+                tracing::warn!(
+                    "Download of episode {} failed (attempt {attempts}), retrying in {}s: {err}",
+                    job.episode_id,
+                    delay.as_secs()
+                );
+                let next_run_at = chrono::Utc::now().naive_utc()
+                    + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::zero());
+                job_repo.schedule_retry(job.id, next_run_at, &error_message)?;
+            }
+        }
+    }
+
+    if let Some(updated) = job_repo.get(job.id)? {
+        broadcast_status(&updated);
+    }
+    Ok(())
+}
+
+/// Downloads the job's episode unless it is available already.
+fn download_job(job: &DownloadJob) -> Result<(), CustomError> {
+    let episode = PodcastEpisodeService::get_podcast_episode_by_internal_id(job.episode_id)?
+        .ok_or_else(|| CustomError::from(CustomErrorInner::NotFound(ErrorSeverity::Warning)))?;
+    if episode.is_downloaded() {
+        return Ok(());
+    }
+    let podcast = PodcastService::get_podcast(job.podcast_id)?;
+
+    let downloaded = PodcastEpisodeService::try_download(&episode, &podcast)?;
+    // Explicitly requested downloads may bring back an episode the user
+    // deleted earlier.
+    if episode.deleted {
+        PodcastEpisodeService::update_deleted(&episode.episode_id, false)?;
+    }
+    ChatServerHandle::broadcast_podcast_episode_offline_available(&downloaded, &podcast);
+
+    if is_env_var_present_and_true(TELEGRAM_API_ENABLED) {
+        send_new_episode_notification(&episode.name, &podcast.name)
+    }
+    NotificationChannelService::notify(NotificationEvent::new_episode(
+        &podcast.name,
+        &episode.name,
+    ));
+    Ok(())
+}
+
+fn notify_final_failure(job: &DownloadJob, err: &CustomError) {
+    match PodcastEpisodeService::get_podcast_episode_by_internal_id(job.episode_id) {
+        Ok(Some(episode)) => {
+            PodcastEpisodeService::notify_download_failed(&episode, err, job.requested_by)
+        }
+        Ok(None) => {}
+        Err(lookup_err) => tracing::error!(
+            "Could not load episode {} for its failed-download notification: {lookup_err}",
+            job.episode_id
+        ),
+    }
+}
+
+/// Endless background loop draining the download queue.
+///
+/// Jobs left `running` by a process that stopped uncleanly are put back to
+/// `pending` once, up front; their partial data is picked up again by the
+/// resumable download.
+pub async fn run_download_worker() {
+    let stop = Arc::new(AtomicBool::new(false));
+    run_worker(stop, Arc::new(download_job)).await
+}
+
+/// The dispatcher loop behind [`run_download_worker`]. It returns once `stop`
+/// is set and the worker is woken (only tests ever do that); downloads that
+/// are already running finish on their own threads.
+async fn run_worker(stop: Arc<AtomicBool>, download: Arc<DownloadFn>) {
+    let job_repo: Arc<DownloadJobRepositoryImpl> =
+        Arc::new(DownloadJobRepositoryImpl::new(database()));
+    match job_repo.reset_running_to_pending() {
+        Ok(0) => {}
+        Ok(reset) => {
+            tracing::info!(
+                "Reset {reset} interrupted download job(s) from running back to pending at startup"
+            )
+        }
+        Err(err) => tracing::error!("Failed to reset interrupted download jobs at startup: {err}"),
+    }
+
+    let outcome = tokio::task::spawn_blocking(move || {
+        let active = Arc::new(AtomicUsize::new(0));
+        while !stop.load(Ordering::Relaxed) {
+            if active.load(Ordering::SeqCst) >= parallel_limit() {
+                wait_for_wake(POLL_INTERVAL);
+                continue;
+            }
+
+            let job = match job_repo.claim_next_due(chrono::Utc::now().naive_utc()) {
+                Ok(Some(job)) => job,
+                Ok(None) => {
+                    wait_for_wake(POLL_INTERVAL);
+                    continue;
+                }
+                Err(err) => {
+                    tracing::error!("Download worker: could not claim a job: {err}");
+                    wait_for_wake(POLL_INTERVAL);
+                    continue;
+                }
+            };
+
+            broadcast_status(&job);
+            active.fetch_add(1, Ordering::SeqCst);
+            let job_repo = job_repo.clone();
+            let download = download.clone();
+            let active = active.clone();
+            std::thread::spawn(move || {
+                if let Err(err) = process_job(job_repo.as_ref(), &job, download.as_ref()) {
+                    tracing::error!("Download worker: could not record job {}: {err}", job.id);
+                }
+                active.fetch_sub(1, Ordering::SeqCst);
+                wake_download_worker();
+            });
+        }
+    })
+    .await;
+
+    if let Err(join_err) = outcome {
+        tracing::error!("Download worker stopped unexpectedly: {join_err}");
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::test_support::tests::{GLOBAL_MUTEX, ensure_test_env_vars};
+    use diesel::prelude::*;
+    use podfetch_persistence::db::{get_connection, run_migrations};
+    use podfetch_persistence::schema::{podcast_episodes, podcasts};
+    use std::sync::MutexGuard;
+    use uuid::Uuid;
+
+    fn lock_and_prepare_db() -> MutexGuard<'static, ()> {
+        ensure_test_env_vars();
+        let guard = GLOBAL_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        run_migrations();
+
+        let mut conn = get_connection();
+        for table in ["download_jobs", "podcast_episodes", "podcasts"] {
+            let _ = diesel::sql_query(format!("DELETE FROM {table}")).execute(&mut conn);
+        }
+
+        guard
+    }
+
+    #[derive(Insertable)]
+    #[diesel(table_name = podcasts)]
+    struct SeedPodcast {
+        id: String,
+        name: String,
+        directory_id: String,
+        rssfeed: String,
+        image_url: String,
+        active: bool,
+        original_image_url: String,
+        directory_name: String,
+    }
+
+    #[derive(Insertable)]
+    #[diesel(table_name = podcast_episodes)]
+    struct SeedEpisode {
+        id: String,
+        podcast_id: String,
+        episode_id: String,
+        name: String,
+        url: String,
+        date_of_recording: String,
+        image_url: String,
+        total_time: i32,
+        description: String,
+        guid: String,
+        deleted: bool,
+        episode_numbering_processed: bool,
+    }
+
+    /// Seeds a podcast with one episode and queues a download job for it.
+    fn queued_job(repo: &DownloadJobRepositoryImpl) -> DownloadJob {
+        let podcast_id = Uuid::new_v4();
+        let episode_id = Uuid::new_v4();
+        let mut conn = get_connection();
+        diesel::insert_into(podcasts::table)
+            .values(SeedPodcast {
+                id: podcast_id.to_string(),
+                name: format!("Test Podcast {podcast_id}"),
+                directory_id: Uuid::new_v4().to_string(),
+                rssfeed: format!("https://example.com/feed/{podcast_id}.xml"),
+                image_url: "https://example.com/img.png".to_string(),
+                active: true,
+                original_image_url: "https://example.com/img.png".to_string(),
+                directory_name: format!("podcast-{podcast_id}"),
+            })
+            .execute(&mut conn)
+            .expect("seed podcast");
+        diesel::insert_into(podcast_episodes::table)
+            .values(SeedEpisode {
+                id: episode_id.to_string(),
+                podcast_id: podcast_id.to_string(),
+                episode_id: Uuid::new_v4().to_string(),
+                name: "Test Episode".to_string(),
+                url: format!("https://example.com/ep/{episode_id}.mp3"),
+                date_of_recording: "2024-01-01".to_string(),
+                image_url: "https://example.com/ep.png".to_string(),
+                total_time: 3600,
+                description: "Test description".to_string(),
+                guid: Uuid::new_v4().to_string(),
+                deleted: false,
+                episode_numbering_processed: false,
+            })
+            .execute(&mut conn)
+            .expect("seed episode");
+        repo.enqueue(episode_id, podcast_id, 0, None)
+            .expect("enqueue")
+            .expect("job created")
+    }
+
+    fn failing_download(_: &DownloadJob) -> Result<(), CustomError> {
+        Err(
+            CustomErrorInner::Conflict("enclosure unavailable".to_string(), ErrorSeverity::Warning)
+                .into(),
+        )
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        assert_eq!(retry_delay(1), Duration::from_secs(30));
+        assert_eq!(retry_delay(2), Duration::from_secs(60));
+        assert_eq!(retry_delay(4), Duration::from_secs(240));
+        assert_eq!(retry_delay(20), RETRY_MAX_DELAY);
+    }
+
+    #[test]
+    fn process_job_marks_successful_download_done() {
+        let _guard = lock_and_prepare_db();
+        let repo = DownloadJobRepositoryImpl::new(database());
+        let job = queued_job(&repo);
+
+        process_job(&repo, &job, &|_| Ok(())).unwrap();
+
+        let stored = repo.get(job.id).unwrap().unwrap();
+        assert_eq!(stored.status, DownloadJobStatus::Done);
+        assert_eq!(stored.attempts, 0);
+    }
+
+    #[test]
+    fn process_job_backs_off_after_a_failed_attempt() {
+        let _guard = lock_and_prepare_db();
+        let repo = DownloadJobRepositoryImpl::new(database());
+        let job = queued_job(&repo);
+        let before = chrono::Utc::now().naive_utc();
+
+        process_job(&repo, &job, &failing_download).unwrap();
+
+        let stored = repo.get(job.id).unwrap().unwrap();
+        assert_eq!(stored.status, DownloadJobStatus::Pending);
+        assert_eq!(stored.attempts, 1);
+        assert!(stored.error.is_some());
+        assert!(stored.next_run_at >= before + chrono::Duration::seconds(30));
+        assert!(
+            repo.claim_next_due(chrono::Utc::now().naive_utc())
+                .unwrap()
+                .is_none(),
+            "a backed-off job must not be claimed before its retry time"
+        );
+    }
+
+    #[test]
+    fn process_job_gives_up_after_max_attempts() {
+        let _guard = lock_and_prepare_db();
+        let repo = DownloadJobRepositoryImpl::new(database());
+        let job = queued_job(&repo);
+
+        for _ in 0..MAX_ATTEMPTS {
+            process_job(&repo, &job, &failing_download).unwrap();
+        }
+
+        let stored = repo.get(job.id).unwrap().unwrap();
+        assert_eq!(stored.status, DownloadJobStatus::Failed);
+        assert_eq!(stored.attempts, MAX_ATTEMPTS);
+    }
+
+    #[test]
+    fn process_job_survives_a_panicking_download() {
+        let _guard = lock_and_prepare_db();
+        let repo = DownloadJobRepositoryImpl::new(database());
+        let job = queued_job(&repo);
+
+        process_job(&repo, &job, &|_| panic!("decoder exploded")).unwrap();
+
+        let stored = repo.get(job.id).unwrap().unwrap();
+        assert_eq!(stored.status, DownloadJobStatus::Pending);
+        assert_eq!(stored.attempts, 1);
+    }
+
+    #[tokio::test]
+    #[allow(clippy::await_holding_lock)]
+    async fn worker_runs_queued_jobs_until_stopped() {
+        let _guard = lock_and_prepare_db();
+        let repo = DownloadJobRepositoryImpl::new(database());
+        let jobs = [queued_job(&repo), queued_job(&repo)];
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = tokio::spawn(run_worker(stop.clone(), Arc::new(|_: &DownloadJob| Ok(()))));
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while jobs
+            .iter()
+            .any(|job| repo.get(job.id).unwrap().unwrap().status != DownloadJobStatus::Done)
+        {
+            assert!(Instant::now() < deadline, "queued jobs were not run");
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        stop.store(true, Ordering::Relaxed);
+        wake_download_worker();
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("worker stops once asked to")
+            .unwrap();
+    }
+}
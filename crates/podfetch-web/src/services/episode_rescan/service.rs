@@ -1,7 +1,9 @@
 use crate::services::download::service::DownloadService;
 use crate::services::file::service::{FileService, prepare_podcast_episode_title_to_directory};
 use crate::services::podcast::service::PodcastService;
+use crate::services::podcast_settings::service::PodcastSettingsService;
 use crate::services::settings::service::SettingsService;
+use crate::services::transcoding::service::{self as transcoding, TranscodingProfileService};
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
 use common_infrastructure::config::FileHandlerType;
 use common_infrastructure::error::{CustomError, CustomErrorInner, ErrorSeverity};
@@ -21,8 +23,8 @@ pub struct RescanOptions {
     /// Rename / move the audio + image file to the path the current naming
     /// settings would produce.
     pub apply_filenames: bool,
-    /// If `auto_transcode_opus` is on and the file is still mp3, convert it
-    /// to opus (same ffmpeg call the downloader uses).
+    /// Transcode files that don't have the format of the podcast's
+    /// transcoding profile yet (same ffmpeg call the downloader uses).
     pub apply_transcode: bool,
     /// If `use_one_cover_for_all_episodes` is on, remove the per-episode
     /// cover image from disk.
@@ -34,6 +36,9 @@ pub struct RescanOptions {
     /// For episodes ingested before SponsorBlock support, the YouTube video id
     /// is backfilled in-memory from the guid/url for this fetch.
     pub refetch_sponsorblock: bool,
+    /// Run skip detection on the episode, replacing its local silence and
+    /// jingle segments. Works regardless of the `skip_detection` setting.
+    pub detect_skip_segments: bool,
     /// (Re)write NFO files for the episode (and rename the cover) using the
     /// current settings, without re-downloading audio.
     pub regenerate_nfo: bool,
@@ -46,6 +51,7 @@ impl RescanOptions {
             || self.apply_covers
             || self.apply_metadata
             || self.refetch_sponsorblock
+            || self.detect_skip_segments
             || self.regenerate_nfo
     }
 }
@@ -129,19 +135,27 @@ impl EpisodeRescanService {
 
         let mut working_audio_path = current_audio_path.clone();
 
-        // Step 1: transcode mp3 -> opus if requested and applicable.
-        if opts.apply_transcode
-            && settings.auto_transcode_opus
-            && extension_lower(&working_audio_path).as_deref() == Some("mp3")
+        // Step 1: transcode with the podcast's profile if requested and the
+        // file doesn't have its format yet.
+        let transcoding_profile = if opts.apply_transcode {
+            TranscodingProfileService::default_service().profile_for(
+                &settings,
+                PodcastSettingsService::get_settings_for_podcast(podcast_uuid)?.as_ref(),
+            )?
+        } else {
+            None
+        };
+        if let Some(profile) = transcoding_profile
+            && !transcoding::is_transcoded(&working_audio_path, &profile)
         {
-            match DownloadService::transcode_to_opus(&working_audio_path) {
+            match transcoding::transcode(&working_audio_path, &profile) {
                 Ok(new_path) => {
                     working_audio_path = new_path;
                     stats.transcoded += 1;
                 }
                 Err(err) => {
                     tracing::warn!(
-                        "Transcode to opus failed for episode {}: {err}",
+                        "Transcoding failed for episode {}: {err}",
                         episode.episode_id
                     );
                     stats.errors += 1;
@@ -372,6 +386,16 @@ impl EpisodeRescanService {
             }
         }
 
+        if opts.detect_skip_segments
+            && let Err(err) = crate::services::skip_detection::service::analyze_episode(
+                episode,
+                &final_audio_path,
+            )
+        {
+            tracing::warn!("Skip detection failed for {}: {err}", episode.id);
+            stats.errors += 1;
+        }
+
         if opts.regenerate_nfo {
             crate::services::nfo::service::regenerate_for_episode(
                 &podcast,
@@ -523,6 +547,13 @@ mod tests {
             }
             .any_enabled()
         );
+        assert!(
+            RescanOptions {
+                detect_skip_segments: true,
+                ..RescanOptions::default()
+            }
+            .any_enabled()
+        );
         assert!(
             RescanOptions {
                 regenerate_nfo: true,
@@ -0,0 +1,99 @@
+//! Podcasting 2.0 `<podcast:locked>`: a publisher marks the feed as not to
+//! be moved to another host, naming the account that may do so in `owner`.
+//! A locked feed is only followed to a new address that is locked by the
+//! same owner, so a forged `<itunes:new-feed-url>` cannot take it over.
+
+use rss::Channel;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedLock {
+    pub locked: bool,
+    pub owner: Option<String>,
+}
+
+/// The channel's lock, keyed by its local or its qualified name like other
+/// `podcast:` tags.
+pub fn feed_lock(channel: &Channel) -> Option<FeedLock> {
+    let podcast_ns = channel.extensions().get("podcast")?;
+    let lock = ["locked", "podcast:locked"]
+        .into_iter()
+        .filter_map(|key| podcast_ns.get(key))
+        .flatten()
+        .next()?;
+    Some(FeedLock {
+        locked: lock
+            .value()
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("yes")),
+        owner: lock
+            .attrs()
+            .get("owner")
+            .map(|owner| owner.trim().to_lowercase())
+            .filter(|owner| !owner.is_empty()),
+    })
+}
+
+/// Whether a feed with lock `from` may be followed to a feed with lock `to`.
+pub fn may_move(from: Option<&FeedLock>, to: Option<&FeedLock>) -> bool {
+    match (from, to) {
+        (Some(from), _) if !from.locked => true,
+        (None, _) => true,
+        (Some(from), Some(to)) => to.locked && to.owner == from.owner,
+        (Some(_), None) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(lock: &str) -> Channel {
+        Channel::read_from(
+            format!(
+                r#"<rss version="2.0" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+                <channel><title>Feed</title><link>https://example.com</link>
+                <description>Feed</description>{lock}</channel></rss>"#
+            )
+            .as_bytes(),
+        )
+        .unwrap()
+    }
+
+    fn lock(locked: bool, owner: Option<&str>) -> FeedLock {
+        FeedLock {
+            locked,
+            owner: owner.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn the_lock_is_read_from_the_channel() {
+        assert_eq!(
+            feed_lock(&channel(
+                r#"<podcast:locked owner="Host@Example.com">yes</podcast:locked>"#
+            )),
+            Some(lock(true, Some("host@example.com")))
+        );
+        assert_eq!(
+            feed_lock(&channel("<podcast:locked>no</podcast:locked>")),
+            Some(lock(false, None))
+        );
+        assert_eq!(feed_lock(&channel("")), None);
+    }
+
+    #[test]
+    fn locked_feeds_only_move_to_feeds_of_the_same_owner() {
+        let owned = lock(true, Some("host@example.com"));
+        assert!(may_move(None, None));
+        assert!(may_move(Some(&lock(false, None)), None));
+        assert!(may_move(Some(&owned), Some(&owned)));
+        assert!(!may_move(Some(&owned), None));
+        assert!(!may_move(
+            Some(&owned),
+            Some(&lock(false, Some("host@example.com")))
+        ));
+        assert!(!may_move(
+            Some(&owned),
+            Some(&lock(true, Some("other@example.com")))
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+//! Health of podcast feeds across refreshes.
+//!
+//! Every refresh ends in [`FeedHealthService::record_success`] or
+//! [`FeedHealthService::record_failure`]. The first failure of a streak sends
+//! a `feed-broken` notification; a feed that keeps failing for longer than
+//! `dead_feed_deactivation_days` is switched inactive so the poller stops
+//! hammering it.
+
+use crate::podcast::PodcastFeedHealthDto;
+use crate::services::notification_channel::channel::NotificationEvent;
+use crate::services::notification_channel::service::NotificationChannelService;
+use crate::services::podcast::service::PodcastService;
+use crate::services::settings::service::SettingsService;
+use chrono::{Duration, NaiveDateTime, Utc};
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::podcast_feed::{PodcastFeedHealth, PodcastFeedStateRepository};
+use podfetch_persistence::adapters::PodcastFeedStateRepositoryImpl;
+use podfetch_persistence::db::database;
+use podfetch_persistence::podcast::PodcastEntity as Podcast;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct FeedHealthService {
+    repository: Arc<dyn PodcastFeedStateRepository<Error = CustomError>>,
+}
+
+impl FeedHealthService {
+    pub fn new(repository: Arc<dyn PodcastFeedStateRepository<Error = CustomError>>) -> Self {
+        Self { repository }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(Arc::new(PodcastFeedStateRepositoryImpl::new(database())))
+    }
+
+    /// Notes a refresh that fetched and processed the feed, `304 Not
+    /// Modified` included.
+    pub fn record_success(&self, podcast: &Podcast, http_status: Option<u16>) {
+        let Ok(podcast_id) = Uuid::parse_str(&podcast.id) else {
+            return;
+        };
+        let previous_failures = self
+            .repository
+            .get_health(podcast_id)
+            .ok()
+            .flatten()
+            .map_or(0, |health| health.consecutive_failures);
+        if let Err(err) = self.repository.record_success(
+            podcast_id,
+            http_status.map(i32::from),
+            Utc::now().naive_utc(),
+        ) {
+            tracing::warn!("Could not record feed health of {}: {err}", podcast.name);
+            return;
+        }
+        if previous_failures > 0 {
+            tracing::info!(
+                "Feed of podcast {} recovered after {previous_failures} failed refreshes",
+                podcast.name
+            );
+        }
+    }
+
+    /// Notes a failed refresh. Notifies on the first failure of a streak and
+    /// deactivates the podcast once the streak outlasts the configured days.
+    pub fn record_failure(&self, podcast: &Podcast, http_status: Option<u16>, err: &CustomError) {
+        let Ok(podcast_id) = Uuid::parse_str(&podcast.id) else {
+            return;
+        };
+        let error = err.inner.to_string();
+        let now = Utc::now().naive_utc();
+        let health = match self.repository.record_failure(
+            podcast_id,
+            &error,
+            http_status.map(i32::from),
+            now,
+        ) {
+            Ok(health) => health,
+            Err(record_err) => {
+                tracing::warn!(
+                    "Could not record feed health of {}: {record_err}",
+                    podcast.name
+                );
+                return;
+            }
+        };
+        if health.consecutive_failures == 1 {
+            NotificationChannelService::notify(NotificationEvent::feed_broken(
+                &podcast.name,
+                &error,
+            ));
+        }
+
+        let deactivation_days = SettingsService::shared()
+            .get_settings()
+            .ok()
+            .flatten()
+            .map_or(0, |settings| settings.dead_feed_deactivation_days);
+        if podcast.active && Self::is_dead(&health, deactivation_days, now) {
+            self.deactivate(podcast, podcast_id, deactivation_days, &error);
+        }
+    }
+
+    fn is_dead(health: &PodcastFeedHealth, deactivation_days: i32, now: NaiveDateTime) -> bool {
+        if deactivation_days <= 0 {
+            return false;
+        }
+        health
+            .failing_since
+            .is_some_and(|since| since <= now - Duration::days(i64::from(deactivation_days)))
+    }
+
+    fn deactivate(&self, podcast: &Podcast, podcast_id: Uuid, days: i32, error: &str) {
+        if let Err(err) = PodcastService::set_podcast_active(podcast_id, false) {
+            tracing::error!("Could not deactivate dead feed of {}: {err}", podcast.name);
+            return;
+        }
+        tracing::warn!(
+            "Deactivated podcast {} as its feed has been failing for {days} days",
+            podcast.name
+        );
+        NotificationChannelService::notify(NotificationEvent::feed_broken(
+            &podcast.name,
+            &format!("Deactivated after failing for {days} days: {error}"),
+        ));
+    }
+
+    /// Starts over after the user switched a deactivated podcast back on, so
+    /// it gets the full grace period again.
+    pub fn clear_failures(&self, podcast_id: Uuid) -> Result<(), CustomError> {
+        self.repository.clear_failures(podcast_id)
+    }
+
+    /// Every podcast whose last refresh failed, longest failing first.
+    pub fn list_unhealthy(&self) -> Result<Vec<PodcastFeedHealthDto>, CustomError> {
+        let failing = self.repository.list_failing()?;
+        let mut unhealthy = Vec::with_capacity(failing.len());
+        for health in failing {
+            let podcast = match PodcastService::get_podcast(health.podcast_id) {
+                Ok(podcast) => podcast,
+                // Deleted podcasts take their feed state with them; a row
+                // left over in between is simply not reported.
+                Err(err) if matches!(err.inner, CustomErrorInner::NotFound(_)) => continue,
+                Err(err) => return Err(err),
+            };
+            unhealthy.push(PodcastFeedHealthDto::new(&podcast.into(), health));
+        }
+        Ok(unhealthy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(failing_since: Option<NaiveDateTime>) -> PodcastFeedHealth {
+        PodcastFeedHealth {
+            podcast_id: Uuid::new_v4(),
+            last_success_at: None,
+            last_failure_at: failing_since,
+            consecutive_failures: i32::from(failing_since.is_some()),
+            failing_since,
+            last_error: None,
+            last_http_status: Some(404),
+        }
+    }
+
+    #[test]
+    fn feeds_are_dead_once_the_streak_outlasts_the_setting() {
+        let now = Utc::now().naive_utc();
+        let long_failing = health(Some(now - Duration::days(31)));
+        let recently_failing = health(Some(now - Duration::days(2)));
+
+        assert!(FeedHealthService::is_dead(&long_failing, 30, now));
+        assert!(!FeedHealthService::is_dead(&recently_failing, 30, now));
+        assert!(!FeedHealthService::is_dead(&long_failing, 0, now));
+        assert!(!FeedHealthService::is_dead(&health(None), 30, now));
+    }
+}
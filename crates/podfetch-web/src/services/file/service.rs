@@ -14,6 +14,7 @@ use crate::services::settings::service::SettingsService;
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
 use common_infrastructure::config::FileHandlerType;
 use common_infrastructure::error::{CustomError, CustomErrorInner, ErrorSeverity};
+use common_infrastructure::feed::parse_feed;
 use common_infrastructure::rss::{PodcastParsed, RSSFeedParser};
 use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
 use podfetch_domain::settings::{ReplacementStrategy, Setting};
@@ -163,6 +164,14 @@ impl FileService {
             if check_if_file_exists(&episode_path, &download_location) {
                 FileHandleWrapper::remove_file(&episode_path, &download_location)?;
             }
+            if download_location == FileHandlerType::Local {
+                for original in crate::services::transcoding::service::kept_originals(&episode_path)
+                {
+                    if let Err(err) = std::fs::remove_file(&original) {
+                        tracing::warn!("Could not remove {}: {err}", original.display());
+                    }
+                }
+            }
         }
         if let Some(image_path) = episode.file_image_path.clone() {
             let file_type =
@@ -228,9 +237,14 @@ pub async fn prepare_podcast_title_to_directory(
             let client = reqwest::Client::new();
             let rss_feed = podcast.feed_url.clone();
             let feed_response = client.get(rss_feed).send().await.unwrap();
+            let content_type = feed_response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
             let content = feed_response.bytes().await.unwrap();
 
-            let channel = Channel::read_from(&content[..]);
+            let channel = parse_feed(&content, content_type.as_deref());
             RSSFeedParser::parse_rss_feed(channel.unwrap())
         }
     };
@@ -401,6 +415,18 @@ pub fn perform_episode_variable_replacement(
     );
     vars.insert("episodeDuration".to_string(), &total_time);
     vars.insert("episodeNumber".to_string(), &episode_number_str);
+    // `{season}`/`{episode}` come from the feed's tags. Without an episode tag
+    // the position based number is used instead.
+    let season = podcast_episode
+        .season
+        .map(|season| season.to_string())
+        .unwrap_or_default();
+    let feed_episode_number = podcast_episode
+        .episode_number
+        .map(|number| number.to_string())
+        .unwrap_or_else(|| episode_number_str.clone());
+    vars.insert("season".to_string(), &season);
+    vars.insert("episode".to_string(), &feed_episode_number);
 
     let fixed_string = episode_format
         .replace("{title}", "{episodeTitle}")
@@ -491,6 +517,20 @@ mod tests {
     use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
     use serial_test::serial;
 
+    fn test_settings(
+        podcast_format: &str,
+        episode_format: &str,
+        replacement_strategy: &str,
+    ) -> Setting {
+        Setting {
+            podcast_format: podcast_format.to_string(),
+            episode_format: episode_format.to_string(),
+            replacement_strategy: replacement_strategy.to_string(),
+            replace_invalid_characters: true,
+            ..Default::default()
+        }
+    }
+
     #[test]
     #[serial]
     fn test_remove_file_suffix() {
@@ -527,28 +567,11 @@ mod tests {
     #[serial]
     fn test_perform_replacement_dash_and_underscore() {
         let title = "test: test";
-        let settings = Setting {
-            id: uuid::Uuid::nil(),
-            episode_numbering: false,
-            auto_download: false,
-            auto_update: false,
-            auto_cleanup: false,
-            auto_cleanup_days: 0,
-            podcast_format: "test{podcasttitle}".to_string(),
-            episode_format: "test123{episodetitle}".to_string(),
-            replacement_strategy: "replace-with-dash-and-underscore".to_string(),
-            replace_invalid_characters: true,
-            use_existing_filename: false,
-
-            podcast_prefill: 0,
-            direct_paths: false,
-            auto_transcode_opus: false,
-            use_one_cover_for_all_episodes: false,
-            max_parallel_downloads: 3,
-            sponsorblock_enabled: true,
-            nfo_format: "off".to_string(),
-            cover_filename: "image".to_string(),
-        };
+        let settings = test_settings(
+            "test{podcasttitle}",
+            "test123{episodetitle}",
+            "replace-with-dash-and-underscore",
+        );
 
         let result = perform_replacement(title, settings, None);
 
@@ -559,28 +582,7 @@ mod tests {
     #[serial]
     fn test_perform_replacement_remove() {
         let title = "test: test";
-        let settings = Setting {
-            id: uuid::Uuid::nil(),
-            episode_numbering: false,
-            auto_download: false,
-            auto_update: false,
-            auto_cleanup: false,
-            auto_cleanup_days: 0,
-            podcast_format: "test{podcasttitle}".to_string(),
-            episode_format: "test123{episodetitle}".to_string(),
-            replacement_strategy: "remove".to_string(),
-            replace_invalid_characters: true,
-            use_existing_filename: false,
-
-            podcast_prefill: 0,
-            direct_paths: false,
-            auto_transcode_opus: false,
-            use_one_cover_for_all_episodes: false,
-            max_parallel_downloads: 3,
-            sponsorblock_enabled: true,
-            nfo_format: "off".to_string(),
-            cover_filename: "image".to_string(),
-        };
+        let settings = test_settings("test{podcasttitle}", "test123{episodetitle}", "remove");
 
         let result = perform_replacement(title, settings, None);
 
@@ -591,28 +593,11 @@ mod tests {
     #[serial]
     fn test_perform_replacement_replace_with_dash() {
         let title = "test: test";
-        let settings = Setting {
-            id: uuid::Uuid::nil(),
-            episode_numbering: false,
-            auto_download: false,
-            auto_update: false,
-            auto_cleanup: false,
-            auto_cleanup_days: 0,
-            podcast_format: "test{podcasttitle}".to_string(),
-            episode_format: "test123{episodetitle}".to_string(),
-            replacement_strategy: "replace-with-dash".to_string(),
-            replace_invalid_characters: true,
-            use_existing_filename: false,
-
-            podcast_prefill: 0,
-            direct_paths: false,
-            auto_transcode_opus: false,
-            use_one_cover_for_all_episodes: false,
-            max_parallel_downloads: 3,
-            sponsorblock_enabled: true,
-            nfo_format: "off".to_string(),
-            cover_filename: "image".to_string(),
-        };
+        let settings = test_settings(
+            "test{podcasttitle}",
+            "test123{episodetitle}",
+            "replace-with-dash",
+        );
 
         let result = perform_replacement(title, settings, None);
 
@@ -622,27 +607,7 @@ mod tests {
     #[test]
     #[serial]
     fn test_podcast_episode_replacement_guid() {
-        let settings = Setting {
-            id: uuid::Uuid::nil(),
-            episode_numbering: false,
-            auto_download: false,
-            auto_update: false,
-            auto_cleanup: false,
-            auto_cleanup_days: 0,
-            podcast_format: "test{guid}".to_string(),
-            episode_format: "test123{guid}".to_string(),
-            replacement_strategy: "replace-with-dash".to_string(),
-            replace_invalid_characters: true,
-            use_existing_filename: false,
-            podcast_prefill: 0,
-            direct_paths: false,
-            auto_transcode_opus: false,
-            use_one_cover_for_all_episodes: false,
-            max_parallel_downloads: 3,
-            sponsorblock_enabled: true,
-            nfo_format: "off".to_string(),
-            cover_filename: "image".to_string(),
-        };
+        let settings = test_settings("test{guid}", "test123{guid}", "replace-with-dash");
 
         let podcast_episode = PodcastEpisode {
             id: uuid::Uuid::nil().to_string(),
@@ -663,6 +628,11 @@ mod tests {
             episode_numbering_processed: false,
             download_location: None,
             youtube_video_id: None,
+            season: None,
+            episode_number: None,
+            episode_type: None,
+            loudness_lufs: None,
+            loudness_true_peak: None,
         };
 
         let result = perform_episode_variable_replacement(settings, podcast_episode, None, 1, None);
@@ -672,27 +642,7 @@ mod tests {
     #[test]
     #[serial]
     fn test_podcast_episode_replacement_title() {
-        let settings = Setting {
-            id: uuid::Uuid::nil(),
-            episode_numbering: false,
-            auto_download: false,
-            auto_update: false,
-            auto_cleanup: false,
-            auto_cleanup_days: 0,
-            podcast_format: "{date}{title}".to_string(),
-            episode_format: "{date}{title}{guid}".to_string(),
-            replacement_strategy: "replace-with-dash".to_string(),
-            replace_invalid_characters: true,
-            use_existing_filename: false,
-            podcast_prefill: 0,
-            direct_paths: false,
-            auto_transcode_opus: false,
-            use_one_cover_for_all_episodes: false,
-            max_parallel_downloads: 3,
-            sponsorblock_enabled: true,
-            nfo_format: "off".to_string(),
-            cover_filename: "image".to_string(),
-        };
+        let settings = test_settings("{date}{title}", "{date}{title}{guid}", "replace-with-dash");
 
         let podcast_episode = PodcastEpisode {
             id: uuid::Uuid::nil().to_string(),
@@ -713,6 +663,11 @@ mod tests {
             episode_numbering_processed: false,
             download_location: None,
             youtube_video_id: None,
+            season: None,
+            episode_number: None,
+            episode_type: None,
+            loudness_lufs: None,
+            loudness_true_peak: None,
         };
 
         let result = perform_episode_variable_replacement(settings, podcast_episode, None, 1, None);
@@ -722,27 +677,7 @@ mod tests {
     #[test]
     #[serial]
     fn test_podcast_episode_replacement_old_format() {
-        let settings = Setting {
-            id: uuid::Uuid::nil(),
-            episode_numbering: false,
-            auto_download: false,
-            auto_update: false,
-            auto_cleanup: false,
-            auto_cleanup_days: 0,
-            podcast_format: "{date}{title}".to_string(),
-            episode_format: "{}".to_string(),
-            replacement_strategy: "replace-with-dash".to_string(),
-            replace_invalid_characters: true,
-            use_existing_filename: false,
-            podcast_prefill: 0,
-            direct_paths: false,
-            auto_transcode_opus: false,
-            use_one_cover_for_all_episodes: false,
-            max_parallel_downloads: 3,
-            sponsorblock_enabled: true,
-            nfo_format: "off".to_string(),
-            cover_filename: "image".to_string(),
-        };
+        let settings = test_settings("{date}{title}", "{}", "replace-with-dash");
 
         let podcast_episode = PodcastEpisode {
             id: uuid::Uuid::nil().to_string(),
@@ -763,6 +698,11 @@ mod tests {
             episode_numbering_processed: false,
             download_location: None,
             youtube_video_id: None,
+            season: None,
+            episode_number: None,
+            episode_type: None,
+            loudness_lufs: None,
+            loudness_true_peak: None,
         };
 
         let result = perform_episode_variable_replacement(settings, podcast_episode, None, 1, None);
@@ -772,27 +712,11 @@ mod tests {
     #[test]
     #[serial]
     fn episode_format_supports_episode_number_token() {
-        let settings = Setting {
-            id: uuid::Uuid::nil(),
-            episode_numbering: false,
-            auto_download: false,
-            auto_update: false,
-            auto_cleanup: false,
-            auto_cleanup_days: 0,
-            podcast_format: "{date}{title}".to_string(),
-            episode_format: "{episodeNumber:0>3} - {episodeTitle}".to_string(),
-            replacement_strategy: "replace-with-dash".to_string(),
-            replace_invalid_characters: true,
-            use_existing_filename: false,
-            podcast_prefill: 0,
-            direct_paths: false,
-            auto_transcode_opus: false,
-            use_one_cover_for_all_episodes: false,
-            max_parallel_downloads: 3,
-            sponsorblock_enabled: true,
-            nfo_format: "off".to_string(),
-            cover_filename: "image".to_string(),
-        };
+        let settings = test_settings(
+            "{date}{title}",
+            "{episodeNumber:0>3} - {episodeTitle}",
+            "replace-with-dash",
+        );
 
         let podcast_episode = PodcastEpisode {
             id: uuid::Uuid::nil().to_string(),
@@ -813,6 +737,11 @@ mod tests {
             episode_numbering_processed: false,
             download_location: None,
             youtube_video_id: None,
+            season: None,
+            episode_number: None,
+            episode_type: None,
+            loudness_lufs: None,
+            loudness_true_peak: None,
         };
 
         let result = perform_episode_variable_replacement(settings, podcast_episode, None, 7, None);
@@ -821,28 +750,36 @@ mod tests {
 
     #[test]
     #[serial]
-    pub fn perform_podcast_variable_replacement_date_title() {
+    pub fn perform_episode_variable_replacement_season_and_episode() {
         let settings = Setting {
-            id: uuid::Uuid::nil(),
-            episode_numbering: false,
-            auto_download: false,
-            auto_update: false,
-            auto_cleanup: false,
-            auto_cleanup_days: 0,
-            podcast_format: "{date}-{title}".to_string(),
-            episode_format: "{date}{}".to_string(),
             replacement_strategy: "replace-with-dash".to_string(),
-            replace_invalid_characters: true,
-            use_existing_filename: false,
-            podcast_prefill: 0,
-            direct_paths: false,
-            auto_transcode_opus: false,
-            use_one_cover_for_all_episodes: false,
-            max_parallel_downloads: 3,
-            sponsorblock_enabled: true,
-            nfo_format: "off".to_string(),
-            cover_filename: "image".to_string(),
+            episode_format: "S{season:0>2}E{episode:0>2} - {title}".to_string(),
+            ..Default::default()
+        };
+
+        let podcast_episode = PodcastEpisode {
+            name: "Hello".to_string(),
+            season: Some(3),
+            episode_number: Some(4),
+            ..Default::default()
         };
+        let result =
+            perform_episode_variable_replacement(settings.clone(), podcast_episode, None, 9, None);
+        assert_eq!(result.unwrap(), "S03E04 - Hello");
+
+        let podcast_episode = PodcastEpisode {
+            name: "Hello".to_string(),
+            season: Some(1),
+            ..Default::default()
+        };
+        let result = perform_episode_variable_replacement(settings, podcast_episode, None, 9, None);
+        assert_eq!(result.unwrap(), "S01E09 - Hello");
+    }
+
+    #[test]
+    #[serial]
+    pub fn perform_podcast_variable_replacement_date_title() {
+        let settings = test_settings("{date}-{title}", "{date}{}", "replace-with-dash");
 
         let podcast_episode = PodcastParsed {
             title: "Test".to_string(),
@@ -859,27 +796,7 @@ mod tests {
     #[test]
     #[serial]
     pub fn perform_podcast_variable_replacement_old_format() {
-        let settings = Setting {
-            id: uuid::Uuid::nil(),
-            episode_numbering: false,
-            auto_download: false,
-            auto_update: false,
-            auto_cleanup: false,
-            auto_cleanup_days: 0,
-            podcast_format: "{}".to_string(),
-            episode_format: "{date}{title}".to_string(),
-            replacement_strategy: "replace-with-dash".to_string(),
-            replace_invalid_characters: true,
-            use_existing_filename: false,
-            podcast_prefill: 0,
-            direct_paths: false,
-            auto_transcode_opus: false,
-            use_one_cover_for_all_episodes: false,
-            max_parallel_downloads: 3,
-            sponsorblock_enabled: true,
-            nfo_format: "off".to_string(),
-            cover_filename: "image".to_string(),
-        };
+        let settings = test_settings("{}", "{date}{title}", "replace-with-dash");
 
         let podcast_episode = PodcastParsed {
             title: "Test".to_string(),
@@ -1,64 +1,139 @@
+use crate::services::api_token::service::ApiTokenService;
+use crate::services::two_factor::service::TwoFactorService;
 use crate::services::user_auth::service::UserAuthService;
 use crate::sys::{LoginApplicationService, LoginDecision};
 use common_infrastructure::config::EnvironmentService;
 use common_infrastructure::error::CustomError;
+use podfetch_domain::api_token::ApiTokenScope;
+use podfetch_domain::user::User;
 use sha256::digest;
 use std::sync::Arc;
 
+/// Name and lifetime of the api token handed out after a two-factor login.
+const LOGIN_TOKEN_NAME: &str = "Web login";
+const LOGIN_TOKEN_LIFETIME_DAYS: i64 = 30;
+/// Login tokens a user may hold at once, one per browser they logged in
+/// with. A further login revokes the oldest.
+const MAX_LOGIN_TOKENS: usize = 10;
+
 #[derive(Clone)]
 pub struct LoginService {
     environment: Arc<EnvironmentService>,
     user_auth_service: Arc<UserAuthService>,
+    two_factor_service: Arc<TwoFactorService>,
+    api_token_service: Arc<ApiTokenService>,
 }
 
 impl LoginService {
     pub fn new(
         environment: Arc<EnvironmentService>,
         user_auth_service: Arc<UserAuthService>,
+        two_factor_service: Arc<TwoFactorService>,
+        api_token_service: Arc<ApiTokenService>,
     ) -> Self {
         Self {
             environment,
             user_auth_service,
+            two_factor_service,
+            api_token_service,
+        }
+    }
+
+    fn verify_second_factor(
+        &self,
+        user: &User,
+        totp_code: Option<&str>,
+    ) -> Result<LoginDecision, CustomError> {
+        if !self.two_factor_service.is_enabled(user)? {
+            return Ok(LoginDecision::Authenticated);
+        }
+        let Some(code) = totp_code.filter(|code| !code.trim().is_empty()) else {
+            return Ok(LoginDecision::SecondFactorRequired);
+        };
+        if !self.two_factor_service.verify(user, code)? {
+            return Ok(LoginDecision::Forbidden);
+        }
+
+        let expires_at =
+            chrono::Utc::now().naive_utc() + chrono::Duration::days(LOGIN_TOKEN_LIFETIME_DAYS);
+        let (_, secret) = self.api_token_service.create(
+            user,
+            LOGIN_TOKEN_NAME,
+            vec![ApiTokenScope::Admin],
+            Some(expires_at),
+        )?;
+        self.revoke_surplus_login_tokens(user)?;
+        Ok(LoginDecision::AuthenticatedWithToken(secret))
+    }
+
+    fn revoke_surplus_login_tokens(&self, user: &User) -> Result<(), CustomError> {
+        // Listed oldest first.
+        let login_tokens: Vec<_> = self
+            .api_token_service
+            .list(user)?
+            .into_iter()
+            .filter(|token| token.name == LOGIN_TOKEN_NAME)
+            .collect();
+        let surplus = login_tokens.len().saturating_sub(MAX_LOGIN_TOKENS);
+        for token in &login_tokens[..surplus] {
+            self.api_token_service.delete(user, token.id)?;
         }
+        Ok(())
+    }
+
+    /// Ends the session of the login token `secret`. Other tokens of the
+    /// user are left alone, as they were handed out on purpose.
+    pub fn logout(&self, user: &User, secret: &str) -> Result<(), CustomError> {
+        let Some(token) = self.api_token_service.find_by_secret(secret)? else {
+            return Ok(());
+        };
+        if token.user_id == user.id && token.name == LOGIN_TOKEN_NAME {
+            self.api_token_service.delete(user, token.id)?;
+        }
+        Ok(())
     }
 }
 
 impl LoginApplicationService for LoginService {
     type Error = CustomError;
 
-    fn verify_login(&self, username: &str, password: &str) -> Result<LoginDecision, Self::Error> {
+    fn verify_login(
+        &self,
+        username: &str,
+        password: &str,
+        totp_code: Option<&str>,
+    ) -> Result<LoginDecision, Self::Error> {
         let digested_password = digest(password);
 
-        if let Some(admin_username) = &self.environment.username
-            && admin_username == username
-            && let Some(admin_password) = &self.environment.password
-            && admin_password == &digested_password
-        {
-            return Ok(LoginDecision::Authenticated);
-        }
+        let is_configured_admin = self
+            .environment
+            .username
+            .as_ref()
+            .is_some_and(|admin_username| admin_username == username)
+            && self
+                .environment
+                .password
+                .as_ref()
+                .is_some_and(|admin_password| admin_password == &digested_password);
 
-        match self.user_auth_service.find_by_username(username) {
-            Ok(user) => {
-                if let Some(stored_password) = user.password {
-                    if stored_password == digested_password {
-                        Ok(LoginDecision::Authenticated)
-                    } else {
-                        Ok(LoginDecision::Forbidden)
-                    }
-                } else {
-                    Ok(LoginDecision::Forbidden)
-                }
-            }
+        let user = match self.user_auth_service.find_by_username(username) {
+            Ok(user) => user,
             Err(err) => {
-                if matches!(
+                return if matches!(
                     err.inner,
                     common_infrastructure::error::CustomErrorInner::NotFound(_)
                 ) {
                     Ok(LoginDecision::WrongUserOrPassword)
                 } else {
                     Err(err)
-                }
+                };
             }
+        };
+
+        if !is_configured_admin && user.password.as_deref() != Some(digested_password.as_str()) {
+            return Ok(LoginDecision::Forbidden);
         }
+
+        self.verify_second_factor(&user, totp_code)
     }
 }
@@ -0,0 +1,182 @@
+//! Loudness analysis of downloaded episodes.
+//!
+//! With `loudness_analysis` on, every local download is measured with
+//! ffmpeg's `loudnorm` filter (EBU R128) once it has reached its final format.
+//! The integrated loudness and true peak are stored on the episode, written
+//! into MP3 and M4A files as ReplayGain tags and exposed as `replay_gain_db`
+//! so players, cast sessions and Mopidy can level episodes out.
+
+use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
+use serde::Deserialize;
+
+/// Loudness ReplayGain 2.0 levels tracks to.
+pub const REPLAYGAIN_REFERENCE_LUFS: f64 = -18.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Loudness {
+    /// Integrated loudness in LUFS.
+    pub integrated_lufs: f64,
+    /// True peak in dBTP.
+    pub true_peak_dbtp: f64,
+}
+
+/// The summary `loudnorm=print_format=json` prints at the end of its run.
+/// ffmpeg writes the numbers as strings, `-inf` for silent input.
+#[derive(Deserialize)]
+struct LoudnormReport {
+    input_i: String,
+    input_tp: String,
+}
+
+/// Measures the audio file at `path`. Takes about as long as decoding it.
+pub fn measure(path: &str) -> Result<Loudness, CustomError> {
+    let output = std::process::Command::new("ffmpeg")
+        .args([
+            "-hide_banner",
+            "-nostats",
+            "-i",
+            path,
+            "-map",
+            "0:a:0",
+            "-af",
+            "loudnorm=print_format=json",
+            "-f",
+            "null",
+            "-",
+        ])
+        .output()
+        .map_err(|e| conflict(format!("Failed to run ffmpeg: {e}")))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(conflict(format!(
+            "ffmpeg loudness analysis failed: {stderr}"
+        )));
+    }
+    parse_loudnorm_report(&stderr)
+        .ok_or_else(|| conflict(format!("No loudness could be measured for {path}")))
+}
+
+/// Measures the downloaded file of `episode` and stores the result. A failed
+/// measurement only costs the gain, so it is logged instead of failing the
+/// download.
+pub fn analyze_download(episode: &PodcastEpisode, file_path: &str) -> Option<Loudness> {
+    let loudness = match measure(file_path) {
+        Ok(loudness) => loudness,
+        Err(e) => {
+            tracing::warn!("Could not measure the loudness of {file_path}: {e}");
+            return None;
+        }
+    };
+    if let Err(e) = PodcastEpisodeService::update_loudness(
+        &episode.episode_id,
+        Some(loudness.integrated_lufs),
+        Some(loudness.true_peak_dbtp),
+    ) {
+        tracing::error!("Could not store the loudness of {}: {e}", episode.name);
+        return None;
+    }
+    tracing::info!(
+        "Measured {:.1} LUFS for {}",
+        loudness.integrated_lufs,
+        episode.name
+    );
+    Some(loudness)
+}
+
+fn parse_loudnorm_report(stderr: &str) -> Option<Loudness> {
+    let start = stderr.rfind('{')?;
+    let end = start + stderr[start..].find('}')?;
+    let report: LoudnormReport = serde_json::from_str(&stderr[start..=end]).ok()?;
+    let integrated_lufs = report.input_i.trim().parse::<f64>().ok()?;
+    let true_peak_dbtp = report.input_tp.trim().parse::<f64>().ok()?;
+    (integrated_lufs.is_finite() && true_peak_dbtp.is_finite()).then_some(Loudness {
+        integrated_lufs,
+        true_peak_dbtp,
+    })
+}
+
+/// Gain in dB that brings a track of `loudness_lufs` to the ReplayGain
+/// reference.
+pub fn replay_gain_db(loudness_lufs: f64) -> f64 {
+    REPLAYGAIN_REFERENCE_LUFS - loudness_lufs
+}
+
+/// ReplayGain track peak, the true peak as a linear sample value.
+pub fn replay_gain_peak(true_peak_dbtp: f64) -> f64 {
+    10f64.powf(true_peak_dbtp / 20.0)
+}
+
+/// Volume factor for players that can only attenuate: quiet episodes play at
+/// full volume, loud ones are turned down by their gain.
+pub fn playback_volume(gain_db: f64) -> f64 {
+    10f64.powf(gain_db / 20.0).clamp(0.0, 1.0)
+}
+
+/// `-6.50 dB`, the notation of ReplayGain gain tags.
+pub fn format_replay_gain(gain_db: f64) -> String {
+    format!("{gain_db:.2} dB")
+}
+
+fn conflict(message: String) -> CustomError {
+    CustomErrorInner::Conflict(message, Warning).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOUDNORM_OUTPUT: &str = r#"Input #0, mp3, from 'episode.mp3':
+  Duration: 00:42:00.05, start: 0.025057, bitrate: 128 kb/s
+[Parsed_loudnorm_0 @ 0x5581d8a1c5c0]
+{
+	"input_i" : "-14.52",
+	"input_tp" : "-0.31",
+	"input_lra" : "5.10",
+	"input_thresh" : "-24.73",
+	"output_i" : "-24.11",
+	"output_tp" : "-9.63",
+	"output_lra" : "4.20",
+	"output_thresh" : "-34.28",
+	"normalization_type" : "dynamic",
+	"target_offset" : "0.11"
+}
+"#;
+
+    #[test]
+    fn the_loudnorm_summary_is_parsed() {
+        assert_eq!(
+            parse_loudnorm_report(LOUDNORM_OUTPUT),
+            Some(Loudness {
+                integrated_lufs: -14.52,
+                true_peak_dbtp: -0.31,
+            })
+        );
+    }
+
+    #[test]
+    fn silent_input_has_no_loudness() {
+        let silent = LOUDNORM_OUTPUT
+            .replace("\"-14.52\"", "\"-inf\"")
+            .replace("\"-0.31\"", "\"-inf\"");
+        assert_eq!(parse_loudnorm_report(&silent), None);
+        assert_eq!(parse_loudnorm_report("Invalid data found"), None);
+    }
+
+    #[test]
+    fn gain_levels_to_the_replaygain_reference() {
+        assert_eq!(replay_gain_db(-14.5), -3.5);
+        assert_eq!(replay_gain_db(-23.0), 5.0);
+        assert_eq!(format_replay_gain(replay_gain_db(-14.52)), "-3.48 dB");
+        assert!((replay_gain_peak(-6.0) - 0.501).abs() < 0.001);
+    }
+
+    #[test]
+    fn playback_volume_only_attenuates() {
+        assert!((playback_volume(-6.0) - 0.501).abs() < 0.001);
+        assert_eq!(playback_volume(5.0), 1.0);
+        assert_eq!(playback_volume(0.0), 1.0);
+    }
+}
@@ -1,5 +1,8 @@
 pub mod agent;
+pub mod api_token;
 pub mod audiobookshelf;
+pub mod audit_log;
+pub mod backup;
 pub mod cast;
 pub mod device;
 pub mod device_sync_group;
@@ -9,26 +12,35 @@ pub mod episode_rescan;
 pub mod episode_scan;
 pub mod episode_triage;
 pub mod favorite_podcast_episode;
+pub mod feed_health;
 pub mod file;
 pub mod filter;
 pub mod gpodder_setting;
 pub mod invite;
 pub mod listening_event;
 pub mod login;
+pub mod loudness;
 pub mod mopidy;
 pub mod nfo;
 pub mod notification;
+pub mod notification_channel;
 pub mod playlist;
 pub mod podcast;
 pub mod podcast_episode_chapter;
 pub mod podcast_settings;
+pub mod retention;
+pub mod search;
 pub mod session;
 pub mod settings;
+pub mod skip_detection;
 pub mod sponsorblock;
 pub mod stats;
+pub mod storage_quota;
 pub mod subscription;
 pub mod tag;
+pub mod transcoding;
 pub mod transcript;
+pub mod two_factor;
 pub mod user_admin;
 pub mod user_auth;
 pub mod user_onboarding;
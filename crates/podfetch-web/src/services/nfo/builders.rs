@@ -80,7 +80,8 @@ pub fn build_tvshow_nfo(podcast: &Podcast) -> String {
     finish(w)
 }
 
-/// Per-episode `<basename>.nfo`.
+/// Per-episode `<basename>.nfo`. Season and episode come from the feed's
+/// tags when it has them, otherwise season 1 and the date `position`.
 pub fn build_episodedetails_nfo(
     podcast: &Podcast,
     episode: &PodcastEpisode,
@@ -92,8 +93,21 @@ pub fn build_episodedetails_nfo(
         .expect("start episodedetails");
     write_text_el(&mut w, "title", Some(&episode.name));
     write_text_el(&mut w, "showtitle", Some(&podcast.name));
-    write_text_el(&mut w, "season", Some("1"));
-    write_text_el(&mut w, "episode", Some(&position.to_string()));
+    write_text_el(
+        &mut w,
+        "season",
+        Some(&episode.season.unwrap_or(1).to_string()),
+    );
+    write_text_el(
+        &mut w,
+        "episode",
+        Some(
+            &episode
+                .episode_number
+                .map_or(position, i64::from)
+                .to_string(),
+        ),
+    );
     write_text_el(&mut w, "plot", Some(&episode.description));
     write_text_el(
         &mut w,
@@ -211,6 +225,16 @@ mod tests {
         assert!(xml.contains("<uniqueid type=\"podfetch\">episode-guid-1</uniqueid>"));
     }
 
+    #[test]
+    fn episodedetails_prefers_feed_season_and_episode() {
+        let mut e = episode();
+        e.season = Some(4);
+        e.episode_number = Some(12);
+        let xml = build_episodedetails_nfo(&podcast(), &e, 7);
+        assert!(xml.contains("<season>4</season>"));
+        assert!(xml.contains("<episode>12</episode>"));
+    }
+
     #[test]
     fn episodedetails_omits_actor_without_author() {
         let p = Podcast {
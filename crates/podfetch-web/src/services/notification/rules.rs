@@ -0,0 +1,330 @@
+use crate::services::podcast::service::PodcastService;
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::favorite::FavoriteRepository;
+use podfetch_domain::notification_rule::{NotificationRule, NotificationRuleRepository};
+use podfetch_domain::subscription::SubscriptionRepository;
+use podfetch_domain::tag::TagRepository;
+use podfetch_persistence::adapters::{
+    NotificationRuleRepositoryImpl, SubscriptionRepositoryImpl, TagRepositoryImpl,
+};
+use podfetch_persistence::db::{PersistenceError, database};
+use podfetch_persistence::favorite::DieselFavoriteRepository;
+use regex::{Regex, RegexBuilder};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Compiled size limit for title patterns, so a user can't make every
+/// download pay for a huge automaton.
+const TITLE_REGEX_SIZE_LIMIT: usize = 1 << 16;
+
+/// The conditions of a rule as submitted by its owner.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationRuleInput {
+    pub podcast_id: Option<Uuid>,
+    pub tag_id: Option<String>,
+    pub min_duration_seconds: Option<i32>,
+    pub max_duration_seconds: Option<i32>,
+    pub title_regex: Option<String>,
+}
+
+/// What a rule is checked against.
+pub struct EpisodeFacts<'a> {
+    pub podcast_id: Uuid,
+    pub title: &'a str,
+    /// Length in seconds; zero or less when the feed didn't say.
+    pub duration_seconds: i32,
+}
+
+#[derive(Clone)]
+pub struct NotificationRuleService {
+    repository: Arc<dyn NotificationRuleRepository<Error = CustomError>>,
+    tag_repository: Arc<dyn TagRepository<Error = CustomError>>,
+    favorite_repository: Arc<dyn FavoriteRepository<Error = PersistenceError>>,
+    subscription_repository: Arc<dyn SubscriptionRepository<Error = CustomError>>,
+}
+
+impl NotificationRuleService {
+    pub fn new(
+        repository: Arc<dyn NotificationRuleRepository<Error = CustomError>>,
+        tag_repository: Arc<dyn TagRepository<Error = CustomError>>,
+        favorite_repository: Arc<dyn FavoriteRepository<Error = PersistenceError>>,
+        subscription_repository: Arc<dyn SubscriptionRepository<Error = CustomError>>,
+    ) -> Self {
+        Self {
+            repository,
+            tag_repository,
+            favorite_repository,
+            subscription_repository,
+        }
+    }
+
+    pub fn default_service() -> Self {
+        let database = database();
+        Self::new(
+            Arc::new(NotificationRuleRepositoryImpl::new(database.clone())),
+            Arc::new(TagRepositoryImpl::new(database.clone())),
+            Arc::new(DieselFavoriteRepository::new(database.clone())),
+            Arc::new(SubscriptionRepositoryImpl::new(database)),
+        )
+    }
+
+    pub fn list(&self, user_id: Uuid) -> Result<Vec<NotificationRule>, CustomError> {
+        self.repository.list_by_user(user_id)
+    }
+
+    pub fn create(
+        &self,
+        user_id: Uuid,
+        input: NotificationRuleInput,
+    ) -> Result<NotificationRule, CustomError> {
+        self.validate(user_id, &input)?;
+        self.repository.create(NotificationRule {
+            id: Uuid::new_v4(),
+            user_id,
+            podcast_id: input.podcast_id,
+            tag_id: input.tag_id,
+            min_duration_seconds: input.min_duration_seconds,
+            max_duration_seconds: input.max_duration_seconds,
+            title_regex: input.title_regex,
+            created_at: chrono::Utc::now().naive_utc(),
+        })
+    }
+
+    pub fn update(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        input: NotificationRuleInput,
+    ) -> Result<NotificationRule, CustomError> {
+        self.validate(user_id, &input)?;
+        let existing = self
+            .repository
+            .get(id, user_id)?
+            .ok_or_else(|| CustomError::from(CustomErrorInner::NotFound(Warning)))?;
+        self.repository
+            .update(NotificationRule {
+                podcast_id: input.podcast_id,
+                tag_id: input.tag_id,
+                min_duration_seconds: input.min_duration_seconds,
+                max_duration_seconds: input.max_duration_seconds,
+                title_regex: input.title_regex,
+                ..existing
+            })?
+            .ok_or_else(|| CustomErrorInner::NotFound(Warning).into())
+    }
+
+    pub fn delete(&self, user_id: Uuid, id: Uuid) -> Result<(), CustomError> {
+        if !self.repository.delete(id, user_id)? {
+            return Err(CustomErrorInner::NotFound(Warning).into());
+        }
+        Ok(())
+    }
+
+    /// Users who follow the podcast, either as a favorite or through a
+    /// gPodder subscription of its feed.
+    pub fn subscribers(&self, podcast_id: Uuid) -> Result<Vec<Uuid>, CustomError> {
+        let mut subscribers: BTreeSet<Uuid> = self
+            .favorite_repository
+            .find_user_ids_favoring(podcast_id)
+            .map_err(CustomError::from)?
+            .into_iter()
+            .collect();
+        subscribers.extend(
+            self.subscription_repository
+                .find_subscriber_ids(podcast_id)?,
+        );
+        Ok(subscribers.into_iter().collect())
+    }
+
+    /// Users to notify about a new episode: the subscribers of its podcast
+    /// without rules, plus those with at least one matching rule.
+    pub fn recipients(&self, episode: &EpisodeFacts) -> Result<Vec<Uuid>, CustomError> {
+        let mut recipients = Vec::new();
+        for user_id in self.subscribers(episode.podcast_id)? {
+            let rules = self.repository.list_by_user(user_id)?;
+            if rules.is_empty() || self.any_rule_matches(user_id, &rules, episode)? {
+                recipients.push(user_id);
+            }
+        }
+        Ok(recipients)
+    }
+
+    fn any_rule_matches(
+        &self,
+        user_id: Uuid,
+        rules: &[NotificationRule],
+        episode: &EpisodeFacts,
+    ) -> Result<bool, CustomError> {
+        let mut podcast_tags: Option<Vec<String>> = None;
+        for rule in rules {
+            if let Some(tag_id) = &rule.tag_id {
+                if podcast_tags.is_none() {
+                    podcast_tags = Some(
+                        self.tag_repository
+                            .get_tags_of_podcast(episode.podcast_id, user_id)?
+                            .into_iter()
+                            .map(|tag| tag.id)
+                            .collect(),
+                    );
+                }
+                if !podcast_tags
+                    .as_ref()
+                    .is_some_and(|tags| tags.contains(tag_id))
+                {
+                    continue;
+                }
+            }
+            if rule_matches(rule, episode) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn validate(&self, user_id: Uuid, input: &NotificationRuleInput) -> Result<(), CustomError> {
+        if input.podcast_id.is_some() && input.tag_id.is_some() {
+            return Err(bad_request(
+                "a rule applies to either a podcast or a tag, not both",
+            ));
+        }
+        if let Some(podcast_id) = input.podcast_id {
+            PodcastService::get_podcast(podcast_id)
+                .map_err(|_| bad_request("the podcast of the rule does not exist"))?;
+        }
+        if let Some(tag_id) = &input.tag_id
+            && self
+                .tag_repository
+                .get_tag_by_id_and_user_id(tag_id, user_id)?
+                .is_none()
+        {
+            return Err(bad_request("the tag of the rule does not exist"));
+        }
+        if input.min_duration_seconds.is_some_and(|min| min < 0)
+            || input.max_duration_seconds.is_some_and(|max| max < 0)
+        {
+            return Err(bad_request("durations can't be negative"));
+        }
+        if let (Some(min), Some(max)) = (input.min_duration_seconds, input.max_duration_seconds)
+            && min > max
+        {
+            return Err(bad_request(
+                "the minimum duration is longer than the maximum",
+            ));
+        }
+        if let Some(pattern) = &input.title_regex {
+            compile_title_regex(pattern)
+                .map_err(|err| bad_request(&format!("invalid title pattern: {err}")))?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the scope and conditions of a rule, apart from tag membership
+/// which needs a lookup. Episodes without a known duration never satisfy a
+/// duration condition.
+fn rule_matches(rule: &NotificationRule, episode: &EpisodeFacts) -> bool {
+    if rule
+        .podcast_id
+        .is_some_and(|podcast_id| podcast_id != episode.podcast_id)
+    {
+        return false;
+    }
+    let known_duration = (episode.duration_seconds > 0).then_some(episode.duration_seconds);
+    if let Some(min) = rule.min_duration_seconds
+        && known_duration.is_none_or(|duration| duration < min)
+    {
+        return false;
+    }
+    if let Some(max) = rule.max_duration_seconds
+        && known_duration.is_none_or(|duration| duration > max)
+    {
+        return false;
+    }
+    match &rule.title_regex {
+        Some(pattern) => compile_title_regex(pattern)
+            .map(|regex| regex.is_match(episode.title))
+            .unwrap_or(false),
+        None => true,
+    }
+}
+
+fn compile_title_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .size_limit(TITLE_REGEX_SIZE_LIMIT)
+        .build()
+}
+
+fn bad_request(message: &str) -> CustomError {
+    CustomErrorInner::BadRequest(message.to_string(), Warning).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule() -> NotificationRule {
+        NotificationRule {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            podcast_id: None,
+            tag_id: None,
+            min_duration_seconds: None,
+            max_duration_seconds: None,
+            title_regex: None,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    fn episode(podcast_id: Uuid, title: &str, duration_seconds: i32) -> EpisodeFacts<'_> {
+        EpisodeFacts {
+            podcast_id,
+            title,
+            duration_seconds,
+        }
+    }
+
+    #[test]
+    fn rule_checks_podcast_duration_and_title() {
+        let podcast = Uuid::new_v4();
+        let long_interviews = NotificationRule {
+            podcast_id: Some(podcast),
+            min_duration_seconds: Some(20 * 60),
+            title_regex: Some("(?i)interview".to_string()),
+            ..rule()
+        };
+
+        assert!(rule_matches(
+            &long_interviews,
+            &episode(podcast, "An Interview with Ada", 45 * 60)
+        ));
+        assert!(!rule_matches(
+            &long_interviews,
+            &episode(podcast, "An Interview with Ada", 10 * 60)
+        ));
+        assert!(!rule_matches(
+            &long_interviews,
+            &episode(podcast, "News roundup", 45 * 60)
+        ));
+        assert!(!rule_matches(
+            &long_interviews,
+            &episode(Uuid::new_v4(), "Interview", 45 * 60)
+        ));
+        assert!(
+            !rule_matches(&long_interviews, &episode(podcast, "Interview", 0)),
+            "unknown durations don't satisfy a duration condition"
+        );
+        assert!(rule_matches(&rule(), &episode(podcast, "Anything", 0)));
+    }
+
+    #[test]
+    fn max_duration_is_inclusive() {
+        let short = NotificationRule {
+            max_duration_seconds: Some(600),
+            ..rule()
+        };
+        assert!(rule_matches(&short, &episode(Uuid::new_v4(), "Short", 600)));
+        assert!(!rule_matches(&short, &episode(Uuid::new_v4(), "Long", 601)));
+    }
+}
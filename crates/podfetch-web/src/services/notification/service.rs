@@ -27,9 +27,12 @@ impl NotificationService {
         self.repository.create(notification.into()).map(Into::into)
     }
 
-    pub fn get_unread_notifications(&self) -> Result<Vec<Notification>, CustomError> {
+    pub fn get_unread_notifications(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<Notification>, CustomError> {
         self.repository
-            .get_unread_notifications()
+            .get_unread_notifications(user_id)
             .map(|notifications| notifications.into_iter().map(Into::into).collect())
     }
 
@@ -45,11 +48,14 @@ impl NotificationService {
 impl NotificationApplicationService for NotificationService {
     type Error = CustomError;
 
-    fn get_unread_notifications(&self) -> Result<Vec<Notification>, Self::Error> {
-        self.get_unread_notifications()
+    fn get_unread_notifications(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<Notification>, Self::Error> {
+        self.get_unread_notifications(user_id)
     }
 
-    fn dismiss_notification(&self, id: uuid::Uuid) -> Result<(), Self::Error> {
-        self.update_status_of_notification(id, "dismissed")
+    fn dismiss_notification(&self, id: uuid::Uuid, user_id: uuid::Uuid) -> Result<(), Self::Error> {
+        self.repository.dismiss_notification(id, user_id)
     }
 }
@@ -0,0 +1,172 @@
+use crate::services::notification_channel::gotify::GotifyChannel;
+use crate::services::notification_channel::ntfy::NtfyChannel;
+use crate::services::notification_channel::smtp::SmtpChannel;
+use crate::services::notification_channel::webhook::WebhookChannel;
+use common_infrastructure::error::{CustomError, CustomErrorInner, ErrorSeverity};
+use podfetch_domain::notification_channel::{NotificationChannelKind, NotificationEventType};
+use serde::de::DeserializeOwned;
+
+/// Something worth telling the user about, independent of how it is
+/// delivered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationEvent {
+    pub event_type: NotificationEventType,
+    pub podcast: String,
+    pub episode: Option<String>,
+    pub error: Option<String>,
+}
+
+impl NotificationEvent {
+    pub fn new_episode(podcast: &str, episode: &str) -> Self {
+        Self {
+            event_type: NotificationEventType::NewEpisode,
+            podcast: podcast.to_string(),
+            episode: Some(episode.to_string()),
+            error: None,
+        }
+    }
+
+    pub fn download_failed(podcast: &str, episode: &str, error: &str) -> Self {
+        Self {
+            event_type: NotificationEventType::DownloadFailed,
+            podcast: podcast.to_string(),
+            episode: Some(episode.to_string()),
+            error: Some(error.to_string()),
+        }
+    }
+
+    pub fn transcription_done(podcast: &str, episode: &str) -> Self {
+        Self {
+            event_type: NotificationEventType::TranscriptionDone,
+            podcast: podcast.to_string(),
+            episode: Some(episode.to_string()),
+            error: None,
+        }
+    }
+
+    pub fn feed_broken(podcast: &str, error: &str) -> Self {
+        Self {
+            event_type: NotificationEventType::FeedBroken,
+            podcast: podcast.to_string(),
+            episode: None,
+            error: Some(error.to_string()),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self.event_type {
+            NotificationEventType::NewEpisode => "New episode available".to_string(),
+            NotificationEventType::DownloadFailed => "Download failed".to_string(),
+            NotificationEventType::TranscriptionDone => "Transcript ready".to_string(),
+            NotificationEventType::FeedBroken => format!("Feed of {} is broken", self.podcast),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        let episode = self.episode.as_deref().unwrap_or_default();
+        let error = self.error.as_deref().unwrap_or_default();
+        match self.event_type {
+            NotificationEventType::NewEpisode => format!(
+                "Episode {episode} of podcast {} was downloaded successfully and is ready to be listened to.",
+                self.podcast
+            ),
+            NotificationEventType::DownloadFailed => format!(
+                "Episode {episode} of podcast {} could not be downloaded: {error}",
+                self.podcast
+            ),
+            NotificationEventType::TranscriptionDone => format!(
+                "The transcript of episode {episode} of podcast {} is ready.",
+                self.podcast
+            ),
+            NotificationEventType::FeedBroken => format!(
+                "The feed of podcast {} could not be refreshed: {error}",
+                self.podcast
+            ),
+        }
+    }
+}
+
+/// A way of delivering notifications. Implementations block until the
+/// message was handed over.
+pub trait Channel: Send + Sync {
+    fn send(&self, event: &NotificationEvent) -> Result<(), CustomError>;
+}
+
+/// Builds the channel of the given kind from its JSON configuration. Fails
+/// with a bad request when the configuration doesn't fit the kind.
+pub fn build_channel(
+    kind: NotificationChannelKind,
+    config: &str,
+) -> Result<Box<dyn Channel>, CustomError> {
+    Ok(match kind {
+        NotificationChannelKind::Webhook => Box::new(WebhookChannel::new(parse_config(config)?)?),
+        NotificationChannelKind::Ntfy => Box::new(NtfyChannel::new(parse_config(config)?)?),
+        NotificationChannelKind::Gotify => Box::new(GotifyChannel::new(parse_config(config)?)?),
+        NotificationChannelKind::Smtp => Box::new(SmtpChannel::new(parse_config(config)?)?),
+    })
+}
+
+fn parse_config<T: DeserializeOwned>(config: &str) -> Result<T, CustomError> {
+    serde_json::from_str(config).map_err(|err| invalid_config(err.to_string()))
+}
+
+pub(crate) fn invalid_config(reason: impl Into<String>) -> CustomError {
+    CustomErrorInner::BadRequest(
+        format!(
+            "Invalid notification channel configuration: {}",
+            reason.into()
+        ),
+        ErrorSeverity::Warning,
+    )
+    .into()
+}
+
+pub(crate) fn delivery_failed(reason: impl std::fmt::Display) -> CustomError {
+    CustomErrorInner::BadRequest(
+        format!("Could not deliver notification: {reason}"),
+        ErrorSeverity::Warning,
+    )
+    .into()
+}
+
+/// Parses `url` and makes sure it is an http(s) URL.
+pub(crate) fn parse_http_url(url: &str) -> Result<url::Url, CustomError> {
+    let parsed = url::Url::parse(url).map_err(|err| invalid_config(format!("{url}: {err}")))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid_config(format!("{url} is not an http(s) URL")));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_name_podcast_episode_and_error() {
+        let event = NotificationEvent::download_failed("Tech Talk", "Episode 4", "HTTP 404");
+        assert_eq!(event.title(), "Download failed");
+        assert_eq!(
+            event.message(),
+            "Episode Episode 4 of podcast Tech Talk could not be downloaded: HTTP 404"
+        );
+        assert_eq!(
+            NotificationEvent::feed_broken("Tech Talk", "timeout").title(),
+            "Feed of Tech Talk is broken"
+        );
+    }
+
+    #[test]
+    fn build_channel_rejects_configs_that_do_not_fit_the_kind() {
+        assert!(build_channel(NotificationChannelKind::Webhook, "{}").is_err());
+        assert!(build_channel(NotificationChannelKind::Gotify, "not json").is_err());
+        assert!(
+            build_channel(
+                NotificationChannelKind::Webhook,
+                r#"{"url":"ftp://example.com"}"#
+            )
+            .is_err()
+        );
+        assert!(build_channel(NotificationChannelKind::Ntfy, r#"{"topic":"podfetch"}"#).is_ok());
+    }
+}
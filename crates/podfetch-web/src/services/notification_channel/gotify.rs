@@ -0,0 +1,68 @@
+//! Gotify: messages are posted to `/message` of the server with an
+//! application token.
+
+use crate::services::notification_channel::channel::{
+    Channel, NotificationEvent, delivery_failed, invalid_config, parse_http_url,
+};
+use common_infrastructure::error::CustomError;
+use common_infrastructure::http::get_sync_client;
+use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GotifyConfig {
+    pub server_url: String,
+    /// Application token created in Gotify.
+    pub token: String,
+    #[serde(default = "default_priority")]
+    pub priority: i32,
+}
+
+fn default_priority() -> i32 {
+    5
+}
+
+#[derive(Debug, Serialize)]
+struct GotifyMessage {
+    title: String,
+    message: String,
+    priority: i32,
+}
+
+pub struct GotifyChannel {
+    config: GotifyConfig,
+}
+
+impl GotifyChannel {
+    pub fn new(config: GotifyConfig) -> Result<Self, CustomError> {
+        parse_http_url(&config.server_url)?;
+        if config.token.trim().is_empty() {
+            return Err(invalid_config("Gotify needs an application token"));
+        }
+        Ok(Self { config })
+    }
+}
+
+impl Channel for GotifyChannel {
+    fn send(&self, event: &NotificationEvent) -> Result<(), CustomError> {
+        let client = get_sync_client(&ENVIRONMENT_SERVICE)
+            .build()
+            .map_err(delivery_failed)?;
+        client
+            .post(format!(
+                "{}/message",
+                self.config.server_url.trim_end_matches('/')
+            ))
+            .header("X-Gotify-Key", &self.config.token)
+            .json(&GotifyMessage {
+                title: event.title(),
+                message: event.message(),
+                priority: self.config.priority,
+            })
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(delivery_failed)
+    }
+}
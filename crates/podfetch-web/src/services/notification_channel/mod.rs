@@ -0,0 +1,6 @@
+pub mod channel;
+pub mod gotify;
+pub mod ntfy;
+pub mod service;
+pub mod smtp;
+pub mod webhook;
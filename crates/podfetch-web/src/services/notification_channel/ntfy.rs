@@ -0,0 +1,139 @@
+//! ntfy (https://ntfy.sh or a self-hosted server): the message is published
+//! to a topic, title and priority travel as headers.
+
+use crate::services::notification_channel::channel::{
+    Channel, NotificationEvent, delivery_failed, invalid_config, parse_http_url,
+};
+use common_infrastructure::error::CustomError;
+use common_infrastructure::http::get_sync_client;
+use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
+use podfetch_domain::notification_channel::NotificationEventType;
+use reqwest::header::AUTHORIZATION;
+use serde::Deserialize;
+
+const DEFAULT_SERVER_URL: &str = "https://ntfy.sh";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NtfyConfig {
+    #[serde(default = "default_server_url")]
+    pub server_url: String,
+    pub topic: String,
+    /// Access token for protected topics.
+    pub token: Option<String>,
+    /// 1 (min) to 5 (max). Failures are sent one level higher.
+    pub priority: Option<u8>,
+}
+
+fn default_server_url() -> String {
+    DEFAULT_SERVER_URL.to_string()
+}
+
+pub struct NtfyChannel {
+    config: NtfyConfig,
+}
+
+impl NtfyChannel {
+    pub fn new(config: NtfyConfig) -> Result<Self, CustomError> {
+        parse_http_url(&config.server_url)?;
+        if config.topic.trim().is_empty() || config.topic.contains('/') {
+            return Err(invalid_config("ntfy needs a topic without slashes"));
+        }
+        if config
+            .priority
+            .is_some_and(|priority| !(1..=5).contains(&priority))
+        {
+            return Err(invalid_config("ntfy priorities range from 1 to 5"));
+        }
+        Ok(Self { config })
+    }
+
+    fn topic_url(&self) -> String {
+        format!(
+            "{}/{}",
+            self.config.server_url.trim_end_matches('/'),
+            self.config.topic
+        )
+    }
+
+    fn priority(&self, event: &NotificationEvent) -> u8 {
+        let priority = self.config.priority.unwrap_or(3);
+        match event.event_type {
+            NotificationEventType::DownloadFailed | NotificationEventType::FeedBroken => {
+                (priority + 1).min(5)
+            }
+            _ => priority,
+        }
+    }
+}
+
+impl Channel for NtfyChannel {
+    fn send(&self, event: &NotificationEvent) -> Result<(), CustomError> {
+        let client = get_sync_client(&ENVIRONMENT_SERVICE)
+            .build()
+            .map_err(delivery_failed)?;
+        let mut request = client
+            .post(self.topic_url())
+            .header("Title", event.title())
+            .header("Priority", self.priority(event).to_string())
+            .header("Tags", event.event_type.as_str());
+        if let Some(token) = self.config.token.as_deref().filter(|t| !t.is_empty()) {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        request
+            .body(event.message())
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(delivery_failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(priority: Option<u8>) -> NtfyChannel {
+        NtfyChannel::new(NtfyConfig {
+            server_url: "https://ntfy.example.com/".to_string(),
+            topic: "podfetch".to_string(),
+            token: None,
+            priority,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn failures_are_sent_with_a_raised_priority() {
+        let channel = channel(Some(5));
+        assert_eq!(channel.topic_url(), "https://ntfy.example.com/podfetch");
+        assert_eq!(
+            channel.priority(&NotificationEvent::feed_broken("Podcast", "timeout")),
+            5
+        );
+        assert_eq!(
+            super::tests::channel(None)
+                .priority(&NotificationEvent::download_failed("Podcast", "Ep", "404")),
+            4
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_topics_and_priorities() {
+        let config = NtfyConfig {
+            server_url: default_server_url(),
+            topic: "a/b".to_string(),
+            token: None,
+            priority: None,
+        };
+        assert!(NtfyChannel::new(config.clone()).is_err());
+        assert!(
+            NtfyChannel::new(NtfyConfig {
+                topic: "podfetch".to_string(),
+                priority: Some(9),
+                ..config
+            })
+            .is_err()
+        );
+    }
+}
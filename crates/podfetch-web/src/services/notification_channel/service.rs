@@ -0,0 +1,381 @@
+use crate::services::notification_channel::channel::{NotificationEvent, build_channel};
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::notification_channel::{
+    NotificationChannel, NotificationChannelKind, NotificationChannelRepository,
+    NotificationEventType,
+};
+use podfetch_persistence::adapters::NotificationChannelRepositoryImpl;
+use podfetch_persistence::db::database;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// What an admin submits when creating or changing a channel.
+#[derive(Debug, Clone)]
+pub struct NotificationChannelInput {
+    pub name: String,
+    pub kind: NotificationChannelKind,
+    pub enabled: bool,
+    pub events: Vec<NotificationEventType>,
+    /// Kind specific JSON configuration.
+    pub config: String,
+}
+
+#[derive(Clone)]
+pub struct NotificationChannelService {
+    repository: Arc<dyn NotificationChannelRepository<Error = CustomError>>,
+}
+
+impl NotificationChannelService {
+    pub fn new(repository: Arc<dyn NotificationChannelRepository<Error = CustomError>>) -> Self {
+        Self { repository }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(Arc::new(NotificationChannelRepositoryImpl::new(database())))
+    }
+
+    /// Hands `event` to every subscribed channel on a background thread so
+    /// slow or unreachable channels never hold up downloads or feed refreshes.
+    pub fn notify(event: NotificationEvent) {
+        std::thread::spawn(move || Self::default_service().dispatch(&event));
+    }
+
+    pub fn list(&self) -> Result<Vec<NotificationChannel>, CustomError> {
+        self.repository.list()
+    }
+
+    pub fn get(&self, id: Uuid) -> Result<NotificationChannel, CustomError> {
+        self.repository
+            .get(id)?
+            .ok_or_else(|| CustomErrorInner::NotFound(Warning).into())
+    }
+
+    pub fn create(
+        &self,
+        input: NotificationChannelInput,
+    ) -> Result<NotificationChannel, CustomError> {
+        Self::validate(&input)?;
+        self.repository.create(NotificationChannel {
+            id: Uuid::new_v4(),
+            name: input.name,
+            kind: input.kind,
+            enabled: input.enabled,
+            events: input.events,
+            config: input.config,
+            created_at: chrono::Utc::now().naive_utc(),
+        })
+    }
+
+    pub fn update(
+        &self,
+        id: Uuid,
+        input: NotificationChannelInput,
+    ) -> Result<NotificationChannel, CustomError> {
+        Self::validate(&input)?;
+        let existing = self.get(id)?;
+        self.repository
+            .update(NotificationChannel {
+                name: input.name,
+                kind: input.kind,
+                enabled: input.enabled,
+                events: input.events,
+                config: input.config,
+                ..existing
+            })?
+            .ok_or_else(|| CustomErrorInner::NotFound(Warning).into())
+    }
+
+    pub fn delete(&self, id: Uuid) -> Result<(), CustomError> {
+        if !self.repository.delete(id)? {
+            return Err(CustomErrorInner::NotFound(Warning).into());
+        }
+        Ok(())
+    }
+
+    /// Sends a sample event through the channel, whether or not it is enabled
+    /// or subscribed, and reports delivery errors to the caller.
+    pub fn send_test(&self, id: Uuid) -> Result<(), CustomError> {
+        let channel = self.get(id)?;
+        let event_type = channel
+            .events
+            .first()
+            .copied()
+            .unwrap_or(NotificationEventType::NewEpisode);
+        let event = NotificationEvent {
+            event_type,
+            podcast: "PodFetch".to_string(),
+            episode: Some("Test notification".to_string()),
+            error: Some("This is a test notification".to_string()),
+        };
+        build_channel(channel.kind, &channel.config)?.send(&event)
+    }
+
+    /// Sends `event` to all enabled channels subscribed to its type. A failing
+    /// channel is logged and doesn't keep the others from being notified.
+    pub fn dispatch(&self, event: &NotificationEvent) {
+        let channels = match self.repository.list() {
+            Ok(channels) => channels,
+            Err(err) => {
+                tracing::error!("Error loading notification channels: {err}");
+                return;
+            }
+        };
+        for channel in channels
+            .into_iter()
+            .filter(|channel| channel.enabled && channel.events.contains(&event.event_type))
+        {
+            if let Err(err) =
+                build_channel(channel.kind, &channel.config).and_then(|c| c.send(event))
+            {
+                tracing::error!(
+                    "Error sending {} notification via channel {}: {err}",
+                    event.event_type.as_str(),
+                    channel.name
+                );
+            }
+        }
+    }
+
+    fn validate(input: &NotificationChannelInput) -> Result<(), CustomError> {
+        if input.name.trim().is_empty() {
+            return Err(CustomErrorInner::BadRequest(
+                "Notification channels need a name".to_string(),
+                Warning,
+            )
+            .into());
+        }
+        build_channel(input.kind, &input.config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::body::Bytes;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use std::sync::Mutex;
+    use std::sync::mpsc::{Receiver, channel};
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct InMemoryRepository {
+        channels: Mutex<Vec<NotificationChannel>>,
+    }
+
+    impl NotificationChannelRepository for InMemoryRepository {
+        type Error = CustomError;
+
+        fn create(&self, channel: NotificationChannel) -> Result<NotificationChannel, CustomError> {
+            self.channels.lock().unwrap().push(channel.clone());
+            Ok(channel)
+        }
+
+        fn update(
+            &self,
+            channel: NotificationChannel,
+        ) -> Result<Option<NotificationChannel>, CustomError> {
+            let mut channels = self.channels.lock().unwrap();
+            Ok(channels.iter_mut().find(|c| c.id == channel.id).map(|c| {
+                *c = channel;
+                c.clone()
+            }))
+        }
+
+        fn delete(&self, id: Uuid) -> Result<bool, CustomError> {
+            let mut channels = self.channels.lock().unwrap();
+            let before = channels.len();
+            channels.retain(|c| c.id != id);
+            Ok(channels.len() != before)
+        }
+
+        fn get(&self, id: Uuid) -> Result<Option<NotificationChannel>, CustomError> {
+            Ok(self
+                .channels
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|c| c.id == id)
+                .cloned())
+        }
+
+        fn list(&self) -> Result<Vec<NotificationChannel>, CustomError> {
+            Ok(self.channels.lock().unwrap().clone())
+        }
+    }
+
+    /// Records the path, headers and body of every request it receives.
+    fn spawn_mock_server() -> (String, Receiver<(String, HeaderMap, Bytes)>) {
+        let (request_tx, request_rx) = channel();
+        let app = Router::new().route(
+            "/{*path}",
+            post(
+                move |axum::extract::Path(path): axum::extract::Path<String>,
+                      headers: HeaderMap,
+                      body: Bytes| {
+                    request_tx.send((path, headers, body)).unwrap();
+                    async {}
+                },
+            ),
+        );
+        let (addr_tx, addr_rx) = channel();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().expect("build mock server runtime");
+            rt.block_on(async move {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+                    .await
+                    .expect("bind mock notification server");
+                addr_tx.send(listener.local_addr().unwrap()).unwrap();
+                axum::serve(listener, app).await.unwrap();
+            });
+        });
+        let addr = addr_rx.recv().expect("mock server address");
+        (format!("http://{addr}"), request_rx)
+    }
+
+    fn service() -> NotificationChannelService {
+        NotificationChannelService::new(Arc::new(InMemoryRepository::default()))
+    }
+
+    fn input(
+        kind: NotificationChannelKind,
+        events: Vec<NotificationEventType>,
+        config: String,
+    ) -> NotificationChannelInput {
+        NotificationChannelInput {
+            name: format!("{} channel", kind.as_str()),
+            kind,
+            enabled: true,
+            events,
+            config,
+        }
+    }
+
+    #[test]
+    fn dispatch_signs_webhooks_and_skips_unsubscribed_or_disabled_channels() {
+        let (base_url, requests) = spawn_mock_server();
+        let service = service();
+        service
+            .create(input(
+                NotificationChannelKind::Webhook,
+                vec![NotificationEventType::FeedBroken],
+                format!(r#"{{"url":"{base_url}/hook","secret":"s3cret"}}"#),
+            ))
+            .unwrap();
+        service
+            .create(input(
+                NotificationChannelKind::Webhook,
+                vec![NotificationEventType::NewEpisode],
+                format!(r#"{{"url":"{base_url}/unsubscribed"}}"#),
+            ))
+            .unwrap();
+        service
+            .create(NotificationChannelInput {
+                enabled: false,
+                ..input(
+                    NotificationChannelKind::Webhook,
+                    vec![NotificationEventType::FeedBroken],
+                    format!(r#"{{"url":"{base_url}/disabled"}}"#),
+                )
+            })
+            .unwrap();
+
+        service.dispatch(&NotificationEvent::feed_broken("Tech Talk", "HTTP 500"));
+
+        let (path, headers, body) = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(path, "hook");
+        assert_eq!(headers["x-podfetch-event"], "feed-broken");
+        assert_eq!(
+            headers["x-podfetch-signature"],
+            crate::services::notification_channel::webhook::sign("s3cret", &body).as_str()
+        );
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["event"], "feed-broken");
+        assert_eq!(payload["podcast"], "Tech Talk");
+        assert_eq!(payload["error"], "HTTP 500");
+        assert!(
+            requests.recv_timeout(Duration::from_millis(300)).is_err(),
+            "only the subscribed and enabled channel is notified"
+        );
+    }
+
+    #[test]
+    fn ntfy_and_gotify_requests_carry_title_and_token() {
+        let (base_url, requests) = spawn_mock_server();
+        let service = service();
+        let ntfy = service
+            .create(input(
+                NotificationChannelKind::Ntfy,
+                vec![NotificationEventType::NewEpisode],
+                format!(r#"{{"serverUrl":"{base_url}","topic":"podcasts","token":"tk"}}"#),
+            ))
+            .unwrap();
+        let gotify = service
+            .create(input(
+                NotificationChannelKind::Gotify,
+                vec![NotificationEventType::NewEpisode],
+                format!(r#"{{"serverUrl":"{base_url}/","token":"app-token"}}"#),
+            ))
+            .unwrap();
+
+        service.send_test(ntfy.id).unwrap();
+        let (path, headers, body) = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(path, "podcasts");
+        assert_eq!(headers["title"], "New episode available");
+        assert_eq!(headers["authorization"], "Bearer tk");
+        assert!(String::from_utf8_lossy(&body).contains("Test notification"));
+
+        service.send_test(gotify.id).unwrap();
+        let (path, headers, body) = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(path, "message");
+        assert_eq!(headers["x-gotify-key"], "app-token");
+        let message: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(message["title"], "New episode available");
+        assert_eq!(message["priority"], 5);
+    }
+
+    #[test]
+    fn create_rejects_invalid_configs_and_update_keeps_identity() {
+        let service = service();
+        assert!(
+            service
+                .create(input(
+                    NotificationChannelKind::Smtp,
+                    vec![],
+                    r#"{"host":"mail.example.com","from":"not an address","to":[]}"#.to_string(),
+                ))
+                .is_err()
+        );
+
+        let created = service
+            .create(input(
+                NotificationChannelKind::Smtp,
+                vec![NotificationEventType::DownloadFailed],
+                r#"{"host":"mail.example.com","from":"podfetch@example.com","to":["me@example.com"]}"#
+                    .to_string(),
+            ))
+            .unwrap();
+        let updated = service
+            .update(
+                created.id,
+                NotificationChannelInput {
+                    name: "Mail".to_string(),
+                    ..input(
+                        NotificationChannelKind::Smtp,
+                        NotificationEventType::ALL.to_vec(),
+                        created.config.clone(),
+                    )
+                },
+            )
+            .unwrap();
+        assert_eq!(updated.id, created.id);
+        assert_eq!(updated.created_at, created.created_at);
+        assert_eq!(updated.events.len(), 4);
+
+        service.delete(created.id).unwrap();
+        assert!(service.get(created.id).is_err());
+        assert!(service.delete(created.id).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+//! Plain-text e-mails over SMTP.
+
+use crate::services::notification_channel::channel::{
+    Channel, NotificationEvent, delivery_failed, invalid_config,
+};
+use common_infrastructure::error::CustomError;
+use lettre::message::Mailbox;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Upgrades a plain connection with STARTTLS (usually port 587).
+    #[default]
+    Starttls,
+    /// Implicit TLS (usually port 465).
+    Tls,
+    /// No encryption at all, only for relays on the local network.
+    None,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+}
+
+pub struct SmtpChannel {
+    transport: SmtpTransport,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl SmtpChannel {
+    pub fn new(config: SmtpConfig) -> Result<Self, CustomError> {
+        if config.host.trim().is_empty() {
+            return Err(invalid_config("SMTP needs a host"));
+        }
+        let from = parse_mailbox(&config.from)?;
+        let to = config
+            .to
+            .iter()
+            .map(|address| parse_mailbox(address))
+            .collect::<Result<Vec<_>, _>>()?;
+        if to.is_empty() {
+            return Err(invalid_config("SMTP needs at least one recipient"));
+        }
+
+        let mut builder = match config.security {
+            SmtpSecurity::Starttls => SmtpTransport::starttls_relay(&config.host),
+            SmtpSecurity::Tls => SmtpTransport::relay(&config.host),
+            SmtpSecurity::None => Ok(SmtpTransport::builder_dangerous(&config.host)),
+        }
+        .map_err(|err| invalid_config(err.to_string()))?;
+        if let Some(port) = config.port {
+            builder = builder.port(port);
+        }
+        if let Some(username) = config.username {
+            builder = builder.credentials(Credentials::new(
+                username,
+                config.password.unwrap_or_default(),
+            ));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+            to,
+        })
+    }
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox, CustomError> {
+    address
+        .parse()
+        .map_err(|_| invalid_config(format!("{address} is not a valid e-mail address")))
+}
+
+impl Channel for SmtpChannel {
+    fn send(&self, event: &NotificationEvent) -> Result<(), CustomError> {
+        let mut message = Message::builder()
+            .from(self.from.clone())
+            .subject(format!("PodFetch: {}", event.title()))
+            .header(ContentType::TEXT_PLAIN);
+        for recipient in &self.to {
+            message = message.to(recipient.clone());
+        }
+        let message = message.body(event.message()).map_err(delivery_failed)?;
+        self.transport
+            .send(&message)
+            .map(|_| ())
+            .map_err(delivery_failed)
+    }
+}
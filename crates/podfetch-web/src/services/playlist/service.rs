@@ -1,8 +1,9 @@
 use crate::controllers::playlist_controller::PlaylistDto;
 use crate::controllers::podcast_episode_controller::PodcastEpisodeWithHistory;
 use crate::history::map_episode_to_dto;
-use crate::playlist::{PlaylistApplicationService, PlaylistDtoPost};
+use crate::playlist::{PlaylistApplicationService, PlaylistDtoPost, SmartPlaylistQuery};
 use crate::podcast_episode_dto::PodcastEpisodeDto;
+use crate::services::playlist::smart::SmartPlaylistService;
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
 use crate::usecases::watchtime::WatchtimeUseCase as WatchtimeService;
 use common_infrastructure::error::CustomError;
@@ -26,29 +27,109 @@ fn parse_episode_id(episode: &str) -> Result<Uuid, CustomError> {
     })
 }
 
+fn smart_playlist_error() -> CustomError {
+    common_infrastructure::error::CustomErrorInner::BadRequest(
+        "the episodes of a smart playlist follow from its rules".to_string(),
+        common_infrastructure::error::ErrorSeverity::Warning,
+    )
+    .into()
+}
+
 #[derive(Clone)]
 pub struct PlaylistService {
     repository: Arc<dyn PlaylistRepository<Error = CustomError>>,
+    smart_playlists: SmartPlaylistService,
 }
 
 impl PlaylistService {
-    pub fn new(repository: Arc<dyn PlaylistRepository<Error = CustomError>>) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: Arc<dyn PlaylistRepository<Error = CustomError>>,
+        smart_playlists: SmartPlaylistService,
+    ) -> Self {
+        Self {
+            repository,
+            smart_playlists,
+        }
     }
 
     pub fn default_service() -> Self {
-        Self::new(Arc::new(PlaylistRepositoryImpl::new(database())))
+        Self::new(
+            Arc::new(PlaylistRepositoryImpl::new(database())),
+            SmartPlaylistService::default_service(),
+        )
+    }
+
+    /// The rules of a smart playlist, `None` for hand-curated playlists.
+    pub fn smart_query(playlist: &Playlist) -> Option<SmartPlaylistQuery> {
+        let query = playlist.smart_query.as_deref()?;
+        serde_json::from_str(query)
+            .inspect_err(|err| {
+                tracing::error!(
+                    "Error reading rules of smart playlist {}: {err}",
+                    playlist.id
+                )
+            })
+            .ok()
+    }
+
+    /// Fails for smart playlists, whose items can't be edited by hand.
+    pub fn ensure_manual(playlist: &Playlist) -> Result<(), CustomError> {
+        match playlist.smart_query {
+            Some(_) => Err(smart_playlist_error()),
+            None => Ok(()),
+        }
+    }
+
+    /// The episodes of a playlist in playlist order. Smart playlists are
+    /// evaluated for `user` on every call.
+    pub fn episodes_of(
+        &self,
+        playlist: &Playlist,
+        user: &User,
+    ) -> Result<Vec<PodcastEpisode>, CustomError> {
+        if playlist.smart_query.is_some() {
+            return match Self::smart_query(playlist) {
+                Some(query) => self.smart_playlists.evaluate(&query, user),
+                None => Ok(Vec::new()),
+            };
+        }
+        let mut episodes = Vec::new();
+        for item in self.repository.list_items_by_playlist_id(&playlist.id)? {
+            if let Some(episode) =
+                PodcastEpisodeService::get_podcast_episode_by_internal_id(item.episode)?
+            {
+                episodes.push(episode);
+            }
+        }
+        Ok(episodes)
+    }
+
+    fn store_smart_query(
+        &self,
+        playlist_id: &str,
+        user: &User,
+        query: Option<&SmartPlaylistQuery>,
+    ) -> Result<(), CustomError> {
+        let query = query.map(serde_json::to_string).transpose().map_err(|_| {
+            CustomError::from(common_infrastructure::error::CustomErrorInner::Unknown(
+                common_infrastructure::error::ErrorSeverity::Error,
+            ))
+        })?;
+        self.repository
+            .update_smart_query(playlist_id, user.id, query.as_deref())?;
+        Ok(())
     }
 
     fn to_playlist_dto(
         playlist: Playlist,
-        items: Vec<(PlaylistItem, PodcastEpisode, Option<Episode>)>,
+        items: Vec<(PodcastEpisode, Option<Episode>)>,
         user: User,
     ) -> PlaylistDto {
+        let smart = Self::smart_query(&playlist);
         let items = items
             .into_iter()
             .map(
-                |(_, podcast_episode, history): (PlaylistItem, PodcastEpisode, Option<Episode>)| {
+                |(podcast_episode, history): (PodcastEpisode, Option<Episode>)| {
                     PodcastEpisodeWithHistory {
                         podcast_episode: PodcastEpisodeDto::from_episode_with_user(
                             podcast_episode,
@@ -69,29 +150,25 @@ impl PlaylistService {
             id: playlist.id,
             name: playlist.name,
             items,
+            smart,
         }
     }
 
     fn load_playlist_items(
         &self,
-        playlist_id: &str,
+        playlist: &Playlist,
         user: &User,
-    ) -> Result<Vec<(PlaylistItem, PodcastEpisode, Option<Episode>)>, CustomError> {
-        let items = self.repository.list_items_by_playlist_id(playlist_id)?;
-
-        items
+    ) -> Result<Vec<(PodcastEpisode, Option<Episode>)>, CustomError> {
+        Ok(self
+            .episodes_of(playlist, user)?
             .into_iter()
-            .filter_map(|item| {
-                let podcast_episode =
-                    PodcastEpisodeService::get_podcast_episode_by_internal_id(item.episode)
-                        .ok()
-                        .flatten()?;
+            .filter_map(|podcast_episode| {
                 let history =
                     WatchtimeService::get_watchtime(&podcast_episode.episode_id, &user.username)
                         .ok()?;
-                Some(Ok((item, podcast_episode, history)))
+                Some((podcast_episode, history))
             })
-            .collect()
+            .collect())
     }
 
     fn find_playlist_by_id(&self, playlist_id: &str) -> Result<Playlist, CustomError> {
@@ -103,7 +180,7 @@ impl PlaylistService {
         })
     }
 
-    fn find_playlist_by_user_and_id(
+    pub fn find_playlist_by_user_and_id(
         &self,
         playlist_id: &str,
         user_id: Uuid,
@@ -127,11 +204,19 @@ impl PlaylistService {
             return Ok(existing);
         }
 
+        if let Some(query) = &playlist.smart {
+            self.smart_playlists.validate(query, user)?;
+        }
         let inserted = self.repository.insert_playlist(Playlist {
             id: uuid::Uuid::new_v4().to_string(),
             name: playlist.name.clone(),
             user_id: user.id,
+            smart_query: None,
         })?;
+        if playlist.smart.is_some() {
+            self.store_smart_query(&inserted.id, user, playlist.smart.as_ref())?;
+            return self.find_playlist_by_id(&inserted.id);
+        }
 
         for (position, item) in playlist.items.iter().enumerate() {
             self.repository.insert_playlist_item(PlaylistItem {
@@ -160,7 +245,7 @@ impl PlaylistApplicationService for PlaylistService {
         playlist: PlaylistDtoPost,
     ) -> Result<Self::PlaylistDto, Self::Error> {
         let playlist = self.create_playlist_if_missing(playlist, &user)?;
-        let items = self.load_playlist_items(&playlist.id, &user)?;
+        let items = self.load_playlist_items(&playlist, &user)?;
         Ok(Self::to_playlist_dto(playlist, items, user))
     }
 
@@ -178,10 +263,18 @@ impl PlaylistApplicationService for PlaylistService {
             .into());
         }
 
+        if let Some(query) = &playlist.smart {
+            self.smart_playlists.validate(query, &user)?;
+        }
         self.repository
             .update_playlist_name(&playlist_id, user.id, &playlist.name)?;
+        self.store_smart_query(&playlist_id, &user, playlist.smart.as_ref())?;
         self.repository.delete_items_by_playlist_id(&playlist_id)?;
-        for (position, item) in playlist.items.iter().enumerate() {
+        let manual_items = match playlist.smart {
+            Some(_) => &[][..],
+            None => &playlist.items[..],
+        };
+        for (position, item) in manual_items.iter().enumerate() {
             self.repository.insert_playlist_item(PlaylistItem {
                 playlist_id: playlist_id.clone(),
                 episode: parse_episode_id(&item.episode)?,
@@ -190,7 +283,7 @@ impl PlaylistApplicationService for PlaylistService {
         }
 
         let playlist = self.find_playlist_by_id(&playlist_id)?;
-        let items = self.load_playlist_items(&playlist.id, &user)?;
+        let items = self.load_playlist_items(&playlist, &user)?;
         Ok(Self::to_playlist_dto(playlist, items, user))
     }
 
@@ -199,7 +292,7 @@ impl PlaylistApplicationService for PlaylistService {
             .list_by_user(user.id)?
             .into_iter()
             .map(|playlist| {
-                let items = self.load_playlist_items(&playlist.id, &user)?;
+                let items = self.load_playlist_items(&playlist, &user)?;
                 Ok(Self::to_playlist_dto(playlist, items, user.clone()))
             })
             .collect()
@@ -211,7 +304,7 @@ impl PlaylistApplicationService for PlaylistService {
         playlist_id: String,
     ) -> Result<Self::PlaylistDto, Self::Error> {
         let playlist = self.find_playlist_by_user_and_id(&playlist_id, user.id)?;
-        let items = self.load_playlist_items(&playlist_id, &user)?;
+        let items = self.load_playlist_items(&playlist, &user)?;
         Ok(Self::to_playlist_dto(playlist, items, user))
     }
 
@@ -242,6 +335,7 @@ impl PlaylistApplicationService for PlaylistService {
             )
             .into());
         }
+        Self::ensure_manual(&playlist)?;
 
         self.repository
             .delete_playlist_item(&playlist_id, episode_id)?;
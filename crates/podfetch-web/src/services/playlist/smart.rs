@@ -0,0 +1,517 @@
+use crate::playlist::{PlayState, SmartPlaylistOrder, SmartPlaylistQuery, TriageFilter};
+use crate::services::podcast::service::PodcastService;
+use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
+use crate::usecases::watchtime::WatchtimeUseCase as WatchtimeService;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::episode_triage::{EpisodeTriageRepository, TriageStatus};
+use podfetch_domain::favorite_podcast_episode::FavoritePodcastEpisodeRepository;
+use podfetch_domain::podcast_episode::{EpisodeCandidateFilter, EpisodeCandidateOrder};
+use podfetch_domain::tag::TagRepository;
+use podfetch_domain::user::User;
+use podfetch_persistence::adapters::{
+    EpisodeTriageRepositoryImpl, FavoritePodcastEpisodeRepositoryImpl, TagRepositoryImpl,
+};
+use podfetch_persistence::db::database;
+use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Most episodes loaded for one evaluation. Rules on the owner's listening
+/// state, triage and favorites are checked after loading, so for those the
+/// database can't stop at the playlist's own limit.
+const MAX_CANDIDATES: i64 = 5_000;
+
+/// What the owner of a smart playlist has done with an episode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeUserState {
+    pub play_state: PlayState,
+    pub triage: Option<TriageStatus>,
+    pub favorite: bool,
+}
+
+impl Default for EpisodeUserState {
+    fn default() -> Self {
+        Self {
+            play_state: PlayState::Unplayed,
+            triage: None,
+            favorite: false,
+        }
+    }
+}
+
+/// Selects the episodes of smart playlists from the episodes, triage state,
+/// favorites, listening history and tags of the playlist owner.
+#[derive(Clone)]
+pub struct SmartPlaylistService {
+    tag_repository: Arc<dyn TagRepository<Error = CustomError>>,
+    triage_repository: Arc<dyn EpisodeTriageRepository<Error = CustomError>>,
+    favorite_repository: Arc<dyn FavoritePodcastEpisodeRepository<Error = CustomError>>,
+}
+
+impl SmartPlaylistService {
+    pub fn new(
+        tag_repository: Arc<dyn TagRepository<Error = CustomError>>,
+        triage_repository: Arc<dyn EpisodeTriageRepository<Error = CustomError>>,
+        favorite_repository: Arc<dyn FavoritePodcastEpisodeRepository<Error = CustomError>>,
+    ) -> Self {
+        Self {
+            tag_repository,
+            triage_repository,
+            favorite_repository,
+        }
+    }
+
+    pub fn default_service() -> Self {
+        let database = database();
+        Self::new(
+            Arc::new(TagRepositoryImpl::new(database.clone())),
+            Arc::new(EpisodeTriageRepositoryImpl::new(database.clone())),
+            Arc::new(FavoritePodcastEpisodeRepositoryImpl::new(database)),
+        )
+    }
+
+    /// Rejects queries referring to unknown podcasts or to tags of other users.
+    pub fn validate(&self, query: &SmartPlaylistQuery, user: &User) -> Result<(), CustomError> {
+        for podcast_id in &query.podcast_ids {
+            let podcast_id = Uuid::parse_str(podcast_id)
+                .map_err(|_| bad_request(format!("'{podcast_id}' is not a valid podcast id")))?;
+            PodcastService::get_podcast(podcast_id)
+                .map_err(|_| bad_request(format!("podcast {podcast_id} does not exist")))?;
+        }
+        for tag_id in &query.tag_ids {
+            if self
+                .tag_repository
+                .get_tag_by_id_and_user_id(tag_id, user.id)?
+                .is_none()
+            {
+                return Err(bad_request(format!("tag {tag_id} does not exist")));
+            }
+        }
+        if let (Some(min), Some(max)) = (query.min_duration_seconds, query.max_duration_seconds)
+            && min > max
+        {
+            return Err(bad_request(
+                "the minimum duration is longer than the maximum".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Runs `query` for `user` against the current state of the library.
+    pub fn evaluate(
+        &self,
+        query: &SmartPlaylistQuery,
+        user: &User,
+    ) -> Result<Vec<PodcastEpisode>, CustomError> {
+        let now = Utc::now().naive_utc();
+        let episodes = self.candidate_episodes(query, user, now)?;
+
+        let history: HashMap<String, PlayState> = if query.play_state.is_some() {
+            WatchtimeService::get_last_watched_episodes(user)?
+                .into_iter()
+                .map(|(episode, action, _)| {
+                    (episode.id, play_state_of(action.position, action.total))
+                })
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        let triage: HashMap<Uuid, TriageStatus> = if query.triage.is_some() {
+            let mut triage = HashMap::new();
+            for status in [
+                TriageStatus::Queued,
+                TriageStatus::Archived,
+                TriageStatus::Dismissed,
+            ] {
+                for episode_id in self
+                    .triage_repository
+                    .list_episode_ids_by_status(user.id, status)?
+                {
+                    triage.insert(episode_id, status);
+                }
+            }
+            triage
+        } else {
+            HashMap::new()
+        };
+        let favorites: HashSet<Uuid> = if query.favorite.is_some() {
+            self.favorite_repository
+                .get_favorites_by_user_id(user.id)?
+                .into_iter()
+                .filter(|favorite| favorite.favorite)
+                .map(|favorite| favorite.episode_id)
+                .collect()
+        } else {
+            HashSet::new()
+        };
+
+        let candidates = episodes.into_iter().map(|episode| {
+            let episode_uuid = Uuid::parse_str(&episode.id).ok();
+            let state = EpisodeUserState {
+                play_state: history
+                    .get(&episode.id)
+                    .copied()
+                    .unwrap_or(PlayState::Unplayed),
+                triage: episode_uuid.and_then(|id| triage.get(&id).copied()),
+                favorite: episode_uuid.is_some_and(|id| favorites.contains(&id)),
+            };
+            (episode, state)
+        });
+        Ok(select(query, candidates, now))
+    }
+
+    fn candidate_episodes(
+        &self,
+        query: &SmartPlaylistQuery,
+        user: &User,
+        now: NaiveDateTime,
+    ) -> Result<Vec<PodcastEpisode>, CustomError> {
+        let podcast_ids = if query.podcast_ids.is_empty() && query.tag_ids.is_empty() {
+            None
+        } else {
+            Some(self.podcast_ids(query, user)?)
+        };
+        PodcastEpisodeService::get_candidate_episodes(&candidate_filter(query, podcast_ids, now))
+    }
+
+    /// The chosen podcasts plus those carrying one of the chosen tags.
+    fn podcast_ids(
+        &self,
+        query: &SmartPlaylistQuery,
+        user: &User,
+    ) -> Result<Vec<Uuid>, CustomError> {
+        let mut podcast_ids: HashSet<Uuid> = query
+            .podcast_ids
+            .iter()
+            .filter_map(|podcast_id| Uuid::parse_str(podcast_id).ok())
+            .collect();
+        for tag_id in &query.tag_ids {
+            podcast_ids.extend(
+                self.tag_repository
+                    .get_podcast_ids_of_tag(tag_id, user.id)?,
+            );
+        }
+
+        Ok(podcast_ids.into_iter().collect())
+    }
+}
+
+/// What the database can answer of `query`. Publish dates are stored with
+/// the offset of their feed, so the text comparison starts a day early and
+/// [`select`] checks the exact time.
+fn candidate_filter(
+    query: &SmartPlaylistQuery,
+    podcast_ids: Option<Vec<Uuid>>,
+    now: NaiveDateTime,
+) -> EpisodeCandidateFilter {
+    let checked_after_loading = query.play_state.is_some()
+        || query.triage.is_some()
+        || query.favorite.is_some()
+        || query.published_within_days.is_some();
+    let limit = match query.limit {
+        Some(limit) if !checked_after_loading => i64::from(limit).min(MAX_CANDIDATES),
+        _ => MAX_CANDIDATES,
+    };
+    let seconds = |seconds: u32| i32::try_from(seconds).unwrap_or(i32::MAX);
+    EpisodeCandidateFilter {
+        podcast_ids,
+        downloaded: query.downloaded,
+        recorded_from: query.published_within_days.map(|days| {
+            (now - chrono::Duration::days(i64::from(days) + 1))
+                .format("%Y-%m-%d")
+                .to_string()
+        }),
+        min_total_time: query.min_duration_seconds.map(seconds),
+        max_total_time: query.max_duration_seconds.map(seconds),
+        order: match query.order {
+            SmartPlaylistOrder::NewestFirst => EpisodeCandidateOrder::NewestFirst,
+            SmartPlaylistOrder::OldestFirst => EpisodeCandidateOrder::OldestFirst,
+            SmartPlaylistOrder::ShortestFirst => EpisodeCandidateOrder::ShortestFirst,
+            SmartPlaylistOrder::LongestFirst => EpisodeCandidateOrder::LongestFirst,
+        },
+        limit,
+    }
+}
+
+/// Filters, orders and trims the candidate episodes as the query asks.
+pub fn select(
+    query: &SmartPlaylistQuery,
+    candidates: impl IntoIterator<Item = (PodcastEpisode, EpisodeUserState)>,
+    now: NaiveDateTime,
+) -> Vec<PodcastEpisode> {
+    let published_after = query
+        .published_within_days
+        .map(|days| now - chrono::Duration::days(i64::from(days)));
+
+    let mut matching: Vec<PodcastEpisode> = candidates
+        .into_iter()
+        .filter(|(episode, state)| {
+            !episode.deleted
+                && query
+                    .play_state
+                    .is_none_or(|play_state| play_state == state.play_state)
+                && query
+                    .triage
+                    .is_none_or(|triage| triage_matches(triage, state.triage))
+                && query
+                    .favorite
+                    .is_none_or(|favorite| favorite == state.favorite)
+                && query
+                    .downloaded
+                    .is_none_or(|downloaded| downloaded == episode.download_location.is_some())
+                && query
+                    .min_duration_seconds
+                    .is_none_or(|min| i64::from(episode.total_time) >= i64::from(min))
+                && query
+                    .max_duration_seconds
+                    .is_none_or(|max| i64::from(episode.total_time) <= i64::from(max))
+                && published_after.is_none_or(|after| {
+                    published_at(episode).is_some_and(|published| published >= after)
+                })
+        })
+        .map(|(episode, _)| episode)
+        .collect();
+
+    match query.order {
+        SmartPlaylistOrder::NewestFirst => {
+            matching.sort_by(|a, b| b.date_of_recording.cmp(&a.date_of_recording))
+        }
+        SmartPlaylistOrder::OldestFirst => {
+            matching.sort_by(|a, b| a.date_of_recording.cmp(&b.date_of_recording))
+        }
+        SmartPlaylistOrder::ShortestFirst => matching.sort_by_key(|episode| episode.total_time),
+        SmartPlaylistOrder::LongestFirst => {
+            matching.sort_by_key(|episode| std::cmp::Reverse(episode.total_time))
+        }
+    }
+
+    if let Some(limit) = query.limit {
+        matching.truncate(limit as usize);
+    }
+    if let Some(max_total) = query.max_total_seconds {
+        let mut total = 0i64;
+        matching.retain(|episode| {
+            let next = total + i64::from(episode.total_time.max(0));
+            if next > i64::from(max_total) {
+                return false;
+            }
+            total = next;
+            true
+        });
+    }
+    matching
+}
+
+fn play_state_of(position: Option<i32>, total: Option<i32>) -> PlayState {
+    match (position, total) {
+        (None | Some(0), _) => PlayState::Unplayed,
+        (Some(position), Some(total)) if total > 0 && position >= total => PlayState::Played,
+        _ => PlayState::InProgress,
+    }
+}
+
+fn triage_matches(filter: TriageFilter, status: Option<TriageStatus>) -> bool {
+    match filter {
+        TriageFilter::Inbox => status.is_none(),
+        TriageFilter::Queued => status == Some(TriageStatus::Queued),
+        TriageFilter::Archived => status == Some(TriageStatus::Archived),
+        TriageFilter::Dismissed => status == Some(TriageStatus::Dismissed),
+    }
+}
+
+fn published_at(episode: &PodcastEpisode) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(&episode.date_of_recording)
+        .ok()
+        .map(|date| date.naive_utc())
+}
+
+fn bad_request(message: String) -> CustomError {
+    CustomErrorInner::BadRequest(message, Warning).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episode(name: &str, published: &str, total_time: i32) -> PodcastEpisode {
+        PodcastEpisode {
+            id: Uuid::new_v4().to_string(),
+            legacy_id: None,
+            podcast_id: Uuid::new_v4().to_string(),
+            episode_id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            url: format!("https://example.com/{name}.mp3"),
+            date_of_recording: published.to_string(),
+            image_url: String::new(),
+            total_time,
+            description: String::new(),
+            download_time: None,
+            guid: name.to_string(),
+            deleted: false,
+            file_episode_path: None,
+            file_image_path: None,
+            episode_numbering_processed: false,
+            download_location: None,
+            youtube_video_id: None,
+            season: None,
+            episode_number: None,
+            episode_type: None,
+            loudness_lufs: None,
+            loudness_true_peak: None,
+        }
+    }
+
+    fn now() -> NaiveDateTime {
+        DateTime::parse_from_rfc3339("2026-09-30T12:00:00Z")
+            .unwrap()
+            .naive_utc()
+    }
+
+    fn names(episodes: &[PodcastEpisode]) -> Vec<&str> {
+        episodes
+            .iter()
+            .map(|episode| episode.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn unplayed_newest_first_within_two_hours() {
+        let query = SmartPlaylistQuery {
+            play_state: Some(PlayState::Unplayed),
+            max_total_seconds: Some(2 * 60 * 60),
+            ..SmartPlaylistQuery::default()
+        };
+        let unplayed = EpisodeUserState::default();
+        let played = EpisodeUserState {
+            play_state: PlayState::Played,
+            ..unplayed
+        };
+        let candidates = vec![
+            (episode("old", "2026-09-01T06:00:00+00:00", 3600), unplayed),
+            (
+                episode("newest", "2026-09-29T06:00:00+00:00", 3000),
+                unplayed,
+            ),
+            (episode("heard", "2026-09-28T06:00:00+00:00", 600), played),
+            (episode("long", "2026-09-20T06:00:00+00:00", 5000), unplayed),
+            (
+                episode("short", "2026-09-10T06:00:00+00:00", 1200),
+                unplayed,
+            ),
+        ];
+
+        let selected = select(&query, candidates, now());
+
+        assert_eq!(names(&selected), vec!["newest", "short"]);
+    }
+
+    #[test]
+    fn favorites_published_in_the_last_30_days() {
+        let query = SmartPlaylistQuery {
+            favorite: Some(true),
+            published_within_days: Some(30),
+            order: SmartPlaylistOrder::OldestFirst,
+            ..SmartPlaylistQuery::default()
+        };
+        let favorite = EpisodeUserState {
+            favorite: true,
+            ..EpisodeUserState::default()
+        };
+        let mut deleted = episode("deleted", "2026-09-25T06:00:00+00:00", 60);
+        deleted.deleted = true;
+        let candidates = vec![
+            (episode("recent", "2026-09-25T06:00:00+00:00", 60), favorite),
+            (episode("older", "2026-09-05T06:00:00+00:00", 60), favorite),
+            (episode("stale", "2026-07-01T06:00:00+00:00", 60), favorite),
+            (
+                episode("not-liked", "2026-09-26T06:00:00+00:00", 60),
+                EpisodeUserState::default(),
+            ),
+            (deleted, favorite),
+        ];
+
+        let selected = select(&query, candidates, now());
+
+        assert_eq!(names(&selected), vec!["older", "recent"]);
+    }
+
+    #[test]
+    fn inbox_means_untriaged_and_limit_applies_after_ordering() {
+        let query = SmartPlaylistQuery {
+            triage: Some(TriageFilter::Inbox),
+            order: SmartPlaylistOrder::LongestFirst,
+            limit: Some(1),
+            ..SmartPlaylistQuery::default()
+        };
+        let queued = EpisodeUserState {
+            triage: Some(TriageStatus::Queued),
+            ..EpisodeUserState::default()
+        };
+        let candidates = vec![
+            (
+                episode("a", "2026-09-01T06:00:00+00:00", 100),
+                EpisodeUserState::default(),
+            ),
+            (
+                episode("b", "2026-09-02T06:00:00+00:00", 300),
+                EpisodeUserState::default(),
+            ),
+            (episode("c", "2026-09-03T06:00:00+00:00", 900), queued),
+        ];
+
+        assert_eq!(names(&select(&query, candidates, now())), vec!["b"]);
+    }
+
+    #[test]
+    fn the_database_limits_only_what_it_can_filter_alone() {
+        let podcast_id = Uuid::new_v4();
+        let query = SmartPlaylistQuery {
+            downloaded: Some(true),
+            min_duration_seconds: Some(600),
+            order: SmartPlaylistOrder::LongestFirst,
+            limit: Some(10),
+            ..SmartPlaylistQuery::default()
+        };
+        assert_eq!(
+            candidate_filter(&query, Some(vec![podcast_id]), now()),
+            EpisodeCandidateFilter {
+                podcast_ids: Some(vec![podcast_id]),
+                downloaded: Some(true),
+                recorded_from: None,
+                min_total_time: Some(600),
+                max_total_time: None,
+                order: EpisodeCandidateOrder::LongestFirst,
+                limit: 10,
+            }
+        );
+
+        let unplayed = SmartPlaylistQuery {
+            play_state: Some(PlayState::Unplayed),
+            ..query.clone()
+        };
+        assert_eq!(
+            candidate_filter(&unplayed, None, now()).limit,
+            MAX_CANDIDATES
+        );
+
+        let recent = SmartPlaylistQuery {
+            published_within_days: Some(30),
+            ..query
+        };
+        let filter = candidate_filter(&recent, None, now());
+        assert_eq!(filter.recorded_from.as_deref(), Some("2026-08-30"));
+        assert_eq!(filter.limit, MAX_CANDIDATES);
+    }
+
+    #[test]
+    fn play_state_follows_the_last_position() {
+        assert_eq!(play_state_of(None, Some(100)), PlayState::Unplayed);
+        assert_eq!(play_state_of(Some(0), Some(100)), PlayState::Unplayed);
+        assert_eq!(play_state_of(Some(40), Some(100)), PlayState::InProgress);
+        assert_eq!(play_state_of(Some(100), Some(100)), PlayState::Played);
+        assert_eq!(play_state_of(Some(40), None), PlayState::InProgress);
+    }
+}
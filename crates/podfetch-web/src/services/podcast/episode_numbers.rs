@@ -0,0 +1,122 @@
+//! Season and episode numbers announced by the feed. Podcasting 2.0's
+//! `<podcast:season>`/`<podcast:episode>` win over `<itunes:season>`/
+//! `<itunes:episode>`; `<itunes:episodeType>` is only taken when it is one of
+//! the three values Apple defines.
+
+use rss::Item;
+
+pub const EPISODE_TYPES: [&str; 3] = ["full", "trailer", "bonus"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EpisodeNumbers {
+    pub season: Option<i32>,
+    pub episode_number: Option<i32>,
+    pub episode_type: Option<String>,
+}
+
+impl EpisodeNumbers {
+    pub fn from_item(item: &Item) -> Self {
+        let itunes = item.itunes_ext();
+        Self {
+            season: podcast_value(item, "season")
+                .and_then(parse_number)
+                .or_else(|| itunes.and_then(|ext| ext.season()).and_then(parse_number)),
+            episode_number: podcast_value(item, "episode")
+                .and_then(parse_number)
+                .or_else(|| itunes.and_then(|ext| ext.episode()).and_then(parse_number)),
+            episode_type: itunes
+                .and_then(|ext| ext.episode_type())
+                .map(|episode_type| episode_type.trim().to_lowercase())
+                .filter(|episode_type| EPISODE_TYPES.contains(&episode_type.as_str())),
+        }
+    }
+}
+
+fn podcast_value<'a>(item: &'a Item, name: &str) -> Option<&'a str> {
+    let podcast_ns = item.extensions().get("podcast")?;
+    [name.to_string(), format!("podcast:{name}")]
+        .iter()
+        .filter_map(|key| podcast_ns.get(key))
+        .flatten()
+        .find_map(|ext| ext.value())
+}
+
+/// Numbers have to be whole and non-negative. `<podcast:episode>` also allows
+/// decimals such as `315.5`, which can't be stored and are ignored.
+fn parse_number(value: &str) -> Option<i32> {
+    value
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|number| *number >= 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rss::extension::itunes::ITunesItemExtension;
+    use rss::extension::{ExtensionBuilder, ExtensionMap};
+    use std::collections::BTreeMap;
+
+    fn item(itunes: Option<ITunesItemExtension>, podcast_tags: &[(&str, &str)]) -> Item {
+        let mut podcast_ns = BTreeMap::new();
+        for (name, value) in podcast_tags {
+            let mut builder = ExtensionBuilder::default();
+            builder.name(format!("podcast:{name}"));
+            builder.value(Some(value.to_string()));
+            podcast_ns.insert(name.to_string(), vec![builder.build()]);
+        }
+        let mut extensions = ExtensionMap::new();
+        extensions.insert("podcast".to_string(), podcast_ns);
+        Item {
+            itunes_ext: itunes,
+            extensions,
+            ..Default::default()
+        }
+    }
+
+    fn itunes(season: &str, episode: &str, episode_type: &str) -> ITunesItemExtension {
+        ITunesItemExtension {
+            season: Some(season.to_string()),
+            episode: Some(episode.to_string()),
+            episode_type: Some(episode_type.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reads_itunes_tags() {
+        let numbers = EpisodeNumbers::from_item(&item(Some(itunes("2", " 14 ", "Trailer")), &[]));
+        assert_eq!(
+            numbers,
+            EpisodeNumbers {
+                season: Some(2),
+                episode_number: Some(14),
+                episode_type: Some("trailer".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn podcast_namespace_wins_over_itunes() {
+        let numbers = EpisodeNumbers::from_item(&item(
+            Some(itunes("2", "14", "full")),
+            &[("season", "3"), ("episode", "7")],
+        ));
+        assert_eq!(numbers.season, Some(3));
+        assert_eq!(numbers.episode_number, Some(7));
+    }
+
+    #[test]
+    fn ignores_values_that_are_not_numbers_or_known_types() {
+        let numbers = EpisodeNumbers::from_item(&item(
+            Some(itunes("first", "-1", "extra")),
+            &[("episode", "315.5")],
+        ));
+        assert_eq!(numbers, EpisodeNumbers::default());
+
+        let numbers =
+            EpisodeNumbers::from_item(&item(Some(itunes("1", "9", "full")), &[("episode", "x")]));
+        assert_eq!(numbers.episode_number, Some(9));
+    }
+}
@@ -1,2 +1,4 @@
+pub mod episode_numbers;
 pub mod metadata;
+pub mod refresh_schedule;
 pub mod service;
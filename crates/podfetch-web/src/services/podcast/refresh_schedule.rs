@@ -0,0 +1,334 @@
+//! Decides when each podcast's feed is refreshed next. The scheduler ticks
+//! every minute and only refreshes podcasts whose `next_refresh_at` has
+//! passed; after every attempt the next one is computed from the podcast's
+//! `refresh_mode`.
+
+use crate::podcast_settings::PodcastSetting;
+use crate::services::podcast_settings::service::PodcastSettingsService;
+use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use common_infrastructure::error::{CustomError, CustomErrorInner, ErrorSeverity};
+use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
+use podfetch_domain::podcast_feed::PodcastFeedStateRepository;
+use podfetch_persistence::adapters::PodcastFeedStateRepositoryImpl;
+use podfetch_persistence::db::database;
+use podfetch_persistence::podcast::PodcastEntity as Podcast;
+use rss::Channel;
+use rss::extension::syndication::UpdatePeriod;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Refresh every `refresh_interval` minutes (or the global polling interval).
+pub const REFRESH_MODE_FIXED: &str = "fixed";
+/// Refresh at a fraction of the gap between the podcast's recent episodes,
+/// never more often than the feed's own `<ttl>`/`<sy:updatePeriod>` asks for.
+pub const REFRESH_MODE_ADAPTIVE: &str = "adaptive";
+
+const MIN_ADAPTIVE_INTERVAL_MINUTES: i64 = 15;
+const MAX_ADAPTIVE_INTERVAL_MINUTES: i64 = 7 * 24 * 60;
+/// A podcast that publishes daily is polled about every six hours.
+const CADENCE_DIVISOR: i32 = 4;
+const CADENCE_SAMPLE_SIZE: i32 = 20;
+
+pub struct RefreshScheduleService {
+    feed_state_repo: Arc<dyn PodcastFeedStateRepository<Error = CustomError>>,
+}
+
+impl RefreshScheduleService {
+    pub fn new(feed_state_repo: Arc<dyn PodcastFeedStateRepository<Error = CustomError>>) -> Self {
+        Self { feed_state_repo }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(Arc::new(PodcastFeedStateRepositoryImpl::new(database())))
+    }
+
+    /// The podcasts whose next refresh is due. Podcasts that were never
+    /// scheduled are always due.
+    pub fn due_podcasts(
+        &self,
+        podcasts: Vec<Podcast>,
+        now: NaiveDateTime,
+    ) -> Result<Vec<Podcast>, CustomError> {
+        let next_refresh_by_podcast = self
+            .feed_state_repo
+            .list()?
+            .into_iter()
+            .filter_map(|state| Some((state.podcast_id, state.next_refresh_at?)))
+            .collect::<HashMap<Uuid, NaiveDateTime>>();
+
+        Ok(podcasts
+            .into_iter()
+            .filter(|podcast| {
+                Uuid::parse_str(&podcast.id)
+                    .ok()
+                    .and_then(|id| next_refresh_by_podcast.get(&id))
+                    .is_none_or(|next_refresh_at| *next_refresh_at <= now)
+            })
+            .collect())
+    }
+
+    /// Computes and stores when the podcast is refreshed next.
+    pub fn schedule_next_refresh(
+        &self,
+        podcast: &Podcast,
+        now: NaiveDateTime,
+    ) -> Result<NaiveDateTime, CustomError> {
+        let podcast_id = parse_id(&podcast.id)?;
+        let setting = PodcastSettingsService::get_settings_for_podcast(podcast_id)?;
+        let adaptive = setting
+            .as_ref()
+            .is_some_and(|setting| setting.refresh_mode == REFRESH_MODE_ADAPTIVE);
+
+        let (update_interval_hint, cadence) = if adaptive {
+            let hint = self
+                .feed_state_repo
+                .get(podcast_id)?
+                .and_then(|state| state.update_interval_hint);
+            let recent_episodes = PodcastEpisodeService::get_last_n_podcast_episodes_by_count(
+                podcast_id,
+                CADENCE_SAMPLE_SIZE,
+            )?;
+            let cadence = publish_cadence(recent_episodes.iter().filter_map(|episode| {
+                DateTime::parse_from_rfc3339(&episode.date_of_recording)
+                    .ok()
+                    .map(|date| date.with_timezone(&Utc))
+            }));
+            (hint, cadence)
+        } else {
+            (None, None)
+        };
+
+        let interval = refresh_interval(
+            setting.as_ref(),
+            update_interval_hint,
+            cadence,
+            i64::from(ENVIRONMENT_SERVICE.get_polling_interval()),
+        );
+        let next_refresh_at = now + interval;
+        self.feed_state_repo
+            .set_next_refresh_at(podcast_id, next_refresh_at)?;
+        Ok(next_refresh_at)
+    }
+}
+
+/// How long the podcast waits until its next refresh. `default_minutes` is
+/// the global polling interval.
+pub fn refresh_interval(
+    setting: Option<&PodcastSetting>,
+    update_interval_hint: Option<i32>,
+    cadence: Option<Duration>,
+    default_minutes: i64,
+) -> Duration {
+    let fixed = setting
+        .map(|setting| i64::from(setting.refresh_interval))
+        .filter(|minutes| *minutes > 0)
+        .unwrap_or(default_minutes)
+        .max(1);
+    let adaptive = setting.is_some_and(|setting| setting.refresh_mode == REFRESH_MODE_ADAPTIVE);
+    if !adaptive {
+        return Duration::minutes(fixed);
+    }
+
+    let from_cadence = cadence
+        .map(|cadence| (cadence / CADENCE_DIVISOR).num_minutes())
+        .unwrap_or(fixed);
+    let minutes = from_cadence.max(i64::from(update_interval_hint.unwrap_or(0)));
+    Duration::minutes(minutes.clamp(MIN_ADAPTIVE_INTERVAL_MINUTES, MAX_ADAPTIVE_INTERVAL_MINUTES))
+}
+
+/// Median gap between consecutive publish dates, or `None` with fewer than
+/// two distinct dates.
+pub fn publish_cadence(dates: impl IntoIterator<Item = DateTime<Utc>>) -> Option<Duration> {
+    let mut dates = dates.into_iter().collect::<Vec<_>>();
+    dates.sort();
+    dates.dedup();
+    let mut gaps = dates
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .collect::<Vec<_>>();
+    if gaps.is_empty() {
+        return None;
+    }
+    gaps.sort();
+    Some(gaps[gaps.len() / 2])
+}
+
+/// Minutes between fetches requested by the feed itself. `<ttl>` wins over
+/// the syndication module because it is the more specific of the two.
+pub fn feed_update_interval_hint(channel: &Channel) -> Option<i32> {
+    if let Some(ttl) = channel
+        .ttl()
+        .and_then(|ttl| ttl.trim().parse::<i32>().ok())
+        .filter(|ttl| *ttl > 0)
+    {
+        return Some(ttl);
+    }
+
+    let syndication = channel.syndication_ext()?;
+    let period_minutes = match syndication.period() {
+        UpdatePeriod::Hourly => 60,
+        UpdatePeriod::Daily => 24 * 60,
+        UpdatePeriod::Weekly => 7 * 24 * 60,
+        UpdatePeriod::Monthly => 30 * 24 * 60,
+        UpdatePeriod::Yearly => 365 * 24 * 60,
+    };
+    let frequency = syndication.frequency().max(1) as i32;
+    Some(period_minutes / frequency)
+}
+
+fn parse_id(id: &str) -> Result<Uuid, CustomError> {
+    Uuid::parse_str(id).map_err(|_| {
+        CustomErrorInner::BadRequest(format!("Invalid UUID: {id}"), ErrorSeverity::Warning).into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn setting(refresh_mode: &str, refresh_interval: i32) -> PodcastSetting {
+        PodcastSetting {
+            refresh_mode: refresh_mode.to_string(),
+            refresh_interval,
+            ..Default::default()
+        }
+    }
+
+    fn day(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 6, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn fixed_mode_uses_the_podcast_interval_or_the_global_one() {
+        assert_eq!(
+            refresh_interval(Some(&setting("fixed", 90)), None, None, 300),
+            Duration::minutes(90)
+        );
+        assert_eq!(
+            refresh_interval(Some(&setting("fixed", 0)), Some(5), None, 300),
+            Duration::minutes(300)
+        );
+        assert_eq!(
+            refresh_interval(None, None, None, 300),
+            Duration::minutes(300)
+        );
+    }
+
+    #[test]
+    fn adaptive_mode_follows_the_publish_cadence_within_bounds() {
+        let adaptive = setting("adaptive", 0);
+        assert_eq!(
+            refresh_interval(Some(&adaptive), None, Some(Duration::days(1)), 300),
+            Duration::hours(6)
+        );
+        assert_eq!(
+            refresh_interval(Some(&adaptive), None, Some(Duration::days(365)), 300),
+            Duration::minutes(MAX_ADAPTIVE_INTERVAL_MINUTES)
+        );
+        assert_eq!(
+            refresh_interval(Some(&adaptive), None, Some(Duration::minutes(20)), 300),
+            Duration::minutes(MIN_ADAPTIVE_INTERVAL_MINUTES)
+        );
+        // Without a cadence the configured interval is the starting point.
+        assert_eq!(
+            refresh_interval(Some(&setting("adaptive", 60)), None, None, 300),
+            Duration::minutes(60)
+        );
+    }
+
+    #[test]
+    fn adaptive_mode_respects_the_feed_hint() {
+        assert_eq!(
+            refresh_interval(
+                Some(&setting("adaptive", 0)),
+                Some(12 * 60),
+                Some(Duration::days(1)),
+                300
+            ),
+            Duration::hours(12)
+        );
+    }
+
+    #[test]
+    fn publish_cadence_is_the_median_gap() {
+        assert_eq!(publish_cadence([day(1)]), None);
+        assert_eq!(
+            publish_cadence([day(8), day(1), day(2), day(3), day(3)]),
+            Some(Duration::days(1))
+        );
+    }
+
+    #[test]
+    fn feed_hint_prefers_ttl_over_syndication() {
+        let feed = |extra: &str| {
+            Channel::read_from(
+                format!(
+                    r#"<rss version="2.0" xmlns:sy="http://purl.org/rss/1.0/modules/syndication/">
+                    <channel><title>t</title><link>l</link><description>d</description>
+                    <sy:updatePeriod>daily</sy:updatePeriod><sy:updateFrequency>2</sy:updateFrequency>
+                    {extra}</channel></rss>"#
+                )
+                .as_bytes(),
+            )
+            .unwrap()
+        };
+        assert_eq!(feed_update_interval_hint(&feed("")), Some(12 * 60));
+        assert_eq!(feed_update_interval_hint(&feed("<ttl>45</ttl>")), Some(45));
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod db_tests {
+    use super::*;
+    use crate::services::podcast::service::PodcastService;
+    use crate::test_support::tests::{GLOBAL_MUTEX, ensure_test_env_vars};
+    use podfetch_persistence::db::run_migrations;
+
+    fn podcast() -> Podcast {
+        let slug = format!("refresh-schedule-{}", Uuid::new_v4());
+        PodcastService::add_podcast_to_database(
+            &slug,
+            &slug,
+            &format!("https://example.com/{slug}.xml"),
+            "http://localhost:8080/ui/default.jpg",
+            &slug,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn only_podcasts_whose_refresh_is_due_are_returned() {
+        ensure_test_env_vars();
+        let _guard = GLOBAL_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        run_migrations();
+        let service = RefreshScheduleService::default_service();
+        let now = Utc::now().naive_utc();
+        let never_scheduled = podcast();
+        let scheduled = podcast();
+
+        let next_refresh_at = service.schedule_next_refresh(&scheduled, now).unwrap();
+        assert_eq!(
+            next_refresh_at,
+            now + Duration::minutes(i64::from(ENVIRONMENT_SERVICE.get_polling_interval()))
+        );
+
+        let due_ids = |at: NaiveDateTime| {
+            service
+                .due_podcasts(vec![never_scheduled.clone(), scheduled.clone()], at)
+                .unwrap()
+                .into_iter()
+                .map(|podcast| podcast.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(due_ids(now), vec![never_scheduled.id.clone()]);
+        assert_eq!(
+            due_ids(next_refresh_at),
+            vec![never_scheduled.id.clone(), scheduled.id.clone()]
+        );
+    }
+}
@@ -4,10 +4,14 @@ use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisod
 use crate::controllers::controller_utils::unwrap_string;
 use crate::podcast::map_podcast_with_context_to_dto;
 use crate::podcast::{ItunesWrapper, PodcastDto, PodcastInsertModel, PodindexResponse};
+use crate::podcast_settings::PodcastSetting;
 use crate::server::ChatServerHandle;
+use crate::services::download::queue::{AUTOMATIC_PRIORITY, DownloadQueueService};
+use crate::services::feed_health::service::FeedHealthService;
 use crate::services::file::service::FileService;
 use crate::services::podcast::metadata::PodcastExtra;
 use crate::services::podcast_settings::service::PodcastSettingsService;
+use crate::services::storage_quota::service::StorageQuotaService;
 use crate::services::tag::service::TagService;
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
 use common_infrastructure::error::ErrorSeverity::{Critical, Error};
@@ -31,7 +35,6 @@ use reqwest::header::{HeaderMap, HeaderValue};
 use rss::Channel;
 use serde_json::Value;
 use sha1::{Digest, Sha1};
-use std::thread;
 use std::time::SystemTime;
 use tokio::task::spawn_blocking;
 use uuid::Uuid;
@@ -202,6 +205,18 @@ impl PodcastService {
         podcast_insert: PodcastInsertModel,
         channel: Option<Channel>,
         added_by: Option<Uuid>,
+    ) -> Result<Podcast, CustomError> {
+        Self::handle_insert_of_podcast_with_settings(podcast_insert, channel, added_by, None).await
+    }
+
+    /// Like [`Self::handle_insert_of_podcast`], but stores `settings` before
+    /// the first episodes are fetched so they already decide what gets
+    /// downloaded.
+    pub async fn handle_insert_of_podcast_with_settings(
+        podcast_insert: PodcastInsertModel,
+        channel: Option<Channel>,
+        added_by: Option<Uuid>,
+        settings: Option<PodcastSetting>,
     ) -> Result<Podcast, CustomError> {
         let opt_podcast = podcast_repo()
             .find_by_rss_feed(&podcast_insert.feed_url)
@@ -244,6 +259,16 @@ impl PodcastService {
             .map_err(CustomError::from)?;
         match podcast {
             Some(podcast) => {
+                if let Some(settings) = settings {
+                    let settings = PodcastSetting {
+                        podcast_id: podcast.id.clone(),
+                        ..settings
+                    };
+                    if let Err(err) = PodcastSettingsService::update_settings_for_podcast(settings)
+                    {
+                        tracing::warn!("Could not apply the settings of {}: {err}", podcast.name);
+                    }
+                }
                 ChatServerHandle::broadcast_podcast_downloaded(podcast.clone());
                 let join_result = spawn_blocking(move || {
                     tracing::debug!("Inserting podcast episodes of {}", podcast.name);
@@ -286,40 +311,21 @@ impl PodcastService {
             PodcastSettingsService::get_settings_for_podcast(parse_id(&podcast.id)?)?;
         match settings {
             Some(settings) => {
-                if (podcast_settings.is_some() && podcast_settings.unwrap().auto_download)
-                    || settings.auto_download
+                if ((podcast_settings.is_some() && podcast_settings.unwrap().auto_download)
+                    || settings.auto_download)
+                    && StorageQuotaService::default_service().allows_automatic_download(podcast)?
                 {
                     let result =
                         PodcastEpisodeService::get_last_n_podcast_episodes(podcast.clone())?;
-                    // Bound parallel downloads (and therefore inline Opus
-                    // transcodes) by the configured setting; never below 1.
-                    let max_parallel_downloads = settings.max_parallel_downloads.max(1) as usize;
-                    for chunk in result.chunks(max_parallel_downloads) {
-                        let mut handles = Vec::with_capacity(chunk.len());
-                        for podcast_episode in chunk.iter().cloned() {
-                            if podcast_episode.deleted {
-                                continue;
-                            }
-                            let podcast_for_thread = podcast.clone();
-                            handles.push(thread::spawn(move || {
-                                if let Err(err) = PodcastEpisodeService::download_podcast_episode_if_not_locally_available(
-                                    podcast_episode,
-                                    podcast_for_thread,
-                                ) {
-                                    tracing::error!("Error downloading podcast episode: {err}");
-                                }
-                            }));
-                        }
-
-                        for handle in handles {
-                            if let Err(err) = handle.join() {
-                                tracing::error!(
-                                    "Error joining download worker for podcast {}: {:?}",
-                                    podcast.id,
-                                    err
-                                );
-                            }
-                        }
+                    // The download worker picks these up, at most
+                    // `max_parallel_downloads` at a time.
+                    let queued = DownloadQueueService::default_service().enqueue_missing(
+                        result,
+                        AUTOMATIC_PRIORITY,
+                        None,
+                    )?;
+                    if queued > 0 {
+                        tracing::info!("Queued {queued} episode download(s) for {}", podcast.name);
                     }
                 }
                 Ok(())
@@ -333,7 +339,7 @@ impl PodcastService {
 
     pub fn refresh_podcast(podcast: &Podcast) -> Result<(), CustomError> {
         tracing::info!("Refreshing podcast: {}", podcast.name);
-        PodcastEpisodeService::insert_podcast_episodes(podcast)?;
+        PodcastEpisodeService::insert_podcast_episodes_if_changed(podcast)?;
         Self::schedule_episode_download(podcast)
     }
 
@@ -363,6 +369,19 @@ impl PodcastService {
             .map(|rows| rows.into_iter().map(Into::into).collect())
     }
 
+    /// Podcasts the user subscribed to, which is what favoring a podcast does.
+    pub fn get_subscribed_podcasts_raw(user_id: Uuid) -> Result<Vec<Podcast>, CustomError> {
+        favorite_repo()
+            .get_favored_podcasts(user_id)
+            .map(|favored| {
+                favored
+                    .into_iter()
+                    .map(|favored| favored.podcast.into())
+                    .collect()
+            })
+            .map_err(CustomError::from)
+    }
+
     pub fn get_podcast_by_rss_feed(rss_feed: &str) -> Result<Podcast, CustomError> {
         podcast_repo()
             .find_by_rss_feed(rss_feed)
@@ -483,20 +502,7 @@ impl PodcastService {
     }
 
     pub fn query_for_podcast(query: &str) -> Result<Vec<PodcastEpisode>, CustomError> {
-        use diesel::BoolExpressionMethods;
-        use diesel::QueryDsl;
-        use diesel::RunQueryDsl;
-        use diesel::TextExpressionMethods;
-        use podfetch_persistence::db::get_connection;
-        use podfetch_persistence::schema::podcast_episodes::dsl::*;
-
-        podcast_episodes
-            .filter(
-                name.like(format!("%{query}%"))
-                    .or(description.like(format!("%{query}%"))),
-            )
-            .load::<PodcastEpisode>(&mut get_connection())
-            .map_err(|e| common_infrastructure::error::map_db_error(e, ErrorSeverity::Critical))
+        crate::services::search::service::SearchService::default_service().search_episodes(query)
     }
 
     pub fn update_favor_podcast(id: Uuid, favored: bool, user_id: Uuid) -> Result<(), CustomError> {
@@ -579,6 +585,18 @@ impl PodcastService {
             .ok_or_else(|| CustomError::from(CustomErrorInner::NotFound(ErrorSeverity::Warning)))?;
         podcast_repo()
             .update_active(id, !found.active)
+            .map_err(CustomError::from)?;
+        if !found.active
+            && let Err(err) = FeedHealthService::default_service().clear_failures(id)
+        {
+            tracing::warn!("Could not reset the feed health of podcast {id}: {err}");
+        }
+        Ok(())
+    }
+
+    pub fn set_podcast_active(id: Uuid, active: bool) -> Result<(), CustomError> {
+        podcast_repo()
+            .update_active(id, active)
             .map_err(CustomError::from)
     }
 
@@ -0,0 +1,161 @@
+//! Podcasting 2.0 chapters: `<podcast:chapters>` links an item to a JSON
+//! document (`application/json+chapters`) listing its chapters with optional
+//! artwork and links.
+
+use podfetch_domain::podcast_episode_chapter::UpsertPodcastEpisodeChapter;
+use rss::Item;
+use serde::Deserialize;
+use uuid::Uuid;
+
+const JSON_CHAPTERS_TYPE: &str = "application/json+chapters";
+
+#[derive(Debug, Deserialize)]
+struct JsonChapters {
+    chapters: Vec<JsonChapter>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonChapter {
+    start_time: f64,
+    end_time: Option<f64>,
+    title: Option<String>,
+    img: Option<String>,
+    url: Option<String>,
+    /// `false` marks a chapter that only carries artwork or a link and is not
+    /// meant to show up in the table of contents.
+    toc: Option<bool>,
+}
+
+/// URL of the item's JSON chapters document. Like transcripts, the tag may
+/// be keyed by its local or its qualified name.
+pub fn extract_chapters_url(item: &Item) -> Option<String> {
+    let podcast_ns = item.extensions().get("podcast")?;
+    ["chapters", "podcast:chapters"]
+        .into_iter()
+        .filter_map(|key| podcast_ns.get(key))
+        .flatten()
+        .find(|ext| {
+            ext.attrs()
+                .get("type")
+                .is_none_or(|mime_type| mime_type.eq_ignore_ascii_case(JSON_CHAPTERS_TYPE))
+        })
+        .and_then(|ext| ext.attrs().get("url").cloned())
+}
+
+/// Parses a JSON chapters document. Chapters are sorted by start time, a
+/// missing end time is taken from the next chapter's start, and chapters
+/// hidden from the table of contents are dropped.
+pub fn parse_json_chapters(
+    episode_id: Uuid,
+    body: &str,
+) -> Result<Vec<UpsertPodcastEpisodeChapter>, serde_json::Error> {
+    let mut chapters = serde_json::from_str::<JsonChapters>(body)?
+        .chapters
+        .into_iter()
+        .filter(|chapter| chapter.toc != Some(false) && chapter.start_time >= 0.0)
+        .collect::<Vec<_>>();
+    chapters.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    // Start times are unique per episode in the database.
+    chapters.dedup_by_key(|chapter| chapter.start_time as i32);
+
+    let next_starts = chapters
+        .iter()
+        .skip(1)
+        .map(|chapter| Some(chapter.start_time))
+        .chain(std::iter::once(None))
+        .collect::<Vec<_>>();
+    Ok(chapters
+        .into_iter()
+        .zip(next_starts)
+        .map(|(chapter, next_start)| UpsertPodcastEpisodeChapter {
+            episode_id,
+            title: chapter.title.unwrap_or_default(),
+            start_time: chapter.start_time as i32,
+            end_time: chapter.end_time.or(next_start).unwrap_or_default() as i32,
+            href: chapter.url,
+            image: chapter.img,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rss::extension::{ExtensionBuilder, ExtensionMap};
+    use std::collections::BTreeMap;
+
+    fn item_with_chapters(attrs: &[(&str, &str)]) -> Item {
+        let mut builder = ExtensionBuilder::default();
+        builder.name("podcast:chapters");
+        builder.attrs(
+            attrs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        );
+        let mut podcast_ns = BTreeMap::new();
+        podcast_ns.insert("chapters".to_string(), vec![builder.build()]);
+        let mut extensions = ExtensionMap::new();
+        extensions.insert("podcast".to_string(), podcast_ns);
+        Item {
+            extensions,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn extract_chapters_url_reads_json_chapters_only() {
+        let json = item_with_chapters(&[
+            ("url", "https://example.com/ep1.json"),
+            ("type", "application/json+chapters"),
+        ]);
+        assert_eq!(
+            extract_chapters_url(&json).as_deref(),
+            Some("https://example.com/ep1.json")
+        );
+
+        let psc = item_with_chapters(&[
+            ("url", "https://example.com/ep1.xml"),
+            ("type", "application/xml+psc"),
+        ]);
+        assert_eq!(extract_chapters_url(&psc), None);
+        assert_eq!(extract_chapters_url(&Item::default()), None);
+    }
+
+    #[test]
+    fn parse_json_chapters_fills_end_times_and_drops_hidden_chapters() {
+        let episode_id = Uuid::new_v4();
+        let body = r#"{
+            "version": "1.2.0",
+            "chapters": [
+                {"startTime": 95.5, "title": "Main topic", "url": "https://example.com/topic"},
+                {"startTime": 0, "title": "Intro", "img": "https://example.com/intro.png"},
+                {"startTime": 60, "img": "https://example.com/ad.png", "toc": false},
+                {"startTime": 300, "endTime": 420, "title": "Outro"}
+            ]
+        }"#;
+
+        let chapters = parse_json_chapters(episode_id, body).unwrap();
+
+        assert_eq!(chapters.len(), 3);
+        assert_eq!(chapters[0].title, "Intro");
+        assert_eq!((chapters[0].start_time, chapters[0].end_time), (0, 95));
+        assert_eq!(
+            chapters[0].image.as_deref(),
+            Some("https://example.com/intro.png")
+        );
+        assert_eq!((chapters[1].start_time, chapters[1].end_time), (95, 300));
+        assert_eq!(
+            chapters[1].href.as_deref(),
+            Some("https://example.com/topic")
+        );
+        assert_eq!((chapters[2].start_time, chapters[2].end_time), (300, 420));
+        assert!(chapters.iter().all(|c| c.episode_id == episode_id));
+    }
+
+    #[test]
+    fn parse_json_chapters_rejects_other_documents() {
+        assert!(parse_json_chapters(Uuid::new_v4(), "<chapters/>").is_err());
+    }
+}
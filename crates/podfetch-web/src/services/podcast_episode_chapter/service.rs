@@ -1,11 +1,20 @@
 use crate::services::download::chapter::Chapter;
+use crate::services::podcast_episode_chapter::feed::parse_json_chapters;
 use crate::settings::UpsertPodcastEpisodeChapter;
-use common_infrastructure::error::CustomError;
-use podfetch_domain::podcast_episode_chapter::PodcastEpisodeChapterRepository;
+use common_infrastructure::error::{
+    CustomError, CustomErrorInner, ErrorSeverity, map_reqwest_error,
+};
+use common_infrastructure::http::{COMMON_USER_AGENT, get_sync_client};
+use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
+use podfetch_domain::podcast_episode_chapter::{
+    ChapterSource, PodcastEpisodeChapterRepository, UpsertPodcastEpisodeChapter as FeedChapter,
+};
 use podfetch_persistence::adapters::PodcastEpisodeChapterRepositoryImpl;
 use podfetch_persistence::db::database;
 use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
+use reqwest::header::USER_AGENT;
 use std::sync::Arc;
+use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct PodcastEpisodeChapterService {
@@ -49,6 +58,67 @@ impl PodcastEpisodeChapterService {
         )
     }
 
+    /// Fetches the episode's JSON chapters document and replaces the stored
+    /// chapters with it. Returns false without a request when the chapters
+    /// were already fetched from the same URL, or when fetching them failed
+    /// recently; failures are recorded and retried less and less often.
+    pub fn sync_feed_chapters(&self, episode_id: Uuid, url: &str) -> Result<bool, CustomError> {
+        let already_synced = self
+            .repository
+            .get_by_episode_id(episode_id)?
+            .iter()
+            .any(|chapter| {
+                chapter.source == ChapterSource::Feed && chapter.source_url.as_deref() == Some(url)
+            });
+        if already_synced {
+            return Ok(false);
+        }
+        let now = chrono::Utc::now().naive_utc();
+        if let Some(failure) = self.repository.get_fetch_failure(episode_id)?
+            && failure.url == url
+            && now < failure.retry_at()
+        {
+            return Ok(false);
+        }
+
+        match self.fetch_feed_chapters(episode_id, url) {
+            Ok(chapters) => {
+                self.repository
+                    .replace_with_feed_chapters(episode_id, url, chapters)?;
+                self.repository.clear_fetch_failure(episode_id)?;
+                Ok(true)
+            }
+            Err(err) => {
+                self.repository
+                    .record_fetch_failure(episode_id, url, &err.to_string(), now)?;
+                Err(err)
+            }
+        }
+    }
+
+    fn fetch_feed_chapters(
+        &self,
+        episode_id: Uuid,
+        url: &str,
+    ) -> Result<Vec<FeedChapter>, CustomError> {
+        let client = get_sync_client(&ENVIRONMENT_SERVICE)
+            .build()
+            .map_err(map_reqwest_error)?;
+        let body = client
+            .get(url)
+            .header(USER_AGENT, COMMON_USER_AGENT)
+            .send()
+            .and_then(|resp| resp.error_for_status())
+            .and_then(|resp| resp.text())
+            .map_err(map_reqwest_error)?;
+        parse_json_chapters(episode_id, &body).map_err(|err| {
+            CustomError::from(CustomErrorInner::BadRequest(
+                format!("Invalid chapters document {url}: {err}"),
+                ErrorSeverity::Warning,
+            ))
+        })
+    }
+
     pub fn get_chapters_by_episode_id(
         &self,
         episode_id: uuid::Uuid,
@@ -57,3 +127,189 @@ impl PodcastEpisodeChapterService {
         self.repository.get_by_episode_id(episode_id)
     }
 }
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::services::podcast::service::PodcastService;
+    use crate::test_support::tests::{GLOBAL_MUTEX, ensure_test_env_vars};
+    use axum::Router;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use diesel::prelude::*;
+    use podfetch_domain::podcast_episode_chapter::UpsertPodcastEpisodeChapter as DomainUpsert;
+    use podfetch_persistence::db::{get_connection, run_migrations};
+    use podfetch_persistence::schema::podcast_episodes::dsl as pe_dsl;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const CHAPTERS: &str = r#"{"version": "1.2.0", "chapters": [
+        {"startTime": 0, "title": "Intro"},
+        {"startTime": 30, "title": "Interview", "img": "https://example.com/guest.png"}
+    ]}"#;
+
+    fn spawn_mock_server(app: Router) -> String {
+        let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().expect("build mock server runtime");
+            rt.block_on(async move {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+                    .await
+                    .expect("bind mock chapters server");
+                addr_tx.send(listener.local_addr().unwrap()).unwrap();
+                axum::serve(listener, app).await.unwrap();
+            });
+        });
+        let addr = addr_rx.recv().expect("mock server address");
+        format!("http://{addr}")
+    }
+
+    fn seed_episode() -> Uuid {
+        let slug = format!("feed-chapters-{}", Uuid::new_v4());
+        let podcast = PodcastService::add_podcast_to_database(
+            &slug,
+            &slug,
+            &format!("https://example.com/{slug}.xml"),
+            "http://localhost:8080/ui/default.jpg",
+            &slug,
+        )
+        .unwrap();
+        let episode_id = Uuid::new_v4();
+        diesel::insert_into(pe_dsl::podcast_episodes)
+            .values((
+                pe_dsl::id.eq(episode_id.to_string()),
+                pe_dsl::podcast_id.eq(podcast.id),
+                pe_dsl::episode_id.eq(Uuid::new_v4().to_string()),
+                pe_dsl::name.eq("Feed Chapters Episode".to_string()),
+                pe_dsl::url.eq(format!("https://example.com/{episode_id}.mp3")),
+                pe_dsl::date_of_recording.eq("2026-03-01T00:00:00Z".to_string()),
+                pe_dsl::image_url.eq("http://localhost:8080/ui/default.jpg".to_string()),
+                pe_dsl::total_time.eq(1800),
+                pe_dsl::description.eq("feed chapters test".to_string()),
+                pe_dsl::guid.eq(Uuid::new_v4().to_string()),
+                pe_dsl::deleted.eq(false),
+                pe_dsl::episode_numbering_processed.eq(false),
+            ))
+            .execute(&mut get_connection())
+            .unwrap();
+        episode_id
+    }
+
+    fn embedded_chapter(episode_id: Uuid, start_time: i32) -> DomainUpsert {
+        DomainUpsert {
+            episode_id,
+            title: "Embedded".to_string(),
+            start_time,
+            end_time: 0,
+            href: None,
+            image: None,
+        }
+    }
+
+    #[test]
+    fn feed_chapters_replace_and_then_shadow_embedded_chapters() {
+        ensure_test_env_vars();
+        let _guard = GLOBAL_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        run_migrations();
+        let service = PodcastEpisodeChapterService::default_service();
+        let episode_id = seed_episode();
+        service
+            .repository
+            .upsert(embedded_chapter(episode_id, 10))
+            .unwrap();
+
+        let base =
+            spawn_mock_server(Router::new().route("/chapters.json", get(|| async { CHAPTERS })));
+        let url = format!("{base}/chapters.json");
+        assert!(service.sync_feed_chapters(episode_id, &url).unwrap());
+
+        // Chapters read from the downloaded file no longer apply.
+        service
+            .repository
+            .upsert(embedded_chapter(episode_id, 60))
+            .unwrap();
+
+        let chapters = service.get_chapters_by_episode_id(episode_id).unwrap();
+        let mut starts = chapters
+            .iter()
+            .map(|chapter| (chapter.start_time, chapter.source))
+            .collect::<Vec<_>>();
+        starts.sort_by_key(|(start, _)| *start);
+        assert_eq!(
+            starts,
+            vec![(0, ChapterSource::Feed), (30, ChapterSource::Feed)]
+        );
+        assert!(
+            chapters
+                .iter()
+                .any(|chapter| chapter.image.as_deref() == Some("https://example.com/guest.png"))
+        );
+
+        assert!(!service.sync_feed_chapters(episode_id, &url).unwrap());
+    }
+
+    #[test]
+    fn failed_chapter_documents_are_not_requested_on_every_refresh() {
+        ensure_test_env_vars();
+        let _guard = GLOBAL_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        run_migrations();
+        let service = PodcastEpisodeChapterService::default_service();
+        let episode_id = seed_episode();
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let base = spawn_mock_server(Router::new().route(
+            "/missing.json",
+            get(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { (StatusCode::NOT_FOUND, "gone") }
+            }),
+        ));
+        let url = format!("{base}/missing.json");
+
+        assert!(service.sync_feed_chapters(episode_id, &url).is_err());
+        assert!(!service.sync_feed_chapters(episode_id, &url).unwrap());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        let failure = service
+            .repository
+            .get_fetch_failure(episode_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(failure.failures, 1);
+        assert_eq!(failure.url, url);
+
+        // Once the back-off has passed, the document is requested again.
+        let long_ago = chrono::Utc::now().naive_utc() - chrono::Duration::hours(3);
+        service
+            .repository
+            .record_fetch_failure(episode_id, &url, "404 Not Found", long_ago)
+            .unwrap();
+        assert!(service.sync_feed_chapters(episode_id, &url).is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            service
+                .repository
+                .get_fetch_failure(episode_id)
+                .unwrap()
+                .map(|failure| failure.failures),
+            Some(3)
+        );
+
+        // A new URL in the feed is tried right away, and a success clears
+        // the failure.
+        let chapters =
+            spawn_mock_server(Router::new().route("/chapters.json", get(|| async { CHAPTERS })));
+        assert!(
+            service
+                .sync_feed_chapters(episode_id, &format!("{chapters}/chapters.json"))
+                .unwrap()
+        );
+        assert_eq!(
+            service.repository.get_fetch_failure(episode_id).unwrap(),
+            None
+        );
+    }
+}
@@ -1,7 +1,12 @@
 use crate::podcast_settings::PodcastSetting;
 use crate::services::download::service::DownloadService;
+use crate::services::podcast::refresh_schedule::{
+    REFRESH_MODE_ADAPTIVE, REFRESH_MODE_FIXED, RefreshScheduleService,
+};
 use crate::services::podcast_episode_chapter::service::PodcastEpisodeChapterService;
+use crate::services::transcoding::service::TranscodingProfileService;
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
+use chrono::Utc;
 use common_infrastructure::error::ErrorSeverity::Warning;
 use common_infrastructure::error::{CustomError, CustomErrorInner};
 use podfetch_domain::podcast_settings::PodcastSettingsRepository;
@@ -47,6 +52,37 @@ impl PodcastSettingsService {
         &self,
         setting_to_insert: PodcastSetting,
     ) -> Result<PodcastSetting, CustomError> {
+        if ![REFRESH_MODE_FIXED, REFRESH_MODE_ADAPTIVE]
+            .contains(&setting_to_insert.refresh_mode.as_str())
+        {
+            return Err(CustomErrorInner::BadRequest(
+                format!(
+                    "'{}' is not a valid refresh mode",
+                    setting_to_insert.refresh_mode
+                ),
+                Warning,
+            )
+            .into());
+        }
+        if setting_to_insert.refresh_interval < 0 {
+            return Err(CustomErrorInner::BadRequest(
+                "refresh interval must not be negative".to_string(),
+                Warning,
+            )
+            .into());
+        }
+        if setting_to_insert.retention_keep_newest < 0
+            || setting_to_insert.retention_played_days < 0
+            || setting_to_insert.retention_max_size_mb < 0
+        {
+            return Err(CustomErrorInner::BadRequest(
+                "retention limits must not be negative".to_string(),
+                Warning,
+            )
+            .into());
+        }
+        TranscodingProfileService::default_service()
+            .validate_reference(setting_to_insert.transcoding_profile_id.as_deref())?;
         let updated_setting = self
             .repository
             .upsert_settings(setting_to_insert.clone().into())?;
@@ -103,6 +139,16 @@ impl PodcastSettingsService {
         }
 
         crate::services::nfo::service::ensure_cover_filename(&podcast);
+        // The refresh mode or interval may have changed.
+        if let Err(error) = RefreshScheduleService::default_service()
+            .schedule_next_refresh(&podcast, Utc::now().naive_utc())
+        {
+            tracing::error!(
+                "Error while rescheduling the refresh of podcast {}: {}",
+                podcast.id,
+                error
+            );
+        }
         Ok(updated_setting.into())
     }
 }
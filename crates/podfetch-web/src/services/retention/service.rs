@@ -0,0 +1,480 @@
+//! Retention policies deciding which downloads the cleanup deletes.
+//!
+//! Every podcast is governed by the age limit of the instance (or its own
+//! `auto_cleanup_days`) plus the `retention_*` rules of its settings. A
+//! download is deleted as soon as one rule picks it, unless someone marked
+//! the episode as favorite or the podcast keeps unplayed downloads and the
+//! episode still waits for a listener.
+//!
+//! The listeners of an episode are the users subscribed to its podcast and
+//! everyone who played or triaged the episode. An episode is finished once
+//! each of them played it to the end, archived or dismissed it.
+
+use crate::podcast_settings::PodcastSetting;
+use crate::server::ChatServerHandle;
+use crate::services::favorite_podcast_episode::service::FavoritePodcastEpisodeService;
+use crate::services::file::service::FileService;
+use crate::services::podcast::service::PodcastService;
+use crate::services::podcast_settings::service::PodcastSettingsService;
+use crate::services::storage_quota::service::StorageQuotaService;
+use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
+use chrono::{Duration, NaiveDateTime, Utc};
+use common_infrastructure::error::CustomError;
+use podfetch_domain::episode::EpisodeRepository;
+use podfetch_domain::episode_triage::{EpisodeTriageRepository, TriageStatus};
+use podfetch_domain::favorite::FavoriteRepository;
+use podfetch_domain::user_admin::UserAdminRepository;
+use podfetch_persistence::adapters::{EpisodeTriageRepositoryImpl, UserAdminRepositoryImpl};
+use podfetch_persistence::db::database;
+use podfetch_persistence::episode::DieselEpisodeRepository;
+use podfetch_persistence::favorite::DieselFavoriteRepository;
+use podfetch_persistence::podcast::PodcastEntity as Podcast;
+use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+const BYTES_PER_MB: i64 = 1024 * 1024;
+
+/// The rules that apply to one podcast. `None` turns a rule off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub max_age_days: Option<i32>,
+    pub keep_newest: Option<usize>,
+    pub played_days: Option<i32>,
+    pub keep_unplayed: bool,
+    pub max_bytes: Option<i64>,
+}
+
+impl RetentionPolicy {
+    /// `global_cleanup_days` is the instance-wide age limit, `None` when
+    /// the automatic cleanup is off. A podcast with its own `auto_cleanup`
+    /// uses its own number of days instead.
+    pub fn new(settings: Option<&PodcastSetting>, global_cleanup_days: Option<i32>) -> Self {
+        let Some(settings) = settings else {
+            return Self {
+                max_age_days: global_cleanup_days,
+                ..Self::default()
+            };
+        };
+        Self {
+            max_age_days: global_cleanup_days.map(|days| {
+                if settings.auto_cleanup {
+                    settings.auto_cleanup_days
+                } else {
+                    days
+                }
+            }),
+            keep_newest: usize::try_from(settings.retention_keep_newest)
+                .ok()
+                .filter(|count| *count > 0),
+            played_days: Some(settings.retention_played_days).filter(|days| *days > 0),
+            keep_unplayed: settings.retention_keep_unplayed,
+            max_bytes: Some(settings.retention_max_size_mb)
+                .filter(|mb| *mb > 0)
+                .map(|mb| i64::from(mb) * BYTES_PER_MB),
+        }
+    }
+}
+
+/// The rule that picked a download for deletion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RetentionReason {
+    /// Downloaded longer ago than the cleanup days.
+    Age,
+    /// Not among the newest downloads the podcast keeps.
+    KeepNewest,
+    /// Every listener finished it long enough ago.
+    Played,
+    /// The podcast is above its size cap.
+    SizeCap,
+}
+
+/// What the planner needs to know about one download.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetainedDownload {
+    pub episode_id: Uuid,
+    pub date_of_recording: String,
+    pub downloaded_at: NaiveDateTime,
+    pub bytes: i64,
+    pub favorite: bool,
+    /// When the last listener finished the episode, `None` while someone
+    /// still has to.
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+/// A download the cleanup deletes, or would delete in a dry run.
+#[derive(Debug, Clone)]
+pub struct RetentionCandidate {
+    pub podcast: Podcast,
+    pub episode: PodcastEpisode,
+    pub bytes: i64,
+    pub reason: RetentionReason,
+}
+
+/// How far each user got with an episode.
+#[derive(Debug, Default)]
+struct ListenerStates {
+    subscribers: HashMap<Uuid, HashSet<Uuid>>,
+    listeners: HashMap<Uuid, HashSet<Uuid>>,
+    finished: HashMap<Uuid, HashMap<Uuid, NaiveDateTime>>,
+}
+
+impl ListenerStates {
+    fn finished_at(&self, podcast_id: Uuid, episode_id: Uuid) -> Option<NaiveDateTime> {
+        let mut listeners: HashSet<Uuid> = self
+            .subscribers
+            .get(&podcast_id)
+            .cloned()
+            .unwrap_or_default();
+        listeners.extend(self.listeners.get(&episode_id).into_iter().flatten());
+        if listeners.is_empty() {
+            return None;
+        }
+        let finished = self.finished.get(&episode_id)?;
+        listeners
+            .iter()
+            .map(|user_id| finished.get(user_id).copied())
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .max()
+    }
+
+    fn mark_finished(&mut self, user_id: Uuid, episode_id: Uuid, at: NaiveDateTime) {
+        self.finished
+            .entry(episode_id)
+            .or_default()
+            .entry(user_id)
+            .and_modify(|first| *first = (*first).min(at))
+            .or_insert(at);
+    }
+}
+
+pub struct RetentionService {
+    users: Arc<dyn UserAdminRepository<Error = CustomError>>,
+    triage: Arc<dyn EpisodeTriageRepository<Error = CustomError>>,
+}
+
+impl RetentionService {
+    pub fn new(
+        users: Arc<dyn UserAdminRepository<Error = CustomError>>,
+        triage: Arc<dyn EpisodeTriageRepository<Error = CustomError>>,
+    ) -> Self {
+        Self { users, triage }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(
+            Arc::new(UserAdminRepositoryImpl::new(database())),
+            Arc::new(EpisodeTriageRepositoryImpl::new(database())),
+        )
+    }
+
+    /// The downloads the cleanup would delete right now, optionally only
+    /// those of one podcast.
+    pub fn preview(
+        &self,
+        global_cleanup_days: Option<i32>,
+        podcast_id: Option<Uuid>,
+    ) -> Result<Vec<RetentionCandidate>, CustomError> {
+        let podcasts = match podcast_id {
+            Some(podcast_id) => vec![PodcastService::get_podcast(podcast_id)?],
+            None => PodcastService::get_all_podcasts_raw()?,
+        };
+        let states = self.listener_states()?;
+        let sizes = StorageQuotaService::default_service().recorded_sizes()?;
+        let favorites = FavoritePodcastEpisodeService::default_service();
+        let now = Utc::now().naive_utc();
+
+        let mut candidates = Vec::new();
+        for podcast in podcasts {
+            let Ok(podcast_uuid) = Uuid::parse_str(&podcast.id) else {
+                continue;
+            };
+            let settings = PodcastSettingsService::get_settings_for_podcast(podcast_uuid)?;
+            let policy = RetentionPolicy::new(settings.as_ref(), global_cleanup_days);
+            if policy == RetentionPolicy::default() {
+                continue;
+            }
+
+            let mut episodes = HashMap::new();
+            let mut downloads = Vec::new();
+            for episode in PodcastEpisodeService::get_episodes_by_podcast_id(podcast_uuid)? {
+                let (true, Some(downloaded_at), Ok(episode_id)) = (
+                    episode.is_downloaded(),
+                    episode.download_time,
+                    Uuid::parse_str(&episode.id),
+                ) else {
+                    continue;
+                };
+                downloads.push(RetainedDownload {
+                    episode_id,
+                    date_of_recording: episode.date_of_recording.clone(),
+                    downloaded_at,
+                    bytes: sizes.get(&episode_id).copied().unwrap_or_default(),
+                    // Same as the quota cleanup: when in doubt, keep it.
+                    favorite: favorites.is_liked_by_someone(episode_id).unwrap_or(true),
+                    finished_at: states.finished_at(podcast_uuid, episode_id),
+                });
+                episodes.insert(episode_id, episode);
+            }
+
+            for (episode_id, reason) in plan(&policy, &downloads, now) {
+                let Some(episode) = episodes.remove(&episode_id) else {
+                    continue;
+                };
+                candidates.push(RetentionCandidate {
+                    podcast: podcast.clone(),
+                    episode,
+                    bytes: sizes.get(&episode_id).copied().unwrap_or_default(),
+                    reason,
+                });
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Deletes what [`Self::preview`] lists for all podcasts. Returns how
+    /// many downloads were deleted.
+    pub fn apply(&self, global_cleanup_days: Option<i32>) -> Result<usize, CustomError> {
+        let candidates = self.preview(global_cleanup_days, None)?;
+        tracing::info!("Cleaning up {} downloads", candidates.len());
+
+        let mut deleted = 0;
+        for candidate in candidates {
+            let episode = candidate.episode;
+            if let Err(err) = FileService::cleanup_old_episode(&episode) {
+                tracing::error!("Error deleting download of episode {}: {err}", episode.id);
+                continue;
+            }
+            let Ok(episode_id) = Uuid::parse_str(&episode.id) else {
+                continue;
+            };
+            if let Err(err) = PodcastEpisodeService::remove_download_status_of_episode(episode_id) {
+                tracing::error!(
+                    "Error clearing download status for episode {}: {err}",
+                    episode.id
+                );
+                continue;
+            }
+            ChatServerHandle::broadcast_podcast_episode_deleted_locally(&episode);
+            deleted += 1;
+        }
+        Ok(deleted)
+    }
+
+    fn listener_states(&self) -> Result<ListenerStates, CustomError> {
+        let favorites = DieselFavoriteRepository::new(database());
+        let episodes = DieselEpisodeRepository::new(database());
+        let mut states = ListenerStates::default();
+        for user in self.users.find_all()? {
+            for favorite in favorites
+                .find_favored_by_user_id(user.id)
+                .map_err(CustomError::from)?
+            {
+                states
+                    .subscribers
+                    .entry(favorite.podcast_id)
+                    .or_default()
+                    .insert(user.id);
+            }
+
+            for triage in self.triage.list_by_user(user.id)? {
+                states
+                    .listeners
+                    .entry(triage.episode_id)
+                    .or_default()
+                    .insert(user.id);
+                // Queued episodes are still to be listened to; archived and
+                // dismissed ones are done with.
+                if triage.status != TriageStatus::Queued {
+                    states.mark_finished(user.id, triage.episode_id, triage.updated_at);
+                }
+            }
+
+            for watched in episodes
+                .find_last_watched_episodes(&user.username)
+                .map_err(CustomError::from)?
+            {
+                let (episode_id, action) = (watched.podcast_episode.id, watched.episode_action);
+                states
+                    .listeners
+                    .entry(episode_id)
+                    .or_default()
+                    .insert(user.id);
+                if let (Some(position), Some(total)) = (action.position, action.total)
+                    && total > 0
+                    && position >= total
+                {
+                    states.mark_finished(user.id, episode_id, action.timestamp);
+                }
+            }
+        }
+        Ok(states)
+    }
+}
+
+/// Picks the downloads of one podcast the policy deletes, newest first.
+/// The size cap is applied last and only to what the other rules left.
+pub fn plan(
+    policy: &RetentionPolicy,
+    downloads: &[RetainedDownload],
+    now: NaiveDateTime,
+) -> Vec<(Uuid, RetentionReason)> {
+    let mut newest_first: Vec<&RetainedDownload> = downloads.iter().collect();
+    newest_first.sort_by(|a, b| b.date_of_recording.cmp(&a.date_of_recording));
+    let is_kept = |download: &RetainedDownload| {
+        download.favorite || (policy.keep_unplayed && download.finished_at.is_none())
+    };
+
+    let mut doomed = Vec::new();
+    for (index, download) in newest_first.iter().enumerate() {
+        if is_kept(download) {
+            continue;
+        }
+        let reason = if policy
+            .max_age_days
+            .is_some_and(|days| download.downloaded_at < now - Duration::days(i64::from(days)))
+        {
+            RetentionReason::Age
+        } else if policy.keep_newest.is_some_and(|count| index >= count) {
+            RetentionReason::KeepNewest
+        } else if let (Some(days), Some(finished_at)) = (policy.played_days, download.finished_at)
+            && finished_at + Duration::days(i64::from(days)) <= now
+        {
+            RetentionReason::Played
+        } else {
+            continue;
+        };
+        doomed.push((download.episode_id, reason));
+    }
+
+    if let Some(max_bytes) = policy.max_bytes {
+        let is_doomed =
+            |download: &RetainedDownload| doomed.iter().any(|(id, _)| *id == download.episode_id);
+        let mut total: i64 = newest_first
+            .iter()
+            .filter(|download| !is_doomed(download))
+            .map(|download| download.bytes)
+            .sum();
+        let mut over_cap = Vec::new();
+        for download in newest_first.iter().rev() {
+            if total <= max_bytes {
+                break;
+            }
+            if is_kept(download) || is_doomed(download) {
+                continue;
+            }
+            total -= download.bytes;
+            over_cap.push((download.episode_id, RetentionReason::SizeCap));
+        }
+        doomed.extend(over_cap.into_iter().rev());
+    }
+    doomed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RetainedDownload, RetentionPolicy, RetentionReason, plan};
+    use chrono::{NaiveDate, NaiveDateTime};
+    use uuid::Uuid;
+
+    fn day(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    fn download(day_of_month: u32) -> RetainedDownload {
+        RetainedDownload {
+            episode_id: Uuid::new_v4(),
+            date_of_recording: format!("2026-10-{day_of_month:02}T06:00:00+00:00"),
+            downloaded_at: day(day_of_month),
+            bytes: 100,
+            favorite: false,
+            finished_at: None,
+        }
+    }
+
+    #[test]
+    fn keeps_the_newest_downloads_and_spares_favorites() {
+        let mut favorite = download(1);
+        favorite.favorite = true;
+        let downloads = vec![download(2), favorite, download(4), download(3)];
+        let policy = RetentionPolicy {
+            keep_newest: Some(2),
+            ..Default::default()
+        };
+
+        let doomed = plan(&policy, &downloads, day(20));
+
+        assert_eq!(
+            doomed,
+            vec![(downloads[0].episode_id, RetentionReason::KeepNewest)]
+        );
+    }
+
+    #[test]
+    fn played_downloads_go_once_the_grace_period_is_over() {
+        let mut heard_long_ago = download(1);
+        heard_long_ago.finished_at = Some(day(5));
+        let mut heard_yesterday = download(2);
+        heard_yesterday.finished_at = Some(day(19));
+        let downloads = vec![heard_long_ago, heard_yesterday, download(3)];
+        let policy = RetentionPolicy {
+            played_days: Some(7),
+            ..Default::default()
+        };
+
+        let doomed = plan(&policy, &downloads, day(20));
+
+        assert_eq!(
+            doomed,
+            vec![(downloads[0].episode_id, RetentionReason::Played)]
+        );
+    }
+
+    #[test]
+    fn unplayed_downloads_survive_the_age_limit() {
+        let mut played = download(1);
+        played.finished_at = Some(day(2));
+        let downloads = vec![played, download(2)];
+        let policy = RetentionPolicy {
+            max_age_days: Some(3),
+            keep_unplayed: true,
+            ..Default::default()
+        };
+
+        let doomed = plan(&policy, &downloads, day(20));
+
+        assert_eq!(
+            doomed,
+            vec![(downloads[0].episode_id, RetentionReason::Age)]
+        );
+    }
+
+    #[test]
+    fn size_cap_deletes_the_oldest_remaining_downloads() {
+        let downloads = vec![download(1), download(2), download(3), download(4)];
+        let policy = RetentionPolicy {
+            keep_newest: Some(3),
+            max_bytes: Some(150),
+            ..Default::default()
+        };
+
+        let doomed = plan(&policy, &downloads, day(20));
+
+        assert_eq!(
+            doomed,
+            vec![
+                (downloads[0].episode_id, RetentionReason::KeepNewest),
+                (downloads[2].episode_id, RetentionReason::SizeCap),
+                (downloads[1].episode_id, RetentionReason::SizeCap),
+            ]
+        );
+    }
+}
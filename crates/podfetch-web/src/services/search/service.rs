@@ -0,0 +1,395 @@
+//! Unified full-text search over podcasts, episode titles and show notes, and
+//! transcripts. Metadata hits come from the FTS index maintained by the
+//! database; transcript hits come from [`TranscriptService::search`] and are
+//! merged into the episode results so an episode shows up once, with both its
+//! highlighted metadata and the matching transcript segments.
+
+use crate::services::transcript::service::{TranscriptSearchGroup, TranscriptService};
+use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::podcast_episode_transcript::TranscriptSearchHit;
+use podfetch_domain::search::{
+    EpisodeSearchHit, PodcastSearchHit, SearchFilter, SearchQuery, SearchRepository,
+};
+use podfetch_domain::tag::TagRepository;
+use podfetch_domain::user::User;
+use podfetch_persistence::adapters::{SearchRepositoryImpl, TagRepositoryImpl};
+use podfetch_persistence::db::database;
+use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Podcasts and episodes returned per page, each.
+pub const SEARCH_PAGE_SIZE: i64 = 20;
+
+/// Upper bound on the episodes [`SearchService::search_episodes`] collects
+/// across all pages.
+pub const MAX_QUERY_RESULTS: usize = 500;
+
+/// An episode matched by its metadata, its transcript, or both.
+#[derive(Debug, Clone)]
+pub struct EpisodeSearchResult {
+    pub episode: PodcastEpisode,
+    pub name_highlight: Option<String>,
+    pub description_snippet: Option<String>,
+    pub transcript_hits: Vec<TranscriptSearchHit>,
+    /// Metadata rank plus the rank of the best transcript segment.
+    pub rank: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchResults {
+    pub podcasts: Vec<PodcastSearchHit>,
+    pub episodes: Vec<EpisodeSearchResult>,
+}
+
+/// Hits for one episode before the episode row is loaded.
+#[derive(Debug, Clone)]
+struct MergedEpisodeHit {
+    episode_id: Uuid,
+    metadata: Option<EpisodeSearchHit>,
+    transcript_hits: Vec<TranscriptSearchHit>,
+    rank: f32,
+}
+
+pub struct SearchService {
+    search_repository: Arc<dyn SearchRepository<Error = CustomError>>,
+    tag_repository: Arc<dyn TagRepository<Error = CustomError>>,
+    transcript_service: Arc<TranscriptService>,
+}
+
+impl SearchService {
+    pub fn new(
+        search_repository: Arc<dyn SearchRepository<Error = CustomError>>,
+        tag_repository: Arc<dyn TagRepository<Error = CustomError>>,
+        transcript_service: Arc<TranscriptService>,
+    ) -> Self {
+        Self {
+            search_repository,
+            tag_repository,
+            transcript_service,
+        }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(
+            Arc::new(SearchRepositoryImpl::new(database())),
+            Arc::new(TagRepositoryImpl::new(database())),
+            Arc::new(TranscriptService::default_service()),
+        )
+    }
+
+    /// Searches everything for `query`. `page` is zero-based and applies to
+    /// podcasts, episode metadata and transcripts alike.
+    pub fn search(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+        requester: &User,
+        page: i64,
+    ) -> Result<SearchResults, CustomError> {
+        let parsed = SearchQuery::parse(query);
+        if parsed.is_empty() {
+            return Ok(SearchResults::default());
+        }
+
+        // Tags are per user, so only the requester's own tags may be used to
+        // filter. Transcript hits are filtered against the tagged podcasts
+        // here since the transcript search only knows about podcasts.
+        let tagged_podcasts = match filter.tag_id {
+            Some(tag_id) => {
+                let tag_id = tag_id.to_string();
+                self.tag_repository
+                    .get_tag_by_id_and_user_id(&tag_id, requester.id)?
+                    .ok_or_else(|| CustomError::from(CustomErrorInner::NotFound(Warning)))?;
+                Some(
+                    self.tag_repository
+                        .get_podcast_ids_of_tag(&tag_id, requester.id)?
+                        .into_iter()
+                        .collect::<HashSet<_>>(),
+                )
+            }
+            None => None,
+        };
+
+        let podcasts = if filter.filters_episodes_only() {
+            Vec::new()
+        } else {
+            self.search_repository
+                .search_podcasts(&parsed, filter, page, SEARCH_PAGE_SIZE)?
+        };
+        let metadata_hits =
+            self.search_repository
+                .search_episodes(&parsed, filter, page, SEARCH_PAGE_SIZE)?;
+        let transcript_groups = self
+            .transcript_service
+            .search(query, filter.podcast_id, page)?;
+
+        let merged = merge_episode_hits(metadata_hits, transcript_groups);
+        let mut loaded = load_episodes(merged.iter().map(|hit| hit.episode_id))?;
+        let mut episodes = Vec::new();
+        for hit in merged {
+            // A hit whose episode row vanished in the meantime is dropped
+            // rather than failing the whole search.
+            let Some(episode) = loaded.remove(&hit.episode_id.to_string()) else {
+                continue;
+            };
+            // Metadata hits were already filtered by the query itself.
+            if hit.metadata.is_none() && !matches_filter(&episode, filter, &tagged_podcasts) {
+                continue;
+            }
+            episodes.push(EpisodeSearchResult {
+                episode,
+                name_highlight: hit.metadata.as_ref().map(|m| m.name_highlight.clone()),
+                description_snippet: hit.metadata.map(|m| m.description_snippet),
+                transcript_hits: hit.transcript_hits,
+                rank: hit.rank,
+            });
+        }
+
+        Ok(SearchResults { podcasts, episodes })
+    }
+
+    /// Episodes whose title or show notes match `query`, best match first.
+    /// Walks the result pages until a short one comes back or
+    /// [`MAX_QUERY_RESULTS`] episodes are collected.
+    pub fn search_episodes(&self, query: &str) -> Result<Vec<PodcastEpisode>, CustomError> {
+        let parsed = SearchQuery::parse(query);
+        let filter = SearchFilter::default();
+        let mut episodes = Vec::new();
+        let mut page = 0;
+        loop {
+            let hits =
+                self.search_repository
+                    .search_episodes(&parsed, &filter, page, SEARCH_PAGE_SIZE)?;
+            let last_page = (hits.len() as i64) < SEARCH_PAGE_SIZE;
+            let mut loaded = load_episodes(hits.iter().map(|hit| hit.episode_id))?;
+            episodes.extend(
+                hits.iter()
+                    .filter_map(|hit| loaded.remove(&hit.episode_id.to_string())),
+            );
+            if last_page || episodes.len() >= MAX_QUERY_RESULTS {
+                episodes.truncate(MAX_QUERY_RESULTS);
+                return Ok(episodes);
+            }
+            page += 1;
+        }
+    }
+}
+
+/// Loads the episodes behind one page of hits in a single query, keyed by id.
+fn load_episodes(
+    ids: impl Iterator<Item = Uuid>,
+) -> Result<HashMap<String, PodcastEpisode>, CustomError> {
+    let ids = ids.collect::<Vec<_>>();
+    Ok(
+        PodcastEpisodeService::get_podcast_episodes_by_internal_ids(&ids)?
+            .into_iter()
+            .map(|episode| (episode.id.clone(), episode))
+            .collect(),
+    )
+}
+
+/// Joins metadata and transcript hits on the episode and orders the result
+/// by the combined rank, best first.
+fn merge_episode_hits(
+    metadata_hits: Vec<EpisodeSearchHit>,
+    transcript_groups: Vec<TranscriptSearchGroup>,
+) -> Vec<MergedEpisodeHit> {
+    let mut merged: Vec<MergedEpisodeHit> = Vec::new();
+    let mut index: HashMap<Uuid, usize> = HashMap::new();
+
+    for hit in metadata_hits {
+        index.insert(hit.episode_id, merged.len());
+        merged.push(MergedEpisodeHit {
+            episode_id: hit.episode_id,
+            rank: hit.rank,
+            metadata: Some(hit),
+            transcript_hits: Vec::new(),
+        });
+    }
+    for group in transcript_groups {
+        let best = group.hits.first().map(|hit| hit.rank).unwrap_or_default();
+        match index.get(&group.episode_id) {
+            Some(&position) => {
+                merged[position].rank += best;
+                merged[position].transcript_hits = group.hits;
+            }
+            None => {
+                index.insert(group.episode_id, merged.len());
+                merged.push(MergedEpisodeHit {
+                    episode_id: group.episode_id,
+                    metadata: None,
+                    transcript_hits: group.hits,
+                    rank: best,
+                });
+            }
+        }
+    }
+
+    merged.sort_by(|a, b| {
+        b.rank
+            .partial_cmp(&a.rank)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    merged
+}
+
+/// Applies the filters the transcript search can't evaluate itself.
+fn matches_filter(
+    episode: &PodcastEpisode,
+    filter: &SearchFilter,
+    tagged_podcasts: &Option<HashSet<Uuid>>,
+) -> bool {
+    if let Some(tagged) = tagged_podcasts {
+        let in_tag = Uuid::parse_str(&episode.podcast_id)
+            .map(|podcast_id| tagged.contains(&podcast_id))
+            .unwrap_or(false);
+        if !in_tag {
+            return false;
+        }
+    }
+    if let Some(downloaded) = filter.downloaded
+        && episode.is_downloaded() != downloaded
+    {
+        return false;
+    }
+    // Recording dates are RFC 3339, so the date part compares as text.
+    let recorded_on = episode.date_of_recording.get(..10).unwrap_or_default();
+    if let Some(from) = filter.from
+        && recorded_on < from.format("%Y-%m-%d").to_string().as_str()
+    {
+        return false;
+    }
+    if let Some(to) = filter.to
+        && recorded_on > to.format("%Y-%m-%d").to_string().as_str()
+    {
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn metadata_hit(episode_id: Uuid, rank: f32) -> EpisodeSearchHit {
+        EpisodeSearchHit {
+            episode_id,
+            podcast_id: Uuid::new_v4(),
+            name_highlight: "<b>Rust</b> news".to_string(),
+            description_snippet: String::new(),
+            rank,
+        }
+    }
+
+    fn transcript_group(episode_id: Uuid, rank: f32) -> TranscriptSearchGroup {
+        TranscriptSearchGroup {
+            episode_id,
+            hits: vec![TranscriptSearchHit {
+                episode_id,
+                transcript_id: Uuid::new_v4(),
+                start_ms: Some(1000),
+                snippet: "about <b>rust</b>".to_string(),
+                rank,
+            }],
+        }
+    }
+
+    fn episode(date_of_recording: &str, downloaded: bool) -> PodcastEpisode {
+        PodcastEpisode {
+            id: Uuid::new_v4().to_string(),
+            legacy_id: None,
+            podcast_id: Uuid::new_v4().to_string(),
+            episode_id: "1".to_string(),
+            name: "Episode".to_string(),
+            url: "https://example.com/1.mp3".to_string(),
+            date_of_recording: date_of_recording.to_string(),
+            image_url: String::new(),
+            total_time: 0,
+            description: String::new(),
+            download_time: None,
+            guid: "1".to_string(),
+            deleted: false,
+            file_episode_path: None,
+            file_image_path: None,
+            episode_numbering_processed: false,
+            download_location: downloaded.then(|| "Local".to_string()),
+            youtube_video_id: None,
+            season: None,
+            episode_number: None,
+            episode_type: None,
+            loudness_lufs: None,
+            loudness_true_peak: None,
+        }
+    }
+
+    #[test]
+    fn merge_combines_metadata_and_transcript_hits_of_the_same_episode() {
+        let both = Uuid::new_v4();
+        let metadata_only = Uuid::new_v4();
+        let transcript_only = Uuid::new_v4();
+
+        let merged = merge_episode_hits(
+            vec![metadata_hit(metadata_only, 3.0), metadata_hit(both, 2.0)],
+            vec![
+                transcript_group(both, 2.5),
+                transcript_group(transcript_only, 1.0),
+            ],
+        );
+
+        let order: Vec<Uuid> = merged.iter().map(|hit| hit.episode_id).collect();
+        assert_eq!(order, vec![both, metadata_only, transcript_only]);
+        assert_eq!(merged[0].rank, 4.5);
+        assert!(merged[0].metadata.is_some());
+        assert_eq!(merged[0].transcript_hits.len(), 1);
+        assert!(merged[2].metadata.is_none());
+    }
+
+    #[test]
+    fn matches_filter_checks_download_state_and_date_range() {
+        let filter = SearchFilter {
+            downloaded: Some(true),
+            from: NaiveDate::from_ymd_opt(2024, 1, 1),
+            to: NaiveDate::from_ymd_opt(2024, 12, 31),
+            ..SearchFilter::default()
+        };
+
+        assert!(matches_filter(
+            &episode("2024-12-31T23:00:00+00:00", true),
+            &filter,
+            &None
+        ));
+        assert!(!matches_filter(
+            &episode("2024-06-01T00:00:00+00:00", false),
+            &filter,
+            &None
+        ));
+        assert!(!matches_filter(
+            &episode("2023-12-31T00:00:00+00:00", true),
+            &filter,
+            &None
+        ));
+    }
+
+    #[test]
+    fn matches_filter_requires_podcast_to_be_tagged() {
+        let tagged_episode = episode("2024-01-01T00:00:00+00:00", false);
+        let tagged: HashSet<Uuid> =
+            HashSet::from([Uuid::parse_str(&tagged_episode.podcast_id).unwrap()]);
+
+        assert!(matches_filter(
+            &tagged_episode,
+            &SearchFilter::default(),
+            &Some(tagged.clone())
+        ));
+        assert!(!matches_filter(
+            &episode("2024-01-01T00:00:00+00:00", false),
+            &SearchFilter::default(),
+            &Some(tagged)
+        ));
+    }
+}
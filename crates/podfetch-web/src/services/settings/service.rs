@@ -82,6 +82,11 @@ impl SettingsService {
             episode_numbering_processed: false,
             download_location: None,
             youtube_video_id: None,
+            season: None,
+            episode_number: None,
+            episode_type: None,
+            loudness_lufs: None,
+            loudness_true_peak: None,
         };
 
         let transient_setting = build_name_only_setting(&update_settings);
@@ -112,6 +117,13 @@ fn build_name_only_setting(update: &UpdateNameSettings) -> podfetch_domain::sett
         sponsorblock_enabled: true,
         nfo_format: "off".to_string(),
         cover_filename: "image".to_string(),
+        audit_log_retention_days: 365,
+        storage_quota_mb: 0,
+        storage_quota_policy: "refuse".to_string(),
+        dead_feed_deactivation_days: 30,
+        transcoding_profile_id: None,
+        loudness_analysis: false,
+        skip_detection: false,
     }
 }
 
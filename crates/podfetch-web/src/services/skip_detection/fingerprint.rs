@@ -0,0 +1,446 @@
+//! Audio fingerprints and the segment search on top of them.
+//!
+//! Audio is decoded to 8 kHz mono and cut into 256 ms frames every 32 ms.
+//! Each frame becomes a 32 bit sub-fingerprint: one bit per pair of
+//! neighbouring bands between 300 Hz and 2 kHz, set when the energy
+//! difference of the pair grew since the previous frame (Haitsma and Kalker).
+//! The same audio gives nearly the same bits even after re-encoding, while
+//! unrelated audio differs in about half of them. Silent frames carry
+//! [`SILENT_FRAME`] instead, which never counts as a match.
+
+use realfft::num_complex::Complex;
+use realfft::{RealFftPlanner, RealToComplex};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+pub const SAMPLE_RATE: u32 = 8_000;
+const FRAME_LEN: usize = 2048;
+/// Fine enough that audio starting between two frames still matches.
+const HOP_LEN: usize = 256;
+/// Milliseconds between two frames.
+pub const FRAME_MS: i64 = (HOP_LEN as i64 * 1000) / SAMPLE_RATE as i64;
+/// Offset of a window's centre from its start.
+const FRAME_CENTRE_MS: i64 = (FRAME_LEN as i64 * 500) / SAMPLE_RATE as i64;
+const BANDS: usize = 33;
+const MIN_BAND_HZ: f32 = 300.0;
+const MAX_BAND_HZ: f32 = 2000.0;
+/// Frames quieter than this are silence.
+const SILENCE_DBFS: f32 = -50.0;
+pub const SILENT_FRAME: u32 = 0;
+
+/// Pauses shorter than this are part of speech.
+const MIN_SILENCE_FRAMES: usize = (3_000 / FRAME_MS) as usize;
+/// Kept at both ends of a silence so skipping it doesn't clip words.
+const SILENCE_PADDING_FRAMES: usize = (250 / FRAME_MS) as usize;
+/// Shorter repeats are too likely a coincidence.
+const MIN_REPEAT_FRAMES: usize = (4_000 / FRAME_MS) as usize;
+/// Longer repeats are re-uploads or the same episode twice, not jingles.
+const MAX_REPEAT_FRAMES: usize = (180_000 / FRAME_MS) as usize;
+/// Frames averaged when comparing two fingerprints, about a second.
+const MATCH_WINDOW: usize = 32;
+/// Share of differing bits below which two windows are the same audio.
+const MATCH_BIT_ERROR_RATE: f32 = 0.3;
+/// Matching sub-fingerprint halves an alignment needs before it is compared.
+const MIN_ALIGNMENT_HITS: usize = 6;
+const MAX_ALIGNMENTS: usize = 10;
+/// Halves found in more frames than this say nothing about where audio
+/// repeats, and voting on all of them would take quadratic time.
+const MAX_KEY_FRAMES: usize = 64;
+
+/// Builds the fingerprint of a stream of 16 bit samples at [`SAMPLE_RATE`].
+pub struct Fingerprinter {
+    fft: Arc<dyn RealToComplex<f32>>,
+    input: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    scratch: Vec<Complex<f32>>,
+    window: Vec<f32>,
+    band_bins: Vec<Range<usize>>,
+    pending: Vec<f32>,
+    previous_bands: Option<[f32; BANDS]>,
+    fingerprint: Vec<u32>,
+}
+
+impl Default for Fingerprinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fingerprinter {
+    pub fn new() -> Self {
+        let window = (0..FRAME_LEN)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * i as f32 / FRAME_LEN as f32;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect();
+        let hz_per_bin = SAMPLE_RATE as f32 / FRAME_LEN as f32;
+        let ratio = (MAX_BAND_HZ / MIN_BAND_HZ).powf(1.0 / BANDS as f32);
+        let band_bins = (0..BANDS)
+            .map(|band| {
+                let low = MIN_BAND_HZ * ratio.powi(band as i32);
+                let high = low * ratio;
+                let first = (low / hz_per_bin).round() as usize;
+                let last = ((high / hz_per_bin).round() as usize).max(first + 1);
+                first..last
+            })
+            .collect();
+        let fft = RealFftPlanner::new().plan_fft_forward(FRAME_LEN);
+        Self {
+            input: fft.make_input_vec(),
+            spectrum: fft.make_output_vec(),
+            scratch: fft.make_scratch_vec(),
+            fft,
+            window,
+            band_bins,
+            pending: Vec::with_capacity(FRAME_LEN * 2),
+            previous_bands: None,
+            fingerprint: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, samples: &[i16]) {
+        self.pending
+            .extend(samples.iter().map(|&sample| f32::from(sample) / 32768.0));
+        let mut pending = std::mem::take(&mut self.pending);
+        let mut start = 0;
+        while pending.len() - start >= FRAME_LEN {
+            let sub_fingerprint = self.sub_fingerprint(&pending[start..start + FRAME_LEN]);
+            self.fingerprint.push(sub_fingerprint);
+            start += HOP_LEN;
+        }
+        pending.drain(..start);
+        self.pending = pending;
+    }
+
+    pub fn finish(self) -> Vec<u32> {
+        self.fingerprint
+    }
+
+    fn sub_fingerprint(&mut self, frame: &[f32]) -> u32 {
+        let mean_square =
+            frame.iter().map(|sample| sample * sample).sum::<f32>() / frame.len() as f32;
+        if 10.0 * mean_square.max(f32::MIN_POSITIVE).log10() < SILENCE_DBFS {
+            self.previous_bands = None;
+            return SILENT_FRAME;
+        }
+
+        for ((input, sample), weight) in self.input.iter_mut().zip(frame).zip(&self.window) {
+            *input = sample * weight;
+        }
+        self.fft
+            .process_with_scratch(&mut self.input, &mut self.spectrum, &mut self.scratch)
+            .expect("buffers are made by the planned FFT");
+        let mut bands = [0f32; BANDS];
+        for (energy, bins) in bands.iter_mut().zip(&self.band_bins) {
+            *energy = self.spectrum[bins.clone()]
+                .iter()
+                .map(|bin| bin.norm_sqr())
+                .sum();
+        }
+
+        let Some(previous) = self.previous_bands.replace(bands) else {
+            return SILENT_FRAME;
+        };
+        let mut bits = 0u32;
+        for band in 0..BANDS - 1 {
+            let now = bands[band] - bands[band + 1];
+            let before = previous[band] - previous[band + 1];
+            if now - before > 0.0 {
+                bits |= 1 << band;
+            }
+        }
+        // A frame whose bits all happen to be clear must not read as silence.
+        bits.max(1)
+    }
+}
+
+/// Frames `[start, end)` of a fingerprint.
+pub type FrameRange = Range<usize>;
+
+/// Long runs of silent frames, minus a little padding at both ends.
+pub fn silences(fingerprint: &[u32]) -> Vec<FrameRange> {
+    let mut silences = Vec::new();
+    let mut run_start = None;
+    for (frame, &sub_fingerprint) in fingerprint.iter().chain([&1]).enumerate() {
+        match (sub_fingerprint == SILENT_FRAME, run_start) {
+            (true, None) => run_start = Some(frame),
+            (false, Some(start)) => {
+                run_start = None;
+                if frame - start >= MIN_SILENCE_FRAMES {
+                    silences.push(start + SILENCE_PADDING_FRAMES..frame - SILENCE_PADDING_FRAMES);
+                }
+            }
+            _ => {}
+        }
+    }
+    silences
+}
+
+/// A stretch of audio that occurs in both fingerprints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repeat {
+    pub first: FrameRange,
+    pub second: FrameRange,
+}
+
+/// Finds audio that occurs in both `first` and `second`, such as jingles and
+/// ads a podcast plays in every episode. Candidate alignments come from
+/// sub-fingerprints with an identical upper or lower half, since whole ones
+/// rarely survive re-encoding unchanged; each is then compared frame by frame.
+/// Halves that occur in more than [`MAX_KEY_FRAMES`] frames of `second` don't
+/// vote, which keeps the search linear in the length of the episodes.
+pub fn repeats(first: &[u32], second: &[u32]) -> Vec<Repeat> {
+    let mut positions: HashMap<(bool, u16), Vec<usize>> = HashMap::new();
+    for (frame, &sub_fingerprint) in second.iter().enumerate() {
+        if sub_fingerprint != SILENT_FRAME {
+            for key in halves(sub_fingerprint) {
+                positions.entry(key).or_default().push(frame);
+            }
+        }
+    }
+    positions.retain(|_, frames| frames.len() <= MAX_KEY_FRAMES);
+    let mut hits: HashMap<isize, usize> = HashMap::new();
+    for (frame, &sub_fingerprint) in first.iter().enumerate() {
+        if sub_fingerprint == SILENT_FRAME {
+            continue;
+        }
+        for key in halves(sub_fingerprint) {
+            for &other in positions.get(&key).into_iter().flatten() {
+                *hits.entry(other as isize - frame as isize).or_default() += 1;
+            }
+        }
+    }
+    let mut offsets: Vec<(isize, usize)> = hits
+        .into_iter()
+        .filter(|&(_, count)| count >= MIN_ALIGNMENT_HITS)
+        .collect();
+    offsets.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut repeats: Vec<Repeat> = Vec::new();
+    for (offset, _) in offsets.into_iter().take(MAX_ALIGNMENTS) {
+        for first_range in aligned_runs(first, second, offset) {
+            let overlaps = repeats.iter().any(|repeat| {
+                repeat.first.start < first_range.end && first_range.start < repeat.first.end
+            });
+            if !overlaps {
+                let second_range = (first_range.start as isize + offset) as usize
+                    ..(first_range.end as isize + offset) as usize;
+                repeats.push(Repeat {
+                    first: first_range,
+                    second: second_range,
+                });
+            }
+        }
+    }
+    repeats.sort_by_key(|repeat| repeat.first.start);
+    repeats
+}
+
+fn halves(sub_fingerprint: u32) -> [(bool, u16); 2] {
+    [
+        (true, (sub_fingerprint >> 16) as u16),
+        (false, sub_fingerprint as u16),
+    ]
+}
+
+/// Runs of `first` that match `second` shifted by `offset` frames.
+fn aligned_runs(first: &[u32], second: &[u32], offset: isize) -> Vec<FrameRange> {
+    let start = 0.max(-offset) as usize;
+    let end = first
+        .len()
+        .min((second.len() as isize - offset).max(0) as usize);
+    if end < start + MATCH_WINDOW {
+        return Vec::new();
+    }
+    let errors: Vec<u32> = (start..end)
+        .map(|frame| {
+            let a = first[frame];
+            let b = second[(frame as isize + offset) as usize];
+            if a == SILENT_FRAME || b == SILENT_FRAME {
+                32
+            } else {
+                (a ^ b).count_ones()
+            }
+        })
+        .collect();
+
+    let threshold = (MATCH_BIT_ERROR_RATE * 32.0 * MATCH_WINDOW as f32) as u32;
+    let mut matching = vec![false; errors.len()];
+    let mut window_errors: u32 = errors[..MATCH_WINDOW].iter().sum();
+    for window_start in 0..=errors.len() - MATCH_WINDOW {
+        if window_start > 0 {
+            window_errors =
+                window_errors - errors[window_start - 1] + errors[window_start + MATCH_WINDOW - 1];
+        }
+        if window_errors < threshold {
+            matching[window_start..window_start + MATCH_WINDOW].fill(true);
+        }
+    }
+
+    let mut runs = Vec::new();
+    let mut run_start = None;
+    for (index, is_match) in matching.iter().chain([&false]).enumerate() {
+        match (is_match, run_start) {
+            (true, None) => run_start = Some(index),
+            (false, Some(begin)) => {
+                run_start = None;
+                let length = index - begin;
+                if (MIN_REPEAT_FRAMES..=MAX_REPEAT_FRAMES).contains(&length) {
+                    runs.push(start + begin..start + index);
+                }
+            }
+            _ => {}
+        }
+    }
+    runs
+}
+
+/// Start of `frame` in milliseconds, at the centre of its window.
+pub fn frame_to_ms(frame: usize) -> i64 {
+    frame as i64 * FRAME_MS + FRAME_CENTRE_MS
+}
+
+/// The frame a position from [`frame_to_ms`] belongs to, so stored segments
+/// come back as the frames they were made of.
+pub fn ms_to_frame(ms: i64) -> usize {
+    ((ms - FRAME_CENTRE_MS).max(0) / FRAME_MS) as usize
+}
+
+pub fn to_bytes(fingerprint: &[u32]) -> Vec<u8> {
+    fingerprint
+        .iter()
+        .flat_map(|sub_fingerprint| sub_fingerprint.to_le_bytes())
+        .collect()
+}
+
+pub fn from_bytes(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic noise, so each seed stands for a different recording.
+    fn noise(seed: u64, seconds: f32) -> Vec<i16> {
+        let mut state = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (0..(seconds * SAMPLE_RATE as f32) as usize)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                ((state >> 48) as i16) / 4
+            })
+            .collect()
+    }
+
+    fn fingerprint(parts: &[Vec<i16>]) -> Vec<u32> {
+        let mut fingerprinter = Fingerprinter::new();
+        for part in parts {
+            fingerprinter.push(part);
+        }
+        fingerprinter.finish()
+    }
+
+    fn seconds(range: &FrameRange) -> (f32, f32) {
+        (
+            frame_to_ms(range.start) as f32 / 1000.0,
+            frame_to_ms(range.end) as f32 / 1000.0,
+        )
+    }
+
+    #[test]
+    fn a_tone_lands_in_its_band() {
+        // 1 kHz sits in one band; only the pairs around it can flip, and a
+        // steady tone flips none of them.
+        let tone: Vec<i16> = (0..SAMPLE_RATE as usize)
+            .map(|i| {
+                // Eight samples per period, so every frame sees the same wave.
+                let phase = 2.0 * std::f32::consts::PI * (i % 8) as f32 / 8.0;
+                (phase.sin() * 8000.0) as i16
+            })
+            .collect();
+        let print = fingerprint(&[tone]);
+        assert!(print.len() > 2);
+        assert!(print[1..].iter().all(|&bits| bits == 1), "{print:x?}");
+    }
+
+    #[test]
+    fn common_halves_do_not_vote() {
+        // Every frame shares its upper half, so without the cap each pair of
+        // frames would vote; the lower halves still find the repeat.
+        let jingle: Vec<u32> = (0..400u32).map(|i| (7 << 16) | (i * 97 + 11)).collect();
+        let first: Vec<u32> = (0..300u32)
+            .map(|i| (7 << 16) | (50_000 + i))
+            .chain(jingle.iter().copied())
+            .collect();
+        let second: Vec<u32> = jingle
+            .iter()
+            .copied()
+            .chain((0..300u32).map(|i| (7 << 16) | (60_000 + i)))
+            .collect();
+
+        let found = repeats(&first, &second);
+        assert_eq!(
+            found,
+            vec![Repeat {
+                first: 300..700,
+                second: 0..400,
+            }]
+        );
+    }
+
+    #[test]
+    fn long_silences_are_found_and_short_pauses_ignored() {
+        let print = fingerprint(&[
+            noise(1, 10.0),
+            vec![0; 5 * SAMPLE_RATE as usize],
+            noise(2, 10.0),
+            vec![0; SAMPLE_RATE as usize],
+            noise(3, 10.0),
+        ]);
+
+        let found = silences(&print);
+        assert_eq!(found.len(), 1, "{found:?}");
+        let (start, end) = seconds(&found[0]);
+        assert!((10.0..11.0).contains(&start), "silence starts at {start}");
+        assert!((14.0..15.0).contains(&end), "silence ends at {end}");
+    }
+
+    #[test]
+    fn a_jingle_is_found_in_two_episodes() {
+        let jingle = noise(42, 8.0);
+        // The jingle starts half a frame step later relative to the frames
+        // of the second episode, the worst alignment real episodes can have.
+        let first = fingerprint(&[noise(1, 20.3), jingle.clone(), noise(2, 20.0)]);
+        let second = fingerprint(&[noise(3, 3.1), jingle, noise(4, 30.0)]);
+
+        let found = repeats(&first, &second);
+        assert_eq!(found.len(), 1, "{found:?}");
+        let (start, end) = seconds(&found[0].first);
+        assert!((19.5..21.5).contains(&start), "first starts at {start}");
+        assert!((27.0..29.0).contains(&end), "first ends at {end}");
+        let (start, _) = seconds(&found[0].second);
+        assert!((2.3..4.3).contains(&start), "second starts at {start}");
+    }
+
+    #[test]
+    fn unrelated_episodes_share_nothing() {
+        let first = fingerprint(&[noise(1, 30.0)]);
+        let second = fingerprint(&[noise(2, 30.0)]);
+        assert!(repeats(&first, &second).is_empty());
+    }
+
+    #[test]
+    fn fingerprints_survive_storage() {
+        let print = vec![SILENT_FRAME, 1, u32::MAX, 0xDEAD_BEEF];
+        assert_eq!(from_bytes(&to_bytes(&print)), print);
+    }
+}
@@ -0,0 +1,298 @@
+//! Local skip segments for episodes SponsorBlock knows nothing about.
+//!
+//! With `skip_detection` on, every local download is fingerprinted (see
+//! [`fingerprint`]). Long silences become `filler` segments. The fingerprint
+//! is then compared with the most recent episodes of the same podcast: audio
+//! found in both is an intro or outro jingle near either end and a recurring
+//! ad otherwise. The results go into the SponsorBlock segments table with
+//! source `local`, so the existing segment endpoint and players skip them
+//! like any SponsorBlock segment.
+
+use crate::services::skip_detection::fingerprint::{self, Fingerprinter, FrameRange};
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
+use podfetch_persistence::sponsorblock::{
+    EpisodeFingerprintEntity, SEGMENT_SOURCE_LOCAL, SponsorSegmentEntity, SponsorblockRepository,
+};
+use std::io::Read;
+use std::process::{Command, Stdio};
+
+/// Earlier episodes of the same podcast each download is compared with.
+const COMPARED_EPISODES: i64 = 5;
+/// Repeats starting or ending this close to an end of the episode are its
+/// intro or outro.
+const INTRO_OUTRO_MS: i64 = 90_000;
+
+/// Decodes the audio file at `path` and fingerprints it.
+pub fn fingerprint_file(path: &str) -> Result<Vec<u32>, CustomError> {
+    let mut child = Command::new("ffmpeg")
+        .args([
+            "-hide_banner",
+            "-nostats",
+            "-loglevel",
+            "error",
+            "-i",
+            path,
+            "-map",
+            "0:a:0",
+            "-ac",
+            "1",
+            "-ar",
+            &fingerprint::SAMPLE_RATE.to_string(),
+            "-f",
+            "s16le",
+            "-",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| conflict(format!("Failed to run ffmpeg: {e}")))?;
+
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| conflict("ffmpeg has no output".to_string()))?;
+    let mut fingerprinter = Fingerprinter::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut carry: Option<u8> = None;
+    let mut samples = Vec::with_capacity(buffer.len() / 2 + 1);
+    loop {
+        let read = stdout
+            .read(&mut buffer)
+            .map_err(|e| conflict(format!("Failed to read decoded audio: {e}")))?;
+        if read == 0 {
+            break;
+        }
+        samples.clear();
+        let mut bytes = buffer[..read].iter().copied();
+        if let Some(low) = carry.take()
+            && let Some(high) = bytes.next()
+        {
+            samples.push(i16::from_le_bytes([low, high]));
+        }
+        let rest: Vec<u8> = bytes.collect();
+        let mut pairs = rest.chunks_exact(2);
+        samples.extend(
+            pairs
+                .by_ref()
+                .map(|pair| i16::from_le_bytes([pair[0], pair[1]])),
+        );
+        carry = pairs.remainder().first().copied();
+        fingerprinter.push(&samples);
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| conflict(format!("Failed to wait for ffmpeg: {e}")))?;
+    if !status.success() {
+        return Err(conflict(format!("ffmpeg could not decode {path}")));
+    }
+    let fingerprint = fingerprinter.finish();
+    if fingerprint.is_empty() {
+        return Err(conflict(format!("{path} contains no audio")));
+    }
+    Ok(fingerprint)
+}
+
+/// Fingerprints the downloaded file of `episode`, stores its silences and the
+/// audio it shares with earlier episodes, and adds the shared audio to those
+/// episodes as well. Returns the number of segments stored for `episode`.
+pub fn analyze_episode(episode: &PodcastEpisode, file_path: &str) -> Result<usize, CustomError> {
+    let print = fingerprint_file(file_path)?;
+    SponsorblockRepository::upsert_fingerprint(EpisodeFingerprintEntity {
+        episode_id: episode.id.clone(),
+        podcast_id: episode.podcast_id.clone(),
+        fingerprint: fingerprint::to_bytes(&print),
+        created_at: chrono::Utc::now().naive_utc(),
+    })?;
+
+    let mut segments: Vec<(&'static str, FrameRange)> = fingerprint::silences(&print)
+        .into_iter()
+        .map(|silence| ("filler", silence))
+        .collect();
+    let earlier = SponsorblockRepository::get_recent_fingerprints(
+        &episode.podcast_id,
+        &episode.id,
+        COMPARED_EPISODES,
+    )?;
+    for other in earlier {
+        let other_print = fingerprint::from_bytes(&other.fingerprint);
+        let repeats = fingerprint::repeats(&print, &other_print);
+        if repeats.is_empty() {
+            continue;
+        }
+        let other_ranges: Vec<FrameRange> =
+            repeats.iter().map(|repeat| repeat.second.clone()).collect();
+        add_to_episode(&other.episode_id, &other_print, &other_ranges)?;
+        for repeat in repeats {
+            let category = classify(&repeat.first, print.len());
+            segments.push((category, repeat.first));
+        }
+    }
+
+    let rows = to_rows(&episode.id, merge(segments));
+    let count = rows.len();
+    SponsorblockRepository::replace_segments_for_episode(&episode.id, SEGMENT_SOURCE_LOCAL, rows)?;
+    tracing::info!("Found {count} skip segments in {}", episode.name);
+    Ok(count)
+}
+
+/// Adds `ranges` to the local segments of an earlier episode, keeping the
+/// ones it already has.
+fn add_to_episode(
+    episode_id: &str,
+    print: &[u32],
+    ranges: &[FrameRange],
+) -> Result<(), CustomError> {
+    let existing =
+        SponsorblockRepository::get_segments_for_episode_from(episode_id, SEGMENT_SOURCE_LOCAL)?;
+    let mut segments: Vec<(&'static str, FrameRange)> = existing
+        .iter()
+        .map(|segment| {
+            let category = match segment.category.as_str() {
+                "filler" => "filler",
+                "intro" => "intro",
+                "outro" => "outro",
+                _ => "sponsor",
+            };
+            (category, ms_to_frames(segment.start_ms, segment.end_ms))
+        })
+        .collect();
+    segments.extend(
+        ranges
+            .iter()
+            .map(|range| (classify(range, print.len()), range.clone())),
+    );
+    SponsorblockRepository::replace_segments_for_episode(
+        episode_id,
+        SEGMENT_SOURCE_LOCAL,
+        to_rows(episode_id, merge(segments)),
+    )?;
+    Ok(())
+}
+
+fn classify(range: &FrameRange, frames: usize) -> &'static str {
+    let start_ms = fingerprint::frame_to_ms(range.start);
+    let end_ms = fingerprint::frame_to_ms(range.end);
+    if start_ms <= INTRO_OUTRO_MS {
+        "intro"
+    } else if fingerprint::frame_to_ms(frames) - end_ms <= INTRO_OUTRO_MS {
+        "outro"
+    } else {
+        "sponsor"
+    }
+}
+
+/// Joins overlapping segments of the same category.
+fn merge(mut segments: Vec<(&'static str, FrameRange)>) -> Vec<(&'static str, FrameRange)> {
+    segments.sort_by(|a, b| a.0.cmp(b.0).then(a.1.start.cmp(&b.1.start)));
+    let mut merged: Vec<(&'static str, FrameRange)> = Vec::with_capacity(segments.len());
+    for (category, range) in segments {
+        match merged.last_mut() {
+            Some((last_category, last))
+                if *last_category == category && range.start <= last.end =>
+            {
+                last.end = last.end.max(range.end);
+            }
+            _ => merged.push((category, range)),
+        }
+    }
+    merged.sort_by_key(|(_, range)| range.start);
+    merged
+}
+
+fn ms_to_frames(start_ms: i64, end_ms: i64) -> FrameRange {
+    fingerprint::ms_to_frame(start_ms)..fingerprint::ms_to_frame(end_ms)
+}
+
+fn to_rows(
+    episode_id: &str,
+    segments: Vec<(&'static str, FrameRange)>,
+) -> Vec<SponsorSegmentEntity> {
+    let now = chrono::Utc::now().naive_utc();
+    segments
+        .into_iter()
+        .map(|(category, range)| {
+            let start_ms = fingerprint::frame_to_ms(range.start);
+            let end_ms = fingerprint::frame_to_ms(range.end);
+            SponsorSegmentEntity {
+                id: uuid::Uuid::new_v4().to_string(),
+                episode_id: episode_id.to_string(),
+                uuid: format!("local-{start_ms}-{end_ms}"),
+                category: category.to_string(),
+                action_type: "skip".to_string(),
+                start_ms,
+                end_ms,
+                votes: 0,
+                locked: false,
+                duration_mismatch: false,
+                fetched_at: now,
+                source: SEGMENT_SOURCE_LOCAL.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn conflict(message: String) -> CustomError {
+    CustomErrorInner::Conflict(message, Warning).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(start_s: i64, end_s: i64) -> FrameRange {
+        let frame = |s: i64| (s * 1000 / fingerprint::FRAME_MS) as usize;
+        frame(start_s)..frame(end_s)
+    }
+
+    #[test]
+    fn stored_segments_read_back_as_the_same_frames() {
+        let segments = vec![("intro", frames(0, 10)), ("sponsor", frames(100, 150))];
+        let mut round_tripped = segments.clone();
+        // Every later episode sharing audio with this one reads its segments
+        // back and stores them again.
+        for _ in 0..3 {
+            round_tripped = to_rows("episode", round_tripped)
+                .into_iter()
+                .map(|row| {
+                    let category = if row.category == "intro" {
+                        "intro"
+                    } else {
+                        "sponsor"
+                    };
+                    (category, ms_to_frames(row.start_ms, row.end_ms))
+                })
+                .collect();
+        }
+        assert_eq!(round_tripped, segments);
+    }
+
+    #[test]
+    fn repeats_are_classified_by_position() {
+        let episode = frames(0, 1800).end;
+        assert_eq!(classify(&frames(5, 20), episode), "intro");
+        assert_eq!(classify(&frames(1750, 1790), episode), "outro");
+        assert_eq!(classify(&frames(900, 960), episode), "sponsor");
+    }
+
+    #[test]
+    fn overlapping_segments_of_a_category_are_merged() {
+        let merged = merge(vec![
+            ("sponsor", frames(100, 130)),
+            ("filler", frames(120, 125)),
+            ("sponsor", frames(120, 150)),
+            ("intro", frames(0, 10)),
+        ]);
+        assert_eq!(
+            merged,
+            vec![
+                ("intro", frames(0, 10)),
+                ("sponsor", frames(100, 150)),
+                ("filler", frames(120, 125)),
+            ]
+        );
+    }
+}
@@ -4,7 +4,9 @@
 use crate::services::sponsorblock::client::{self, FetchedSegment};
 use common_infrastructure::error::CustomError;
 use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
-use podfetch_persistence::sponsorblock::{SponsorSegmentEntity, SponsorblockRepository};
+use podfetch_persistence::sponsorblock::{
+    SEGMENT_SOURCE_SPONSORBLOCK, SponsorSegmentEntity, SponsorblockRepository,
+};
 
 /// Tolerance for declaring a duration mismatch: the larger of 2 seconds or 1%.
 fn durations_mismatch(episode_secs: i64, sb_secs: f64) -> bool {
@@ -76,11 +78,16 @@ pub async fn fetch_and_store(episode: &PodcastEpisode) -> Result<usize, CustomEr
             locked: seg.locked,
             duration_mismatch: durations_mismatch(episode_secs, seg.video_duration_secs),
             fetched_at: now,
+            source: SEGMENT_SOURCE_SPONSORBLOCK.to_string(),
         })
         .collect();
 
     let count = rows.len();
-    SponsorblockRepository::replace_segments_for_episode(&episode.id, rows)?;
+    SponsorblockRepository::replace_segments_for_episode(
+        &episode.id,
+        SEGMENT_SOURCE_SPONSORBLOCK,
+        rows,
+    )?;
     Ok(count)
 }
 
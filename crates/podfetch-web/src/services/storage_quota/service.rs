@@ -0,0 +1,478 @@
+//! Accounting of the space downloads take up and the quotas on it.
+//!
+//! Every downloaded episode has an `episode_storage` row with the size of its
+//! file. A podcast's bytes count towards the user who added it
+//! (`Podcast::added_by`) and, like everything else, towards the instance-wide
+//! quota. Rows are written after a download and dropped together with the
+//! download status; [`StorageQuotaService::reconcile`] repairs drift against
+//! the storage backend once a day.
+
+use crate::services::favorite_podcast_episode::service::FavoritePodcastEpisodeService;
+use crate::services::podcast::service::PodcastService;
+use crate::services::settings::service::SettingsService;
+use crate::settings::Setting;
+use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
+use crate::user_admin::{PodcastStorageUsage, UserStorageUsage};
+use common_infrastructure::config::FileHandlerType;
+use common_infrastructure::error::CustomError;
+use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
+use podfetch_domain::storage_usage::{
+    EpisodeStorage, StorageQuotaPolicy, StorageUsageRepository, UserStorageQuota,
+};
+use podfetch_domain::user::UserWithoutPassword;
+use podfetch_persistence::adapters::StorageUsageRepositoryImpl;
+use podfetch_persistence::db::database;
+use podfetch_persistence::podcast::PodcastEntity as Podcast;
+use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
+use podfetch_storage::FileHandleWrapper;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const BYTES_PER_MB: i64 = 1024 * 1024;
+
+/// Bytes on disk at one point in time, summed per podcast and per user.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageUsage {
+    pub total_bytes: i64,
+    pub by_podcast: HashMap<Uuid, i64>,
+    pub by_user: HashMap<Uuid, i64>,
+}
+
+#[derive(Clone)]
+pub struct StorageQuotaService {
+    repository: Arc<dyn StorageUsageRepository<Error = CustomError>>,
+}
+
+impl StorageQuotaService {
+    pub fn new(repository: Arc<dyn StorageUsageRepository<Error = CustomError>>) -> Self {
+        Self { repository }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(Arc::new(StorageUsageRepositoryImpl::new(database())))
+    }
+
+    /// Remembers how large the freshly stored file of `episode` is.
+    pub fn record_download(
+        &self,
+        episode: &PodcastEpisode,
+        path: &str,
+        location: &FileHandlerType,
+    ) -> Result<(), CustomError> {
+        let Some(bytes) = FileHandleWrapper::file_size(path, location) else {
+            tracing::warn!("Could not determine the size of {path}, not accounting for it");
+            return Ok(());
+        };
+        self.repository.upsert(EpisodeStorage {
+            episode_id: parse_id(&episode.id)?,
+            podcast_id: parse_id(&episode.podcast_id)?,
+            bytes: i64::try_from(bytes).unwrap_or(i64::MAX),
+            recorded_at: chrono::Utc::now().naive_utc(),
+        })
+    }
+
+    pub fn forget(&self, episode_id: Uuid) -> Result<(), CustomError> {
+        self.repository.delete_by_episode_id(episode_id)
+    }
+
+    /// The recorded size of every download, by episode.
+    pub fn recorded_sizes(&self) -> Result<HashMap<Uuid, i64>, CustomError> {
+        Ok(self
+            .repository
+            .find_all()?
+            .into_iter()
+            .map(|entry| (entry.episode_id, entry.bytes))
+            .collect())
+    }
+
+    pub fn usage(&self) -> Result<StorageUsage, CustomError> {
+        let entries = self.repository.find_all()?;
+        Ok(summarize(&entries, &podcast_owners()?))
+    }
+
+    /// Usage and quota of each of `users`, broken down by the podcasts they
+    /// added.
+    pub fn user_reports(
+        &self,
+        users: &[UserWithoutPassword],
+    ) -> Result<Vec<UserStorageUsage>, CustomError> {
+        let podcasts = PodcastService::get_all_podcasts_raw()?;
+        let owners = owners_of(&podcasts);
+        let usage = summarize(&self.repository.find_all()?, &owners);
+
+        users
+            .iter()
+            .map(|user| {
+                let podcasts = podcasts
+                    .iter()
+                    .filter_map(|podcast| {
+                        let podcast_id = Uuid::parse_str(&podcast.id).ok()?;
+                        (owners.get(&podcast_id) == Some(&user.id)).then(|| PodcastStorageUsage {
+                            podcast_id: podcast.id.clone(),
+                            name: podcast.name.clone(),
+                            used_bytes: usage.by_podcast.get(&podcast_id).copied().unwrap_or(0),
+                        })
+                    })
+                    .collect();
+                Ok(UserStorageUsage {
+                    user_id: user.id.to_string(),
+                    username: user.username.clone(),
+                    used_bytes: usage.by_user.get(&user.id).copied().unwrap_or(0),
+                    quota_bytes: self.user_quota_bytes(user.id)?,
+                    quota_override_mb: self.quota_override(user.id)?,
+                    podcasts,
+                })
+            })
+            .collect()
+    }
+
+    /// The per-user quota an admin set, if any.
+    pub fn quota_override(&self, user_id: Uuid) -> Result<Option<i32>, CustomError> {
+        Ok(self
+            .repository
+            .find_quota(user_id)?
+            .map(|quota| quota.quota_mb))
+    }
+
+    /// Sets the user's quota in MB (0 for unlimited), or falls back to the
+    /// instance default again for `None`.
+    pub fn set_quota_override(
+        &self,
+        user_id: Uuid,
+        quota_mb: Option<i32>,
+    ) -> Result<(), CustomError> {
+        match quota_mb {
+            Some(quota_mb) => self
+                .repository
+                .save_quota(UserStorageQuota { user_id, quota_mb }),
+            None => self.repository.delete_quota(user_id),
+        }
+    }
+
+    /// Quota that applies to the user in bytes, `None` when unlimited.
+    pub fn user_quota_bytes(&self, user_id: Uuid) -> Result<Option<i64>, CustomError> {
+        let quota_mb = self
+            .quota_override(user_id)?
+            .map(i64::from)
+            .unwrap_or(i64::from(ENVIRONMENT_SERVICE.user_storage_quota_mb));
+        Ok(mb_to_bytes(quota_mb))
+    }
+
+    pub fn global_quota_bytes(settings: &Setting) -> Option<i64> {
+        mb_to_bytes(i64::from(settings.storage_quota_mb))
+    }
+
+    pub fn policy(settings: &Setting) -> StorageQuotaPolicy {
+        StorageQuotaPolicy::from_str(&settings.storage_quota_policy).unwrap_or_default()
+    }
+
+    /// Whether the feed refresh may queue downloads for `podcast`. Only the
+    /// `refuse` policy holds them back; with `cleanup-oldest` space is made
+    /// after the download instead.
+    pub fn allows_automatic_download(&self, podcast: &Podcast) -> Result<bool, CustomError> {
+        let Some(settings) = SettingsService::shared().get_settings()? else {
+            return Ok(true);
+        };
+        if Self::policy(&settings) != StorageQuotaPolicy::Refuse {
+            return Ok(true);
+        }
+        let usage = self.usage()?;
+        if Self::global_quota_bytes(&settings).is_some_and(|quota| usage.total_bytes >= quota) {
+            tracing::warn!(
+                "Storage quota of the instance is used up, not downloading new episodes of {}",
+                podcast.name
+            );
+            return Ok(false);
+        }
+        let Some(owner) = podcast
+            .added_by
+            .as_deref()
+            .and_then(|id| Uuid::parse_str(id).ok())
+        else {
+            return Ok(true);
+        };
+        let used = usage.by_user.get(&owner).copied().unwrap_or_default();
+        if self
+            .user_quota_bytes(owner)?
+            .is_some_and(|quota| used >= quota)
+        {
+            tracing::warn!(
+                "Storage quota of user {owner} is used up, not downloading new episodes of {}",
+                podcast.name
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// With the `cleanup-oldest` policy, deletes the oldest downloads until
+    /// every quota holds again. Episodes someone marked as favorite and
+    /// `keep` (usually the episode that was just downloaded) are left alone.
+    /// Returns how many downloads were deleted.
+    pub fn enforce_quotas(&self, keep: Option<Uuid>) -> Result<usize, CustomError> {
+        let Some(settings) = SettingsService::shared().get_settings()? else {
+            return Ok(0);
+        };
+        if Self::policy(&settings) != StorageQuotaPolicy::CleanupOldest {
+            return Ok(0);
+        }
+        let entries = self.repository.find_all()?;
+        let owners = podcast_owners()?;
+        let mut user_quotas = HashMap::new();
+        for owner in owners.values().collect::<HashSet<_>>() {
+            if let Some(quota) = self.user_quota_bytes(*owner)? {
+                user_quotas.insert(*owner, quota);
+            }
+        }
+
+        let favorites = FavoritePodcastEpisodeService::default_service();
+        let doomed = plan_cleanup(
+            &entries,
+            &owners,
+            &user_quotas,
+            Self::global_quota_bytes(&settings),
+            |entry| {
+                Some(entry.episode_id) == keep
+                    || favorites
+                        .is_liked_by_someone(entry.episode_id)
+                        .unwrap_or(true)
+            },
+        );
+
+        let mut deleted = 0;
+        for episode_id in doomed {
+            let Some(episode) =
+                PodcastEpisodeService::get_podcast_episode_by_internal_id(episode_id)?
+            else {
+                self.forget(episode_id)?;
+                continue;
+            };
+            match PodcastEpisodeService::delete_podcast_episode_locally(&episode.episode_id) {
+                Ok(episode) => {
+                    tracing::info!(
+                        "Deleted download of episode {} to stay within the storage quota",
+                        episode.name
+                    );
+                    crate::server::ChatServerHandle::broadcast_podcast_episode_deleted_locally(
+                        &episode,
+                    );
+                    deleted += 1;
+                }
+                Err(err) => {
+                    tracing::error!("Error deleting download of episode {episode_id}: {err}")
+                }
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Brings the recorded sizes in line with the storage backend: accounts
+    /// for downloads made before accounting existed, and drops entries of
+    /// episodes that are no longer downloaded.
+    pub fn reconcile(&self) -> Result<(), CustomError> {
+        let recorded = self
+            .repository
+            .find_all()?
+            .into_iter()
+            .map(|entry| (entry.episode_id, entry))
+            .collect::<HashMap<_, _>>();
+        let mut downloaded = HashSet::new();
+
+        for episode in PodcastEpisodeService::get_episodes()? {
+            let (Some(path), Some(location)) = (
+                episode.file_episode_path.as_deref(),
+                episode.download_location.as_deref(),
+            ) else {
+                continue;
+            };
+            let episode_id = parse_id(&episode.id)?;
+            downloaded.insert(episode_id);
+            let Some(bytes) = FileHandleWrapper::file_size(path, &FileHandlerType::from(location))
+            else {
+                continue;
+            };
+            let bytes = i64::try_from(bytes).unwrap_or(i64::MAX);
+            if recorded
+                .get(&episode_id)
+                .is_some_and(|entry| entry.bytes == bytes)
+            {
+                continue;
+            }
+            self.repository.upsert(EpisodeStorage {
+                episode_id,
+                podcast_id: parse_id(&episode.podcast_id)?,
+                bytes,
+                recorded_at: recorded
+                    .get(&episode_id)
+                    .map(|entry| entry.recorded_at)
+                    .or(episode.download_time)
+                    .unwrap_or_else(|| chrono::Utc::now().naive_utc()),
+            })?;
+        }
+
+        for episode_id in recorded.keys() {
+            if !downloaded.contains(episode_id) {
+                self.forget(*episode_id)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_id(id: &str) -> Result<Uuid, CustomError> {
+    Uuid::parse_str(id).map_err(|_| {
+        common_infrastructure::error::CustomErrorInner::BadRequest(
+            format!("Invalid UUID: {id}"),
+            common_infrastructure::error::ErrorSeverity::Warning,
+        )
+        .into()
+    })
+}
+
+fn mb_to_bytes(quota_mb: i64) -> Option<i64> {
+    (quota_mb > 0).then(|| quota_mb.saturating_mul(BYTES_PER_MB))
+}
+
+fn podcast_owners() -> Result<HashMap<Uuid, Uuid>, CustomError> {
+    Ok(owners_of(&PodcastService::get_all_podcasts_raw()?))
+}
+
+/// Maps every podcast that was added by a user to that user.
+fn owners_of(podcasts: &[Podcast]) -> HashMap<Uuid, Uuid> {
+    podcasts
+        .iter()
+        .filter_map(|podcast| {
+            let podcast_id = Uuid::parse_str(&podcast.id).ok()?;
+            let owner = Uuid::parse_str(podcast.added_by.as_deref()?).ok()?;
+            Some((podcast_id, owner))
+        })
+        .collect()
+}
+
+fn summarize(entries: &[EpisodeStorage], owners: &HashMap<Uuid, Uuid>) -> StorageUsage {
+    let mut usage = StorageUsage::default();
+    for entry in entries {
+        usage.total_bytes += entry.bytes;
+        *usage.by_podcast.entry(entry.podcast_id).or_default() += entry.bytes;
+        if let Some(owner) = owners.get(&entry.podcast_id) {
+            *usage.by_user.entry(*owner).or_default() += entry.bytes;
+        }
+    }
+    usage
+}
+
+/// Picks downloads to delete, oldest first, until no user is above their
+/// quota and the total is within `global_quota`. A download is only picked
+/// when it counts towards a quota that is exceeded.
+fn plan_cleanup(
+    entries: &[EpisodeStorage],
+    owners: &HashMap<Uuid, Uuid>,
+    user_quotas: &HashMap<Uuid, i64>,
+    global_quota: Option<i64>,
+    is_protected: impl Fn(&EpisodeStorage) -> bool,
+) -> Vec<Uuid> {
+    let mut usage = summarize(entries, owners);
+    let mut doomed = Vec::new();
+    for entry in entries {
+        let owner = owners.get(&entry.podcast_id);
+        let over_user_quota = owner.is_some_and(|owner| {
+            user_quotas
+                .get(owner)
+                .is_some_and(|quota| usage.by_user.get(owner).copied().unwrap_or_default() > *quota)
+        });
+        let over_global_quota = global_quota.is_some_and(|quota| usage.total_bytes > quota);
+        if !(over_user_quota || over_global_quota) || is_protected(entry) {
+            continue;
+        }
+        usage.total_bytes -= entry.bytes;
+        if let Some(owner) = owner {
+            *usage.by_user.entry(*owner).or_default() -= entry.bytes;
+        }
+        doomed.push(entry.episode_id);
+    }
+    doomed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mb_to_bytes, plan_cleanup, summarize};
+    use chrono::NaiveDate;
+    use podfetch_domain::storage_usage::EpisodeStorage;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn entry(podcast_id: Uuid, bytes: i64, day: u32) -> EpisodeStorage {
+        EpisodeStorage {
+            episode_id: Uuid::new_v4(),
+            podcast_id,
+            bytes,
+            recorded_at: NaiveDate::from_ymd_opt(2026, 1, day)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+        }
+    }
+
+    #[test]
+    fn summarize_attributes_bytes_to_the_podcast_owner() {
+        let (alice, podcast, orphan) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let entries = vec![
+            entry(podcast, 10, 1),
+            entry(podcast, 5, 2),
+            entry(orphan, 7, 3),
+        ];
+        let usage = summarize(&entries, &HashMap::from([(podcast, alice)]));
+
+        assert_eq!(usage.total_bytes, 22);
+        assert_eq!(usage.by_podcast[&podcast], 15);
+        assert_eq!(usage.by_podcast[&orphan], 7);
+        assert_eq!(usage.by_user, HashMap::from([(alice, 15)]));
+    }
+
+    #[test]
+    fn plan_cleanup_deletes_the_oldest_downloads_of_the_user_over_quota() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let (alice_podcast, bob_podcast) = (Uuid::new_v4(), Uuid::new_v4());
+        let entries = vec![
+            entry(bob_podcast, 50, 1),
+            entry(alice_podcast, 30, 2),
+            entry(alice_podcast, 30, 3),
+            entry(alice_podcast, 30, 4),
+        ];
+        let owners = HashMap::from([(alice_podcast, alice), (bob_podcast, bob)]);
+
+        let doomed = plan_cleanup(
+            &entries,
+            &owners,
+            &HashMap::from([(alice, 60)]),
+            None,
+            |_| false,
+        );
+
+        assert_eq!(doomed, vec![entries[1].episode_id]);
+    }
+
+    #[test]
+    fn plan_cleanup_respects_the_global_quota_and_protected_downloads() {
+        let podcast = Uuid::new_v4();
+        let entries = vec![
+            entry(podcast, 40, 1),
+            entry(podcast, 40, 2),
+            entry(podcast, 40, 3),
+        ];
+        let protected = entries[0].episode_id;
+
+        let doomed = plan_cleanup(&entries, &HashMap::new(), &HashMap::new(), Some(50), |e| {
+            e.episode_id == protected
+        });
+
+        assert_eq!(doomed, vec![entries[1].episode_id, entries[2].episode_id]);
+    }
+
+    #[test]
+    fn zero_megabytes_means_unlimited() {
+        assert_eq!(mb_to_bytes(0), None);
+        assert_eq!(mb_to_bytes(2), Some(2 * 1024 * 1024));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::tags::{Tag, TagCreate, TagsApplicationService, TagsPodcast};
+use crate::tags::{Color, Tag, TagCreate, TagsApplicationService, TagsPodcast};
 use common_infrastructure::error::ErrorSeverity::Debug;
 use common_infrastructure::error::{CustomError, CustomErrorInner};
 use podfetch_domain::tag::{TagRepository, TagUpdate};
@@ -75,6 +75,37 @@ impl TagService {
             .map(Into::into)
     }
 
+    /// Puts the podcast below the user's tags with the given names, creating
+    /// missing tags. Tags the podcast already has are left alone.
+    pub fn tag_podcast_by_names(
+        &self,
+        user_id: Uuid,
+        podcast_id: Uuid,
+        names: &[String],
+    ) -> Result<(), CustomError> {
+        if names.is_empty() {
+            return Ok(());
+        }
+        let mut user_tags = self.get_tags(user_id)?;
+        let assigned = self.get_tags_of_podcast(podcast_id, user_id)?;
+        for name in names {
+            if assigned.iter().any(|tag| &tag.name == name) {
+                continue;
+            }
+            let tag = match user_tags.iter().find(|tag| &tag.name == name) {
+                Some(tag) => tag.clone(),
+                None => {
+                    let tag =
+                        self.create_tag(user_id, name.clone(), None, Color::Green.to_string())?;
+                    user_tags.push(tag.clone());
+                    tag
+                }
+            };
+            self.add_podcast_to_tag(user_id, &tag.id, podcast_id)?;
+        }
+        Ok(())
+    }
+
     pub fn delete_podcast_from_tag(
         &self,
         user_id: Uuid,
@@ -0,0 +1,437 @@
+//! Transcoding profiles and the ffmpeg run that applies them to downloads.
+//!
+//! While `auto_transcode_opus` is on, every download is transcoded with the
+//! instance profile, or with the built-in 48 kbps Opus profile when none is
+//! picked. A podcast can pick a profile of its own, which applies even while
+//! instance-wide transcoding is off, or keep its downloads as they are.
+//! Profiles that were deleted in the meantime count as not picked.
+//!
+//! ffmpeg copies tags and chapters of the source into the transcoded file.
+//! With `keep_original` the source stays next to it as
+//! `<name>.original.<extension>`.
+
+use crate::podcast_settings::PodcastSetting;
+use crate::settings::Setting;
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::transcoding_profile::{
+    AudioCodec, KEEP_ORIGINAL_AUDIO, TranscodingProfile, TranscodingProfileRepository,
+};
+use podfetch_persistence::adapters::TranscodingProfileRepositoryImpl;
+use podfetch_persistence::db::database;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Integrated loudness podcasts are commonly mastered to.
+const LOUDNORM_FILTER: &str = "loudnorm=I=-16:TP=-1.5:LRA=11";
+/// Drops leading silence and shortens pauses longer than a second to half a
+/// second.
+const SILENCE_FILTER: &str = "silenceremove=start_periods=1:start_threshold=-50dB:\
+stop_periods=-1:stop_duration=1:stop_silence=0.5:stop_threshold=-50dB";
+/// `loudnorm` resamples to 192 kHz unless told otherwise.
+const LOUDNORM_SAMPLE_RATE: i32 = 48_000;
+
+/// What an admin submits when creating or changing a profile.
+#[derive(Debug, Clone)]
+pub struct TranscodingProfileInput {
+    pub name: String,
+    pub codec: AudioCodec,
+    pub bitrate_kbps: i32,
+    pub channels: i32,
+    pub sample_rate: i32,
+    pub loudnorm: bool,
+    pub trim_silence: bool,
+    pub keep_original: bool,
+}
+
+#[derive(Clone)]
+pub struct TranscodingProfileService {
+    repository: Arc<dyn TranscodingProfileRepository<Error = CustomError>>,
+}
+
+impl TranscodingProfileService {
+    pub fn new(repository: Arc<dyn TranscodingProfileRepository<Error = CustomError>>) -> Self {
+        Self { repository }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(Arc::new(TranscodingProfileRepositoryImpl::new(database())))
+    }
+
+    pub fn list(&self) -> Result<Vec<TranscodingProfile>, CustomError> {
+        self.repository.list()
+    }
+
+    pub fn get(&self, id: Uuid) -> Result<TranscodingProfile, CustomError> {
+        self.repository
+            .get(id)?
+            .ok_or_else(|| CustomErrorInner::NotFound(Warning).into())
+    }
+
+    pub fn create(
+        &self,
+        input: TranscodingProfileInput,
+    ) -> Result<TranscodingProfile, CustomError> {
+        Self::validate(&input)?;
+        self.repository.create(TranscodingProfile {
+            id: Uuid::new_v4(),
+            name: input.name,
+            codec: input.codec,
+            bitrate_kbps: input.bitrate_kbps,
+            channels: input.channels,
+            sample_rate: input.sample_rate,
+            loudnorm: input.loudnorm,
+            trim_silence: input.trim_silence,
+            keep_original: input.keep_original,
+            created_at: chrono::Utc::now().naive_utc(),
+        })
+    }
+
+    pub fn update(
+        &self,
+        id: Uuid,
+        input: TranscodingProfileInput,
+    ) -> Result<TranscodingProfile, CustomError> {
+        Self::validate(&input)?;
+        let existing = self.get(id)?;
+        self.repository
+            .update(TranscodingProfile {
+                name: input.name,
+                codec: input.codec,
+                bitrate_kbps: input.bitrate_kbps,
+                channels: input.channels,
+                sample_rate: input.sample_rate,
+                loudnorm: input.loudnorm,
+                trim_silence: input.trim_silence,
+                keep_original: input.keep_original,
+                ..existing
+            })?
+            .ok_or_else(|| CustomErrorInner::NotFound(Warning).into())
+    }
+
+    pub fn delete(&self, id: Uuid) -> Result<(), CustomError> {
+        if !self.repository.delete(id)? {
+            return Err(CustomErrorInner::NotFound(Warning).into());
+        }
+        Ok(())
+    }
+
+    /// The profile downloads of a podcast are transcoded with, `None` when
+    /// they are kept as downloaded.
+    pub fn profile_for(
+        &self,
+        settings: &Setting,
+        podcast_settings: Option<&PodcastSetting>,
+    ) -> Result<Option<TranscodingProfile>, CustomError> {
+        match podcast_settings.and_then(|s| s.transcoding_profile_id.as_deref()) {
+            Some(KEEP_ORIGINAL_AUDIO) => return Ok(None),
+            Some(id) => {
+                if let Some(profile) = self.find(id)? {
+                    return Ok(Some(profile));
+                }
+            }
+            None => {}
+        }
+        if !settings.auto_transcode_opus {
+            return Ok(None);
+        }
+        let instance_profile = match settings.transcoding_profile_id.as_deref() {
+            Some(id) => self.find(id)?,
+            None => None,
+        };
+        Ok(Some(instance_profile.unwrap_or_else(builtin_opus_profile)))
+    }
+
+    /// Makes sure a profile picked in the settings exists.
+    pub fn validate_reference(&self, profile_id: Option<&str>) -> Result<(), CustomError> {
+        match profile_id {
+            None | Some(KEEP_ORIGINAL_AUDIO) => Ok(()),
+            Some(id) => self.find(id)?.map(|_| ()).ok_or_else(|| {
+                CustomErrorInner::BadRequest(
+                    format!("'{id}' is not a transcoding profile"),
+                    Warning,
+                )
+                .into()
+            }),
+        }
+    }
+
+    fn find(&self, id: &str) -> Result<Option<TranscodingProfile>, CustomError> {
+        match Uuid::parse_str(id) {
+            Ok(id) => self.repository.get(id),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn validate(input: &TranscodingProfileInput) -> Result<(), CustomError> {
+        let problem = if input.name.trim().is_empty() {
+            Some("Transcoding profiles need a name")
+        } else if !(8..=512).contains(&input.bitrate_kbps) {
+            Some("The bitrate must be between 8 and 512 kbps")
+        } else if !(0..=2).contains(&input.channels) {
+            Some("Channels must be 1 (mono), 2 (stereo) or 0 to keep the source")
+        } else if input.sample_rate != 0 && !(8_000..=192_000).contains(&input.sample_rate) {
+            Some("The sample rate must be between 8000 and 192000 Hz, or 0 to keep the source")
+        } else {
+            None
+        };
+        match problem {
+            Some(message) => Err(CustomErrorInner::BadRequest(message.to_string(), Warning).into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// What downloads were transcoded with before profiles existed.
+pub fn builtin_opus_profile() -> TranscodingProfile {
+    TranscodingProfile {
+        id: Uuid::nil(),
+        name: "Opus 48 kbps".to_string(),
+        codec: AudioCodec::Opus,
+        bitrate_kbps: 48,
+        channels: 0,
+        sample_rate: 0,
+        loudnorm: false,
+        trim_silence: false,
+        keep_original: false,
+        created_at: chrono::NaiveDateTime::default(),
+    }
+}
+
+pub fn file_extension(codec: AudioCodec) -> &'static str {
+    match codec {
+        AudioCodec::Opus => "opus",
+        AudioCodec::Mp3 => "mp3",
+        AudioCodec::Aac => "m4a",
+        AudioCodec::Vorbis => "ogg",
+    }
+}
+
+fn encoder(codec: AudioCodec) -> &'static str {
+    match codec {
+        AudioCodec::Opus => "libopus",
+        AudioCodec::Mp3 => "libmp3lame",
+        AudioCodec::Aac => "aac",
+        AudioCodec::Vorbis => "libvorbis",
+    }
+}
+
+/// Whether `path` already has the format `profile` produces.
+pub fn is_transcoded(path: &str, profile: &TranscodingProfile) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(file_extension(profile.codec)))
+}
+
+/// Where the source of `path` is kept with `keep_original`.
+fn kept_original_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}.original.{}", extension.to_string_lossy()),
+        None => format!("{stem}.original"),
+    };
+    path.with_file_name(name)
+}
+
+/// Sources kept next to the transcoded `episode_path`.
+pub fn kept_originals(episode_path: &str) -> Vec<PathBuf> {
+    let path = Path::new(episode_path);
+    let (Some(directory), Some(stem)) = (path.parent(), path.file_stem()) else {
+        return vec![];
+    };
+    let prefix = format!("{}.original", stem.to_string_lossy());
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return vec![];
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|candidate| {
+            candidate
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .is_some_and(|name| name == prefix || name.starts_with(&format!("{prefix}.")))
+        })
+        .collect()
+}
+
+pub fn ffmpeg_args(profile: &TranscodingProfile, input: &str, output: &str) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "-i".into(),
+        input.into(),
+        "-map".into(),
+        "0:a:0".into(),
+        "-map_metadata".into(),
+        "0".into(),
+        "-map_chapters".into(),
+        "0".into(),
+        "-vn".into(),
+        "-c:a".into(),
+        encoder(profile.codec).into(),
+        "-b:a".into(),
+        format!("{}k", profile.bitrate_kbps),
+    ];
+    if profile.channels > 0 {
+        args.extend(["-ac".into(), profile.channels.to_string()]);
+    }
+    let sample_rate = match profile.sample_rate {
+        0 if profile.loudnorm => LOUDNORM_SAMPLE_RATE,
+        sample_rate => sample_rate,
+    };
+    if sample_rate > 0 {
+        args.extend(["-ar".into(), sample_rate.to_string()]);
+    }
+    let filters: Vec<&str> = [
+        profile.trim_silence.then_some(SILENCE_FILTER),
+        profile.loudnorm.then_some(LOUDNORM_FILTER),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !filters.is_empty() {
+        args.extend(["-af".into(), filters.join(",")]);
+    }
+    match profile.codec {
+        AudioCodec::Mp3 => args.extend(["-id3v2_version".into(), "3".into()]),
+        AudioCodec::Aac => args.extend(["-movflags".into(), "+faststart".into()]),
+        AudioCodec::Opus | AudioCodec::Vorbis => {}
+    }
+    args.extend(["-y".into(), output.into()]);
+    args
+}
+
+/// Transcodes the local file at `input_path` with `profile` and returns the
+/// path of the result. On failure the source is left where it was.
+pub fn transcode(input_path: &str, profile: &TranscodingProfile) -> Result<String, CustomError> {
+    let input = Path::new(input_path);
+    let output = input
+        .with_extension(file_extension(profile.codec))
+        .to_string_lossy()
+        .to_string();
+    // ffmpeg can't write over the file it reads, so the source moves aside
+    // first.
+    let source = kept_original_path(input);
+    std::fs::rename(input, &source)
+        .map_err(|e| conflict(format!("Failed to move {input_path}: {e}")))?;
+    let source_path = source.to_string_lossy().to_string();
+
+    let result = std::process::Command::new("ffmpeg")
+        .args(ffmpeg_args(profile, &source_path, &output))
+        .output()
+        .map_err(|e| conflict(format!("Failed to run ffmpeg: {e}")))
+        .and_then(|output| match output.status.success() {
+            true => Ok(()),
+            false => Err(conflict(format!(
+                "ffmpeg transcode failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))),
+        });
+    if let Err(err) = result {
+        let _ = std::fs::remove_file(&output);
+        if let Err(e) = std::fs::rename(&source, input) {
+            tracing::error!("Could not restore {input_path} after a failed transcode: {e}");
+        }
+        return Err(err);
+    }
+
+    if !profile.keep_original
+        && let Err(e) = std::fs::remove_file(&source)
+    {
+        tracing::warn!("Could not remove original file after transcoding: {e}");
+    }
+    tracing::info!("Transcoded with profile {}: {output}", profile.name);
+    Ok(output)
+}
+
+fn conflict(message: String) -> CustomError {
+    CustomErrorInner::Conflict(message, Warning).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(codec: AudioCodec) -> TranscodingProfile {
+        TranscodingProfile {
+            codec,
+            ..builtin_opus_profile()
+        }
+    }
+
+    #[test]
+    fn builtin_profile_matches_the_former_opus_transcode() {
+        let args = ffmpeg_args(&builtin_opus_profile(), "in.mp3", "in.opus");
+        assert_eq!(
+            args,
+            [
+                "-i",
+                "in.mp3",
+                "-map",
+                "0:a:0",
+                "-map_metadata",
+                "0",
+                "-map_chapters",
+                "0",
+                "-vn",
+                "-c:a",
+                "libopus",
+                "-b:a",
+                "48k",
+                "-y",
+                "in.opus"
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_channels_and_sample_rate_end_up_in_the_arguments() {
+        let args = ffmpeg_args(
+            &TranscodingProfile {
+                bitrate_kbps: 64,
+                channels: 1,
+                loudnorm: true,
+                trim_silence: true,
+                ..profile(AudioCodec::Mp3)
+            },
+            "in.m4a",
+            "in.mp3",
+        );
+        let joined = args.join(" ");
+
+        assert!(joined.contains("-c:a libmp3lame -b:a 64k -ac 1 -ar 48000"));
+        assert!(joined.contains(&format!("-af {SILENCE_FILTER},{LOUDNORM_FILTER}")));
+        assert!(joined.contains("-id3v2_version 3"));
+    }
+
+    #[test]
+    fn transcoded_files_are_recognized_by_their_extension() {
+        assert!(is_transcoded("/p/episode.OPUS", &builtin_opus_profile()));
+        assert!(!is_transcoded("/p/episode.mp3", &builtin_opus_profile()));
+        assert!(is_transcoded("/p/episode.m4a", &profile(AudioCodec::Aac)));
+    }
+
+    #[test]
+    fn kept_originals_are_found_next_to_the_episode() {
+        let directory = std::env::temp_dir().join(format!("podfetch-kept-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&directory).unwrap();
+        for name in [
+            "episode.opus",
+            "episode.original.mp3",
+            "episode2.original.mp3",
+        ] {
+            std::fs::write(directory.join(name), b"").unwrap();
+        }
+        let episode = directory.join("episode.opus");
+
+        let kept = kept_originals(&episode.to_string_lossy());
+
+        assert_eq!(kept, vec![directory.join("episode.original.mp3")]);
+        assert_eq!(
+            kept_original_path(&episode),
+            directory.join("episode.original.opus")
+        );
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,324 @@
+//! On-the-fly transcoding for the native streaming endpoints.
+//!
+//! `?codec=` and `?bitrate=` on `/podcasts/...` and `/proxy/podcast` turn the
+//! response into a progressive stream encoded by the shared [`HlsTranscoder`],
+//! so the stream lands in its bounded cache and every ffmpeg run counts
+//! against its concurrency limit. The length of such a stream isn't known up
+//! front, so it can't be range requested; `?t=` starts it at a later position
+//! instead.
+
+use crate::services::audiobookshelf::hls_transcoder::{
+    HlsTranscoder, SEGMENT_DURATION_SECONDS, StreamCodec, StreamEncoding, TranscodeError,
+    TranscodeInput,
+};
+use axum::body::Body;
+use axum::http::{HeaderValue, Response, StatusCode, header};
+use common_infrastructure::config::FileHandlerType;
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use futures::StreamExt;
+use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use utoipa::IntoParams;
+
+pub const DEFAULT_STREAM_BITRATE_KBPS: u32 = 64;
+const STREAM_BITRATE_RANGE_KBPS: std::ops::RangeInclusive<u32> = 16..=320;
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StreamTranscodeParams {
+    /// `mp3` or `aac`. Defaults to mp3 when only a bitrate is given.
+    pub codec: Option<String>,
+    /// Target bitrate in kbps, 16 to 320. Defaults to 64.
+    pub bitrate: Option<u32>,
+    /// Position in seconds to start the stream at. It is rounded down to a
+    /// multiple of six seconds, so streams of nearby positions share a cache
+    /// file.
+    pub t: Option<f64>,
+}
+
+impl StreamTranscodeParams {
+    /// The requested encoding, `None` when the file should be served as is.
+    pub fn encoding(&self) -> Result<Option<StreamEncoding>, CustomError> {
+        if self.codec.is_none() && self.bitrate.is_none() {
+            return Ok(None);
+        }
+        let codec = match self.codec.as_deref() {
+            None => StreamCodec::Mp3,
+            Some(codec) => StreamCodec::from_str(codec).ok_or_else(|| {
+                bad_request(format!("'{codec}' can't be streamed, use mp3 or aac"))
+            })?,
+        };
+        let bitrate_kbps = self.bitrate.unwrap_or(DEFAULT_STREAM_BITRATE_KBPS);
+        if !STREAM_BITRATE_RANGE_KBPS.contains(&bitrate_kbps) {
+            return Err(bad_request(
+                "bitrate must be between 16 and 320 kbps".to_string(),
+            ));
+        }
+        Ok(Some(StreamEncoding {
+            codec,
+            bitrate_kbps,
+        }))
+    }
+
+    /// The six second step the stream starts at.
+    pub fn start_segment(&self) -> Result<u32, CustomError> {
+        match self.t {
+            None => Ok(0),
+            Some(t) if t.is_finite() && t >= 0.0 => {
+                Ok((t / SEGMENT_DURATION_SECONDS).floor() as u32)
+            }
+            Some(_) => Err(bad_request("t must be a position in seconds".to_string())),
+        }
+    }
+}
+
+/// What ffmpeg reads from, and the cache key of its segments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamSource {
+    pub key: String,
+    pub input: TranscodeInput,
+}
+
+impl StreamSource {
+    /// The local download if it is on disk, the episode URL otherwise. The
+    /// modification time is part of the key so a re-downloaded or transcoded
+    /// file doesn't hit segments of the previous one. Only http(s) episode
+    /// URLs are streamed from.
+    pub fn for_episode(episode: &PodcastEpisode) -> Result<Self, CustomError> {
+        let local_file = episode
+            .download_location
+            .as_deref()
+            .filter(|location| FileHandlerType::from(*location) == FileHandlerType::Local)
+            .and(episode.file_episode_path.as_deref())
+            .filter(|path| Path::new(path).is_file());
+        match local_file {
+            Some(path) => {
+                let modified = std::fs::metadata(path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                    .map(|since_epoch| since_epoch.as_secs())
+                    .unwrap_or_default();
+                Ok(Self {
+                    key: format!("{}-{modified}", episode.id),
+                    input: TranscodeInput::File(path.into()),
+                })
+            }
+            None => Ok(Self {
+                key: format!("{}-remote", episode.id),
+                input: TranscodeInput::remote(&episode.url).ok_or_else(|| {
+                    bad_request("The episode URL can't be transcoded".to_string())
+                })?,
+            }),
+        }
+    }
+}
+
+/// Streams `source` transcoded to `encoding`, starting `start_segment` six
+/// second steps in. The first bytes are encoded before answering so a source
+/// ffmpeg can't read ends up as an error instead of an empty body; later
+/// failures end the stream early. A start past the end gives an empty stream.
+pub async fn transcoded_stream(
+    transcoder: Arc<HlsTranscoder>,
+    source: StreamSource,
+    encoding: StreamEncoding,
+    start_segment: u32,
+) -> Result<Response<Body>, CustomError> {
+    let transcode_failed = |e: TranscodeError| {
+        CustomError::from(CustomErrorInner::Conflict(
+            format!("Transcoding the stream failed: {e}"),
+            Warning,
+        ))
+    };
+    let mut stream = transcoder
+        .open_stream(&source.key, &source.input, encoding, start_segment)
+        .await
+        .map_err(transcode_failed)?;
+    let first_chunk = stream.next_chunk().await.map_err(transcode_failed)?;
+
+    let key = source.key;
+    let rest = futures::stream::unfold(first_chunk.is_some().then_some(stream), move |stream| {
+        let key = key.clone();
+        async move {
+            let mut stream = stream?;
+            match stream.next_chunk().await {
+                Ok(Some(bytes)) => Some((Ok::<_, std::io::Error>(bytes), Some(stream))),
+                Ok(None) => None,
+                Err(e) => {
+                    tracing::warn!("Stopped transcoded stream of {key}: {e}");
+                    None
+                }
+            }
+        }
+    });
+    let body = futures::stream::iter(first_chunk.map(Ok)).chain(rest);
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(encoding.codec.mime_type()),
+        )
+        .header(header::ACCEPT_RANGES, HeaderValue::from_static("none"))
+        .header(header::CACHE_CONTROL, HeaderValue::from_static("no-store"))
+        .body(Body::from_stream(body))
+        .unwrap())
+}
+
+fn bad_request(message: String) -> CustomError {
+    CustomErrorInner::BadRequest(message, Warning).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_infrastructure::config::EnvironmentService;
+
+    fn params(codec: Option<&str>, bitrate: Option<u32>) -> StreamTranscodeParams {
+        StreamTranscodeParams {
+            codec: codec.map(str::to_string),
+            bitrate,
+            t: None,
+        }
+    }
+
+    #[test]
+    fn no_parameters_serve_the_file_as_is() {
+        assert_eq!(params(None, None).encoding().unwrap(), None);
+    }
+
+    #[test]
+    fn missing_parameters_fall_back_to_defaults() {
+        assert_eq!(
+            params(None, Some(32)).encoding().unwrap(),
+            Some(StreamEncoding {
+                codec: StreamCodec::Mp3,
+                bitrate_kbps: 32
+            })
+        );
+        assert_eq!(
+            params(Some("aac"), None).encoding().unwrap(),
+            Some(StreamEncoding {
+                codec: StreamCodec::Aac,
+                bitrate_kbps: DEFAULT_STREAM_BITRATE_KBPS
+            })
+        );
+    }
+
+    #[test]
+    fn unsupported_codecs_and_bitrates_are_rejected() {
+        assert!(params(Some("opus"), None).encoding().is_err());
+        assert!(params(Some("aac"), Some(8)).encoding().is_err());
+        assert!(params(None, Some(1000)).encoding().is_err());
+    }
+
+    #[test]
+    fn the_start_position_picks_the_segment_it_falls_into() {
+        let at = |t: f64| StreamTranscodeParams {
+            t: Some(t),
+            ..params(Some("mp3"), None)
+        };
+        assert_eq!(params(Some("mp3"), None).start_segment().unwrap(), 0);
+        assert_eq!(at(0.0).start_segment().unwrap(), 0);
+        assert_eq!(at(5.9).start_segment().unwrap(), 0);
+        assert_eq!(at(6.0).start_segment().unwrap(), 1);
+        assert_eq!(at(95.5).start_segment().unwrap(), 15);
+        assert!(at(-1.0).start_segment().is_err());
+        assert!(at(f64::NAN).start_segment().is_err());
+    }
+
+    #[tokio::test]
+    async fn streams_start_at_the_requested_segment() {
+        let environment = EnvironmentService {
+            audiobookshelf_data_dir: std::env::temp_dir()
+                .join(format!("podfetch-stream-{}", uuid::Uuid::new_v4()))
+                .to_string_lossy()
+                .to_string(),
+            ..EnvironmentService::default()
+        };
+        let transcoder = Arc::new(HlsTranscoder::new(Arc::new(environment)));
+        let encoding = StreamEncoding {
+            codec: StreamCodec::Mp3,
+            bitrate_kbps: 64,
+        };
+        // Cached streams are served without running ffmpeg.
+        for (segment, content) in [(0, "zeroonetwo"), (1, "onetwo"), (3, "")] {
+            let path = transcoder.stream_path("episode", encoding, segment);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let source = StreamSource {
+            key: "episode".to_string(),
+            input: TranscodeInput::File("/does/not/exist.mp3".into()),
+        };
+
+        let response = transcoded_stream(transcoder.clone(), source.clone(), encoding, 1)
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, "onetwo");
+
+        let past_the_end = transcoded_stream(transcoder.clone(), source, encoding, 3)
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(past_the_end.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.is_empty());
+        let _ = std::fs::remove_dir_all(transcoder.cache_root());
+    }
+
+    fn remote_episode(url: &str) -> PodcastEpisode {
+        PodcastEpisode {
+            id: "episode-1".to_string(),
+            legacy_id: None,
+            podcast_id: "podcast-1".to_string(),
+            episode_id: "1".to_string(),
+            name: "Episode".to_string(),
+            url: url.to_string(),
+            date_of_recording: "2024-01-01".to_string(),
+            image_url: String::new(),
+            total_time: 0,
+            description: String::new(),
+            download_time: None,
+            guid: "guid-1".to_string(),
+            deleted: false,
+            file_episode_path: Some("/does/not/exist.mp3".to_string()),
+            file_image_path: None,
+            episode_numbering_processed: false,
+            download_location: Some("Local".to_string()),
+            youtube_video_id: None,
+            season: None,
+            episode_number: None,
+            episode_type: None,
+            loudness_lufs: None,
+            loudness_true_peak: None,
+        }
+    }
+
+    #[test]
+    fn episodes_without_a_local_file_stream_from_their_url() {
+        let episode = remote_episode("https://example.com/episode.mp3");
+
+        assert_eq!(
+            StreamSource::for_episode(&episode).unwrap(),
+            StreamSource {
+                key: "episode-1-remote".to_string(),
+                input: TranscodeInput::Url("https://example.com/episode.mp3".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn episode_urls_other_than_http_are_not_streamed() {
+        for url in ["file:///etc/passwd", "concat:/etc/passwd|/etc/hosts"] {
+            assert!(StreamSource::for_episode(&remote_episode(url)).is_err());
+        }
+    }
+}
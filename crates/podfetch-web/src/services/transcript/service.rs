@@ -19,7 +19,7 @@ use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
 use podfetch_domain::podcast_episode_transcript::{
     PodcastEpisodeTranscript, PodcastEpisodeTranscriptRepository, TranscriptSearchHit,
     TranscriptSegment, TranscriptSource, TranscriptStatus, TranscriptionJob,
-    TranscriptionJobRepository, UpsertTranscript,
+    TranscriptionJobRepository, TranscriptionJobStatus, UpsertTranscript,
 };
 use podfetch_persistence::adapters::{
     PodcastEpisodeTranscriptRepositoryImpl, TranscriptionJobRepositoryImpl,
@@ -469,6 +469,10 @@ impl TranscriptService {
         self.job_repo.get_by_episode_id(episode_id)
     }
 
+    pub fn count_jobs_by_status(&self) -> Result<Vec<(TranscriptionJobStatus, i64)>, CustomError> {
+        self.job_repo.count_by_status()
+    }
+
     /// Persists a freshly Whisper-generated transcript for `episode`: writes
     /// the segments out as a VTT file archived next to the episode's audio
     /// (mirroring how feed transcripts are archived), upserts the episode's
@@ -774,12 +778,13 @@ mod tests {
             .execute(&mut get_connection())
             .expect("seed episode");
 
-        let mut episode = PodcastEpisode::default();
-        episode.id = id.to_string();
-        episode.podcast_id = podcast_id.to_string();
-        episode.name = "Test Episode".to_string();
-        episode.file_episode_path = file_episode_path.map(|s| s.to_string());
-        episode
+        PodcastEpisode {
+            id: id.to_string(),
+            podcast_id: podcast_id.to_string(),
+            name: "Test Episode".to_string(),
+            file_episode_path: file_episode_path.map(|s| s.to_string()),
+            ..Default::default()
+        }
     }
 
     fn service() -> TranscriptService {
@@ -1420,6 +1425,17 @@ mod tests {
         ) -> Result<Option<TranscriptionJob>, Self::Error> {
             unimplemented!()
         }
+        fn count_by_status(
+            &self,
+        ) -> Result<
+            Vec<(
+                podfetch_domain::podcast_episode_transcript::TranscriptionJobStatus,
+                i64,
+            )>,
+            Self::Error,
+        > {
+            unimplemented!()
+        }
     }
 
     fn hit(episode_id: Uuid, rank: f32, snippet: &str) -> TranscriptSearchHit {
@@ -9,6 +9,9 @@
 //! blocking HTTP call that can legitimately run for minutes.
 
 use crate::server::ChatServerHandle;
+use crate::services::notification_channel::channel::NotificationEvent;
+use crate::services::notification_channel::service::NotificationChannelService;
+use crate::services::podcast::service::PodcastService;
 use crate::services::transcript::service::TranscriptService;
 use crate::services::transcript::whisper_client::WhisperClient;
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase;
@@ -22,6 +25,7 @@ use podfetch_persistence::db::database;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
+use uuid::Uuid;
 
 /// After this many failed attempts a job is given up on (`failed`) instead of
 /// being put back on the queue (`pending`).
@@ -103,7 +107,18 @@ fn transcribe_job(
     })?;
 
     let (segments, language) = client.transcribe(Path::new(audio_path))?;
-    service.store_generated(&episode, segments, language)
+    service.store_generated(&episode, segments, language)?;
+
+    match Uuid::parse_str(&episode.podcast_id).map(PodcastService::get_podcast) {
+        Ok(Ok(podcast)) => NotificationChannelService::notify(
+            NotificationEvent::transcription_done(&podcast.name, &episode.name),
+        ),
+        _ => tracing::warn!(
+            "Could not load podcast {} for the transcription notification",
+            episode.podcast_id
+        ),
+    }
+    Ok(())
 }
 
 /// Endless background loop that drains the transcription job queue one job
@@ -208,6 +223,7 @@ mod tests {
     /// `run_worker_with_config`). The worker task must still be alive and
     /// polling after startup instead of having died on a construction panic.
     #[tokio::test]
+    #[allow(clippy::await_holding_lock)]
     async fn worker_survives_startup_inside_the_async_runtime() {
         let _guard = lock_and_prepare_db();
         let config = TranscriptionConfig {
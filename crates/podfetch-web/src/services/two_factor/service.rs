@@ -0,0 +1,305 @@
+use crate::services::two_factor::totp;
+use common_infrastructure::error::ErrorSeverity::Warning;
+use common_infrastructure::error::{CustomError, CustomErrorInner};
+use data_encoding::BASE32_NOPAD;
+use podfetch_domain::two_factor::{TwoFactorAuth, TwoFactorRepository};
+use podfetch_domain::user::User;
+use podfetch_persistence::adapters::TwoFactorRepositoryImpl;
+use podfetch_persistence::db::database;
+use rand::RngExt;
+use sha256::digest;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const ISSUER: &str = "PodFetch";
+const RECOVERY_CODE_COUNT: usize = 10;
+/// Wrong codes in a row after which no code is checked for a while.
+const MAX_FAILED_ATTEMPTS: i32 = 5;
+const LOCKOUT_MINUTES: i64 = 15;
+/// Rereads of a second factor another request changed during a check.
+const MAX_CHECK_RETRIES: usize = 5;
+
+/// Secret handed out when enrollment starts. It is only stored as pending
+/// until [`TwoFactorService::confirm_enrollment`] saw a matching code.
+pub struct TwoFactorEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Clone)]
+pub struct TwoFactorService {
+    repository: Arc<dyn TwoFactorRepository<Error = CustomError>>,
+}
+
+impl TwoFactorService {
+    pub fn new(repository: Arc<dyn TwoFactorRepository<Error = CustomError>>) -> Self {
+        Self { repository }
+    }
+
+    pub fn default_service() -> Self {
+        Self::new(Arc::new(TwoFactorRepositoryImpl::new(database())))
+    }
+
+    pub fn find(&self, user: &User) -> Result<Option<TwoFactorAuth>, CustomError> {
+        self.repository.find_by_user_id(user.id)
+    }
+
+    pub fn is_enabled(&self, user: &User) -> Result<bool, CustomError> {
+        Ok(self
+            .find(user)?
+            .is_some_and(|two_factor| two_factor.enabled))
+    }
+
+    /// Creates a fresh secret for `user`, replacing an unconfirmed one.
+    pub fn start_enrollment(&self, user: &User) -> Result<TwoFactorEnrollment, CustomError> {
+        if self.is_enabled(user)? {
+            return Err(CustomErrorInner::Conflict(
+                "two-factor authentication is already enabled".to_string(),
+                Warning,
+            )
+            .into());
+        }
+        let secret = totp::generate_secret();
+        self.repository.save(TwoFactorAuth {
+            user_id: user.id,
+            secret: secret.clone(),
+            enabled: false,
+            recovery_codes: vec![],
+            last_used_step: None,
+            failed_attempts: 0,
+            locked_until: None,
+            created_at: chrono::Utc::now().naive_utc(),
+        })?;
+        Ok(TwoFactorEnrollment {
+            otpauth_uri: totp::provisioning_uri(ISSUER, &user.username, &secret),
+            secret,
+        })
+    }
+
+    /// Enables the pending secret once `code` proves the authenticator was
+    /// set up, and returns the recovery codes in plain text.
+    pub fn confirm_enrollment(&self, user: &User, code: &str) -> Result<Vec<String>, CustomError> {
+        let Some(mut two_factor) = self.find(user)? else {
+            return Err(CustomErrorInner::NotFound(Warning).into());
+        };
+        if two_factor.enabled {
+            return Err(CustomErrorInner::Conflict(
+                "two-factor authentication is already enabled".to_string(),
+                Warning,
+            )
+            .into());
+        }
+        let Some(step) = totp::verify(&two_factor.secret, code, now_unix(), None) else {
+            return Err(invalid_code());
+        };
+        let recovery_codes = generate_recovery_codes();
+        two_factor.enabled = true;
+        two_factor.last_used_step = Some(step);
+        two_factor.recovery_codes = recovery_codes
+            .iter()
+            .map(|code| hash_recovery_code(code))
+            .collect();
+        self.repository.save(two_factor)?;
+        Ok(recovery_codes)
+    }
+
+    /// Checks a second factor of `user`: either the current TOTP code or one
+    /// of the recovery codes, which is used up by it. After
+    /// [`MAX_FAILED_ATTEMPTS`] wrong codes every code is refused for
+    /// [`LOCKOUT_MINUTES`].
+    pub fn verify(&self, user: &User, code: &str) -> Result<bool, CustomError> {
+        for _ in 0..MAX_CHECK_RETRIES {
+            let Some(current) = self.find(user)?.filter(|two_factor| two_factor.enabled) else {
+                return Ok(false);
+            };
+            let now = chrono::Utc::now().naive_utc();
+            let Some((accepted, updated)) = check(&current, code, now) else {
+                return Ok(false);
+            };
+            let locks = updated.locked_until.is_some() && current.locked_until.is_none();
+            // A concurrent check may have used the same code or counted a
+            // failure meanwhile; this one then starts over.
+            if self.repository.replace(&current, updated)? {
+                if locks {
+                    tracing::warn!(
+                        "Too many wrong two-factor codes for {}, locked for {LOCKOUT_MINUTES} minutes",
+                        user.username
+                    );
+                }
+                return Ok(accepted);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Replaces all recovery codes of `user`, e.g. after most were used up.
+    pub fn regenerate_recovery_codes(
+        &self,
+        user: &User,
+        code: &str,
+    ) -> Result<Vec<String>, CustomError> {
+        if !self.verify(user, code)? {
+            return Err(invalid_code());
+        }
+        let Some(mut two_factor) = self.find(user)? else {
+            return Err(CustomErrorInner::NotFound(Warning).into());
+        };
+        let recovery_codes = generate_recovery_codes();
+        two_factor.recovery_codes = recovery_codes
+            .iter()
+            .map(|code| hash_recovery_code(code))
+            .collect();
+        self.repository.save(two_factor)?;
+        Ok(recovery_codes)
+    }
+
+    pub fn disable(&self, user: &User, code: &str) -> Result<(), CustomError> {
+        if !self.verify(user, code)? {
+            return Err(invalid_code());
+        }
+        self.repository.delete_by_user_id(user.id)?;
+        Ok(())
+    }
+
+    /// Removes the second factor of a user who lost both authenticator and
+    /// recovery codes. Only reachable for admins.
+    pub fn reset(&self, user_id: Uuid) -> Result<(), CustomError> {
+        match self.repository.delete_by_user_id(user_id)? {
+            true => Ok(()),
+            false => Err(CustomErrorInner::NotFound(Warning).into()),
+        }
+    }
+}
+
+/// Outcome of checking `code` against `two_factor` at `now` and the state to
+/// store for it, or `None` while wrong codes locked the second factor.
+fn check(
+    two_factor: &TwoFactorAuth,
+    code: &str,
+    now: chrono::NaiveDateTime,
+) -> Option<(bool, TwoFactorAuth)> {
+    if two_factor
+        .locked_until
+        .is_some_and(|locked_until| locked_until > now)
+    {
+        return None;
+    }
+    let mut updated = two_factor.clone();
+    updated.locked_until = None;
+
+    let step = totp::verify(
+        &two_factor.secret,
+        code,
+        now.and_utc().timestamp(),
+        two_factor.last_used_step,
+    );
+    let hashed = hash_recovery_code(code);
+    let recovery_code = two_factor
+        .recovery_codes
+        .iter()
+        .position(|recovery_code| recovery_code == &hashed);
+    let accepted = match (step, recovery_code) {
+        (Some(step), _) => {
+            updated.last_used_step = Some(step);
+            true
+        }
+        (None, Some(position)) => {
+            updated.recovery_codes.remove(position);
+            true
+        }
+        (None, None) => false,
+    };
+
+    updated.failed_attempts = if accepted {
+        0
+    } else {
+        two_factor.failed_attempts + 1
+    };
+    if updated.failed_attempts >= MAX_FAILED_ATTEMPTS {
+        updated.failed_attempts = 0;
+        updated.locked_until = Some(now + chrono::Duration::minutes(LOCKOUT_MINUTES));
+    }
+    Some((accepted, updated))
+}
+
+fn now_unix() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn invalid_code() -> CustomError {
+    CustomErrorInner::BadRequest("invalid two-factor code".to_string(), Warning).into()
+}
+
+/// Codes look like `abcd-efgh`; the dash and case are ignored on input.
+fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 5];
+            rand::rng().fill(&mut bytes);
+            let code = BASE32_NOPAD.encode(&bytes).to_lowercase();
+            format!("{}-{}", &code[..4], &code[4..])
+        })
+        .collect()
+}
+
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|char| char.is_ascii_alphanumeric())
+        .map(|char| char.to_ascii_lowercase())
+        .collect();
+    digest(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_factor(recovery_code: &str) -> TwoFactorAuth {
+        TwoFactorAuth {
+            user_id: Uuid::new_v4(),
+            secret: totp::generate_secret(),
+            enabled: true,
+            recovery_codes: vec![hash_recovery_code(recovery_code)],
+            last_used_step: None,
+            failed_attempts: 0,
+            locked_until: None,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn codes_are_used_up() {
+        let now = chrono::Utc::now().naive_utc();
+        let current = two_factor("abcd-efgh");
+        let code = totp::code_at(&current.secret, now.and_utc().timestamp());
+
+        let (accepted, current) = check(&current, &code, now).unwrap();
+        assert!(accepted);
+        let (accepted, current) = check(&current, &code, now).unwrap();
+        assert!(!accepted, "a TOTP code must not be replayed");
+
+        let (accepted, current) = check(&current, "ABCDEFGH", now).unwrap();
+        assert!(accepted);
+        assert!(current.recovery_codes.is_empty());
+        assert_eq!(current.failed_attempts, 0);
+    }
+
+    #[test]
+    fn wrong_codes_lock_the_second_factor_for_a_while() {
+        let now = chrono::Utc::now().naive_utc();
+        let mut current = two_factor("abcd-efgh");
+        for _ in 0..MAX_FAILED_ATTEMPTS {
+            let (accepted, updated) = check(&current, "wrong", now).unwrap();
+            assert!(!accepted);
+            current = updated;
+        }
+        assert!(current.locked_until.is_some());
+
+        assert!(check(&current, "abcd-efgh", now).is_none());
+        let later = now + chrono::Duration::minutes(LOCKOUT_MINUTES + 1);
+        let (accepted, current) = check(&current, "abcd-efgh", later).unwrap();
+        assert!(accepted);
+        assert_eq!(current.locked_until, None);
+    }
+}
@@ -2,23 +2,36 @@ use crate::role::{Role, STANDARD_USER, STANDARD_USER_ID};
 use common_infrastructure::config::EnvironmentService;
 use common_infrastructure::error::ErrorSeverity::{Debug, Warning};
 use common_infrastructure::error::{CustomError, CustomErrorInner};
+use podfetch_domain::api_token::{ApiToken, ApiTokenRepository, ApiTokenScope};
+use podfetch_domain::two_factor::TwoFactorRepository;
 use podfetch_domain::user::User;
 use podfetch_domain::user_admin::{ManagedUser, UserAdminRepository};
+use sha256::digest;
 use std::sync::Arc;
 
+/// How often `last_used_at` of a token is written; streaming clients use the
+/// same token for every range request.
+const LAST_USED_RESOLUTION_SECONDS: i64 = 60;
+
 #[derive(Clone)]
 pub struct UserAuthService {
     repository: Arc<dyn UserAdminRepository<Error = CustomError>>,
+    token_repository: Arc<dyn ApiTokenRepository<Error = CustomError>>,
+    two_factor_repository: Arc<dyn TwoFactorRepository<Error = CustomError>>,
     environment: Arc<EnvironmentService>,
 }
 
 impl UserAuthService {
     pub fn new(
         repository: Arc<dyn UserAdminRepository<Error = CustomError>>,
+        token_repository: Arc<dyn ApiTokenRepository<Error = CustomError>>,
+        two_factor_repository: Arc<dyn TwoFactorRepository<Error = CustomError>>,
         environment: Arc<EnvironmentService>,
     ) -> Self {
         Self {
             repository,
+            token_repository,
+            two_factor_repository,
             environment,
         }
     }
@@ -61,7 +74,14 @@ impl UserAuthService {
         })
     }
 
-    pub fn find_by_api_key(&self, api_key: &str) -> Result<Option<User>, CustomError> {
+    /// Resolves the user behind `api_key` when it may be used for `scope`.
+    /// The account api key and the configured admin key grant every scope;
+    /// named tokens only the scopes they were created with.
+    pub fn find_by_api_key(
+        &self,
+        api_key: &str,
+        scope: ApiTokenScope,
+    ) -> Result<Option<User>, CustomError> {
         if api_key.is_empty() {
             return Ok(None);
         }
@@ -73,15 +93,81 @@ impl UserAuthService {
             return self.ensure_admin_user().map(Some);
         }
 
-        self.repository.find_by_api_key(api_key)
+        if let Some(user) = self.repository.find_by_api_key(api_key)? {
+            return Ok(Some(user));
+        }
+
+        match self.find_token(api_key, scope)? {
+            Some(token) => self.repository.find_by_id(token.user_id),
+            None => Ok(None),
+        }
     }
 
-    pub fn is_api_key_valid(&self, api_key: &str) -> bool {
-        self.find_by_api_key(api_key)
+    pub fn is_api_key_valid(&self, api_key: &str, scope: ApiTokenScope) -> bool {
+        self.find_by_api_key(api_key, scope)
             .map(|user| user.is_some())
             .unwrap_or(false)
     }
 
+    /// Whether `secret` is a named token of `user` granting `scope`. Lets
+    /// clients that only speak basic auth log in with a token as password.
+    pub fn is_api_token_of(
+        &self,
+        user: &User,
+        secret: &str,
+        scope: ApiTokenScope,
+    ) -> Result<bool, CustomError> {
+        Ok(self
+            .find_token(secret, scope)?
+            .is_some_and(|token| token.user_id == user.id))
+    }
+
+    /// Whether `user` enrolled a TOTP second factor. Such users can't log in
+    /// with their password alone; clients without 2FA support use app
+    /// passwords (named tokens) instead.
+    pub fn requires_second_factor(&self, user: &User) -> Result<bool, CustomError> {
+        Ok(self
+            .two_factor_repository
+            .find_by_user_id(user.id)?
+            .is_some_and(|two_factor| two_factor.enabled))
+    }
+
+    /// Refuses a login that only presented the account password when `user`
+    /// has a second factor enrolled.
+    pub fn ensure_password_only_login_allowed(&self, user: &User) -> Result<(), CustomError> {
+        if self.requires_second_factor(user)? {
+            return Err(CustomErrorInner::UnAuthorized(
+                "two-factor authentication is enabled, use an app password".to_string(),
+                Warning,
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Looks up a named token by its secret. Expired tokens and tokens
+    /// without `scope` are treated as unknown.
+    fn find_token(
+        &self,
+        secret: &str,
+        scope: ApiTokenScope,
+    ) -> Result<Option<ApiToken>, CustomError> {
+        let Some(token) = self.token_repository.find_by_token_hash(&digest(secret))? else {
+            return Ok(None);
+        };
+        let now = chrono::Utc::now().naive_utc();
+        if token.is_expired(now) || !token.grants(scope) {
+            return Ok(None);
+        }
+        let recently_used = token.last_used_at.is_some_and(|last_used_at| {
+            (now - last_used_at).num_seconds() < LAST_USED_RESOLUTION_SECONDS
+        });
+        if !recently_used {
+            self.token_repository.update_last_used(token.id, now)?;
+        }
+        Ok(Some(token))
+    }
+
     pub fn configured_admin_user(&self) -> User {
         User {
             id: STANDARD_USER_ID,
@@ -174,7 +260,10 @@ mod tests {
     use crate::test_support::tests::handle_test_startup;
     use common_infrastructure::config::EnvironmentService;
     use podfetch_domain::filter::{Filter, FilterRepository};
-    use podfetch_persistence::adapters::{FilterRepositoryImpl, UserAdminRepositoryImpl};
+    use podfetch_persistence::adapters::{
+        ApiTokenRepositoryImpl, FilterRepositoryImpl, TwoFactorRepositoryImpl,
+        UserAdminRepositoryImpl,
+    };
     use podfetch_persistence::db::database;
     use serial_test::serial;
 
@@ -186,6 +275,8 @@ mod tests {
         environment.username = None;
         UserAuthService::new(
             Arc::new(UserAdminRepositoryImpl::new(database())),
+            Arc::new(ApiTokenRepositoryImpl::new(database())),
+            Arc::new(TwoFactorRepositoryImpl::new(database())),
             Arc::new(environment),
         )
     }
@@ -256,6 +347,8 @@ mod tests {
 
         let service = UserAuthService::new(
             Arc::new(UserAdminRepositoryImpl::new(database())),
+            Arc::new(ApiTokenRepositoryImpl::new(database())),
+            Arc::new(TwoFactorRepositoryImpl::new(database())),
             Arc::new(EnvironmentService::for_tests()),
         );
 
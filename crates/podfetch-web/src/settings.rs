@@ -1,5 +1,9 @@
+use crate::podcast_settings::PodcastSetting;
 use chrono::Local;
+use quick_xml::events::Event;
+use quick_xml::{Reader, XmlVersion};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use uuid::Uuid;
 use xml_builder::{XMLBuilder, XMLElement, XMLVersion};
@@ -40,6 +44,32 @@ pub struct Setting {
     /// Defaulted on deserialize so older clients that omit it keep working.
     #[serde(default = "default_cover_filename")]
     pub cover_filename: String,
+    /// Days audit log entries are kept; 0 keeps them forever. Defaulted on
+    /// deserialize so older clients that omit it keep working.
+    #[serde(default = "default_audit_log_retention_days")]
+    pub audit_log_retention_days: i32,
+    /// Space all downloads together may take up, in MB; 0 means unlimited.
+    #[serde(default)]
+    pub storage_quota_mb: i32,
+    /// `refuse` skips automatic downloads once a quota is used up,
+    /// `cleanup-oldest` deletes the oldest downloads instead.
+    #[serde(default = "default_storage_quota_policy")]
+    pub storage_quota_policy: String,
+    /// Days a feed may keep failing before it is deactivated; 0 never does.
+    #[serde(default = "default_dead_feed_deactivation_days")]
+    pub dead_feed_deactivation_days: i32,
+    /// Profile downloads are transcoded with while `auto_transcode_opus` is
+    /// on; unset uses the built-in 48 kbps Opus profile.
+    #[serde(default)]
+    pub transcoding_profile_id: Option<String>,
+    /// Measure the integrated loudness of every download (EBU R128) so
+    /// players can level episodes out with the ReplayGain derived from it.
+    #[serde(default)]
+    pub loudness_analysis: bool,
+    /// Detect long silences and jingles or ads that repeat across a podcast's
+    /// episodes, served next to the SponsorBlock segments.
+    #[serde(default)]
+    pub skip_detection: bool,
 }
 
 fn default_max_parallel_downloads() -> i32 {
@@ -54,10 +84,26 @@ pub(crate) fn default_nfo_format() -> String {
     "off".to_string()
 }
 
+pub(crate) fn default_refresh_mode() -> String {
+    crate::services::podcast::refresh_schedule::REFRESH_MODE_FIXED.to_string()
+}
+
 pub(crate) fn default_cover_filename() -> String {
     "image".to_string()
 }
 
+fn default_audit_log_retention_days() -> i32 {
+    365
+}
+
+fn default_storage_quota_policy() -> String {
+    "refuse".to_string()
+}
+
+fn default_dead_feed_deactivation_days() -> i32 {
+    30
+}
+
 impl From<podfetch_domain::settings::Setting> for Setting {
     fn from(value: podfetch_domain::settings::Setting) -> Self {
         Self {
@@ -80,6 +126,13 @@ impl From<podfetch_domain::settings::Setting> for Setting {
             sponsorblock_enabled: value.sponsorblock_enabled,
             nfo_format: value.nfo_format,
             cover_filename: value.cover_filename,
+            audit_log_retention_days: value.audit_log_retention_days,
+            storage_quota_mb: value.storage_quota_mb,
+            storage_quota_policy: value.storage_quota_policy,
+            dead_feed_deactivation_days: value.dead_feed_deactivation_days,
+            transcoding_profile_id: value.transcoding_profile_id.map(|id| id.to_string()),
+            loudness_analysis: value.loudness_analysis,
+            skip_detection: value.skip_detection,
         }
     }
 }
@@ -106,6 +159,15 @@ impl From<Setting> for podfetch_domain::settings::Setting {
             sponsorblock_enabled: value.sponsorblock_enabled,
             nfo_format: value.nfo_format,
             cover_filename: value.cover_filename,
+            audit_log_retention_days: value.audit_log_retention_days,
+            storage_quota_mb: value.storage_quota_mb,
+            storage_quota_policy: value.storage_quota_policy,
+            dead_feed_deactivation_days: value.dead_feed_deactivation_days,
+            transcoding_profile_id: value
+                .transcoding_profile_id
+                .and_then(|id| Uuid::parse_str(&id).ok()),
+            loudness_analysis: value.loudness_analysis,
+            skip_detection: value.skip_detection,
         }
     }
 }
@@ -455,18 +517,61 @@ impl From<String> for Mode {
     }
 }
 
+/// XML namespace of the PodFetch attributes in extended OPML exports.
+pub const PODFETCH_OPML_NAMESPACE: &str = "https://github.com/SamTV12345/PodFetch";
+
 #[derive(Clone)]
 pub struct OpmlPodcast {
     pub id: String,
     pub name: String,
     pub summary: Option<String>,
     pub rssfeed: String,
+    /// Names of the requester's tags. The podcast is listed once below each of
+    /// them and at the top level when it has none.
+    pub tags: Vec<String>,
+    pub active: bool,
+    pub settings: Option<PodcastSetting>,
+}
+
+/// A feed of an imported OPML document together with the PodFetch data that
+/// was exported alongside it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpmlFeed {
+    pub xml_url: String,
+    /// Titles of the category outlines the feed was nested in.
+    pub tags: Vec<String>,
+    pub active: Option<bool>,
+    pub settings: Option<PodcastSetting>,
+}
+
+/// Which podcasts an OPML export contains.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OpmlScope {
+    /// The podcasts the requester subscribed to.
+    #[default]
+    Subscriptions,
+    /// Every podcast of the instance. Restricted to privileged users.
+    All,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct OpmlExportParams {
+    #[serde(default)]
+    pub scope: OpmlScope,
+    /// Adds the active flag and podcast settings as `podfetch:` attributes
+    /// so another PodFetch instance can restore them on import.
+    #[serde(default)]
+    pub extended: bool,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum OpmlError {
     #[error("api key required")]
     ApiKeyRequired,
+    #[error("forbidden")]
+    Forbidden,
     #[error("xml error: {0}")]
     Xml(String),
 }
@@ -477,6 +582,7 @@ pub fn build_opml(
     requester_api_key: Option<&str>,
     any_auth_enabled: bool,
     server_url: &str,
+    extended: bool,
 ) -> Result<String, OpmlError> {
     if any_auth_enabled && requester_api_key.is_none() {
         return Err(OpmlError::ApiKeyRequired);
@@ -488,14 +594,18 @@ pub fn build_opml(
         .build();
     let mut opml = XMLElement::new("opml");
     opml.add_attribute("version", "2.0");
+    if extended {
+        opml.add_attribute("xmlns:podfetch", PODFETCH_OPML_NAMESPACE);
+    }
     opml.add_child(add_header())
         .map_err(|error| OpmlError::Xml(error.to_string()))?;
     opml.add_child(add_podcasts(
-        podcasts,
-        type_of,
+        &podcasts,
+        &type_of,
         requester_api_key,
         server_url,
-    ))
+        extended,
+    )?)
     .map_err(|error| OpmlError::Xml(error.to_string()))?;
     xml.set_root_element(opml);
 
@@ -522,30 +632,238 @@ fn add_header() -> XMLElement {
 }
 
 fn add_podcasts(
-    podcasts: Vec<OpmlPodcast>,
-    type_of: Mode,
+    podcasts: &[OpmlPodcast],
+    type_of: &Mode,
     requester_api_key: Option<&str>,
     server_url: &str,
-) -> XMLElement {
-    let mut body = XMLElement::new("body");
+    extended: bool,
+) -> Result<XMLElement, OpmlError> {
+    let mut by_tag: BTreeMap<&str, Vec<&OpmlPodcast>> = BTreeMap::new();
     for podcast in podcasts {
-        let mut outline = XMLElement::new("outline");
-        if let Some(summary) = podcast.summary {
-            outline.add_attribute("text", &summary);
+        for tag in &podcast.tags {
+            by_tag.entry(tag.as_str()).or_default().push(podcast);
         }
-        outline.add_attribute("title", &podcast.name);
-        outline.add_attribute("type", "rss");
-        match type_of {
-            Mode::Local => {
-                let mut local_url = format!("{}rss/{}", server_url, podcast.id);
-                if let Some(api_key) = requester_api_key {
-                    local_url = format!("{local_url}?apiKey={api_key}");
+    }
+
+    let mut body = XMLElement::new("body");
+    for (tag, tagged_podcasts) in by_tag {
+        let mut category = XMLElement::new("outline");
+        category.add_attribute("text", tag);
+        category.add_attribute("title", tag);
+        for podcast in tagged_podcasts {
+            category
+                .add_child(podcast_outline(
+                    podcast,
+                    type_of,
+                    requester_api_key,
+                    server_url,
+                    extended,
+                )?)
+                .expect("outline should be attached");
+        }
+        body.add_child(category)
+            .expect("category should be attached");
+    }
+    for podcast in podcasts.iter().filter(|podcast| podcast.tags.is_empty()) {
+        body.add_child(podcast_outline(
+            podcast,
+            type_of,
+            requester_api_key,
+            server_url,
+            extended,
+        )?)
+        .expect("outline should be attached");
+    }
+    Ok(body)
+}
+
+fn podcast_outline(
+    podcast: &OpmlPodcast,
+    type_of: &Mode,
+    requester_api_key: Option<&str>,
+    server_url: &str,
+    extended: bool,
+) -> Result<XMLElement, OpmlError> {
+    let mut outline = XMLElement::new("outline");
+    if let Some(summary) = &podcast.summary {
+        outline.add_attribute("text", summary);
+    }
+    outline.add_attribute("title", &podcast.name);
+    outline.add_attribute("type", "rss");
+    match type_of {
+        Mode::Local => {
+            let mut local_url = format!("{}rss/{}", server_url, podcast.id);
+            if let Some(api_key) = requester_api_key {
+                local_url = format!("{local_url}?apiKey={api_key}");
+            }
+            outline.add_attribute("xmlUrl", &local_url);
+        }
+        Mode::Online => outline.add_attribute("xmlUrl", &podcast.rssfeed),
+    }
+    if extended {
+        outline.add_attribute("podfetch:active", &podcast.active.to_string());
+        if let Some(settings) = &podcast.settings {
+            let settings = serde_json::to_string(settings)
+                .map_err(|error| OpmlError::Xml(error.to_string()))?;
+            outline.add_attribute("podfetch:settings", &settings);
+        }
+    }
+    Ok(outline)
+}
+
+/// Collects the feeds of an OPML document. Feeds listed below several
+/// categories are returned once with all of them as tags. The `podfetch:`
+/// attributes of extended exports are read as well, which the `opml` crate
+/// would drop.
+pub fn read_opml_feeds(content: &str) -> Result<Vec<OpmlFeed>, OpmlError> {
+    let mut reader = Reader::from_str(content);
+    let mut categories: Vec<Option<String>> = Vec::new();
+    let mut feeds: Vec<OpmlFeed> = Vec::new();
+
+    loop {
+        let (element, is_empty) = match reader.read_event() {
+            Ok(Event::Start(element)) => (element, false),
+            Ok(Event::Empty(element)) => (element, true),
+            Ok(Event::End(element)) => {
+                if element.name().as_ref() == b"outline" {
+                    categories.pop();
+                }
+                continue;
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => continue,
+            Err(error) => return Err(OpmlError::Xml(error.to_string())),
+        };
+        if element.name().as_ref() != b"outline" {
+            continue;
+        }
+
+        let mut feed = OpmlFeed::default();
+        let mut label = None;
+        for attribute in element.attributes() {
+            let attribute = attribute.map_err(|error| OpmlError::Xml(error.to_string()))?;
+            let value = attribute
+                .normalized_value(XmlVersion::Implicit1_0)
+                .map_err(|error| OpmlError::Xml(error.to_string()))?
+                .into_owned();
+            match attribute.key.as_ref() {
+                b"xmlUrl" => feed.xml_url = value,
+                b"text" => label = Some(value),
+                b"title" => {
+                    label.get_or_insert(value);
+                }
+                b"podfetch:active" => feed.active = value.parse().ok(),
+                b"podfetch:settings" => match serde_json::from_str(&value) {
+                    Ok(settings) => feed.settings = Some(settings),
+                    Err(error) => {
+                        tracing::warn!("Ignoring invalid podcast settings in OPML: {error}")
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        if feed.xml_url.is_empty() {
+            if !is_empty {
+                categories.push(label);
+            }
+            continue;
+        }
+        if !is_empty {
+            categories.push(None);
+        }
+        feed.tags = categories.iter().flatten().cloned().collect();
+        match feeds.iter_mut().find(|known| known.xml_url == feed.xml_url) {
+            Some(known) => {
+                for tag in feed.tags {
+                    if !known.tags.contains(&tag) {
+                        known.tags.push(tag);
+                    }
                 }
-                outline.add_attribute("xmlUrl", &local_url);
             }
-            Mode::Online => outline.add_attribute("xmlUrl", &podcast.rssfeed),
+            None => feeds.push(feed),
         }
-        body.add_child(outline).expect("outline should be attached");
     }
-    body
+    Ok(feeds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opml_podcast(id: &str, tags: &[&str]) -> OpmlPodcast {
+        OpmlPodcast {
+            id: id.to_string(),
+            name: format!("Podcast {id}"),
+            summary: None,
+            rssfeed: format!("https://example.com/{id}.xml"),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            active: true,
+            settings: None,
+        }
+    }
+
+    #[test]
+    fn extended_export_is_read_back_with_tags_and_settings() {
+        let mut news = opml_podcast("news", &["Daily", "Favorites"]);
+        news.active = false;
+        news.settings = Some(PodcastSetting {
+            podcast_id: "news".to_string(),
+            podcast_prefill: 3,
+            refresh_mode: "fixed".to_string(),
+            episode_format: "{title} & \"more\"".to_string(),
+            ..Default::default()
+        });
+        let podcasts = vec![news, opml_podcast("talk", &[])];
+
+        let xml = build_opml(podcasts, Mode::Online, None, false, "", true).unwrap();
+        let feeds = read_opml_feeds(&xml).unwrap();
+
+        assert_eq!(feeds.len(), 2);
+        let news = &feeds[0];
+        assert_eq!(news.xml_url, "https://example.com/news.xml");
+        assert_eq!(news.tags, vec!["Daily", "Favorites"]);
+        assert_eq!(news.active, Some(false));
+        let settings = news.settings.as_ref().unwrap();
+        assert_eq!(settings.podcast_prefill, 3);
+        assert_eq!(settings.episode_format, "{title} & \"more\"");
+        assert_eq!(feeds[1].xml_url, "https://example.com/talk.xml");
+        assert!(feeds[1].tags.is_empty());
+        assert_eq!(feeds[1].active, Some(true));
+    }
+
+    #[test]
+    fn plain_export_has_no_podfetch_attributes() {
+        let xml = build_opml(
+            vec![opml_podcast("news", &["Daily"])],
+            Mode::Online,
+            None,
+            false,
+            "",
+            false,
+        )
+        .unwrap();
+
+        assert!(!xml.contains("podfetch:"));
+        let feeds = read_opml_feeds(&xml).unwrap();
+        assert_eq!(feeds[0].tags, vec!["Daily"]);
+        assert_eq!(feeds[0].active, None);
+    }
+
+    #[test]
+    fn nested_categories_of_other_apps_become_tags() {
+        let xml = r#"<?xml version="1.0"?>
+<opml version="1.0"><head><title>Subs</title></head><body>
+  <outline text="Tech"><outline title="Rust">
+    <outline type="rss" text="A" xmlUrl="https://a.example/feed"/>
+  </outline></outline>
+  <outline type="rss" text="B" xmlUrl="https://b.example/feed"></outline>
+</body></opml>"#;
+
+        let feeds = read_opml_feeds(xml).unwrap();
+
+        assert_eq!(feeds.len(), 2);
+        assert_eq!(feeds[0].tags, vec!["Tech", "Rust"]);
+        assert!(feeds[1].tags.is_empty());
+    }
 }
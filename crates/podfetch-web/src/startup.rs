@@ -3,28 +3,37 @@ use crate::auth_middleware::{
     handle_basic_auth, handle_no_auth, handle_oidc_auth, handle_proxy_auth,
 };
 use crate::controllers::agent_ws_controller::get_agent_ws_router;
+use crate::controllers::api_token_controller::get_api_token_router;
+use crate::controllers::audit_log_controller::get_audit_log_router;
 use crate::controllers::cast_controller::get_cast_router;
 use crate::controllers::discover_controller::get_discover_router;
+use crate::controllers::download_queue_controller::get_download_queue_router;
 use crate::controllers::episode_triage_controller::get_episode_triage_router;
 use crate::controllers::file_hosting::podcast_serving;
 use crate::controllers::manifest_controller::get_manifest_router;
 use crate::controllers::mopidy_controller::get_mopidy_router;
+use crate::controllers::notification_channel_controller::get_notification_channel_router;
 use crate::controllers::notification_controller::get_notification_router;
 use crate::controllers::playlist_controller::get_playlist_router;
 use crate::controllers::podcast_controller::{get_podcast_router, proxy_podcast};
 use crate::controllers::podcast_episode_controller::get_podcast_episode_router;
+use crate::controllers::search_controller::get_search_router;
 use crate::controllers::settings_controller::get_settings_router;
 use crate::controllers::sponsorblock_controller::get_sponsorblock_router;
 use crate::controllers::stats_controller::get_stats_router;
 use crate::controllers::sys_info_controller::{get_public_config, get_sys_info_router, login};
 use crate::controllers::tags_controller::get_tags_router;
+use crate::controllers::transcoding_profile_controller::get_transcoding_profile_router;
 use crate::controllers::transcript_controller::get_transcript_router;
+use crate::controllers::two_factor_controller::get_two_factor_router;
 use crate::controllers::user_controller::{get_invite, get_user_router, onboard_user};
 use crate::controllers::watch_time_controller::get_watchtime_router;
 use crate::controllers::websocket_controller::get_websocket_router;
+use crate::metrics::METRICS;
 use crate::routes::global_routes;
 use crate::server::SOCKET_IO_LAYER;
 use crate::services::file::service::FileService;
+use crate::services::podcast::refresh_schedule::RefreshScheduleService;
 use crate::services::podcast::service::PodcastService;
 use crate::services::settings::service::SettingsService;
 use crate::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
@@ -34,10 +43,13 @@ use axum::extract::Request;
 use axum::middleware::from_fn_with_state;
 use axum::response::{Redirect, Response};
 use axum::routing::get;
+use chrono::Utc;
 use clokwerk::{Scheduler, TimeUnits};
 use common_infrastructure::error::{CustomError, CustomErrorInner};
 use common_infrastructure::runtime::{ENVIRONMENT_SERVICE, MAIN_ROOM};
 use maud::{Markup, html};
+use podfetch_domain::api_token::ApiTokenScope;
+use podfetch_persistence::podcast::PodcastEntity as Podcast;
 use socketioxide::SocketIoBuilder;
 use socketioxide::extract::SocketRef;
 use tracing::info;
@@ -94,7 +106,7 @@ fn is_socket_authenticated(socket: &SocketRef, auth_service: &Arc<UserAuthServic
             .or_else(|| param.strip_prefix("token="));
         if let Some(value) = candidate {
             let decoded = urlencoding::decode(value).unwrap_or_default();
-            if auth_service.is_api_key_valid(&decoded) {
+            if auth_service.is_api_key_valid(&decoded, ApiTokenScope::Admin) {
                 return true;
             }
         }
@@ -102,34 +114,54 @@ fn is_socket_authenticated(socket: &SocketRef, auth_service: &Arc<UserAuthServic
     false
 }
 
+/// Refreshes every active podcast whose next refresh is due and schedules the
+/// one after it. Called once a minute by the background scheduler.
 pub fn run_poll() -> Result<(), CustomError> {
-    let podcast_result = PodcastService::get_all_podcasts_raw()?;
-    for podcast in podcast_result {
-        if podcast.active {
-            let podcast_clone = podcast.clone();
-            let insert_result = PodcastEpisodeService::insert_podcast_episodes(&podcast);
-            if let Err(e) = insert_result {
-                tracing::error!(
-                    "Could not insert new podcast episodes for podcast: {} with cause {}",
-                    &podcast.name,
-                    e
-                );
-                continue;
-            }
-            let schedule = PodcastService::schedule_episode_download(&podcast_clone);
-            if let Err(e) = schedule {
-                tracing::error!(
-                    "Could not schedule episode download for podcast: {} with cause {}",
-                    &podcast.name,
-                    e
-                );
-                continue;
-            }
+    let refresh_schedule = RefreshScheduleService::default_service();
+    let now = Utc::now().naive_utc();
+    let active_podcasts = PodcastService::get_all_podcasts_raw()?
+        .into_iter()
+        .filter(|podcast| podcast.active)
+        .collect();
+    let due_podcasts = refresh_schedule.due_podcasts(active_podcasts, now)?;
+    if !due_podcasts.is_empty() {
+        info!("Polling {} podcasts for new episodes", due_podcasts.len());
+    }
+    for podcast in due_podcasts {
+        poll_podcast(&podcast);
+        // Failed refreshes are scheduled as well, so a broken feed is retried
+        // on its own interval instead of on every tick.
+        if let Err(e) = refresh_schedule.schedule_next_refresh(&podcast, Utc::now().naive_utc()) {
+            tracing::error!(
+                "Could not schedule the next refresh for podcast: {} with cause {}",
+                &podcast.name,
+                e
+            );
         }
     }
     Ok(())
 }
 
+fn poll_podcast(podcast: &Podcast) {
+    let insert_result = PodcastEpisodeService::insert_podcast_episodes_if_changed(podcast);
+    if let Err(e) = insert_result {
+        tracing::error!(
+            "Could not insert new podcast episodes for podcast: {} with cause {}",
+            &podcast.name,
+            e
+        );
+        return;
+    }
+    let schedule = PodcastService::schedule_episode_download(podcast);
+    if let Err(e) = schedule {
+        tracing::error!(
+            "Could not schedule episode download for podcast: {} with cause {}",
+            &podcast.name,
+            e
+        );
+    }
+}
+
 fn fix_links(content: &str) -> String {
     let dir = ENVIRONMENT_SERVICE.sub_directory.clone().unwrap() + "/ui/";
     content.replace("/ui/", &dir)
@@ -356,12 +388,19 @@ fn get_private_api(state: AppState) -> OpenApiRouter {
         .merge(get_watchtime_router().with_state(state.clone()))
         .merge(get_stats_router().with_state(state.clone()))
         .merge(get_notification_router().with_state(state.clone()))
+        .merge(get_notification_channel_router().with_state(state.clone()))
         .merge(get_podcast_episode_router().with_state(state.clone()))
         .merge(get_episode_triage_router().with_state(state.clone()))
+        .merge(get_search_router().with_state(state.clone()))
         .merge(get_settings_router().with_state(state.clone()))
         .merge(get_sponsorblock_router().with_state(state.clone()))
         .merge(get_tags_router().with_state(state.clone()))
+        .merge(get_transcoding_profile_router().with_state(state.clone()))
         .merge(get_transcript_router().with_state(state.clone()))
+        .merge(get_download_queue_router().with_state(state.clone()))
+        .merge(get_api_token_router().with_state(state.clone()))
+        .merge(get_audit_log_router().with_state(state.clone()))
+        .merge(get_two_factor_router().with_state(state.clone()))
         .merge(get_user_router().with_state(state.clone()));
 
     if ENVIRONMENT_SERVICE.mopidy_integration_enabled {
@@ -513,16 +552,30 @@ pub fn build_server_router() -> Router {
 /// Goes in front of `TraceLayer` so the message includes the path directly
 /// (TraceLayer puts the URI in a span; the default subscriber doesn't
 /// render span fields, so the message alone would be path-less).
+///
+/// The latency also feeds the request histogram of `/metrics`, labelled by
+/// the route template instead of the raw path to keep the label set small.
 async fn http_request_logger(
     req: axum::extract::Request,
     next: axum::middleware::Next,
 ) -> axum::response::Response {
     let method = req.method().clone();
     let uri = req.uri().clone();
+    let route = req
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|path| path.as_str().to_string());
     let started = std::time::Instant::now();
     let response = next.run(req).await;
-    let elapsed_ms = started.elapsed().as_millis();
+    let elapsed = started.elapsed();
+    let elapsed_ms = elapsed.as_millis();
     let status = response.status().as_u16();
+    METRICS.observe_http_request(
+        method.as_str(),
+        route.as_deref().unwrap_or("unmatched"),
+        status,
+        elapsed,
+    );
     let path_and_query = uri
         .path_and_query()
         .map(|p| p.as_str())
@@ -545,20 +598,26 @@ pub fn handle_config_for_server_startup() -> Router {
     let settings_service_for_polling = AppState::new().settings_service.clone();
     let settings_service_for_cleanup = AppState::new().settings_service.clone();
     let session_service_for_cleanup = AppState::new().session_service.clone();
+    let api_token_service_for_cleanup = AppState::new().api_token_service.clone();
+    let audit_log_service_for_cleanup = AppState::new().audit_log_service.clone();
+    let storage_quota_service_for_cleanup = AppState::new().storage_quota_service.clone();
     thread::spawn(move || {
+        // Accounts for downloads made before storage accounting existed
+        // without waiting a day for the first cleanup run.
+        if let Err(e) = storage_quota_service_for_cleanup.reconcile() {
+            tracing::error!("Error reconciling storage usage: {e}");
+        }
         let mut scheduler = Scheduler::new();
 
-        let polling_interval = ENVIRONMENT_SERVICE.get_polling_interval();
-        scheduler.every(polling_interval.minutes()).run(move || {
+        // Each podcast has its own refresh schedule (see `run_poll`); the
+        // global `POLLING_INTERVAL` is only the default interval.
+        scheduler.every(1.minute()).run(move || {
             let settings = settings_service_for_polling.get_settings().unwrap();
             match settings {
                 Some(settings) => {
                     if settings.auto_update {
-                        info!("Polling for new episodes");
                         match run_poll() {
-                            Ok(_) => {
-                                info!("Polling for new episodes successful");
-                            }
+                            Ok(_) => {}
                             Err(e) => {
                                 tracing::error!("Error polling for new episodes: {e}");
                             }
@@ -575,11 +634,25 @@ pub fn handle_config_for_server_startup() -> Router {
             session_service_for_cleanup
                 .cleanup_expired()
                 .expect("Error clearing old sessions");
+            if let Err(e) = api_token_service_for_cleanup.purge_expired() {
+                tracing::error!("Error purging expired api tokens: {e}");
+            }
             let settings = settings_service_for_cleanup.get_settings().unwrap();
             match settings {
                 Some(settings) => {
-                    if settings.auto_cleanup {
-                        PodcastEpisodeService::cleanup_old_episodes(settings.auto_cleanup_days)
+                    PodcastEpisodeService::cleanup_old_episodes(
+                        settings.auto_cleanup.then_some(settings.auto_cleanup_days),
+                    );
+                    if let Err(e) = audit_log_service_for_cleanup
+                        .purge_expired(settings.audit_log_retention_days)
+                    {
+                        tracing::error!("Error purging expired audit log entries: {e}");
+                    }
+                    if let Err(e) = storage_quota_service_for_cleanup
+                        .reconcile()
+                        .and_then(|_| storage_quota_service_for_cleanup.enforce_quotas(None))
+                    {
+                        tracing::error!("Error enforcing storage quotas: {e}");
                     }
                 }
                 None => {
@@ -604,5 +677,8 @@ pub fn handle_config_for_server_startup() -> Router {
         tokio::spawn(crate::services::transcript::worker::run_transcription_worker());
     }
 
+    // Download queue worker, skipped in tests for the same reason.
+    tokio::spawn(crate::services::download::worker::run_download_worker());
+
     router
 }
@@ -6,9 +6,22 @@ use url::Url;
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
+    /// Current TOTP or recovery code, needed once the user enrolled a
+    /// second factor.
+    #[serde(default)]
+    pub totp_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginResponse {
+    /// Only set after a two-factor login. Basic auth is refused for such
+    /// users, so the client sends this as `Authorization: Bearer` instead.
+    pub token: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
@@ -26,6 +39,19 @@ pub struct SysExtraInfo {
     pub system: SystemDto,
     pub disks: Vec<SimplifiedDisk>,
     pub podcast_directory: u64,
+    pub storage: StorageOverview,
+}
+
+/// Space taken up by downloaded episodes, as recorded after each download.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageOverview {
+    pub used_bytes: i64,
+    /// Instance-wide quota, `None` when unlimited.
+    pub quota_bytes: Option<i64>,
+    /// `refuse` or `cleanup-oldest`.
+    pub quota_policy: String,
+    pub users: Vec<crate::user_admin::UserStorageUsage>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -66,6 +92,10 @@ pub struct SimplifiedDisk {
 
 pub enum LoginDecision {
     Authenticated,
+    /// Password and second factor were valid; carries the api token the
+    /// client authenticates with from now on.
+    AuthenticatedWithToken(String),
+    SecondFactorRequired,
     WrongUserOrPassword,
     Forbidden,
 }
@@ -73,7 +103,12 @@ pub enum LoginDecision {
 pub trait LoginApplicationService {
     type Error;
 
-    fn verify_login(&self, username: &str, password: &str) -> Result<LoginDecision, Self::Error>;
+    fn verify_login(
+        &self,
+        username: &str,
+        password: &str,
+        totp_code: Option<&str>,
+    ) -> Result<LoginDecision, Self::Error>;
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -82,20 +117,31 @@ pub enum LoginControllerError<E: Display> {
     Unauthorized,
     #[error("forbidden")]
     Forbidden,
+    #[error("second factor required")]
+    SecondFactorRequired,
     #[error("{0}")]
     Service(E),
 }
 
-pub fn login<S>(service: &S, request: &LoginRequest) -> Result<(), LoginControllerError<S::Error>>
+pub fn login<S>(
+    service: &S,
+    request: &LoginRequest,
+) -> Result<LoginResponse, LoginControllerError<S::Error>>
 where
     S: LoginApplicationService,
     S::Error: Display,
 {
     match service
-        .verify_login(&request.username, &request.password)
+        .verify_login(
+            &request.username,
+            &request.password,
+            request.totp_code.as_deref(),
+        )
         .map_err(LoginControllerError::Service)?
     {
-        LoginDecision::Authenticated => Ok(()),
+        LoginDecision::Authenticated => Ok(LoginResponse { token: None }),
+        LoginDecision::AuthenticatedWithToken(token) => Ok(LoginResponse { token: Some(token) }),
+        LoginDecision::SecondFactorRequired => Err(LoginControllerError::SecondFactorRequired),
         LoginDecision::WrongUserOrPassword => Err(LoginControllerError::Unauthorized),
         LoginDecision::Forbidden => Err(LoginControllerError::Forbidden),
     }
@@ -118,17 +118,28 @@ END $$;
             "favorite_podcast_episodes",
             "subscriptions",
             "episodes",
+            "episode_fingerprints",
             "episode_sponsor_segments",
+            "episode_storage",
+            "chapter_fetch_failures",
             "podcast_episodes",
             "podcast_episode_chapters",
             "sponsorblock_user_settings",
             "tags_podcasts",
+            "notification_rules",
             "tags",
             "invites",
             "sessions",
+            "api_tokens",
+            "audit_log",
+            "two_factor_auth",
+            "user_storage_quotas",
             "settings",
             "podcast_settings",
             "podcasts",
+            "transcoding_profiles",
+            "notification_channels",
+            "notification_dismissals",
             "notifications",
             "devices",
             "users",
@@ -166,6 +177,8 @@ END $$;
             // value, so we pass the plaintext here.
             std::env::set_var("PASSWORD", "postgres");
             std::env::set_var("API_KEY", "test-api-key");
+            std::env::set_var("METRICS_ENABLED", "true");
+            std::env::set_var("METRICS_TOKEN", "test-metrics-token");
             #[cfg(feature = "sqlite")]
             std::env::set_var("DATABASE_URL", "sqlite://./podcast.db");
             #[cfg(all(feature = "postgresql", not(feature = "sqlite")))]
@@ -32,6 +32,7 @@ pub mod tests {
                 status: self.status,
                 created_at: Time().fake(),
                 type_of_message: "Download".to_string(),
+                user_id: None,
             }
         }
     }
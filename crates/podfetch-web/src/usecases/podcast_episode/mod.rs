@@ -1,40 +1,59 @@
+use crate::metrics::METRICS;
 use crate::notification::Notification;
 use crate::server::ChatServerHandle;
+use crate::services::download::queue::{DownloadQueueService, MANUAL_PRIORITY};
 use crate::services::download::service::DownloadService;
 use crate::services::episode_triage::service::EpisodeTriageService;
 use crate::services::favorite_podcast_episode::service::FavoritePodcastEpisodeService;
+use crate::services::feed_health::lock::{feed_lock, may_move};
+use crate::services::feed_health::service::FeedHealthService;
 use crate::services::file::service::FileService;
+use crate::services::notification::rules::{EpisodeFacts, NotificationRuleService};
 use crate::services::notification::service::NotificationService;
+use crate::services::notification_channel::channel::NotificationEvent;
+use crate::services::notification_channel::service::NotificationChannelService;
 use crate::services::playlist::service::PlaylistService;
+use crate::services::podcast::episode_numbers::EpisodeNumbers;
 use crate::services::podcast::metadata::PodcastBuilder;
+use crate::services::podcast::refresh_schedule::feed_update_interval_hint;
+use crate::services::podcast_episode_chapter::feed::extract_chapters_url;
+use crate::services::podcast_episode_chapter::service::PodcastEpisodeChapterService;
 use crate::services::podcast_settings::service::PodcastSettingsService;
+use crate::services::retention::service::RetentionService;
 use crate::services::settings::service::SettingsService;
+use crate::services::storage_quota::service::StorageQuotaService;
 use crate::services::transcript::service::{FeedTranscriptTag, TranscriptService};
 use chrono::{DateTime, FixedOffset, Utc};
 use common_infrastructure::config::FileHandlerType;
-use common_infrastructure::config::TELEGRAM_API_ENABLED;
-use common_infrastructure::config::is_env_var_present_and_true;
 use common_infrastructure::error::ErrorSeverity::{Critical, Warning};
 use common_infrastructure::error::{
     CustomError, CustomErrorInner, ErrorSeverity, map_db_error, map_reqwest_error,
 };
+use common_infrastructure::feed::parse_feed;
 use common_infrastructure::http::COMMON_USER_AGENT;
 use common_infrastructure::http::get_sync_client;
 use common_infrastructure::mutex::LockResultExt;
 use common_infrastructure::retry::do_retry;
 use common_infrastructure::runtime::ENVIRONMENT_SERVICE;
-use common_infrastructure::telegram::send_new_episode_notification;
 use common_infrastructure::time::opt_or_empty_string;
 use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
-use podfetch_domain::podcast_episode::{NewPodcastEpisode, PodcastEpisodeRepository};
+use podfetch_domain::podcast_episode::{
+    EpisodeCandidateFilter, EpisodeListFilter, NewPodcastEpisode, PodcastEpisodeRepository,
+};
+use podfetch_domain::podcast_feed::{PodcastFeedState, PodcastFeedStateRepository};
 use podfetch_domain::user::User;
+use podfetch_persistence::adapters::PodcastFeedStateRepositoryImpl;
 use podfetch_persistence::db::database;
 use podfetch_persistence::db::get_connection;
 use podfetch_persistence::podcast::PodcastEntity as Podcast;
 use podfetch_persistence::podcast_episode::DieselPodcastEpisodeRepository;
 use podfetch_persistence::podcast_episode::PodcastEpisodeEntity as PodcastEpisode;
 use podfetch_storage::{FileHandleWrapper, FileRequest};
-use reqwest::header::{ACCEPT, HeaderMap};
+use reqwest::StatusCode;
+use reqwest::header::{
+    ACCEPT, CONTENT_TYPE, ETAG, HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+    LAST_MODIFIED,
+};
 use reqwest::redirect::Policy;
 use rss::{Channel, Guid, Item};
 use std::collections::HashSet;
@@ -44,10 +63,14 @@ use std::path::Path;
 use std::sync::LazyLock;
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Instant;
 use url::Url;
 use uuid::Uuid;
 
 pub struct PodcastEpisodeUseCase;
+
+const MAX_FEED_REDIRECTS: usize = 10;
+
 static IN_PROGRESS_DOWNLOADS: LazyLock<Mutex<HashSet<String>>> =
     LazyLock::new(|| Mutex::new(HashSet::new()));
 
@@ -111,6 +134,15 @@ impl PodcastEpisodeUseCase {
             .map_err(Into::into)
     }
 
+    pub fn get_podcast_episodes_by_internal_ids(
+        podcast_episode_ids: &[Uuid],
+    ) -> Result<Vec<PodcastEpisode>, CustomError> {
+        Self::repo()
+            .find_by_ids(podcast_episode_ids)
+            .map(|episodes| episodes.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
     /// Resolve an episode by its pre-migration integer id (backwards-compat).
     pub fn get_podcast_episode_by_legacy_id(
         legacy_id: i64,
@@ -230,6 +262,7 @@ impl PodcastEpisodeUseCase {
             item.guid.as_ref().map(|g| g.value.as_str()),
             item.enclosure.as_ref().map(|e| e.url.as_str()),
         );
+        let numbers = EpisodeNumbers::from_item(item);
 
         Self::repo()
             .create(NewPodcastEpisode {
@@ -246,11 +279,38 @@ impl PodcastEpisodeUseCase {
                 description: opt_or_empty_string(item.clone().description),
                 guid: item.guid.clone().unwrap_or(guid_to_insert).value,
                 youtube_video_id,
+                season: numbers.season,
+                episode_number: numbers.episode_number,
+                episode_type: numbers.episode_type,
             })
             .map(Into::into)
             .map_err(Into::into)
     }
 
+    /// Fetches `<podcast:chapters>` documents in the background so a refresh
+    /// doesn't wait for one request per episode.
+    fn sync_feed_chapters(feed_chapters: Vec<(String, String)>) {
+        if feed_chapters.is_empty() {
+            return;
+        }
+        thread::spawn(move || {
+            let chapter_service = PodcastEpisodeChapterService::default_service();
+            for (episode_id, url) in feed_chapters {
+                let Ok(episode_uuid) = Self::parse_id(&episode_id) else {
+                    continue;
+                };
+                if let Err(err) = chapter_service.sync_feed_chapters(episode_uuid, &url) {
+                    tracing::error!(
+                        "Error fetching chapters {} for episode {}: {}",
+                        url,
+                        episode_id,
+                        err
+                    );
+                }
+            }
+        });
+    }
+
     /// Bridges a feed item's `<podcast:transcript>` tags into transcript
     /// bookkeeping (Task 6's `TranscriptService::upsert_from_feed`) right
     /// after the episode row they belong to has been created or updated.
@@ -290,6 +350,7 @@ impl PodcastEpisodeUseCase {
         podcast_id: Uuid,
         last_id: Option<String>,
         only_unlistened: Option<bool>,
+        filter: &EpisodeListFilter,
         user: &User,
     ) -> PodcastEpisodeWithFavorited {
         Self::repo()
@@ -298,6 +359,7 @@ impl PodcastEpisodeUseCase {
                 &user.username,
                 last_id.as_deref(),
                 only_unlistened.unwrap_or(false),
+                filter,
                 75,
             )
             .map(|rows| {
@@ -381,6 +443,15 @@ impl PodcastEpisodeUseCase {
             .map_err(Into::into)
     }
 
+    pub fn get_candidate_episodes(
+        filter: &EpisodeCandidateFilter,
+    ) -> Result<Vec<PodcastEpisode>, CustomError> {
+        Self::repo()
+            .find_candidates(filter)
+            .map(|episodes| episodes.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
     pub fn get_podcast_episodes_older_than_days(
         days: i32,
         podcast_id: Uuid,
@@ -392,7 +463,12 @@ impl PodcastEpisodeUseCase {
     }
 
     pub fn remove_download_status_of_episode(id: Uuid) -> Result<(), CustomError> {
-        do_retry(|| Self::repo().remove_download_status(id).map_err(Into::into))
+        do_retry(|| {
+            Self::repo()
+                .remove_download_status(id)
+                .map_err(CustomError::from)
+        })?;
+        StorageQuotaService::default_service().forget(id)
     }
 
     pub fn get_episodes_by_podcast_id(id: Uuid) -> Result<Vec<PodcastEpisode>, CustomError> {
@@ -441,27 +517,31 @@ impl PodcastEpisodeUseCase {
             .map_err(Into::into)
     }
 
-    pub fn download_podcast_episode_if_not_locally_available(
-        podcast_episode: PodcastEpisode,
-        podcast: Podcast,
+    pub fn update_loudness(
+        episode_id: &str,
+        loudness_lufs: Option<f64>,
+        loudness_true_peak: Option<f64>,
     ) -> Result<(), CustomError> {
-        let podcast_episode_cloned = podcast_episode.clone();
-
-        if podcast_episode.is_downloaded() {
-            return Ok(());
-        }
-        let podcast_inserted = Self::perform_download(&podcast_episode_cloned, &podcast)?;
-        ChatServerHandle::broadcast_podcast_episode_offline_available(&podcast_inserted, &podcast);
-
-        if is_env_var_present_and_true(TELEGRAM_API_ENABLED) {
-            send_new_episode_notification(&podcast_episode.name, &podcast.name)
-        }
-        Ok(())
+        Self::repo()
+            .update_loudness(episode_id, loudness_lufs, loudness_true_peak)
+            .map_err(Into::into)
     }
 
     pub fn perform_download(
         podcast_episode: &PodcastEpisode,
         podcast_cloned: &Podcast,
+    ) -> Result<PodcastEpisode, CustomError> {
+        Self::try_download(podcast_episode, podcast_cloned)
+            .inspect_err(|err| Self::notify_download_failed(podcast_episode, err, None))
+    }
+
+    /// Downloads the episode and marks it as available, without the
+    /// `DownloadFailed` notification of [`Self::perform_download`]. The
+    /// download queue uses this so that only the final failed attempt of a
+    /// job notifies.
+    pub fn try_download(
+        podcast_episode: &PodcastEpisode,
+        podcast_cloned: &Podcast,
     ) -> Result<PodcastEpisode, CustomError> {
         let _guard = match Self::try_acquire_download_guard(&podcast_episode.episode_id) {
             Some(guard) => guard,
@@ -474,39 +554,82 @@ impl PodcastEpisodeUseCase {
             }
         };
         tracing::info!("Downloading podcast episode: {}", podcast_episode.name);
-        if let Err(err) =
-            DownloadService::download_podcast_episode(podcast_episode.clone(), podcast_cloned)
-        {
-            if let Err(notification_err) = NotificationService::create_notification(Notification {
-                id: String::new(),
-                message: format!("{} ({})", podcast_episode.name, err.inner),
-                created_at: chrono::Utc::now().naive_utc().to_string(),
-                type_of_message: "DownloadFailed".to_string(),
-                status: "unread".to_string(),
-            }) {
-                tracing::error!(
-                    "Failed to insert failed-download notification for episode {}: {}",
-                    podcast_episode.episode_id,
-                    notification_err
-                );
-            }
-            return Err(err);
-        }
+        DownloadService::download_podcast_episode(podcast_episode.clone(), podcast_cloned)?;
         let podcast = Self::update_podcast_episode_status(
             &podcast_episode.url,
             Some(ENVIRONMENT_SERVICE.default_file_handler.clone()),
         )?;
-        let notification = Notification {
-            id: String::new(),
-            message: podcast_episode.name.to_string(),
-            created_at: chrono::Utc::now().naive_utc().to_string(),
-            type_of_message: "Download".to_string(),
-            status: "unread".to_string(),
-        };
-        NotificationService::create_notification(notification)?;
+        // Makes room when the `cleanup-oldest` quota policy is active. The
+        // download itself already succeeded, so failures are only logged.
+        if let Err(err) = StorageQuotaService::default_service()
+            .enforce_quotas(Some(Self::parse_id(&podcast_episode.id)?))
+        {
+            tracing::error!("Error enforcing storage quotas: {err}");
+        }
+        let recipients = NotificationRuleService::default_service().recipients(&EpisodeFacts {
+            podcast_id: Self::parse_id(&podcast_cloned.id)?,
+            title: &podcast_episode.name,
+            duration_seconds: podcast_episode.total_time,
+        })?;
+        let created_at = chrono::Utc::now().naive_utc().to_string();
+        for user_id in recipients {
+            NotificationService::create_notification(Notification {
+                id: String::new(),
+                message: podcast_episode.name.to_string(),
+                created_at: created_at.clone(),
+                type_of_message: "Download".to_string(),
+                status: "unread".to_string(),
+                user_id: Some(user_id.to_string()),
+            })?;
+        }
         Ok(podcast)
     }
 
+    /// Tells the user who asked for the download that it failed, or the
+    /// subscribers of the podcast when nobody did.
+    pub fn notify_download_failed(
+        podcast_episode: &PodcastEpisode,
+        err: &CustomError,
+        requested_by: Option<Uuid>,
+    ) {
+        let recipients = match requested_by {
+            Some(user_id) => Ok(vec![user_id]),
+            None => Self::parse_id(&podcast_episode.podcast_id).and_then(|podcast_id| {
+                NotificationRuleService::default_service().subscribers(podcast_id)
+            }),
+        };
+        let created_at = chrono::Utc::now().naive_utc().to_string();
+        let notified = recipients.and_then(|recipients| {
+            recipients.into_iter().try_for_each(|user_id| {
+                NotificationService::create_notification(Notification {
+                    id: String::new(),
+                    message: format!("{} ({})", podcast_episode.name, err.inner),
+                    created_at: created_at.clone(),
+                    type_of_message: "DownloadFailed".to_string(),
+                    status: "unread".to_string(),
+                    user_id: Some(user_id.to_string()),
+                })
+                .map(|_| ())
+            })
+        });
+        if let Err(notification_err) = notified {
+            tracing::error!(
+                "Failed to insert failed-download notification for episode {}: {}",
+                podcast_episode.episode_id,
+                notification_err
+            );
+        }
+        let podcast_name = Self::parse_id(&podcast_episode.podcast_id)
+            .and_then(crate::services::podcast::service::PodcastService::get_podcast)
+            .map(|podcast| podcast.name)
+            .unwrap_or_else(|_| podcast_episode.podcast_id.clone());
+        NotificationChannelService::notify(NotificationEvent::download_failed(
+            &podcast_name,
+            &podcast_episode.name,
+            &err.inner.to_string(),
+        ));
+    }
+
     pub fn get_last_n_podcast_episodes(
         podcast: Podcast,
     ) -> Result<Vec<PodcastEpisode>, CustomError> {
@@ -531,16 +654,142 @@ impl PodcastEpisodeUseCase {
     // Used for creating/updating podcasts
     #[tracing::instrument(skip_all, fields(podcast_id = podcast.id, podcast_name = %podcast.name))]
     pub fn insert_podcast_episodes(podcast: &Podcast) -> Result<Vec<PodcastEpisode>, CustomError> {
-        let is_redirected = Arc::new(Mutex::new(false)); // Variable to store the redirection status
+        let started = Instant::now();
+        let mut http_status = None;
+        let result = Self::do_request_to_podcast_server(podcast.clone(), &mut http_status)
+            .and_then(|returned_data_from_podcast_insert| {
+                Self::insert_podcast_episodes_from_response(
+                    podcast,
+                    returned_data_from_podcast_insert,
+                )
+            });
+        METRICS.observe_feed_refresh(
+            &podcast.id,
+            &podcast.name,
+            started.elapsed(),
+            result.is_ok(),
+        );
+        Self::record_feed_health(podcast, http_status, &result);
+        result
+    }
+
+    /// Like [`Self::insert_podcast_episodes`], but asks the server whether the
+    /// feed changed since the last refresh. Returns `None` without touching
+    /// the episodes when the server answers `304 Not Modified` or sends the
+    /// same document as last time.
+    #[tracing::instrument(skip_all, fields(podcast_id = podcast.id, podcast_name = %podcast.name))]
+    pub fn insert_podcast_episodes_if_changed(
+        podcast: &Podcast,
+    ) -> Result<Option<Vec<PodcastEpisode>>, CustomError> {
+        let started = Instant::now();
+        let mut http_status = None;
+        let result = Self::refresh_podcast_episodes_if_changed(podcast, &mut http_status);
+        METRICS.observe_feed_refresh(
+            &podcast.id,
+            &podcast.name,
+            started.elapsed(),
+            result.is_ok(),
+        );
+        Self::record_feed_health(podcast, http_status, &result);
+        result
+    }
+
+    fn record_feed_health<T>(
+        podcast: &Podcast,
+        http_status: Option<u16>,
+        result: &Result<T, CustomError>,
+    ) {
+        let feed_health = FeedHealthService::default_service();
+        match result {
+            Ok(_) => feed_health.record_success(podcast, http_status),
+            Err(err) => feed_health.record_failure(podcast, http_status, err),
+        }
+    }
+
+    fn refresh_podcast_episodes_if_changed(
+        podcast: &Podcast,
+        http_status: &mut Option<u16>,
+    ) -> Result<Option<Vec<PodcastEpisode>>, CustomError> {
+        let podcast_id = Self::parse_id(&podcast.id)?;
+        let feed_state = Self::feed_state_repo().get(podcast_id)?;
+
+        let Some(response) = Self::do_conditional_request_to_podcast_server(
+            podcast.clone(),
+            feed_state.as_ref(),
+            http_status,
+        )?
+        else {
+            tracing::info!(
+                "Feed of podcast {} not modified (304), skipping episode refresh",
+                podcast.name
+            );
+            return Ok(None);
+        };
+
+        let content_hash = sha256::digest(&response.content);
+        if let Some(feed_state) = feed_state
+            && feed_state.content_hash.as_deref() == Some(content_hash.as_str())
+        {
+            tracing::info!(
+                "Feed of podcast {} is unchanged, skipping episode refresh",
+                podcast.name
+            );
+            // The server may have handed out new validators for the same body.
+            Self::store_feed_state(
+                podcast_id,
+                &response,
+                content_hash,
+                feed_state.update_interval_hint,
+            );
+            return Ok(None);
+        }
+
+        Self::insert_podcast_episodes_from_response(podcast, response).map(Some)
+    }
+
+    fn feed_state_repo() -> PodcastFeedStateRepositoryImpl {
+        PodcastFeedStateRepositoryImpl::new(database())
+    }
 
-        let returned_data_from_podcast_insert =
-            Self::do_request_to_podcast_server(podcast.clone())?;
+    /// Remembers validators and hash of a processed feed. A failure only costs
+    /// a full refresh on the next poll, so it is logged rather than returned.
+    fn store_feed_state(
+        podcast_id: Uuid,
+        response: &RequestReturnType,
+        content_hash: String,
+        update_interval_hint: Option<i32>,
+    ) {
+        let next_refresh_at = Self::feed_state_repo()
+            .get(podcast_id)
+            .ok()
+            .flatten()
+            .and_then(|state| state.next_refresh_at);
+        let state = PodcastFeedState {
+            podcast_id,
+            etag: response.etag.clone(),
+            last_modified: response.last_modified.clone(),
+            content_hash: Some(content_hash),
+            update_interval_hint,
+            next_refresh_at,
+            updated_at: Utc::now().naive_utc(),
+        };
+        if let Err(err) = Self::feed_state_repo().upsert(state) {
+            tracing::warn!("Could not store feed state of podcast {podcast_id}: {err}");
+        }
+    }
 
-        let channel = Channel::read_from(returned_data_from_podcast_insert.content.as_bytes());
+    fn insert_podcast_episodes_from_response(
+        podcast: &Podcast,
+        returned_data_from_podcast_insert: RequestReturnType,
+    ) -> Result<Vec<PodcastEpisode>, CustomError> {
+        let channel = parse_feed(
+            returned_data_from_podcast_insert.content.as_bytes(),
+            returned_data_from_podcast_insert.content_type.as_deref(),
+        );
 
         match channel {
             Ok(channel) => {
-                if *is_redirected.clone().lock().ignore_poison() {
+                if returned_data_from_podcast_insert.permanent_redirect {
                     tracing::info!(
                         "The podcast {} has moved to {}",
                         podcast.name,
@@ -558,6 +807,7 @@ impl PodcastEpisodeUseCase {
                 Self::update_podcast_fields(channel.clone(), Self::parse_id(&podcast.id)?)?;
 
                 let mut podcast_inserted = Vec::new();
+                let mut feed_chapters = Vec::new();
 
                 Self::handle_podcast_image_insert(podcast, &channel)?;
 
@@ -614,15 +864,33 @@ impl PodcastEpisodeUseCase {
                             updated_podcast_episode.image_url = podcast.original_image_url.clone();
                         }
 
+                        let numbers = EpisodeNumbers::from_item(item);
+                        if numbers.season.is_some() {
+                            updated_podcast_episode.season = numbers.season;
+                        }
+                        if numbers.episode_number.is_some() {
+                            updated_podcast_episode.episode_number = numbers.episode_number;
+                        }
+                        if numbers.episode_type.is_some() {
+                            updated_podcast_episode.episode_type = numbers.episode_type;
+                        }
+
                         if updated_podcast_episode.name != podcast_episode.name
                             || updated_podcast_episode.url != podcast_episode.url
                             || updated_podcast_episode.description != podcast_episode.description
                             || updated_podcast_episode.image_url != podcast_episode.image_url
+                            || updated_podcast_episode.season != podcast_episode.season
+                            || updated_podcast_episode.episode_number
+                                != podcast_episode.episode_number
+                            || updated_podcast_episode.episode_type != podcast_episode.episode_type
                         {
                             Self::update_podcast_episode(updated_podcast_episode.clone())?;
                         }
 
                         Self::sync_transcript_tags_for_episode(item, &podcast_episode.id);
+                        if let Some(url) = extract_chapters_url(item) {
+                            feed_chapters.push((podcast_episode.id.clone(), url));
+                        }
 
                         // Skip already existing episodes with insert
                         continue;
@@ -657,8 +925,19 @@ impl PodcastEpisodeUseCase {
                         duration_of_podcast_episode as i32,
                     )?;
                     Self::sync_transcript_tags_for_episode(item, &inserted_episode.id);
+                    if let Some(url) = extract_chapters_url(item) {
+                        feed_chapters.push((inserted_episode.id.clone(), url));
+                    }
                     podcast_inserted.push(inserted_episode);
                 }
+                let content_hash = sha256::digest(&returned_data_from_podcast_insert.content);
+                Self::store_feed_state(
+                    Self::parse_id(&podcast.id)?,
+                    &returned_data_from_podcast_insert,
+                    content_hash,
+                    feed_update_interval_hint(&channel),
+                );
+                Self::sync_feed_chapters(feed_chapters);
                 Ok(podcast_inserted)
             }
             Err(e) => {
@@ -698,25 +977,70 @@ impl PodcastEpisodeUseCase {
         Ok(())
     }
 
+    /// Follows `<itunes:new-feed-url>`. The stored URL only changes once the
+    /// new feed could be fetched and parsed, so a typo in the tag does not
+    /// break a working podcast, and a `<podcast:locked>` feed only moves to
+    /// a feed locked by the same owner.
     fn handle_itunes_extension(podcast: &Podcast, channel: &Channel) -> Result<(), CustomError> {
-        if let Some(extension) = &channel.itunes_ext
-            && let Some(new_feed) = &extension.new_feed_url
-        {
-            let new_url = new_feed;
-            crate::services::podcast::service::PodcastService::update_podcast_urls_on_redirect(
-                Self::parse_id(&podcast.id)?,
-                new_url,
-            );
-
-            let returned_data_from_server = Self::do_request_to_podcast_server(podcast.clone())?;
+        let Some(new_url) = channel
+            .itunes_ext
+            .as_ref()
+            .and_then(|extension| extension.new_feed_url.as_deref())
+            .map(str::trim)
+        else {
+            return Ok(());
+        };
+        if new_url == podcast.rssfeed || !Self::is_http_url(new_url) {
+            return Ok(());
+        }
 
-            let channel = Channel::read_from(returned_data_from_server.content.as_bytes()).unwrap();
-            let items = channel.items();
-            Self::update_episodes_on_redirect(items)?;
+        let mut moved_podcast = podcast.clone();
+        moved_podcast.rssfeed = new_url.to_string();
+        let new_channel =
+            Self::do_request_to_podcast_server(moved_podcast, &mut None).and_then(|response| {
+                parse_feed(
+                    response.content.as_bytes(),
+                    response.content_type.as_deref(),
+                )
+                .map_err(|err| {
+                    CustomErrorInner::BadRequest(err.to_string(), ErrorSeverity::Warning).into()
+                })
+            });
+        match new_channel {
+            Ok(new_channel)
+                if !may_move(
+                    feed_lock(channel).as_ref(),
+                    feed_lock(&new_channel).as_ref(),
+                ) =>
+            {
+                tracing::warn!(
+                    "Ignoring new feed url {} of podcast {}: the feed is locked to another owner",
+                    new_url,
+                    podcast.name
+                );
+            }
+            Ok(new_channel) => {
+                tracing::info!("The podcast {} has moved to {}", podcast.name, new_url);
+                crate::services::podcast::service::PodcastService::update_podcast_urls_on_redirect(
+                    Self::parse_id(&podcast.id)?,
+                    new_url,
+                );
+                Self::update_episodes_on_redirect(new_channel.items())?;
+            }
+            Err(err) => tracing::warn!(
+                "Ignoring new feed url {} of podcast {}: {}",
+                new_url,
+                podcast.name,
+                err
+            ),
         }
         Ok(())
     }
 
+    fn is_http_url(url: &str) -> bool {
+        Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    }
+
     fn update_episodes_on_redirect(items: &[Item]) -> Result<(), CustomError> {
         for item in items.iter() {
             match &item.guid {
@@ -839,93 +1163,52 @@ impl PodcastEpisodeUseCase {
         Ok(())
     }
 
-    pub fn cleanup_old_episodes(days_from_settings: i32) {
-        let podcasts = crate::services::podcast::service::PodcastService::get_all_podcasts_raw();
-
-        if podcasts.is_err() {
-            return;
+    /// Deletes the downloads the retention policies of the podcasts pick.
+    /// `days_from_settings` is the instance-wide age limit, `None` while the
+    /// automatic cleanup is turned off.
+    pub fn cleanup_old_episodes(days_from_settings: Option<i32>) {
+        match RetentionService::default_service().apply(days_from_settings) {
+            Ok(deleted) => tracing::info!("Retention cleanup deleted {deleted} downloads"),
+            Err(err) => tracing::error!("Error running the retention cleanup: {err}"),
         }
+    }
 
-        for p in podcasts.unwrap() {
-            let p_uuid = match Self::parse_id(&p.id) {
-                Ok(id) => id,
-                Err(_) => continue,
-            };
-            let podcast_settings = PodcastSettingsService::get_settings_for_podcast(p_uuid);
-            if podcast_settings.is_err() {
-                continue;
-            }
-            let days = match podcast_settings.unwrap() {
-                Some(podcast_settings) if podcast_settings.auto_cleanup => {
-                    podcast_settings.auto_cleanup_days
-                }
-                _ => days_from_settings,
-            };
-
-            let old_podcast_episodes =
-                match Self::get_podcast_episodes_older_than_days(days, p_uuid) {
-                    Ok(episodes) => episodes,
-                    Err(err) => {
-                        tracing::error!(
-                            "Error loading old podcast episodes for podcast {}: {}",
-                            p.id,
-                            err
-                        );
-                        continue;
-                    }
-                };
-
-            tracing::info!("Cleaning up {} old episodes", old_podcast_episodes.len());
-            for old_podcast_episode in old_podcast_episodes {
-                let episode_id = match Self::parse_id(&old_podcast_episode.id) {
-                    Ok(id) => id,
-                    Err(err) => {
-                        tracing::error!("Invalid episode id {}: {}", old_podcast_episode.id, err);
-                        continue;
-                    }
-                };
-                match FavoritePodcastEpisodeService::default_service()
-                    .is_liked_by_someone(episode_id)
-                {
-                    Ok(true) => {
-                        continue;
-                    }
-                    Ok(false) => {}
-                    Err(e) => {
-                        tracing::error!("Error checking if podcast episode is liked.{e}");
-                    }
-                }
-
-                let res = FileService::cleanup_old_episode(&old_podcast_episode);
-
-                match res {
-                    Ok(_) => {
-                        if let Err(err) = Self::remove_download_status_of_episode(episode_id) {
-                            tracing::error!(
-                                "Error clearing download status for episode {}: {}",
-                                old_podcast_episode.id,
-                                err
-                            );
-                        }
-                    }
-                    Err(e) => {
-                        println!("Error deleting podcast episode.{e}");
-                    }
-                }
-            }
-        }
+    fn do_request_to_podcast_server(
+        podcast: Podcast,
+        http_status: &mut Option<u16>,
+    ) -> Result<RequestReturnType, CustomError> {
+        Self::do_conditional_request_to_podcast_server(podcast, None, http_status)?.ok_or_else(
+            || {
+                CustomErrorInner::BadRequest(
+                    "Podcast server answered an unconditional request with 304".to_string(),
+                    ErrorSeverity::Warning,
+                )
+                .into()
+            },
+        )
     }
 
-    fn do_request_to_podcast_server(podcast: Podcast) -> Result<RequestReturnType, CustomError> {
-        let is_redirected = Arc::new(Mutex::new(false)); // Variable to store the redirection status
+    /// Fetches the feed, sending the validators of `feed_state` as
+    /// `If-None-Match`/`If-Modified-Since`. Returns `None` when the server
+    /// answers `304 Not Modified`. `http_status` receives the status of the
+    /// final response, also when it is turned into an error.
+    fn do_conditional_request_to_podcast_server(
+        podcast: Podcast,
+        feed_state: Option<&PodcastFeedState>,
+        http_status: &mut Option<u16>,
+    ) -> Result<Option<RequestReturnType>, CustomError> {
+        // Statuses of the redirects on the way to the feed. Only a chain of
+        // permanent ones moves the podcast to the new URL.
+        let redirects = Arc::new(Mutex::new(Vec::new()));
         let client = get_sync_client(&ENVIRONMENT_SERVICE)
             .redirect(Policy::custom({
-                let is_redirected = Arc::clone(&is_redirected);
+                let redirects = Arc::clone(&redirects);
 
                 move |attempt| {
-                    if !attempt.previous().is_empty() {
-                        *is_redirected.lock().unwrap() = true;
+                    if attempt.previous().len() > MAX_FEED_REDIRECTS {
+                        return attempt.error("too many redirects");
                     }
+                    redirects.lock().ignore_poison().push(attempt.status());
                     attempt.follow()
                 }
             }))
@@ -935,18 +1218,72 @@ impl PodcastEpisodeUseCase {
         header_map.append(
             ACCEPT,
             // Safe as it is a standard header
-            "application/rss+xml,application/xml".parse().unwrap(),
+            "application/rss+xml,application/atom+xml,application/feed+json,application/xml,application/json"
+                .parse()
+                .unwrap(),
         );
         header_map.append("User-Agent", COMMON_USER_AGENT.parse().unwrap());
+        if let Some(feed_state) = feed_state {
+            // Validators are stored verbatim from earlier responses; skip any
+            // that aren't valid header values instead of failing the poll.
+            if let Some(etag) = feed_state.etag.as_deref().and_then(|v| v.parse().ok()) {
+                header_map.append(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = feed_state
+                .last_modified
+                .as_deref()
+                .and_then(|v| v.parse().ok())
+            {
+                header_map.append(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
         let result = client
             .get(podcast.clone().rssfeed)
             .headers(header_map)
             .send()
             .map_err(map_reqwest_error)?;
+        *http_status = Some(result.status().as_u16());
+        if result.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if !result.status().is_success() {
+            return Err(CustomErrorInner::BadRequest(
+                format!("Feed server answered with HTTP {}", result.status()),
+                ErrorSeverity::Warning,
+            )
+            .into());
+        }
+        let permanent_redirect = {
+            let redirects = redirects.lock().ignore_poison();
+            !redirects.is_empty()
+                && redirects.iter().all(|status| {
+                    matches!(
+                        *status,
+                        StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT
+                    )
+                })
+        };
         let url = result.url().clone().to_string();
+        let header_value = |name| {
+            result
+                .headers()
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header_value(ETAG);
+        let last_modified = header_value(LAST_MODIFIED);
+        let content_type = header_value(CONTENT_TYPE);
         let content = result.text().map_err(map_reqwest_error)?;
 
-        Ok(RequestReturnType { url, content })
+        Ok(Some(RequestReturnType {
+            url,
+            content,
+            etag,
+            last_modified,
+            content_type,
+            permanent_redirect,
+        }))
     }
 
     pub(crate) fn delete_podcast_episode_locally(
@@ -968,23 +1305,30 @@ impl PodcastEpisodeUseCase {
         }
     }
 
-    /// Downloads every episode of the podcast whose DB row has no
+    /// Queues every episode of the podcast whose DB row has no
     /// `download_location` (i.e. never downloaded) and is not soft-deleted.
-    /// Runs downloads in parallel chunks of 3, matching `schedule_episode_download`.
     /// Returns the number of episodes that were queued.
-    pub fn download_missing_episodes_for_podcast(podcast: &Podcast) -> Result<usize, CustomError> {
+    pub fn download_missing_episodes_for_podcast(
+        podcast: &Podcast,
+        requested_by: Uuid,
+    ) -> Result<usize, CustomError> {
         let episodes = Self::get_episodes_by_podcast_id(Self::parse_id(&podcast.id)?)?;
-        Ok(Self::download_missing_in(podcast, episodes))
+        DownloadQueueService::default_service().enqueue_missing(
+            episodes,
+            MANUAL_PRIORITY,
+            Some(requested_by),
+        )
     }
 
-    /// Download the episodes whose chronological position (oldest = 1) falls
+    /// Queue the episodes whose chronological position (oldest = 1) falls
     /// within the inclusive `[from, to]` range. Positions match
     /// `get_position_of_episode` (ascending by publication date). Only missing
-    /// episodes are fetched. Returns how many episodes were queued.
+    /// episodes are queued. Returns how many episodes were queued.
     pub fn download_episode_range_for_podcast(
         podcast: &Podcast,
         from: usize,
         to: usize,
+        requested_by: Uuid,
     ) -> Result<usize, CustomError> {
         let mut episodes = Self::get_episodes_by_podcast_id(Self::parse_id(&podcast.id)?)?;
         episodes.sort_by(|a, b| a.date_of_recording.cmp(&b.date_of_recording));
@@ -995,93 +1339,37 @@ impl PodcastEpisodeUseCase {
         } else {
             episodes[start..end].to_vec()
         };
-        Ok(Self::download_missing_in(podcast, slice))
-    }
-
-    /// Download every not-yet-downloaded, non-deleted episode in `episodes`
-    /// (in parallel, capped at `MAX_PARALLEL_DOWNLOADS`). Returns how many
-    /// episodes were queued.
-    fn download_missing_in(podcast: &Podcast, episodes: Vec<PodcastEpisode>) -> usize {
-        const MAX_PARALLEL_DOWNLOADS: usize = 3;
-        let missing: Vec<PodcastEpisode> = episodes
-            .into_iter()
-            .filter(|e| !e.deleted && !e.is_downloaded())
-            .collect();
-        let count = missing.len();
-
-        for chunk in missing.chunks(MAX_PARALLEL_DOWNLOADS) {
-            let mut handles = Vec::with_capacity(chunk.len());
-            for episode in chunk.iter().cloned() {
-                let podcast_for_thread = podcast.clone();
-                handles.push(thread::spawn(move || {
-                    if let Err(err) = Self::download_podcast_episode_if_not_locally_available(
-                        episode,
-                        podcast_for_thread,
-                    ) {
-                        tracing::error!("Error downloading podcast episode: {err}");
-                    }
-                }));
-            }
-            for handle in handles {
-                if let Err(err) = handle.join() {
-                    tracing::error!(
-                        "Error joining download worker for podcast {}: {:?}",
-                        podcast.id,
-                        err
-                    );
-                }
-            }
-        }
-        count
+        DownloadQueueService::default_service().enqueue_missing(
+            slice,
+            MANUAL_PRIORITY,
+            Some(requested_by),
+        )
     }
 
     /// Re-downloads episodes whose DB row says they are downloaded but whose
-    /// file is missing on disk / in the configured backend. Uses
-    /// `perform_download` directly (bypasses the `is_downloaded` guard in
-    /// `download_podcast_episode_if_not_locally_available`). Returns the
-    /// number of episodes that were queued.
-    pub fn redownload_missing_files_for_podcast(podcast: &Podcast) -> Result<usize, CustomError> {
-        const MAX_PARALLEL_DOWNLOADS: usize = 3;
+    /// file is missing on disk / in the configured backend. Their download
+    /// flags are cleared and they are queued like any requested download, so
+    /// the `max_parallel_downloads` setting and the retries of the download
+    /// worker apply. Returns the number of episodes that were queued.
+    pub fn redownload_missing_files_for_podcast(
+        podcast: &Podcast,
+        requested_by: Uuid,
+    ) -> Result<usize, CustomError> {
         let episodes = Self::get_episodes_by_podcast_id(Self::parse_id(&podcast.id)?)?;
-        let to_redownload: Vec<PodcastEpisode> = episodes
-            .into_iter()
-            .filter(|e| !e.deleted && e.is_downloaded() && Self::episode_file_missing(e))
-            .collect();
-        let count = to_redownload.len();
-
-        for chunk in to_redownload.chunks(MAX_PARALLEL_DOWNLOADS) {
-            let mut handles = Vec::with_capacity(chunk.len());
-            for episode in chunk.iter().cloned() {
-                let podcast_for_thread = podcast.clone();
-                handles.push(thread::spawn(move || {
-                    match Self::perform_download(&episode, &podcast_for_thread) {
-                        Ok(updated) => {
-                            ChatServerHandle::broadcast_podcast_episode_offline_available(
-                                &updated,
-                                &podcast_for_thread,
-                            );
-                        }
-                        Err(err) => {
-                            tracing::error!(
-                                "Error re-downloading episode {}: {}",
-                                episode.episode_id,
-                                err
-                            );
-                        }
-                    }
-                }));
+        let queue = DownloadQueueService::default_service();
+        let mut queued = 0usize;
+        for episode in episodes {
+            if episode.deleted || !episode.is_downloaded() || !Self::episode_file_missing(&episode)
+            {
+                continue;
             }
-            for handle in handles {
-                if let Err(err) = handle.join() {
-                    tracing::error!(
-                        "Error joining re-download worker for podcast {}: {:?}",
-                        podcast.id,
-                        err
-                    );
-                }
+            Self::remove_download_status_of_episode(Self::parse_id(&episode.id)?)?;
+            ChatServerHandle::broadcast_podcast_episode_deleted_locally(&episode);
+            if queue.enqueue(&episode, MANUAL_PRIORITY, Some(requested_by))? {
+                queued += 1;
             }
         }
-        Ok(count)
+        Ok(queued)
     }
 
     /// Clears DB download flags for episodes whose file is missing on disk.
@@ -1168,6 +1456,11 @@ impl PodcastEpisodeUseCase {
 struct RequestReturnType {
     pub url: String,
     pub content: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content_type: Option<String>,
+    /// Whether the feed was reached only through 301/308 redirects.
+    pub permanent_redirect: bool,
 }
 
 /// Reads `<podcast:transcript>` tags out of a feed item's extensions.
@@ -1338,3 +1631,357 @@ mod tests {
         assert!(tags.is_empty());
     }
 }
+
+#[cfg(all(test, feature = "sqlite"))]
+mod feed_refresh_tests {
+    use super::*;
+    use crate::services::podcast::service::PodcastService;
+    use crate::test_support::tests::{GLOBAL_MUTEX, ensure_test_env_vars};
+    use crate::test_utils::test_builder::user_test_builder::tests::UserTestDataBuilder;
+    use axum::Router;
+    use axum::http::{HeaderMap as AxumHeaderMap, StatusCode as AxumStatusCode, header};
+    use axum::response::IntoResponse;
+    use axum::routing::get;
+    use podfetch_domain::podcast_episode::EpisodeSort;
+    use podfetch_persistence::db::run_migrations;
+    use std::sync::MutexGuard;
+
+    /// A single-episode feed. Episodes are looked up by guid across all
+    /// podcasts, so every test gets its own.
+    fn feed() -> String {
+        let guid = Uuid::new_v4();
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Conditional Feed</title>
+    <link>https://example.com</link>
+    <description>Feed used to test conditional polling</description>
+    <item>
+      <title>Episode 1</title>
+      <guid>{guid}</guid>
+      <enclosure url="https://example.com/{guid}.mp3" length="1" type="audio/mpeg"/>
+    </item>
+  </channel>
+</rss>"#
+        )
+    }
+
+    fn lock_and_prepare_db() -> MutexGuard<'static, ()> {
+        ensure_test_env_vars();
+        let guard = GLOBAL_MUTEX
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        run_migrations();
+        guard
+    }
+
+    fn spawn_mock_server(app: Router) -> String {
+        let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().expect("build mock server runtime");
+            rt.block_on(async move {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+                    .await
+                    .expect("bind mock feed server");
+                addr_tx.send(listener.local_addr().unwrap()).unwrap();
+                axum::serve(listener, app).await.unwrap();
+            });
+        });
+        let addr = addr_rx.recv().expect("mock server address");
+        format!("http://{addr}")
+    }
+
+    fn etag_feed_router() -> Router {
+        let body = feed();
+        Router::new().route(
+            "/feed.xml",
+            get(move |headers: AxumHeaderMap| async move {
+                if headers
+                    .get(header::IF_NONE_MATCH)
+                    .is_some_and(|value| value == "\"v1\"")
+                {
+                    return AxumStatusCode::NOT_MODIFIED.into_response();
+                }
+                ([(header::ETAG, "\"v1\"")], body).into_response()
+            }),
+        )
+    }
+
+    fn podcast_for(feed_url: &str) -> Podcast {
+        let slug = format!("conditional-feed-{}", Uuid::new_v4());
+        PodcastService::add_podcast_to_database(
+            &slug,
+            &slug,
+            feed_url,
+            "http://localhost:8080/ui/default.jpg",
+            &slug,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn not_modified_feed_skips_the_episode_refresh() {
+        let _guard = lock_and_prepare_db();
+        let base = spawn_mock_server(etag_feed_router());
+        let podcast = podcast_for(&format!("{base}/feed.xml"));
+
+        let first = PodcastEpisodeUseCase::insert_podcast_episodes_if_changed(&podcast).unwrap();
+        assert_eq!(first.map(|episodes| episodes.len()), Some(1));
+        let state = PodcastEpisodeUseCase::feed_state_repo()
+            .get(Uuid::parse_str(&podcast.id).unwrap())
+            .unwrap()
+            .expect("feed state stored after the first refresh");
+        assert_eq!(state.etag.as_deref(), Some("\"v1\""));
+
+        let second = PodcastEpisodeUseCase::insert_podcast_episodes_if_changed(&podcast).unwrap();
+        assert!(second.is_none());
+    }
+
+    #[test]
+    fn identical_feed_without_validators_skips_the_episode_refresh() {
+        let _guard = lock_and_prepare_db();
+        let body = feed();
+        let base =
+            spawn_mock_server(Router::new().route("/feed.xml", get(move || async move { body })));
+        let podcast = podcast_for(&format!("{base}/feed.xml"));
+
+        let first = PodcastEpisodeUseCase::insert_podcast_episodes_if_changed(&podcast).unwrap();
+        assert!(first.is_some());
+
+        let second = PodcastEpisodeUseCase::insert_podcast_episodes_if_changed(&podcast).unwrap();
+        assert!(second.is_none());
+    }
+
+    #[test]
+    fn failing_feed_builds_a_streak_until_it_answers_again() {
+        let _guard = lock_and_prepare_db();
+        let body = feed();
+        let available = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let router = Router::new().route(
+            "/feed.xml",
+            get({
+                let available = Arc::clone(&available);
+                move || async move {
+                    if available.load(std::sync::atomic::Ordering::SeqCst) {
+                        body.into_response()
+                    } else {
+                        AxumStatusCode::NOT_FOUND.into_response()
+                    }
+                }
+            }),
+        );
+        let base = spawn_mock_server(router);
+        let podcast = podcast_for(&format!("{base}/feed.xml"));
+        let podcast_id = Uuid::parse_str(&podcast.id).unwrap();
+
+        assert!(PodcastEpisodeUseCase::insert_podcast_episodes_if_changed(&podcast).is_err());
+        assert!(PodcastEpisodeUseCase::insert_podcast_episodes_if_changed(&podcast).is_err());
+        let health = PodcastEpisodeUseCase::feed_state_repo()
+            .get_health(podcast_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(health.consecutive_failures, 2);
+        assert_eq!(health.last_http_status, Some(404));
+        assert!(health.failing_since.is_some());
+
+        available.store(true, std::sync::atomic::Ordering::SeqCst);
+        PodcastEpisodeUseCase::insert_podcast_episodes_if_changed(&podcast).unwrap();
+        let health = PodcastEpisodeUseCase::feed_state_repo()
+            .get_health(podcast_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.last_http_status, Some(200));
+        assert!(health.last_success_at.is_some());
+    }
+
+    #[test]
+    fn only_permanent_redirects_move_the_feed_url() {
+        let _guard = lock_and_prepare_db();
+        let moved_body = feed();
+        let temporary_body = feed();
+        let router = Router::new()
+            .route(
+                "/old.xml",
+                get(|| async {
+                    (
+                        AxumStatusCode::MOVED_PERMANENTLY,
+                        [(header::LOCATION, "/new.xml")],
+                    )
+                }),
+            )
+            .route("/new.xml", get(move || async move { moved_body }))
+            .route(
+                "/mirror.xml",
+                get(|| async { (AxumStatusCode::FOUND, [(header::LOCATION, "/current.xml")]) }),
+            )
+            .route("/current.xml", get(move || async move { temporary_body }));
+        let base = spawn_mock_server(router);
+        let moved = podcast_for(&format!("{base}/old.xml"));
+        let mirrored = podcast_for(&format!("{base}/mirror.xml"));
+
+        PodcastEpisodeUseCase::insert_podcast_episodes(&moved).unwrap();
+        PodcastEpisodeUseCase::insert_podcast_episodes(&mirrored).unwrap();
+
+        let rssfeed_of = |podcast: &Podcast| {
+            PodcastService::get_podcast(Uuid::parse_str(&podcast.id).unwrap())
+                .unwrap()
+                .rssfeed
+        };
+        assert_eq!(rssfeed_of(&moved), format!("{base}/new.xml"));
+        assert_eq!(rssfeed_of(&mirrored), format!("{base}/mirror.xml"));
+    }
+
+    #[test]
+    fn atom_and_json_feeds_are_ingested_like_rss() {
+        let _guard = lock_and_prepare_db();
+        let atom_id = Uuid::new_v4();
+        let atom = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Atom only</title>
+  <id>urn:uuid:{atom_id}</id>
+  <updated>2024-03-02T10:00:00Z</updated>
+  <entry>
+    <title>Atom episode</title>
+    <id>urn:uuid:{atom_id}:1</id>
+    <updated>2024-03-01T10:00:00Z</updated>
+    <link rel="enclosure" href="https://example.com/{atom_id}.mp3" type="audio/mpeg" length="1"/>
+  </entry>
+  <entry>
+    <title>Blog post without audio</title>
+    <id>urn:uuid:{atom_id}:2</id>
+    <updated>2024-03-01T11:00:00Z</updated>
+  </entry>
+</feed>"#
+        );
+        let json_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"version": "https://jsonfeed.org/version/1.1", "title": "JSON only",
+                "items": [{{"id": "{json_id}", "title": "JSON episode",
+                "date_published": "2024-03-01T09:00:00Z",
+                "attachments": [{{"url": "https://example.com/{json_id}.m4a",
+                "mime_type": "audio/x-m4a", "duration_in_seconds": 90}}]}}]}}"#
+        );
+        let router = Router::new()
+            .route(
+                "/atom",
+                get(
+                    move || async move { ([(header::CONTENT_TYPE, "application/atom+xml")], atom) },
+                ),
+            )
+            // Served with a generic type, so the format has to be sniffed.
+            .route(
+                "/json",
+                get(move || async move { ([(header::CONTENT_TYPE, "text/plain")], json) }),
+            );
+        let base = spawn_mock_server(router);
+
+        let atom_episodes =
+            PodcastEpisodeUseCase::insert_podcast_episodes(&podcast_for(&format!("{base}/atom")))
+                .unwrap();
+        assert_eq!(atom_episodes.len(), 1);
+        assert_eq!(atom_episodes[0].name, "Atom episode");
+        assert_eq!(
+            atom_episodes[0].url,
+            format!("https://example.com/{atom_id}.mp3")
+        );
+
+        let json_episodes =
+            PodcastEpisodeUseCase::insert_podcast_episodes(&podcast_for(&format!("{base}/json")))
+                .unwrap();
+        assert_eq!(json_episodes.len(), 1);
+        assert_eq!(json_episodes[0].name, "JSON episode");
+        assert_eq!(json_episodes[0].total_time, 90);
+        assert_eq!(json_episodes[0].guid, json_id.to_string());
+    }
+
+    fn numbered_item(title: &str, date: &str, tags: &str) -> String {
+        let guid = Uuid::new_v4();
+        format!(
+            r#"<item>
+      <title>{title}</title>
+      <guid>{guid}</guid>
+      <pubDate>{date}</pubDate>
+      {tags}
+      <enclosure url="https://example.com/{guid}.mp3" length="1" type="audio/mpeg"/>
+    </item>"#
+        )
+    }
+
+    #[test]
+    fn feed_season_and_episode_tags_are_stored_and_listable() {
+        let _guard = lock_and_prepare_db();
+        let items = [
+            numbered_item(
+                "Trailer",
+                "Mon, 01 Jan 2024 10:00:00 +0000",
+                "<itunes:season>2</itunes:season><itunes:episodeType>trailer</itunes:episodeType>",
+            ),
+            numbered_item(
+                "Second",
+                "Mon, 08 Jan 2024 10:00:00 +0000",
+                "<itunes:season>1</itunes:season><itunes:episode>2</itunes:episode>",
+            ),
+            numbered_item(
+                "First",
+                "Mon, 15 Jan 2024 10:00:00 +0000",
+                "<podcast:season>1</podcast:season><podcast:episode>1</podcast:episode>",
+            ),
+        ]
+        .join("\n");
+        let body = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+  <channel>
+    <title>Numbered Feed</title>
+    <link>https://example.com</link>
+    <description>Feed whose items are numbered out of date order</description>
+    {items}
+  </channel>
+</rss>"#
+        );
+        let base =
+            spawn_mock_server(Router::new().route("/feed.xml", get(move || async move { body })));
+        let podcast = podcast_for(&format!("{base}/feed.xml"));
+
+        let inserted = PodcastEpisodeUseCase::insert_podcast_episodes(&podcast).unwrap();
+        let trailer = inserted.iter().find(|e| e.name == "Trailer").unwrap();
+        assert_eq!(trailer.season, Some(2));
+        assert_eq!(trailer.episode_number, None);
+        assert_eq!(trailer.episode_type.as_deref(), Some("trailer"));
+
+        let user = UserTestDataBuilder::new().build();
+        let podcast_id = Uuid::parse_str(&podcast.id).unwrap();
+        let list = |filter: EpisodeListFilter| {
+            PodcastEpisodeUseCase::get_podcast_episodes_of_podcast(
+                podcast_id, None, None, &filter, &user,
+            )
+            .unwrap()
+            .into_iter()
+            .map(|(episode, _, _)| episode.name)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            list(EpisodeListFilter {
+                season: Some(1),
+                sort: EpisodeSort::Episode,
+                ..Default::default()
+            }),
+            vec!["First", "Second"]
+        );
+        assert_eq!(
+            list(EpisodeListFilter {
+                episode_type: Some("trailer".to_string()),
+                ..Default::default()
+            }),
+            vec!["Trailer"]
+        );
+        assert_eq!(
+            list(EpisodeListFilter::default()),
+            vec!["First", "Second", "Trailer"]
+        );
+    }
+}
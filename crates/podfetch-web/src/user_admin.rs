@@ -157,6 +157,37 @@ pub struct UserCoreUpdateModel {
     pub api_key: Option<String>,
 }
 
+#[derive(serde::Serialize, Deserialize, Clone, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PodcastStorageUsage {
+    pub podcast_id: String,
+    pub name: String,
+    pub used_bytes: i64,
+}
+
+/// Space taken up by the downloads of the podcasts a user added.
+#[derive(serde::Serialize, Deserialize, Clone, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStorageUsage {
+    pub user_id: String,
+    pub username: String,
+    pub used_bytes: i64,
+    /// Quota that applies to the user, `None` when unlimited.
+    pub quota_bytes: Option<i64>,
+    /// Quota an admin set for this user in MB. `None` when the instance
+    /// default (`USER_STORAGE_QUOTA_MB`) applies.
+    pub quota_override_mb: Option<i32>,
+    pub podcasts: Vec<PodcastStorageUsage>,
+}
+
+#[derive(Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStorageQuotaUpdate {
+    /// Quota in MB, 0 for unlimited. `null` goes back to the instance
+    /// default.
+    pub quota_mb: Option<i32>,
+}
+
 pub trait UserAdminApplicationService {
     type Error;
 
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE episode_download_states (
+    episode_id TEXT PRIMARY KEY NOT NULL REFERENCES podcast_episodes(id) ON DELETE CASCADE,
+    file_path TEXT NOT NULL,
+    bytes_received BIGINT NOT NULL DEFAULT 0,
+    etag TEXT,
+    last_modified TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
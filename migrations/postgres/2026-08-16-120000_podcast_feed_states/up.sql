@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TABLE podcast_feed_states (
+    podcast_id TEXT PRIMARY KEY NOT NULL REFERENCES podcasts(id) ON DELETE CASCADE,
+    etag TEXT,
+    last_modified TEXT,
+    content_hash TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
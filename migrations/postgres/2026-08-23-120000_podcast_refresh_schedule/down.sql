@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE podcast_feed_states DROP COLUMN next_refresh_at;
+ALTER TABLE podcast_feed_states DROP COLUMN update_interval_hint;
+ALTER TABLE podcast_settings DROP COLUMN refresh_interval;
+ALTER TABLE podcast_settings DROP COLUMN refresh_mode;
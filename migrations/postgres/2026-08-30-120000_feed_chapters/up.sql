@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE podcast_episode_chapters ADD COLUMN source TEXT NOT NULL DEFAULT 'embedded';
+ALTER TABLE podcast_episode_chapters ADD COLUMN source_url TEXT;
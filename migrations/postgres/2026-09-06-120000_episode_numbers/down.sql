@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE podcast_episodes DROP COLUMN episode_type;
+ALTER TABLE podcast_episodes DROP COLUMN episode_number;
+ALTER TABLE podcast_episodes DROP COLUMN season;
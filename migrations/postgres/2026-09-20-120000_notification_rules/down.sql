@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE notification_rules;
+DROP TABLE notification_dismissals;
+DROP INDEX idx_notifications_user_id;
+ALTER TABLE notifications DROP COLUMN user_id;
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE playlists ADD COLUMN smart_query TEXT;
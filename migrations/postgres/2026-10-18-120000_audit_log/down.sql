@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE audit_log;
+ALTER TABLE settings DROP COLUMN audit_log_retention_days;
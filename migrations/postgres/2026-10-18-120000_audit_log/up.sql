@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE audit_log (
+    id TEXT PRIMARY KEY NOT NULL,
+    actor TEXT NOT NULL,
+    action TEXT NOT NULL,
+    target TEXT,
+    ip_address TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+CREATE INDEX idx_audit_log_created_at ON audit_log (created_at);
+ALTER TABLE settings ADD COLUMN audit_log_retention_days INTEGER NOT NULL DEFAULT 365;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE user_storage_quotas;
+DROP TABLE episode_storage;
+ALTER TABLE settings DROP COLUMN storage_quota_policy;
+ALTER TABLE settings DROP COLUMN storage_quota_mb;
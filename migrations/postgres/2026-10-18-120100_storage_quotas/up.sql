@@ -0,0 +1,14 @@
+-- Your SQL goes here
+CREATE TABLE episode_storage (
+    episode_id TEXT PRIMARY KEY NOT NULL REFERENCES podcast_episodes(id) ON DELETE CASCADE,
+    podcast_id TEXT NOT NULL REFERENCES podcasts(id) ON DELETE CASCADE,
+    bytes BIGINT NOT NULL,
+    recorded_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+CREATE INDEX idx_episode_storage_podcast_id ON episode_storage (podcast_id);
+CREATE TABLE user_storage_quotas (
+    user_id TEXT PRIMARY KEY NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    quota_mb INTEGER NOT NULL
+);
+ALTER TABLE settings ADD COLUMN storage_quota_mb INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN storage_quota_policy TEXT NOT NULL DEFAULT 'refuse';
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS idx_podcast_episodes_fts;
+ALTER TABLE podcast_episodes DROP COLUMN text_search;
+
+DROP INDEX IF EXISTS idx_podcasts_fts;
+ALTER TABLE podcasts DROP COLUMN text_search;
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+ALTER TABLE podcasts
+    ADD COLUMN text_search tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', name), 'A') ||
+        setweight(to_tsvector('simple', coalesce(author, '')), 'B') ||
+        setweight(to_tsvector('simple', coalesce(summary, '')), 'C')
+    ) STORED;
+CREATE INDEX idx_podcasts_fts ON podcasts USING GIN (text_search);
+
+ALTER TABLE podcast_episodes
+    ADD COLUMN text_search tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', name), 'A') ||
+        setweight(to_tsvector('simple', description), 'C')
+    ) STORED;
+CREATE INDEX idx_podcast_episodes_fts ON podcast_episodes USING GIN (text_search);
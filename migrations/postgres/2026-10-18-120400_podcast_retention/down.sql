@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE podcast_settings DROP COLUMN retention_max_size_mb;
+ALTER TABLE podcast_settings DROP COLUMN retention_keep_unplayed;
+ALTER TABLE podcast_settings DROP COLUMN retention_played_days;
+ALTER TABLE podcast_settings DROP COLUMN retention_keep_newest;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE podcast_episodes ADD COLUMN loudness_lufs DOUBLE PRECISION;
+ALTER TABLE podcast_episodes ADD COLUMN loudness_true_peak DOUBLE PRECISION;
+ALTER TABLE settings ADD COLUMN loudness_analysis BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX idx_episode_fingerprints_podcast_id;
+DROP TABLE episode_fingerprints;
+ALTER TABLE settings DROP COLUMN skip_detection;
+ALTER TABLE episode_sponsor_segments DROP COLUMN source;
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+ALTER TABLE episode_sponsor_segments ADD COLUMN source TEXT NOT NULL DEFAULT 'sponsorblock';
+ALTER TABLE settings ADD COLUMN skip_detection BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE episode_fingerprints (
+    episode_id TEXT PRIMARY KEY NOT NULL REFERENCES podcast_episodes(id) ON DELETE CASCADE,
+    podcast_id TEXT NOT NULL REFERENCES podcasts(id) ON DELETE CASCADE,
+    fingerprint BYTEA NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_episode_fingerprints_podcast_id
+    ON episode_fingerprints (podcast_id, created_at);
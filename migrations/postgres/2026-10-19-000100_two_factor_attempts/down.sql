@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE two_factor_auth DROP COLUMN locked_until;
+ALTER TABLE two_factor_auth DROP COLUMN failed_attempts;
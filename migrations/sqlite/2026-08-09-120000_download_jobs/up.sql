@@ -0,0 +1,15 @@
+-- Your SQL goes here
+CREATE TABLE download_jobs (
+    id TEXT PRIMARY KEY NOT NULL,
+    episode_id TEXT NOT NULL UNIQUE REFERENCES podcast_episodes(id) ON DELETE CASCADE,
+    podcast_id TEXT NOT NULL REFERENCES podcasts(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending', -- 'pending'|'running'|'done'|'failed'|'cancelled'
+    priority INTEGER NOT NULL DEFAULT 0,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    next_run_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_download_jobs_status_next_run ON download_jobs(status, next_run_at);
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE podcast_settings ADD COLUMN refresh_mode TEXT NOT NULL DEFAULT 'fixed';
+ALTER TABLE podcast_settings ADD COLUMN refresh_interval INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE podcast_feed_states ADD COLUMN update_interval_hint INTEGER;
+ALTER TABLE podcast_feed_states ADD COLUMN next_refresh_at TIMESTAMP;
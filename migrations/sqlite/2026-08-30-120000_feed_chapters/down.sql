@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE podcast_episode_chapters DROP COLUMN source_url;
+ALTER TABLE podcast_episode_chapters DROP COLUMN source;
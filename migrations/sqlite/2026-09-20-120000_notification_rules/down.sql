@@ -0,0 +1,17 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE notification_rules;
+DROP TABLE notification_dismissals;
+DROP INDEX idx_notifications_user_id;
+
+-- SQLite can't drop a column that is part of a foreign key.
+CREATE TABLE notifications_old (
+    id TEXT PRIMARY KEY NOT NULL,
+    type_of_message TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    status TEXT NOT NULL
+);
+INSERT INTO notifications_old (id, type_of_message, message, created_at, status)
+    SELECT id, type_of_message, message, created_at, status FROM notifications;
+DROP TABLE notifications;
+ALTER TABLE notifications_old RENAME TO notifications;
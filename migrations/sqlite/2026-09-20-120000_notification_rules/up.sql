@@ -0,0 +1,22 @@
+-- Your SQL goes here
+ALTER TABLE notifications ADD COLUMN user_id TEXT REFERENCES users(id) ON DELETE CASCADE;
+CREATE INDEX idx_notifications_user_id ON notifications (user_id);
+
+CREATE TABLE notification_dismissals (
+    notification_id TEXT NOT NULL REFERENCES notifications(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    dismissed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (notification_id, user_id)
+);
+
+CREATE TABLE notification_rules (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    podcast_id TEXT REFERENCES podcasts(id) ON DELETE CASCADE,
+    tag_id TEXT REFERENCES tags(id) ON DELETE CASCADE,
+    min_duration_seconds INTEGER,
+    max_duration_seconds INTEGER,
+    title_regex TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX idx_notification_rules_user_id ON notification_rules (user_id);
@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS podcast_episodes_fts_au;
+DROP TRIGGER IF EXISTS podcast_episodes_fts_ad;
+DROP TRIGGER IF EXISTS podcast_episodes_fts_ai;
+DROP TABLE IF EXISTS podcast_episodes_fts;
+
+DROP TRIGGER IF EXISTS podcasts_fts_au;
+DROP TRIGGER IF EXISTS podcasts_fts_ad;
+DROP TRIGGER IF EXISTS podcasts_fts_ai;
+DROP TABLE IF EXISTS podcasts_fts;
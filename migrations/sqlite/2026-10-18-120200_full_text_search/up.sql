@@ -0,0 +1,45 @@
+-- Your SQL goes here
+CREATE VIRTUAL TABLE podcasts_fts USING fts5(
+    name,
+    summary,
+    author,
+    content='podcasts',
+    content_rowid='rowid'
+);
+CREATE TRIGGER podcasts_fts_ai AFTER INSERT ON podcasts BEGIN
+    INSERT INTO podcasts_fts(rowid, name, summary, author)
+        VALUES (new.rowid, new.name, new.summary, new.author);
+END;
+CREATE TRIGGER podcasts_fts_ad AFTER DELETE ON podcasts BEGIN
+    INSERT INTO podcasts_fts(podcasts_fts, rowid, name, summary, author)
+        VALUES ('delete', old.rowid, old.name, old.summary, old.author);
+END;
+CREATE TRIGGER podcasts_fts_au AFTER UPDATE OF name, summary, author ON podcasts BEGIN
+    INSERT INTO podcasts_fts(podcasts_fts, rowid, name, summary, author)
+        VALUES ('delete', old.rowid, old.name, old.summary, old.author);
+    INSERT INTO podcasts_fts(rowid, name, summary, author)
+        VALUES (new.rowid, new.name, new.summary, new.author);
+END;
+INSERT INTO podcasts_fts(podcasts_fts) VALUES ('rebuild');
+
+CREATE VIRTUAL TABLE podcast_episodes_fts USING fts5(
+    name,
+    description,
+    content='podcast_episodes',
+    content_rowid='rowid'
+);
+CREATE TRIGGER podcast_episodes_fts_ai AFTER INSERT ON podcast_episodes BEGIN
+    INSERT INTO podcast_episodes_fts(rowid, name, description)
+        VALUES (new.rowid, new.name, new.description);
+END;
+CREATE TRIGGER podcast_episodes_fts_ad AFTER DELETE ON podcast_episodes BEGIN
+    INSERT INTO podcast_episodes_fts(podcast_episodes_fts, rowid, name, description)
+        VALUES ('delete', old.rowid, old.name, old.description);
+END;
+CREATE TRIGGER podcast_episodes_fts_au AFTER UPDATE OF name, description ON podcast_episodes BEGIN
+    INSERT INTO podcast_episodes_fts(podcast_episodes_fts, rowid, name, description)
+        VALUES ('delete', old.rowid, old.name, old.description);
+    INSERT INTO podcast_episodes_fts(rowid, name, description)
+        VALUES (new.rowid, new.name, new.description);
+END;
+INSERT INTO podcast_episodes_fts(podcast_episodes_fts) VALUES ('rebuild');
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE settings DROP COLUMN dead_feed_deactivation_days;
+ALTER TABLE podcast_feed_states DROP COLUMN last_http_status;
+ALTER TABLE podcast_feed_states DROP COLUMN last_error;
+ALTER TABLE podcast_feed_states DROP COLUMN failing_since;
+ALTER TABLE podcast_feed_states DROP COLUMN consecutive_failures;
+ALTER TABLE podcast_feed_states DROP COLUMN last_failure_at;
+ALTER TABLE podcast_feed_states DROP COLUMN last_success_at;
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+ALTER TABLE podcast_feed_states ADD COLUMN last_success_at TIMESTAMP;
+ALTER TABLE podcast_feed_states ADD COLUMN last_failure_at TIMESTAMP;
+ALTER TABLE podcast_feed_states ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE podcast_feed_states ADD COLUMN failing_since TIMESTAMP;
+ALTER TABLE podcast_feed_states ADD COLUMN last_error TEXT;
+ALTER TABLE podcast_feed_states ADD COLUMN last_http_status INTEGER;
+ALTER TABLE settings ADD COLUMN dead_feed_deactivation_days INTEGER NOT NULL DEFAULT 30;
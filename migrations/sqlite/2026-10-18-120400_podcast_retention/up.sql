@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE podcast_settings ADD COLUMN retention_keep_newest INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE podcast_settings ADD COLUMN retention_played_days INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE podcast_settings ADD COLUMN retention_keep_unplayed BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE podcast_settings ADD COLUMN retention_max_size_mb INTEGER NOT NULL DEFAULT 0;
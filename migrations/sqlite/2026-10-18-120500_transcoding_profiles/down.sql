@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE podcast_settings DROP COLUMN transcoding_profile_id;
+ALTER TABLE settings DROP COLUMN transcoding_profile_id;
+DROP TABLE transcoding_profiles;
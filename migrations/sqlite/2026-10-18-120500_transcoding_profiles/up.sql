@@ -0,0 +1,16 @@
+-- Your SQL goes here
+CREATE TABLE transcoding_profiles (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    codec TEXT NOT NULL,
+    bitrate_kbps INTEGER NOT NULL,
+    channels INTEGER NOT NULL DEFAULT 0,
+    sample_rate INTEGER NOT NULL DEFAULT 0,
+    loudnorm BOOLEAN NOT NULL DEFAULT FALSE,
+    trim_silence BOOLEAN NOT NULL DEFAULT FALSE,
+    keep_original BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+ALTER TABLE settings ADD COLUMN transcoding_profile_id TEXT;
+ALTER TABLE podcast_settings ADD COLUMN transcoding_profile_id TEXT;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE settings DROP COLUMN loudness_analysis;
+ALTER TABLE podcast_episodes DROP COLUMN loudness_true_peak;
+ALTER TABLE podcast_episodes DROP COLUMN loudness_lufs;
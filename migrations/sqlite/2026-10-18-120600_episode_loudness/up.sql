@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE podcast_episodes ADD COLUMN loudness_lufs REAL;
+ALTER TABLE podcast_episodes ADD COLUMN loudness_true_peak REAL;
+ALTER TABLE settings ADD COLUMN loudness_analysis BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TABLE chapter_fetch_failures (
+    episode_id TEXT PRIMARY KEY NOT NULL REFERENCES podcast_episodes(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    failures INTEGER NOT NULL DEFAULT 0,
+    last_failure_at TIMESTAMP NOT NULL,
+    last_error TEXT NOT NULL
+);
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE download_jobs DROP COLUMN requested_by;
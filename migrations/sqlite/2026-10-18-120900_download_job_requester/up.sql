@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE download_jobs ADD COLUMN requested_by TEXT REFERENCES users(id) ON DELETE SET NULL;
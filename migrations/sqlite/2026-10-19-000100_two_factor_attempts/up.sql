@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE two_factor_auth ADD COLUMN failed_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE two_factor_auth ADD COLUMN locked_until TIMESTAMP;
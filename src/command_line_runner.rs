@@ -3,10 +3,11 @@ use common_infrastructure::error::{CustomError, CustomErrorInner};
 use common_infrastructure::time::get_current_timestamp_str;
 use podfetch_domain::user::{User, UserWithoutPassword};
 use podfetch_persistence::adapters::DeviceRepositoryImpl;
-use podfetch_persistence::db::database;
+use podfetch_persistence::db::{database, run_migrations};
 use podfetch_web::app_state::AppState;
 use podfetch_web::controllers::sys_info_controller::built_info;
 use podfetch_web::role::Role;
+use podfetch_web::services::backup::service::{BackupManifest, BackupService};
 use podfetch_web::services::device::service::DeviceService;
 use podfetch_web::services::podcast::service::PodcastService;
 use podfetch_web::usecases::podcast_episode::PodcastEpisodeUseCase as PodcastEpisodeService;
@@ -14,6 +15,7 @@ use podfetch_web::usecases::watchtime::WatchtimeUseCase as WatchtimeService;
 use sha256::digest;
 use std::env::Args;
 use std::io::{Error, ErrorKind, stdin};
+use std::path::Path;
 use std::process::exit;
 use std::sync::Arc;
 use tracing::error;
@@ -38,6 +40,8 @@ pub async fn start_command_line(mut args: Args) -> Result<(), CustomError> {
                 r" The following commands are available:
             users => Handles user management
             podcasts => Handles podcast management
+            backup <file> [--with-media] => Writes database and optionally media into one archive
+            restore <file> => Restores an archive written by backup into an empty instance
             "
             );
             Ok(())
@@ -261,6 +265,39 @@ pub async fn start_command_line(mut args: Args) -> Result<(), CustomError> {
                 }
             }
         }
+        "backup" => {
+            let (target, include_media) = match parse_backup_args(args) {
+                Some(parsed) => parsed,
+                None => {
+                    println!("Usage: backup <file> [--with-media]");
+                    exit(1);
+                }
+            };
+            let manifest = BackupService::default_service()
+                .create_backup(Path::new(&target), include_media)?;
+            print_backup_summary(&manifest);
+            println!("Backup written to {target}");
+            Ok(())
+        }
+        "restore" => {
+            let source = match args.next() {
+                Some(source) => source,
+                None => {
+                    println!("Usage: restore <file>");
+                    exit(1);
+                }
+            };
+            // A fresh instance may never have started the server, so its
+            // schema could still be missing.
+            run_migrations();
+            let manifest = BackupService::default_service().restore_backup(Path::new(&source))?;
+            print_backup_summary(&manifest);
+            println!(
+                "Restored backup of PodFetch {} from {}",
+                manifest.podfetch_version, manifest.created_at
+            );
+            Ok(())
+        }
         "migration" => {
             error!("Command not found");
             Ok(())
@@ -276,6 +313,28 @@ pub async fn start_command_line(mut args: Args) -> Result<(), CustomError> {
     }
 }
 
+/// `<file> [--with-media]`, with the flag accepted on either side.
+fn parse_backup_args(args: impl Iterator<Item = String>) -> Option<(String, bool)> {
+    let mut target = None;
+    let mut include_media = false;
+    for arg in args {
+        match arg.as_str() {
+            "--with-media" => include_media = true,
+            _ if target.is_none() => target = Some(arg),
+            _ => return None,
+        }
+    }
+    target.map(|target| (target, include_media))
+}
+
+fn print_backup_summary(manifest: &BackupManifest) {
+    println!("|Table|Rows|");
+    for table in &manifest.tables {
+        println!("|{}|{}|", table.name, table.rows);
+    }
+    println!("Media files: {}", manifest.media.len());
+}
+
 fn list_users(state: &AppState) -> Result<Vec<UserWithoutPassword>, CustomError> {
     let users = state.user_admin_service.list_users()?;
 
@@ -50,6 +50,11 @@ async fn main() -> std::io::Result<()> {
     let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
     tracing::info!("Listening on {bind_addr}");
 
-    axum::serve(listener, router).await?;
+    // Connect info gives `ClientIp` the peer address for the audit log.
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await?;
     Ok(())
 }
@@ -4,6 +4,22 @@
  */
 
 export interface paths {
+    "/api/v1/audit-log": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["get_audit_log"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/v1/cast/devices": {
         parameters: {
             query?: never;
@@ -358,6 +374,22 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/v1/logout": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        post: operations["sys_logout"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/v1/mopidy/servers": {
         parameters: {
             query?: never;
@@ -422,6 +454,40 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/v1/notifications/rules": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["get_notification_rules"];
+        put?: never;
+        post: operations["create_notification_rule"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/notifications/rules/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        get?: never;
+        put: operations["update_notification_rule"];
+        post?: never;
+        delete: operations["delete_notification_rule"];
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/v1/notifications/unread": {
         parameters: {
             query?: never;
@@ -726,6 +792,22 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/v1/podcasts/feed-health": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["get_unhealthy_feeds"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/v1/podcasts/filter": {
         parameters: {
             query?: never;
@@ -1096,6 +1178,22 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/v1/search": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["search"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/v1/settings": {
         parameters: {
             query?: never;
@@ -1112,6 +1210,22 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/v1/settings/cleanup/preview": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["preview_cleanup"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/v1/settings/name": {
         parameters: {
             query?: never;
@@ -1128,6 +1242,58 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/v1/settings/notification-channels": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["get_notification_channels"];
+        put?: never;
+        post: operations["create_notification_channel"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/settings/notification-channels/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        get?: never;
+        put: operations["update_notification_channel"];
+        post?: never;
+        delete: operations["delete_notification_channel"];
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/settings/notification-channels/{id}/test": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        post: operations["test_notification_channel"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/v1/settings/opml/{type_of}": {
         parameters: {
             query?: never;
@@ -1192,6 +1358,40 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/v1/settings/transcoding-profiles": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["get_transcoding_profiles"];
+        put?: never;
+        post: operations["create_transcoding_profile"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/settings/transcoding-profiles/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        get?: never;
+        put: operations["update_transcoding_profile"];
+        post?: never;
+        delete: operations["delete_transcoding_profile"];
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/v1/settings/transcripts/reparse": {
         parameters: {
             query?: never;
@@ -1368,127 +1568,129 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
-    "/api/v1/users/{username}": {
+    "/api/v1/users/me/tokens": {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
-        get: operations["get_user"];
-        put: operations["update_user"];
-        post?: never;
-        delete: operations["delete_user"];
+        get: operations["get_api_tokens"];
+        put?: never;
+        post: operations["create_api_token"];
+        delete?: never;
         options?: never;
         head?: never;
         patch?: never;
         trace?: never;
     };
-    "/api/v1/users/{username}/role": {
+    "/api/v1/users/me/tokens/{id}": {
         parameters: {
             query?: never;
             header?: never;
-            path?: never;
+            path: {
+                id: string;
+            };
             cookie?: never;
         };
         get?: never;
-        put: operations["update_role"];
+        put?: never;
         post?: never;
-        delete?: never;
+        delete: operations["delete_api_token"];
         options?: never;
         head?: never;
         patch?: never;
         trace?: never;
     };
-    "/proxy/podcast": {
+    "/api/v1/users/me/two-factor": {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
-        get: operations["proxy_podcast"];
+        get: operations["get_two_factor_status"];
         put?: never;
-        post?: never;
+        post: operations["start_two_factor_enrollment"];
         delete?: never;
         options?: never;
         head?: never;
         patch?: never;
         trace?: never;
     };
-    "/proxy/podcast/apiKey/{apiKey}": {
+    "/api/v1/users/me/two-factor/confirm": {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
-        get: operations["proxy_podcast_with_path_api_key"];
+        get?: never;
         put?: never;
-        post?: never;
+        post: operations["confirm_two_factor_enrollment"];
         delete?: never;
         options?: never;
         head?: never;
         patch?: never;
         trace?: never;
     };
-    "/rss": {
+    "/api/v1/users/me/two-factor/disable": {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
-        get: operations["get_rss_feed"];
+        get?: never;
         put?: never;
-        post?: never;
+        post: operations["disable_two_factor"];
         delete?: never;
         options?: never;
         head?: never;
         patch?: never;
         trace?: never;
     };
-    "/rss/apiKey/{apiKey}": {
+    "/api/v1/users/me/two-factor/recovery-codes": {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
-        get: operations["get_rss_feed_with_path_api_key"];
+        get?: never;
         put?: never;
-        post?: never;
+        post: operations["regenerate_two_factor_recovery_codes"];
         delete?: never;
         options?: never;
         head?: never;
         patch?: never;
         trace?: never;
     };
-    "/rss/apiKey/{apiKey}/{id}": {
+    "/api/v1/users/{username}": {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
-        get: operations["get_rss_feed_for_podcast_with_path_api_key"];
-        put?: never;
+        get: operations["get_user"];
+        put: operations["update_user"];
         post?: never;
-        delete?: never;
+        delete: operations["delete_user"];
         options?: never;
         head?: never;
         patch?: never;
         trace?: never;
     };
-    "/rss/{id}": {
+    "/api/v1/users/{username}/role": {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
-        get: operations["get_rss_feed_for_podcast"];
-        put?: never;
+        get?: never;
+        put: operations["update_role"];
         post?: never;
         delete?: never;
         options?: never;
@@ -1496,15 +1698,15 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
-    "/ui/index.html": {
+    "/api/v1/users/{username}/storage": {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
-        get: operations["index"];
-        put?: never;
+        get: operations["get_user_storage"];
+        put: operations["update_user_storage_quota"];
         post?: never;
         delete?: never;
         options?: never;
@@ -1512,26 +1714,228 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
-}
-export type webhooks = Record<string, never>;
-export interface components {
-    schemas: {
-        AddMopidyServerRequest: {
-            name: string;
-            /** @description When true the server is visible to every user; otherwise owner-only. */
-            shared: boolean;
-            url: string;
-        };
-        BatchActionResponse: {
-            affected: number;
+    "/api/v1/users/{username}/two-factor": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                username: string;
+            };
+            cookie?: never;
         };
-        DownloadRange: {
-            /** Format: int32 */
-            from: number;
-            /** Format: int32 */
-            to: number;
+        get?: never;
+        put?: never;
+        post?: never;
+        delete: operations["reset_two_factor"];
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/proxy/podcast": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
         };
-        /** @description Request body for `POST /cast/sessions/:id/control`. */
+        get: operations["proxy_podcast"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/proxy/podcast/apiKey/{apiKey}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["proxy_podcast_with_path_api_key"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/rss": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["get_rss_feed"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/rss/apiKey/{apiKey}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["get_rss_feed_with_path_api_key"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/rss/apiKey/{apiKey}/playlist/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["get_rss_feed_for_playlist_with_path_api_key"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/rss/apiKey/{apiKey}/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["get_rss_feed_for_podcast_with_path_api_key"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/rss/playlist/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["get_rss_feed_for_playlist"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/rss/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["get_rss_feed_for_podcast"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/ui/index.html": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get: operations["index"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+}
+export type webhooks = Record<string, never>;
+export interface components {
+    schemas: {
+        AddMopidyServerRequest: {
+            name: string;
+            /** @description When true the server is visible to every user; otherwise owner-only. */
+            shared: boolean;
+            url: string;
+        };
+        ApiTokenCreate: {
+            /** @description RFC 3339 timestamp after which the token stops working. */
+            expiresAt?: string | null;
+            name: string;
+            scopes: string[];
+        };
+        ApiTokenCreated: {
+            /** @description The secret to hand to the client. It is only shown once. */
+            secret: string;
+            token: components["schemas"]["ApiTokenDto"];
+        };
+        ApiTokenDto: {
+            createdAt: string;
+            expiresAt?: string | null;
+            id: string;
+            lastUsedAt?: string | null;
+            name: string;
+            /** @description Any of `rss-read`, `stream`, `gpodder-sync`, `audiobookshelf` and `admin`. */
+            scopes: string[];
+        };
+        AuditLogEntryDto: {
+            action: string;
+            actor: string;
+            createdAt: string;
+            id: string;
+            ipAddress?: string | null;
+            target?: string | null;
+        };
+        AuditLogPageDto: {
+            items: components["schemas"]["AuditLogEntryDto"][];
+            /** Format: int64 */
+            page: number;
+            /** Format: int64 */
+            pageSize: number;
+            /**
+             * Format: int64
+             * @description Number of entries matching the filters across all pages.
+             */
+            total: number;
+        };
+        BatchActionResponse: {
+            affected: number;
+        };
+        DownloadRange: {
+            /** Format: int32 */
+            from: number;
+            /** Format: int32 */
+            to: number;
+        };
+        /** @description Request body for `POST /cast/sessions/:id/control`. */
         CastControlRequest: {
             /** @enum {string} */
             cmd: "pause";
@@ -1592,6 +1996,12 @@ export interface components {
              *     URL); the controller passes it through unchanged.
              */
             url: string;
+            /**
+             * Format: float
+             * @description Volume (0.0 to 1.0) to start at. It is turned down by the episode's
+             *     ReplayGain when its loudness has been measured.
+             */
+            volume?: number | null;
         };
         /**
          * @description Wire-friendly mirror of [`podfetch_cast::CastState`] — gives utoipa a
@@ -1684,6 +2094,15 @@ export interface components {
         EpisodeFormatDto: {
             content: string;
         };
+        EpisodeSearchResultDto: {
+            descriptionSnippet?: string | null;
+            episode: components["schemas"]["PodcastEpisodeDto"];
+            /** @description `None` when only the transcript matched. */
+            nameHighlight?: string | null;
+            /** Format: float */
+            rank: number;
+            transcriptHits: components["schemas"]["TranscriptSearchHitDto"][];
+        };
         FavoritePut: {
             favored: boolean;
         };
@@ -1800,8 +2219,16 @@ export interface components {
         };
         LoginRequest: {
             password: string;
+            /** @description Current TOTP or recovery code, needed once the user enrolled a
+             *     second factor. */
+            totpCode?: string | null;
             username: string;
         };
+        LoginResponse: {
+            /** @description Only set after a two-factor login. Basic auth is refused for such
+             *     users, so the client sends this as `Authorization: Bearer` instead. */
+            token?: string | null;
+        };
         MopidyServerResponse: {
             id: string;
             kind: string;
@@ -1819,10 +2246,61 @@ export interface components {
             message: string;
             status: string;
             typeOfMessage: string;
+            /** @description Owner of the notification; `None` for notifications every user sees. */
+            userId?: string | null;
+        };
+        NotificationChannelDto: {
+            config: Record<string, never>;
+            createdAt: string;
+            enabled: boolean;
+            /** @description Subscribed events: `new-episode`, `download-failed`,
+             *     `transcription-done` and `feed-broken`. */
+            events: string[];
+            id: string;
+            /** @description One of `webhook`, `ntfy`, `gotify` or `smtp`. */
+            kind: string;
+            name: string;
+        };
+        NotificationChannelUpsert: {
+            /** @description Kind specific settings, e.g. `{"url": "...", "secret": "..."}` for
+             *     webhooks or `{"serverUrl": "...", "topic": "..."}` for ntfy. */
+            config: Record<string, never>;
+            enabled?: boolean;
+            events: string[];
+            kind: string;
+            name: string;
         };
         NotificationId: {
             id: string;
         };
+        NotificationRuleDto: {
+            createdAt: string;
+            id: string;
+            /** Format: int32 */
+            maxDurationSeconds?: number | null;
+            /** Format: int32 */
+            minDurationSeconds?: number | null;
+            podcastId?: string | null;
+            tagId?: string | null;
+            titleRegex?: string | null;
+        };
+        /** @description Leave `podcastId` and `tagId` empty for a rule covering all podcasts. */
+        NotificationRuleUpsert: {
+            /**
+             * Format: int32
+             * @description Only episodes at most this long (in seconds) match.
+             */
+            maxDurationSeconds?: number | null;
+            /**
+             * Format: int32
+             * @description Only episodes at least this long (in seconds) match.
+             */
+            minDurationSeconds?: number | null;
+            podcastId?: string | null;
+            tagId?: string | null;
+            /** @description Regular expression the episode title must match, e.g. `(?i)interview`. */
+            titleRegex?: string | null;
+        };
         OidcConfig: {
             authority: string;
             clientId: string;
@@ -1835,14 +2313,26 @@ export interface components {
         OpmlModel: {
             content: string;
         };
+        /**
+         * @description Which podcasts an OPML export contains.
+         * @enum {string}
+         */
+        OpmlScope: "subscriptions" | "all";
+        /**
+         * @description Listening progress of an episode for the playlist owner.
+         * @enum {string}
+         */
+        PlayState: "unplayed" | "inProgress" | "played";
         PlaylistDto: {
             id: string;
             items: components["schemas"]["PodcastEpisodeWithHistory"][];
             name: string;
+            smart?: null | components["schemas"]["SmartPlaylistQuery"];
         };
         PlaylistDtoPost: {
-            items: components["schemas"]["PlaylistItem"][];
+            items?: components["schemas"]["PlaylistItem"][];
             name: string;
+            smart?: null | components["schemas"]["SmartPlaylistQuery"];
         };
         PlaylistItem: {
             episode: string;
@@ -1856,7 +2346,10 @@ export interface components {
         PodcastChapterDto: {
             /** Format: int32 */
             endTime: number;
+            href?: string | null;
             id: string;
+            image?: string | null;
+            source: string;
             /** Format: int32 */
             startTime: number;
             title: string;
@@ -1889,7 +2382,10 @@ export interface components {
             /** Format: date-time */
             download_time?: string | null;
             episode_id: string;
+            /** Format: int32 */
+            episode_number?: number | null;
             episode_numbering_processed: boolean;
+            episode_type?: string | null;
             favored?: boolean | null;
             guid: string;
             id: string;
@@ -1900,6 +2396,14 @@ export interface components {
             local_url: string;
             name: string;
             podcast_id: string;
+            /**
+             * Format: double
+             * @description Gain in dB that levels the downloaded file to the ReplayGain
+             *     reference, set once its loudness has been measured.
+             */
+            replay_gain_db?: number | null;
+            /** Format: int32 */
+            season?: number | null;
             status: boolean;
             /** Format: int32 */
             total_time: number;
@@ -1913,9 +2417,35 @@ export interface components {
             favored: boolean;
             id: string;
         };
+        /** @description A podcast whose feed failed on its last refresh. */
+        PodcastFeedHealthDto: {
+            active: boolean;
+            /** Format: int32 */
+            consecutiveFailures: number;
+            failingSince?: string | null;
+            lastError?: string | null;
+            /**
+             * Format: int32
+             * @description Status of the last response, missing if the server was not reached.
+             */
+            lastHttpStatus?: number | null;
+            lastFailureAt?: string | null;
+            lastSuccessAt?: string | null;
+            name: string;
+            podcastId: string;
+            rssfeed: string;
+        };
         PodcastRSSAddModel: {
             rssFeedUrl: string;
         };
+        /** @description Highlights wrap the matched words in `<b>`/`</b>`. */
+        PodcastSearchResultDto: {
+            nameHighlight: string;
+            podcast: components["schemas"]["PodcastDto"];
+            /** Format: float */
+            rank: number;
+            summarySnippet: string;
+        };
         PodcastSearchReturn: components["schemas"]["ItunesWrapper"] | components["schemas"]["PodindexResponse"];
         PodcastSetting: {
             activated: boolean;
@@ -1934,11 +2464,32 @@ export interface components {
             podcastId: string;
             /** Format: int32 */
             podcastPrefill: number;
+            /** Format: int32 */
+            refreshInterval?: number;
+            refreshMode?: string;
             replaceInvalidCharacters: boolean;
             replacementStrategy: string;
+            /** Format: int32 */
+            retentionKeepNewest?: number;
+            retentionKeepUnplayed?: boolean;
+            /** Format: int32 */
+            retentionMaxSizeMb?: number;
+            /** Format: int32 */
+            retentionPlayedDays?: number;
+            /**
+             * @description Unset follows the instance settings, `original` keeps downloads as
+             *     they are, anything else is the id of a transcoding profile.
+             */
+            transcodingProfileId?: string | null;
             useExistingFilename: boolean;
             useOneCoverForAllEpisodes: boolean;
         };
+        PodcastStorageUsage: {
+            name: string;
+            podcastId: string;
+            /** Format: int64 */
+            usedBytes: number;
+        };
         PodcastUpdateNameRequest: {
             name: string;
         };
@@ -1987,11 +2538,17 @@ export interface components {
              */
             applyMetadata: boolean;
             /**
-             * @description If `auto_transcode_opus` is on and the file is still mp3, convert it
-             *     to opus (same ffmpeg call the downloader uses).
+             * @description Transcode files that don't have the format of the podcast's
+             *     transcoding profile yet (same ffmpeg call the downloader uses).
              * @default false
              */
             applyTranscode: boolean;
+            /**
+             * @description Run skip detection on the episode, replacing its local silence and
+             *     jingle segments. Works regardless of the `skip_detection` setting.
+             * @default false
+             */
+            detectSkipSegments: boolean;
             /**
              * @description Re-query SponsorBlock and replace stored segments for the episode.
              *     For episodes ingested before SponsorBlock support, the YouTube video id
@@ -2006,7 +2563,28 @@ export interface components {
              */
             regenerateNfo: boolean;
         };
+        RetentionCandidateDto: {
+            /** Format: int64 */
+            bytes: number;
+            episodeId: string;
+            episodeName: string;
+            podcastId: string;
+            podcastName: string;
+            reason: components["schemas"]["RetentionReason"];
+        };
+        /** @description The rule that picked a download for deletion. */
+        RetentionReason: "age" | "keepNewest" | "played" | "sizeCap";
+        SearchResultDto: {
+            episodes: components["schemas"]["EpisodeSearchResultDto"][];
+            podcasts: components["schemas"]["PodcastSearchResultDto"][];
+        };
         Setting: {
+            /**
+             * Format: int32
+             * @description Days audit log entries are kept; 0 keeps them forever. Defaulted on
+             *     deserialize so older clients that omit it keep working.
+             */
+            auditLogRetentionDays?: number;
             autoCleanup: boolean;
             /** Format: int32 */
             autoCleanupDays: number;
@@ -2015,6 +2593,11 @@ export interface components {
             autoUpdate: boolean;
             /** @description Defaulted on deserialize so older clients that omit it keep working. */
             coverFilename?: string;
+            /**
+             * Format: int32
+             * @description Days a feed may keep failing before it is deactivated; 0 never does.
+             */
+            deadFeedDeactivationDays?: number;
             directPaths: boolean;
             episodeFormat: string;
             /**
@@ -2023,6 +2606,11 @@ export interface components {
              */
             episodeNumbering?: boolean;
             id: string;
+            /**
+             * @description Measure the integrated loudness of every download (EBU R128) so
+             *     players can level episodes out with the ReplayGain derived from it.
+             */
+            loudnessAnalysis?: boolean;
             /**
              * Format: int32
              * @description Defaulted on deserialize so older clients that omit it keep working.
@@ -2035,21 +2623,74 @@ export interface components {
             podcastPrefill: number;
             replaceInvalidCharacters: boolean;
             replacementStrategy: string;
+            /**
+             * @description Detect long silences and jingles or ads that repeat across a podcast's
+             *     episodes, served next to the SponsorBlock segments.
+             */
+            skipDetection?: boolean;
             /**
              * @description Enable SponsorBlock for this instance. Defaulted on deserialize so older
              *     clients that omit it keep working.
              */
             sponsorblockEnabled?: boolean;
-            useExistingFilename: boolean;
-            useOneCoverForAllEpisodes: boolean;
-        };
-        SimplifiedDisk: {
-            /** Format: int64 */
+            /**
+             * Format: int32
+             * @description Space all downloads together may take up, in MB; 0 means unlimited.
+             */
+            storageQuotaMb?: number;
+            /**
+             * @description `refuse` skips automatic downloads once a quota is used up,
+             *     `cleanup-oldest` deletes the oldest downloads instead.
+             */
+            storageQuotaPolicy?: string;
+            /**
+             * @description Profile downloads are transcoded with while `auto_transcode_opus` is
+             *     on; unset uses the built-in 48 kbps Opus profile.
+             */
+            transcodingProfileId?: string | null;
+            useExistingFilename: boolean;
+            useOneCoverForAllEpisodes: boolean;
+        };
+        SimplifiedDisk: {
+            /** Format: int64 */
             available_space: number;
             name: string;
             /** Format: int64 */
             total_space: number;
         };
+        /** @enum {string} */
+        SmartPlaylistOrder: "newestFirst" | "oldestFirst" | "shortestFirst" | "longestFirst";
+        /**
+         * @description Stored rules of a smart playlist, evaluated whenever the playlist is read.
+         *     Every condition that is set has to hold; podcasts and tags are combined,
+         *     so `podcastIds` plus `tagIds` selects the episodes of either.
+         */
+        SmartPlaylistQuery: {
+            downloaded?: boolean | null;
+            favorite?: boolean | null;
+            /** Format: int32 */
+            limit?: number | null;
+            /** Format: int32 */
+            maxDurationSeconds?: number | null;
+            /**
+             * Format: int32
+             * @description Leaves out episodes that would push the total length past this many
+             *     seconds, e.g. `7200` for "at most two hours".
+             */
+            maxTotalSeconds?: number | null;
+            /** Format: int32 */
+            minDurationSeconds?: number | null;
+            order?: components["schemas"]["SmartPlaylistOrder"];
+            playState?: null | components["schemas"]["PlayState"];
+            podcastIds?: string[];
+            /**
+             * Format: int32
+             * @description Only episodes published within this many days.
+             */
+            publishedWithinDays?: number | null;
+            tagIds?: string[];
+            triage?: null | components["schemas"]["TriageFilter"];
+        };
         SponsorSegmentDto: {
             actionType: string;
             category: string;
@@ -2057,6 +2698,8 @@ export interface components {
             /** Format: int64 */
             endMs: number;
             locked: boolean;
+            /** @description `sponsorblock` or `local` for segments found by skip detection. */
+            source: string;
             /** Format: int64 */
             startMs: number;
             uuid: string;
@@ -2092,10 +2735,24 @@ export interface components {
             /** Format: int64 */
             totalListenedSeconds: number;
         };
+        /** @description Space taken up by downloaded episodes, as recorded after each download. */
+        StorageOverview: {
+            /**
+             * Format: int64
+             * @description Instance-wide quota, `None` when unlimited.
+             */
+            quotaBytes?: number | null;
+            /** @description `refuse` or `cleanup-oldest`. */
+            quotaPolicy: string;
+            /** Format: int64 */
+            usedBytes: number;
+            users: components["schemas"]["UserStorageUsage"][];
+        };
         SysExtraInfo: {
             disks: components["schemas"]["SimplifiedDisk"][];
             /** Format: int64 */
             podcast_directory: number;
+            storage: components["schemas"]["StorageOverview"];
             system: components["schemas"]["SystemDto"];
         };
         SystemDto: {
@@ -2153,6 +2810,41 @@ export interface components {
             podcastId: string;
             podcastName: string;
         };
+        TranscodingProfileDto: {
+            /** Format: int32 */
+            bitrateKbps: number;
+            /**
+             * Format: int32
+             * @description 1 for mono, 2 for stereo, 0 keeps the channels of the source.
+             */
+            channels: number;
+            /** @description One of `opus`, `mp3`, `aac` or `vorbis`. */
+            codec: string;
+            createdAt: string;
+            id: string;
+            keepOriginal: boolean;
+            loudnorm: boolean;
+            name: string;
+            /**
+             * Format: int32
+             * @description In Hz, 0 keeps the sample rate of the source.
+             */
+            sampleRate: number;
+            trimSilence: boolean;
+        };
+        TranscodingProfileUpsert: {
+            /** Format: int32 */
+            bitrateKbps: number;
+            /** Format: int32 */
+            channels?: number;
+            codec: string;
+            keepOriginal?: boolean;
+            loudnorm?: boolean;
+            name: string;
+            /** Format: int32 */
+            sampleRate?: number;
+            trimSilence?: boolean;
+        };
         TranscriptDto: {
             error?: string | null;
             id: string;
@@ -2197,10 +2889,34 @@ export interface components {
             source: string;
             status: string;
         };
+        /**
+         * @description Triage state to match; `inbox` selects episodes that weren't triaged yet.
+         * @enum {string}
+         */
+        TriageFilter: "inbox" | "queued" | "archived" | "dismissed";
         TriageStatusPut: {
             /** @description One of `queued`, `archived` or `dismissed`. */
             status: string;
         };
+        TwoFactorCode: {
+            /** @description Current TOTP code, or a recovery code where noted. */
+            code: string;
+        };
+        TwoFactorEnrollmentDto: {
+            otpauthUri: string;
+            /** @description Base32 secret for entering the account by hand. */
+            secret: string;
+        };
+        TwoFactorRecoveryCodesDto: {
+            /** @description Each code works once. They are only shown now. */
+            recoveryCodes: string[];
+        };
+        TwoFactorStatusDto: {
+            enabled: boolean;
+            /** @description Enrollment was started but not confirmed with a code yet. */
+            pending: boolean;
+            recoveryCodesLeft: number;
+        };
         UpdateNameSettings: {
             directPaths: boolean;
             episodeFormat: string;
@@ -2227,6 +2943,33 @@ export interface components {
             explicitConsent: boolean;
             role: string;
         };
+        UserStorageQuotaUpdate: {
+            /**
+             * Format: int32
+             * @description Quota in MB, 0 for unlimited. `null` goes back to the instance
+             *     default.
+             */
+            quotaMb?: number | null;
+        };
+        /** @description Space taken up by the downloads of the podcasts a user added. */
+        UserStorageUsage: {
+            podcasts: components["schemas"]["PodcastStorageUsage"][];
+            /**
+             * Format: int64
+             * @description Quota that applies to the user, `None` when unlimited.
+             */
+            quotaBytes?: number | null;
+            /**
+             * Format: int32
+             * @description Quota an admin set for this user in MB. `None` when the instance
+             *     default (`USER_STORAGE_QUOTA_MB`) applies.
+             */
+            quotaOverrideMb?: number | null;
+            /** Format: int64 */
+            usedBytes: number;
+            userId: string;
+            username: string;
+        };
         UserSummary: {
             /** Format: date-time */
             createdAt: string;
@@ -2271,6 +3014,53 @@ export interface components {
 }
 export type $defs = Record<string, never>;
 export interface operations {
+    get_audit_log: {
+        parameters: {
+            query?: {
+                /** @description Zero based page, newest entries first. */
+                page?: number | null;
+                /** @description Entries per page, at most 500. */
+                pageSize?: number | null;
+                actor?: string | null;
+                /** @description e.g. `podcast.delete`, `user.role`, `invite.create` or `settings.update`. */
+                action?: string | null;
+                target?: string | null;
+                /** @description RFC 3339 timestamp; only entries at or after it. */
+                from?: string | null;
+                /** @description RFC 3339 timestamp; only entries before it. */
+                to?: string | null;
+            };
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description One page of the audit log (admin). */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AuditLogPageDto"];
+                };
+            };
+            /** @description Unknown action or malformed timestamp. */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+            /** @description Only admins may read the audit log. */
+            403: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
     list_cast_devices: {
         parameters: {
             query?: never;
@@ -2647,6 +3437,15 @@ export interface operations {
             path: {
                 last_podcast_episode: string | null;
                 only_unlistened: boolean | null;
+                /** @description Only episodes of this season. */
+                season: number | null;
+                /** @description Only episodes of this type: `full`, `trailer` or `bonus`. */
+                episode_type: string | null;
+                /**
+                 * @description `date` (default, newest first) or `episode` (by season and episode
+                 *     number). The `episode` order is not paginated.
+                 */
+                sort: string | null;
                 id: string;
             };
             cookie?: never;
@@ -2849,14 +3648,32 @@ export interface operations {
             };
         };
         responses: {
-            /** @description Performs a login if basic auth is enabled */
+            /** @description Performs a login if basic auth is enabled. Users with a second factor also need `totpCode` and receive a token to use instead of basic auth. */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "text/plain": string;
+                    "application/json": components["schemas"]["LoginResponse"];
+                };
+            };
+        };
+    };
+    sys_logout: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Revokes the token handed out by a two-factor login that authenticated this request. Other logins need nothing revoked. */
+            204: {
+                headers: {
+                    [name: string]: unknown;
                 };
+                content?: never;
             };
         };
     };
@@ -2989,8 +3806,126 @@ export interface operations {
             };
         };
         responses: {
-            /** @description Dismisses a notification */
+            /** @description Dismisses a notification for the current user */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    get_notification_rules: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description The new-episode alert rules of the current user. Without rules every new episode notifies. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["NotificationRuleDto"][];
+                };
+            };
+        };
+    };
+    create_notification_rule: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["NotificationRuleUpsert"];
+            };
+        };
+        responses: {
+            /** @description The created rule. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["NotificationRuleDto"];
+                };
+            };
+            /** @description Unknown podcast or tag, or invalid conditions. */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    update_notification_rule: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["NotificationRuleUpsert"];
+            };
+        };
+        responses: {
+            /** @description The updated rule. */
             200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["NotificationRuleDto"];
+                };
+            };
+            /** @description Unknown podcast or tag, or invalid conditions. */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+            /** @description The current user has no such rule. */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    delete_notification_rule: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description The rule was removed. */
+            204: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+            /** @description The current user has no such rule. */
+            404: {
                 headers: {
                     [name: string]: unknown;
                 };
@@ -3007,7 +3942,7 @@ export interface operations {
         };
         requestBody?: never;
         responses: {
-            /** @description Gets all unread notifications. */
+            /** @description Gets the unread notifications of the current user. */
             200: {
                 headers: {
                     [name: string]: unknown;
@@ -3761,6 +4696,26 @@ export interface operations {
             };
         };
     };
+    get_unhealthy_feeds: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Podcasts whose feed failed on the last refresh, longest failing first. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["PodcastFeedHealthDto"][];
+                };
+            };
+        };
+    };
     update_active_podcast: {
         parameters: {
             query?: never;
@@ -3786,6 +4741,15 @@ export interface operations {
             query?: {
                 last_podcast_episode?: string | null;
                 only_unlistened?: boolean | null;
+                /** @description Only episodes of this season. */
+                season?: number | null;
+                /** @description Only episodes of this type: `full`, `trailer` or `bonus`. */
+                episode_type?: string | null;
+                /**
+                 * @description `date` (default, newest first) or `episode` (by season and episode
+                 *     number). The `episode` order is not paginated.
+                 */
+                sort?: string | null;
             };
             header?: never;
             path: {
@@ -4168,29 +5132,27 @@ export interface operations {
             };
         };
     };
-    get_opml: {
+    get_notification_channels: {
         parameters: {
             query?: never;
             header?: never;
-            path: {
-                type_of: string;
-            };
+            path?: never;
             cookie?: never;
         };
         requestBody?: never;
         responses: {
-            /** @description Gets the podcasts in opml format */
+            /** @description All configured notification channels. */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "text/plain": string;
+                    "application/json": components["schemas"]["NotificationChannelDto"][];
                 };
             };
         };
     };
-    rescan_episodes: {
+    create_notification_channel: {
         parameters: {
             query?: never;
             header?: never;
@@ -4199,12 +5161,21 @@ export interface operations {
         };
         requestBody: {
             content: {
-                "application/json": components["schemas"]["RescanOptions"];
+                "application/json": components["schemas"]["NotificationChannelUpsert"];
             };
         };
         responses: {
-            /** @description Rescans all episodes for metadata */
+            /** @description The created channel. */
             200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["NotificationChannelDto"];
+                };
+            };
+            /** @description Unknown kind or event, or a configuration that doesn't fit the kind. */
+            400: {
                 headers: {
                     [name: string]: unknown;
                 };
@@ -4212,11 +5183,304 @@ export interface operations {
             };
         };
     };
-    run_cleanup: {
+    update_notification_channel: {
         parameters: {
             query?: never;
             header?: never;
-            path?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["NotificationChannelUpsert"];
+            };
+        };
+        responses: {
+            /** @description The updated channel. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["NotificationChannelDto"];
+                };
+            };
+            /** @description Unknown kind or event, or a configuration that doesn't fit the kind. */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+            /** @description No such channel. */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    delete_notification_channel: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description The channel was removed. */
+            204: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+            /** @description No such channel. */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    test_notification_channel: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description A test notification was delivered. */
+            204: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+            /** @description The channel couldn't deliver the notification. */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+            /** @description No such channel. */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    get_transcoding_profiles: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description All transcoding profiles. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["TranscodingProfileDto"][];
+                };
+            };
+        };
+    };
+    create_transcoding_profile: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["TranscodingProfileUpsert"];
+            };
+        };
+        responses: {
+            /** @description The created profile. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["TranscodingProfileDto"];
+                };
+            };
+            /** @description Unknown codec or out of range values. */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    update_transcoding_profile: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["TranscodingProfileUpsert"];
+            };
+        };
+        responses: {
+            /** @description The updated profile. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["TranscodingProfileDto"];
+                };
+            };
+            /** @description Unknown codec or out of range values. */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+            /** @description No such profile. */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    delete_transcoding_profile: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description The profile was removed. Podcasts that used it fall back to the instance settings. */
+            204: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+            /** @description No such profile. */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    get_opml: {
+        parameters: {
+            query?: {
+                scope?: components["schemas"]["OpmlScope"];
+                /**
+                 * @description Adds the active flag and podcast settings as `podfetch:` attributes
+                 *     so another PodFetch instance can restore them on import.
+                 */
+                extended?: boolean;
+            };
+            header?: never;
+            path: {
+                type_of: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Gets the podcasts in opml format, grouped by the requester's tags */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "text/plain": string;
+                };
+            };
+        };
+    };
+    preview_cleanup: {
+        parameters: {
+            query?: {
+                /** @description Only list downloads of this podcast. */
+                podcastId?: string | null;
+            };
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Lists the downloads the next scheduled cleanup deletes, without deleting anything */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["RetentionCandidateDto"][];
+                };
+            };
+        };
+    };
+    rescan_episodes: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["RescanOptions"];
+            };
+        };
+        responses: {
+            /** @description Rescans all episodes for metadata */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    run_cleanup: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
             cookie?: never;
         };
         requestBody?: never;
@@ -4232,49 +5496,322 @@ export interface operations {
     };
     get_sponsorblock_settings: {
         parameters: {
-            query?: never;
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Current user's SponsorBlock preferences */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["SponsorblockUserSettingsDto"];
+                };
+            };
+        };
+    };
+    update_sponsorblock_settings: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["SponsorblockUserSettingsDto"];
+            };
+        };
+        responses: {
+            /** @description Updated SponsorBlock preferences */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["SponsorblockUserSettingsDto"];
+                };
+            };
+        };
+    };
+    reparse_transcripts: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Re-parsed every archived transcript. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ReparseReportDto"];
+                };
+            };
+        };
+    };
+    get_stats_overview: {
+        parameters: {
+            query?: {
+                from?: string | null;
+                to?: string | null;
+                topLimit?: number | null;
+            };
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Gets listening statistics overview for the current user. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["StatsOverview"];
+                };
+            };
+        };
+    };
+    get_public_config: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Gets the environment configuration */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ConfigModel"];
+                };
+            };
+        };
+    };
+    get_sys_info: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Gets the system information */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["SysExtraInfo"];
+                };
+            };
+        };
+    };
+    get_tags: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Gets all tags of a user */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["Tag"][];
+                };
+            };
+        };
+    };
+    insert_tag: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["TagCreate"];
+            };
+        };
+        responses: {
+            /** @description Creates a new tag */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["Tag"];
+                };
+            };
+        };
+    };
+    update_tag: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                tag_id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["TagCreate"];
+            };
+        };
+        responses: {
+            /** @description Updates a tag by id */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    delete_tag: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                tag_id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Deletes a tag by id */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    add_podcast_to_tag: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                tag_id: string;
+                podcast_id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Adds a podcast to a tag */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["TagsPodcast"];
+                };
+            };
+        };
+    };
+    delete_podcast_from_tag: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                tag_id: string;
+                podcast_id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Deletes a podcast from a tag */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    search_transcripts: {
+        parameters: {
+            query: {
+                q: string;
+                podcastId?: string | null;
+                page?: number | null;
+            };
             header?: never;
             path?: never;
             cookie?: never;
         };
         requestBody?: never;
         responses: {
-            /** @description Current user's SponsorBlock preferences */
+            /** @description Transcript segments matching the query, grouped by episode. */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["SponsorblockUserSettingsDto"];
+                    "application/json": components["schemas"]["TranscriptSearchGroupDto"][];
                 };
             };
         };
     };
-    update_sponsorblock_settings: {
+    search: {
         parameters: {
-            query?: never;
+            query: {
+                /** @description Words match as prefixes, `"quoted words"` as an exact phrase. */
+                q: string;
+                podcastId?: string | null;
+                tagId?: string | null;
+                /** @description Only downloaded (`true`) or only not yet downloaded (`false`) episodes. */
+                downloaded?: boolean | null;
+                /** @description Earliest recording date (`YYYY-MM-DD`), inclusive. */
+                from?: string | null;
+                /** @description Latest recording date (`YYYY-MM-DD`), inclusive. */
+                to?: string | null;
+                /** @description Zero-based page. */
+                page?: number | null;
+            };
             header?: never;
             path?: never;
             cookie?: never;
         };
-        requestBody: {
-            content: {
-                "application/json": components["schemas"]["SponsorblockUserSettingsDto"];
-            };
-        };
+        requestBody?: never;
         responses: {
-            /** @description Updated SponsorBlock preferences */
+            /** @description Podcasts and episodes matching the query, best match first. Episode results include the matching transcript segments. */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["SponsorblockUserSettingsDto"];
+                    "application/json": components["schemas"]["SearchResultDto"];
                 };
             };
         };
     };
-    reparse_transcripts: {
+    get_api_tokens: {
         parameters: {
             query?: never;
             header?: never;
@@ -4283,62 +5820,76 @@ export interface operations {
         };
         requestBody?: never;
         responses: {
-            /** @description Re-parsed every archived transcript. */
+            /** @description The api tokens of the current user. */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["ReparseReportDto"];
+                    "application/json": components["schemas"]["ApiTokenDto"][];
                 };
             };
         };
     };
-    get_stats_overview: {
+    create_api_token: {
         parameters: {
-            query?: {
-                from?: string | null;
-                to?: string | null;
-                topLimit?: number | null;
-            };
+            query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
-        requestBody?: never;
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["ApiTokenCreate"];
+            };
+        };
         responses: {
-            /** @description Gets listening statistics overview for the current user. */
+            /** @description The created token and its secret. */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["StatsOverview"];
+                    "application/json": components["schemas"]["ApiTokenCreated"];
+                };
+            };
+            /** @description Missing name, unknown scope or an expiry in the past. */
+            400: {
+                headers: {
+                    [name: string]: unknown;
                 };
+                content?: never;
             };
         };
     };
-    get_public_config: {
+    delete_api_token: {
         parameters: {
             query?: never;
             header?: never;
-            path?: never;
+            path: {
+                id: string;
+            };
             cookie?: never;
         };
         requestBody?: never;
         responses: {
-            /** @description Gets the environment configuration */
-            200: {
+            /** @description The token was revoked. */
+            204: {
                 headers: {
                     [name: string]: unknown;
                 };
-                content: {
-                    "application/json": components["schemas"]["ConfigModel"];
+                content?: never;
+            };
+            /** @description No such token. */
+            404: {
+                headers: {
+                    [name: string]: unknown;
                 };
+                content?: never;
             };
         };
     };
-    get_sys_info: {
+    get_two_factor_status: {
         parameters: {
             query?: never;
             header?: never;
@@ -4347,18 +5898,18 @@ export interface operations {
         };
         requestBody?: never;
         responses: {
-            /** @description Gets the system information */
+            /** @description Two-factor state of the current user. */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["SysExtraInfo"];
+                    "application/json": components["schemas"]["TwoFactorStatusDto"];
                 };
             };
         };
     };
-    get_tags: {
+    start_two_factor_enrollment: {
         parameters: {
             query?: never;
             header?: never;
@@ -4367,18 +5918,25 @@ export interface operations {
         };
         requestBody?: never;
         responses: {
-            /** @description Gets all tags of a user */
+            /** @description A new secret to add to an authenticator app. */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["Tag"][];
+                    "application/json": components["schemas"]["TwoFactorEnrollmentDto"];
+                };
+            };
+            /** @description Two-factor authentication is already enabled. */
+            409: {
+                headers: {
+                    [name: string]: unknown;
                 };
+                content?: never;
             };
         };
     };
-    insert_tag: {
+    confirm_two_factor_enrollment: {
         parameters: {
             query?: never;
             header?: never;
@@ -4387,58 +5945,57 @@ export interface operations {
         };
         requestBody: {
             content: {
-                "application/json": components["schemas"]["TagCreate"];
+                "application/json": components["schemas"]["TwoFactorCode"];
             };
         };
         responses: {
-            /** @description Creates a new tag */
+            /** @description Two-factor authentication is enabled. */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["Tag"];
+                    "application/json": components["schemas"]["TwoFactorRecoveryCodesDto"];
+                };
+            };
+            /** @description The code doesn't match the pending secret. */
+            400: {
+                headers: {
+                    [name: string]: unknown;
                 };
+                content?: never;
+            };
+            /** @description No enrollment was started. */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
             };
         };
     };
-    update_tag: {
+    disable_two_factor: {
         parameters: {
             query?: never;
             header?: never;
-            path: {
-                tag_id: string;
-            };
+            path?: never;
             cookie?: never;
         };
         requestBody: {
             content: {
-                "application/json": components["schemas"]["TagCreate"];
+                "application/json": components["schemas"]["TwoFactorCode"];
             };
         };
         responses: {
-            /** @description Updates a tag by id */
-            200: {
+            /** @description Two-factor authentication was switched off. */
+            204: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content?: never;
             };
-        };
-    };
-    delete_tag: {
-        parameters: {
-            query?: never;
-            header?: never;
-            path: {
-                tag_id: string;
-            };
-            cookie?: never;
-        };
-        requestBody?: never;
-        responses: {
-            /** @description Deletes a tag by id */
-            200: {
+            /** @description Invalid code. */
+            400: {
                 headers: {
                     [name: string]: unknown;
                 };
@@ -4446,71 +6003,68 @@ export interface operations {
             };
         };
     };
-    add_podcast_to_tag: {
+    regenerate_two_factor_recovery_codes: {
         parameters: {
             query?: never;
             header?: never;
-            path: {
-                tag_id: string;
-                podcast_id: string;
-            };
+            path?: never;
             cookie?: never;
         };
-        requestBody?: never;
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["TwoFactorCode"];
+            };
+        };
         responses: {
-            /** @description Adds a podcast to a tag */
+            /** @description Replaces the recovery codes. */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["TagsPodcast"];
+                    "application/json": components["schemas"]["TwoFactorRecoveryCodesDto"];
+                };
+            };
+            /** @description Invalid code. */
+            400: {
+                headers: {
+                    [name: string]: unknown;
                 };
+                content?: never;
             };
         };
     };
-    delete_podcast_from_tag: {
+    reset_two_factor: {
         parameters: {
             query?: never;
             header?: never;
             path: {
-                tag_id: string;
-                podcast_id: string;
+                username: string;
             };
             cookie?: never;
         };
         requestBody?: never;
         responses: {
-            /** @description Deletes a podcast from a tag */
-            200: {
+            /** @description The second factor of the user was removed (admin). */
+            204: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content?: never;
             };
-        };
-    };
-    search_transcripts: {
-        parameters: {
-            query: {
-                q: string;
-                podcastId?: string | null;
-                page?: number | null;
-            };
-            header?: never;
-            path?: never;
-            cookie?: never;
-        };
-        requestBody?: never;
-        responses: {
-            /** @description Transcript segments matching the query, grouped by episode. */
-            200: {
+            /** @description Only admins may reset a second factor. */
+            403: {
                 headers: {
                     [name: string]: unknown;
                 };
-                content: {
-                    "application/json": components["schemas"]["TranscriptSearchGroupDto"][];
+                content?: never;
+            };
+            /** @description Unknown user or no second factor. */
+            404: {
+                headers: {
+                    [name: string]: unknown;
                 };
+                content?: never;
             };
         };
     };
@@ -4676,10 +6230,63 @@ export interface operations {
             };
         };
     };
-    proxy_podcast: {
+    get_user_storage: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                username: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Storage used by the podcasts the user added, and the quota that applies to them */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["UserStorageUsage"];
+                };
+            };
+        };
+    };
+    update_user_storage_quota: {
         parameters: {
             query?: never;
             header?: never;
+            path: {
+                username: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["UserStorageQuotaUpdate"];
+            };
+        };
+        responses: {
+            /** @description Sets the storage quota of a user (admin) */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["UserStorageUsage"];
+                };
+            };
+        };
+    };
+    proxy_podcast: {
+        parameters: {
+            query?: {
+                /** @description `mp3` or `aac`. Defaults to mp3 when only a bitrate is given. */
+                codec?: string | null;
+                /** @description Target bitrate in kbps, 16 to 320. Defaults to 64. */
+                bitrate?: number | null;
+            };
+            header?: never;
             path?: never;
             cookie?: never;
         };
@@ -4696,7 +6303,12 @@ export interface operations {
     };
     proxy_podcast_with_path_api_key: {
         parameters: {
-            query?: never;
+            query?: {
+                /** @description `mp3` or `aac`. Defaults to mp3 when only a bitrate is given. */
+                codec?: string | null;
+                /** @description Target bitrate in kbps, 16 to 320. Defaults to 64. */
+                bitrate?: number | null;
+            };
             header?: never;
             path: {
                 apiKey: string;
@@ -4773,6 +6385,47 @@ export interface operations {
             };
         };
     };
+    get_rss_feed_for_playlist: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Gets the rss feed of a playlist. Smart playlists are evaluated for their owner. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
+    get_rss_feed_for_playlist_with_path_api_key: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                apiKey: string;
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Gets the rss feed of a playlist (API key in path) */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content?: never;
+            };
+        };
+    };
     get_rss_feed_for_podcast: {
         parameters: {
             query?: never;
@@ -39,6 +39,7 @@ export const CastButton: FC = () => {
     const { t } = useTranslation()
     const { enqueueSnackbar } = useSnackbar()
     const podcastEpisode = useAudioPlayer((state) => state.loadedPodcastEpisode)
+    const volume = useAudioPlayer((state) => state.volume)
     const activeSession = useCast((state) => state.activeSession)
     const setActiveSession = useCast((state) => state.setActiveSession)
 
@@ -78,6 +79,8 @@ export const CastButton: FC = () => {
                     title: ep.name,
                     artwork_url: ep.image_url || ep.local_image_url || undefined,
                     duration_secs: ep.total_time > 0 ? ep.total_time : undefined,
+                    // Lets the server level the episode with its ReplayGain.
+                    volume: ep.replay_gain_db != null ? Math.min(1, volume / 100) : undefined,
                 },
             })
             setActiveSession({
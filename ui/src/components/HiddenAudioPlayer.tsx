@@ -3,7 +3,7 @@ import useOnMount from '../hooks/useOnMount'
 import useAudioPlayer from '../store/AudioPlayerSlice'
 import { AudioAmplifier } from '../models/AudioAmplifier'
 import { $api } from '../utils/http'
-import {getAudioPlayer} from "../utils/audioPlayer";
+import {getAudioPlayer, replayGainFactor} from "../utils/audioPlayer";
 import useCommon from "../store/CommonSlice";
 import {SKIPPED_TIME} from "../utils/Utilities";
 import {usePlaybackLogger} from "../hooks/usePlaybackLogger";
@@ -51,8 +51,9 @@ export const HiddenAudioPlayer: FC<HiddenAudioPlayerProps> = ({ setAudioAmplifie
         if (!audioPlayer) {
             return
         }
-        audioPlayer.volume = Math.min(1, Math.max(0, volume / 100))
-    }, [volume])
+        const gain = replayGainFactor(podcastEpisode?.podcastEpisode.replay_gain_db)
+        audioPlayer.volume = Math.min(1, Math.max(0, volume / 100 * gain))
+    }, [volume, podcastEpisode])
 
     useEffect(() => {
         if (!('mediaSession' in navigator)) {
@@ -53,7 +53,9 @@ export const OpmlAdd: FC<OpmlAddProps> = ({}) => {
             return
         }
         let content = files[0]!.content
-        const count = (content.match(/xmlUrl\s*=/gi) || []).length
+        // Feeds exported below several tags are listed more than once but imported once
+        const feedUrls = content.match(/xmlUrl\s*=\s*("[^"]*"|'[^']*')/gi) || []
+        const count = new Set(feedUrls.map(url => url.replace(/^xmlUrl\s*=\s*/i, '').slice(1, -1))).size
 
         useOpmlImport.getState().setProgress([])
         useOpmlImport.getState().setMessages([])
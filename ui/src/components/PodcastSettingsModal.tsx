@@ -48,6 +48,7 @@ export const PodcastSettingsModal: FC<PodcastSettingsModalProps> = ({
     // podcast has no activated override, so seed the draft from them rather than
     // from hardcoded constants (see issue #2141).
     const globalSettingsQuery = $api.useQuery('get', '/api/v1/settings')
+    const transcodingProfilesQuery = $api.useQuery('get', '/api/v1/settings/transcoding-profiles')
 
     const updateSettings = $api.useMutation(
         'put',
@@ -101,6 +102,13 @@ export const PodcastSettingsModal: FC<PodcastSettingsModalProps> = ({
                 nfoFormat: settingsQuery.data.nfoFormat ?? 'off',
                 coverFilename: settingsQuery.data.coverFilename ?? 'image',
                 autoTranscribe: settingsQuery.data.autoTranscribe ?? false,
+                refreshMode: settingsQuery.data.refreshMode ?? 'fixed',
+                refreshInterval: settingsQuery.data.refreshInterval ?? 0,
+                retentionKeepNewest: settingsQuery.data.retentionKeepNewest ?? 0,
+                retentionPlayedDays: settingsQuery.data.retentionPlayedDays ?? 0,
+                retentionKeepUnplayed: settingsQuery.data.retentionKeepUnplayed ?? false,
+                retentionMaxSizeMb: settingsQuery.data.retentionMaxSizeMb ?? 0,
+                transcodingProfileId: settingsQuery.data.transcodingProfileId ?? null,
             })
         } else if (!settingsQuery.isLoading && !globalSettingsQuery.isLoading) {
             setDraft(generatePodcastDefaultSettings(podcast.id, globalSettingsQuery.data))
@@ -223,6 +231,61 @@ export const PodcastSettingsModal: FC<PodcastSettingsModalProps> = ({
                                 }
                             />
 
+                            <label className="col-span-2 ui-text">
+                                {t('retention-keep-newest')}
+                            </label>
+                            <CustomInput
+                                type="number"
+                                min={0}
+                                value={draft.retentionKeepNewest}
+                                onChange={(e) =>
+                                    update(
+                                        'retentionKeepNewest',
+                                        Number(e.target.value)
+                                    )
+                                }
+                            />
+
+                            <label className="col-span-2 ui-text">
+                                {t('retention-played-days')}
+                            </label>
+                            <CustomInput
+                                type="number"
+                                min={0}
+                                value={draft.retentionPlayedDays}
+                                onChange={(e) =>
+                                    update(
+                                        'retentionPlayedDays',
+                                        Number(e.target.value)
+                                    )
+                                }
+                            />
+
+                            <label className="col-span-2 ui-text">
+                                {t('retention-keep-unplayed')}
+                            </label>
+                            <Switcher
+                                checked={draft.retentionKeepUnplayed}
+                                onChange={(v) =>
+                                    update('retentionKeepUnplayed', v)
+                                }
+                            />
+
+                            <label className="col-span-2 ui-text">
+                                {t('retention-max-size-mb')}
+                            </label>
+                            <CustomInput
+                                type="number"
+                                min={0}
+                                value={draft.retentionMaxSizeMb}
+                                onChange={(e) =>
+                                    update(
+                                        'retentionMaxSizeMb',
+                                        Number(e.target.value)
+                                    )
+                                }
+                            />
+
                             <label className="col-span-2 ui-text">
                                 {t('auto-update')}
                             </label>
@@ -233,6 +296,37 @@ export const PodcastSettingsModal: FC<PodcastSettingsModalProps> = ({
                                 }
                             />
 
+                            <label className="col-span-2 ui-text">
+                                {t('refresh-mode')}
+                                <SettingsInfoIcon
+                                    headerKey="refresh-mode"
+                                    textKey="refresh-mode-explanation"
+                                />
+                            </label>
+                            <CustomSelect
+                                value={draft.refreshMode}
+                                options={[
+                                    { label: t('refresh-mode-fixed'), value: 'fixed' },
+                                    { label: t('refresh-mode-adaptive'), value: 'adaptive' },
+                                ]}
+                                onChange={(v) => update('refreshMode', v)}
+                            />
+
+                            <label className="col-span-2 ui-text">
+                                {t('refresh-interval')}
+                            </label>
+                            <CustomInput
+                                type="number"
+                                min={0}
+                                value={draft.refreshInterval}
+                                onChange={(e) =>
+                                    update(
+                                        'refreshInterval',
+                                        Number(e.target.value)
+                                    )
+                                }
+                            />
+
                             <label className="col-span-2 ui-text">
                                 {t('auto-download')}
                             </label>
@@ -297,6 +391,28 @@ export const PodcastSettingsModal: FC<PodcastSettingsModalProps> = ({
                                 onChange={(v) => update('nfoFormat', v)}
                             />
 
+                            <label className="col-span-2 ui-text">
+                                {t('transcoding-profile')}
+                                <SettingsInfoIcon
+                                    headerKey="transcoding-profile"
+                                    textKey="transcoding-profile-podcast-explanation"
+                                />
+                            </label>
+                            <CustomSelect
+                                value={draft.transcodingProfileId ?? 'inherit'}
+                                options={[
+                                    { label: t('transcoding-profile-inherit'), value: 'inherit' },
+                                    { label: t('transcoding-profile-original'), value: 'original' },
+                                    ...(transcodingProfilesQuery.data ?? []).map((profile) => ({
+                                        label: profile.name,
+                                        value: profile.id,
+                                    })),
+                                ]}
+                                onChange={(v) =>
+                                    update('transcodingProfileId', v === 'inherit' ? null : v)
+                                }
+                            />
+
                             <label className="col-span-2 ui-text">
                                 {t('cover-filename')}
                             </label>
@@ -14,6 +14,7 @@ import {useQueryClient} from "@tanstack/react-query";
 export const Settings = () => {
     const {enqueueSnackbar} = useSnackbar()
     const settingsModel = $api.useQuery('get', '/api/v1/settings')
+    const transcodingProfiles = $api.useQuery('get', '/api/v1/settings/transcoding-profiles')
     const runCleanupMutation = $api.useMutation('put', '/api/v1/settings/runcleanup')
     const saveSettingsMutation = $api.useMutation('put', '/api/v1/settings')
     const { t } = useTranslation()
@@ -104,6 +105,44 @@ export const Settings = () => {
                         }))
                     }} />
                 </div>
+                <div className="flex flex-col gap-2 xs:contents mb-4">
+                    <label htmlFor="transcoding-profile" className="flex gap-1">{t('transcoding-profile')} <SettingsInfoIcon headerKey="transcoding-profile" textKey="transcoding-profile-explanation" /></label>
+                    <CustomSelect
+                        id="transcoding-profile"
+                        value={settingsModel.data?.transcodingProfileId ?? 'builtin'}
+                        options={[
+                            { label: t('transcoding-profile-builtin'), value: 'builtin' },
+                            ...(transcodingProfiles.data ?? []).map((profile) => ({
+                                label: profile.name,
+                                value: profile.id,
+                            })),
+                        ]}
+                        onChange={(v) => {
+                            queryClient.setQueryData(['get', '/api/v1/settings'], (oldData: Setting) => ({
+                                ...oldData,
+                                transcodingProfileId: v === 'builtin' ? null : v
+                            }))
+                        }}
+                    />
+                </div>
+                <div className="flex flex-col gap-2 xs:contents mb-4">
+                    <label htmlFor="loudness-analysis" className="flex gap-1">{t('loudness-analysis')} <SettingsInfoIcon headerKey="loudness-analysis" textKey="loudness-analysis-explanation" /></label>
+                    <Switcher loading={settingsModel.isLoading} checked={settingsModel.data?.loudnessAnalysis} className="xs:justify-self-end" id="loudness-analysis" onChange={() => {
+                        queryClient.setQueryData(['get', '/api/v1/settings'], (oldData: Setting) => ({
+                            ...oldData,
+                            loudnessAnalysis: !oldData?.loudnessAnalysis
+                        }))
+                    }} />
+                </div>
+                <div className="flex flex-col gap-2 xs:contents mb-4">
+                    <label htmlFor="skip-detection" className="flex gap-1">{t('skip-detection')} <SettingsInfoIcon headerKey="skip-detection" textKey="skip-detection-explanation" /></label>
+                    <Switcher loading={settingsModel.isLoading} checked={settingsModel.data?.skipDetection} className="xs:justify-self-end" id="skip-detection" onChange={() => {
+                        queryClient.setQueryData(['get', '/api/v1/settings'], (oldData: Setting) => ({
+                            ...oldData,
+                            skipDetection: !oldData?.skipDetection
+                        }))
+                    }} />
+                </div>
                 <div className="flex flex-col gap-2 xs:contents mb-4">
                     <label htmlFor="use-one-cover-for-all-episodes" className="flex gap-1">{t('use-one-cover-for-all-episodes')} <SettingsInfoIcon headerKey="use-one-cover-for-all-episodes" textKey="use-one-cover-for-all-episodes-explanation" /></label>
                     <Switcher loading={settingsModel.isLoading} checked={settingsModel.data?.useOneCoverForAllEpisodes} className="xs:justify-self-end" id="use-one-cover-for-all-episodes" onChange={() => {
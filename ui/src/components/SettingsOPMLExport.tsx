@@ -1,12 +1,20 @@
-import { FC } from 'react'
+import { FC, useState } from 'react'
 import { useTranslation } from 'react-i18next'
 import { CustomButtonSecondary } from './CustomButtonSecondary'
 import { Download } from 'lucide-react'
 import {$api} from "../utils/http";
+import { Switcher } from './Switcher'
+import { USER_ROLE } from '../models/constants'
 
 export const SettingsOPMLExport: FC = () => {
     const { t } = useTranslation()
     const exportOpmlMutation = $api.useMutation('get', '/api/v1/settings/opml/{type_of}')
+    const me = $api.useQuery('get', '/api/v1/users/{username}', {
+        params: { path: { username: 'me' } },
+    })
+    const isPrivileged = me.data !== undefined && me.data.role !== USER_ROLE
+    const [allPodcasts, setAllPodcasts] = useState(false)
+    const [extended, setExtended] = useState(false)
 
     const downloadOPML = (exportType: string) => {
         exportOpmlMutation.mutateAsync({
@@ -14,6 +22,10 @@ export const SettingsOPMLExport: FC = () => {
             params: {
                 path: {
                     type_of: exportType
+                },
+                query: {
+                    scope: allPodcasts ? 'all' : 'subscriptions',
+                    extended: isPrivileged && extended
                 }
             }
         }).then((response) => {
@@ -35,6 +47,14 @@ export const SettingsOPMLExport: FC = () => {
 
     return (
         <div className="grid grid-cols-1 xs:grid-cols-[auto_1fr] items-center justify-items-start gap-x-20 gap-y-4 xs:gap-y-6 mb-10 ui-text">
+            {isPrivileged && <>
+                <label htmlFor="opml-all-podcasts">{t('export-all-podcasts')}</label>
+                <Switcher checked={allPodcasts} id="opml-all-podcasts" onChange={setAllPodcasts}/>
+
+                <label htmlFor="opml-extended">{t('export-podfetch-settings')}</label>
+                <Switcher checked={extended} id="opml-extended" onChange={setExtended}/>
+            </>}
+
             <span>{t('export-with-local-urls')}</span>
             <CustomButtonSecondary className="flex items-center" onClick={() => {
                 downloadOPML('local')
@@ -14,6 +14,7 @@ const OPTION_KEYS = [
     'applyTranscode',
     'applyCovers',
     'applyMetadata',
+    'detectSkipSegments',
 ] as const
 
 export const SettingsRescan: FC = () => {
@@ -25,6 +26,7 @@ export const SettingsRescan: FC = () => {
         applyTranscode: false,
         applyCovers: false,
         applyMetadata: false,
+        detectSkipSegments: false,
         refetchSponsorblock: false,
         regenerateNfo: false,
     })
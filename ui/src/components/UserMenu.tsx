@@ -3,7 +3,7 @@ import { CustomDropdownMenu, MenuItem } from './CustomDropdownMenu'
 import { CircleUserRound, Info, LogOut, Settings, Users } from 'lucide-react'
 import useCommon from "../store/CommonSlice";
 import {$api} from "../utils/http";
-import {removeLogin} from "../utils/login";
+import {logout} from "../utils/passwordLogin";
 import {ADMIN_ROLE} from "../models/constants";
 
 
@@ -70,8 +70,7 @@ export const UserMenu: FC = () => {
                 icon: <LogOut size={16} />,
                 translationKey: 'logout',
                 onClick: () => {
-                    removeLogin()
-                    window.location.reload()
+                    void logout().then(() => window.location.reload())
                 }
             })
         }
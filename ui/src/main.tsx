@@ -11,7 +11,7 @@ import '@fontsource-variable/geist'
 import './index.css'
 import {$api} from "./utils/http";
 import {QueryClientProvider} from "@tanstack/react-query";
-import {setAuth, setLogin} from "./utils/login";
+import {getAuth, setAuth, setLogin} from "./utils/login";
 import {getConfigFromHtmlFile} from "./utils/config";
 import {queryClient} from "./utils/socketio";
 import {registerPwaServiceWorker} from "./utils/pwa";
@@ -126,23 +126,30 @@ if (config) {
                 rememberMe: false,
                 loginType: 'basic'
             })
-            let basicAuth = sessionStorage.getItem('auth') || localStorage.getItem('auth')
+            if (getAuth()?.kind === 'token') {
+                // A two-factor login: its api token stands in for the password.
+                queryClient.fetchQuery($api.queryOptions('get', '/api/v1/users/me/two-factor', {}, {retry: false})).catch(()=>{
+                    window.location.href = postUrl
+                })
+            } else {
+                let basicAuth = sessionStorage.getItem('auth') || localStorage.getItem('auth')
 
-            if (!basicAuth || atob(basicAuth).split(':').length !== 2) {
-                window.location.replace(postUrl)
-                throw new Error('No basic auth found')
-            }
-            const basicAuthDecoded = atob(basicAuth)
-            queryClient.fetchQuery($api.queryOptions('post', '/api/v1/login', {
-                body: {
-                    username: basicAuthDecoded.split(':')[0]!,
-                    password: basicAuthDecoded.split(':')[1]!
+                if (!basicAuth || atob(basicAuth).split(':').length !== 2) {
+                    window.location.replace(postUrl)
+                    throw new Error('No basic auth found')
                 }
-            }, {retry: false})).then(()=>{
-                console.log('Logged in successfully')
-            }).catch(()=>{
-                window.location.href = postUrl
-            })
+                const basicAuthDecoded = atob(basicAuth)
+                queryClient.fetchQuery($api.queryOptions('post', '/api/v1/login', {
+                    body: {
+                        username: basicAuthDecoded.split(':')[0]!,
+                        password: basicAuthDecoded.split(':')[1]!
+                    }
+                }, {retry: false})).then(()=>{
+                    console.log('Logged in successfully')
+                }).catch(()=>{
+                    window.location.href = postUrl
+                })
+            }
         }
     }
 }
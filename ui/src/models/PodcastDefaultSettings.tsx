@@ -29,6 +29,13 @@ export const generatePodcastDefaultSettings = (
         useOneCoverForAllEpisodes: globalSettings?.useOneCoverForAllEpisodes ?? false,
         nfoFormat: globalSettings?.nfoFormat ?? "off",
         coverFilename: globalSettings?.coverFilename ?? "image",
-        autoTranscribe: false
+        autoTranscribe: false,
+        refreshMode: "fixed",
+        refreshInterval: 0,
+        retentionKeepNewest: 0,
+        retentionPlayedDays: 0,
+        retentionKeepUnplayed: false,
+        retentionMaxSizeMb: 0,
+        transcodingProfileId: null
     } satisfies components['schemas']['PodcastSetting']
 }
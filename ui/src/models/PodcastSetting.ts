@@ -17,4 +17,11 @@ export type PodcastSetting = {
     nfoFormat: string,
     coverFilename: string,
     autoTranscribe: boolean,
+    refreshMode: string,
+    refreshInterval: number,
+    retentionKeepNewest: number,
+    retentionPlayedDays: number,
+    retentionKeepUnplayed: boolean,
+    retentionMaxSizeMb: number,
+    transcodingProfileId: string | null,
 }
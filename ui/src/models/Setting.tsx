@@ -17,4 +17,7 @@ export type Setting = {
     nfoFormat: string,
     coverFilename: string,
     maxParallelDownloads: number,
+    transcodingProfileId?: string | null,
+    loudnessAnalysis?: boolean,
+    skipDetection?: boolean,
 }
@@ -11,49 +11,44 @@ import { Loading } from '../components/Loading'
 import { OIDCButton } from '../components/OIDCButton'
 import { Mic } from 'lucide-react'
 import {$api} from "../utils/http";
-import {setAuth, setLogin} from "../utils/login";
+import {loginWithPassword} from "../utils/passwordLogin";
 
 export type LoginData = {
     username: string,
     password: string,
-    rememberMe: boolean
+    rememberMe: boolean,
+    totpCode: string
 }
 export const Login = () => {
     const configModel = $api.useQuery('get', '/api/v1/sys/config')
-    const loginMutation = $api.useMutation('post', '/api/v1/login')
     const navigate = useNavigate()
     const [alert, setAlert] = useState<string>()
+    const [secondFactorRequired, setSecondFactorRequired] = useState(false)
     const { t } = useTranslation()
 
     const { control, handleSubmit, formState: {} } = useForm<LoginData>({
         defaultValues: {
             username: '',
             password: '',
-            rememberMe: false
+            rememberMe: false,
+            totpCode: ''
         }
     })
 
     const onSubmit: SubmitHandler<LoginData> = (data, p) => {
         p?.preventDefault()
 
-        setLogin({
-            rememberMe: data.rememberMe,
-            loginType: 'basic'
-        })
-
-        loginMutation.mutateAsync({
-            body: {
-                username: data.username,
-                password: data.password
-            }
-        }).then(()=>{
-            const basicAuthString = btoa(data.username + ':' + data.password)
-
-            setAuth(basicAuthString)
-            navigate('/')
-        }).catch((e)=>{
-            setAlert(e.toString())
-        })
+        loginWithPassword(data.username, data.password, data.rememberMe, data.totpCode)
+            .then((result)=>{
+                if (result === 'second-factor-required') {
+                    setAlert(undefined)
+                    setSecondFactorRequired(true)
+                    return
+                }
+                navigate('/')
+            }).catch((e)=>{
+                setAlert(e.toString())
+            })
     }
 
     if (!configModel) {
@@ -103,6 +98,20 @@ export const Login = () => {
                                 <CustomInput autoComplete="current-password" className="w-full" id="password" name={name} onChange={onChange} placeholder="••••••••" type="password" value={value} required />
                             )} />
                         </div>
+                        {secondFactorRequired && (
+                            <div className="flex flex-col gap-2">
+                                <label className="text-sm ui-text" htmlFor="totp-code">{t('two-factor-code')}</label>
+
+                                <Controller
+                                name="totpCode"
+                                control={control}
+                                render={({ field: { name, onChange, value }}) => (
+                                    <CustomInput autoComplete="one-time-code" autoFocus className="w-full" id="totp-code" name={name} onChange={onChange} placeholder="123456" value={value} required />
+                                )} />
+
+                                <span className="text-xs ui-text-muted">{t('two-factor-code-explanation')}</span>
+                            </div>
+                        )}
                         <div className="flex items-center">
                             <Controller
                             name="rememberMe"
@@ -4,6 +4,7 @@ import {useQuery} from "@tanstack/react-query";
 import {Heading1} from "../components/Heading1";
 import {apiURL, HEADER_TO_USE} from "../utils/http";
 import {getConfigFromHtmlFile} from "../utils/config";
+import {getAuthorizationHeader} from "../utils/login";
 
 type WeekdayStats = {
     dayIndex: number
@@ -38,13 +39,10 @@ const formatDuration = (seconds: number) => {
 }
 
 const createAuthHeaders = () => {
-    const auth = localStorage.getItem('auth') || sessionStorage.getItem('auth')
-    const config = getConfigFromHtmlFile()
     const headers = new Headers(HEADER_TO_USE)
-    if (auth && config?.basicAuth) {
-        headers.set('Authorization', 'Basic ' + auth)
-    } else if (auth && config?.oidcConfigured) {
-        headers.set('Authorization', 'Bearer ' + auth)
+    const authorization = getAuthorizationHeader(getConfigFromHtmlFile())
+    if (authorization) {
+        headers.set('Authorization', authorization)
     }
     return headers
 }
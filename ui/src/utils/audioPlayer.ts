@@ -9,6 +9,13 @@ export const isVideoUrl = (url?: string | null): boolean => {
     return ['mp4', 'm4v', 'mov', 'webm'].includes(ext)
 }
 
+// Volume factor of an episode's ReplayGain. The audio element can't amplify,
+// so quiet episodes stay at the chosen volume and loud ones are turned down.
+export const replayGainFactor = (gainDb?: number | null): number => {
+    if (gainDb == null) return 1
+    return Math.min(1, Math.pow(10, gainDb / 20))
+}
+
 export const startAudioPlayer = async (audioUrl: string, position: number) => {
     const audioPlayer = getAudioPlayer()
     if (!audioPlayer) {
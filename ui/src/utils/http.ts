@@ -4,7 +4,7 @@ import {components, paths} from "../../schema";
 import {APIError} from "./ErrorDefinition";
 import { enqueueSnackbar } from "@/utils/toast";
 import i18n from "../language/i18n";
-import {getLogin} from "./login";
+import {getAuthorizationHeader} from "./login";
 import {getConfigFromHtmlFile} from "./config";
 
 
@@ -40,14 +40,12 @@ function isJsonString(str: string) {
 
 const authMiddleware: Middleware = {
     async onRequest({ request}) {
-        const auth = localStorage.getItem('auth') || sessionStorage.getItem('auth')
         Object.entries(HEADER_TO_USE).forEach(([key, value]) => {
             request.headers.set(key, value)
         })
-        if (auth && configObj && configObj.basicAuth) {
-            request.headers.set('Authorization', 'Basic '+ auth)
-        } else if (auth && configObj && configObj.oidcConfigured) {
-            request.headers.set('Authorization', 'Bearer '+ auth)
+        const authorization = getAuthorizationHeader(configObj)
+        if (authorization) {
+            request.headers.set('Authorization', authorization)
         }
         return request;
     },
@@ -57,8 +55,11 @@ const authMiddleware: Middleware = {
                 const textData = await response.text()
                 if (isJsonString(textData)) {
                     const e = JSON.parse(textData)
-                    // @ts-ignore
-                    enqueueSnackbar(i18n.t(e.errorCode, e.arguments), {variant: 'error'})
+                    // The login form asks for the code itself.
+                    if (e.errorCode !== 'SECOND_FACTOR_REQUIRED') {
+                        // @ts-ignore
+                        enqueueSnackbar(i18n.t(e.errorCode, e.arguments), {variant: 'error'})
+                    }
                     throw new APIError(e)
                 } else {
                     throw new Error("Request failed: " + response.body === null? response.statusText: textData);
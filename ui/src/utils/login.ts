@@ -1,10 +1,19 @@
+import type {components} from "../../schema";
+
 type LoginObject = {
     loginType: 'oidc' | 'basic',
     rememberMe: boolean
 }
 
+/**
+ * What is stored under `auth`: the base64 `user:password` pair of a basic
+ * login, or the api token a two-factor login hands out instead.
+ */
+export type AuthKind = 'credentials' | 'token'
 
 export const LoginKey = 'login'
+const AuthKey = 'auth'
+const AuthKindKey = 'authKind'
 
 export const getLogin = (): LoginObject | null => {
     const item = localStorage.getItem(LoginKey) || sessionStorage.getItem(LoginKey)
@@ -15,23 +24,38 @@ export const getLogin = (): LoginObject | null => {
 }
 
 
-export const setAuth = (auth: string) => {
+export const setAuth = (auth: string, kind: AuthKind = 'credentials') => {
     const login = getLogin()
     if (login) {
-        if (login.loginType === 'basic') {
-            if (login.rememberMe) {
-                localStorage.setItem('auth', auth)
-            } else {
-                sessionStorage.setItem('auth', auth)
-            }
-        } else if (login.loginType === 'oidc') {
-            if (login.rememberMe) {
-                localStorage.setItem('auth', auth)
-            } else {
-                sessionStorage.setItem('auth', auth)
-            }
+        const storage = login.rememberMe ? localStorage : sessionStorage
+        storage.setItem(AuthKey, auth)
+        storage.setItem(AuthKindKey, kind)
+    }
+}
+
+export const getAuth = (): { value: string, kind: AuthKind } | null => {
+    for (const storage of [localStorage, sessionStorage]) {
+        const value = storage.getItem(AuthKey)
+        if (value) {
+            return { value, kind: storage.getItem(AuthKindKey) === 'token' ? 'token' : 'credentials' }
         }
     }
+    return null
+}
+
+/** The `Authorization` header for the stored login, if there is one. */
+export const getAuthorizationHeader = (config: components['schemas']['ConfigModel'] | undefined): string | undefined => {
+    const auth = getAuth()
+    if (!auth || !config) {
+        return undefined
+    }
+    if (config.basicAuth) {
+        return (auth.kind === 'token' ? 'Bearer ' : 'Basic ') + auth.value
+    }
+    if (config.oidcConfigured) {
+        return 'Bearer ' + auth.value
+    }
+    return undefined
 }
 
 export const setLogin = (login: LoginObject) => {
@@ -40,6 +64,8 @@ export const setLogin = (login: LoginObject) => {
 
 
 export const removeLogin = () => {
-    sessionStorage.removeItem('auth')
-    localStorage.removeItem('auth')
-}
\ No newline at end of file
+    for (const storage of [localStorage, sessionStorage]) {
+        storage.removeItem(AuthKey)
+        storage.removeItem(AuthKindKey)
+    }
+}
@@ -0,0 +1,61 @@
+import {client} from "./http";
+import {APIError} from "./ErrorDefinition";
+import {getAuth, removeLogin, setAuth, setLogin} from "./login";
+
+export const SECOND_FACTOR_REQUIRED = 'SECOND_FACTOR_REQUIRED'
+
+export type PasswordLoginResult = 'authenticated' | 'second-factor-required'
+
+/**
+ * Logs in with username and password. Users with a second factor are asked
+ * for their TOTP or recovery code first and then get an api token, which the
+ * UI sends instead of basic auth because the server refuses their password
+ * alone.
+ */
+export const loginWithPassword = async (
+    username: string,
+    password: string,
+    rememberMe: boolean,
+    totpCode?: string
+): Promise<PasswordLoginResult> => {
+    setLogin({
+        rememberMe,
+        loginType: 'basic'
+    })
+
+    try {
+        const { data } = await client.POST('/api/v1/login', {
+            body: {
+                username,
+                password,
+                totpCode: totpCode || undefined
+            }
+        })
+        if (data?.token) {
+            setAuth(data.token, 'token')
+        } else {
+            setAuth(btoa(username + ':' + password))
+        }
+        return 'authenticated'
+    } catch (e) {
+        if (e instanceof APIError && e.details.errorCode === SECOND_FACTOR_REQUIRED) {
+            return 'second-factor-required'
+        }
+        throw e
+    }
+}
+
+/**
+ * Forgets the stored login. The token of a two-factor login is revoked on
+ * the server first, so it can't be used from a copy of the browser storage.
+ */
+export const logout = async () => {
+    if (getAuth()?.kind === 'token') {
+        try {
+            await client.POST('/api/v1/logout')
+        } catch (e) {
+            // Logging out of this browser must work even if the server is down.
+        }
+    }
+    removeLogin()
+}
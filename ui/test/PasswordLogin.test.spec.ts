@@ -0,0 +1,121 @@
+import {afterEach, beforeAll, describe, expect, it, vi} from "vitest"
+
+const TOKEN = "pf_login_token"
+
+type RecordedRequest = {
+    path: string
+    authorization: string | null
+}
+
+const requests: RecordedRequest[] = []
+
+const json = (status: number, body: unknown) =>
+    new Response(JSON.stringify(body), {status, headers: {"Content-Type": "application/json"}})
+
+/** Answers like the server: `totp` has a second factor, `plain` doesn't. */
+const server = async (request: Request) => {
+    const path = new URL(request.url).pathname
+    requests.push({path, authorization: request.headers.get("Authorization")})
+
+    if (path.endsWith("/api/v1/login")) {
+        const body = await request.json()
+        if (body.password !== "secret") {
+            return json(403, {errorCode: "WRONG_USER_OR_PASSWORD", arguments: {}})
+        }
+        if (body.username === "plain") {
+            return json(200, {token: null})
+        }
+        if (!body.totpCode) {
+            return json(401, {errorCode: "SECOND_FACTOR_REQUIRED", arguments: {}})
+        }
+        if (body.totpCode !== "123456") {
+            return json(403, {errorCode: "WRONG_USER_OR_PASSWORD", arguments: {}})
+        }
+        return json(200, {token: TOKEN})
+    }
+    if (path.endsWith("/api/v1/logout")) {
+        return new Response(null, {status: 204})
+    }
+    if (path.endsWith("/api/v1/users/me/two-factor")) {
+        return request.headers.get("Authorization") === `Bearer ${TOKEN}`
+            ? json(200, {enabled: true, recoveryCodesLeft: 10})
+            : json(401, {errorCode: "UNAUTHORIZED", arguments: {}})
+    }
+    return json(404, {errorCode: "NOT_FOUND", arguments: {}})
+}
+
+let loginModule: typeof import("../src/utils/passwordLogin")
+let authModule: typeof import("../src/utils/login")
+let httpModule: typeof import("../src/utils/http")
+
+describe("Password login", () => {
+    beforeAll(async () => {
+        // The http client reads the config and captures fetch on import.
+        document.body.innerHTML =
+            `<div id="config" data-config='${JSON.stringify({basicAuth: true, oidcConfigured: false})}'></div>`
+        vi.stubGlobal("fetch", vi.fn(server))
+        httpModule = await import("../src/utils/http")
+        authModule = await import("../src/utils/login")
+        loginModule = await import("../src/utils/passwordLogin")
+    })
+
+    afterEach(() => {
+        localStorage.clear()
+        sessionStorage.clear()
+        requests.length = 0
+    })
+
+    it("keeps basic auth for users without a second factor", async () => {
+        const result = await loginModule.loginWithPassword("plain", "secret", true)
+
+        expect(result).toBe("authenticated")
+        expect(authModule.getAuth()).toEqual({value: btoa("plain:secret"), kind: "credentials"})
+        expect(localStorage.getItem("auth")).toBe(btoa("plain:secret"))
+    })
+
+    it("asks for the code and then authenticates with the returned token", async () => {
+        const first = await loginModule.loginWithPassword("totp", "secret", false)
+        expect(first).toBe("second-factor-required")
+        expect(authModule.getAuth()).toBeNull()
+
+        const second = await loginModule.loginWithPassword("totp", "secret", false, "123456")
+        expect(second).toBe("authenticated")
+        expect(authModule.getAuth()).toEqual({value: TOKEN, kind: "token"})
+        expect(sessionStorage.getItem("auth")).toBe(TOKEN)
+
+        const status = await httpModule.client.GET("/api/v1/users/me/two-factor")
+        expect(status.response.status).toBe(200)
+        expect(requests.at(-1)).toEqual({
+            path: "/api/v1/users/me/two-factor",
+            authorization: `Bearer ${TOKEN}`,
+        })
+    })
+
+    it("rejects a wrong code without storing anything", async () => {
+        await expect(
+            loginModule.loginWithPassword("totp", "secret", false, "000000")
+        ).rejects.toThrow()
+        expect(authModule.getAuth()).toBeNull()
+    })
+
+    it("revokes and forgets the token on logout", async () => {
+        await loginModule.loginWithPassword("totp", "secret", true, "123456")
+        await loginModule.logout()
+
+        expect(requests.at(-1)).toEqual({
+            path: "/api/v1/logout",
+            authorization: `Bearer ${TOKEN}`,
+        })
+        expect(authModule.getAuth()).toBeNull()
+        expect(authModule.getAuthorizationHeader({basicAuth: true} as never)).toBeUndefined()
+    })
+
+    it("logs basic auth users out without asking the server", async () => {
+        await loginModule.loginWithPassword("plain", "secret", true)
+        requests.length = 0
+        await loginModule.logout()
+
+        expect(requests).toEqual([])
+        expect(authModule.getAuth()).toBeNull()
+    })
+})
@@ -3,6 +3,8 @@ import { defineConfig } from 'vitest/config';
 export default defineConfig({
     test: {
         environment: 'jsdom',
+        // The http client derives the api URL from a `/ui` page location.
+        environmentOptions: { jsdom: { url: 'http://localhost:8000/ui/' } },
         // e2e/ contains playwright specs with their own runner (pnpm run e2e).
         exclude: ['e2e/**', 'node_modules/**'],
     },